@@ -0,0 +1,58 @@
+use crate::config::Config;
+use std::time::Duration;
+
+/// Operations that take at least this long alert when they finish, as the
+/// screen may have looked frozen meanwhile.
+pub const DEFAULT_ALERT_AFTER_SECONDS: u64 = 2;
+
+/// How refused keys and finished long operations are signalled, so a key
+/// that did nothing is not mistaken for one that got lost.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum AlertStyle {
+    #[default]
+    Off,
+    /// The terminal bell.
+    Bell,
+    /// A flash of the screen.
+    Flash,
+}
+
+/// Whether an operation that took `elapsed` alerts when it finishes.
+pub fn is_long_operation(config: &Config, elapsed: Duration) -> bool {
+    let after = config
+        .alert_after_seconds
+        .unwrap_or(DEFAULT_ALERT_AFTER_SECONDS);
+    elapsed >= Duration::from_secs(after)
+}
+
+/// Rings the bell or flashes the screen. Terminals without a visible bell
+/// ring instead of flashing, and the other way around.
+pub fn ring(style: AlertStyle) {
+    match style {
+        AlertStyle::Off => {}
+        AlertStyle::Bell => {
+            pancurses::beep();
+        }
+        AlertStyle::Flash => {
+            pancurses::flash();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_long_operations() {
+        let config = Config::default();
+        assert!(!is_long_operation(&config, Duration::from_millis(1999)));
+        assert!(is_long_operation(&config, Duration::from_secs(2)));
+
+        let config = Config {
+            alert_after_seconds: Some(0),
+            ..Default::default()
+        };
+        assert!(is_long_operation(&config, Duration::ZERO));
+    }
+}
@@ -1,14 +1,63 @@
-use crate::background::{BackgroundWorker, Response};
-use crate::command::{Command, CommandHistory};
+use crate::alert::{self, AlertStyle};
+use crate::background::{BackgroundWorker, RemoteOperation, RemoteSync, Response};
+use crate::blame_summary::{self, Key as BlameKey, Summary as BlameSummary};
+use crate::command::{ApplyPatchCommand, Command, CommandHistory, UnstageFileCommand};
 use crate::commit_storage;
+use crate::config::Config;
 use crate::cursor_state::CursorState;
+use crate::diff_filter::{self, DiffFilterCache};
+use crate::edit_stage::{self, EditStageOffer};
+use crate::external_command::EditorMode;
 use crate::git::{
-    CommitInfo, FileDiff, get_commit_diff, get_diff, get_local_commits, get_unstaged_diff,
-    get_untracked_files,
+    self, BranchStatus, CommitInfo, FileDiff, FileFingerprint, FileStatus, StatusCaches,
+    get_local_commits,
 };
-use crate::ui::main_screen::{ListItem as MainScreenListItem, UnstagedListItem};
+use crate::git_patch;
+use crate::keymap::KeyMap;
+use crate::message_log::{MessageLog, Severity, StatusMessage};
+use crate::never_commit::{Guarded, NeverCommit};
+use crate::notify;
+use crate::profile::{self, Phases, Profile};
+use crate::split_commit::{self, SplitCommit};
+use crate::staged_test::{self, StagedTest};
+use crate::ui::commit_folds::{self, CommitFolds};
+use crate::ui::commit_view::{self, HistoryRecall, PendingMessageEdit};
+use crate::ui::diagnostics_view::DiagnosticsView;
+use crate::ui::diff_search::DiffSearch;
+use crate::ui::discard_bin::{DiscardBin, DiscardedChange};
+use crate::ui::explain::{self, Explanation};
+use crate::ui::final_review::FinalReview;
+use crate::ui::fixup_picker::FixupPicker;
+use crate::ui::format_preview::FormatPreview;
+use crate::ui::help::HelpView;
+use crate::ui::history::History;
+use crate::ui::hook_output::HookOutput;
+use crate::ui::hunk_edit::{self, PendingHunkEdit};
+use crate::ui::ignore_picker::IgnorePicker;
+use crate::ui::index_lock::{self, HeldAction, IndexLockWait};
+use crate::ui::main_screen::{self, ListItem as MainScreenListItem, UnstagedListItem};
+use crate::ui::maintenance::{self as maintenance_panel, MaintenancePanel};
+use crate::ui::message_log_view::MessageLogView;
+use crate::ui::patch_preview::PatchPreview;
+use crate::ui::plan_preview::PlanPreview;
+use crate::ui::prompt::Prompt;
+use crate::ui::rebase_plan::RebasePlan;
+use crate::ui::rebase_recovery::RebaseRecovery;
+use crate::ui::restore_picker::RestorePicker;
+use crate::ui::reword_preview::RewordPreview;
+use crate::ui::stashes::Stashes;
+use crate::ui::stat_panel::StatPanel;
+use crate::ui::status_filter;
+use crate::ui::tutorial::Tutorial;
+use crate::ui::unified_list::UnifiedList;
+use crate::ui::worktrees::WorktreePicker;
+use crate::util::path_order::compare_directories_first;
+use crate::util::rename_groups::{find_pending_rename_groups, find_rename_groups};
+use crate::util::word_diff::WordChange;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum FocusedPane {
@@ -16,9 +65,144 @@ pub enum FocusedPane {
     Unstaged,
 }
 
+/// A view drawn instead of the main screen. Opening one closes the one
+/// open before.
+pub enum Screen {
+    FinalReview(FinalReview),
+    History(History),
+    RebasePlan(RebasePlan),
+    DiscardBin(DiscardBin),
+    RestorePicker(RestorePicker),
+    FixupPicker(FixupPicker),
+    Worktrees(WorktreePicker),
+    Maintenance(MaintenancePanel),
+    UnifiedList(UnifiedList),
+    Diagnostics(DiagnosticsView),
+    MessageLog(MessageLogView),
+    Help(HelpView),
+    Stashes(Stashes),
+}
+
+/// A popup drawn over the main screen or the open [`Screen`], taking the
+/// keys until it is closed.
+pub enum Modal {
+    PlanPreview(PlanPreview),
+    RewordPreview(RewordPreview),
+    HookOutput(HookOutput),
+    RebaseRecovery(RebaseRecovery),
+    Explanation(Explanation),
+    /// The action held back while another process locks the index.
+    IndexLockWait(IndexLockWait),
+    PatchPreview(PatchPreview),
+    StatPanel(StatPanel),
+    IgnorePicker(IgnorePicker),
+    FormatPreview(FormatPreview),
+}
+
+/// Order in which staged files are listed on the main screen.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FileListOrder {
+    /// The order reported by `git diff --staged`.
+    #[default]
+    Diff,
+    /// Directories before files at every level, like a file tree.
+    Tree,
+}
+
+/// How the hashes of the commits in the log are shown, switched with `#`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum HashDisplay {
+    /// Only the subject.
+    #[default]
+    Hidden,
+    /// The abbreviated hash before the subject.
+    Short,
+    /// The full 40 character hash before the subject.
+    Full,
+}
+
+impl HashDisplay {
+    pub fn next(self) -> Self {
+        match self {
+            HashDisplay::Hidden => HashDisplay::Short,
+            HashDisplay::Short => HashDisplay::Full,
+            HashDisplay::Full => HashDisplay::Hidden,
+        }
+    }
+}
+
+/// Where the cursor goes when the item it was on disappears after a refresh.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum CursorFallback {
+    /// Prefer the item that followed the removed one in the same section.
+    #[default]
+    Next,
+    /// Prefer the item that preceded the removed one in the same section.
+    Previous,
+}
+
+/// Identity of the item under a cursor, used to find it again after the
+/// list has been rebuilt.
+#[derive(Clone, Debug, PartialEq)]
+enum CursorAnchor {
+    Header(&'static str),
+    File(String),
+    RenameGroup((String, String)),
+    Untracked(String),
+    Commit(String),
+}
+
 pub struct EditorRequest {
     pub file_path: String,
     pub line_number: Option<usize>,
+    /// Set for files created from the TUI: the file is staged when the editor
+    /// exits if its content differs from what it was created with.
+    pub stage_if_saved: Option<PendingStage>,
+    /// Set for hunks edited with `E`: the edited hunk is applied when the
+    /// editor exits.
+    pub edit_hunk: Option<PendingHunkEdit>,
+    /// Set for commit messages opened with Ctrl-X: the message is read back
+    /// when the editor exits.
+    pub edit_message: Option<PendingMessageEdit>,
+    /// Set for files opened with `e` when `stage_after_edit` is on: what
+    /// the editor changed is staged, or offered to be, when it exits.
+    pub stage_edit: Option<PendingStage>,
+    /// Set for files opened with `e`: where the cursor goes back to once
+    /// the editor exits.
+    pub return_to: Option<HunkAnchor>,
+}
+
+impl EditorRequest {
+    /// Whether the file is read back once the editor exits, so the editor
+    /// has to be waited for until the file is closed.
+    pub fn reads_back(&self) -> bool {
+        self.stage_if_saved.is_some()
+            || self.edit_hunk.is_some()
+            || self.edit_message.is_some()
+            || self.stage_edit.is_some()
+    }
+
+    /// Whether the editor is started on its own, the screen staying.
+    pub fn detaches(&self, mode: EditorMode) -> bool {
+        mode == EditorMode::Detach && !self.reads_back()
+    }
+}
+
+/// The hunk the cursor was on when a file was opened with `e`, found again
+/// by its lines afterwards since editing shifts line numbers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HunkAnchor {
+    pub file_name: String,
+    pub pane: FocusedPane,
+    /// The lines of the hunk, empty if the cursor was not on one.
+    pub lines: Vec<String>,
+    /// The line of the hunk the cursor was on.
+    pub offset: usize,
+}
+
+pub struct PendingStage {
+    pub file_name: String,
+    pub initial_content: Vec<u8>,
 }
 
 #[derive(Default)]
@@ -29,6 +213,17 @@ pub struct MainScreenState {
     pub file_cursor: usize,
     pub line_cursor: usize,
     pub commit_message: String,
+    /// The template `commit_message` was filled with, while there was no
+    /// draft. Not saved as a draft as long as it is unchanged.
+    pub commit_template: Option<String>,
+    /// The message whose problems were shown when committing it with
+    /// `block_on_lint`. Committing it unchanged again goes ahead.
+    pub lint_confirmed_message: Option<String>,
+    /// The message refused for committing nothing but whitespace changes.
+    /// Committing it unchanged again goes ahead.
+    pub empty_commit_confirmed_message: Option<String>,
+    /// Earlier messages being gone through with Ctrl-P and Ctrl-N.
+    pub history_recall: Option<HistoryRecall>,
     pub commit_cursor: usize,
     pub commit_scroll_offset: usize,
     pub commit_scroll_extra_space: bool,
@@ -38,18 +233,36 @@ pub struct MainScreenState {
     pub list_items: Vec<MainScreenListItem>,
     pub is_reordering_commits: bool,
     pub original_list_items_for_reorder: Vec<MainScreenListItem>,
+    /// Up/Down move the selected commit instead of the selection while
+    /// reordering (grab mode, entered with `g`).
+    pub is_grabbing_commit: bool,
+    pub file_list_order: FileListOrder,
+    /// Show commit diffs with `--word-diff` instead of line by line.
+    pub commit_word_diff: bool,
+    pub commit_hashes: HashDisplay,
+    /// Directory renames whose files are listed below the collapsed entry.
+    pub expanded_rename_groups: HashSet<(String, String)>,
+    /// Text searched in the diff with `/`, highlighted until cleared.
+    pub search: Option<DiffSearch>,
+    /// Files of the selected commit's diff collapsed to their header.
+    pub commit_folds: CommitFolds,
 }
 
 #[derive(Default)]
 pub struct UnstagedPaneState {
     pub unstaged_files: Vec<FileDiff>,
     pub untracked_files: Vec<String>,
+    /// Untracked files over the size limit with their sizes, listed in a
+    /// section of their own since staging everything leaves them out.
+    pub large_files: Vec<(String, u64)>,
     pub cursor: usize,
     pub scroll: usize,
     pub diff_scroll: usize,
     pub horizontal_scroll: usize,
     pub is_diff_cursor_active: bool,
     pub list_items: Vec<UnstagedListItem>,
+    /// Text searched in the diff with `/`, highlighted until cleared.
+    pub search: Option<DiffSearch>,
 }
 
 pub struct AppState {
@@ -62,38 +275,171 @@ pub struct AppState {
     pub reorder_command_history: Option<CommandHistory>,
     pub previous_commits: Vec<CommitInfo>,
     pub selected_commit_files: Vec<FileDiff>,
+    /// Word spans of every line of `selected_commit_files` while
+    /// `commit_word_diff` is on; empty otherwise.
+    pub selected_commit_word_spans: Vec<Option<Vec<(String, WordChange)>>>,
     pub focused_pane: FocusedPane,
     pub editor_request: Option<EditorRequest>,
-    pub error_message: Option<String>,
+    /// The message on the status line.
+    pub message: Option<StatusMessage>,
+    pub prompt: Option<Prompt>,
+    pub screen: Option<Screen>,
+    pub modal: Option<Modal>,
+    /// The maintenance command running in the background.
+    pub maintenance_running: Option<String>,
+    /// An edit made with `e`, staged if the next key is `y`.
+    pub edit_stage_offer: Option<EditStageOffer>,
+    /// Staged files changing only whitespace, unstaged if the next key is
+    /// `u`.
+    pub empty_commit_cleanup: Option<Vec<String>>,
+    /// The worktree to start the view again on once it stops, picked in
+    /// the worktree picker.
+    pub relaunch: Option<PathBuf>,
+    /// Everything `!` discarded in this session, oldest first. Unlike the
+    /// undo history, it survives commits.
+    pub discarded_changes: Vec<DiscardedChange>,
+    /// Files bookmarked with `b`, in the order they were bookmarked. Kept
+    /// by name, so refreshes and staging leave them alone.
+    pub bookmarks: Vec<String>,
+    pub tutorial: Option<Tutorial>,
+    /// Messages shown on the status line in this session, for the message
+    /// log and the diagnostics view.
+    pub message_log: MessageLog,
+    /// How long starting up and refreshing took, printed with `--profile`.
+    pub profile: Profile,
+    /// What the listed files looked like when the lists were last read,
+    /// checked again right before a discard.
+    pub file_fingerprints: HashMap<String, FileFingerprint>,
+    /// When the push running on the background worker was started.
+    pub push_started: Option<Instant>,
+    /// Who last touched the lines the staged files change, filled in the
+    /// background as files get selected. `None` while it is being read.
+    pub blame_summaries: HashMap<BlameKey, Option<BlameSummary>>,
+    /// The fetch or pull running on the background worker.
+    pub remote_sync: Option<RemoteSync>,
+    /// The staged tree as of the last refresh, while `staged_test_command`
+    /// is set and something is staged.
+    pub staged_tree: Option<String>,
+    /// The last run of `staged_test_command`.
+    pub staged_test: Option<StagedTest>,
+    /// The branch and how far it is from its upstream, as of the last
+    /// refresh.
+    pub branch_status: BranchStatus,
     pub last_interaction_time: Option<Instant>,
     pub background_worker: BackgroundWorker,
+    /// Whether the file lists show fish-style abbreviated paths.
+    pub abbreviate_paths: bool,
+    /// Whether the staged and unstaged diffs are shown without their
+    /// `diff --git`, `index` and `---`/`+++` lines, toggled with `h`.
+    pub hide_patch_headers: bool,
+    /// Whether the staged and unstaged diffs show the whole new file around
+    /// their hunks, toggled with `V`.
+    pub full_file_view: bool,
+    /// Whether the diffs highlight trailing whitespace, mixed indentation
+    /// and missing newlines at the end of files, toggled with `_`.
+    pub show_whitespace: bool,
+    /// Lines of context around the changes of the staged and unstaged
+    /// diffs, changed with `+` and `-`. `None` leaves it to git.
+    pub diff_context: Option<usize>,
+    /// The file whose other side the diff shows: its unstaged changes while
+    /// it is selected among the staged ones, or the other way round.
+    /// Toggled with `=`, and dropped by any key but scrolling.
+    pub other_side: Option<String>,
+    /// The commit being split with `S`, until all its parts are committed.
+    pub split: Option<SplitCommit>,
+    /// Outputs of the configured diff filters for the listed files.
+    pub diff_filter_cache: DiffFilterCache,
+    /// Files whose diff is shown unfiltered, toggled with `F`.
+    pub raw_diff_files: HashSet<String>,
+    /// The only status of the files both lists show, picked from the menu
+    /// `|` opens and cleared with Esc.
+    pub status_filter: Option<FileStatus>,
+    /// Whether the next key picks the status filter.
+    pub status_filter_menu: bool,
+    /// Listed files whose diff goes through a textconv driver. Only whole
+    /// files of these can be staged, since the shown lines are not theirs.
+    pub textconv_files: HashSet<String>,
+    /// Whether untracked files are read from `git status`, which the caches
+    /// make faster in large repositories.
+    pub status_caches: StatusCaches,
+    /// A refused key or a finished long operation is to be signalled with
+    /// the configured bell or flash on the next render.
+    pub alert_pending: bool,
+    /// Changes of this repository never to be committed, marked with `X`.
+    pub never_commit: NeverCommit,
+    /// The keys of the panes, with the `[keys]` of the config applied.
+    pub keymap: KeyMap,
+    pub config: Config,
 }
 impl AppState {
     pub fn new(repo_path: PathBuf, files: Vec<FileDiff>) -> Self {
+        Self::new_with_config(repo_path, files, Config::default())
+    }
+
+    pub fn new_with_config(repo_path: PathBuf, mut files: Vec<FileDiff>, config: Config) -> Self {
+        Self::sort_files(&mut files, config.file_list_order);
+        if config.hide_patch_headers {
+            git::strip_patch_headers(&mut files);
+        }
         let commit_message =
             commit_storage::load_commit_message(&repo_path).unwrap_or_else(|_| String::new());
-        let previous_commits = get_local_commits(&repo_path).unwrap_or_default();
-        let selected_commit_files = previous_commits
-            .first()
-            .map(|c| get_commit_diff(&repo_path, &c.hash).unwrap_or_default())
-            .unwrap_or_default();
+        let never_commit = NeverCommit::load(&repo_path);
+        let mut startup = Phases::default();
+        let previous_commits = startup.time("commit list", || {
+            get_local_commits(&repo_path).unwrap_or_default()
+        });
+        let branch_status = startup.time("branch status", || git::get_branch_status(&repo_path));
+        let selected_commit_files = startup.time("commit diff", || {
+            previous_commits
+                .first()
+                .map(|c| {
+                    git::get_commit_diff_with_fixup_preview(&repo_path, &c.hash).unwrap_or_default()
+                })
+                .unwrap_or_default()
+        });
 
-        let unstaged_files = get_unstaged_diff(&repo_path);
-        let untracked_files = get_untracked_files(&repo_path).unwrap_or_default();
+        let mut unstaged_files = startup.time("unstaged diff", || {
+            git::get_unstaged_diff_with_context(&repo_path, config.diff_context)
+        });
+        if config.hide_patch_headers {
+            git::strip_patch_headers(&mut unstaged_files);
+        }
+        let status_caches = StatusCaches::read(&repo_path);
+        let untracked_files = startup.time("untracked files", || {
+            git::list_untracked_files(&repo_path, status_caches).unwrap_or_default()
+        });
         let has_unstaged_changes = !unstaged_files.is_empty() || !untracked_files.is_empty();
+        let large_files = Self::find_large_files(
+            &repo_path,
+            &untracked_files,
+            config.max_file_size.unwrap_or(git::DEFAULT_MAX_FILE_SIZE),
+        );
+        let file_fingerprints = startup.time("fingerprints", || {
+            Self::read_file_fingerprints(&repo_path, &files, &unstaged_files, &untracked_files)
+        });
 
         let main_screen = MainScreenState {
             commit_message,
             has_unstaged_changes,
-            list_items: Self::build_main_screen_list_items(&files, &previous_commits),
+            list_items: Self::build_main_screen_list_items(
+                &files,
+                &previous_commits,
+                &HashSet::new(),
+            ),
             file_cursor: if !files.is_empty() { 1 } else { 0 },
+            file_list_order: config.file_list_order,
             ..Default::default()
         };
 
         let unstaged_pane = UnstagedPaneState {
             unstaged_files: unstaged_files.clone(),
             untracked_files: untracked_files.clone(),
-            list_items: Self::build_unstaged_screen_list_items(&unstaged_files, &untracked_files),
+            list_items: Self::build_unstaged_screen_list_items(
+                &unstaged_files,
+                &untracked_files,
+                &large_files,
+            ),
+            large_files,
             ..Default::default()
         };
 
@@ -109,24 +455,156 @@ impl AppState {
             reorder_command_history: None,
             previous_commits,
             selected_commit_files,
+            selected_commit_word_spans: Vec::new(),
             focused_pane,
             editor_request: None,
-            error_message: None,
+            message: None,
+            prompt: None,
+            screen: None,
+            modal: None,
+            maintenance_running: None,
+            edit_stage_offer: None,
+            empty_commit_cleanup: None,
+            relaunch: None,
+            discarded_changes: Vec::new(),
+            bookmarks: Vec::new(),
+            tutorial: None,
+            message_log: MessageLog::default(),
+            profile: Profile {
+                startup,
+                ..Default::default()
+            },
+            file_fingerprints,
+            push_started: None,
+            remote_sync: None,
+            blame_summaries: HashMap::new(),
+            staged_tree: None,
+            staged_test: None,
+            branch_status,
             last_interaction_time: None,
             background_worker: BackgroundWorker::new(),
+            abbreviate_paths: config.abbreviate_paths,
+            hide_patch_headers: config.hide_patch_headers,
+            full_file_view: false,
+            show_whitespace: config.show_whitespace,
+            diff_context: config.diff_context,
+            other_side: None,
+            split: None,
+            alert_pending: false,
+            diff_filter_cache: DiffFilterCache::default(),
+            raw_diff_files: HashSet::new(),
+            status_filter: None,
+            status_filter_menu: false,
+            textconv_files: HashSet::new(),
+            status_caches,
+            never_commit,
+            keymap: KeyMap::new(&config.key_bindings),
+            config,
         };
+        if s.main_screen.commit_message.is_empty() {
+            s.fill_commit_template();
+        }
         s.update_selected_commit_diff();
+        if !s.config.diff_filters.is_empty() {
+            let started = Instant::now();
+            s.run_diff_filters();
+            s.profile
+                .startup
+                .0
+                .push(("diff filters", started.elapsed()));
+        }
+        let started = Instant::now();
+        s.textconv_files = s.find_textconv_files();
+        s.profile
+            .startup
+            .0
+            .push(("textconv drivers", started.elapsed()));
+        if !s.never_commit.is_empty() {
+            s.unstage_never_commit(false);
+        }
+        s.update_staged_test();
+        if s.message.is_none()
+            && let Some(hint) = maintenance_panel::startup_hint(&s)
+        {
+            s.set_message(Severity::Warning, hint);
+        }
+        if let Some(problem) = s.keymap.problems().first() {
+            let more = match s.keymap.problems().len() - 1 {
+                0 => String::new(),
+                1 => ", and 1 more problem".to_string(),
+                more => format!(", and {more} more problems"),
+            };
+            s.set_message(
+                Severity::Warning,
+                format!(
+                    "Key bindings: {problem}{more} (see the diagnostics, {})",
+                    s.keymap.key_label("diagnostics")
+                ),
+            );
+        }
+        if split_commit::is_left_over(&s.repo_path) {
+            s.set_message(Severity::Warning, format!(
+                "A split was not finished, git reset --hard {} restores the branch from before it",
+                split_commit::BACKUP_REF
+            ));
+        }
         s
     }
 
+    fn read_file_fingerprints(
+        repo_path: &std::path::Path,
+        files: &[FileDiff],
+        unstaged_files: &[FileDiff],
+        untracked_files: &[String],
+    ) -> HashMap<String, FileFingerprint> {
+        let mut paths: Vec<String> = files
+            .iter()
+            .chain(unstaged_files)
+            .map(|file| file.file_name.clone())
+            .chain(untracked_files.iter().cloned())
+            .collect();
+        paths.sort();
+        paths.dedup();
+        git::file_fingerprints(repo_path, &paths).unwrap_or_default()
+    }
+
     fn build_main_screen_list_items(
         files: &[FileDiff],
         previous_commits: &[CommitInfo],
+        expanded_rename_groups: &HashSet<(String, String)>,
     ) -> Vec<MainScreenListItem> {
+        let groups = find_rename_groups(
+            files
+                .iter()
+                .filter(|f| f.status == FileStatus::Renamed)
+                .map(|f| (f.old_file_name.as_str(), f.file_name.as_str())),
+        );
+
         let mut items = Vec::new();
         items.push(MainScreenListItem::StagedChangesHeader);
         for file in files {
-            items.push(MainScreenListItem::File(file.clone()));
+            let Some(group) = groups
+                .iter()
+                .find(|g| file.status == FileStatus::Renamed && g.contains(&file.file_name))
+            else {
+                items.push(MainScreenListItem::File(file.clone()));
+                continue;
+            };
+            let expanded = expanded_rename_groups.contains(&group.key());
+            if group.renames[0].1 == file.file_name {
+                items.push(MainScreenListItem::RenameGroup {
+                    group: group.clone(),
+                    files: files
+                        .iter()
+                        .filter(|f| f.status == FileStatus::Renamed && group.contains(&f.file_name))
+                        .cloned()
+                        .collect(),
+                    expanded,
+                });
+            }
+            if expanded {
+                items.push(MainScreenListItem::File(file.clone()));
+            }
         }
         items.push(MainScreenListItem::CommitMessageInput);
         for commit in previous_commits {
@@ -140,27 +618,87 @@ impl AppState {
         items
     }
 
+    /// Untracked files over the size limit, with their sizes.
+    fn find_large_files(
+        repo_path: &std::path::Path,
+        untracked_files: &[String],
+        size_limit: u64,
+    ) -> Vec<(String, u64)> {
+        untracked_files
+            .iter()
+            .filter_map(|name| {
+                let size = std::fs::metadata(repo_path.join(name)).ok()?.len();
+                (size > size_limit).then(|| (name.clone(), size))
+            })
+            .collect()
+    }
+
+    /// The size over which untracked files are not staged automatically.
+    pub fn max_file_size(&self) -> u64 {
+        self.config
+            .max_file_size
+            .unwrap_or(git::DEFAULT_MAX_FILE_SIZE)
+    }
+
     pub fn build_unstaged_screen_list_items(
         unstaged_files: &[FileDiff],
         untracked_files: &[String],
+        large_files: &[(String, u64)],
     ) -> Vec<UnstagedListItem> {
+        let deleted: Vec<&str> = unstaged_files
+            .iter()
+            .filter(|f| f.status == FileStatus::Deleted)
+            .map(|f| f.file_name.as_str())
+            .collect();
+        let untracked: Vec<&str> = untracked_files.iter().map(String::as_str).collect();
+        let groups = find_pending_rename_groups(&deleted, &untracked);
+        let group_of = |path: &str| groups.iter().find(|g| g.contains(path));
+
         let mut items = Vec::new();
         items.push(UnstagedListItem::UnstagedChangesHeader);
         for file in unstaged_files {
-            items.push(UnstagedListItem::File(file.clone()));
+            match group_of(&file.file_name) {
+                Some(group) if file.status == FileStatus::Deleted => {
+                    if group.renames[0].0 == file.file_name {
+                        items.push(UnstagedListItem::RenameGroup(group.clone()));
+                    }
+                }
+                _ => items.push(UnstagedListItem::File(file.clone())),
+            }
         }
-        if !untracked_files.is_empty() {
+        let is_large = |name: &str| large_files.iter().any(|(large, _)| large == name);
+        let (large, ungrouped_untracked): (Vec<&String>, Vec<&String>) = untracked_files
+            .iter()
+            .filter(|name| group_of(name).is_none())
+            .partition(|name| is_large(name));
+        if !ungrouped_untracked.is_empty() {
             items.push(UnstagedListItem::UntrackedFilesHeader);
-            for file_name in untracked_files {
+            for file_name in ungrouped_untracked {
+                items.push(UnstagedListItem::UntrackedFile(file_name.clone()));
+            }
+        }
+        if !large.is_empty() {
+            items.push(UnstagedListItem::LargeFilesHeader);
+            for file_name in large {
                 items.push(UnstagedListItem::UntrackedFile(file_name.clone()));
             }
         }
         items
     }
 
+    /// Leaves only the files of the status filter in both lists.
+    fn apply_status_filter(&mut self) {
+        if let Some(status) = &self.status_filter {
+            status_filter::filter_main(&mut self.main_screen.list_items, status);
+            status_filter::filter_unstaged(&mut self.unstaged_pane.list_items, status);
+        }
+    }
+
     pub fn get_cursor_line_index(&self) -> usize {
         if let Some(
-            MainScreenListItem::File(_) | MainScreenListItem::PreviousCommitInfo { .. },
+            MainScreenListItem::File(_)
+            | MainScreenListItem::RenameGroup { .. }
+            | MainScreenListItem::PreviousCommitInfo { .. },
         ) = self
             .main_screen
             .list_items
@@ -180,23 +718,75 @@ impl AppState {
         let old_unstaged_cursor = self.unstaged_pane.cursor;
         let old_unstaged_scroll = self.unstaged_pane.scroll;
         let old_unstaged_diff_scroll = self.unstaged_pane.diff_scroll;
+        let old_main_anchors = Self::main_screen_anchors(&self.main_screen.list_items);
+        let old_unstaged_anchors = Self::unstaged_pane_anchors(&self.unstaged_pane.list_items);
 
-        self.files = get_diff(self.repo_path.clone());
-        self.previous_commits = get_local_commits(&self.repo_path).unwrap_or_default();
+        let mut refresh = Phases::default();
+        self.files = refresh.time("diff parse", || self.staged_diff());
+        Self::sort_files(&mut self.files, self.main_screen.file_list_order);
+        self.previous_commits = refresh.time("commit list", || {
+            get_local_commits(&self.repo_path).unwrap_or_default()
+        });
+        // Blames of an older HEAD may no longer be right.
+        let head = self
+            .previous_commits
+            .first()
+            .map(|commit| &commit.full_hash);
+        self.blame_summaries
+            .retain(|key, _| Some(&key.head) == head);
+        self.branch_status =
+            refresh.time("branch status", || git::get_branch_status(&self.repo_path));
 
-        let unstaged_files = get_unstaged_diff(&self.repo_path);
-        let untracked_files = get_untracked_files(&self.repo_path).unwrap_or_default();
+        let mut unstaged_files = refresh.time("unstaged diff", || {
+            git::get_unstaged_diff_with_context(&self.repo_path, self.diff_context)
+        });
+        if self.full_file_view {
+            git::expand_to_full_file(&mut unstaged_files, |name| {
+                self.full_file_content(name, false)
+            });
+        }
+        if self.hide_patch_headers {
+            git::strip_patch_headers(&mut unstaged_files);
+        }
+        let untracked_files = refresh.time("untracked files", || {
+            git::list_untracked_files(&self.repo_path, self.status_caches).unwrap_or_default()
+        });
         self.main_screen.has_unstaged_changes =
             !unstaged_files.is_empty() || !untracked_files.is_empty();
+        self.file_fingerprints = refresh.time("fingerprints", || {
+            Self::read_file_fingerprints(
+                &self.repo_path,
+                &self.files,
+                &unstaged_files,
+                &untracked_files,
+            )
+        });
 
-        self.main_screen.list_items =
-            Self::build_main_screen_list_items(&self.files, &self.previous_commits);
+        self.main_screen.list_items = Self::build_main_screen_list_items(
+            &self.files,
+            &self.previous_commits,
+            &self.main_screen.expanded_rename_groups,
+        );
+        let large_files =
+            Self::find_large_files(&self.repo_path, &untracked_files, self.max_file_size());
         self.unstaged_pane.list_items =
-            Self::build_unstaged_screen_list_items(&unstaged_files, &untracked_files);
+            Self::build_unstaged_screen_list_items(&unstaged_files, &untracked_files, &large_files);
+        self.apply_status_filter();
+        self.unstaged_pane.large_files = large_files;
         self.unstaged_pane.unstaged_files = unstaged_files;
         self.unstaged_pane.untracked_files = untracked_files;
 
+        let started = Instant::now();
         self.update_selected_commit_diff();
+        refresh.0.push(("commit diff", started.elapsed()));
+        if !self.config.diff_filters.is_empty() {
+            let started = Instant::now();
+            self.run_diff_filters();
+            refresh.0.push(("diff filters", started.elapsed()));
+        }
+        let textconv_files = refresh.time("textconv drivers", || self.find_textconv_files());
+        self.textconv_files = textconv_files;
+        self.record_refresh(&refresh);
 
         if reset_cursor {
             self.main_screen.file_cursor = if self.main_screen.list_items.len() > 1 {
@@ -211,12 +801,20 @@ impl AppState {
             self.main_screen.line_cursor = 0;
             self.main_screen.diff_scroll = 0;
         } else {
-            self.main_screen.file_cursor =
-                old_file_cursor.min(self.main_screen.list_items.len() - 1);
+            let new_main_anchors = Self::main_screen_anchors(&self.main_screen.list_items);
+            self.main_screen.file_cursor = Self::anchored_cursor(
+                &old_main_anchors,
+                &new_main_anchors,
+                old_file_cursor,
+                self.config.cursor_fallback,
+            );
+            let stayed_on_same_item = old_main_anchors.get(old_file_cursor)
+                == new_main_anchors.get(self.main_screen.file_cursor);
             if let Some(MainScreenListItem::File(file)) = self
                 .main_screen
                 .list_items
                 .get(self.main_screen.file_cursor)
+                && stayed_on_same_item
             {
                 let max_line = file.lines.len().saturating_sub(1);
                 self.main_screen.line_cursor = old_line_cursor.min(max_line);
@@ -228,16 +826,778 @@ impl AppState {
         }
         self.main_screen.file_list_scroll = old_file_list_scroll;
 
-        let max_unstaged_cursor = self.unstaged_pane.list_items.len().saturating_sub(1);
-        self.unstaged_pane.cursor = old_unstaged_cursor.min(max_unstaged_cursor);
+        let new_unstaged_anchors = Self::unstaged_pane_anchors(&self.unstaged_pane.list_items);
+        self.unstaged_pane.cursor = Self::anchored_cursor(
+            &old_unstaged_anchors,
+            &new_unstaged_anchors,
+            old_unstaged_cursor,
+            self.config.cursor_fallback,
+        );
         self.unstaged_pane.scroll = old_unstaged_scroll;
         self.unstaged_pane.diff_scroll = old_unstaged_diff_scroll;
+        self.update_staged_test();
+    }
+
+    /// Reads the staged tree again, and tests it if `staged_test_auto` is
+    /// set and it has no result yet.
+    fn update_staged_test(&mut self) {
+        if self.config.staged_test_command.is_none() {
+            return;
+        }
+        self.staged_tree = if self.files.is_empty() {
+            None
+        } else {
+            git::write_tree(&self.repo_path).ok()
+        };
+        let is_untested = self
+            .staged_test
+            .as_ref()
+            .is_none_or(|test| Some(&test.tree) != self.staged_tree.as_ref());
+        if self.config.staged_test_auto && is_untested {
+            self.start_staged_test();
+        }
     }
 
-    pub fn execute_and_refresh(&mut self, command: Box<dyn Command>) {
+    /// Starts running `staged_test_command` on the staged tree, unless a run
+    /// is going on already or nothing is staged. Returns whether it started.
+    pub fn start_staged_test(&mut self) -> bool {
+        let (Some(command), Some(tree)) = (
+            self.config.staged_test_command.clone(),
+            self.staged_tree.clone(),
+        ) else {
+            return false;
+        };
+        if self
+            .staged_test
+            .as_ref()
+            .is_some_and(|test| test.status == staged_test::Status::Running)
+        {
+            return false;
+        }
+        self.staged_test = Some(StagedTest {
+            tree: tree.clone(),
+            status: staged_test::Status::Running,
+        });
+        self.background_worker
+            .test_staged_tree(self.repo_path.clone(), tree, command);
+        true
+    }
+
+    /// Runs the configured diff filters over the staged and unstaged diffs
+    /// that changed since the last run. Failing filters are reported once
+    /// and their files shown unfiltered.
+    fn run_diff_filters(&mut self) {
+        let files = self
+            .files
+            .iter()
+            .chain(&self.unstaged_pane.unstaged_files)
+            .map(|file| (file.file_name.as_str(), file.lines.as_slice()));
+        let failures =
+            self.diff_filter_cache
+                .update(&self.config.diff_filters, &self.repo_path, files);
+        if let Some((file_name, reason)) = failures.first() {
+            self.set_message(
+                Severity::Warning,
+                format!("Diff filter failed for {file_name}, showing the raw diff: {reason}"),
+            );
+        }
+    }
+
+    /// Starts the commit message from the commit template, as `git commit`
+    /// would, with the cursor at the end of its first line.
+    pub fn fill_commit_template(&mut self) {
+        match commit_storage::load_commit_template(&self.repo_path) {
+            Ok(Some(template)) => {
+                self.main_screen.commit_cursor =
+                    template.lines().next().unwrap_or_default().chars().count();
+                self.main_screen.commit_message = template.clone();
+                self.main_screen.commit_template = Some(template);
+            }
+            Ok(None) => self.main_screen.commit_template = None,
+            Err(e) => self.set_message(
+                Severity::Error,
+                format!("Failed to prepare the commit message: {e}"),
+            ),
+        }
+    }
+
+    /// The staged diff, without its patch headers if they are hidden.
+    fn staged_diff(&self) -> Vec<FileDiff> {
+        let mut files = git::get_diff_with_context(self.repo_path.clone(), self.diff_context);
+        if self.full_file_view {
+            git::expand_to_full_file(&mut files, |name| self.full_file_content(name, true));
+        }
+        if self.hide_patch_headers {
+            git::strip_patch_headers(&mut files);
+        }
+        files
+    }
+
+    /// Shows or hides the patch headers of the staged and unstaged diffs.
+    /// The diff cursor and scroll of the focused pane move with the lines
+    /// of the shown file, so they stay on the same line.
+    pub fn toggle_patch_headers(&mut self) {
+        let shown_lines = |state: &Self| match state.focused_pane {
+            FocusedPane::Main => state.current_main_file().map(|file| file.lines.len()),
+            FocusedPane::Unstaged => state.get_unstaged_file().map(|file| file.lines.len()),
+        };
+        let before = shown_lines(self);
+        let line_cursor = self.main_screen.line_cursor;
+        let diff_scroll = match self.focused_pane {
+            FocusedPane::Main => self.main_screen.diff_scroll,
+            FocusedPane::Unstaged => self.unstaged_pane.diff_scroll,
+        };
+
+        self.hide_patch_headers = !self.hide_patch_headers;
+        self.refresh_diff(false);
+
+        let (Some(before), Some(after)) = (before, shown_lines(self)) else {
+            return;
+        };
+        let shift = |line: usize| {
+            (line + after)
+                .saturating_sub(before)
+                .min(after.saturating_sub(1))
+        };
+        self.main_screen.line_cursor = shift(line_cursor);
+        match self.focused_pane {
+            FocusedPane::Main => self.main_screen.diff_scroll = shift(diff_scroll),
+            FocusedPane::Unstaged => self.unstaged_pane.diff_scroll = shift(diff_scroll),
+        }
+    }
+
+    /// The new side of `file_name` for the full file view: what is staged,
+    /// or what is in the working tree. Files shown through a textconv
+    /// driver have other lines than the file's own, so they are not read.
+    fn full_file_content(&self, file_name: &str, staged: bool) -> Option<String> {
+        if self.textconv_files.contains(file_name) {
+            return None;
+        }
+        if staged {
+            git::run_git_command(&self.repo_path, &["show", &format!(":{file_name}")]).ok()
+        } else {
+            std::fs::read_to_string(self.repo_path.join(file_name)).ok()
+        }
+    }
+
+    /// Switches the staged and unstaged diffs between their hunks and the
+    /// whole file. A diff cursor on a hunk stays on the same line of it;
+    /// otherwise the view starts at the first hunk of the full file.
+    pub fn toggle_full_file_view(&mut self) {
+        const CONTEXT_ABOVE: usize = 3;
+        let focused_file = |state: &Self| match state.focused_pane {
+            FocusedPane::Main => state.current_main_file().cloned(),
+            FocusedPane::Unstaged => state.get_unstaged_file().cloned(),
+        };
+        let line_cursor = self.main_screen.line_cursor;
+        let in_hunk = focused_file(self).and_then(|file| {
+            file.hunks
+                .iter()
+                .position(|hunk| {
+                    (hunk.start_line..hunk.start_line + hunk.lines.len()).contains(&line_cursor)
+                })
+                .map(|index| (index, line_cursor - file.hunks[index].start_line))
+        });
+
+        self.full_file_view = !self.full_file_view;
+        self.refresh_diff(false);
+        self.set_message(
+            Severity::Info,
+            if self.full_file_view {
+                "Showing the whole files, V shows the hunks only"
+            } else {
+                "Showing the hunks only"
+            }
+            .to_string(),
+        );
+
+        let Some(file) = focused_file(self) else {
+            return;
+        };
+        let (line_cursor, diff_scroll) = match in_hunk
+            .and_then(|(index, offset)| file.hunks.get(index).map(|hunk| hunk.start_line + offset))
+        {
+            Some(line) => (line, line.saturating_sub(CONTEXT_ABOVE)),
+            None if self.full_file_view => {
+                let first = file.hunks.first().map_or(0, |hunk| hunk.start_line);
+                (first, first.saturating_sub(CONTEXT_ABOVE))
+            }
+            None => (0, 0),
+        };
+        self.main_screen.line_cursor = line_cursor;
+        match self.focused_pane {
+            FocusedPane::Main => self.main_screen.diff_scroll = diff_scroll,
+            FocusedPane::Unstaged => self.unstaged_pane.diff_scroll = diff_scroll,
+        }
+    }
+
+    /// Shows `delta` more or fewer lines of context around the changes of
+    /// the staged and unstaged diffs. The diff cursor stays on its line, or
+    /// on the first change of its hunk once the line is no longer shown,
+    /// at the same height on the screen.
+    pub fn change_diff_context(&mut self, delta: isize) {
+        let current = self
+            .diff_context
+            .unwrap_or_else(|| git::get_diff_context(&self.repo_path));
+        let context = current.saturating_add_signed(delta);
+        if context == current {
+            self.set_message(Severity::Warning, "Already showing no context");
+            self.alert();
+            return;
+        }
+        let focused_file = |state: &Self| match state.focused_pane {
+            FocusedPane::Main => state.current_main_file().cloned(),
+            FocusedPane::Unstaged => state.get_unstaged_file().cloned(),
+        };
+        let before = focused_file(self);
+        let line_cursor = self.main_screen.line_cursor;
+        let diff_scroll = match self.focused_pane {
+            FocusedPane::Main => self.main_screen.diff_scroll,
+            FocusedPane::Unstaged => self.unstaged_pane.diff_scroll,
+        };
+        let cursor_row = line_cursor.saturating_sub(diff_scroll);
+
+        self.diff_context = Some(context);
+        self.refresh_diff(false);
+        self.set_message(
+            Severity::Info,
+            format!(
+                "Showing {context} {} of context, + and - change it",
+                if context == 1 { "line" } else { "lines" }
+            ),
+        );
+
+        let Some(line) = before
+            .zip(focused_file(self))
+            .and_then(|(before, after)| git_patch::find_same_line(&before, line_cursor, &after))
+        else {
+            return;
+        };
+        self.main_screen.line_cursor = line;
+        let diff_scroll = line.saturating_sub(cursor_row);
+        match self.focused_pane {
+            FocusedPane::Main => self.main_screen.diff_scroll = diff_scroll,
+            FocusedPane::Unstaged => self.unstaged_pane.diff_scroll = diff_scroll,
+        }
+    }
+
+    fn find_textconv_files(&self) -> HashSet<String> {
+        let paths: Vec<String> = self
+            .files
+            .iter()
+            .chain(&self.unstaged_pane.unstaged_files)
+            .map(|file| file.file_name.clone())
+            .collect();
+        git::get_textconv_files(&self.repo_path, &paths).unwrap_or_default()
+    }
+
+    /// Refuses to stage, unstage, discard or edit part of `file_name` when
+    /// its diff is shown through a textconv driver, telling why.
+    pub fn refuse_partial_textconv(&mut self, file_name: &str) -> bool {
+        if !self.textconv_files.contains(file_name) {
+            return false;
+        }
+        self.set_message(
+            Severity::Warning,
+            format!(
+                "{file_name} is shown through a textconv driver, only the whole file can be staged"
+            ),
+        );
+        self.alert();
+        true
+    }
+
+    /// `file` as shown in the diff view: rewritten by its diff filter, unless
+    /// it has none, the filter failed or the raw diff was asked for. Only the
+    /// text of the lines changes, so line numbers and hunks stay valid.
+    pub fn displayed_diff<'a>(&self, file: &'a FileDiff) -> Cow<'a, FileDiff> {
+        if self.raw_diff_files.contains(&file.file_name) {
+            return Cow::Borrowed(file);
+        }
+        let filtered = diff_filter::find(&self.config.diff_filters, &file.file_name)
+            .and_then(|filter| self.diff_filter_cache.get(&filter.command, &file.lines));
+        match filtered {
+            Some(Ok(lines)) => Cow::Owned(FileDiff {
+                file_name: file.file_name.clone(),
+                old_file_name: file.old_file_name.clone(),
+                hunks: file.hunks.clone(),
+                lines: lines.clone(),
+                status: file.status.clone(),
+                binary: file.binary.clone(),
+            }),
+            _ => Cow::Borrowed(file),
+        }
+    }
+
+    /// Switches `file_name` between its filtered and its raw diff.
+    pub fn toggle_raw_diff(&mut self, file_name: &str) {
+        if diff_filter::find(&self.config.diff_filters, file_name).is_none() {
+            self.set_message(
+                Severity::Info,
+                format!("No diff filter is configured for {file_name}"),
+            );
+        } else if self.raw_diff_files.remove(file_name) {
+            self.set_message(
+                Severity::Info,
+                format!("Showing the filtered diff of {file_name}"),
+            );
+        } else {
+            self.raw_diff_files.insert(file_name.to_string());
+            self.set_message(
+                Severity::Info,
+                format!("Showing the raw diff of {file_name}"),
+            );
+        }
+    }
+
+    /// Warns when a refresh took long enough to be felt, unless something
+    /// more important is already on the status line.
+    fn record_refresh(&mut self, refresh: &Phases) {
+        let threshold = Duration::from_millis(
+            self.config
+                .slow_refresh_ms
+                .unwrap_or(profile::DEFAULT_SLOW_REFRESH_MS),
+        );
+        if self.message.is_none()
+            && let Some(warning) = profile::slow_refresh_warning(refresh, threshold)
+        {
+            self.set_message(Severity::Warning, warning);
+        }
+        self.profile.record_refresh(refresh);
+    }
+
+    fn main_screen_anchors(items: &[MainScreenListItem]) -> Vec<CursorAnchor> {
+        items
+            .iter()
+            .map(|item| match item {
+                MainScreenListItem::StagedChangesHeader => CursorAnchor::Header("staged"),
+                MainScreenListItem::File(file) => CursorAnchor::File(file.file_name.clone()),
+                MainScreenListItem::RenameGroup { group, .. } => {
+                    CursorAnchor::RenameGroup(group.key())
+                }
+                MainScreenListItem::CommitMessageInput => CursorAnchor::Header("commit_message"),
+                MainScreenListItem::PreviousCommitInfo { hash, .. }
+                | MainScreenListItem::AmendingCommitMessageInput { hash, .. }
+                | MainScreenListItem::EditingReorderCommit { hash, .. } => {
+                    CursorAnchor::Commit(hash.clone())
+                }
+            })
+            .collect()
+    }
+
+    fn unstaged_pane_anchors(items: &[UnstagedListItem]) -> Vec<CursorAnchor> {
+        items
+            .iter()
+            .map(|item| match item {
+                UnstagedListItem::UnstagedChangesHeader => CursorAnchor::Header("unstaged"),
+                UnstagedListItem::File(file) => CursorAnchor::File(file.file_name.clone()),
+                UnstagedListItem::RenameGroup(group) => CursorAnchor::RenameGroup(group.key()),
+                UnstagedListItem::UntrackedFilesHeader => CursorAnchor::Header("untracked"),
+                UnstagedListItem::LargeFilesHeader => CursorAnchor::Header("large"),
+                UnstagedListItem::UntrackedFile(name) => CursorAnchor::Untracked(name.clone()),
+            })
+            .collect()
+    }
+
+    /// Finds the item the cursor was on in the rebuilt list. When it is gone,
+    /// the nearest surviving item of the same kind is chosen according to
+    /// `fallback`, then the header of its section, and as a last resort the
+    /// old index is clamped.
+    fn anchored_cursor(
+        old: &[CursorAnchor],
+        new: &[CursorAnchor],
+        old_cursor: usize,
+        fallback: CursorFallback,
+    ) -> usize {
+        let Some(last) = new.len().checked_sub(1) else {
+            return 0;
+        };
+        let Some(anchor) = old.get(old_cursor) else {
+            return old_cursor.min(last);
+        };
+        if let Some(index) = new.iter().position(|a| a == anchor) {
+            return index;
+        }
+
+        let same_kind =
+            |a: &&CursorAnchor| std::mem::discriminant(*a) == std::mem::discriminant(anchor);
+        let after = old[old_cursor + 1..].iter().filter(same_kind);
+        let before = old[..old_cursor].iter().rev().filter(same_kind);
+        let candidates: Vec<&CursorAnchor> = match fallback {
+            CursorFallback::Next => after.chain(before).collect(),
+            CursorFallback::Previous => before.chain(after).collect(),
+        };
+        let section_header = old[..old_cursor]
+            .iter()
+            .rev()
+            .find(|a| matches!(a, CursorAnchor::Header(_)));
+        candidates
+            .into_iter()
+            .chain(section_header)
+            .find_map(|candidate| new.iter().position(|a| a == candidate))
+            .unwrap_or_else(|| old_cursor.min(last))
+    }
+
+    fn sort_files(files: &mut [FileDiff], order: FileListOrder) {
+        if order == FileListOrder::Tree {
+            files.sort_by(|a, b| compare_directories_first(&a.file_name, &b.file_name));
+        }
+    }
+
+    /// Switches between diff order and tree order, keeping the cursor on the
+    /// file it was on.
+    pub fn toggle_file_list_order(&mut self) {
+        let selected_file = self.current_main_file().map(|f| f.file_name.clone());
+
+        self.main_screen.file_list_order = match self.main_screen.file_list_order {
+            FileListOrder::Diff => FileListOrder::Tree,
+            FileListOrder::Tree => FileListOrder::Diff,
+        };
+        if self.main_screen.file_list_order == FileListOrder::Tree {
+            Self::sort_files(&mut self.files, FileListOrder::Tree);
+        } else {
+            self.files = self.staged_diff();
+        }
+        self.main_screen.list_items = Self::build_main_screen_list_items(
+            &self.files,
+            &self.previous_commits,
+            &self.main_screen.expanded_rename_groups,
+        );
+        self.apply_status_filter();
+
+        if let Some(file_name) = selected_file
+            && let Some(index) = self.main_screen.list_items.iter().position(
+                |item| matches!(item, MainScreenListItem::File(f) if f.file_name == file_name),
+            )
+        {
+            self.main_screen.file_cursor = index;
+        }
+    }
+
+    pub fn finish_editor_request(&mut self, request: EditorRequest) {
+        let return_to = request.return_to.clone();
+        self.apply_editor_request(request);
+        if let Some(anchor) = return_to {
+            self.return_to_hunk(&anchor);
+        }
+    }
+
+    fn apply_editor_request(&mut self, request: EditorRequest) {
+        if let Some(pending) = request.edit_hunk {
+            hunk_edit::finish(self, pending);
+            return;
+        }
+        if let Some(pending) = request.edit_message {
+            commit_view::finish_message_edit(self, pending);
+            return;
+        }
+        if let Some(pending) = request.stage_edit {
+            edit_stage::finish(self, pending);
+            return;
+        }
+        let Some(pending) = request.stage_if_saved else {
+            self.refresh_diff(false);
+            return;
+        };
+
+        let saved = std::fs::read(self.repo_path.join(&pending.file_name))
+            .is_ok_and(|content| content != pending.initial_content);
+        if saved && let Err(e) = git::stage_path(&self.repo_path, &pending.file_name) {
+            self.set_message(
+                Severity::Error,
+                format!("Failed to stage {}: {e}", pending.file_name),
+            );
+        }
+        self.refresh_diff(false);
+
+        if let Some(index) = self.main_screen.list_items.iter().position(
+            |item| matches!(item, MainScreenListItem::File(f) if f.file_name == pending.file_name),
+        ) {
+            self.focused_pane = FocusedPane::Main;
+            self.main_screen.file_cursor = index;
+            self.main_screen.line_cursor = 0;
+            self.main_screen.diff_scroll = 0;
+        }
+    }
+
+    /// Puts the cursor back on the hunk most like the one it was on before
+    /// the editor opened, in the pane it was in or else the other one, and
+    /// tells where the file's changes are now.
+    fn return_to_hunk(&mut self, anchor: &HunkAnchor) {
+        let in_main = self
+            .main_screen
+            .list_items
+            .iter()
+            .enumerate()
+            .find_map(|(index, item)| match item {
+                MainScreenListItem::File(file) if file.file_name == anchor.file_name => {
+                    Some((index, file))
+                }
+                _ => None,
+            });
+        let in_unstaged =
+            self.unstaged_pane
+                .list_items
+                .iter()
+                .enumerate()
+                .find_map(|(index, item)| match item {
+                    UnstagedListItem::File(file) if file.file_name == anchor.file_name => {
+                        Some((index, file))
+                    }
+                    _ => None,
+                });
+        let badge = match (in_main.is_some(), in_unstaged.is_some()) {
+            (true, true) => "staged and unstaged changes",
+            (true, false) => "staged",
+            (false, true) => "not staged",
+            (false, false) => "no changes",
+        };
+
+        let found = |pane: FocusedPane| {
+            let (index, file) = match pane {
+                FocusedPane::Main => in_main?,
+                FocusedPane::Unstaged => in_unstaged?,
+            };
+            let hunk = git_patch::find_similar_hunk(file, &anchor.lines)?;
+            let line = hunk.start_line + anchor.offset.min(hunk.lines.len().saturating_sub(1));
+            Some((pane, index, hunk.start_line, line))
+        };
+        let other_pane = match anchor.pane {
+            FocusedPane::Main => FocusedPane::Unstaged,
+            FocusedPane::Unstaged => FocusedPane::Main,
+        };
+        match found(anchor.pane).or_else(|| found(other_pane)) {
+            Some((FocusedPane::Main, index, hunk_start, line)) => {
+                self.focused_pane = FocusedPane::Main;
+                self.main_screen.file_cursor = index;
+                self.main_screen.is_diff_cursor_active = true;
+                self.main_screen.line_cursor = line;
+                self.main_screen.diff_scroll = hunk_start;
+            }
+            Some((FocusedPane::Unstaged, index, hunk_start, line)) => {
+                self.focused_pane = FocusedPane::Unstaged;
+                self.unstaged_pane.cursor = index;
+                self.unstaged_pane.is_diff_cursor_active = true;
+                self.main_screen.line_cursor = line;
+                self.unstaged_pane.diff_scroll = hunk_start;
+            }
+            None => {}
+        }
+        if self.message.is_none() {
+            self.set_message(Severity::Info, format!("{}: {badge}", anchor.file_name));
+        }
+    }
+
+    /// Whether `file_name` is still what the screen shows. If it was changed
+    /// behind our back, the lists are read again and the user is told, so
+    /// nothing is discarded based on a stale diff.
+    pub fn is_file_unchanged_since_refresh(&mut self, file_name: &str) -> bool {
+        let expected = self
+            .file_fingerprints
+            .get(file_name)
+            .cloned()
+            .unwrap_or_default();
+        if git::is_file_unchanged(&self.repo_path, file_name, &expected) {
+            return true;
+        }
+        self.refresh_diff(false);
+        self.set_message(
+            Severity::Warning,
+            format!("{file_name} changed on disk. Refreshed, check the diff and try again"),
+        );
+        self.alert();
+        false
+    }
+
+    /// Shows `text` on the status line, colored by `severity`.
+    pub fn set_message(&mut self, severity: Severity, text: impl Into<String>) {
+        self.message = Some(StatusMessage {
+            severity,
+            text: text.into(),
+        });
+    }
+
+    /// The text on the status line, if there is one.
+    pub fn message_text(&self) -> Option<&str> {
+        self.message.as_ref().map(|message| message.text.as_str())
+    }
+
+    /// How the message on the status line is to be colored, if there is one.
+    pub fn message_severity(&self) -> Option<Severity> {
+        self.message.as_ref().map(|message| message.severity)
+    }
+
+    /// Keeps the message on the status line for the message log.
+    pub fn remember_message(&mut self) {
+        if let Some(message) = &self.message {
+            self.message_log.push(&message.text, message.severity);
+        }
+    }
+
+    pub fn execute_and_refresh(&mut self, command: Box<dyn Command>) -> bool {
+        let Some(command) = self.hold_while_index_locked(command, false) else {
+            return false;
+        };
         let cursor_state = CursorState::from_app_state(self);
-        self.command_history.execute(command, cursor_state);
+        let succeeded = self.command_history.execute(command, cursor_state);
         self.refresh_diff(false);
+        if !succeeded {
+            if let Some(failure) = self.command_history.take_failure() {
+                self.set_message(Severity::Error, failure);
+            }
+            self.alert();
+        }
+        succeeded
+    }
+
+    /// Gives `command` back to run, unless another process locks the index,
+    /// in which case it waits in [`IndexLockWait`] until the lock is gone.
+    fn hold_while_index_locked(
+        &mut self,
+        command: Box<dyn Command>,
+        guarded: bool,
+    ) -> Option<Box<dyn Command>> {
+        match git::index_lock(&self.repo_path) {
+            Some(lock) => {
+                index_lock::open(self, lock, HeldAction::Command { command, guarded });
+                None
+            }
+            None => Some(command),
+        }
+    }
+
+    /// Tells why the command undone or redone last failed, if it did.
+    pub fn show_command_failure(&mut self) {
+        if let Some(failure) = self.command_history.take_failure() {
+            self.set_message(Severity::Error, failure);
+            self.alert();
+        }
+    }
+
+    /// Runs a command that stages something, then unstages what the
+    /// never-commit rules keep out, as a single undo step.
+    pub fn execute_and_guard(&mut self, command: Box<dyn Command>) -> bool {
+        let Some(command) = self.hold_while_index_locked(command, true) else {
+            return false;
+        };
+        if self.never_commit.is_empty() {
+            return self.execute_and_refresh(command);
+        }
+        self.command_history.begin_group();
+        let succeeded = self.execute_and_refresh(command);
+        if succeeded {
+            self.unstage_never_commit(true);
+        }
+        self.command_history.end_group();
+        succeeded
+    }
+
+    /// Unstages the files and lines the never-commit rules keep out, one at
+    /// a time since each changes the staged diff. With `in_history`, each
+    /// step can be undone. Returns how many were unstaged.
+    pub fn unstage_never_commit(&mut self, in_history: bool) -> usize {
+        let steps = self
+            .never_commit
+            .guarded(&self.files, &self.textconv_files)
+            .len();
+        let mut unstaged = 0;
+        // Bounded, so a patch that changes nothing cannot loop forever.
+        for _ in 0..steps {
+            let repo_path = self.repo_path.clone();
+            let mut command: Box<dyn Command> = match self
+                .never_commit
+                .guarded(&self.files, &self.textconv_files)
+                .first()
+            {
+                None => break,
+                Some(Guarded::File(file)) => {
+                    Box::new(UnstageFileCommand::new(repo_path, file.file_name.clone()))
+                }
+                Some(Guarded::Line(file, index)) => {
+                    let Some(patch) = git_patch::create_unstage_line_patch(file, *index, true)
+                    else {
+                        break;
+                    };
+                    if !git::can_apply_patch(&repo_path, &patch, true, true) {
+                        break;
+                    }
+                    Box::new(ApplyPatchCommand::new(repo_path, patch))
+                }
+            };
+            if in_history {
+                self.execute_and_refresh(command);
+            } else {
+                command.execute();
+                self.refresh_diff(false);
+            }
+            unstaged += 1;
+        }
+        if unstaged > 0 {
+            self.set_message(
+                Severity::Info,
+                format!(
+                    "Left {unstaged} never-commit change{} unstaged",
+                    if unstaged == 1 { "" } else { "s" }
+                ),
+            );
+        }
+        unstaged
+    }
+
+    /// Asks for the configured bell or flash, if any, for refusing what
+    /// the message on the status line tells about.
+    pub fn alert(&mut self) {
+        self.ring();
+    }
+
+    fn ring(&mut self) {
+        if self.config.alert != AlertStyle::Off {
+            self.alert_pending = true;
+        }
+    }
+
+    /// Sends a desktop notification about an operation started at `started`,
+    /// if it took long enough and a notify command is configured. Alerts if
+    /// it took long enough for that.
+    pub fn notify_finished(
+        &mut self,
+        operation: &str,
+        started: Instant,
+        outcome: Result<(), String>,
+    ) {
+        if alert::is_long_operation(&self.config, started.elapsed()) {
+            self.ring();
+        }
+        if let Some(args) =
+            notify::notification_command(&self.config, operation, started.elapsed(), &outcome)
+        {
+            self.background_worker.notify(args);
+        }
+    }
+
+    /// Starts pushing the current branch unless a push is already running.
+    pub fn start_push(&mut self) {
+        if self.push_started.is_some() {
+            return;
+        }
+        self.push_started = Some(Instant::now());
+        self.background_worker.push(self.repo_path.clone());
+    }
+
+    /// Starts fetching or pulling the current branch unless one of them is
+    /// already running.
+    pub fn start_remote_sync(&mut self, operation: RemoteOperation) -> bool {
+        if self.remote_sync.is_some() {
+            return false;
+        }
+        self.remote_sync = Some(RemoteSync {
+            operation,
+            started: Instant::now(),
+            progress: String::new(),
+        });
+        self.background_worker
+            .sync_with_remote(self.repo_path.clone(), operation);
+        true
     }
 
     pub fn execute_reorder_command(&mut self, command: Box<dyn Command>) {
@@ -248,14 +1608,56 @@ impl AppState {
     }
 
     pub fn update_selected_commit_diff(&mut self) {
+        self.selected_commit_word_spans.clear();
         if let Some(hash) = self.get_selected_commit_hash() {
-            self.selected_commit_files =
-                get_commit_diff(&self.repo_path, &hash).unwrap_or_default();
+            if self.main_screen.commit_word_diff {
+                let diff = git::get_commit_word_diff(&self.repo_path, &hash).unwrap_or_default();
+                self.selected_commit_files = diff.files;
+                self.selected_commit_word_spans = diff.spans;
+            } else {
+                self.selected_commit_files =
+                    git::get_commit_diff_with_fixup_preview(&self.repo_path, &hash)
+                        .unwrap_or_default();
+            }
         } else {
             self.selected_commit_files.clear();
         }
     }
 
+    /// Switches commit diffs between line and word diff. The diff is reloaded
+    /// and shown from the top, since the two have different line counts.
+    pub fn toggle_commit_word_diff(&mut self) {
+        self.main_screen.commit_word_diff = !self.main_screen.commit_word_diff;
+        self.update_selected_commit_diff();
+        self.main_screen.diff_scroll = 0;
+        self.main_screen.line_cursor = 0;
+    }
+
+    /// Expands or collapses the directory rename group with `key` and moves
+    /// the cursor onto its row.
+    pub fn toggle_rename_group(&mut self, key: (String, String)) {
+        if !self.main_screen.expanded_rename_groups.remove(&key) {
+            self.main_screen.expanded_rename_groups.insert(key.clone());
+        }
+        self.main_screen.list_items = Self::build_main_screen_list_items(
+            &self.files,
+            &self.previous_commits,
+            &self.main_screen.expanded_rename_groups,
+        );
+        self.apply_status_filter();
+        if let Some(index) = self.main_screen.list_items.iter().position(
+            |item| matches!(item, MainScreenListItem::RenameGroup { group, .. } if group.key() == key),
+        ) {
+            self.main_screen.file_cursor = index;
+        }
+        self.main_screen.diff_scroll = 0;
+        self.main_screen.line_cursor = 0;
+    }
+
+    pub fn is_bookmarked(&self, file_name: &str) -> bool {
+        self.bookmarks.iter().any(|name| name == file_name)
+    }
+
     pub fn current_main_item(&self) -> Option<&MainScreenListItem> {
         self.main_screen
             .list_items
@@ -270,6 +1672,28 @@ impl AppState {
         }
     }
 
+    /// Full path of the file selected in the focused pane, as shown in the
+    /// status bar while paths are abbreviated.
+    pub fn selected_file_path(&self) -> Option<String> {
+        let file = match self.focused_pane {
+            FocusedPane::Main => self.current_main_file(),
+            FocusedPane::Unstaged => {
+                match self.unstaged_pane.list_items.get(self.unstaged_pane.cursor) {
+                    Some(UnstagedListItem::File(file)) => Some(file),
+                    Some(UnstagedListItem::UntrackedFile(file_name)) => {
+                        return Some(file_name.clone());
+                    }
+                    _ => None,
+                }
+            }
+        }?;
+        Some(if file.status == FileStatus::Renamed {
+            format!("{} -> {}", file.old_file_name, file.file_name)
+        } else {
+            file.file_name.clone()
+        })
+    }
+
     pub fn get_unstaged_file(&self) -> Option<&FileDiff> {
         if let Some(UnstagedListItem::File(file_diff)) =
             self.unstaged_pane.list_items.get(self.unstaged_pane.cursor)
@@ -306,30 +1730,143 @@ impl AppState {
     }
 
     pub fn check_diff_update(&mut self) -> bool {
-        if let Some(last_time) = self.last_interaction_time {
-            if last_time.elapsed() > std::time::Duration::from_millis(30) {
-                if let Some(hash) = self.get_selected_commit_hash() {
+        if let Some(last_time) = self.last_interaction_time
+            && last_time.elapsed() > std::time::Duration::from_millis(30)
+        {
+            if let Some(hash) = self.get_selected_commit_hash() {
+                if self.main_screen.commit_word_diff {
+                    self.background_worker
+                        .request_commit_word_diff(self.repo_path.clone(), hash);
+                } else {
                     self.background_worker
                         .request_commit_diff(self.repo_path.clone(), hash);
                 }
-                self.last_interaction_time = None;
-                return false; // Don't trigger render yet, wait for response
             }
+            self.last_interaction_time = None;
+            return false; // Don't trigger render yet, wait for response
+        }
+        if self.last_interaction_time.is_none() {
+            self.request_blame_summary();
         }
         false
     }
 
+    /// The blame key of the staged file selected in the Main pane.
+    pub fn selected_blame_key(&self) -> Option<BlameKey> {
+        let head = self.previous_commits.first()?;
+        blame_summary::key(self.current_main_file()?, &head.full_hash)
+    }
+
+    /// Reads who last touched the selected staged file, unless it was read
+    /// already.
+    fn request_blame_summary(&mut self) {
+        if let Some(key) = self.selected_blame_key()
+            && !self.blame_summaries.contains_key(&key)
+        {
+            self.blame_summaries.insert(key.clone(), None);
+            self.background_worker
+                .request_blame_summary(self.repo_path.clone(), key);
+        }
+    }
+
     pub fn poll_background(&mut self) -> bool {
-        let mut needs_render = false;
+        let mut needs_render = index_lock::poll(self);
         while let Some(response) = self.background_worker.poll() {
             match response {
                 Response::CommitDiff(hash, diff) => {
-                    if let Some(current_hash) = self.get_selected_commit_hash() {
-                        if current_hash == hash {
-                            self.selected_commit_files = diff;
-                            needs_render = true;
-                        }
+                    if !self.main_screen.commit_word_diff
+                        && self.get_selected_commit_hash() == Some(hash)
+                    {
+                        self.selected_commit_files = diff;
+                        self.selected_commit_word_spans.clear();
+                        needs_render = true;
+                    }
+                }
+                Response::CommitWordDiff(hash, diff) => {
+                    if self.main_screen.commit_word_diff
+                        && self.get_selected_commit_hash() == Some(hash)
+                    {
+                        self.selected_commit_files = diff.files;
+                        self.selected_commit_word_spans = diff.spans;
+                        needs_render = true;
+                    }
+                }
+                Response::PushFinished(result) => {
+                    if let Some(started) = self.push_started.take() {
+                        self.notify_finished("Push", started, result.clone());
+                    }
+                    match result {
+                        // Pushed commits are now on the remote.
+                        Ok(()) => self.refresh_diff(false),
+                        Err(e) => self.set_message(Severity::Error, format!("Push failed: {e}")),
                     }
+                    needs_render = true;
+                }
+                Response::BlameSummary(key, summary) => {
+                    if self.selected_blame_key().as_ref() == Some(&key) {
+                        needs_render = true;
+                    }
+                    self.blame_summaries.insert(key, Some(summary));
+                }
+                Response::ExplanationFinished(patch, result) => {
+                    explain::finish(self, &patch, result);
+                    needs_render = true;
+                }
+                Response::RemoteProgress(line) => {
+                    if let Some(sync) = &mut self.remote_sync {
+                        sync.progress = line;
+                        needs_render = true;
+                    }
+                }
+                Response::RemoteFinished(operation, result) => {
+                    if let Some(sync) = self.remote_sync.take() {
+                        self.notify_finished(operation.name(), sync.started, result.clone());
+                    }
+                    // The rebase replaced the commits the undo history
+                    // points at, while a failed pull leaves them alone.
+                    if operation == RemoteOperation::PullRebase && result.is_ok() {
+                        self.command_history.clear();
+                    }
+                    // Even a failed pull may have fetched new commits.
+                    self.refresh_diff(false);
+                    match result {
+                        Ok(()) => self.set_message(
+                            Severity::Success,
+                            format!(
+                                "{}: {}",
+                                match operation {
+                                    RemoteOperation::Fetch => "Fetched",
+                                    RemoteOperation::PullRebase => "Pulled",
+                                },
+                                main_screen::branch_status_label(&self.branch_status)
+                            ),
+                        ),
+                        Err(e) => self.set_message(
+                            Severity::Error,
+                            format!("{} failed: {e}", operation.name()),
+                        ),
+                    }
+                    needs_render = true;
+                }
+                Response::MaintenanceFinished(command, result) => {
+                    maintenance_panel::finish(self, command, result);
+                    needs_render = true;
+                }
+                Response::StagedTestFinished(tree, result) => {
+                    let status = match result {
+                        Ok(None) => staged_test::Status::Passed,
+                        Ok(Some(output)) | Err(output) => {
+                            self.set_message(
+                                Severity::Error,
+                                format!("Staged tests failed: {}", staged_test::summary(&output)),
+                            );
+                            staged_test::Status::Failed(output)
+                        }
+                    };
+                    self.staged_test = Some(StagedTest { tree, status });
+                    // What is staged may have changed while it ran.
+                    self.update_staged_test();
+                    needs_render = true;
                 }
             }
         }
@@ -353,14 +1890,15 @@ impl AppState {
             return false;
         }
 
-        if self.selected_commit_files.is_empty() {
+        let files = commit_folds::shown_files(self);
+        if files.is_empty() {
             return false;
         }
 
         let line_index = self.main_screen.line_cursor;
 
         // The first file contains the header which includes the stat summary.
-        let first_file = &self.selected_commit_files[0];
+        let first_file = &files[0];
         if line_index >= first_file.lines.len() {
             // We are already beyond the first file's lines (including header),
             // so we are definitely not in the stat summary.
@@ -384,7 +1922,9 @@ impl AppState {
                         || right.contains('-')
                         || right.contains("Bin")
                         || (!right.is_empty()
-                            && right.chars().all(|c| c.is_ascii_digit() || c.is_whitespace()))
+                            && right
+                                .chars()
+                                .all(|c| c.is_ascii_digit() || c.is_whitespace()))
                     {
                         stat_line_indices.push(i);
                     }
@@ -397,10 +1937,10 @@ impl AppState {
             let mut target_offset = 0;
             if stat_index == 0 {
                 target_offset = patch_start_index;
-            } else if stat_index < self.selected_commit_files.len() {
+            } else if stat_index < files.len() {
                 // Sum lengths of previous files
-                for i in 0..stat_index {
-                    target_offset += self.selected_commit_files[i].lines.len();
+                for file in &files[..stat_index] {
+                    target_offset += file.lines.len();
                 }
             } else {
                 return false;
@@ -1,19 +1,76 @@
-use crate::git::{self, FileDiff};
+use crate::blame_summary::{self, Key as BlameKey, Summary as BlameSummary};
+use crate::external_command;
+use crate::git::{self, CommitWordDiff, FileDiff};
+use crate::maintenance::{self, Suggestion};
+use crate::notify;
 use std::path::PathBuf;
 use std::sync::mpsc::{Receiver, Sender, channel};
 use std::thread;
+use std::time::Instant;
 
 pub enum Request {
     GetCommitDiff(PathBuf, String),
+    GetCommitWordDiff(PathBuf, String),
+    GetBlameSummary(PathBuf, BlameKey),
+    /// Runs a notify command; there is no response.
+    Notify(Vec<String>),
+}
+
+/// What is fetched from the remote in the background.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RemoteOperation {
+    Fetch,
+    PullRebase,
+}
+
+impl RemoteOperation {
+    /// Its name for messages and notifications.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Fetch => "Fetch",
+            Self::PullRebase => "Pull",
+        }
+    }
+
+    /// What the bottom line says while it runs.
+    pub fn running_label(self) -> &'static str {
+        match self {
+            Self::Fetch => "Fetching…",
+            Self::PullRebase => "Pulling…",
+        }
+    }
+}
+
+/// A fetch or pull running on the background worker.
+#[derive(Debug, Clone)]
+pub struct RemoteSync {
+    pub operation: RemoteOperation,
+    pub started: Instant,
+    /// The last progress line git printed.
+    pub progress: String,
 }
 
 pub enum Response {
     CommitDiff(String, Vec<FileDiff>),
+    CommitWordDiff(String, CommitWordDiff),
+    BlameSummary(BlameKey, BlameSummary),
+    PushFinished(Result<(), String>),
+    /// A progress line of the running fetch or pull.
+    RemoteProgress(String),
+    RemoteFinished(RemoteOperation, Result<(), String>),
+    /// The patch explained, and whether the command succeeded with what it
+    /// printed, or why it could not run.
+    ExplanationFinished(String, Result<(bool, String), String>),
+    /// The tree tested, and what the test printed if it failed.
+    StagedTestFinished(String, Result<Option<String>, String>),
+    /// The maintenance command run, and what to tell about how it went.
+    MaintenanceFinished(String, Result<String, String>),
 }
 
 pub struct BackgroundWorker {
     tx: Sender<Request>,
     rx: Receiver<Response>,
+    res_tx: Sender<Response>,
 }
 
 impl Default for BackgroundWorker {
@@ -27,13 +84,26 @@ impl BackgroundWorker {
         let (req_tx, req_rx) = channel::<Request>();
         let (res_tx, res_rx) = channel::<Response>();
 
+        let worker_tx = res_tx.clone();
         thread::spawn(move || {
             while let Ok(request) = req_rx.recv() {
                 match request {
                     Request::GetCommitDiff(repo_path, hash) => {
-                        let diff = git::get_commit_diff(&repo_path, &hash).unwrap_or_default();
-                        let _ = res_tx.send(Response::CommitDiff(hash, diff));
+                        let diff = git::get_commit_diff_with_fixup_preview(&repo_path, &hash)
+                            .unwrap_or_default();
+                        let _ = worker_tx.send(Response::CommitDiff(hash, diff));
+                    }
+                    Request::GetCommitWordDiff(repo_path, hash) => {
+                        let diff = git::get_commit_word_diff(&repo_path, &hash).unwrap_or_default();
+                        let _ = worker_tx.send(Response::CommitWordDiff(hash, diff));
+                    }
+                    Request::GetBlameSummary(repo_path, key) => {
+                        let summary = git::blame_lines(&repo_path, &key.path, &key.ranges)
+                            .map(|porcelain| blame_summary::parse(&porcelain))
+                            .unwrap_or_default();
+                        let _ = worker_tx.send(Response::BlameSummary(key, summary));
                     }
+                    Request::Notify(args) => notify::send(&args),
                 }
             }
         });
@@ -41,6 +111,7 @@ impl BackgroundWorker {
         Self {
             tx: req_tx,
             rx: res_rx,
+            res_tx,
         }
     }
 
@@ -48,6 +119,86 @@ impl BackgroundWorker {
         let _ = self.tx.send(Request::GetCommitDiff(repo_path, hash));
     }
 
+    pub fn request_commit_word_diff(&self, repo_path: PathBuf, hash: String) {
+        let _ = self.tx.send(Request::GetCommitWordDiff(repo_path, hash));
+    }
+
+    pub fn request_blame_summary(&self, repo_path: PathBuf, key: BlameKey) {
+        let _ = self.tx.send(Request::GetBlameSummary(repo_path, key));
+    }
+
+    pub fn notify(&self, args: Vec<String>) {
+        let _ = self.tx.send(Request::Notify(args));
+    }
+
+    /// Pushes on a thread of its own, so that commit diffs keep loading while
+    /// the network is slow.
+    pub fn push(&self, repo_path: PathBuf) {
+        let res_tx = self.res_tx.clone();
+        thread::spawn(move || {
+            let result = git::push(&repo_path).map_err(|e| e.to_string());
+            let _ = res_tx.send(Response::PushFinished(result));
+        });
+    }
+
+    /// Fetches or pulls on a thread of its own, like [`Self::push`],
+    /// sending what git prints about its progress along the way.
+    pub fn sync_with_remote(&self, repo_path: PathBuf, operation: RemoteOperation) {
+        let res_tx = self.res_tx.clone();
+        thread::spawn(move || {
+            let progress = |line: &str| {
+                let _ = res_tx.send(Response::RemoteProgress(line.to_string()));
+            };
+            let result = match operation {
+                RemoteOperation::Fetch => git::fetch(&repo_path, progress),
+                RemoteOperation::PullRebase => git::pull_rebase(&repo_path, progress),
+            }
+            .map_err(|e| e.to_string());
+            let _ = res_tx.send(Response::RemoteFinished(operation, result));
+        });
+    }
+
+    /// Pipes `patch` of `file` to `command` on a thread of its own, since
+    /// explainers may take long.
+    pub fn explain(&self, repo_path: PathBuf, command: String, file: String, patch: String) {
+        let res_tx = self.res_tx.clone();
+        thread::spawn(move || {
+            let result = external_command::run_with_input(
+                &command,
+                &patch,
+                &repo_path,
+                &[("GIT_FULL_COMMIT_FILE", &file)],
+            )
+            .map(|output| {
+                let mut printed = String::from_utf8_lossy(&output.stdout).to_string();
+                printed.push_str(&String::from_utf8_lossy(&output.stderr));
+                (output.status.success(), printed)
+            })
+            .map_err(|e| e.to_string());
+            let _ = res_tx.send(Response::ExplanationFinished(patch, result));
+        });
+    }
+
+    /// Runs `command` on a checkout of `tree` on a thread of its own, as
+    /// tests take long.
+    pub fn test_staged_tree(&self, repo_path: PathBuf, tree: String, command: String) {
+        let res_tx = self.res_tx.clone();
+        thread::spawn(move || {
+            let result = git::check_tree(&repo_path, &tree, &command).map_err(|e| e.to_string());
+            let _ = res_tx.send(Response::StagedTestFinished(tree, result));
+        });
+    }
+
+    /// Runs the command of `suggestion` on a thread of its own, as packing
+    /// a large repository takes a while.
+    pub fn maintain(&self, repo_path: PathBuf, suggestion: Suggestion) {
+        let res_tx = self.res_tx.clone();
+        thread::spawn(move || {
+            let result = maintenance::run(&repo_path, &suggestion);
+            let _ = res_tx.send(Response::MaintenanceFinished(suggestion.command(), result));
+        });
+    }
+
     pub fn poll(&self) -> Option<Response> {
         self.rx.try_recv().ok()
     }
@@ -0,0 +1,135 @@
+use crate::git::FileDiff;
+
+/// Which lines of a staged file a summary is for, as of which `HEAD`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Key {
+    pub head: String,
+    /// The path on the old side.
+    pub path: String,
+    /// Ranges of old lines, first and last.
+    pub ranges: Vec<(usize, usize)>,
+}
+
+/// Authors with how many of the lines they touched last, most first.
+pub type Summary = Vec<(String, usize)>;
+
+/// The old lines `file` changes: the removed ones, and the line above where
+/// lines are only added. `None` for a file without an old side.
+pub fn key(file: &FileDiff, head: &str) -> Option<Key> {
+    let mut lines: Vec<usize> = Vec::new();
+    for hunk in &file.hunks {
+        for (line, &(old, _)) in hunk.lines.iter().zip(&hunk.line_numbers).skip(1) {
+            if (line.starts_with('-') || line.starts_with('+')) && old > 0 {
+                lines.push(old);
+            }
+        }
+    }
+    lines.sort_unstable();
+    lines.dedup();
+
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for line in lines {
+        match ranges.last_mut() {
+            Some((_, last)) if *last + 1 == line => *last = line,
+            _ => ranges.push((line, line)),
+        }
+    }
+    if ranges.is_empty() {
+        return None;
+    }
+    Some(Key {
+        head: head.to_string(),
+        path: file.old_file_name.clone(),
+        ranges,
+    })
+}
+
+/// Counts the lines per author in `git blame --line-porcelain` output.
+pub fn parse(porcelain: &str) -> Summary {
+    let mut summary: Summary = Vec::new();
+    for author in porcelain
+        .lines()
+        .filter_map(|line| line.strip_prefix("author "))
+    {
+        match summary.iter_mut().find(|(name, _)| name == author) {
+            Some((_, count)) => *count += 1,
+            None => summary.push((author.to_string(), 1)),
+        }
+    }
+    summary.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    summary
+}
+
+/// What the bottom line says, e.g. `Last touched by Alice (12), Bob (3)`.
+pub fn label(summary: &Summary) -> Option<String> {
+    if summary.is_empty() {
+        return None;
+    }
+    let authors: Vec<String> = summary
+        .iter()
+        .map(|(name, count)| format!("{name} ({count})"))
+        .collect();
+    Some(format!("Last touched by {}", authors.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::{FileStatus, Hunk};
+
+    fn file(lines: &[&str], line_numbers: &[(usize, usize)]) -> FileDiff {
+        FileDiff {
+            file_name: "a.txt".to_string(),
+            old_file_name: "a.txt".to_string(),
+            hunks: vec![Hunk {
+                start_line: 0,
+                lines: lines.iter().map(|line| line.to_string()).collect(),
+                old_start: 1,
+                new_start: 1,
+                line_numbers: line_numbers.to_vec(),
+            }],
+            lines: Vec::new(),
+            status: FileStatus::Modified,
+            binary: None,
+        }
+    }
+
+    #[test]
+    fn test_key_takes_removed_lines_and_the_line_above_additions() {
+        let file = file(
+            &["@@ -1,5 +1,5 @@", " a", "-b", "-c", "+B", " d", "+e", " f"],
+            &[
+                (0, 0),
+                (1, 1),
+                (2, 1),
+                (3, 1),
+                (3, 2),
+                (4, 3),
+                (4, 4),
+                (5, 5),
+            ],
+        );
+        let key = key(&file, "head").unwrap();
+        assert_eq!(key.ranges, [(2, 4)]);
+        assert_eq!(key.path, "a.txt");
+    }
+
+    #[test]
+    fn test_key_of_a_new_file_is_none() {
+        let file = file(&["@@ -0,0 +1,1 @@", "+a"], &[(0, 0), (0, 1)]);
+        assert_eq!(key(&file, "head"), None);
+    }
+
+    #[test]
+    fn test_parse_counts_lines_per_author() {
+        let porcelain = "abc 1 1 1\nauthor Bob\nauthor-mail <bob@example.com>\n\tline\n\
+                         abc 2 2\nauthor Alice\n\tline\nabc 3 3\nauthor Bob\n\tline\n";
+        let summary = parse(porcelain);
+        assert_eq!(summary, [("Bob".to_string(), 2), ("Alice".to_string(), 1)]);
+        assert_eq!(
+            label(&summary).as_deref(),
+            Some("Last touched by Bob (2), Alice (1)")
+        );
+        assert_eq!(label(&Vec::new()), None);
+    }
+}
@@ -0,0 +1,84 @@
+use std::path::PathBuf;
+
+use super::{Command, check};
+use crate::cursor_state::CursorState;
+use crate::git;
+
+/// Applies a patch read from a file to the working tree, or to the index
+/// and the working tree when `staged`.
+pub struct ApplyExternalPatchCommand {
+    pub repo_path: PathBuf,
+    pub patch: String,
+    pub staged: bool,
+    failure: Option<String>,
+    cursor_before_execute: Option<CursorState>,
+    cursor_before_undo: Option<CursorState>,
+}
+
+impl ApplyExternalPatchCommand {
+    pub fn new(repo_path: PathBuf, patch: String, staged: bool) -> Self {
+        Self {
+            repo_path,
+            patch,
+            staged,
+            failure: None,
+            cursor_before_execute: None,
+            cursor_before_undo: None,
+        }
+    }
+}
+
+impl Command for ApplyExternalPatchCommand {
+    fn execute(&mut self) -> bool {
+        self.failure = None;
+        if !check(
+            &mut self.failure,
+            "The patch does not apply",
+            git::check_patch(&self.repo_path, &self.patch, self.staged),
+        ) {
+            return false;
+        }
+        if self.staged
+            && !check(
+                &mut self.failure,
+                "Failed to apply the patch to the index",
+                git::apply_patch(&self.repo_path, &self.patch, false, true),
+            )
+        {
+            return false;
+        }
+        if !check(
+            &mut self.failure,
+            "Failed to apply the patch to the working tree",
+            git::apply_patch(&self.repo_path, &self.patch, false, false),
+        ) {
+            if self.staged {
+                let _ = git::apply_patch(&self.repo_path, &self.patch, true, true);
+            }
+            return false;
+        }
+        true
+    }
+
+    fn undo(&mut self) {
+        self.failure = None;
+        check(
+            &mut self.failure,
+            "Failed to revert the patch in the working tree",
+            git::apply_patch(&self.repo_path, &self.patch, true, false),
+        );
+        if self.staged {
+            check(
+                &mut self.failure,
+                "Failed to revert the patch in the index",
+                git::apply_patch(&self.repo_path, &self.patch, true, true),
+            );
+        }
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
+    command_impl!();
+}
@@ -0,0 +1,59 @@
+#[cfg(test)]
+mod tests {
+    use crate::command::test_helpers::TestRepo;
+    use crate::command::{ApplyExternalPatchCommand, Command};
+
+    const PATCH: &str =
+        "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n@@ -1,2 +1,2 @@\n one\n-two\n+2\n";
+
+    fn setup() -> TestRepo {
+        let repo = TestRepo::new();
+        repo.create_file("a.txt", "one\ntwo\n");
+        repo.add_all();
+        repo.commit("initial");
+        repo
+    }
+
+    #[test]
+    fn test_apply_to_the_working_tree() {
+        let repo = setup();
+        let mut command =
+            ApplyExternalPatchCommand::new(repo.path.clone(), PATCH.to_string(), false);
+        assert!(command.execute());
+        assert_eq!(repo.get_status(), " M a.txt\n");
+
+        command.undo();
+        assert_eq!(repo.get_status(), "");
+    }
+
+    #[test]
+    fn test_apply_to_the_index_and_the_working_tree() {
+        let repo = setup();
+        let mut command =
+            ApplyExternalPatchCommand::new(repo.path.clone(), PATCH.to_string(), true);
+        assert!(command.execute());
+        assert_eq!(repo.get_status(), "M  a.txt\n");
+
+        command.undo();
+        assert_eq!(repo.get_status(), "");
+    }
+
+    #[test]
+    fn test_a_patch_that_does_not_apply_changes_nothing() {
+        let repo = setup();
+        repo.create_file("a.txt", "one\nzwei\n");
+        let mut command =
+            ApplyExternalPatchCommand::new(repo.path.clone(), PATCH.to_string(), false);
+        assert!(!command.execute());
+        assert!(
+            command
+                .failure()
+                .unwrap()
+                .starts_with("The patch does not apply")
+        );
+        assert_eq!(
+            std::fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+            "one\nzwei\n"
+        );
+    }
+}
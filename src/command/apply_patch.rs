@@ -1,12 +1,13 @@
 use std::path::PathBuf;
 
-use super::Command;
+use super::{Command, check};
 use crate::cursor_state::CursorState;
 use crate::git;
 
 pub struct ApplyPatchCommand {
     pub repo_path: PathBuf,
     pub patch: String,
+    failure: Option<String>,
     cursor_before_execute: Option<CursorState>,
     cursor_before_undo: Option<CursorState>,
 }
@@ -16,6 +17,7 @@ impl ApplyPatchCommand {
         Self {
             repo_path,
             patch,
+            failure: None,
             cursor_before_execute: None,
             cursor_before_undo: None,
         }
@@ -24,14 +26,24 @@ impl ApplyPatchCommand {
 
 impl Command for ApplyPatchCommand {
     fn execute(&mut self) -> bool {
-        git::apply_patch(&self.repo_path, &self.patch, true, true)
-            .expect("Failed to apply patch in reverse.");
-        true
+        check(
+            &mut self.failure,
+            "Failed to unstage the patch",
+            git::apply_patch(&self.repo_path, &self.patch, true, true),
+        )
     }
 
     fn undo(&mut self) {
-        git::apply_patch(&self.repo_path, &self.patch, false, true)
-            .expect("Failed to apply patch.");
+        self.failure = None;
+        check(
+            &mut self.failure,
+            "Failed to stage the patch again",
+            git::apply_patch(&self.repo_path, &self.patch, false, true),
+        );
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
     }
 
     command_impl!();
@@ -0,0 +1,86 @@
+use std::path::PathBuf;
+
+use super::{Command, check};
+use crate::cursor_state::CursorState;
+use crate::git;
+
+/// Replaces what is staged with a saved staging recipe. Patches whose base
+/// is gone from `HEAD`, or whose change is not in the working tree, are
+/// left out, so nothing gets staged that the working tree does not have.
+pub struct ApplyRecipeCommand {
+    pub repo_path: PathBuf,
+    pub patches: Vec<String>,
+    staged_patch: String,
+    failure: Option<String>,
+    cursor_before_execute: Option<CursorState>,
+    cursor_before_undo: Option<CursorState>,
+}
+
+impl ApplyRecipeCommand {
+    pub fn new(repo_path: PathBuf, patches: Vec<String>) -> Self {
+        let staged_patch = git::get_staged_diff_patch(&repo_path).unwrap_or_default();
+        Self {
+            repo_path,
+            patches,
+            staged_patch,
+            failure: None,
+            cursor_before_execute: None,
+            cursor_before_undo: None,
+        }
+    }
+}
+
+impl Command for ApplyRecipeCommand {
+    fn execute(&mut self) -> bool {
+        self.failure = None;
+        if !check(
+            &mut self.failure,
+            "Failed to unstage all files",
+            git::unstage_all(&self.repo_path),
+        ) {
+            return false;
+        }
+        for index in 0..self.patches.len() {
+            let patch = &self.patches[index];
+            if git::can_apply_patch(&self.repo_path, patch, false, true)
+                && git::can_apply_patch(&self.repo_path, patch, true, false)
+                && !check(
+                    &mut self.failure,
+                    "Failed to apply a recipe patch",
+                    git::apply_patch(&self.repo_path, patch, false, true),
+                )
+            {
+                // Put back what was staged before.
+                let failure = self.failure.take();
+                self.undo();
+                self.failure = failure;
+                return false;
+            }
+        }
+        true
+    }
+
+    fn undo(&mut self) {
+        self.failure = None;
+        if !check(
+            &mut self.failure,
+            "Failed to unstage all files",
+            git::unstage_all(&self.repo_path),
+        ) {
+            return;
+        }
+        if !self.staged_patch.is_empty() {
+            check(
+                &mut self.failure,
+                "Failed to restore the staged changes",
+                git::apply_patch(&self.repo_path, &self.staged_patch, false, true),
+            );
+        }
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
+    command_impl!();
+}
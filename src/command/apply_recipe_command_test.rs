@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod tests {
+    use crate::command::{ApplyRecipeCommand, Command, test_helpers::TestRepo};
+    use crate::git;
+    use crate::recipe;
+
+    fn setup() -> TestRepo {
+        let repo = TestRepo::new();
+        repo.create_file("a.txt", "a\n");
+        repo.create_file("b.txt", "b\n");
+        repo.add_all();
+        repo.commit("initial");
+        repo
+    }
+
+    #[test]
+    fn test_apply_recipe_replaces_the_staged_changes() {
+        let repo = setup();
+        repo.create_file("a.txt", "a2\n");
+        repo.create_file("new.txt", "new\n");
+        repo.add_all();
+        let patch = git::get_staged_diff_patch(&repo.path).unwrap();
+        let patches = recipe::split_patch(&patch);
+        assert_eq!(patches.len(), 2);
+
+        // A fresh working tree where everything else is staged
+        repo.create_file("b.txt", "b2\n");
+        repo.add_all();
+        let mut command = ApplyRecipeCommand::new(repo.path.clone(), patches);
+
+        assert!(command.execute());
+        assert_eq!(repo.get_status(), "M  a.txt\n M b.txt\nA  new.txt\n");
+
+        command.undo();
+        assert_eq!(repo.get_status(), "M  a.txt\nM  b.txt\nA  new.txt\n");
+    }
+
+    #[test]
+    fn test_apply_recipe_skips_changes_not_in_the_working_tree() {
+        let repo = setup();
+        repo.create_file("a.txt", "a2\n");
+        repo.create_file("b.txt", "b2\n");
+        repo.add_all();
+        let patch = git::get_staged_diff_patch(&repo.path).unwrap();
+        let patches = recipe::split_patch(&patch);
+
+        // a.txt changed differently since, b.txt is back as it was
+        repo.create_file("a.txt", "a3\n");
+        repo.create_file("b.txt", "b\n");
+        repo.add_all();
+        let mut command = ApplyRecipeCommand::new(repo.path.clone(), patches);
+
+        assert!(command.execute());
+        assert_eq!(repo.get_status(), " M a.txt\n");
+    }
+}
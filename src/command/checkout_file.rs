@@ -2,22 +2,25 @@ use std::path::PathBuf;
 
 use super::Command;
 use crate::cursor_state::CursorState;
-use crate::git;
+use crate::git::{self, FileFingerprint};
 
 pub struct CheckoutFileCommand {
     pub repo_path: PathBuf,
     pub file_name: String,
     pub patch: String,
+    fingerprint: FileFingerprint,
     cursor_before_execute: Option<CursorState>,
     cursor_before_undo: Option<CursorState>,
 }
 
 impl CheckoutFileCommand {
     pub fn new(repo_path: PathBuf, file_name: String, patch: String) -> Self {
+        let fingerprint = git::file_fingerprint(&repo_path, &file_name).unwrap_or_default();
         Self {
             repo_path,
             file_name,
             patch,
+            fingerprint,
             cursor_before_execute: None,
             cursor_before_undo: None,
         }
@@ -26,6 +29,10 @@ impl CheckoutFileCommand {
 
 impl Command for CheckoutFileCommand {
     fn execute(&mut self) -> bool {
+        if !git::is_file_unchanged(&self.repo_path, &self.file_name, &self.fingerprint) {
+            // Changed since the patch to restore on undo was taken
+            return false;
+        }
         git::checkout_file(&self.repo_path, &self.file_name).expect("Failed to checkout file.");
         true
     }
@@ -33,4 +33,25 @@ mod tests {
         assert!(result);
         assert_eq!(repo.get_status(), "");
     }
+
+    #[test]
+    fn test_checkout_file_keeps_changes_made_after_new() {
+        let repo = TestRepo::new();
+        let file_name = "test.txt";
+        repo.create_file(file_name, "line1\n");
+        repo.add_all();
+        repo.commit("initial");
+        repo.append_file(file_name, "line2\n");
+
+        let patch = git::get_unstaged_file_diff_patch(&repo.path, file_name).unwrap();
+        let mut command = CheckoutFileCommand::new(repo.path.clone(), file_name.to_string(), patch);
+        // Edited in another window after the diff was taken
+        repo.append_file(file_name, "line3\n");
+
+        assert!(!command.execute());
+        assert_eq!(
+            std::fs::read_to_string(repo.path.join(file_name)).unwrap(),
+            "line1\nline2\nline3\n"
+        );
+    }
 }
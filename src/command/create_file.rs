@@ -0,0 +1,153 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{Command, check};
+use crate::cursor_state::CursorState;
+use crate::git;
+
+pub struct CreateFileCommand {
+    pub repo_path: PathBuf,
+    pub path: String,
+    pub is_dir: bool,
+    content: Vec<u8>,
+    stage: bool,
+    created_dir: Option<PathBuf>,
+    failure: Option<String>,
+    cursor_before_execute: Option<CursorState>,
+    cursor_before_undo: Option<CursorState>,
+}
+
+impl CreateFileCommand {
+    pub fn new_file(repo_path: PathBuf, path: String, content: Vec<u8>) -> Self {
+        Self::new(repo_path, path, false, content)
+    }
+
+    pub fn new_dir(repo_path: PathBuf, path: String) -> Self {
+        Self::new(repo_path, path, true, Vec::new())
+    }
+
+    fn new(repo_path: PathBuf, path: String, is_dir: bool, content: Vec<u8>) -> Self {
+        Self {
+            repo_path,
+            path,
+            is_dir,
+            content,
+            stage: false,
+            created_dir: None,
+            failure: None,
+            cursor_before_execute: None,
+            cursor_before_undo: None,
+        }
+    }
+
+    /// Returns the outermost directory that has to be created for `path`.
+    fn first_missing_dir(&self) -> Option<PathBuf> {
+        let path = Path::new(&self.path);
+        let skip = if self.is_dir { 0 } else { 1 };
+        path.ancestors()
+            .skip(skip)
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .take_while(|dir| !self.repo_path.join(dir).exists())
+            .last()
+            .map(Path::to_path_buf)
+    }
+
+    /// Removes the directories that `execute` created, innermost first.
+    fn remove_created_dirs(&self) {
+        if let Some(created_dir) = &self.created_dir {
+            let skip = if self.is_dir { 0 } else { 1 };
+            for dir in Path::new(&self.path).ancestors().skip(skip) {
+                if fs::remove_dir(self.repo_path.join(dir)).is_err() || dir == created_dir {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl Command for CreateFileCommand {
+    fn execute(&mut self) -> bool {
+        self.failure = None;
+        let full_path = self.repo_path.join(&self.path);
+        if full_path.exists() {
+            self.failure = Some(format!("Cannot create: {} already exists", self.path));
+            return false;
+        }
+        self.created_dir = self.first_missing_dir();
+
+        if self.is_dir {
+            return check(
+                &mut self.failure,
+                &format!("Failed to create {}", self.path),
+                fs::create_dir_all(&full_path).map_err(Into::into),
+            );
+        }
+        if let Some(parent) = full_path.parent()
+            && !check(
+                &mut self.failure,
+                &format!("Failed to create {}", self.path),
+                fs::create_dir_all(parent).map_err(Into::into),
+            )
+        {
+            return false;
+        }
+        if !check(
+            &mut self.failure,
+            &format!("Failed to write {}", self.path),
+            fs::write(&full_path, &self.content).map_err(Into::into),
+        ) {
+            self.remove_created_dirs();
+            return false;
+        }
+        if self.stage
+            && !check(
+                &mut self.failure,
+                &format!("Failed to stage {}", self.path),
+                git::stage_path(&self.repo_path, &self.path),
+            )
+        {
+            let _ = fs::remove_file(&full_path);
+            self.remove_created_dirs();
+            return false;
+        }
+        true
+    }
+
+    fn undo(&mut self) {
+        self.failure = None;
+        let full_path = self.repo_path.join(&self.path);
+        if !self.is_dir {
+            // Keep what was written in the editor so that redo restores it.
+            self.content = fs::read(&full_path).unwrap_or_default();
+            self.stage = git::is_tracked(&self.repo_path, &self.path);
+            if self.stage
+                && !check(
+                    &mut self.failure,
+                    &format!("Failed to unstage {}", self.path),
+                    git::rm_cached(&self.repo_path, &self.path),
+                )
+            {
+                return;
+            }
+            // Already gone is as good as removed.
+            let removed = match fs::remove_file(&full_path) {
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                result => result,
+            };
+            if !check(
+                &mut self.failure,
+                &format!("Failed to remove {}", self.path),
+                removed.map_err(Into::into),
+            ) {
+                return;
+            }
+        }
+        self.remove_created_dirs();
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
+    command_impl!();
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+    use crate::command::{Command, CreateFileCommand, test_helpers::TestRepo};
+
+    #[test]
+    fn test_create_file_from_template() {
+        let repo = TestRepo::new();
+        let mut command = CreateFileCommand::new_file(
+            repo.path.clone(),
+            "notes/todo.md".into(),
+            b"# TODO\n".to_vec(),
+        );
+
+        // Execute
+        assert!(command.execute());
+        assert_eq!(
+            std::fs::read_to_string(repo.path.join("notes/todo.md")).unwrap(),
+            "# TODO\n"
+        );
+        assert_eq!(repo.get_status(), "?? notes/\n");
+
+        // Undo removes the file and the created directory
+        command.undo();
+        assert!(!repo.path.join("notes").exists());
+        assert_eq!(repo.get_status(), "");
+    }
+
+    #[test]
+    fn test_undo_of_staged_file_is_redone_with_edited_content() {
+        let repo = TestRepo::new();
+        let mut command =
+            CreateFileCommand::new_file(repo.path.clone(), "a.txt".into(), Vec::new());
+
+        assert!(command.execute());
+        // Simulate editing and staging after the editor was closed.
+        repo.create_file("a.txt", "edited");
+        repo.add_file("a.txt");
+        assert_eq!(repo.get_status(), "A  a.txt\n");
+
+        command.undo();
+        assert_eq!(repo.get_status(), "");
+
+        // Redo
+        assert!(command.execute());
+        assert_eq!(repo.get_status(), "A  a.txt\n");
+        assert_eq!(
+            std::fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+            "edited"
+        );
+    }
+
+    #[test]
+    fn test_create_directory() {
+        let repo = TestRepo::new();
+        let mut command = CreateFileCommand::new_dir(repo.path.clone(), "a/b".into());
+
+        assert!(command.execute());
+        assert!(repo.path.join("a/b").is_dir());
+
+        command.undo();
+        assert!(!repo.path.join("a").exists());
+    }
+
+    #[test]
+    fn test_create_existing_file_fails() {
+        let repo = TestRepo::new();
+        repo.create_file("a.txt", "keep");
+        let mut command =
+            CreateFileCommand::new_file(repo.path.clone(), "a.txt".into(), Vec::new());
+
+        assert!(!command.execute());
+        assert_eq!(
+            command.failure().as_deref(),
+            Some("Cannot create: a.txt already exists")
+        );
+        assert_eq!(
+            std::fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+            "keep"
+        );
+    }
+
+    #[test]
+    fn test_create_file_under_a_file_reports_why() {
+        let repo = TestRepo::new();
+        repo.create_file("a.txt", "keep");
+        let mut command =
+            CreateFileCommand::new_file(repo.path.clone(), "a.txt/b.txt".into(), Vec::new());
+
+        assert!(!command.execute());
+        assert!(
+            command
+                .failure()
+                .unwrap()
+                .starts_with("Failed to create a.txt/b.txt: ")
+        );
+    }
+}
@@ -3,21 +3,25 @@ use std::path::PathBuf;
 
 use super::Command;
 use crate::cursor_state::CursorState;
+use crate::git::{self, FileFingerprint};
 
 pub struct DeleteUntrackedFileCommand {
     pub repo_path: PathBuf,
     pub file_name: String,
     content: Vec<u8>,
+    fingerprint: FileFingerprint,
     cursor_before_execute: Option<CursorState>,
     cursor_before_undo: Option<CursorState>,
 }
 
 impl DeleteUntrackedFileCommand {
     pub fn new(repo_path: PathBuf, file_name: String, content: Vec<u8>) -> Self {
+        let fingerprint = git::file_fingerprint(&repo_path, &file_name).unwrap_or_default();
         Self {
             repo_path,
             file_name,
             content,
+            fingerprint,
             cursor_before_execute: None,
             cursor_before_undo: None,
         }
@@ -26,6 +30,10 @@ impl DeleteUntrackedFileCommand {
 
 impl Command for DeleteUntrackedFileCommand {
     fn execute(&mut self) -> bool {
+        if !git::is_file_unchanged(&self.repo_path, &self.file_name, &self.fingerprint) {
+            // Changed since the content to restore on undo was read
+            return false;
+        }
         fs::remove_file(self.repo_path.join(&self.file_name)).expect("Failed to delete file");
         true
     }
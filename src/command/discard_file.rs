@@ -3,13 +3,14 @@ use std::path::PathBuf;
 
 use super::Command;
 use crate::cursor_state::CursorState;
-use crate::git;
+use crate::git::{self, FileFingerprint};
 
 pub struct DiscardFileCommand {
     pub repo_path: PathBuf,
     pub file_name: String,
     staged_patch: String,
     is_new_file: bool,
+    fingerprint: FileFingerprint,
     cursor_before_execute: Option<CursorState>,
     cursor_before_undo: Option<CursorState>,
 }
@@ -17,11 +18,13 @@ pub struct DiscardFileCommand {
 impl DiscardFileCommand {
     pub fn new(repo_path: PathBuf, file_name: String, is_new_file: bool) -> Self {
         let staged_patch = git::get_file_diff_patch(&repo_path, &file_name).unwrap_or_default();
+        let fingerprint = git::file_fingerprint(&repo_path, &file_name).unwrap_or_default();
         Self {
             repo_path,
             file_name,
             staged_patch,
             is_new_file,
+            fingerprint,
             cursor_before_execute: None,
             cursor_before_undo: None,
         }
@@ -34,6 +37,10 @@ impl Command for DiscardFileCommand {
             // Don't discard if there are unstaged changes
             return false;
         }
+        if !git::is_file_unchanged(&self.repo_path, &self.file_name, &self.fingerprint) {
+            // Changed since the patch to restore on undo was taken
+            return false;
+        }
 
         if self.is_new_file {
             git::rm_cached(&self.repo_path, &self.file_name)
@@ -3,22 +3,28 @@ use std::path::PathBuf;
 use lazy_static::lazy_static;
 use regex::Regex;
 
-use super::Command;
+use super::{Command, check};
 use crate::cursor_state::CursorState;
-use crate::git;
+use crate::git::{self, FileFingerprint};
 
 pub struct DiscardHunkCommand {
     pub repo_path: PathBuf,
     pub patch: String,
+    fingerprint: Option<FileFingerprint>,
+    failure: Option<String>,
     cursor_before_execute: Option<CursorState>,
     cursor_before_undo: Option<CursorState>,
 }
 
 impl DiscardHunkCommand {
     pub fn new(repo_path: PathBuf, patch: String) -> Self {
+        let fingerprint = get_file_name_from_patch(&patch)
+            .map(|file_name| git::file_fingerprint(&repo_path, &file_name).unwrap_or_default());
         Self {
             repo_path,
             patch,
+            fingerprint,
+            failure: None,
             cursor_before_execute: None,
             cursor_before_undo: None,
         }
@@ -27,34 +33,66 @@ impl DiscardHunkCommand {
 
 impl Command for DiscardHunkCommand {
     fn execute(&mut self) -> bool {
-        if let Some(file_name) = get_file_name_from_patch(&self.patch) {
-            if git::has_unstaged_changes_in_file(&self.repo_path, &file_name).unwrap_or(true) {
-                // Don't discard if there are unstaged changes
-                return false;
-            }
+        if let Some(file_name) = get_file_name_from_patch(&self.patch)
+            && git::has_unstaged_changes_in_file(&self.repo_path, &file_name).unwrap_or(true)
+        {
+            // Don't discard if there are unstaged changes
+            return false;
+        }
+        if let Some(file_name) = get_file_name_from_patch(&self.patch)
+            && let Some(fingerprint) = &self.fingerprint
+            && !git::is_file_unchanged(&self.repo_path, &file_name, fingerprint)
+        {
+            // Changed since the hunk was taken
+            return false;
         }
 
         // Unstage
-        git::apply_patch(&self.repo_path, &self.patch, true, true)
-            .expect("Failed to unstage hunk.");
+        if !check(
+            &mut self.failure,
+            "Failed to unstage the hunk",
+            git::apply_patch(&self.repo_path, &self.patch, true, true),
+        ) {
+            return false;
+        }
         // Discard from working tree
-        git::apply_patch(&self.repo_path, &self.patch, true, false)
-            .expect("Failed to discard hunk from working tree.");
+        if !check(
+            &mut self.failure,
+            "Failed to discard the hunk from the working tree",
+            git::apply_patch(&self.repo_path, &self.patch, true, false),
+        ) {
+            // Stage it again, so nothing changed
+            let _ = git::apply_patch(&self.repo_path, &self.patch, false, true);
+            return false;
+        }
         true
     }
 
     fn undo(&mut self) {
+        self.failure = None;
         // Re-apply to working tree
-        git::apply_patch(&self.repo_path, &self.patch, false, false)
-            .expect("Failed to re-apply hunk to working tree.");
-        // Stage
-        git::apply_patch(&self.repo_path, &self.patch, false, true).expect("Failed to stage hunk.");
+        if check(
+            &mut self.failure,
+            "Failed to restore the hunk in the working tree",
+            git::apply_patch(&self.repo_path, &self.patch, false, false),
+        ) {
+            // Stage
+            check(
+                &mut self.failure,
+                "Failed to stage the hunk again",
+                git::apply_patch(&self.repo_path, &self.patch, false, true),
+            );
+        }
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
     }
 
     command_impl!();
 }
 
-fn get_file_name_from_patch(patch: &str) -> Option<String> {
+pub(super) fn get_file_name_from_patch(patch: &str) -> Option<String> {
     lazy_static! {
         static ref RE: Regex = Regex::new(r#"^diff --git a/("[^"]+"|\S+)"#).unwrap();
     }
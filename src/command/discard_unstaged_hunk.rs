@@ -1,21 +1,28 @@
 use std::path::PathBuf;
 
-use super::Command;
+use super::discard_hunk::get_file_name_from_patch;
+use super::{Command, check};
 use crate::cursor_state::CursorState;
-use crate::git;
+use crate::git::{self, FileFingerprint};
 
 pub struct DiscardUnstagedHunkCommand {
     pub repo_path: PathBuf,
     pub patch: String,
+    fingerprint: Option<FileFingerprint>,
+    failure: Option<String>,
     cursor_before_execute: Option<CursorState>,
     cursor_before_undo: Option<CursorState>,
 }
 
 impl DiscardUnstagedHunkCommand {
     pub fn new(repo_path: PathBuf, patch: String) -> Self {
+        let fingerprint = get_file_name_from_patch(&patch)
+            .map(|file_name| git::file_fingerprint(&repo_path, &file_name).unwrap_or_default());
         Self {
             repo_path,
             patch,
+            fingerprint,
+            failure: None,
             cursor_before_execute: None,
             cursor_before_undo: None,
         }
@@ -24,14 +31,31 @@ impl DiscardUnstagedHunkCommand {
 
 impl Command for DiscardUnstagedHunkCommand {
     fn execute(&mut self) -> bool {
-        git::apply_patch(&self.repo_path, &self.patch, true, false)
-            .expect("Failed to discard hunk from working tree.");
-        true
+        if let Some(file_name) = get_file_name_from_patch(&self.patch)
+            && let Some(fingerprint) = &self.fingerprint
+            && !git::is_file_unchanged(&self.repo_path, &file_name, fingerprint)
+        {
+            // Changed since the hunk was taken
+            return false;
+        }
+        check(
+            &mut self.failure,
+            "Failed to discard the hunk from the working tree",
+            git::apply_patch(&self.repo_path, &self.patch, true, false),
+        )
     }
 
     fn undo(&mut self) {
-        git::apply_patch(&self.repo_path, &self.patch, false, false)
-            .expect("Failed to re-apply hunk to working tree.");
+        self.failure = None;
+        check(
+            &mut self.failure,
+            "Failed to restore the hunk in the working tree",
+            git::apply_patch(&self.repo_path, &self.patch, false, false),
+        );
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
     }
 
     command_impl!();
@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+use super::Command;
+use crate::cursor_state::CursorState;
+use crate::git::{self, RebaseStep};
+
+/// Rebases a local commit out of the branch, with the commits after it
+/// picked onto its parent.
+pub struct DropCommitCommand {
+    pub repo_path: PathBuf,
+    /// The dropped commit and the newer ones, oldest first.
+    pub steps: Vec<RebaseStep>,
+    /// The dropped commit, picked back on undo if the branch moved on.
+    pub hash: String,
+    head_before: String,
+    head_after: String,
+    cursor_before_execute: Option<CursorState>,
+    cursor_before_undo: Option<CursorState>,
+}
+
+impl DropCommitCommand {
+    pub fn new(repo_path: PathBuf, steps: Vec<RebaseStep>, hash: String) -> Self {
+        Self {
+            repo_path,
+            steps,
+            hash,
+            head_before: String::new(),
+            head_after: String::new(),
+            cursor_before_execute: None,
+            cursor_before_undo: None,
+        }
+    }
+}
+
+impl Command for DropCommitCommand {
+    fn execute(&mut self) -> bool {
+        let Ok(head) = git::head_hash(&self.repo_path) else {
+            return false;
+        };
+        if git::run_rebase_plan(&self.repo_path, &self.steps).is_err() {
+            return false;
+        }
+        self.head_before = head;
+        self.head_after = git::head_hash(&self.repo_path).unwrap_or_default();
+        true
+    }
+
+    fn undo(&mut self) {
+        // The branch is put back as it was, unless it moved on since.
+        if git::head_hash(&self.repo_path).is_ok_and(|head| head == self.head_after)
+            && git::reset_keep(&self.repo_path, &self.head_before).is_ok()
+        {
+            return;
+        }
+        if git::cherry_pick(&self.repo_path, &self.hash).is_err() {
+            let _ = git::cherry_pick_abort(&self.repo_path);
+        }
+    }
+
+    command_impl!();
+}
@@ -0,0 +1,62 @@
+use crate::command::test_helpers::{TestRepo, commit, create_file, get_log};
+use crate::command::{Command, DropCommitCommand};
+use crate::git::{self, RebaseAction, RebaseStep};
+
+fn subjects(repo: &TestRepo) -> Vec<String> {
+    get_log(&repo.path)
+        .into_iter()
+        .map(|commit| commit.message)
+        .collect()
+}
+
+/// Drops `second` from `first`, `second` (adding `a.txt`) and `third`.
+fn setup() -> (TestRepo, DropCommitCommand) {
+    let repo = TestRepo::new();
+    commit(&repo.path, "first");
+    create_file(&repo.path, "a.txt", "a\n");
+    commit(&repo.path, "second");
+    create_file(&repo.path, "b.txt", "b\n");
+    commit(&repo.path, "third");
+
+    let log = get_log(&repo.path);
+    let step = |index: usize, action| RebaseStep {
+        hash: log[index].hash.clone(),
+        message: log[index].message.clone(),
+        action,
+        new_message: None,
+    };
+    let steps = vec![step(1, RebaseAction::Drop), step(0, RebaseAction::Pick)];
+    let command = DropCommitCommand::new(repo.path.clone(), steps, log[1].full_hash.clone());
+    (repo, command)
+}
+
+#[test]
+fn test_drop_commit_and_undo() {
+    let (repo, mut command) = setup();
+    let original_head = git::head_hash(&repo.path).unwrap();
+
+    // Execute
+    assert!(command.execute());
+    assert_eq!(subjects(&repo), vec!["third", "first"]);
+    assert!(!repo.path.join("a.txt").exists());
+
+    // Undo puts the branch back as it was
+    command.undo();
+    assert_eq!(git::head_hash(&repo.path).unwrap(), original_head);
+    assert!(repo.path.join("a.txt").exists());
+
+    // Redo
+    assert!(command.execute());
+    assert_eq!(subjects(&repo), vec!["third", "first"]);
+}
+
+#[test]
+fn test_undo_after_the_branch_moved_picks_the_commit_back() {
+    let (repo, mut command) = setup();
+    assert!(command.execute());
+    commit(&repo.path, "fourth");
+
+    command.undo();
+    assert_eq!(subjects(&repo), vec!["second", "fourth", "third", "first"]);
+    assert!(repo.path.join("a.txt").exists());
+}
@@ -26,10 +26,10 @@ impl FixupCommitCommand {
                 .iter()
                 .rposition(|item| matches!(item, ListItem::PreviousCommitInfo { .. }));
 
-            if let Some(last_commit_index) = last_commit_index {
-                if self.index == last_commit_index {
-                    return false; // Can't fixup the root commit
-                }
+            if let Some(last_commit_index) = last_commit_index
+                && self.index == last_commit_index
+            {
+                return false; // Can't fixup the root commit
             }
 
             if let Some(ListItem::PreviousCommitInfo { is_fixup, .. }) =
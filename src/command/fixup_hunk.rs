@@ -0,0 +1,73 @@
+use std::path::PathBuf;
+
+use super::Command;
+use crate::cursor_state::CursorState;
+use crate::git;
+
+/// Squashes one unstaged hunk straight into an earlier local commit: it is
+/// staged on its own, committed as a `fixup!` of the target and squashed
+/// with `rebase --autosquash`. Whatever was staged before stays staged.
+pub struct FixupHunkCommand {
+    pub repo_path: PathBuf,
+    pub patch: String,
+    pub target_hash: String,
+    /// HEAD and the index, written as a tree, before the fixup.
+    before: Option<(String, String)>,
+    cursor_before_execute: Option<CursorState>,
+    cursor_before_undo: Option<CursorState>,
+}
+
+impl FixupHunkCommand {
+    pub fn new(repo_path: PathBuf, patch: String, target_hash: String) -> Self {
+        Self {
+            repo_path,
+            patch,
+            target_hash,
+            before: None,
+            cursor_before_execute: None,
+            cursor_before_undo: None,
+        }
+    }
+
+    fn restore(&self, head: &str, index: &str) {
+        let _ = git::reset_soft(&self.repo_path, head);
+        let _ = git::read_tree(&self.repo_path, index);
+    }
+
+    fn fixup(&self) -> anyhow::Result<()> {
+        let staged = git::get_staged_diff_patch(&self.repo_path)?;
+        git::read_tree(&self.repo_path, "HEAD")?;
+        git::apply_patch(&self.repo_path, &self.patch, false, true)?;
+        git::fixup_staged_changes(&self.repo_path, &self.target_hash)?;
+        if !staged.is_empty() {
+            git::apply_patch(&self.repo_path, &staged, false, true)?;
+        }
+        Ok(())
+    }
+}
+
+impl Command for FixupHunkCommand {
+    fn execute(&mut self) -> bool {
+        let Ok(head) = git::run_git_command(&self.repo_path, &["rev-parse", "HEAD"]) else {
+            return false;
+        };
+        let head = head.trim().to_string();
+        let Ok(index) = git::write_tree(&self.repo_path) else {
+            return false;
+        };
+        if self.fixup().is_err() {
+            self.restore(&head, &index);
+            return false;
+        }
+        self.before = Some((head, index));
+        true
+    }
+
+    fn undo(&mut self) {
+        if let Some((head, index)) = self.before.take() {
+            self.restore(&head, &index);
+        }
+    }
+
+    command_impl!();
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use crate::command::{Command, FixupHunkCommand, test_helpers::TestRepo};
+    use crate::git;
+
+    fn rev_parse(repo: &TestRepo, rev: &str) -> String {
+        git::run_git_command(&repo.path, &["rev-parse", rev])
+            .unwrap()
+            .trim()
+            .to_string()
+    }
+
+    /// `a.txt` from the first commit changed in the working tree, `b.txt`
+    /// from the second one changed and staged.
+    fn setup() -> (TestRepo, String) {
+        let repo = TestRepo::new();
+        repo.create_file("a.txt", "a\n");
+        repo.add_all();
+        repo.commit("first");
+        let first = rev_parse(&repo, "HEAD");
+        repo.create_file("b.txt", "b\n");
+        repo.add_all();
+        repo.commit("second");
+        repo.create_file("b.txt", "b2\n");
+        repo.add_all();
+        repo.create_file("a.txt", "a2\n");
+        (repo, first)
+    }
+
+    #[test]
+    fn test_hunk_is_squashed_into_the_commit_and_staged_changes_stay() {
+        let (repo, first) = setup();
+        let head = rev_parse(&repo, "HEAD");
+        let patch = git::get_unstaged_file_diff_patch(&repo.path, "a.txt").unwrap();
+
+        let mut command = FixupHunkCommand::new(repo.path.clone(), patch, first);
+        assert!(command.execute());
+        let log = git::run_git_command(&repo.path, &["log", "--format=%s"]).unwrap();
+        assert_eq!(log, "second\nfirst\n");
+        let content = git::run_git_command(&repo.path, &["show", "HEAD~1:a.txt"]).unwrap();
+        assert_eq!(content, "a2\n");
+        assert_eq!(repo.get_status(), "M  b.txt\n");
+
+        command.undo();
+        assert_eq!(rev_parse(&repo, "HEAD"), head);
+        assert_eq!(repo.get_status(), " M a.txt\nM  b.txt\n");
+    }
+
+    #[test]
+    fn test_failed_fixup_changes_nothing() {
+        let (repo, _first) = setup();
+        let head = rev_parse(&repo, "HEAD");
+        let patch = git::get_unstaged_file_diff_patch(&repo.path, "a.txt").unwrap();
+
+        let mut command = FixupHunkCommand::new(repo.path.clone(), patch, "0000000".to_string());
+        assert!(!command.execute());
+        assert_eq!(rev_parse(&repo, "HEAD"), head);
+        assert_eq!(repo.get_status(), " M a.txt\nM  b.txt\n");
+    }
+}
@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+
+use super::{Command, check};
+use crate::cursor_state::CursorState;
+use crate::git;
+
+/// Stages the formatting fixes of the staged content, and makes them in the
+/// working tree too where they apply, so they don't show up reverted as
+/// unstaged changes.
+pub struct FormatStagedCommand {
+    pub repo_path: PathBuf,
+    pub patches: Vec<String>,
+    /// Whether each patch was also applied to the working tree.
+    pub in_working_tree: Vec<bool>,
+    failure: Option<String>,
+    cursor_before_execute: Option<CursorState>,
+    cursor_before_undo: Option<CursorState>,
+}
+
+impl FormatStagedCommand {
+    pub fn new(repo_path: PathBuf, patches: Vec<String>) -> Self {
+        Self {
+            repo_path,
+            patches,
+            in_working_tree: Vec::new(),
+            failure: None,
+            cursor_before_execute: None,
+            cursor_before_undo: None,
+        }
+    }
+}
+
+impl Command for FormatStagedCommand {
+    fn execute(&mut self) -> bool {
+        self.failure = None;
+        if !self
+            .patches
+            .iter()
+            .all(|patch| git::can_apply_patch(&self.repo_path, patch, false, true))
+        {
+            self.failure = Some("The staged changes moved on, format them again".to_string());
+            return false;
+        }
+        self.in_working_tree.clear();
+        for index in 0..self.patches.len() {
+            let patch = &self.patches[index];
+            if !check(
+                &mut self.failure,
+                "Failed to stage the formatting fix",
+                git::apply_patch(&self.repo_path, patch, false, true),
+            ) {
+                // Take back the fixes staged so far.
+                let failure = self.failure.take();
+                self.undo();
+                self.failure = failure;
+                return false;
+            }
+            // Left out where the working tree changed the same lines.
+            self.in_working_tree
+                .push(git::apply_patch(&self.repo_path, patch, false, false).is_ok());
+        }
+        true
+    }
+
+    fn undo(&mut self) {
+        self.failure = None;
+        for (patch, in_working_tree) in self.patches.iter().zip(&self.in_working_tree).rev() {
+            if *in_working_tree {
+                check(
+                    &mut self.failure,
+                    "Failed to revert the formatting fix in the working tree",
+                    git::apply_patch(&self.repo_path, patch, true, false),
+                );
+            }
+            check(
+                &mut self.failure,
+                "Failed to unstage the formatting fix",
+                git::apply_patch(&self.repo_path, patch, true, true),
+            );
+        }
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
+    command_impl!();
+}
@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod tests {
+    use crate::command::test_helpers::TestRepo;
+    use crate::command::{Command, FormatStagedCommand};
+    use crate::formatter;
+
+    #[test]
+    fn test_format_staged_fixes_the_index_and_the_working_tree() {
+        let repo = TestRepo::new();
+        repo.create_file("a.txt", "one\ntwo\n");
+        repo.add_all();
+        repo.commit("initial");
+        repo.create_file("a.txt", "one  \ntwo\n");
+        repo.add_all();
+        repo.create_file("a.txt", "one  \ntwo\nthree\n");
+
+        let patch = formatter::patch("a.txt", "one  \ntwo\n", "one\ntwo\n").unwrap();
+        let mut command = FormatStagedCommand::new(repo.path.clone(), vec![patch]);
+        assert!(command.execute());
+        assert_eq!(command.in_working_tree, [true]);
+        assert_eq!(repo.get_status(), " M a.txt\n");
+        assert_eq!(
+            std::fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+            "one\ntwo\nthree\n"
+        );
+
+        command.undo();
+        assert_eq!(repo.get_status(), "MM a.txt\n");
+        assert_eq!(
+            std::fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+            "one  \ntwo\nthree\n"
+        );
+    }
+
+    #[test]
+    fn test_format_staged_leaves_a_conflicting_working_tree_alone() {
+        let repo = TestRepo::new();
+        repo.create_file("a.txt", "one\n");
+        repo.add_all();
+        repo.commit("initial");
+        repo.create_file("a.txt", "one  \n");
+        repo.add_all();
+        repo.create_file("a.txt", "uno\n");
+
+        let patch = formatter::patch("a.txt", "one  \n", "one\n").unwrap();
+        let mut command = FormatStagedCommand::new(repo.path.clone(), vec![patch]);
+        assert!(command.execute());
+        assert_eq!(command.in_working_tree, [false]);
+        // The index is back to HEAD, the working tree keeps its own change.
+        assert_eq!(repo.get_status(), " M a.txt\n");
+        assert_eq!(
+            std::fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+            "uno\n"
+        );
+    }
+
+    #[test]
+    fn test_format_staged_undo_reports_a_changed_index() {
+        let repo = TestRepo::new();
+        repo.create_file(
+            "a.txt", "one
+",
+        );
+        repo.add_all();
+        repo.commit("initial");
+        repo.create_file(
+            "a.txt", "one  
+",
+        );
+        repo.add_all();
+
+        let patch = formatter::patch("a.txt", "one  \n", "one\n").unwrap();
+        let mut command = FormatStagedCommand::new(repo.path.clone(), vec![patch]);
+        assert!(command.execute());
+        repo.create_file("a.txt", "uno\n");
+        repo.add_all();
+
+        command.undo();
+        assert!(
+            command
+                .failure()
+                .unwrap()
+                .starts_with("Failed to unstage the formatting fix")
+        );
+        assert_eq!(repo.get_status(), "M  a.txt\n");
+    }
+}
@@ -0,0 +1,67 @@
+use super::Command;
+use crate::cursor_state::CursorState;
+
+/// Several commands run as one step of the undo history, e.g. the parts of
+/// a single user action. They are undone in reverse order.
+pub struct GroupCommand {
+    pub commands: Vec<Box<dyn Command>>,
+    failure: Option<String>,
+    cursor_before_execute: Option<CursorState>,
+    cursor_before_undo: Option<CursorState>,
+}
+
+impl GroupCommand {
+    pub fn new(commands: Vec<Box<dyn Command>>) -> Self {
+        Self {
+            commands,
+            failure: None,
+            cursor_before_execute: None,
+            cursor_before_undo: None,
+        }
+    }
+
+    /// Groups commands that already ran, restoring the cursor from before
+    /// the first of them on undo.
+    pub(super) fn from_executed(commands: Vec<Box<dyn Command>>) -> Self {
+        let cursor_before_execute = commands
+            .first()
+            .and_then(|command| command.get_cursor_to_restore_on_undo());
+        Self {
+            commands,
+            failure: None,
+            cursor_before_execute,
+            cursor_before_undo: None,
+        }
+    }
+}
+
+impl Command for GroupCommand {
+    /// Runs the commands in order. If one fails, those that ran are undone,
+    /// so the group either happens as a whole or not at all.
+    fn execute(&mut self) -> bool {
+        for index in 0..self.commands.len() {
+            if !self.commands[index].execute() {
+                self.failure = self.commands[index].failure();
+                for command in self.commands[..index].iter_mut().rev() {
+                    command.undo();
+                }
+                return false;
+            }
+        }
+        true
+    }
+
+    fn undo(&mut self) {
+        self.failure = None;
+        for command in self.commands.iter_mut().rev() {
+            command.undo();
+            self.failure = self.failure.take().or_else(|| command.failure());
+        }
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
+    command_impl!();
+}
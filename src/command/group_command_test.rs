@@ -0,0 +1,114 @@
+use crate::app_state::FocusedPane;
+use crate::command::test_helpers::TestRepo;
+use crate::command::{Command, CommandHistory, CreateFileCommand, GroupCommand, StageFileCommand};
+use crate::cursor_state::CursorState;
+
+fn cursor(file_cursor: usize) -> CursorState {
+    CursorState {
+        focused_pane: FocusedPane::Main,
+        file_cursor,
+        line_cursor: 0,
+        scroll: 0,
+        file_list_scroll: 0,
+        horizontal_scroll: 0,
+        unstaged_cursor: 0,
+        unstaged_scroll: 0,
+        unstaged_diff_scroll: 0,
+        unstaged_horizontal_scroll: 0,
+    }
+}
+
+fn create(repo: &TestRepo, name: &str) -> Box<dyn Command> {
+    Box::new(CreateFileCommand::new_file(
+        repo.path.clone(),
+        name.to_string(),
+        b"content\n".to_vec(),
+    ))
+}
+
+fn stage(repo: &TestRepo, name: &str) -> Box<dyn Command> {
+    Box::new(StageFileCommand::new(repo.path.clone(), name.to_string()))
+}
+
+#[test]
+fn test_group_runs_and_undoes_in_order() {
+    let repo = TestRepo::new();
+    let mut command = GroupCommand::new(vec![create(&repo, "a.txt"), stage(&repo, "a.txt")]);
+
+    assert!(command.execute());
+    assert_eq!(repo.get_status(), "A  a.txt\n");
+
+    command.undo();
+    assert_eq!(repo.get_status(), "");
+
+    assert!(command.execute());
+    assert_eq!(repo.get_status(), "A  a.txt\n");
+}
+
+#[test]
+fn test_failing_group_is_rolled_back() {
+    let repo = TestRepo::new();
+    repo.create_file("b.txt", "exists\n");
+    let mut command = GroupCommand::new(vec![create(&repo, "a.txt"), create(&repo, "b.txt")]);
+
+    assert!(!command.execute());
+    assert_eq!(repo.get_status(), "?? b.txt\n");
+}
+
+#[test]
+fn test_begin_and_end_group_make_one_undo_step() {
+    let repo = TestRepo::new();
+    let mut history = CommandHistory::new();
+
+    history.begin_group();
+    assert!(history.execute(create(&repo, "a.txt"), cursor(1)));
+    // Nested groups join the outer one
+    history.begin_group();
+    assert!(history.execute(stage(&repo, "a.txt"), cursor(2)));
+    history.end_group();
+    assert!(history.undo_stack.is_empty());
+    history.end_group();
+    assert_eq!(history.undo_stack.len(), 1);
+    assert_eq!(repo.get_status(), "A  a.txt\n");
+
+    // The cursor from before the first command comes back
+    assert_eq!(history.undo(cursor(3)), Some(cursor(1)));
+    assert_eq!(repo.get_status(), "");
+    assert_eq!(history.redo(cursor(4)), Some(cursor(3)));
+    assert_eq!(repo.get_status(), "A  a.txt\n");
+}
+
+#[test]
+fn test_undo_closes_an_open_group() {
+    let repo = TestRepo::new();
+    let mut history = CommandHistory::new();
+
+    history.begin_group();
+    assert!(history.execute(create(&repo, "a.txt"), cursor(1)));
+    assert!(history.execute(stage(&repo, "a.txt"), cursor(2)));
+    history.undo(cursor(3));
+    assert_eq!(repo.get_status(), "");
+
+    // Later commands are recorded on their own again
+    assert!(history.execute(create(&repo, "b.txt"), cursor(4)));
+    assert_eq!(history.undo_stack.len(), 1);
+    history.end_group();
+    assert_eq!(history.undo_stack.len(), 1);
+}
+
+#[test]
+fn test_the_failure_of_a_grouped_command_is_kept() {
+    let repo = TestRepo::new();
+    let mut history = CommandHistory::new();
+    let group = GroupCommand::new(vec![create(&repo, "a.txt"), stage(&repo, "missing.txt")]);
+
+    assert!(!history.execute(Box::new(group), cursor(1)));
+    let failure = history.take_failure().unwrap();
+    assert!(
+        failure.starts_with("Failed to stage missing.txt: fatal: "),
+        "{failure}"
+    );
+    assert_eq!(history.take_failure(), None);
+    assert!(history.undo_stack.is_empty());
+    assert_eq!(repo.get_status(), "");
+}
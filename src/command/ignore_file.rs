@@ -1,23 +1,36 @@
-use std::fs;
-use std::io::Write;
 use std::path::PathBuf;
 
-use super::Command;
+use super::{Command, check};
 use crate::cursor_state::CursorState;
 use crate::git;
+use crate::ignore_target::IgnoreTarget;
 
 pub struct IgnoreFileCommand {
     pub repo_path: PathBuf,
     pub file_name: String,
+    /// What is added to the ignore file: the path, or a pattern matching it.
+    pub pattern: String,
+    pub target: IgnoreTarget,
+    created: bool,
+    failure: Option<String>,
     cursor_before_execute: Option<CursorState>,
     cursor_before_undo: Option<CursorState>,
 }
 
 impl IgnoreFileCommand {
-    pub fn new(repo_path: PathBuf, file_name: String) -> Self {
+    pub fn new(
+        repo_path: PathBuf,
+        file_name: String,
+        pattern: String,
+        target: IgnoreTarget,
+    ) -> Self {
         Self {
             repo_path,
             file_name,
+            pattern,
+            target,
+            created: false,
+            failure: None,
             cursor_before_execute: None,
             cursor_before_undo: None,
         }
@@ -26,43 +39,58 @@ impl IgnoreFileCommand {
 
 impl Command for IgnoreFileCommand {
     fn execute(&mut self) -> bool {
-        let gitignore_path = self.repo_path.join(".gitignore");
-        let mut gitignore = fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(gitignore_path)
-            .expect("Failed to open .gitignore");
-        writeln!(gitignore, "{}", self.file_name).expect("Failed to write to .gitignore");
-
-        git::stage_path(&self.repo_path, ".gitignore").expect("Failed to stage .gitignore");
-
+        self.failure = None;
+        let added = self
+            .target
+            .add(&self.repo_path, &self.pattern)
+            .map(|created| self.created = created);
+        if !check(
+            &mut self.failure,
+            &format!("Failed to add {} to {}", self.pattern, self.target.label()),
+            added,
+        ) {
+            return false;
+        }
         // For staged files, we need to unstage them.
-        git::rm_cached(&self.repo_path, &self.file_name).expect("Failed to unstage file");
-        true
+        if check(
+            &mut self.failure,
+            &format!("Failed to untrack {}", self.file_name),
+            git::rm_cached(&self.repo_path, &self.file_name),
+        ) {
+            return true;
+        }
+        // The pattern would otherwise stay without an undo step.
+        let _ = self
+            .target
+            .take_back(&self.repo_path, &self.pattern, self.created);
+        false
     }
 
     fn undo(&mut self) {
-        let gitignore_path = self.repo_path.join(".gitignore");
-        if gitignore_path.exists() {
-            let content = fs::read_to_string(&gitignore_path).expect("Failed to read .gitignore");
-            let new_content: String = content
-                .lines()
-                .filter(|line| !line.trim().is_empty() && *line != self.file_name)
-                .collect::<Vec<_>>()
-                .join("\n");
-
-            if new_content.is_empty() {
-                fs::remove_file(&gitignore_path).expect("Failed to remove .gitignore");
-                git::rm_file_from_index(&self.repo_path, ".gitignore")
-                    .expect("Failed to remove .gitignore from index");
-            } else {
-                fs::write(&gitignore_path, new_content + "\n")
-                    .expect("Failed to write to .gitignore");
-                git::stage_path(&self.repo_path, ".gitignore").expect("Failed to stage .gitignore");
-            }
+        self.failure = None;
+        let taken_back = self
+            .target
+            .take_back(&self.repo_path, &self.pattern, self.created);
+        if !check(
+            &mut self.failure,
+            &format!(
+                "Failed to take {} out of {}",
+                self.pattern,
+                self.target.label()
+            ),
+            taken_back,
+        ) {
+            return;
         }
+        check(
+            &mut self.failure,
+            &format!("Failed to stage {}", self.file_name),
+            git::force_stage_file(&self.repo_path, &self.file_name),
+        );
+    }
 
-        git::stage_file(&self.repo_path, &self.file_name).expect("Failed to stage file");
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
     }
 
     command_impl!();
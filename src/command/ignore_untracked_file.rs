@@ -1,30 +1,35 @@
-use std::fs;
-use std::io::Write;
 use std::path::PathBuf;
 
-use super::Command;
+use super::{Command, check};
 use crate::cursor_state::CursorState;
-use crate::git;
+use crate::ignore_target::IgnoreTarget;
 
 pub struct IgnoreUntrackedFileCommand {
     pub repo_path: PathBuf,
     pub file_name: String,
-    was_empty_before: bool,
+    /// What is added to the ignore file: the path, or a pattern matching it.
+    pub pattern: String,
+    pub target: IgnoreTarget,
+    created: bool,
+    failure: Option<String>,
     cursor_before_execute: Option<CursorState>,
     cursor_before_undo: Option<CursorState>,
 }
 
 impl IgnoreUntrackedFileCommand {
-    pub fn new(repo_path: PathBuf, file_name: String) -> Self {
-        let gitignore_path = repo_path.join(".gitignore");
-        let was_empty_before = !gitignore_path.exists()
-            || fs::read_to_string(gitignore_path)
-                .map(|c| c.trim().is_empty())
-                .unwrap_or(true);
+    pub fn new(
+        repo_path: PathBuf,
+        file_name: String,
+        pattern: String,
+        target: IgnoreTarget,
+    ) -> Self {
         Self {
             repo_path,
             file_name,
-            was_empty_before,
+            pattern,
+            target,
+            created: false,
+            failure: None,
             cursor_before_execute: None,
             cursor_before_undo: None,
         }
@@ -33,41 +38,34 @@ impl IgnoreUntrackedFileCommand {
 
 impl Command for IgnoreUntrackedFileCommand {
     fn execute(&mut self) -> bool {
-        let gitignore_path = self.repo_path.join(".gitignore");
-        let mut gitignore = fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(gitignore_path)
-            .expect("Failed to open .gitignore");
-        writeln!(gitignore, "{}", self.file_name).expect("Failed to write to .gitignore");
-
-        git::stage_path(&self.repo_path, ".gitignore").expect("Failed to stage .gitignore");
-        true
+        self.failure = None;
+        let added = self
+            .target
+            .add(&self.repo_path, &self.pattern)
+            .map(|created| self.created = created);
+        check(
+            &mut self.failure,
+            &format!("Failed to add {} to {}", self.pattern, self.target.label()),
+            added,
+        )
     }
 
     fn undo(&mut self) {
-        let gitignore_path = self.repo_path.join(".gitignore");
-        if gitignore_path.exists() {
-            let content = fs::read_to_string(&gitignore_path).expect("Failed to read .gitignore");
-            let new_content: String = content
-                .lines()
-                .filter(|line| !line.trim().is_empty() && *line != self.file_name)
-                .collect::<Vec<_>>()
-                .join("\n");
+        self.failure = None;
+        check(
+            &mut self.failure,
+            &format!(
+                "Failed to take {} out of {}",
+                self.pattern,
+                self.target.label()
+            ),
+            self.target
+                .take_back(&self.repo_path, &self.pattern, self.created),
+        );
+    }
 
-            if new_content.is_empty() {
-                fs::remove_file(&gitignore_path).expect("Failed to remove .gitignore");
-                if !self.was_empty_before {
-                    // If the file was not empty before, we need to remove it from the index
-                    git::rm_cached(&self.repo_path, ".gitignore")
-                        .expect("Failed to remove .gitignore from index");
-                }
-            } else {
-                fs::write(&gitignore_path, new_content + "\n")
-                    .expect("Failed to write to .gitignore");
-                git::stage_path(&self.repo_path, ".gitignore").expect("Failed to stage .gitignore");
-            }
-        }
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
     }
 
     command_impl!();
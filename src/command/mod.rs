@@ -1,4 +1,5 @@
 use crate::cursor_state::CursorState;
+use crate::message_log::reason;
 
 pub trait Command {
     fn execute(&mut self) -> bool;
@@ -7,6 +8,23 @@ pub trait Command {
     fn set_cursor_before_undo(&mut self, cursor: CursorState);
     fn get_cursor_to_restore_on_undo(&self) -> Option<CursorState>;
     fn get_cursor_to_restore_on_redo(&self) -> Option<CursorState>;
+
+    /// Why the last `execute` or `undo` failed, for the status line.
+    fn failure(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Keeps why `result` failed in `failure`, as `what` followed by the line
+/// git gave as the reason. Returns whether it succeeded.
+fn check(failure: &mut Option<String>, what: &str, result: anyhow::Result<()>) -> bool {
+    match result {
+        Ok(()) => true,
+        Err(e) => {
+            *failure = Some(format!("{what}: {}", reason(&e)));
+            false
+        }
+    }
 }
 
 macro_rules! command_impl {
@@ -29,53 +47,95 @@ macro_rules! command_impl {
     };
 }
 
+mod apply_external_patch;
 mod apply_patch;
+mod apply_recipe;
 mod checkout_file;
+mod create_file;
 mod delete_untracked_file;
 mod discard_commit;
 mod discard_file;
 mod discard_hunk;
 mod discard_unstaged_hunk;
+mod drop_commit;
 mod fixup_commit;
+mod fixup_hunk;
+mod format_staged;
+mod group;
 mod ignore_file;
 mod ignore_unstaged_tracked_file;
 mod ignore_untracked_file;
 mod remove_file;
+mod rename_file;
 mod reorder_commits;
+mod restore_deleted_file;
+mod restore_discarded_patch;
+mod restore_dropped_commit;
+mod restore_file_from_commit;
 mod stage_all;
 mod stage_file;
 mod stage_patch;
+mod stage_paths;
+mod stage_pathspec;
 mod stage_unstaged;
 mod stage_untracked;
+mod stash_apply;
+mod stash_drop;
 mod swap_commit;
+mod toggle_executable;
 mod unstage_all;
 mod unstage_file;
+mod unstage_paths;
 
+pub use apply_external_patch::ApplyExternalPatchCommand;
 pub use apply_patch::ApplyPatchCommand;
+pub use apply_recipe::ApplyRecipeCommand;
 pub use checkout_file::CheckoutFileCommand;
+pub use create_file::CreateFileCommand;
 pub use delete_untracked_file::DeleteUntrackedFileCommand;
 pub use discard_commit::DiscardCommitCommand;
 pub use discard_file::DiscardFileCommand;
 pub use discard_hunk::DiscardHunkCommand;
 pub use discard_unstaged_hunk::DiscardUnstagedHunkCommand;
+pub use drop_commit::DropCommitCommand;
 pub use fixup_commit::FixupCommitCommand;
+pub use fixup_hunk::FixupHunkCommand;
+pub use format_staged::FormatStagedCommand;
+pub use group::GroupCommand;
 pub use ignore_file::IgnoreFileCommand;
 pub use ignore_unstaged_tracked_file::IgnoreUnstagedTrackedFileCommand;
 pub use ignore_untracked_file::IgnoreUntrackedFileCommand;
 pub use remove_file::RemoveFileCommand;
+pub use rename_file::RenameFileCommand;
 pub use reorder_commits::ReorderCommitsCommand;
+pub use restore_deleted_file::RestoreDeletedFileCommand;
+pub use restore_discarded_patch::RestoreDiscardedPatchCommand;
+pub use restore_dropped_commit::RestoreDroppedCommitCommand;
+pub use restore_file_from_commit::RestoreFileFromCommitCommand;
 pub use stage_all::StageAllCommand;
 pub use stage_file::StageFileCommand;
 pub use stage_patch::StagePatchCommand;
+pub use stage_paths::StagePathsCommand;
+pub use stage_pathspec::StagePathspecCommand;
 pub use stage_unstaged::StageUnstagedCommand;
 pub use stage_untracked::StageUntrackedCommand;
+pub use stash_apply::StashApplyCommand;
+pub use stash_drop::StashDropCommand;
 pub use swap_commit::SwapCommitCommand;
+pub use toggle_executable::ToggleExecutableCommand;
 pub use unstage_all::UnstageAllCommand;
 pub use unstage_file::UnstageFileCommand;
+pub use unstage_paths::UnstagePathsCommand;
 
 pub struct CommandHistory {
     pub undo_stack: Vec<Box<dyn Command>>,
     pub redo_stack: Vec<Box<dyn Command>>,
+    /// Commands run since [`CommandHistory::begin_group`], and how many
+    /// groups are open. Nested groups join the outermost one.
+    group: Vec<Box<dyn Command>>,
+    group_depth: usize,
+    /// Why the command run, undone or redone last failed.
+    failure: Option<String>,
 }
 
 impl Default for CommandHistory {
@@ -89,26 +149,74 @@ impl CommandHistory {
         CommandHistory {
             undo_stack: Vec::new(),
             redo_stack: Vec::new(),
+            group: Vec::new(),
+            group_depth: 0,
+            failure: None,
         }
     }
 
     pub fn clear(&mut self) {
         self.undo_stack.clear();
         self.redo_stack.clear();
+        self.group.clear();
+        self.group_depth = 0;
+    }
+
+    /// Starts collecting the commands run from now on into one undo step,
+    /// until the matching [`CommandHistory::end_group`].
+    pub fn begin_group(&mut self) {
+        self.group_depth += 1;
+    }
+
+    /// Closes the group opened last. Once the outermost one is closed, its
+    /// commands become a single entry of the undo history.
+    pub fn end_group(&mut self) {
+        if self.group_depth == 0 {
+            return;
+        }
+        self.group_depth -= 1;
+        if self.group_depth == 0 && !self.group.is_empty() {
+            let commands = std::mem::take(&mut self.group);
+            self.undo_stack
+                .push(Box::new(GroupCommand::from_executed(commands)));
+        }
+    }
+
+    /// Closes all open groups, so undo and redo never split one.
+    fn close_groups(&mut self) {
+        self.group_depth = self.group_depth.min(1);
+        self.end_group();
     }
 
-    pub fn execute(&mut self, mut command: Box<dyn Command>, cursor_state: CursorState) {
+    /// Takes why the command run, undone or redone last failed.
+    pub fn take_failure(&mut self) -> Option<String> {
+        self.failure.take()
+    }
+
+    /// Runs `command` and returns whether it succeeded.
+    pub fn execute(&mut self, mut command: Box<dyn Command>, cursor_state: CursorState) -> bool {
         command.set_cursor_before_execute(cursor_state);
+        self.failure = None;
         if command.execute() {
-            self.undo_stack.push(command);
+            if self.group_depth > 0 {
+                self.group.push(command);
+            } else {
+                self.undo_stack.push(command);
+            }
             self.redo_stack.clear();
+            true
+        } else {
+            self.failure = command.failure();
+            false
         }
     }
 
     pub fn undo(&mut self, cursor_state: CursorState) -> Option<CursorState> {
+        self.close_groups();
         if let Some(mut command) = self.undo_stack.pop() {
             command.set_cursor_before_undo(cursor_state);
             command.undo();
+            self.failure = command.failure();
             let cursor_to_restore = command.get_cursor_to_restore_on_undo();
             self.redo_stack.push(command);
             cursor_to_restore
@@ -118,12 +226,19 @@ impl CommandHistory {
     }
 
     pub fn redo(&mut self, cursor_state: CursorState) -> Option<CursorState> {
+        self.close_groups();
         if let Some(mut command) = self.redo_stack.pop() {
             let cursor_to_restore = command.get_cursor_to_restore_on_redo();
 
             command.set_cursor_before_execute(cursor_state);
 
-            command.execute();
+            self.failure = None;
+            if !command.execute() {
+                self.failure = command.failure();
+                // Nothing changed, so it can still be redone later.
+                self.redo_stack.push(command);
+                return None;
+            }
 
             self.undo_stack.push(command);
             cursor_to_restore
@@ -133,27 +248,61 @@ impl CommandHistory {
     }
 }
 
+#[cfg(test)]
+mod apply_external_patch_command_test;
 #[cfg(test)]
 mod apply_patch_command_test;
 #[cfg(test)]
+mod apply_recipe_command_test;
+#[cfg(test)]
 mod checkout_file_command_test;
 #[cfg(test)]
+mod create_file_command_test;
+#[cfg(test)]
+mod drop_commit_command_test;
+#[cfg(test)]
 mod fixup_commit_test;
 #[cfg(test)]
+mod fixup_hunk_command_test;
+#[cfg(test)]
+mod format_staged_command_test;
+#[cfg(test)]
+mod group_command_test;
+#[cfg(test)]
+mod rename_file_command_test;
+#[cfg(test)]
 mod reorder_commits_command_test;
 #[cfg(test)]
+mod restore_deleted_file_command_test;
+#[cfg(test)]
+mod restore_discarded_patch_command_test;
+#[cfg(test)]
+mod restore_file_from_commit_command_test;
+#[cfg(test)]
 mod stage_all_command_test;
 #[cfg(test)]
 mod stage_file_command_test;
 #[cfg(test)]
 mod stage_patch_command_test;
 #[cfg(test)]
+mod stage_paths_command_test;
+#[cfg(test)]
+mod stage_pathspec_command_test;
+#[cfg(test)]
 mod stage_unstaged_command_test;
 #[cfg(test)]
 mod stage_untracked_command_test;
 #[cfg(test)]
+mod stash_apply_command_test;
+#[cfg(test)]
+mod stash_drop_command_test;
+#[cfg(test)]
 mod test_helpers;
 #[cfg(test)]
+mod toggle_executable_command_test;
+#[cfg(test)]
 mod unstage_all_command_test;
 #[cfg(test)]
 mod unstage_file_command_test;
+#[cfg(test)]
+mod unstage_paths_command_test;
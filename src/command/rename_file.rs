@@ -0,0 +1,85 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{Command, check};
+use crate::cursor_state::CursorState;
+use crate::git;
+
+pub struct RenameFileCommand {
+    pub repo_path: PathBuf,
+    pub from: String,
+    pub to: String,
+    created_dir: Option<PathBuf>,
+    failure: Option<String>,
+    cursor_before_execute: Option<CursorState>,
+    cursor_before_undo: Option<CursorState>,
+}
+
+impl RenameFileCommand {
+    pub fn new(repo_path: PathBuf, from: String, to: String) -> Self {
+        Self {
+            repo_path,
+            from,
+            to,
+            created_dir: None,
+            failure: None,
+            cursor_before_execute: None,
+            cursor_before_undo: None,
+        }
+    }
+
+    /// Returns the outermost parent directory of `to` that does not exist yet.
+    fn first_missing_dir(&self) -> Option<PathBuf> {
+        Path::new(&self.to)
+            .ancestors()
+            .skip(1)
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .take_while(|dir| !self.repo_path.join(dir).exists())
+            .last()
+            .map(Path::to_path_buf)
+    }
+
+    /// Removes the directories created for the new path, innermost first.
+    fn remove_created_dirs(&self) {
+        if let Some(created_dir) = &self.created_dir {
+            for dir in Path::new(&self.to).ancestors().skip(1) {
+                if fs::remove_dir(self.repo_path.join(dir)).is_err() || dir == created_dir {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl Command for RenameFileCommand {
+    fn execute(&mut self) -> bool {
+        self.created_dir = self.first_missing_dir();
+        self.failure = None;
+        let moved = check(
+            &mut self.failure,
+            &format!("Failed to rename {} to {}", self.from, self.to),
+            git::move_file(&self.repo_path, &self.from, &self.to),
+        );
+        if !moved {
+            self.remove_created_dirs();
+        }
+        moved
+    }
+
+    fn undo(&mut self) {
+        self.failure = None;
+        if check(
+            &mut self.failure,
+            &format!("Failed to rename {} back to {}", self.to, self.from),
+            git::move_file(&self.repo_path, &self.to, &self.from),
+        ) {
+            self.remove_created_dirs();
+        }
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
+    command_impl!();
+}
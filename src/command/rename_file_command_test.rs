@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use crate::command::{Command, RenameFileCommand, test_helpers::TestRepo};
+
+    #[test]
+    fn test_rename_staged_file() {
+        let repo = TestRepo::new();
+        repo.create_file("old.txt", "content");
+        repo.add_file("old.txt");
+
+        let mut command =
+            RenameFileCommand::new(repo.path.clone(), "old.txt".into(), "new.txt".into());
+
+        // Execute
+        assert!(command.execute());
+        assert_eq!(repo.get_status(), "A  new.txt\n");
+
+        // Undo
+        command.undo();
+        assert_eq!(repo.get_status(), "A  old.txt\n");
+
+        // Redo
+        assert!(command.execute());
+        assert_eq!(repo.get_status(), "A  new.txt\n");
+    }
+
+    #[test]
+    fn test_rename_into_new_directory_and_undo_removes_it() {
+        let repo = TestRepo::new();
+        repo.create_file("a.txt", "content");
+        repo.add_file("a.txt");
+        repo.commit("initial commit");
+
+        let mut command =
+            RenameFileCommand::new(repo.path.clone(), "a.txt".into(), "src/lib/a.txt".into());
+
+        assert!(command.execute());
+        assert_eq!(repo.get_status(), "R  a.txt -> src/lib/a.txt\n");
+
+        command.undo();
+        assert_eq!(repo.get_status(), "");
+        assert!(!repo.path.join("src").exists());
+    }
+
+    #[test]
+    fn test_rename_keeps_unstaged_changes() {
+        let repo = TestRepo::new();
+        repo.create_file("a.txt", "content\n");
+        repo.add_file("a.txt");
+        repo.commit("initial commit");
+        repo.append_file("a.txt", "more\n");
+
+        let mut command = RenameFileCommand::new(repo.path.clone(), "a.txt".into(), "b.txt".into());
+
+        assert!(command.execute());
+        assert_eq!(repo.get_status(), "RM a.txt -> b.txt\n");
+
+        command.undo();
+        assert_eq!(repo.get_status(), " M a.txt\n");
+    }
+
+    #[test]
+    fn test_rename_to_existing_path_fails() {
+        let repo = TestRepo::new();
+        repo.create_file("a.txt", "a");
+        repo.create_file("b.txt", "b");
+        repo.add_file("a.txt");
+        repo.add_file("b.txt");
+
+        let mut command = RenameFileCommand::new(repo.path.clone(), "a.txt".into(), "b.txt".into());
+
+        assert!(!command.execute());
+        assert_eq!(repo.get_status(), "A  a.txt\nA  b.txt\n");
+    }
+
+    #[test]
+    fn test_rename_failure_is_kept() {
+        let repo = TestRepo::new();
+        repo.create_file("a.txt", "content");
+
+        // git mv refuses an untracked source
+        let mut command =
+            RenameFileCommand::new(repo.path.clone(), "a.txt".into(), "src/b.txt".into());
+
+        assert!(!command.execute());
+        let failure = command.failure().unwrap();
+        assert!(
+            failure.starts_with("Failed to rename a.txt to src/b.txt: "),
+            "{failure}"
+        );
+        assert!(!repo.path.join("src").exists());
+    }
+}
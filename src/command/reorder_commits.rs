@@ -2,7 +2,7 @@ use std::path::PathBuf;
 
 use super::Command;
 use crate::cursor_state::CursorState;
-use crate::git::{self, CommitInfo};
+use crate::git::{self, CommitInfo, RebaseAction, RebaseStep};
 
 pub struct ReorderCommitsCommand {
     pub repo_path: PathBuf,
@@ -26,6 +26,82 @@ impl ReorderCommitsCommand {
             cursor_before_undo: None,
         }
     }
+
+    /// The last commit both lists share, oldest first, and the commits to
+    /// pick onto it in order.
+    fn split_at_base(&self) -> (Option<CommitInfo>, Vec<CommitInfo>) {
+        let mut original_chrono = self.original_commits.clone();
+        original_chrono.reverse();
+        let mut reordered_chrono = self.reordered_commits.clone();
+        reordered_chrono.reverse();
+
+        let mut base_commit = None;
+        let mut first_diverged_idx = 0;
+        for (i, (original, reordered)) in original_chrono
+            .iter()
+            .zip(reordered_chrono.iter())
+            .enumerate()
+        {
+            if original == reordered {
+                base_commit = Some(original.clone());
+                first_diverged_idx = i + 1;
+            } else {
+                break;
+            }
+        }
+
+        (base_commit, reordered_chrono.split_off(first_diverged_idx))
+    }
+
+    /// What `execute` will do, as rebase steps: the commits picked onto the
+    /// base, oldest first, followed by the dropped ones.
+    pub fn plan(&self) -> Vec<RebaseStep> {
+        let (_, commits_to_pick) = self.split_at_base();
+        let mut steps: Vec<RebaseStep> = commits_to_pick
+            .iter()
+            .map(|commit| {
+                let original_message = self
+                    .original_commits
+                    .iter()
+                    .find(|c| c.hash == commit.hash)
+                    .map(|c| c.message.clone())
+                    .unwrap_or_else(|| commit.message.clone());
+                let reworded = original_message != commit.message;
+                let action = if commit.is_fixup {
+                    RebaseAction::Fixup
+                } else if reworded {
+                    RebaseAction::Reword
+                } else {
+                    RebaseAction::Pick
+                };
+                RebaseStep {
+                    hash: commit.hash.clone(),
+                    message: original_message,
+                    action,
+                    new_message: reworded.then(|| commit.message.clone()),
+                }
+            })
+            .collect();
+
+        let mut dropped: Vec<&CommitInfo> = self
+            .original_commits
+            .iter()
+            .filter(|original| {
+                !self
+                    .reordered_commits
+                    .iter()
+                    .any(|c| c.hash == original.hash)
+            })
+            .collect();
+        dropped.reverse();
+        steps.extend(dropped.into_iter().map(|commit| RebaseStep {
+            hash: commit.hash.clone(),
+            message: commit.message.clone(),
+            action: RebaseAction::Drop,
+            new_message: None,
+        }));
+        steps
+    }
 }
 
 impl Command for ReorderCommitsCommand {
@@ -48,27 +124,7 @@ impl Command for ReorderCommitsCommand {
         };
 
         // --- Find the base and the commits to re-order ---
-        let mut original_chrono = self.original_commits.clone();
-        original_chrono.reverse();
-        let mut reordered_chrono = self.reordered_commits.clone();
-        reordered_chrono.reverse();
-
-        let mut base_commit_opt: Option<&CommitInfo> = None;
-        let mut first_diverged_idx = 0;
-        for (i, (original, reordered)) in original_chrono
-            .iter()
-            .zip(reordered_chrono.iter())
-            .enumerate()
-        {
-            if original == reordered {
-                base_commit_opt = Some(original);
-                first_diverged_idx = i + 1;
-            } else {
-                break;
-            }
-        }
-
-        let commits_to_pick = &reordered_chrono[first_diverged_idx..];
+        let (base_commit_opt, commits_to_pick) = self.split_at_base();
         let temp_branch = format!("reorder-temp-{}", chrono::Utc::now().timestamp());
 
         // --- Create temp branch ---
@@ -108,7 +164,7 @@ impl Command for ReorderCommitsCommand {
         };
 
         // Iterate through chronological list of commits to apply
-        for commit in commits_to_pick {
+        for commit in &commits_to_pick {
             if commit.is_fixup {
                 // If it's a fixup, we apply its changes to the staging area, then amend them into the previous commit.
                 if let Err(e) = git::cherry_pick_no_commit(&self.repo_path, &commit.hash) {
@@ -130,14 +186,12 @@ impl Command for ReorderCommitsCommand {
             // Check if the message needs to be amended
             let original_commit = self.original_commits.iter().find(|c| c.hash == commit.hash);
 
-            if let Some(original) = original_commit {
-                if original.message != commit.message {
-                    if let Err(e) = git::commit_amend_with_message(&self.repo_path, &commit.message)
-                    {
-                        rebase_failed(e);
-                        return false;
-                    }
-                }
+            if let Some(original) = original_commit
+                && original.message != commit.message
+                && let Err(e) = git::commit_amend_with_message(&self.repo_path, &commit.message)
+            {
+                rebase_failed(e);
+                return false;
             }
         }
 
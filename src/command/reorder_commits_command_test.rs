@@ -174,3 +174,41 @@ fn test_reorder_commits_with_message_change() {
         vec!["new message", "commit 2", "commit 0"]
     );
 }
+
+#[test]
+fn test_reorder_commits_plan() {
+    let repo = TestRepo::new();
+    commit(&repo.path, "first");
+    commit(&repo.path, "second");
+    commit(&repo.path, "third");
+    commit(&repo.path, "fourth");
+    let log = get_log(&repo.path);
+
+    // fourth is reworded, third dropped, second fixed up into first.
+    let mut reordered = log.clone();
+    reordered.remove(1);
+    reordered[0].message = "fourth, reworded".to_string();
+    reordered[1].is_fixup = true;
+    let command = ReorderCommitsCommand::new(repo.path.clone(), log.clone(), reordered);
+
+    let todo: Vec<String> = command
+        .plan()
+        .iter()
+        .map(|step| {
+            format!(
+                "{} {} {}",
+                step.action.label(),
+                step.hash,
+                step.new_message.as_deref().unwrap_or(&step.message)
+            )
+        })
+        .collect();
+    assert_eq!(
+        todo,
+        vec![
+            format!("fixup {} second", log[2].hash),
+            format!("reword {} fourth, reworded", log[0].hash),
+            format!("drop {} third", log[1].hash),
+        ]
+    );
+}
@@ -0,0 +1,56 @@
+use std::path::PathBuf;
+
+use super::{Command, check};
+use crate::cursor_state::CursorState;
+use crate::git;
+
+/// Brings back a file whose deletion is staged, as it is in `HEAD`.
+pub struct RestoreDeletedFileCommand {
+    pub repo_path: PathBuf,
+    pub file_name: String,
+    failure: Option<String>,
+    cursor_before_execute: Option<CursorState>,
+    cursor_before_undo: Option<CursorState>,
+}
+
+impl RestoreDeletedFileCommand {
+    pub fn new(repo_path: PathBuf, file_name: String) -> Self {
+        Self {
+            repo_path,
+            file_name,
+            failure: None,
+            cursor_before_execute: None,
+            cursor_before_undo: None,
+        }
+    }
+}
+
+impl Command for RestoreDeletedFileCommand {
+    fn execute(&mut self) -> bool {
+        self.failure = None;
+        if self.repo_path.join(&self.file_name).exists() {
+            // Something new took its place, which restoring would overwrite
+            return false;
+        }
+        check(
+            &mut self.failure,
+            &format!("Failed to restore {}", self.file_name),
+            git::restore_from_head(&self.repo_path, &self.file_name),
+        )
+    }
+
+    fn undo(&mut self) {
+        self.failure = None;
+        check(
+            &mut self.failure,
+            &format!("Failed to delete {} again", self.file_name),
+            git::rm_file(&self.repo_path, &self.file_name),
+        );
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
+    command_impl!();
+}
@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod tests {
+    use crate::command::{Command, RestoreDeletedFileCommand, test_helpers::TestRepo};
+
+    #[test]
+    fn test_restore_deleted_file() {
+        let repo = TestRepo::new();
+        repo.create_file("logo.png", "\0binary\0");
+        repo.add_all();
+        repo.commit("initial");
+        std::fs::remove_file(repo.path.join("logo.png")).unwrap();
+        repo.add_all();
+
+        let mut command = RestoreDeletedFileCommand::new(repo.path.clone(), "logo.png".to_string());
+        assert_eq!(repo.get_status(), "D  logo.png\n");
+        assert!(command.execute());
+        assert_eq!(repo.get_status(), "");
+        assert_eq!(
+            std::fs::read(repo.path.join("logo.png")).unwrap(),
+            b"\0binary\0"
+        );
+
+        command.undo();
+        assert_eq!(repo.get_status(), "D  logo.png\n");
+        assert!(!repo.path.join("logo.png").exists());
+    }
+
+    #[test]
+    fn test_restore_keeps_a_new_file_in_its_place() {
+        let repo = TestRepo::new();
+        repo.create_file("a.txt", "old\n");
+        repo.add_all();
+        repo.commit("initial");
+        std::fs::remove_file(repo.path.join("a.txt")).unwrap();
+        repo.add_all();
+        repo.create_file("a.txt", "new\n");
+
+        let mut command = RestoreDeletedFileCommand::new(repo.path.clone(), "a.txt".to_string());
+        assert!(!command.execute());
+        assert_eq!(
+            std::fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+            "new\n"
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use super::{Command, check};
+use crate::cursor_state::CursorState;
+use crate::git;
+
+/// Applies a discarded change back to the working tree, from the discard bin.
+pub struct RestoreDiscardedPatchCommand {
+    pub repo_path: PathBuf,
+    pub patch: String,
+    failure: Option<String>,
+    cursor_before_execute: Option<CursorState>,
+    cursor_before_undo: Option<CursorState>,
+}
+
+impl RestoreDiscardedPatchCommand {
+    pub fn new(repo_path: PathBuf, patch: String) -> Self {
+        Self {
+            repo_path,
+            patch,
+            failure: None,
+            cursor_before_execute: None,
+            cursor_before_undo: None,
+        }
+    }
+}
+
+impl Command for RestoreDiscardedPatchCommand {
+    fn execute(&mut self) -> bool {
+        self.failure = None;
+        // A change that is there already could still apply a second time, as
+        // patches are applied with `--unidiff-zero`.
+        if git::can_apply_patch(&self.repo_path, &self.patch, true, false) {
+            return false;
+        }
+        // Fails without touching anything once the file moved on.
+        git::apply_patch(&self.repo_path, &self.patch, false, false).is_ok()
+    }
+
+    fn undo(&mut self) {
+        self.failure = None;
+        check(
+            &mut self.failure,
+            "Failed to discard the restored change again",
+            git::apply_patch(&self.repo_path, &self.patch, true, false),
+        );
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
+    command_impl!();
+}
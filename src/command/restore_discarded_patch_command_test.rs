@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests {
+    use crate::command::test_helpers::TestRepo;
+    use crate::command::{Command, DiscardUnstagedHunkCommand, RestoreDiscardedPatchCommand};
+    use crate::git;
+
+    #[test]
+    fn test_restore_discarded_patch() {
+        let repo = TestRepo::new();
+        let file_name = "test.txt";
+        repo.create_file(file_name, "line1\n");
+        repo.add_all();
+        repo.commit("initial");
+        repo.append_file(file_name, "line2\n");
+
+        let patch = git::get_unstaged_file_diff_patch(&repo.path, file_name).unwrap();
+        let mut discard = DiscardUnstagedHunkCommand::new(repo.path.clone(), patch.clone());
+        assert!(discard.execute());
+        assert_eq!(repo.get_status(), "");
+
+        let mut restore = RestoreDiscardedPatchCommand::new(repo.path.clone(), patch.clone());
+        assert!(restore.execute());
+        assert_eq!(repo.get_status(), " M test.txt\n");
+
+        // Restoring twice doesn't apply the change again
+        let mut again = RestoreDiscardedPatchCommand::new(repo.path.clone(), patch);
+        assert!(!again.execute());
+        assert_eq!(
+            std::fs::read_to_string(repo.path.join(file_name)).unwrap(),
+            "line1\nline2\n"
+        );
+
+        restore.undo();
+        assert_eq!(repo.get_status(), "");
+    }
+
+    #[test]
+    fn test_undo_of_a_changed_restore_reports_failure() {
+        let repo = TestRepo::new();
+        repo.create_file("test.txt", "line1\n");
+        repo.add_all();
+        repo.commit("initial");
+        repo.append_file("test.txt", "line2\n");
+        let patch = git::get_unstaged_file_diff_patch(&repo.path, "test.txt").unwrap();
+        let mut discard = DiscardUnstagedHunkCommand::new(repo.path.clone(), patch.clone());
+        assert!(discard.execute());
+
+        let mut restore = RestoreDiscardedPatchCommand::new(repo.path.clone(), patch);
+        assert!(restore.execute());
+        repo.create_file("test.txt", "rewritten\n");
+
+        restore.undo();
+        assert!(
+            restore
+                .failure()
+                .unwrap()
+                .starts_with("Failed to discard the restored change again")
+        );
+        assert_eq!(
+            std::fs::read_to_string(repo.path.join("test.txt")).unwrap(),
+            "rewritten\n"
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use std::path::PathBuf;
+
+use super::{Command, check};
+use crate::cursor_state::CursorState;
+use crate::git;
+
+/// Picks a dropped commit back onto the branch, from the discard bin.
+pub struct RestoreDroppedCommitCommand {
+    pub repo_path: PathBuf,
+    pub hash: String,
+    head_after: String,
+    failure: Option<String>,
+    cursor_before_execute: Option<CursorState>,
+    cursor_before_undo: Option<CursorState>,
+}
+
+impl RestoreDroppedCommitCommand {
+    pub fn new(repo_path: PathBuf, hash: String) -> Self {
+        Self {
+            repo_path,
+            hash,
+            head_after: String::new(),
+            failure: None,
+            cursor_before_execute: None,
+            cursor_before_undo: None,
+        }
+    }
+}
+
+impl Command for RestoreDroppedCommitCommand {
+    fn execute(&mut self) -> bool {
+        self.failure = None;
+        if !check(
+            &mut self.failure,
+            "Failed to pick the commit back",
+            git::cherry_pick(&self.repo_path, &self.hash),
+        ) {
+            let _ = git::cherry_pick_abort(&self.repo_path);
+            return false;
+        }
+        self.head_after = git::head_hash(&self.repo_path).unwrap_or_default();
+        true
+    }
+
+    fn undo(&mut self) {
+        self.failure = None;
+        // Only the picked commit is dropped, never one made on top of it.
+        if !git::head_hash(&self.repo_path).is_ok_and(|head| head == self.head_after) {
+            self.failure =
+                Some("Failed to drop the commit again: the branch moved on since".to_string());
+            return;
+        }
+        check(
+            &mut self.failure,
+            "Failed to drop the commit again",
+            git::reset_keep(&self.repo_path, "HEAD~1"),
+        );
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
+    command_impl!();
+}
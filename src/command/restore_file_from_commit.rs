@@ -0,0 +1,113 @@
+use std::fs;
+use std::path::PathBuf;
+
+use super::{Command, check};
+use crate::cursor_state::CursorState;
+use crate::git;
+
+/// What restoring overwrote, to put it back on undo.
+struct Overwritten {
+    /// The working tree file with its permissions, or `None` if it did not
+    /// exist.
+    file: Option<(Vec<u8>, fs::Permissions)>,
+    /// The index entry, when the index was restored too.
+    index: Option<Option<(String, String)>>,
+}
+
+/// Replaces a file with its content in an earlier commit, with
+/// `git restore --source`.
+pub struct RestoreFileFromCommitCommand {
+    pub repo_path: PathBuf,
+    pub file_name: String,
+    pub source: String,
+    /// Whether the index is restored as well as the working tree.
+    pub staged: bool,
+    overwritten: Option<Overwritten>,
+    failure: Option<String>,
+    cursor_before_execute: Option<CursorState>,
+    cursor_before_undo: Option<CursorState>,
+}
+
+impl RestoreFileFromCommitCommand {
+    pub fn new(repo_path: PathBuf, file_name: String, source: String, staged: bool) -> Self {
+        Self {
+            repo_path,
+            file_name,
+            source,
+            staged,
+            overwritten: None,
+            failure: None,
+            cursor_before_execute: None,
+            cursor_before_undo: None,
+        }
+    }
+
+    fn read_overwritten(&self) -> std::io::Result<Overwritten> {
+        let path = self.repo_path.join(&self.file_name);
+        let file = match fs::metadata(&path) {
+            Ok(metadata) => Some((fs::read(&path)?, metadata.permissions())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e),
+        };
+        let index = if self.staged {
+            Some(
+                git::index_entry(&self.repo_path, &self.file_name)
+                    .map_err(std::io::Error::other)?,
+            )
+        } else {
+            None
+        };
+        Ok(Overwritten { file, index })
+    }
+}
+
+impl Command for RestoreFileFromCommitCommand {
+    fn execute(&mut self) -> bool {
+        self.failure = None;
+        let Ok(overwritten) = self.read_overwritten() else {
+            return false;
+        };
+        if git::restore_from_commit(&self.repo_path, &self.source, &self.file_name, self.staged)
+            .is_err()
+        {
+            return false;
+        }
+        self.overwritten = Some(overwritten);
+        true
+    }
+
+    fn undo(&mut self) {
+        self.failure = None;
+        let Some(overwritten) = self.overwritten.take() else {
+            return;
+        };
+        if let Some(entry) = &overwritten.index {
+            check(
+                &mut self.failure,
+                &format!("Failed to restore the staged {}", self.file_name),
+                git::set_index_entry(&self.repo_path, &self.file_name, entry.as_ref()),
+            );
+        }
+        let path = self.repo_path.join(&self.file_name);
+        match overwritten.file {
+            Some((content, permissions)) => {
+                let restored = fs::write(&path, content)
+                    .and_then(|()| fs::set_permissions(&path, permissions));
+                check(
+                    &mut self.failure,
+                    &format!("Failed to restore {}", self.file_name),
+                    restored.map_err(Into::into),
+                );
+            }
+            None => {
+                let _ = fs::remove_file(&path);
+            }
+        }
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
+    command_impl!();
+}
@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use crate::command::{Command, RestoreFileFromCommitCommand, test_helpers::TestRepo};
+    use crate::git;
+
+    fn setup() -> (TestRepo, String) {
+        let repo = TestRepo::new();
+        repo.create_file("a.txt", "first\n");
+        repo.add_all();
+        repo.commit("first");
+        let first = git::run_git_command(&repo.path, &["rev-parse", "--short", "HEAD"])
+            .unwrap()
+            .trim()
+            .to_string();
+        repo.create_file("a.txt", "second\n");
+        repo.add_all();
+        repo.commit("second");
+        (repo, first)
+    }
+
+    fn content(repo: &TestRepo) -> String {
+        std::fs::read_to_string(repo.path.join("a.txt")).unwrap()
+    }
+
+    #[test]
+    fn test_restore_to_working_tree_only() {
+        let (repo, first) = setup();
+        repo.create_file("a.txt", "edited\n");
+
+        let mut command =
+            RestoreFileFromCommitCommand::new(repo.path.clone(), "a.txt".to_string(), first, false);
+        assert!(command.execute());
+        assert_eq!(content(&repo), "first\n");
+        assert_eq!(repo.get_status(), " M a.txt\n");
+
+        command.undo();
+        assert_eq!(content(&repo), "edited\n");
+    }
+
+    #[test]
+    fn test_restore_with_index_puts_the_staged_content_back_on_undo() {
+        let (repo, first) = setup();
+        repo.create_file("a.txt", "staged\n");
+        repo.add_all();
+        repo.create_file("a.txt", "edited\n");
+
+        let mut command =
+            RestoreFileFromCommitCommand::new(repo.path.clone(), "a.txt".to_string(), first, true);
+        assert!(command.execute());
+        assert_eq!(content(&repo), "first\n");
+        assert_eq!(repo.get_status(), "M  a.txt\n");
+
+        command.undo();
+        assert_eq!(content(&repo), "edited\n");
+        assert_eq!(repo.get_status(), "MM a.txt\n");
+        let staged = git::run_git_command(&repo.path, &["show", ":a.txt"]).unwrap();
+        assert_eq!(staged, "staged\n");
+    }
+
+    #[test]
+    fn test_undo_removes_a_file_that_did_not_exist() {
+        let (repo, first) = setup();
+        std::fs::remove_file(repo.path.join("a.txt")).unwrap();
+        repo.add_all();
+
+        let mut command =
+            RestoreFileFromCommitCommand::new(repo.path.clone(), "a.txt".to_string(), first, true);
+        assert!(command.execute());
+        assert_eq!(content(&repo), "first\n");
+
+        command.undo();
+        assert!(!repo.path.join("a.txt").exists());
+        assert_eq!(repo.get_status(), "D  a.txt\n");
+    }
+
+    #[test]
+    fn test_undo_reports_a_file_it_cannot_write() {
+        let (repo, first) = setup();
+        let mut command =
+            RestoreFileFromCommitCommand::new(repo.path.clone(), "a.txt".to_string(), first, false);
+        assert!(command.execute());
+        std::fs::remove_file(repo.path.join("a.txt")).unwrap();
+        std::fs::create_dir(repo.path.join("a.txt")).unwrap();
+
+        command.undo();
+        assert!(
+            command
+                .failure()
+                .unwrap()
+                .starts_with("Failed to restore a.txt: ")
+        );
+    }
+}
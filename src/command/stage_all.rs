@@ -1,25 +1,39 @@
 use std::path::PathBuf;
 
-use super::Command;
+use super::{Command, check};
 use crate::cursor_state::CursorState;
 use crate::git;
 
 pub struct StageAllCommand {
     pub repo_path: PathBuf,
+    /// Untracked files larger than this are left out.
+    pub size_limit: u64,
     patch: String,
     untracked_files: Vec<String>,
+    failure: Option<String>,
     cursor_before_execute: Option<CursorState>,
     cursor_before_undo: Option<CursorState>,
 }
 
 impl StageAllCommand {
     pub fn new(repo_path: PathBuf) -> Self {
+        Self::new_with_size_limit(repo_path, git::DEFAULT_MAX_FILE_SIZE)
+    }
+
+    pub fn new_with_size_limit(repo_path: PathBuf, size_limit: u64) -> Self {
         let patch = git::get_unstaged_diff_patch(&repo_path).unwrap_or_default();
-        let untracked_files = git::get_untracked_files(&repo_path).unwrap_or_default();
+        // Only the files that get staged are unstaged again on undo.
+        let untracked_files = git::get_untracked_files(&repo_path)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|file| git::is_within_size_limit(&repo_path, file, size_limit))
+            .collect();
         Self {
             repo_path,
+            size_limit,
             patch,
             untracked_files,
+            failure: None,
             cursor_before_execute: None,
             cursor_before_undo: None,
         }
@@ -28,22 +42,37 @@ impl StageAllCommand {
 
 impl Command for StageAllCommand {
     fn execute(&mut self) -> bool {
-        git::add_all(&self.repo_path).expect("Failed to stage all files.");
-        true
+        check(
+            &mut self.failure,
+            "Failed to stage all files",
+            git::add_all_with_size_limit(&self.repo_path, self.size_limit),
+        )
     }
 
     fn undo(&mut self) {
+        self.failure = None;
         // Untracked files are now tracked, so we need to unstage them.
         for file in &self.untracked_files {
-            git::rm_cached(&self.repo_path, file).expect("Failed to unstage file.");
+            check(
+                &mut self.failure,
+                &format!("Failed to unstage {file}"),
+                git::rm_cached(&self.repo_path, file),
+            );
         }
 
         // For modified and deleted files, we apply the reverse of the patch.
         if !self.patch.is_empty() {
-            git::apply_patch(&self.repo_path, &self.patch, true, true)
-                .expect("Failed to apply patch in reverse.");
+            check(
+                &mut self.failure,
+                "Failed to unstage the changes",
+                git::apply_patch(&self.repo_path, &self.patch, true, true),
+            );
         }
     }
 
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
     command_impl!();
 }
@@ -99,4 +99,21 @@ mod tests {
         command.undo();
         assert_eq!(repo.get_status(), "");
     }
+
+    #[test]
+    fn test_stage_all_leaves_out_large_untracked_files() {
+        let repo = TestRepo::new();
+        repo.create_file("small.txt", "small");
+        repo.create_file("large.bin", &"x".repeat(2048));
+
+        let mut command = StageAllCommand::new_with_size_limit(repo.path.clone(), 1024);
+
+        // Execute
+        assert!(command.execute());
+        assert_eq!(repo.get_status(), "A  small.txt\n?? large.bin\n");
+
+        // Undo
+        command.undo();
+        assert_eq!(repo.get_status(), "?? large.bin\n?? small.txt\n");
+    }
 }
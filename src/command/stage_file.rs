@@ -1,12 +1,13 @@
 use std::path::PathBuf;
 
-use super::Command;
+use super::{Command, check};
 use crate::cursor_state::CursorState;
 use crate::git;
 
 pub struct StageFileCommand {
     pub repo_path: PathBuf,
     pub file_name: String,
+    failure: Option<String>,
     cursor_before_execute: Option<CursorState>,
     cursor_before_undo: Option<CursorState>,
 }
@@ -16,6 +17,7 @@ impl StageFileCommand {
         Self {
             repo_path,
             file_name,
+            failure: None,
             cursor_before_execute: None,
             cursor_before_undo: None,
         }
@@ -24,12 +26,24 @@ impl StageFileCommand {
 
 impl Command for StageFileCommand {
     fn execute(&mut self) -> bool {
-        git::stage_file(&self.repo_path, &self.file_name).expect("Failed to stage file.");
-        true
+        check(
+            &mut self.failure,
+            &format!("Failed to stage {}", self.file_name),
+            git::stage_file(&self.repo_path, &self.file_name),
+        )
     }
 
     fn undo(&mut self) {
-        git::unstage_file(&self.repo_path, &self.file_name).expect("Failed to unstage file.");
+        self.failure = None;
+        check(
+            &mut self.failure,
+            &format!("Failed to unstage {}", self.file_name),
+            git::unstage_file(&self.repo_path, &self.file_name),
+        );
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
     }
 
     command_impl!();
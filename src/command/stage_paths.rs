@@ -0,0 +1,64 @@
+use std::path::PathBuf;
+
+use super::{Command, check};
+use crate::cursor_state::CursorState;
+use crate::git;
+
+/// Stages several paths at once, e.g. all files of a moved directory so that
+/// git records them as renames.
+pub struct StagePathsCommand {
+    pub repo_path: PathBuf,
+    pub paths: Vec<String>,
+    /// Changes of `paths` that were already staged before.
+    previously_staged: String,
+    failure: Option<String>,
+    cursor_before_execute: Option<CursorState>,
+    cursor_before_undo: Option<CursorState>,
+}
+
+impl StagePathsCommand {
+    pub fn new(repo_path: PathBuf, paths: Vec<String>) -> Self {
+        Self {
+            repo_path,
+            paths,
+            previously_staged: String::new(),
+            failure: None,
+            cursor_before_execute: None,
+            cursor_before_undo: None,
+        }
+    }
+}
+
+impl Command for StagePathsCommand {
+    fn execute(&mut self) -> bool {
+        self.previously_staged =
+            git::get_paths_diff_patch(&self.repo_path, &self.paths).unwrap_or_default();
+        check(
+            &mut self.failure,
+            &format!("Failed to stage {}", self.paths.join(", ")),
+            git::stage_paths(&self.repo_path, &self.paths),
+        )
+    }
+
+    fn undo(&mut self) {
+        self.failure = None;
+        let unstaged = check(
+            &mut self.failure,
+            &format!("Failed to unstage {}", self.paths.join(", ")),
+            git::unstage_paths(&self.repo_path, &self.paths),
+        );
+        if unstaged && !self.previously_staged.is_empty() {
+            check(
+                &mut self.failure,
+                "Failed to restore the changes staged before",
+                git::apply_patch(&self.repo_path, &self.previously_staged, false, true),
+            );
+        }
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
+    command_impl!();
+}
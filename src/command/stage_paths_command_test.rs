@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use crate::command::{Command, StagePathsCommand, test_helpers::TestRepo};
+
+    fn paths() -> Vec<String> {
+        ["old/a.txt", "new/a.txt", "old/b.txt", "new/b.txt"]
+            .iter()
+            .map(|p| p.to_string())
+            .collect()
+    }
+
+    fn setup_moved_directory() -> TestRepo {
+        let repo = TestRepo::new();
+        std::fs::create_dir_all(repo.path.join("old")).unwrap();
+        repo.create_file("old/a.txt", "a\n");
+        repo.create_file("old/b.txt", "b\n");
+        repo.add_all();
+        repo.commit("initial");
+        std::fs::rename(repo.path.join("old"), repo.path.join("new")).unwrap();
+        repo
+    }
+
+    #[test]
+    fn test_stage_paths_records_renames() {
+        let repo = setup_moved_directory();
+        let mut command = StagePathsCommand::new(repo.path.clone(), paths());
+
+        // Execute
+        assert!(command.execute());
+        assert_eq!(
+            repo.get_status(),
+            "R  old/a.txt -> new/a.txt\nR  old/b.txt -> new/b.txt\n"
+        );
+
+        // Undo
+        command.undo();
+        assert_eq!(repo.get_status(), " D old/a.txt\n D old/b.txt\n?? new/\n");
+
+        // Redo
+        assert!(command.execute());
+        assert_eq!(
+            repo.get_status(),
+            "R  old/a.txt -> new/a.txt\nR  old/b.txt -> new/b.txt\n"
+        );
+    }
+
+    #[test]
+    fn test_stage_paths_undo_keeps_previously_staged_changes() {
+        let repo = setup_moved_directory();
+        repo.add_file("new/a.txt");
+
+        let mut command = StagePathsCommand::new(repo.path.clone(), paths());
+        assert!(command.execute());
+        command.undo();
+
+        assert_eq!(
+            repo.get_status(),
+            "A  new/a.txt\n D old/a.txt\n D old/b.txt\n?? new/b.txt\n"
+        );
+    }
+}
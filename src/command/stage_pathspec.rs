@@ -0,0 +1,72 @@
+use std::path::PathBuf;
+
+use super::{Command, check};
+use crate::cursor_state::CursorState;
+use crate::git;
+
+/// Stages the files matching a pathspec like `*.rs` or `src/**`. The files
+/// are listed when the command is made, so undo and redo unstage and stage
+/// exactly those, even once more files match.
+pub struct StagePathspecCommand {
+    pub repo_path: PathBuf,
+    pub pathspec: String,
+    /// The changed, deleted and untracked files the pathspec matched.
+    pub files: Vec<String>,
+    /// Changes of `files` that were already staged before.
+    previously_staged: String,
+    failure: Option<String>,
+    cursor_before_execute: Option<CursorState>,
+    cursor_before_undo: Option<CursorState>,
+}
+
+impl StagePathspecCommand {
+    /// Lists the files `pathspec`, separated by spaces for several, would
+    /// stage.
+    pub fn new(repo_path: PathBuf, pathspec: &str) -> anyhow::Result<Self> {
+        let pathspecs: Vec<String> = pathspec.split_whitespace().map(String::from).collect();
+        let files = git::get_pathspec_changes(&repo_path, &pathspecs)?;
+        Ok(Self {
+            repo_path,
+            pathspec: pathspec.to_string(),
+            files,
+            previously_staged: String::new(),
+            failure: None,
+            cursor_before_execute: None,
+            cursor_before_undo: None,
+        })
+    }
+}
+
+impl Command for StagePathspecCommand {
+    fn execute(&mut self) -> bool {
+        self.previously_staged =
+            git::get_paths_diff_patch(&self.repo_path, &self.files).unwrap_or_default();
+        check(
+            &mut self.failure,
+            &format!("Failed to stage {}", self.pathspec),
+            git::stage_paths(&self.repo_path, &self.files),
+        )
+    }
+
+    fn undo(&mut self) {
+        self.failure = None;
+        let unstaged = check(
+            &mut self.failure,
+            &format!("Failed to unstage {}", self.pathspec),
+            git::unstage_paths(&self.repo_path, &self.files),
+        );
+        if unstaged && !self.previously_staged.is_empty() {
+            check(
+                &mut self.failure,
+                "Failed to restore the changes staged before",
+                git::apply_patch(&self.repo_path, &self.previously_staged, false, true),
+            );
+        }
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
+    command_impl!();
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    use crate::command::{Command, StagePathspecCommand, test_helpers::TestRepo};
+
+    fn setup() -> TestRepo {
+        let repo = TestRepo::new();
+        std::fs::create_dir_all(repo.path.join("app/src")).unwrap();
+        repo.create_file("app/src/main.rs", "fn main() {}\n");
+        repo.create_file("README.md", "readme\n");
+        repo.add_all();
+        repo.commit("initial");
+        repo.create_file("app/src/main.rs", "fn main() { run() }\n");
+        repo.create_file("app/src/run.rs", "fn run() {}\n");
+        repo.create_file("README.md", "readme, changed\n");
+        repo
+    }
+
+    #[test]
+    fn test_stage_pathspec_stages_the_matching_files() {
+        let repo = setup();
+        let mut command = StagePathspecCommand::new(repo.path.clone(), "*.rs").unwrap();
+        assert_eq!(command.files, vec!["app/src/main.rs", "app/src/run.rs"]);
+
+        // Execute
+        assert!(command.execute());
+        assert_eq!(
+            repo.get_status(),
+            " M README.md\nM  app/src/main.rs\nA  app/src/run.rs\n"
+        );
+
+        // Undo
+        command.undo();
+        assert_eq!(
+            repo.get_status(),
+            " M README.md\n M app/src/main.rs\n?? app/src/run.rs\n"
+        );
+
+        // Redo
+        assert!(command.execute());
+        assert_eq!(
+            repo.get_status(),
+            " M README.md\nM  app/src/main.rs\nA  app/src/run.rs\n"
+        );
+    }
+
+    #[test]
+    fn test_stage_pathspec_undo_leaves_files_matched_later() {
+        let repo = setup();
+        let mut command = StagePathspecCommand::new(repo.path.clone(), "app/**").unwrap();
+        assert!(command.execute());
+        repo.create_file("app/src/lib.rs", "\n");
+        repo.add_file("app/src/lib.rs");
+
+        command.undo();
+
+        assert_eq!(
+            repo.get_status(),
+            " M README.md\nA  app/src/lib.rs\n M app/src/main.rs\n?? app/src/run.rs\n"
+        );
+    }
+
+    #[test]
+    fn test_stage_pathspec_matching_nothing() {
+        let repo = setup();
+        let command = StagePathspecCommand::new(repo.path.clone(), "*.toml docs/").unwrap();
+        assert!(command.files.is_empty());
+    }
+}
@@ -1,12 +1,13 @@
 use std::path::PathBuf;
 
-use super::Command;
+use super::{Command, check};
 use crate::cursor_state::CursorState;
 use crate::git;
 
 pub struct StageUntrackedCommand {
     pub repo_path: PathBuf,
     untracked_files: Vec<String>,
+    failure: Option<String>,
     cursor_before_execute: Option<CursorState>,
     cursor_before_undo: Option<CursorState>,
 }
@@ -17,6 +18,7 @@ impl StageUntrackedCommand {
         Self {
             repo_path,
             untracked_files,
+            failure: None,
             cursor_before_execute: None,
             cursor_before_undo: None,
         }
@@ -24,18 +26,39 @@ impl StageUntrackedCommand {
 }
 
 impl Command for StageUntrackedCommand {
+    /// Stages the files one by one. If one fails, those staged are
+    /// unstaged again.
     fn execute(&mut self) -> bool {
-        for file in &self.untracked_files {
-            git::stage_file(&self.repo_path, file).expect("Failed to stage untracked file.");
+        for (index, file) in self.untracked_files.iter().enumerate() {
+            let staged = check(
+                &mut self.failure,
+                &format!("Failed to stage {file}"),
+                git::stage_file(&self.repo_path, file),
+            );
+            if !staged {
+                for file in &self.untracked_files[..index] {
+                    let _ = git::rm_cached(&self.repo_path, file);
+                }
+                return false;
+            }
         }
         true
     }
 
     fn undo(&mut self) {
+        self.failure = None;
         for file in &self.untracked_files {
-            git::rm_cached(&self.repo_path, file).expect("Failed to unstage untracked file.");
+            check(
+                &mut self.failure,
+                &format!("Failed to unstage {file}"),
+                git::rm_cached(&self.repo_path, file),
+            );
         }
     }
 
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
     command_impl!();
 }
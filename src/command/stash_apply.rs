@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use super::{Command, check};
+use crate::cursor_state::CursorState;
+use crate::git;
+
+/// Applies a stash to the working tree. A pop groups it with a
+/// [`StashDropCommand`](super::StashDropCommand).
+pub struct StashApplyCommand {
+    pub repo_path: PathBuf,
+    pub hash: String,
+    patch: String,
+    failure: Option<String>,
+    cursor_before_execute: Option<CursorState>,
+    cursor_before_undo: Option<CursorState>,
+}
+
+impl StashApplyCommand {
+    pub fn new(repo_path: PathBuf, hash: String) -> Self {
+        let patch = git::get_stash_patch(&repo_path, &hash).unwrap_or_default();
+        Self {
+            repo_path,
+            hash,
+            patch,
+            failure: None,
+            cursor_before_execute: None,
+            cursor_before_undo: None,
+        }
+    }
+}
+
+impl Command for StashApplyCommand {
+    fn execute(&mut self) -> bool {
+        self.failure = None;
+        check(
+            &mut self.failure,
+            "Failed to apply the stash",
+            git::stash_apply(&self.repo_path, &self.hash),
+        )
+    }
+
+    fn undo(&mut self) {
+        self.failure = None;
+        if !self.patch.is_empty() {
+            check(
+                &mut self.failure,
+                "Failed to take the stash back out of the working tree",
+                git::apply_patch(&self.repo_path, &self.patch, true, false),
+            );
+        }
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
+    command_impl!();
+}
@@ -0,0 +1,100 @@
+use crate::command::test_helpers::{TestRepo, commit, create_file};
+use crate::command::{Command, GroupCommand, StashApplyCommand, StashDropCommand};
+use crate::git;
+
+fn stash_changes(repo: &TestRepo) -> git::StashEntry {
+    commit(&repo.path, "initial");
+    create_file(&repo.path, "a.txt", "stashed\n");
+    create_file(&repo.path, "new.txt", "untracked\n");
+    assert!(git::stash_push(&repo.path, "wip", false).unwrap());
+    git::get_stashes(&repo.path).unwrap().remove(0)
+}
+
+fn pop_command(repo: &TestRepo, stash: &git::StashEntry) -> GroupCommand {
+    GroupCommand::new(vec![
+        Box::new(StashApplyCommand::new(
+            repo.path.clone(),
+            stash.hash.clone(),
+        )),
+        Box::new(StashDropCommand::new(
+            repo.path.clone(),
+            stash.hash.clone(),
+            stash.message.clone(),
+        )),
+    ])
+}
+
+#[test]
+fn test_stash_apply_and_undo() {
+    let repo = TestRepo::new();
+    create_file(&repo.path, "a.txt", "base\n");
+    let stash = stash_changes(&repo);
+    assert_eq!(repo.get_status(), "");
+
+    let mut command = StashApplyCommand::new(repo.path.clone(), stash.hash);
+    assert!(command.execute());
+    assert_eq!(
+        std::fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+        "stashed\n"
+    );
+    assert!(repo.path.join("new.txt").exists());
+    assert_eq!(git::get_stashes(&repo.path).unwrap().len(), 1);
+
+    command.undo();
+    assert_eq!(repo.get_status(), "");
+    assert!(!repo.path.join("new.txt").exists());
+    assert_eq!(git::get_stashes(&repo.path).unwrap().len(), 1);
+}
+
+#[test]
+fn test_stash_pop_and_undo() {
+    let repo = TestRepo::new();
+    create_file(&repo.path, "a.txt", "base\n");
+    let stash = stash_changes(&repo);
+
+    let mut command = pop_command(&repo, &stash);
+    assert!(command.execute());
+    assert!(git::get_stashes(&repo.path).unwrap().is_empty());
+
+    command.undo();
+    assert_eq!(repo.get_status(), "");
+    let stashes = git::get_stashes(&repo.path).unwrap();
+    assert_eq!(stashes.len(), 1);
+    assert_eq!(stashes[0].hash, stash.hash);
+    assert_eq!(stashes[0].message, stash.message);
+}
+
+#[test]
+fn test_stash_apply_conflict_fails() {
+    let repo = TestRepo::new();
+    create_file(&repo.path, "a.txt", "base\n");
+    let stash = stash_changes(&repo);
+    create_file(&repo.path, "a.txt", "local edit\n");
+
+    let mut command = pop_command(&repo, &stash);
+    assert!(!command.execute());
+    assert_eq!(git::get_stashes(&repo.path).unwrap().len(), 1);
+}
+
+#[test]
+fn test_stash_apply_undo_reports_failure() {
+    let repo = TestRepo::new();
+    create_file(&repo.path, "a.txt", "base\n");
+    let stash = stash_changes(&repo);
+
+    let mut command = StashApplyCommand::new(repo.path.clone(), stash.hash);
+    assert!(command.execute());
+    create_file(&repo.path, "a.txt", "edited after apply\n");
+
+    command.undo();
+    assert!(
+        command
+            .failure()
+            .unwrap()
+            .starts_with("Failed to take the stash back out of the working tree")
+    );
+    assert_eq!(
+        std::fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+        "edited after apply\n"
+    );
+}
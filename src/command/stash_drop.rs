@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use super::{Command, check};
+use crate::cursor_state::CursorState;
+use crate::git;
+
+/// Removes a stash from the stash list. The stash commit itself survives, so
+/// undo stores it again.
+pub struct StashDropCommand {
+    pub repo_path: PathBuf,
+    pub hash: String,
+    pub message: String,
+    failure: Option<String>,
+    cursor_before_execute: Option<CursorState>,
+    cursor_before_undo: Option<CursorState>,
+}
+
+impl StashDropCommand {
+    pub fn new(repo_path: PathBuf, hash: String, message: String) -> Self {
+        Self {
+            repo_path,
+            hash,
+            message,
+            failure: None,
+            cursor_before_execute: None,
+            cursor_before_undo: None,
+        }
+    }
+}
+
+impl Command for StashDropCommand {
+    fn execute(&mut self) -> bool {
+        self.failure = None;
+        check(
+            &mut self.failure,
+            "Failed to drop the stash",
+            git::stash_drop(&self.repo_path, &self.hash),
+        )
+    }
+
+    fn undo(&mut self) {
+        self.failure = None;
+        check(
+            &mut self.failure,
+            "Failed to restore the stash",
+            git::stash_store(&self.repo_path, &self.hash, &self.message),
+        );
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
+    command_impl!();
+}
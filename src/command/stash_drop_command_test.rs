@@ -0,0 +1,32 @@
+use crate::command::test_helpers::{TestRepo, commit, create_file};
+use crate::command::{Command, StashDropCommand};
+use crate::git;
+
+#[test]
+fn test_stash_drop_and_undo() {
+    let repo = TestRepo::new();
+    create_file(&repo.path, "a.txt", "base\n");
+    commit(&repo.path, "initial");
+    create_file(&repo.path, "a.txt", "first\n");
+    git::stash_push(&repo.path, "first", false).unwrap();
+    create_file(&repo.path, "a.txt", "second\n");
+    git::stash_push(&repo.path, "second", false).unwrap();
+    let stashes = git::get_stashes(&repo.path).unwrap();
+    let older = stashes[1].clone();
+
+    let mut command =
+        StashDropCommand::new(repo.path.clone(), older.hash.clone(), older.message.clone());
+    assert!(command.execute());
+    let remaining = git::get_stashes(&repo.path).unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].hash, stashes[0].hash);
+
+    command.undo();
+    let restored = git::get_stashes(&repo.path).unwrap();
+    assert_eq!(restored.len(), 2);
+    assert!(
+        restored
+            .iter()
+            .any(|s| s.hash == older.hash && s.message == older.message)
+    );
+}
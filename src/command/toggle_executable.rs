@@ -0,0 +1,127 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::{Command, check};
+use crate::cursor_state::CursorState;
+use crate::git;
+
+pub struct ToggleExecutableCommand {
+    pub repo_path: PathBuf,
+    pub file_name: String,
+    /// Executable bits before the toggle: `(index, worktree)`. The index value
+    /// is `None` for untracked files.
+    previous: Option<(Option<bool>, bool)>,
+    failure: Option<String>,
+    cursor_before_execute: Option<CursorState>,
+    cursor_before_undo: Option<CursorState>,
+}
+
+impl ToggleExecutableCommand {
+    pub fn new(repo_path: PathBuf, file_name: String) -> Self {
+        Self {
+            repo_path,
+            file_name,
+            previous: None,
+            failure: None,
+            cursor_before_execute: None,
+            cursor_before_undo: None,
+        }
+    }
+}
+
+impl Command for ToggleExecutableCommand {
+    fn execute(&mut self) -> bool {
+        self.failure = None;
+        let full_path = self.repo_path.join(&self.file_name);
+        let Some(worktree) = is_executable(&full_path) else {
+            return false;
+        };
+        let index = git::index_mode(&self.repo_path, &self.file_name)
+            .ok()
+            .flatten()
+            .map(|mode| mode == "100755");
+
+        // The index decides for tracked files, so a staged mode change is what
+        // gets flipped; the worktree follows to avoid an unstaged mode change.
+        let target = !index.unwrap_or(worktree);
+        if !check(
+            &mut self.failure,
+            &format!("Failed to change the mode of {}", self.file_name),
+            set_executable(&full_path, target).map_err(Into::into),
+        ) {
+            return false;
+        }
+        if index.is_some()
+            && !check(
+                &mut self.failure,
+                &format!("Failed to stage the mode of {}", self.file_name),
+                git::update_index_chmod(&self.repo_path, &self.file_name, target),
+            )
+        {
+            let _ = set_executable(&full_path, worktree);
+            return false;
+        }
+        self.previous = Some((index, worktree));
+        true
+    }
+
+    fn undo(&mut self) {
+        self.failure = None;
+        let Some((index, worktree)) = self.previous else {
+            return;
+        };
+        let full_path = self.repo_path.join(&self.file_name);
+        check(
+            &mut self.failure,
+            &format!("Failed to restore the mode of {}", self.file_name),
+            set_executable(&full_path, worktree).map_err(Into::into),
+        );
+        if let Some(index) = index {
+            check(
+                &mut self.failure,
+                &format!("Failed to restore the staged mode of {}", self.file_name),
+                git::update_index_chmod(&self.repo_path, &self.file_name, index),
+            );
+        }
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
+    command_impl!();
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> Option<bool> {
+    use std::os::unix::fs::PermissionsExt;
+    let metadata = fs::metadata(path).ok()?;
+    metadata
+        .is_file()
+        .then(|| metadata.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> Option<bool> {
+    fs::metadata(path).ok()?.is_file().then_some(false)
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path, executable: bool) -> std::io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = fs::metadata(path)?.permissions();
+    let mode = permissions.mode();
+    // Like `chmod +x`, grant execute wherever read is granted.
+    let mode = if executable {
+        mode | ((mode & 0o444) >> 2)
+    } else {
+        mode & !0o111
+    };
+    permissions.set_mode(mode);
+    fs::set_permissions(path, permissions)
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path, _executable: bool) -> std::io::Result<()> {
+    Ok(())
+}
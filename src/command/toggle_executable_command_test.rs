@@ -0,0 +1,111 @@
+#[cfg(test)]
+mod tests {
+    use crate::command::{Command, ToggleExecutableCommand, test_helpers::TestRepo};
+    use crate::git;
+    use std::os::unix::fs::PermissionsExt;
+
+    fn worktree_mode(repo: &TestRepo, file_name: &str) -> u32 {
+        std::fs::metadata(repo.path.join(file_name))
+            .unwrap()
+            .permissions()
+            .mode()
+            & 0o777
+    }
+
+    fn index_mode(repo: &TestRepo, file_name: &str) -> Option<String> {
+        git::index_mode(&repo.path, file_name).unwrap()
+    }
+
+    #[test]
+    fn test_toggle_executable_on_staged_file() {
+        let repo = TestRepo::new();
+        repo.create_file("run.sh", "echo hi\n");
+        std::fs::set_permissions(
+            repo.path.join("run.sh"),
+            std::fs::Permissions::from_mode(0o644),
+        )
+        .unwrap();
+        repo.add_file("run.sh");
+
+        let mut command = ToggleExecutableCommand::new(repo.path.clone(), "run.sh".into());
+
+        // Execute
+        assert!(command.execute());
+        assert_eq!(index_mode(&repo, "run.sh").as_deref(), Some("100755"));
+        assert_eq!(worktree_mode(&repo, "run.sh"), 0o755);
+
+        // Undo
+        command.undo();
+        assert_eq!(index_mode(&repo, "run.sh").as_deref(), Some("100644"));
+        assert_eq!(worktree_mode(&repo, "run.sh"), 0o644);
+
+        // Redo
+        assert!(command.execute());
+        assert_eq!(index_mode(&repo, "run.sh").as_deref(), Some("100755"));
+    }
+
+    #[test]
+    fn test_toggle_executable_off() {
+        let repo = TestRepo::new();
+        repo.create_file("run.sh", "echo hi\n");
+        std::fs::set_permissions(
+            repo.path.join("run.sh"),
+            std::fs::Permissions::from_mode(0o755),
+        )
+        .unwrap();
+        repo.add_file("run.sh");
+        repo.commit("initial");
+
+        let mut command = ToggleExecutableCommand::new(repo.path.clone(), "run.sh".into());
+
+        assert!(command.execute());
+        assert_eq!(index_mode(&repo, "run.sh").as_deref(), Some("100644"));
+        assert_eq!(worktree_mode(&repo, "run.sh"), 0o644);
+        assert_eq!(repo.get_status(), "M  run.sh\n");
+    }
+
+    #[test]
+    fn test_toggle_executable_on_untracked_file_only_changes_worktree() {
+        let repo = TestRepo::new();
+        repo.create_file("run.sh", "echo hi\n");
+        std::fs::set_permissions(
+            repo.path.join("run.sh"),
+            std::fs::Permissions::from_mode(0o600),
+        )
+        .unwrap();
+
+        let mut command = ToggleExecutableCommand::new(repo.path.clone(), "run.sh".into());
+
+        assert!(command.execute());
+        assert_eq!(worktree_mode(&repo, "run.sh"), 0o700);
+        assert_eq!(index_mode(&repo, "run.sh"), None);
+
+        command.undo();
+        assert_eq!(worktree_mode(&repo, "run.sh"), 0o600);
+    }
+
+    #[test]
+    fn test_toggle_executable_on_missing_file_fails() {
+        let repo = TestRepo::new();
+        let mut command = ToggleExecutableCommand::new(repo.path.clone(), "missing".into());
+        assert!(!command.execute());
+    }
+
+    #[test]
+    fn test_undo_of_a_removed_file_reports_failure() {
+        let repo = TestRepo::new();
+        repo.create_file("run.sh", "echo hi\n");
+        repo.add_file("run.sh");
+        let mut command = ToggleExecutableCommand::new(repo.path.clone(), "run.sh".into());
+        assert!(command.execute());
+        std::fs::remove_file(repo.path.join("run.sh")).unwrap();
+
+        command.undo();
+        assert!(
+            command
+                .failure()
+                .unwrap()
+                .starts_with("Failed to restore the ")
+        );
+    }
+}
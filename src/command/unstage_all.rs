@@ -1,12 +1,13 @@
 use std::path::PathBuf;
 
-use super::Command;
+use super::{Command, check};
 use crate::cursor_state::CursorState;
 use crate::git;
 
 pub struct UnstageAllCommand {
     pub repo_path: PathBuf,
     patch: String,
+    failure: Option<String>,
     cursor_before_execute: Option<CursorState>,
     cursor_before_undo: Option<CursorState>,
 }
@@ -17,6 +18,7 @@ impl UnstageAllCommand {
         Self {
             repo_path,
             patch,
+            failure: None,
             cursor_before_execute: None,
             cursor_before_undo: None,
         }
@@ -25,16 +27,27 @@ impl UnstageAllCommand {
 
 impl Command for UnstageAllCommand {
     fn execute(&mut self) -> bool {
-        git::unstage_all(&self.repo_path).expect("Failed to unstage all files.");
-        true
+        check(
+            &mut self.failure,
+            "Failed to unstage all files",
+            git::unstage_all(&self.repo_path),
+        )
     }
 
     fn undo(&mut self) {
+        self.failure = None;
         if !self.patch.is_empty() {
-            git::apply_patch(&self.repo_path, &self.patch, false, true)
-                .expect("Failed to apply patch for unstage undo.");
+            check(
+                &mut self.failure,
+                "Failed to stage the files again",
+                git::apply_patch(&self.repo_path, &self.patch, false, true),
+            );
         }
     }
 
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
     command_impl!();
 }
@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use super::Command;
+use super::{Command, check};
 use crate::cursor_state::CursorState;
 use crate::git;
 
@@ -8,6 +8,7 @@ pub struct UnstageFileCommand {
     pub repo_path: PathBuf,
     pub file_name: String,
     patch: String,
+    failure: Option<String>,
     cursor_before_execute: Option<CursorState>,
     cursor_before_undo: Option<CursorState>,
 }
@@ -19,6 +20,7 @@ impl UnstageFileCommand {
             repo_path,
             file_name,
             patch,
+            failure: None,
             cursor_before_execute: None,
             cursor_before_undo: None,
         }
@@ -27,16 +29,27 @@ impl UnstageFileCommand {
 
 impl Command for UnstageFileCommand {
     fn execute(&mut self) -> bool {
-        git::unstage_file(&self.repo_path, &self.file_name).expect("Failed to unstage file.");
-        true
+        check(
+            &mut self.failure,
+            &format!("Failed to unstage {}", self.file_name),
+            git::unstage_file(&self.repo_path, &self.file_name),
+        )
     }
 
     fn undo(&mut self) {
+        self.failure = None;
         if !self.patch.is_empty() {
-            git::apply_patch(&self.repo_path, &self.patch, false, true)
-                .expect("Failed to apply patch for unstage undo.");
+            check(
+                &mut self.failure,
+                &format!("Failed to stage {} again", self.file_name),
+                git::apply_patch(&self.repo_path, &self.patch, false, true),
+            );
         }
     }
 
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
     command_impl!();
 }
@@ -0,0 +1,56 @@
+use std::path::PathBuf;
+
+use super::{Command, check};
+use crate::cursor_state::CursorState;
+use crate::git;
+
+/// Unstages several paths at once, e.g. all files of a moved directory.
+pub struct UnstagePathsCommand {
+    pub repo_path: PathBuf,
+    pub paths: Vec<String>,
+    patch: String,
+    failure: Option<String>,
+    cursor_before_execute: Option<CursorState>,
+    cursor_before_undo: Option<CursorState>,
+}
+
+impl UnstagePathsCommand {
+    pub fn new(repo_path: PathBuf, paths: Vec<String>) -> Self {
+        let patch = git::get_paths_diff_patch(&repo_path, &paths).unwrap_or_default();
+        Self {
+            repo_path,
+            paths,
+            patch,
+            failure: None,
+            cursor_before_execute: None,
+            cursor_before_undo: None,
+        }
+    }
+}
+
+impl Command for UnstagePathsCommand {
+    fn execute(&mut self) -> bool {
+        check(
+            &mut self.failure,
+            &format!("Failed to unstage {}", self.paths.join(", ")),
+            git::unstage_paths(&self.repo_path, &self.paths),
+        )
+    }
+
+    fn undo(&mut self) {
+        self.failure = None;
+        if !self.patch.is_empty() {
+            check(
+                &mut self.failure,
+                &format!("Failed to stage {} again", self.paths.join(", ")),
+                git::apply_patch(&self.repo_path, &self.patch, false, true),
+            );
+        }
+    }
+
+    fn failure(&self) -> Option<String> {
+        self.failure.clone()
+    }
+
+    command_impl!();
+}
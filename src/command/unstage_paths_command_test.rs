@@ -0,0 +1,33 @@
+#[cfg(test)]
+mod tests {
+    use crate::command::{Command, UnstagePathsCommand, test_helpers::TestRepo};
+
+    #[test]
+    fn test_unstage_paths_of_renamed_directory() {
+        let repo = TestRepo::new();
+        std::fs::create_dir_all(repo.path.join("old")).unwrap();
+        repo.create_file("old/a.txt", "a\n");
+        repo.create_file("old/b.txt", "b\n");
+        repo.add_all();
+        repo.commit("initial");
+        std::fs::rename(repo.path.join("old"), repo.path.join("new")).unwrap();
+        repo.add_all();
+
+        let paths: Vec<String> = ["old/a.txt", "new/a.txt", "old/b.txt", "new/b.txt"]
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        let mut command = UnstagePathsCommand::new(repo.path.clone(), paths);
+
+        // Execute
+        assert!(command.execute());
+        assert_eq!(repo.get_status(), " D old/a.txt\n D old/b.txt\n?? new/\n");
+
+        // Undo
+        command.undo();
+        assert_eq!(
+            repo.get_status(),
+            "R  old/a.txt -> new/a.txt\nR  old/b.txt -> new/b.txt\n"
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use crate::config::Config;
+
+/// Subjects longer than this get a warning, as many tools cut them off.
+pub const DEFAULT_SUBJECT_WARN_LENGTH: usize = 50;
+
+/// Subjects longer than this are a problem, the way `git log --oneline`
+/// is read in a terminal of 80 columns.
+pub const DEFAULT_SUBJECT_MAX_LENGTH: usize = 72;
+
+/// Subjects made by `git commit --fixup` and `--squash`, which follow
+/// the subject of their target instead of the configured pattern.
+const AUTOSQUASH_PREFIXES: [&str; 3] = ["fixup! ", "squash! ", "amend! "];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    pub severity: Severity,
+    pub text: String,
+}
+
+/// What is wrong with the subject of `message`, worst first. An empty
+/// message has no problems, since it cannot be committed anyway.
+pub fn check(config: &Config, message: &str) -> Vec<Problem> {
+    let subject = message.lines().next().unwrap_or("").trim_end();
+    if subject.is_empty() {
+        return Vec::new();
+    }
+
+    let mut problems = Vec::new();
+    let length = subject.chars().count();
+    let max = config
+        .subject_max_length
+        .unwrap_or(DEFAULT_SUBJECT_MAX_LENGTH);
+    let warn = config
+        .subject_warn_length
+        .unwrap_or(DEFAULT_SUBJECT_WARN_LENGTH);
+    if length > max {
+        problems.push(Problem {
+            severity: Severity::Error,
+            text: format!("Subject is {length} characters, longer than {max}"),
+        });
+    } else if length > warn {
+        problems.push(Problem {
+            severity: Severity::Warning,
+            text: format!("Subject is {length} characters, longer than {warn}"),
+        });
+    }
+
+    if let Some(pattern) = &config.subject_pattern
+        && !AUTOSQUASH_PREFIXES
+            .iter()
+            .any(|prefix| subject.starts_with(prefix))
+        && !pattern.is_match(subject)
+    {
+        problems.push(Problem {
+            severity: Severity::Error,
+            text: format!("Subject does not match {}", pattern.as_str()),
+        });
+    }
+
+    problems.sort_by_key(|problem| std::cmp::Reverse(problem.severity));
+    problems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    fn conventional() -> Config {
+        Config {
+            subject_pattern: Some(Regex::new(r"^(feat|fix)(\(.+\))?: ").unwrap()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_subject_length() {
+        let config = Config::default();
+        assert!(check(&config, &"a".repeat(50)).is_empty());
+        assert_eq!(
+            check(&config, &format!("{}\n\nbody", "a".repeat(51))),
+            vec![Problem {
+                severity: Severity::Warning,
+                text: "Subject is 51 characters, longer than 50".to_string(),
+            }]
+        );
+        assert_eq!(
+            check(&config, &"あ".repeat(73))[0].severity,
+            Severity::Error
+        );
+        // The body is not checked
+        assert!(check(&config, &format!("Subject\n\n{}", "a".repeat(100))).is_empty());
+        assert!(check(&config, "").is_empty());
+    }
+
+    #[test]
+    fn test_subject_pattern() {
+        let config = conventional();
+        assert!(check(&config, "feat(ui): add a thing").is_empty());
+        assert!(check(&config, "fixup! Add a thing").is_empty());
+
+        let long = format!("Add a thing {}", "a".repeat(50));
+        let problems = check(&config, &long);
+        assert_eq!(problems.len(), 2);
+        assert_eq!(
+            problems[0].text,
+            r"Subject does not match ^(feat|fix)(\(.+\))?: "
+        );
+        assert_eq!(problems[1].severity, Severity::Warning);
+    }
+}
@@ -0,0 +1,140 @@
+use crate::diff_filter;
+
+/// A scope from the `[scopes]` section, for the files matching `pattern`
+/// or lying below a directory matching it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScopeMapping {
+    pub pattern: String,
+    pub scope: String,
+}
+
+/// Directories holding the code of most projects, which say nothing as a
+/// scope.
+const SOURCE_DIRS: [&str; 2] = ["src", "lib"];
+
+/// The scope mapped to `path`, the longest matching pattern winning like
+/// with diff filters.
+fn mapped<'a>(mappings: &'a [ScopeMapping], path: &str) -> Option<&'a str> {
+    let mut prefixes: Vec<&str> = path.match_indices('/').map(|(i, _)| &path[..i]).collect();
+    prefixes.push(path);
+    mappings
+        .iter()
+        .filter(|mapping| {
+            prefixes
+                .iter()
+                .any(|prefix| diff_filter::matches(&mapping.pattern, prefix))
+        })
+        .max_by_key(|mapping| mapping.pattern.len())
+        .map(|mapping| mapping.scope.as_str())
+}
+
+/// The conventional commit scope of a change to `paths`. When the mappings
+/// give a scope for any of them, it is that scope if all of them get the
+/// same one. Otherwise it is the name of the directory they all lie in,
+/// e.g. `ui` for `src/ui/a.rs` and `src/ui/b.rs`, or the name of the file
+/// without its extension when there is only one.
+pub fn infer(mappings: &[ScopeMapping], paths: &[&str]) -> Option<String> {
+    let scopes: Vec<Option<&str>> = paths.iter().map(|path| mapped(mappings, path)).collect();
+    if scopes.iter().any(Option::is_some) {
+        let first = scopes.first().copied().flatten()?;
+        return scopes
+            .iter()
+            .all(|scope| *scope == Some(first))
+            .then(|| first.to_string());
+    }
+
+    let (first, rest) = paths.split_first()?;
+    let mut common: Vec<&str> = first.split('/').collect();
+    for path in rest {
+        let shared = common
+            .iter()
+            .zip(path.split('/'))
+            .take_while(|(a, b)| **a == *b)
+            .count();
+        common.truncate(shared);
+    }
+    let name = match common.last() {
+        // A single file: its name without the extension.
+        Some(last) if common.len() == first.split('/').count() => {
+            last.split('.').next().unwrap_or(last)
+        }
+        Some(last) => last,
+        None => return None,
+    };
+    if name.is_empty() || (common.len() == 1 && SOURCE_DIRS.contains(&name)) {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+/// Where a scope would be typed: right after `type(` at the start of the
+/// subject, `before` and `after` being the line around the cursor.
+pub fn wants_scope(before: &str, after: &str) -> bool {
+    let Some(kind) = before.strip_suffix('(') else {
+        return false;
+    };
+    !kind.is_empty()
+        && kind.chars().all(|c| c.is_ascii_alphabetic())
+        && (after.is_empty() || after.starts_with(')'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mapping(pattern: &str, scope: &str) -> ScopeMapping {
+        ScopeMapping {
+            pattern: pattern.to_string(),
+            scope: scope.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_infer_from_the_common_directory() {
+        assert_eq!(
+            infer(&[], &["src/ui/a.rs", "src/ui/b.rs"]).as_deref(),
+            Some("ui")
+        );
+        assert_eq!(
+            infer(&[], &["src/command/a.rs", "src/command/a_test.rs"]).as_deref(),
+            Some("command")
+        );
+        assert_eq!(infer(&[], &["src/git.rs"]).as_deref(), Some("git"));
+        assert_eq!(infer(&[], &["src/git.rs", "src/config.rs"]), None);
+        assert_eq!(infer(&[], &["src/git.rs", "README.md"]), None);
+        assert_eq!(infer(&[], &[]), None);
+    }
+
+    #[test]
+    fn test_infer_from_the_mappings() {
+        let mappings = [
+            mapping("src/ui", "tui"),
+            mapping("src/ui/render.rs", "render"),
+            mapping("*.md", "docs"),
+        ];
+        assert_eq!(
+            infer(&mappings, &["src/ui/a.rs", "src/ui/deep/b.rs"]).as_deref(),
+            Some("tui")
+        );
+        assert_eq!(
+            infer(&mappings, &["src/ui/render.rs"]).as_deref(),
+            Some("render")
+        );
+        assert_eq!(
+            infer(&mappings, &["README.md", "spec/a.md"]).as_deref(),
+            Some("docs")
+        );
+        assert_eq!(infer(&mappings, &["src/ui/a.rs", "README.md"]), None);
+        assert_eq!(infer(&mappings, &["src/ui/a.rs", "src/git.rs"]), None);
+    }
+
+    #[test]
+    fn test_wants_scope() {
+        assert!(wants_scope("feat(", ""));
+        assert!(wants_scope("fix(", "): typo"));
+        assert!(!wants_scope("feat(", "ui): x"));
+        assert!(!wants_scope("Add a (", ""));
+        assert!(!wants_scope("(", ""));
+        assert!(!wants_scope("feat", ""));
+    }
+}
@@ -1,9 +1,10 @@
+use crate::git;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
 use std::{fs, io};
 
-fn get_storage_dir() -> Result<PathBuf, io::Error> {
+pub(crate) fn get_storage_dir() -> Result<PathBuf, io::Error> {
     dirs::home_dir()
         .map(|home| home.join(".git-reset-pp"))
         .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Home directory not found"))
@@ -17,6 +18,47 @@ fn get_commit_message_file_path(repo_path: &Path) -> Result<PathBuf, io::Error>
     Ok(storage_dir.join(format!("{repo_hash}")))
 }
 
+/// Messages kept in the history of a repository. Older ones are dropped.
+const HISTORY_LIMIT: usize = 100;
+
+/// Separates the messages in the history file, since they can have
+/// several lines.
+const HISTORY_SEPARATOR: char = '\0';
+
+fn get_history_file_path(repo_path: &Path) -> Result<PathBuf, io::Error> {
+    let mut path = get_commit_message_file_path(repo_path)?.into_os_string();
+    path.push(".history");
+    Ok(PathBuf::from(path))
+}
+
+/// Messages committed through the tool in this repository, oldest first.
+pub fn load_history(repo_path: &Path) -> Vec<String> {
+    get_history_file_path(repo_path)
+        .and_then(fs::read_to_string)
+        .map(|content| {
+            content
+                .split(HISTORY_SEPARATOR)
+                .filter(|message| !message.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Adds `message` as the newest entry of the history, moving it there if
+/// it was already in it.
+pub fn add_to_history(repo_path: &Path, message: &str) -> Result<(), io::Error> {
+    let mut history = load_history(repo_path);
+    history.retain(|entry| entry != message);
+    history.push(message.to_string());
+    let start = history.len().saturating_sub(HISTORY_LIMIT);
+    fs::create_dir_all(get_storage_dir()?)?;
+    fs::write(
+        get_history_file_path(repo_path)?,
+        history[start..].join(&HISTORY_SEPARATOR.to_string()),
+    )
+}
+
 pub fn save_commit_message(repo_path: &Path, message: &str) -> Result<(), io::Error> {
     let storage_dir = get_storage_dir()?;
     fs::create_dir_all(&storage_dir)?;
@@ -24,6 +66,27 @@ pub fn save_commit_message(repo_path: &Path, message: &str) -> Result<(), io::Er
     fs::write(file_path, message)
 }
 
+/// Saves `message` as the draft of the next commit, unless it is still the
+/// `template` the input was filled with. Then any draft is removed, so the
+/// template is made anew next time instead of being kept like typed text.
+pub fn save_draft(
+    repo_path: &Path,
+    message: &str,
+    template: Option<&str>,
+) -> Result<(), io::Error> {
+    if template == Some(message) {
+        delete_commit_message(repo_path)
+    } else {
+        save_commit_message(repo_path, message)
+    }
+}
+
+/// The message a new commit starts with when there is no draft, from
+/// `commit.template` and the `prepare-commit-msg` hook.
+pub fn load_commit_template(repo_path: &Path) -> anyhow::Result<Option<String>> {
+    git::prepare_commit_message(repo_path)
+}
+
 pub fn load_commit_message(repo_path: &Path) -> Result<String, io::Error> {
     let file_path = get_commit_message_file_path(repo_path)?;
     fs::read_to_string(file_path)
@@ -37,3 +100,32 @@ pub fn delete_commit_message(repo_path: &Path) -> Result<(), io::Error> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_keeps_the_newest_messages_once() {
+        let repo = tempfile::TempDir::new().unwrap();
+        let repo_path = repo.path();
+        assert!(load_history(repo_path).is_empty());
+
+        add_to_history(repo_path, "First\n\nWith a body").unwrap();
+        add_to_history(repo_path, "Second").unwrap();
+        add_to_history(repo_path, "First\n\nWith a body").unwrap();
+        assert_eq!(
+            load_history(repo_path),
+            vec!["Second", "First\n\nWith a body"]
+        );
+
+        for i in 0..HISTORY_LIMIT {
+            add_to_history(repo_path, &format!("Message {i}")).unwrap();
+        }
+        let history = load_history(repo_path);
+        assert_eq!(history.len(), HISTORY_LIMIT);
+        assert_eq!(history[0], "Message 0");
+
+        fs::remove_file(get_history_file_path(repo_path).unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,382 @@
+use crate::alert::AlertStyle;
+use crate::app_state::{CursorFallback, FileListOrder};
+use crate::commit_scope::ScopeMapping;
+use crate::diff_filter::DiffFilter;
+use crate::edit_stage::StageAfterEdit;
+use crate::external_command::EditorMode;
+use crate::formatter::Formatter;
+use crate::ui::color::Palette;
+use crate::util::file_size::parse_size;
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// User settings read from `~/.config/git-full-commit/config.toml`.
+///
+/// The file uses a small subset of TOML: `[section]` headers, `key = value`
+/// pairs with string, integer or boolean values, and `#` comments. Unknown
+/// keys are ignored and invalid values fall back to the defaults so a broken
+/// config never prevents the tool from starting.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    pub file_list_order: FileListOrder,
+    pub cursor_fallback: CursorFallback,
+    /// Directory holding templates for newly created files. Defaults to
+    /// `~/.config/git-full-commit/templates`.
+    pub template_dir: Option<PathBuf>,
+    /// Start with directory names abbreviated in the file lists.
+    pub abbreviate_paths: bool,
+    /// Start with the patch headers of the staged and unstaged diffs hidden.
+    pub hide_patch_headers: bool,
+    /// Start with the whitespace problems of the diffs highlighted.
+    pub show_whitespace: bool,
+    /// Lines of context around the changes of the staged and unstaged
+    /// diffs, like `git diff -U`. Defaults to git's `diff.context`.
+    pub diff_context: Option<usize>,
+    /// Command that shows a desktop notification when a long operation
+    /// finishes, e.g. `notify-send`. No notifications are sent without it.
+    pub notify_command: Option<String>,
+    /// Minimum duration of an operation to notify about. Defaults to
+    /// [`crate::notify::DEFAULT_NOTIFY_AFTER_SECONDS`].
+    pub notify_after_seconds: Option<u64>,
+    /// Command run on a checkout of the staged tree before each commit, e.g.
+    /// `cargo check`. The commit stops if it fails.
+    pub staged_check_command: Option<String>,
+    /// Command that opens the directory the staged tree is exported to with
+    /// `m`, which is appended to it. Defaults to the file manager.
+    pub staged_tree_command: Option<String>,
+    /// Command run on a checkout of the staged tree in the background when
+    /// asked with `t`, e.g. `cargo test`, its result shown next to the
+    /// commit input.
+    pub staged_test_command: Option<String>,
+    /// Run `staged_test_command` whenever the staged tree changes, so every
+    /// commit has a result before it is made.
+    pub staged_test_auto: bool,
+    /// Command the hunk under the diff cursor is piped to with `J`, e.g. a
+    /// linter explainer or an LLM CLI, its output shown in a popup.
+    pub explain_command: Option<String>,
+    /// What happens to the lines changed in a file opened with `e`.
+    pub stage_after_edit: StageAfterEdit,
+    /// Whether the screen waits for the editor opened with `e`.
+    pub editor_mode: EditorMode,
+    /// Bell or flash on refused keys and when a long operation finishes.
+    pub alert: AlertStyle,
+    /// Minimum duration of an operation to alert about. Defaults to
+    /// [`crate::alert::DEFAULT_ALERT_AFTER_SECONDS`].
+    pub alert_after_seconds: Option<u64>,
+    /// Colors of the diff and of the commit markers, with `+` and `-` in
+    /// the gutter for palettes that do not tell changes apart by color.
+    pub palette: Palette,
+    /// Subjects longer than this get a warning. Defaults to
+    /// [`crate::commit_lint::DEFAULT_SUBJECT_WARN_LENGTH`].
+    pub subject_warn_length: Option<usize>,
+    /// Subjects longer than this are a problem. Defaults to
+    /// [`crate::commit_lint::DEFAULT_SUBJECT_MAX_LENGTH`].
+    pub subject_max_length: Option<usize>,
+    /// Subjects must match this, e.g. a conventional commit pattern.
+    pub subject_pattern: Option<Regex>,
+    /// A message with problems is only committed once it is confirmed.
+    pub block_on_lint: bool,
+    /// Refreshes taking this long show a warning. Defaults to
+    /// [`crate::profile::DEFAULT_SLOW_REFRESH_MS`].
+    pub slow_refresh_ms: Option<u64>,
+    /// Untracked files larger than this many bytes are not staged
+    /// automatically. Defaults to [`crate::git::DEFAULT_MAX_FILE_SIZE`].
+    pub max_file_size: Option<u64>,
+    /// Commands from the `[diff_filters]` section that rewrite the shown
+    /// diff of the files matching their glob, sorted by pattern.
+    pub diff_filters: Vec<DiffFilter>,
+    /// Commands from the `[formatters]` section that format the staged
+    /// content of the files matching their glob, sorted by pattern.
+    pub formatters: Vec<Formatter>,
+    /// Scopes from the `[scopes]` section, offered for the commits changing
+    /// the files matching their glob, sorted by pattern.
+    pub scopes: Vec<ScopeMapping>,
+    /// `action = "keys"` pairs from the `[keys]` section, sorted by action.
+    /// See [`crate::keymap::KeyMap::new`].
+    pub key_bindings: Vec<(String, String)>,
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        config_dir().map(|dir| dir.join("config.toml"))
+    }
+
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|content| Self::parse(&content))
+            .unwrap_or_default()
+    }
+
+    pub fn parse(content: &str) -> Self {
+        let values = parse_values(content);
+        let mut config = Self::default();
+
+        match values.get("file_list_order").map(String::as_str) {
+            Some("tree") => config.file_list_order = FileListOrder::Tree,
+            Some("diff") => config.file_list_order = FileListOrder::Diff,
+            _ => {}
+        }
+        match values.get("cursor_fallback").map(String::as_str) {
+            Some("next") => config.cursor_fallback = CursorFallback::Next,
+            Some("previous") => config.cursor_fallback = CursorFallback::Previous,
+            _ => {}
+        }
+
+        match values.get("abbreviate_paths").map(String::as_str) {
+            Some("true") => config.abbreviate_paths = true,
+            Some("false") => config.abbreviate_paths = false,
+            _ => {}
+        }
+
+        match values.get("hide_patch_headers").map(String::as_str) {
+            Some("true") => config.hide_patch_headers = true,
+            Some("false") => config.hide_patch_headers = false,
+            _ => {}
+        }
+
+        match values.get("show_whitespace").map(String::as_str) {
+            Some("true") => config.show_whitespace = true,
+            Some("false") => config.show_whitespace = false,
+            _ => {}
+        }
+        if let Some(Ok(lines)) = values.get("diff_context").map(|v| v.parse()) {
+            config.diff_context = Some(lines);
+        }
+
+        if let Some(command) = values.get("notify_command")
+            && !command.trim().is_empty()
+        {
+            config.notify_command = Some(command.clone());
+        }
+        if let Some(command) = values.get("staged_check_command")
+            && !command.trim().is_empty()
+        {
+            config.staged_check_command = Some(command.clone());
+        }
+        if let Some(command) = values.get("staged_tree_command")
+            && !command.trim().is_empty()
+        {
+            config.staged_tree_command = Some(command.clone());
+        }
+        if let Some(command) = values.get("staged_test_command")
+            && !command.trim().is_empty()
+        {
+            config.staged_test_command = Some(command.clone());
+        }
+        if let Some(command) = values.get("explain_command")
+            && !command.trim().is_empty()
+        {
+            config.explain_command = Some(command.clone());
+        }
+        match values.get("stage_after_edit").map(String::as_str) {
+            Some("off") => config.stage_after_edit = StageAfterEdit::Off,
+            Some("stage") => config.stage_after_edit = StageAfterEdit::Stage,
+            Some("ask") => config.stage_after_edit = StageAfterEdit::Ask,
+            _ => {}
+        }
+        match values.get("editor_mode").map(String::as_str) {
+            Some("auto") => config.editor_mode = EditorMode::Auto,
+            Some("wait") => config.editor_mode = EditorMode::Wait,
+            Some("detach") => config.editor_mode = EditorMode::Detach,
+            _ => {}
+        }
+        match values.get("staged_test_auto").map(String::as_str) {
+            Some("true") => config.staged_test_auto = true,
+            Some("false") => config.staged_test_auto = false,
+            _ => {}
+        }
+        if let Some(Ok(seconds)) = values.get("notify_after_seconds").map(|v| v.parse()) {
+            config.notify_after_seconds = Some(seconds);
+        }
+        match values.get("alert").map(String::as_str) {
+            Some("bell") => config.alert = AlertStyle::Bell,
+            Some("flash") => config.alert = AlertStyle::Flash,
+            Some("off") => config.alert = AlertStyle::Off,
+            _ => {}
+        }
+        match values.get("palette").map(String::as_str) {
+            Some("default") => config.palette = Palette::Default,
+            Some("high-contrast") => config.palette = Palette::HighContrast,
+            Some("colorblind") => config.palette = Palette::Colorblind,
+            _ => {}
+        }
+        if let Some(Ok(seconds)) = values.get("alert_after_seconds").map(|v| v.parse()) {
+            config.alert_after_seconds = Some(seconds);
+        }
+        if let Some(Ok(length)) = values.get("subject_warn_length").map(|v| v.parse()) {
+            config.subject_warn_length = Some(length);
+        }
+        if let Some(Ok(length)) = values.get("subject_max_length").map(|v| v.parse()) {
+            config.subject_max_length = Some(length);
+        }
+        if let Some(Ok(pattern)) = values
+            .get("subject_pattern")
+            .filter(|pattern| !pattern.is_empty())
+            .map(|pattern| Regex::new(pattern))
+        {
+            config.subject_pattern = Some(pattern);
+        }
+        match values.get("block_on_lint").map(String::as_str) {
+            Some("true") => config.block_on_lint = true,
+            Some("false") => config.block_on_lint = false,
+            _ => {}
+        }
+        if let Some(Ok(ms)) = values.get("slow_refresh_ms").map(|v| v.parse()) {
+            config.slow_refresh_ms = Some(ms);
+        }
+
+        if let Some(size) = values.get("max_file_size").and_then(|v| parse_size(v)) {
+            config.max_file_size = Some(size);
+        }
+
+        if let Some(dir) = values.get("template_dir") {
+            config.template_dir = Some(expand_home(dir));
+        }
+
+        config.diff_filters = values
+            .iter()
+            .filter_map(|(key, command)| {
+                let pattern = key.strip_prefix("diff_filters.")?;
+                (!pattern.is_empty() && !command.trim().is_empty()).then(|| DiffFilter {
+                    pattern: pattern.to_string(),
+                    command: command.clone(),
+                })
+            })
+            .collect();
+        config
+            .diff_filters
+            .sort_by(|a, b| a.pattern.cmp(&b.pattern));
+
+        config.formatters = values
+            .iter()
+            .filter_map(|(key, command)| {
+                let pattern = key.strip_prefix("formatters.")?;
+                (!pattern.is_empty() && !command.trim().is_empty()).then(|| Formatter {
+                    pattern: pattern.to_string(),
+                    command: command.clone(),
+                })
+            })
+            .collect();
+        config.formatters.sort_by(|a, b| a.pattern.cmp(&b.pattern));
+
+        config.scopes = values
+            .iter()
+            .filter_map(|(key, scope)| {
+                let pattern = key.strip_prefix("scopes.")?;
+                (!pattern.is_empty() && !scope.trim().is_empty()).then(|| ScopeMapping {
+                    pattern: pattern.to_string(),
+                    scope: scope.trim().to_string(),
+                })
+            })
+            .collect();
+        config.scopes.sort_by(|a, b| a.pattern.cmp(&b.pattern));
+
+        config.key_bindings = values
+            .iter()
+            .filter_map(|(key, keys)| {
+                let action = key.strip_prefix("keys.")?;
+                Some((action.to_string(), keys.clone()))
+            })
+            .collect();
+        config.key_bindings.sort();
+
+        config
+    }
+
+    /// Directory the templates for new files are read from.
+    pub fn template_dir(&self) -> Option<PathBuf> {
+        self.template_dir
+            .clone()
+            .or_else(|| config_dir().map(|dir| dir.join("templates")))
+    }
+
+    /// Returns the initial content for a new file at `path`. A template named
+    /// like the file itself (e.g. `CHANGELOG.md`) wins over one for its
+    /// extension (`default.md`). Without a template the file starts empty.
+    pub fn template_for(&self, path: &str) -> Vec<u8> {
+        let Some(dir) = self.template_dir() else {
+            return Vec::new();
+        };
+        let path = Path::new(path);
+        let by_name = path.file_name().map(|name| dir.join(name));
+        let by_extension = path
+            .extension()
+            .map(|ext| dir.join(format!("default.{}", ext.to_string_lossy())));
+
+        by_name
+            .into_iter()
+            .chain(by_extension)
+            .find_map(|candidate| std::fs::read(candidate).ok())
+            .unwrap_or_default()
+    }
+}
+
+fn config_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".config/git-full-commit"))
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Flattens the file into `section.key -> value` pairs. Keys outside of any
+/// section are stored without a prefix. String values are unquoted.
+fn parse_values(content: &str) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    let mut section = String::new();
+
+    for raw_line in content.lines() {
+        let line = strip_comment(raw_line).trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim().to_string();
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().trim_matches('"');
+        let value = unquote(value.trim());
+        let full_key = if section.is_empty() {
+            key.to_string()
+        } else {
+            format!("{section}.{key}")
+        };
+        values.insert(full_key, value);
+    }
+
+    values
+}
+
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut previous = '\0';
+    for (i, ch) in line.char_indices() {
+        match ch {
+            '"' if previous != '\\' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+        previous = ch;
+    }
+    line
+}
+
+fn unquote(value: &str) -> String {
+    if let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        inner.replace("\\\"", "\"").replace("\\\\", "\\")
+    } else if let Some(inner) = value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')) {
+        inner.to_string()
+    } else {
+        value.to_string()
+    }
+}
@@ -1,4 +1,5 @@
 use crate::app_state::{AppState, FocusedPane};
+use crate::ui::main_screen::{ListItem, UnstagedListItem};
 
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct CursorState {
@@ -39,7 +40,14 @@ impl CursorState {
         state.focused_pane = self.focused_pane;
 
         // Restore main screen cursors
-        state.main_screen.file_cursor = self.file_cursor.min(state.files.len() + 2);
+        // Rows are counted from the list rather than the files, as collapsed
+        // rename groups show several files in one row.
+        let main_items = &state.main_screen.list_items;
+        let last_main_row = main_items
+            .iter()
+            .position(|item| matches!(item, ListItem::PreviousCommitInfo { .. }))
+            .unwrap_or(main_items.len().saturating_sub(1));
+        state.main_screen.file_cursor = self.file_cursor.min(last_main_row);
         if let Some(file) = state.current_main_file() {
             state.main_screen.line_cursor =
                 self.line_cursor.min(file.lines.len().saturating_sub(1));
@@ -51,9 +59,17 @@ impl CursorState {
         state.main_screen.horizontal_scroll = self.horizontal_scroll;
 
         // Restore unstaged screen cursors
-        state.unstaged_pane.cursor = self
-            .unstaged_cursor
-            .min(state.unstaged_pane.unstaged_files.len() + 1);
+        let unstaged_items = &state.unstaged_pane.list_items;
+        let last_unstaged_row = unstaged_items
+            .iter()
+            .position(|item| {
+                matches!(
+                    item,
+                    UnstagedListItem::UntrackedFilesHeader | UnstagedListItem::LargeFilesHeader
+                )
+            })
+            .unwrap_or(unstaged_items.len().saturating_sub(1));
+        state.unstaged_pane.cursor = self.unstaged_cursor.min(last_unstaged_row);
         // This seems to be unused in the current implementation, but we restore it anyway.
         if let Some(_file) = state.get_unstaged_file() {
             // Unstaged view doesn't have a line_cursor in the same way, it's implicit
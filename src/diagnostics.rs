@@ -0,0 +1,180 @@
+use crate::commit_storage::get_storage_dir;
+use crate::config::Config;
+use crate::git;
+use crate::keymap::KeyMap;
+use std::path::{Path, PathBuf};
+
+/// How many of the recent status line messages the diagnostics show.
+pub const RECENT_MESSAGE_LIMIT: usize = 20;
+
+/// Facts about the installation and the environment, shown by `--doctor`
+/// and in the diagnostics view so they can be pasted into a bug report.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostics {
+    pub version: String,
+    /// `git --version`, or why it could not be run.
+    pub git_version: Result<String, String>,
+    pub repo_path: Option<PathBuf>,
+    pub config_path: Option<PathBuf>,
+    pub config_exists: bool,
+    pub template_dir: Option<PathBuf>,
+    pub storage_dir: Option<PathBuf>,
+    /// `default`, or how many actions the config rebinds.
+    pub keymap: String,
+    /// What is wrong with the `[keys]` section, see [`KeyMap::problems`].
+    pub keymap_problems: Vec<String>,
+    /// Status line messages of this session, oldest first. `None` outside
+    /// of a session, as with `--doctor`.
+    pub recent_messages: Option<Vec<String>>,
+}
+
+impl Diagnostics {
+    pub fn collect(
+        repo_path: Option<&Path>,
+        config: &Config,
+        recent_messages: Option<&[String]>,
+    ) -> Self {
+        let config_path = Config::path();
+        let keymap = KeyMap::new(&config.key_bindings);
+        Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            git_version: git::get_git_version().map_err(|e| e.to_string()),
+            repo_path: repo_path.map(Path::to_path_buf),
+            config_exists: config_path.as_ref().is_some_and(|path| path.is_file()),
+            config_path,
+            template_dir: config.template_dir(),
+            storage_dir: get_storage_dir().ok(),
+            keymap: match keymap.rebound_count() {
+                0 => "default".to_string(),
+                1 => "1 action rebound".to_string(),
+                count => format!("{count} actions rebound"),
+            },
+            keymap_problems: keymap.problems().to_vec(),
+            recent_messages: recent_messages.map(<[String]>::to_vec),
+        }
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let path = |path: &Option<PathBuf>| {
+            path.as_ref()
+                .map_or_else(|| "unknown".to_string(), |p| p.display().to_string())
+        };
+        let mut lines = vec![
+            format!("git-full-commit {}", self.version),
+            format!(
+                "git:        {}",
+                match &self.git_version {
+                    Ok(version) => version.clone(),
+                    Err(e) => format!("not available ({e})"),
+                }
+            ),
+            format!(
+                "repository: {}",
+                self.repo_path
+                    .as_ref()
+                    .map_or_else(|| "not found".to_string(), |p| p.display().to_string())
+            ),
+            format!(
+                "config:     {}{}",
+                path(&self.config_path),
+                if self.config_exists {
+                    ""
+                } else {
+                    " (not found, using defaults)"
+                }
+            ),
+            format!("templates:  {}", path(&self.template_dir)),
+            format!("storage:    {}", path(&self.storage_dir)),
+            format!("keymap:     {}", self.keymap),
+        ];
+        lines.extend(
+            self.keymap_problems
+                .iter()
+                .map(|problem| format!("            {problem}")),
+        );
+        if let Some(messages) = &self.recent_messages {
+            lines.push(String::new());
+            lines.push("Recent messages:".to_string());
+            if messages.is_empty() {
+                lines.push("  (none)".to_string());
+            }
+            lines.extend(messages.iter().map(|message| format!("  {message}")));
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostics() -> Diagnostics {
+        Diagnostics {
+            version: "1.2.3".to_string(),
+            git_version: Ok("git version 2.43.0".to_string()),
+            repo_path: Some(PathBuf::from("/work/app")),
+            config_path: Some(PathBuf::from(
+                "/home/me/.config/git-full-commit/config.toml",
+            )),
+            config_exists: false,
+            template_dir: None,
+            storage_dir: Some(PathBuf::from("/home/me/.git-reset-pp")),
+            keymap: "default".to_string(),
+            keymap_problems: Vec::new(),
+            recent_messages: Some(Vec::new()),
+        }
+    }
+
+    #[test]
+    fn test_lines() {
+        assert_eq!(
+            diagnostics().lines(),
+            vec![
+                "git-full-commit 1.2.3",
+                "git:        git version 2.43.0",
+                "repository: /work/app",
+                "config:     /home/me/.config/git-full-commit/config.toml (not found, using defaults)",
+                "templates:  unknown",
+                "storage:    /home/me/.git-reset-pp",
+                "keymap:     default",
+                "",
+                "Recent messages:",
+                "  (none)",
+            ]
+        );
+
+        let failing = Diagnostics {
+            git_version: Err("No such file or directory".to_string()),
+            repo_path: None,
+            recent_messages: Some(vec!["Push failed: rejected".to_string()]),
+            ..diagnostics()
+        };
+        let lines = failing.lines();
+        assert_eq!(
+            lines[1],
+            "git:        not available (No such file or directory)"
+        );
+        assert_eq!(lines[2], "repository: not found");
+        assert_eq!(lines.last().unwrap(), "  Push failed: rejected");
+
+        let doctor = Diagnostics {
+            recent_messages: None,
+            ..diagnostics()
+        };
+        assert_eq!(doctor.lines().last().unwrap(), "keymap:     default");
+
+        let rebound = Diagnostics {
+            keymap: "2 actions rebound".to_string(),
+            keymap_problems: vec!["Unknown action bogus".to_string()],
+            recent_messages: None,
+            ..diagnostics()
+        };
+        assert_eq!(
+            rebound.lines()[6..],
+            [
+                "keymap:     2 actions rebound",
+                "            Unknown action bogus"
+            ]
+        );
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// An external command that rewrites the diff of the files matching
+/// `pattern` before it is shown, e.g. to fold minified lines.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffFilter {
+    pub pattern: String,
+    pub command: String,
+}
+
+/// Whether `path` matches the glob `pattern`, where `*` matches any run of
+/// characters but `/` and `?` one character. A pattern without `/` is
+/// matched against the file name only, like in `.gitignore`.
+pub fn matches(pattern: &str, path: &str) -> bool {
+    let target = if pattern.contains('/') {
+        path
+    } else {
+        path.rsplit('/').next().unwrap_or(path)
+    };
+    glob_match(pattern.as_bytes(), target.as_bytes())
+}
+
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len())
+            .take_while(|&skip| !text[..skip].contains(&b'/'))
+            .any(|skip| glob_match(rest, &text[skip..])),
+        Some((b'?', rest)) => text
+            .split_first()
+            .is_some_and(|(c, text)| *c != b'/' && glob_match(rest, text)),
+        Some((c, rest)) => text
+            .split_first()
+            .is_some_and(|(t, text)| t == c && glob_match(rest, text)),
+    }
+}
+
+/// The filter for `path`. When several match, the longest pattern wins as
+/// it is the most specific.
+pub fn find<'a>(filters: &'a [DiffFilter], path: &str) -> Option<&'a DiffFilter> {
+    filters
+        .iter()
+        .filter(|filter| matches(&filter.pattern, path))
+        .max_by_key(|filter| filter.pattern.len())
+}
+
+/// Runs `command` with `sh -c` in the repository, the diff lines on its
+/// standard input. The output must have as many lines as the input, so the
+/// cursor and the staging commands still line up with the real diff.
+pub fn run(command: &str, repo_path: &Path, lines: &[String]) -> Result<Vec<String>, String> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(repo_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    let mut input = lines.join("\n");
+    input.push('\n');
+    let mut stdin = child.stdin.take().ok_or("no standard input")?;
+    // Written from another thread so a filter that prints before reading
+    // everything can't block on a full pipe.
+    let writer = std::thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    let _ = writer.join();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(match stderr.trim() {
+            "" => format!("exited with {}", output.status),
+            stderr => stderr.to_string(),
+        });
+    }
+    let filtered: Vec<String> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(String::from)
+        .collect();
+    if filtered.len() != lines.len() {
+        return Err(format!(
+            "printed {} lines instead of {}",
+            filtered.len(),
+            lines.len()
+        ));
+    }
+    Ok(filtered)
+}
+
+/// Filter outputs by command and diff, so unchanged files are not filtered
+/// again on every refresh.
+#[derive(Debug, Default)]
+pub struct DiffFilterCache {
+    entries: HashMap<(String, u64), Result<Vec<String>, String>>,
+}
+
+impl DiffFilterCache {
+    fn key(command: &str, lines: &[String]) -> (String, u64) {
+        let mut hasher = DefaultHasher::new();
+        lines.hash(&mut hasher);
+        (command.to_string(), hasher.finish())
+    }
+
+    pub fn get(&self, command: &str, lines: &[String]) -> Option<&Result<Vec<String>, String>> {
+        self.entries.get(&Self::key(command, lines))
+    }
+
+    /// Filters the diff of every file in `files` that has a filter, reusing
+    /// the outputs that are still valid and forgetting the others. Returns
+    /// the failures of the filters that had to run, by file name.
+    pub fn update<'a>(
+        &mut self,
+        filters: &[DiffFilter],
+        repo_path: &Path,
+        files: impl IntoIterator<Item = (&'a str, &'a [String])>,
+    ) -> Vec<(String, String)> {
+        let mut entries = HashMap::new();
+        let mut failures = Vec::new();
+        for (file_name, lines) in files {
+            let Some(filter) = find(filters, file_name) else {
+                continue;
+            };
+            let key = Self::key(&filter.command, lines);
+            if entries.contains_key(&key) {
+                continue;
+            }
+            let result = self.entries.remove(&key).unwrap_or_else(|| {
+                let result = run(&filter.command, repo_path, lines);
+                if let Err(e) = &result {
+                    failures.push((file_name.to_string(), e.clone()));
+                }
+                result
+            });
+            entries.insert(key, result);
+        }
+        self.entries = entries;
+        failures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn test_matches() {
+        assert!(matches("*.min.js", "dist/app.min.js"));
+        assert!(!matches("*.min.js", "dist/app.js"));
+        assert!(matches("dist/*.js", "dist/app.js"));
+        assert!(!matches("dist/*.js", "dist/lib/app.js"));
+        assert!(!matches("dist/*.js", "app.js"));
+        assert!(matches("v?.lock", "v1.lock"));
+        assert!(matches("Cargo.lock", "Cargo.lock"));
+    }
+
+    #[test]
+    fn test_longest_pattern_wins() {
+        let filters = vec![
+            DiffFilter {
+                pattern: "*.js".to_string(),
+                command: "a".to_string(),
+            },
+            DiffFilter {
+                pattern: "*.min.js".to_string(),
+                command: "b".to_string(),
+            },
+        ];
+        assert_eq!(find(&filters, "app.min.js").unwrap().command, "b");
+        assert_eq!(find(&filters, "app.js").unwrap().command, "a");
+        assert_eq!(find(&filters, "app.ts"), None);
+    }
+
+    #[test]
+    fn test_run() {
+        let dir = std::env::temp_dir();
+        let diff = lines(&["@@ -1 +1 @@", "-old", "+new"]);
+        assert_eq!(
+            run("tr a-z A-Z", &dir, &diff),
+            Ok(lines(&["@@ -1 +1 @@", "-OLD", "+NEW"]))
+        );
+        assert_eq!(
+            run("head -n 1", &dir, &diff),
+            Err("printed 1 lines instead of 3".to_string())
+        );
+        assert_eq!(
+            run("echo broken >&2; exit 3", &dir, &diff),
+            Err("broken".to_string())
+        );
+    }
+
+    #[test]
+    fn test_cache_runs_each_filter_once() {
+        let dir = std::env::temp_dir();
+        let filters = vec![DiffFilter {
+            pattern: "*.txt".to_string(),
+            command: "tr a-z A-Z".to_string(),
+        }];
+        let diff = lines(&["+new"]);
+        let mut cache = DiffFilterCache::default();
+        let files = [("a.txt", diff.as_slice()), ("b.rs", diff.as_slice())];
+        assert!(cache.update(&filters, &dir, files).is_empty());
+        assert_eq!(cache.get("tr a-z A-Z", &diff), Some(&Ok(lines(&["+NEW"]))));
+
+        // A broken filter is reported once, then its failure is cached
+        let broken = vec![DiffFilter {
+            pattern: "*.txt".to_string(),
+            command: "exit 1".to_string(),
+        }];
+        let failures = cache.update(&broken, &dir, [("a.txt", diff.as_slice())]);
+        assert_eq!(failures.len(), 1);
+        assert!(
+            cache
+                .update(&broken, &dir, [("a.txt", diff.as_slice())])
+                .is_empty()
+        );
+        assert_eq!(cache.get("tr a-z A-Z", &diff), None);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::git::{FileDiff, FileStatus};
+
+/// What a file's diff adds and removes, like a line of `git diff --stat`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileStat {
+    /// The path, `old => new` for a rename.
+    pub name: String,
+    pub added: usize,
+    pub removed: usize,
+    pub binary: bool,
+}
+
+impl FileStat {
+    pub fn changes(&self) -> usize {
+        self.added + self.removed
+    }
+}
+
+/// Counts the lines of the hunks of `file`, without another git call.
+pub fn file_stat(file: &FileDiff) -> FileStat {
+    let (added, removed) = file
+        .hunks
+        .iter()
+        .flat_map(|hunk| hunk.lines.iter().skip(1))
+        .fold((0, 0), |(added, removed), line| match line.chars().next() {
+            Some('+') => (added + 1, removed),
+            Some('-') => (added, removed + 1),
+            _ => (added, removed),
+        });
+    let name = if file.status == FileStatus::Renamed && file.old_file_name != file.file_name {
+        format!("{} => {}", file.old_file_name, file.file_name)
+    } else {
+        file.file_name.clone()
+    };
+    FileStat {
+        name,
+        added,
+        removed,
+        binary: file.binary.is_some(),
+    }
+}
+
+/// The last line of `git diff --stat`, e.g.
+/// `2 files changed, 5 insertions(+), 1 deletion(-)`.
+pub fn summary(stats: &[FileStat]) -> String {
+    let plural = |count: usize, one: &str, many: &str| {
+        format!("{count} {}", if count == 1 { one } else { many })
+    };
+    let added: usize = stats.iter().map(|stat| stat.added).sum();
+    let removed: usize = stats.iter().map(|stat| stat.removed).sum();
+    let mut parts = vec![format!("{} changed", plural(stats.len(), "file", "files"))];
+    if added > 0 {
+        parts.push(format!("{}(+)", plural(added, "insertion", "insertions")));
+    }
+    if removed > 0 {
+        parts.push(format!("{}(-)", plural(removed, "deletion", "deletions")));
+    }
+    parts.join(", ")
+}
+
+/// How many `+` and `-` the bar of `stat` has when the file with the most
+/// changes, `most`, fills `width`. Like git, a side with changes keeps at
+/// least one mark.
+pub fn bar(stat: &FileStat, most: usize, width: usize) -> (usize, usize) {
+    if most <= width {
+        return (stat.added, stat.removed);
+    }
+    let sides = usize::from(stat.added > 0) + usize::from(stat.removed > 0);
+    let total = (stat.changes() * width / most).max(sides);
+    let mut removed = (stat.removed * total)
+        .checked_div(stat.changes())
+        .unwrap_or(0);
+    if stat.removed > 0 {
+        removed = removed.max(1);
+    }
+    if stat.added > 0 {
+        removed = removed.min(total - 1);
+    }
+    (total - removed, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::parse_diff;
+
+    #[test]
+    fn test_file_stat_counts_hunk_lines() {
+        let files = parse_diff(
+            "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n@@ -1,2 +1,3 @@\n a\n-b\n+c\n+d\n",
+        );
+        assert_eq!(
+            file_stat(&files[0]),
+            FileStat {
+                name: "a.txt".to_string(),
+                added: 2,
+                removed: 1,
+                binary: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_summary_like_git() {
+        let stat = |added, removed| FileStat {
+            name: "a".to_string(),
+            added,
+            removed,
+            binary: false,
+        };
+        assert_eq!(summary(&[stat(1, 0)]), "1 file changed, 1 insertion(+)");
+        assert_eq!(
+            summary(&[stat(3, 1), stat(2, 0)]),
+            "2 files changed, 5 insertions(+), 1 deletion(-)"
+        );
+        assert_eq!(summary(&[stat(0, 0)]), "1 file changed");
+    }
+
+    #[test]
+    fn test_bar_scales_to_the_width() {
+        let stat = |added, removed| FileStat {
+            name: "a".to_string(),
+            added,
+            removed,
+            binary: false,
+        };
+        assert_eq!(bar(&stat(3, 2), 5, 20), (3, 2));
+        assert_eq!(bar(&stat(100, 100), 200, 20), (10, 10));
+        // A side with changes keeps a mark
+        assert_eq!(bar(&stat(199, 1), 200, 20), (19, 1));
+        assert_eq!(bar(&stat(1, 0), 200, 20), (1, 0));
+    }
+}
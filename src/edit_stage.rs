@@ -0,0 +1,126 @@
+use crate::app_state::{AppState, PendingStage};
+use crate::command::StagePatchCommand;
+use crate::formatter;
+use crate::git;
+use crate::message_log::Severity;
+
+/// What happens to the lines changed in a file opened with `e`, once the
+/// editor exits.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum StageAfterEdit {
+    /// They are left unstaged.
+    #[default]
+    Off,
+    /// They are staged.
+    Stage,
+    /// Staging them is offered, `y` accepting.
+    Ask,
+}
+
+/// An edit made in the editor, waiting for `y` to be staged.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EditStageOffer {
+    pub file_name: String,
+    /// From the file before the editor opened to the saved one.
+    pub patch: String,
+}
+
+/// The content of `file_name` before the editor opens it, to find what it
+/// changed, unless edits are left unstaged.
+pub fn snapshot(state: &AppState, file_name: &str) -> Option<PendingStage> {
+    if state.config.stage_after_edit == StageAfterEdit::Off {
+        return None;
+    }
+    let initial_content = std::fs::read(state.repo_path.join(file_name)).ok()?;
+    Some(PendingStage {
+        file_name: file_name.to_string(),
+        initial_content,
+    })
+}
+
+/// The lines added and removed by `patch`, like `git diff --numstat`.
+pub fn line_counts(patch: &str) -> (usize, usize) {
+    let changes = patch
+        .lines()
+        .filter(|line| !line.starts_with("+++") && !line.starts_with("---"));
+    changes.fold((0, 0), |(added, removed), line| match line.chars().next() {
+        Some('+') => (added + 1, removed),
+        Some('-') => (added, removed + 1),
+        _ => (added, removed),
+    })
+}
+
+fn describe(offer: &EditStageOffer) -> String {
+    let (added, removed) = line_counts(&offer.patch);
+    format!("your edit of {} (+{added} -{removed})", offer.file_name)
+}
+
+/// Stages or offers to stage what the editor changed in the file, leaving
+/// the changes that were not staged before it opened as they are.
+pub fn finish(state: &mut AppState, pending: PendingStage) {
+    let saved = std::fs::read(state.repo_path.join(&pending.file_name)).unwrap_or_default();
+    // Binary files cannot be staged by lines.
+    let (Ok(before), Ok(after)) = (
+        String::from_utf8(pending.initial_content),
+        String::from_utf8(saved),
+    ) else {
+        state.refresh_diff(false);
+        return;
+    };
+    let Some(patch) = formatter::patch(&pending.file_name, &before, &after) else {
+        state.refresh_diff(false);
+        return;
+    };
+    let offer = EditStageOffer {
+        file_name: pending.file_name,
+        patch,
+    };
+    match state.config.stage_after_edit {
+        StageAfterEdit::Stage => stage(state, offer),
+        StageAfterEdit::Ask => {
+            state.refresh_diff(false);
+            state.set_message(
+                Severity::Info,
+                format!("Stage {}? y stages it", describe(&offer)),
+            );
+            state.edit_stage_offer = Some(offer);
+        }
+        StageAfterEdit::Off => state.refresh_diff(false),
+    }
+}
+
+/// Stages the edit as one undo step, unless it changed lines next to
+/// unstaged changes, which the index does not have.
+pub fn stage(state: &mut AppState, offer: EditStageOffer) {
+    if !git::can_apply_patch(&state.repo_path, &offer.patch, false, true) {
+        state.refresh_diff(false);
+        state.set_message(
+            Severity::Warning,
+            format!(
+                "Left {} unstaged, it touches lines that are not staged",
+                describe(&offer)
+            ),
+        );
+        state.alert();
+        return;
+    }
+    let command = Box::new(StagePatchCommand::new(
+        state.repo_path.clone(),
+        offer.patch.clone(),
+    ));
+    if state.execute_and_refresh(command) {
+        state.set_message(Severity::Success, format!("Staged {}", describe(&offer)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_counts_leave_out_the_headers() {
+        let patch = formatter::patch("a.txt", "a\nb\nc\n", "a\nB\nc\nd\n").unwrap();
+        assert_eq!(line_counts(&patch), (2, 1));
+        assert_eq!(line_counts(""), (0, 0));
+    }
+}
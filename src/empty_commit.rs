@@ -0,0 +1,136 @@
+use crate::app_state::AppState;
+use crate::command::UnstagePathsCommand;
+use crate::git::{FileDiff, FileStatus, Hunk};
+use crate::message_log::Severity;
+use std::collections::HashSet;
+
+/// Whether `hunk` takes out the same text it puts in, once whitespace and
+/// blank lines are left aside, like a change reverted by hand.
+fn is_whitespace_only(hunk: &Hunk) -> bool {
+    let mut removed = String::new();
+    let mut added = String::new();
+    for line in hunk.lines.iter().skip(1) {
+        let mut chars = line.chars();
+        let side = match chars.next() {
+            Some('-') => &mut removed,
+            Some('+') => &mut added,
+            _ => continue,
+        };
+        side.extend(chars.filter(|c| !c.is_whitespace()));
+    }
+    removed == added
+}
+
+/// Whether committing the staged change of `file` would only change its
+/// whitespace, or nothing at all. Added, deleted, renamed and binary files
+/// and mode changes always count.
+pub fn is_no_op(file: &FileDiff) -> bool {
+    file.status == FileStatus::Modified
+        && file.binary.is_none()
+        && !file.lines.iter().any(|line| line.starts_with("old mode "))
+        && file.hunks.iter().all(is_whitespace_only)
+}
+
+/// The staged files, if none of them changes more than whitespace. Files
+/// shown through a textconv driver are taken as they are, their lines are
+/// not the content.
+pub fn no_op_files(files: &[FileDiff], textconv_files: &HashSet<String>) -> Option<Vec<String>> {
+    let meaningful = files
+        .iter()
+        .any(|file| textconv_files.contains(&file.file_name) || !is_no_op(file));
+    if files.is_empty() || meaningful {
+        return None;
+    }
+    Some(files.iter().map(|file| file.file_name.clone()).collect())
+}
+
+/// Refuses committing `message` when the staged changes are only
+/// whitespace, offering to unstage them with `u`. Committing the same
+/// message again goes ahead. Whether it refused.
+pub fn refuse(state: &mut AppState, message: &str) -> bool {
+    if state.main_screen.empty_commit_confirmed_message.as_deref() == Some(message) {
+        return false;
+    }
+    let Some(files) = no_op_files(&state.files, &state.textconv_files) else {
+        return false;
+    };
+    let what = match files.as_slice() {
+        [file] => format!("{file} only changes"),
+        _ => format!("{} files only change", files.len()),
+    };
+    state.set_message(
+        Severity::Warning,
+        format!(
+            "Nothing meaningful to commit, {what} whitespace. u unstages, Alt+Enter commits anyway"
+        ),
+    );
+    state.main_screen.empty_commit_confirmed_message = Some(message.to_string());
+    state.empty_commit_cleanup = Some(files);
+    state.alert();
+    true
+}
+
+/// Unstages the files [`refuse`] found, as one undo step.
+pub fn unstage(state: &mut AppState, files: Vec<String>) {
+    let count = files.len();
+    let command = Box::new(UnstagePathsCommand::new(state.repo_path.clone(), files));
+    if state.execute_and_refresh(command) {
+        state.set_message(
+            Severity::Success,
+            if count == 1 {
+                "Unstaged the file that only changed whitespace".to_string()
+            } else {
+                format!("Unstaged {count} files that only changed whitespace")
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::parse_diff;
+
+    fn diff(header: &str, hunks: &str) -> FileDiff {
+        let text = format!("diff --git a/a.txt b/a.txt\n{header}--- a/a.txt\n+++ b/a.txt\n{hunks}");
+        parse_diff(&text).remove(0)
+    }
+
+    #[test]
+    fn test_whitespace_changes_are_no_ops() {
+        let file = diff(
+            "",
+            "@@ -1,3 +1,4 @@\n-fn main() {\n+fn main()  {\n+\n \tx\n-}\n+  }\n",
+        );
+        assert!(is_no_op(&file));
+
+        // A line split in two keeps its text
+        let file = diff("", "@@ -1 +1,2 @@\n-a b\n+a\n+b\n");
+        assert!(is_no_op(&file));
+
+        let file = diff("", "@@ -1 +1 @@\n-a\n+b\n");
+        assert!(!is_no_op(&file));
+    }
+
+    #[test]
+    fn test_mode_changes_count() {
+        let file = diff("old mode 100644\nnew mode 100755\n", "");
+        assert!(!is_no_op(&file));
+    }
+
+    #[test]
+    fn test_no_op_files_needs_all_of_them() {
+        let blank = diff("", "@@ -1 +1,2 @@\n a\n+\n");
+        let real = diff("", "@@ -1 +1 @@\n-a\n+b\n");
+        let none = HashSet::new();
+        assert_eq!(
+            no_op_files(std::slice::from_ref(&blank), &none),
+            Some(vec!["a.txt".to_string()])
+        );
+        assert_eq!(no_op_files(&[blank.clone(), real], &none), None);
+        assert_eq!(no_op_files(&[], &none), None);
+
+        let textconv = HashSet::from(["a.txt".to_string()]);
+        assert_eq!(no_op_files(&[blank], &textconv), None);
+    }
+}
@@ -13,12 +13,55 @@ fn is_command_available(command: &str) -> bool {
         .is_ok_and(|status| status.success())
 }
 
+/// How the editor opened with `e` runs, from `editor_mode` in the config.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum EditorMode {
+    /// The screen is suspended until the editor returns, which GUI editors
+    /// do right away.
+    #[default]
+    Auto,
+    /// The screen is suspended until the file is closed, GUI editors being
+    /// asked to wait.
+    Wait,
+    /// The editor is started on its own and the screen stays, for GUI
+    /// editors.
+    Detach,
+}
+
+/// Opens `file_path` in the user's editor. With `wait`, GUI editors are
+/// asked not to return before the file is closed, for when its content is
+/// read back right after.
 #[cfg(not(test))]
-pub fn open_editor(file_path: &str, line_number: Option<usize>) -> std::io::Result<()> {
+pub fn open_editor(file_path: &str, line_number: Option<usize>, wait: bool) -> std::io::Result<()> {
+    editor_command(file_path, line_number, wait)
+        .status()
+        .map(|_| ())
+}
+
+/// Starts the user's editor on `file_path` without waiting for it, with
+/// nothing attached to the terminal.
+#[cfg(not(test))]
+pub fn spawn_editor(file_path: &str, line_number: Option<usize>) -> std::io::Result<()> {
+    use std::process::Stdio;
+
+    let mut child = editor_command(file_path, line_number, false)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    std::thread::spawn(move || child.wait());
+    Ok(())
+}
+
+#[cfg(not(test))]
+fn editor_command(file_path: &str, line_number: Option<usize>, wait: bool) -> Command {
     let mut cmd;
 
     if is_command_available("code") {
         cmd = Command::new("code");
+        if wait {
+            cmd.arg("--wait");
+        }
         if let Some(line) = line_number {
             cmd.arg("-g").arg(format!("{file_path}:{line}"));
         } else {
@@ -32,6 +75,9 @@ pub fn open_editor(file_path: &str, line_number: Option<usize>) -> std::io::Resu
                 // But user might have `EDITOR=/path/to/code`
                 let mut inner_cmd = Command::new(&editor);
                 if editor.contains("code") {
+                    if wait {
+                        inner_cmd.arg("--wait");
+                    }
                     if let Some(line) = line_number {
                         inner_cmd.arg("-g").arg(format!("{file_path}:{line}"));
                     } else {
@@ -50,6 +96,9 @@ pub fn open_editor(file_path: &str, line_number: Option<usize>) -> std::io::Resu
                 // $EDITOR is not set or is empty, use platform defaults
                 if cfg!(target_os = "macos") {
                     let mut command = Command::new("open");
+                    if wait {
+                        command.arg("-W");
+                    }
                     command.arg(file_path);
                     command
                 } else {
@@ -65,16 +114,152 @@ pub fn open_editor(file_path: &str, line_number: Option<usize>) -> std::io::Resu
         };
     }
 
-    cmd.status().map(|_| ()).map_err(|e| {
-        // If the command fails (e.g. `code` not found), we can add a fallback here in the future.
-        // For now, just return the error to the caller.
-        eprintln!("Failed to open editor: {e}");
-        e
-    })
+    cmd
+}
+
+/// Runs `editor`, a shell command like `core.editor`, on `file_path` and
+/// waits for it, the way git runs its editor.
+#[cfg(not(test))]
+pub fn run_editor(editor: &str, file_path: &str) -> std::io::Result<()> {
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{editor} \"$@\""))
+        .arg(editor)
+        .arg(file_path)
+        .status()?;
+    if !status.success() {
+        return Err(std::io::Error::other(format!(
+            "{editor} exited with {status}"
+        )));
+    }
+    Ok(())
+}
+
+/// Programs that put their standard input on the clipboard, tried in order.
+#[cfg(not(test))]
+const CLIPBOARD_COMMANDS: &[(&str, &[&str])] = &[
+    ("pbcopy", &[]),
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+    ("clip.exe", &[]),
+];
+
+/// Puts `text` on the system clipboard with the first of
+/// `CLIPBOARD_COMMANDS` that is installed.
+#[cfg(not(test))]
+pub fn copy_to_clipboard(text: &str) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    for (program, args) in CLIPBOARD_COMMANDS {
+        let mut child = match Command::new(program)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        let status = child.wait()?;
+        return if status.success() {
+            Ok(())
+        } else {
+            Err(std::io::Error::other(format!("{program} failed")))
+        };
+    }
+    Err(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "no clipboard command found (pbcopy, wl-copy, xclip, xsel)",
+    ))
+}
+
+/// Programs that show a directory in the file manager, tried in order.
+#[cfg(not(test))]
+const OPEN_COMMANDS: &[&str] = if cfg!(target_os = "macos") {
+    &["open"]
+} else {
+    &["xdg-open", "explorer.exe"]
+};
+
+/// Opens the directory `path` with `command`, split on whitespace and with
+/// the path appended, or else in the file manager. It runs on its own,
+/// without being waited for.
+#[cfg(not(test))]
+pub fn open_directory(path: &std::path::Path, command: Option<&str>) -> std::io::Result<()> {
+    use std::process::Stdio;
+
+    let configured: Vec<&str> = command
+        .map(|command| command.split_whitespace().collect())
+        .unwrap_or_default();
+    let candidates: Vec<Vec<&str>> = if configured.is_empty() {
+        OPEN_COMMANDS.iter().map(|program| vec![*program]).collect()
+    } else {
+        vec![configured]
+    };
+    for args in &candidates {
+        let (program, rest) = args.split_first().expect("not empty");
+        let mut child = match Command::new(program)
+            .args(rest)
+            .arg(path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && command.is_none() => continue,
+            Err(e) => return Err(e),
+        };
+        std::thread::spawn(move || child.wait());
+        return Ok(());
+    }
+    Err(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "no file manager found (open, xdg-open, explorer.exe)",
+    ))
+}
+
+/// Runs `command` with the shell in `dir`, with `input` on its standard
+/// input, and waits for it to finish.
+pub fn run_with_input(
+    command: &str,
+    input: &str,
+    dir: &std::path::Path,
+    envs: &[(&str, &str)],
+) -> std::io::Result<std::process::Output> {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = std::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .envs(envs.iter().copied())
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let input = input.to_string();
+    // Written on the side, so a command printing a lot before it reads all
+    // of its input cannot block.
+    let writer = std::thread::spawn(move || {
+        let _ = stdin.write_all(input.as_bytes());
+    });
+    let output = child.wait_with_output()?;
+    let _ = writer.join();
+    Ok(output)
 }
 
 #[cfg(test)]
-pub use mock::open_editor;
+pub use mock::{copy_to_clipboard, open_directory, open_editor, run_editor, spawn_editor};
 
 #[cfg(test)]
 pub mod mock {
@@ -82,9 +267,14 @@ pub mod mock {
 
     lazy_static::lazy_static! {
         pub static ref CALLS: Mutex<Vec<(String, Option<usize>)>> = Mutex::new(Vec::new());
+        pub static ref CLIPBOARD: Mutex<Vec<String>> = Mutex::new(Vec::new());
     }
 
-    pub fn open_editor(file_path: &str, line_number: Option<usize>) -> std::io::Result<()> {
+    pub fn open_editor(
+        file_path: &str,
+        line_number: Option<usize>,
+        _wait: bool,
+    ) -> std::io::Result<()> {
         CALLS
             .lock()
             .unwrap()
@@ -92,6 +282,24 @@ pub mod mock {
         Ok(())
     }
 
+    pub fn spawn_editor(file_path: &str, line_number: Option<usize>) -> std::io::Result<()> {
+        open_editor(file_path, line_number, false)
+    }
+
+    pub fn run_editor(_editor: &str, file_path: &str) -> std::io::Result<()> {
+        CALLS.lock().unwrap().push((file_path.to_string(), None));
+        Ok(())
+    }
+
+    pub fn copy_to_clipboard(text: &str) -> std::io::Result<()> {
+        CLIPBOARD.lock().unwrap().push(text.to_string());
+        Ok(())
+    }
+
+    pub fn open_directory(_path: &std::path::Path, _command: Option<&str>) -> std::io::Result<()> {
+        Ok(())
+    }
+
     #[allow(dead_code)]
     pub fn get_calls() -> Vec<(String, Option<usize>)> {
         CALLS.lock().unwrap().clone()
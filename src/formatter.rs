@@ -0,0 +1,136 @@
+use crate::diff_filter;
+use crate::external_command;
+use crate::git::{self, FileDiff, FileStatus};
+use similar::TextDiff;
+use std::path::Path;
+
+/// A formatter from the `[formatters]` section, run on the staged content
+/// of the files matching `pattern`. It reads the file on its standard input
+/// and prints it formatted, e.g. `rustfmt --edition 2024`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Formatter {
+    pub pattern: String,
+    pub command: String,
+}
+
+/// What a formatter would change in the staged content of a file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fix {
+    pub file: String,
+    /// From the staged content to the formatted one, to apply to the index.
+    pub patch: String,
+}
+
+/// The formatter for `path`, the longest matching pattern winning like
+/// with diff filters.
+pub fn find<'a>(formatters: &'a [Formatter], path: &str) -> Option<&'a Formatter> {
+    formatters
+        .iter()
+        .filter(|formatter| diff_filter::matches(&formatter.pattern, path))
+        .max_by_key(|formatter| formatter.pattern.len())
+}
+
+/// Runs the formatters on the staged content of `files`, which is read from
+/// the index, so changes that are not staged are not formatted. Returns
+/// the fixes, and a line for each file its formatter failed on.
+pub fn format_staged(
+    repo_path: &Path,
+    formatters: &[Formatter],
+    files: &[FileDiff],
+) -> (Vec<Fix>, Vec<String>) {
+    let mut fixes = Vec::new();
+    let mut errors = Vec::new();
+    for file in files {
+        if file.status == FileStatus::Deleted || file.binary.is_some() {
+            continue;
+        }
+        let Some(formatter) = find(formatters, &file.file_name) else {
+            continue;
+        };
+        let staged =
+            match git::run_git_command(repo_path, &["show", &format!(":{}", file.file_name)]) {
+                Ok(staged) => staged,
+                Err(e) => {
+                    errors.push(format!("{}: {e}", file.file_name));
+                    continue;
+                }
+            };
+        match external_command::run_with_input(
+            &formatter.command,
+            &staged,
+            repo_path,
+            &[("GIT_FULL_COMMIT_FILE", &file.file_name)],
+        ) {
+            Ok(output) if !output.status.success() => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                errors.push(format!(
+                    "{}: {} failed: {}",
+                    file.file_name,
+                    formatter.command,
+                    stderr.lines().next().unwrap_or_default()
+                ));
+            }
+            Ok(output) if output.stdout.is_empty() && !staged.is_empty() => {
+                errors.push(format!(
+                    "{}: {} printed nothing",
+                    file.file_name, formatter.command
+                ));
+            }
+            Ok(output) => {
+                let formatted = String::from_utf8_lossy(&output.stdout);
+                if let Some(patch) = patch(&file.file_name, &staged, &formatted) {
+                    fixes.push(Fix {
+                        file: file.file_name.clone(),
+                        patch,
+                    });
+                }
+            }
+            Err(e) => errors.push(format!("{}: {e}", file.file_name)),
+        }
+    }
+    (fixes, errors)
+}
+
+/// A patch from `old` to `new` for `path`, `None` when they are the same.
+pub fn patch(path: &str, old: &str, new: &str) -> Option<String> {
+    if old == new {
+        return None;
+    }
+    let diff = TextDiff::from_lines(old, new)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{path}"), &format!("b/{path}"))
+        .to_string();
+    Some(format!("diff --git a/{path} b/{path}\n{diff}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn formatter(pattern: &str) -> Formatter {
+        Formatter {
+            pattern: pattern.to_string(),
+            command: format!("format {pattern}"),
+        }
+    }
+
+    #[test]
+    fn test_find_prefers_the_longest_pattern() {
+        let formatters = [formatter("*.rs"), formatter("src/*.rs")];
+        assert_eq!(find(&formatters, "src/main.rs"), Some(&formatters[1]));
+        assert_eq!(find(&formatters, "build.rs"), Some(&formatters[0]));
+        assert_eq!(find(&formatters, "README.md"), None);
+    }
+
+    #[test]
+    fn test_patch_goes_from_the_old_content_to_the_new_one() {
+        assert_eq!(patch("a.rs", "fn a() {}\n", "fn a() {}\n"), None);
+        assert_eq!(
+            patch("a.rs", "a\nb  \nc\n", "a\nb\nc\n").as_deref(),
+            Some(
+                "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1,3 +1,3 @@\n a\n-b  \n+b\n c\n"
+            )
+        );
+    }
+}
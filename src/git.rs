@@ -1,5 +1,7 @@
+use crate::util::word_diff::{WordChange, parse_porcelain};
 use anyhow::Result;
 use regex::Regex;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::process::Command as OsCommand;
 
@@ -33,14 +35,58 @@ pub struct FileDiff {
     pub hunks: Vec<Hunk>,
     pub lines: Vec<String>,
     pub status: FileStatus,
+    /// Set for a file git shows as `Binary files ... differ`.
+    pub binary: Option<BinaryDiff>,
 }
 
-#[derive(Debug, Clone)]
+/// What the header of a binary file's diff tells, since there are no lines
+/// to show.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BinaryDiff {
+    /// Abbreviated blob hashes from the `index` line, all zeros for a side
+    /// that does not exist.
+    pub old_blob: String,
+    pub new_blob: String,
+    pub old_mode: Option<String>,
+    pub new_mode: Option<String>,
+    /// In bytes, filled in by `fill_binary_sizes`.
+    pub old_size: Option<u64>,
+    pub new_size: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct CommitInfo {
     pub hash: String,
+    /// The full 40 character hash, `hash` being abbreviated.
+    pub full_hash: String,
+    /// The subject line.
     pub message: String,
     pub is_on_remote: bool,
     pub is_fixup: bool,
+    /// `Name <email>` of the author.
+    pub author: String,
+    /// When it was authored, relative to now, e.g. `3 days ago`.
+    pub date: String,
+    /// The message below the subject, without the blank line between them.
+    pub body: String,
+    /// Whether it carries a GPG or SSH signature. It is not verified.
+    pub is_signed: bool,
+    /// The paths it changes.
+    pub files: Vec<String>,
+    pub push: PushStatus,
+}
+
+/// What `git push` would do with a commit of the log.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum PushStatus {
+    /// It is on a remote already.
+    Pushed,
+    /// It is sent on the next push, on top of the upstream.
+    #[default]
+    WillPush,
+    /// The upstream has commits the branch does not, e.g. after amending a
+    /// pushed commit elsewhere, so pushing it needs `--force`.
+    NeedsForce,
 }
 
 impl PartialEq for CommitInfo {
@@ -53,9 +99,10 @@ impl PartialEq for CommitInfo {
 }
 
 pub fn get_local_commits(repo_path: &Path) -> Result<Vec<CommitInfo>> {
+    // Fields are separated by US and commits by RS, as bodies span lines.
     let output = git_command()
         .arg("log")
-        .arg("--pretty=%h %s")
+        .arg("--pretty=format:%h%x1f%H%x1f%s%x1f%an <%ae>%x1f%ar%x1f%b%x1e")
         .current_dir(repo_path)
         .output()?;
 
@@ -66,17 +113,29 @@ pub fn get_local_commits(repo_path: &Path) -> Result<Vec<CommitInfo>> {
     let stdout = String::from_utf8_lossy(&output.stdout);
     let mut commits = Vec::new();
 
-    for line in stdout.lines() {
-        let mut parts = line.splitn(2, ' ');
-        let hash = parts.next().unwrap_or("").to_string();
-        let message = parts.next().unwrap_or("").to_string();
+    for record in stdout.split('\x1e') {
+        let mut fields = record.trim_start_matches('\n').split('\x1f');
+        let hash = fields.next().unwrap_or("").to_string();
+        if hash.is_empty() {
+            continue;
+        }
+        let mut next = || fields.next().unwrap_or("").to_string();
+        let (full_hash, message, author, date) = (next(), next(), next(), next());
+        let body = next().trim_end().to_string();
 
         let is_on_remote = is_commit_on_remote(repo_path, &hash)?;
         commits.push(CommitInfo {
             hash,
+            full_hash,
             message,
             is_on_remote,
             is_fixup: false,
+            author,
+            date,
+            body,
+            is_signed: false,
+            files: Vec::new(),
+            push: PushStatus::default(),
         });
 
         if is_on_remote {
@@ -84,9 +143,325 @@ pub fn get_local_commits(repo_path: &Path) -> Result<Vec<CommitInfo>> {
         }
     }
 
+    let signed = signed_commits(repo_path, commits.len());
+    for commit in &mut commits {
+        commit.is_signed = signed.iter().any(|hash| hash.starts_with(&commit.hash));
+    }
+    let mut files = changed_files(repo_path, commits.len());
+    for commit in &mut commits {
+        commit.files = files.remove(&commit.full_hash).unwrap_or_default();
+    }
+    let diverged = has_diverged_from_upstream(repo_path);
+    for commit in &mut commits {
+        commit.push = if commit.is_on_remote {
+            PushStatus::Pushed
+        } else if diverged {
+            PushStatus::NeedsForce
+        } else {
+            PushStatus::WillPush
+        };
+    }
+
     Ok(commits)
 }
 
+/// Where the current branch stands against its upstream.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BranchStatus {
+    /// The branch name, or `None` on a detached `HEAD`.
+    pub branch: Option<String>,
+    /// E.g. `origin/main`, or `None` without an upstream.
+    pub upstream: Option<String>,
+    /// Commits on the branch and not on the upstream.
+    pub ahead: usize,
+    /// Commits on the upstream and not on the branch.
+    pub behind: usize,
+}
+
+/// A working tree of the repository, from `git worktree list`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Worktree {
+    pub path: PathBuf,
+    /// The commit checked out, empty in a bare repository.
+    pub head: String,
+    /// The branch checked out, or `None` when detached or bare.
+    pub branch: Option<String>,
+    pub bare: bool,
+    /// Whether `git worktree prune` would remove it, e.g. as its directory
+    /// is gone.
+    pub prunable: bool,
+    /// Whether `repo_path` is in it.
+    pub current: bool,
+}
+
+/// The main worktree and the linked ones, in the order git lists them.
+pub fn list_worktrees(repo_path: &Path) -> Result<Vec<Worktree>> {
+    let output = run_git_command(repo_path, &["worktree", "list", "--porcelain"])?;
+    let current = run_git_command(repo_path, &["rev-parse", "--show-toplevel"])
+        .ok()
+        .and_then(|top| PathBuf::from(top.trim()).canonicalize().ok());
+    let mut worktrees = Vec::new();
+    for block in output.split("\n\n") {
+        let mut lines = block.lines();
+        let Some(path) = lines.next().and_then(|line| line.strip_prefix("worktree ")) else {
+            continue;
+        };
+        let path = PathBuf::from(path);
+        let mut worktree = Worktree {
+            current: current.is_some() && path.canonicalize().ok() == current,
+            path,
+            head: String::new(),
+            branch: None,
+            bare: false,
+            prunable: false,
+        };
+        for line in lines {
+            if let Some(head) = line.strip_prefix("HEAD ") {
+                worktree.head = head.to_string();
+            } else if let Some(branch) = line.strip_prefix("branch ") {
+                let branch = branch.strip_prefix("refs/heads/").unwrap_or(branch);
+                worktree.branch = Some(branch.to_string());
+            } else if line == "bare" {
+                worktree.bare = true;
+            } else if line == "prunable" || line.starts_with("prunable ") {
+                worktree.prunable = true;
+            }
+        }
+        worktrees.push(worktree);
+    }
+    Ok(worktrees)
+}
+
+pub fn get_branch_status(repo_path: &Path) -> BranchStatus {
+    let branch = run_git_command(repo_path, &["symbolic-ref", "--short", "-q", "HEAD"])
+        .ok()
+        .map(|name| name.trim().to_string());
+    let upstream = run_git_command(
+        repo_path,
+        &[
+            "rev-parse",
+            "--abbrev-ref",
+            "--symbolic-full-name",
+            "@{upstream}",
+        ],
+    )
+    .ok()
+    .map(|name| name.trim().to_string());
+    let (ahead, behind) = upstream
+        .as_ref()
+        .and_then(|_| {
+            run_git_command(
+                repo_path,
+                &["rev-list", "--left-right", "--count", "HEAD...@{upstream}"],
+            )
+            .ok()
+        })
+        .and_then(|counts| {
+            let (ahead, behind) = counts.trim().split_once('\t')?;
+            Some((ahead.parse().ok()?, behind.parse().ok()?))
+        })
+        .unwrap_or((0, 0));
+    BranchStatus {
+        branch,
+        upstream,
+        ahead,
+        behind,
+    }
+}
+
+/// Whether the upstream of the branch has commits that `HEAD` does not, so
+/// it cannot be fast-forwarded. False without an upstream.
+fn has_diverged_from_upstream(repo_path: &Path) -> bool {
+    let has_upstream = git_command()
+        .args(["rev-parse", "--verify", "--quiet", "@{upstream}"])
+        .current_dir(repo_path)
+        .output()
+        .is_ok_and(|output| output.status.success());
+    has_upstream
+        && git_command()
+            .args(["merge-base", "--is-ancestor", "@{upstream}", "HEAD"])
+            .current_dir(repo_path)
+            .output()
+            .is_ok_and(|output| !output.status.success())
+}
+
+/// The paths changed by each of the newest `count` commits, by full hash.
+fn changed_files(repo_path: &Path, count: usize) -> HashMap<String, Vec<String>> {
+    let Ok(log) = run_git_command(
+        repo_path,
+        &[
+            "log",
+            "--format=%x1e%H",
+            "--name-only",
+            "-n",
+            &count.to_string(),
+        ],
+    ) else {
+        return HashMap::new();
+    };
+    log.split('\x1e')
+        .filter_map(|record| {
+            let mut lines = record.lines().filter(|line| !line.is_empty());
+            let hash = lines.next()?;
+            Some((hash.to_string(), lines.map(str::to_string).collect()))
+        })
+        .collect()
+}
+
+/// Full hashes of the signed ones among the newest `count` commits. A
+/// signature is only looked for in the headers, as verifying it would run
+/// gpg for every commit.
+fn signed_commits(repo_path: &Path, count: usize) -> HashSet<String> {
+    let Ok(log) = run_git_command(
+        repo_path,
+        &["log", "--pretty=raw", "-n", &count.to_string()],
+    ) else {
+        return HashSet::new();
+    };
+    let mut signed = HashSet::new();
+    let mut current: Option<&str> = None;
+    for line in log.lines() {
+        if let Some(hash) = line.strip_prefix("commit ") {
+            current = Some(hash);
+        } else if line.is_empty() {
+            // The headers end at the first blank line.
+            current = None;
+        } else if line.starts_with("gpgsig")
+            && let Some(hash) = current
+        {
+            signed.insert(hash.to_string());
+        }
+    }
+    signed
+}
+
+/// Whether commits are to be signed, as `commit.gpgsign` asks. It covers
+/// GPG and SSH keys alike, as `gpg.format` picks between them.
+pub fn is_signing_enabled(repo_path: &Path) -> bool {
+    run_git_command(repo_path, &["config", "--bool", "commit.gpgsign"])
+        .is_ok_and(|value| value.trim() == "true")
+}
+
+/// `-S` when commits are to be signed, so no commit made here is left
+/// unsigned.
+fn signing_args(repo_path: &Path) -> &'static [&'static str] {
+    if is_signing_enabled(repo_path) {
+        &["-S"]
+    } else {
+        &[]
+    }
+}
+
+/// The error of a `git commit` that failed as the signature could not be
+/// made, on one line for the status line.
+fn signing_error(repo_path: &Path, stderr: &[u8]) -> Option<anyhow::Error> {
+    let stderr = String::from_utf8_lossy(stderr);
+    let is_signing_error = stderr.contains("sign") || stderr.contains("failed to write commit");
+    (is_signing_error && is_signing_enabled(repo_path)).then(|| {
+        anyhow::anyhow!(
+            "Signing failed (commit.gpgsign is set): {}",
+            stderr.trim().lines().collect::<Vec<_>>().join(" ")
+        )
+    })
+}
+
+/// Top-level directories of the repository containing `start` and of every
+/// repository enclosing it, innermost first. Empty outside of a repository.
+pub fn get_enclosing_repos(start: &Path) -> Vec<PathBuf> {
+    let mut repos = Vec::new();
+    let mut dir = start.to_path_buf();
+    loop {
+        let output = match git_command()
+            .arg("rev-parse")
+            .arg("--show-toplevel")
+            .current_dir(&dir)
+            .output()
+        {
+            Ok(output) if output.status.success() => output,
+            _ => break,
+        };
+        let toplevel = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+        if repos.contains(&toplevel) {
+            break;
+        }
+        let Some(parent) = toplevel.parent() else {
+            repos.push(toplevel);
+            break;
+        };
+        dir = parent.to_path_buf();
+        repos.push(toplevel);
+    }
+    repos
+}
+
+/// One commit of the history screen.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryCommit {
+    pub hash: String,
+    pub date: String,
+    pub author: String,
+    pub message: String,
+}
+
+/// Every commit reachable from HEAD, newest first. Unlike
+/// [`get_local_commits`] this does not stop at pushed commits.
+pub fn get_commit_history(repo_path: &Path) -> Result<Vec<HistoryCommit>> {
+    let output = git_command()
+        .arg("log")
+        .arg("--date=short")
+        .arg("--pretty=%h%x1f%ad%x1f%an%x1f%s")
+        .current_dir(repo_path)
+        .output()?;
+
+    if !output.status.success() {
+        return Ok(Vec::new());
+    }
+
+    Ok(parse_history(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Reads `%h%x1f%ad%x1f%an%x1f%s` lines.
+fn parse_history(log: &str) -> Vec<HistoryCommit> {
+    log.lines()
+        .map(|line| {
+            let mut parts = line.splitn(4, '\x1f');
+            let mut next = || parts.next().unwrap_or("").to_string();
+            HistoryCommit {
+                hash: next(),
+                date: next(),
+                author: next(),
+                message: next(),
+            }
+        })
+        .collect()
+}
+
+/// The newest `count` commits that left `file_name` with content, so
+/// commits deleting it are skipped.
+pub fn get_file_history(
+    repo_path: &Path,
+    file_name: &str,
+    count: usize,
+) -> Result<Vec<HistoryCommit>> {
+    let output = git_command()
+        .arg("log")
+        .arg(format!("-n{count}"))
+        .arg("--diff-filter=ACMRT")
+        .arg("--date=short")
+        .arg("--pretty=%h%x1f%ad%x1f%an%x1f%s")
+        .arg("--")
+        .arg(file_name)
+        .current_dir(repo_path)
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git log failed. Stderr: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(parse_history(&String::from_utf8_lossy(&output.stdout)))
+}
+
 fn calc_line_numbers(hunk: &Hunk) -> Vec<(usize, usize)> {
     let mut line_numbers: Vec<(usize, usize)> = Vec::new();
     let mut old_line_counter: i32 = hunk.old_start as i32 - 1;
@@ -112,16 +487,20 @@ fn calc_line_numbers(hunk: &Hunk) -> Vec<(usize, usize)> {
     line_numbers
 }
 
-fn parse_diff(diff_str: &str) -> Vec<FileDiff> {
+pub fn parse_diff(diff_str: &str) -> Vec<FileDiff> {
     let mut files = Vec::new();
     let mut current_file: Option<FileDiff> = None;
     let mut current_hunk: Option<Hunk> = None;
     let mut current_file_lines: Vec<String> = Vec::new();
     let mut header_lines: Vec<String> = Vec::new();
+    let mut binary_header = BinaryDiff::default();
 
     let diff_line_re = Regex::new(r#"^diff --git a/(.+) b/(.+)"#).unwrap();
 
     for line in diff_str.lines() {
+        if current_hunk.is_none() {
+            parse_binary_header(line, &mut binary_header);
+        }
         if let Some(caps) = diff_line_re.captures(line) {
             if let Some(mut file) = current_file.take() {
                 if let Some(mut hunk) = current_hunk.take() {
@@ -151,11 +530,17 @@ fn parse_diff(diff_str: &str) -> Vec<FileDiff> {
                 hunks: Vec::new(),
                 lines: Vec::new(), // Will be filled in later
                 status: FileStatus::Modified,
+                binary: None,
             });
+            binary_header = BinaryDiff::default();
 
             if files.is_empty() {
                 current_file_lines.append(&mut header_lines);
             }
+        } else if line.starts_with("Binary files ") && line.ends_with(" differ") {
+            if let Some(file) = current_file.as_mut() {
+                file.binary = Some(binary_header.clone());
+            }
         } else if line.starts_with("new file mode") {
             if let Some(file) = current_file.as_mut() {
                 file.status = FileStatus::Added;
@@ -169,11 +554,11 @@ fn parse_diff(diff_str: &str) -> Vec<FileDiff> {
                 file.status = FileStatus::Renamed;
             }
         } else if line.starts_with("@@ ") {
-            if let Some(mut hunk) = current_hunk.take() {
-                if let Some(file) = current_file.as_mut() {
-                    hunk.line_numbers = calc_line_numbers(&hunk);
-                    file.hunks.push(hunk);
-                }
+            if let Some(mut hunk) = current_hunk.take()
+                && let Some(file) = current_file.as_mut()
+            {
+                hunk.line_numbers = calc_line_numbers(&hunk);
+                file.hunks.push(hunk);
             }
 
             let parts: Vec<&str> = line.split(' ').collect();
@@ -220,22 +605,187 @@ fn parse_diff(diff_str: &str) -> Vec<FileDiff> {
             hunks: Vec::new(),
             lines: header_lines,
             status: FileStatus::Modified,
+            binary: None,
         });
     }
 
     files
 }
 
+/// Takes the blobs and modes from an extended header line of a file diff.
+fn parse_binary_header(line: &str, header: &mut BinaryDiff) {
+    if let Some(rest) = line.strip_prefix("index ") {
+        let (blobs, mode) = rest.split_once(' ').unwrap_or((rest, ""));
+        if let Some((old, new)) = blobs.split_once("..") {
+            header.old_blob = old.to_string();
+            header.new_blob = new.to_string();
+        }
+        if !mode.is_empty() {
+            header.old_mode = Some(mode.to_string());
+            header.new_mode = Some(mode.to_string());
+        }
+    } else if let Some(mode) = line
+        .strip_prefix("old mode ")
+        .or_else(|| line.strip_prefix("deleted file mode "))
+    {
+        header.old_mode = Some(mode.to_string());
+    } else if let Some(mode) = line
+        .strip_prefix("new mode ")
+        .or_else(|| line.strip_prefix("new file mode "))
+    {
+        header.new_mode = Some(mode.to_string());
+    }
+}
+
+/// Looks up the sizes of both sides of the binary files of `files`. The new
+/// side of an unstaged change is not in the object database, so the file
+/// in the working tree is measured instead.
+pub fn fill_binary_sizes(repo_path: &Path, files: &mut [FileDiff]) {
+    let blob_size = |blob: &str| -> Option<u64> {
+        if blob.is_empty() || blob.chars().all(|c| c == '0') {
+            return None;
+        }
+        run_git_command(repo_path, &["cat-file", "-s", blob])
+            .ok()
+            .and_then(|size| size.trim().parse().ok())
+    };
+    for file in files {
+        let file_name = file.file_name.clone();
+        let Some(binary) = file.binary.as_mut() else {
+            continue;
+        };
+        binary.old_size = blob_size(&binary.old_blob);
+        binary.new_size = blob_size(&binary.new_blob).or_else(|| {
+            (!binary.new_blob.chars().all(|c| c == '0'))
+                .then(|| std::fs::metadata(repo_path.join(&file_name)).ok())
+                .flatten()
+                .map(|metadata| metadata.len())
+        });
+    }
+}
+
+/// Puts the lines of the new file that are not in a hunk between the hunks
+/// of each file, as context lines, so the diff shows the whole file. The
+/// hunks are kept as they are and move with their lines, so they stage the
+/// same. `new_content` reads the new side of a file by name; files it has
+/// nothing for, binary files and deletions are left alone.
+pub fn expand_to_full_file(files: &mut [FileDiff], new_content: impl Fn(&str) -> Option<String>) {
+    for file in files {
+        if file.hunks.is_empty() || file.binary.is_some() || file.status == FileStatus::Deleted {
+            continue;
+        }
+        let Some(content) = new_content(&file.file_name) else {
+            continue;
+        };
+        let new_lines: Vec<&str> = content.lines().collect();
+        let context = |from: usize, to: usize| {
+            new_lines
+                .get(from.min(new_lines.len())..to.min(new_lines.len()))
+                .unwrap_or_default()
+                .iter()
+                .map(|line| format!(" {line}"))
+                .collect::<Vec<_>>()
+        };
+
+        let mut lines: Vec<String> = file.lines[..file.hunks[0].start_line].to_vec();
+        // The next line of the new file to show, counted from 0.
+        let mut next = 0;
+        for hunk in &mut file.hunks {
+            let new_count = hunk
+                .lines
+                .iter()
+                .skip(1)
+                .filter(|line| line.starts_with(' ') || line.starts_with('+'))
+                .count();
+            // A hunk that only removes lines comes after its `new_start`.
+            let first = if new_count == 0 {
+                hunk.new_start
+            } else {
+                hunk.new_start.saturating_sub(1)
+            };
+            lines.extend(context(next, first));
+            hunk.start_line = lines.len();
+            lines.extend(hunk.lines.iter().cloned());
+            next = first + new_count;
+        }
+        lines.extend(context(next, new_lines.len()));
+        file.lines = lines;
+    }
+}
+
+/// Header lines that only repeat what the file list already shows.
+const BOILERPLATE_HEADERS: [&str; 6] = [
+    "diff --git ",
+    "index ",
+    "--- ",
+    "+++ ",
+    "new file mode ",
+    "deleted file mode ",
+];
+
+/// Removes the boilerplate header lines above the first hunk of each file
+/// and moves the hunks up with them, so `start_line` still points into
+/// `lines`. Mode changes, renames and binary notices are kept, since a
+/// file without hunks has nothing else to show.
+pub fn strip_patch_headers(files: &mut [FileDiff]) {
+    for file in files {
+        let header_end = file
+            .hunks
+            .first()
+            .map_or(file.lines.len(), |hunk| hunk.start_line);
+        let mut index = 0;
+        file.lines.retain(|line| {
+            index += 1;
+            index > header_end
+                || !BOILERPLATE_HEADERS
+                    .iter()
+                    .any(|prefix| line.starts_with(prefix))
+        });
+        let removed = index - file.lines.len();
+        for hunk in &mut file.hunks {
+            hunk.start_line -= removed;
+        }
+    }
+}
+
+/// Lines of context around the changes of a diff when git's
+/// `diff.context` is not set.
+pub const DEFAULT_DIFF_CONTEXT: usize = 3;
+
+/// The `-U<n>` argument asking for `context` lines around each change, none
+/// to leave it to git.
+fn context_arg(context: Option<usize>) -> Option<String> {
+    context.map(|lines| format!("-U{lines}"))
+}
+
+/// The lines of context git shows around changes here: `diff.context`, or
+/// [`DEFAULT_DIFF_CONTEXT`].
+pub fn get_diff_context(repo_path: &Path) -> usize {
+    run_git_command(repo_path, &["config", "--get", "diff.context"])
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(DEFAULT_DIFF_CONTEXT)
+}
+
 pub fn get_diff(repo_path: PathBuf) -> Vec<FileDiff> {
+    get_diff_with_context(repo_path, None)
+}
+
+/// [`get_diff`] with `context` lines around each change.
+pub fn get_diff_with_context(repo_path: PathBuf, context: Option<usize>) -> Vec<FileDiff> {
     let output = git_command()
         .arg("diff")
         .arg("--staged")
+        .arg("--textconv")
+        .args(context_arg(context))
         .current_dir(&repo_path)
         .output()
         .expect("Failed to execute git diff");
 
     let diff_str = String::from_utf8_lossy(&output.stdout);
-    parse_diff(&diff_str)
+    let mut files = parse_diff(&diff_str);
+    fill_binary_sizes(&repo_path, &mut files);
+    files
 }
 
 pub fn get_commit_diff(repo_path: &Path, hash: &str) -> Result<Vec<FileDiff>> {
@@ -243,6 +793,7 @@ pub fn get_commit_diff(repo_path: &Path, hash: &str) -> Result<Vec<FileDiff>> {
         .arg("show")
         .arg("--stat")
         .arg("--patch")
+        .arg("--textconv")
         .arg(hash)
         .current_dir(repo_path)
         .output()?;
@@ -251,6 +802,234 @@ pub fn get_commit_diff(repo_path: &Path, hash: &str) -> Result<Vec<FileDiff>> {
     Ok(parse_diff(&diff_str))
 }
 
+/// [`get_commit_diff`], followed by [`get_fixup_preview`] for `fixup!`
+/// commits, as shown in the commit log.
+pub fn get_commit_diff_with_fixup_preview(repo_path: &Path, hash: &str) -> Result<Vec<FileDiff>> {
+    let mut files = get_commit_diff(repo_path, hash)?;
+    if let Some(preview) = get_fixup_preview(repo_path, hash)? {
+        files.extend(preview);
+    }
+    Ok(files)
+}
+
+/// Finds the commit a `fixup!` commit will be squashed into by
+/// `rebase --autosquash`: the nearest older commit whose subject matches,
+/// or whose hash starts with the text after `fixup! `. Returns the
+/// abbreviated hash and the subject.
+pub fn find_fixup_target(
+    repo_path: &Path,
+    fixup_hash: &str,
+    fixup_subject: &str,
+) -> Result<Option<(String, String)>> {
+    let mut target = fixup_subject;
+    while let Some(rest) = target.strip_prefix("fixup! ") {
+        target = rest;
+    }
+    if target == fixup_subject {
+        return Ok(None);
+    }
+
+    let output = git_command()
+        .arg("log")
+        .arg("--pretty=%h%x1f%H%x1f%s")
+        .arg(format!("{fixup_hash}^"))
+        .current_dir(repo_path)
+        .output()?;
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let commits: Vec<(&str, &str, &str)> = stdout
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '\x1f');
+            Some((parts.next()?, parts.next()?, parts.next()?))
+        })
+        .collect();
+
+    let is_hash = target.len() >= 4 && target.chars().all(|c| c.is_ascii_hexdigit());
+    let found = commits
+        .iter()
+        .find(|(_, _, subject)| *subject == target)
+        .or_else(|| {
+            commits
+                .iter()
+                .find(|(_, full_hash, _)| is_hash && full_hash.starts_with(target))
+        })
+        .or_else(|| {
+            commits
+                .iter()
+                .find(|(_, _, subject)| subject.starts_with(target))
+        });
+    Ok(found.map(|(hash, _, subject)| (hash.to_string(), subject.to_string())))
+}
+
+/// For a `fixup!` commit, a preview of its target commit as it will look
+/// after `rebase --autosquash`: the fixup applied on top of the target,
+/// diffed against the target's parent. `None` for other commits.
+pub fn get_fixup_preview(repo_path: &Path, hash: &str) -> Result<Option<Vec<FileDiff>>> {
+    let subject_output = git_command()
+        .arg("log")
+        .arg("-1")
+        .arg("--pretty=%s")
+        .arg(hash)
+        .current_dir(repo_path)
+        .output()?;
+    let subject = String::from_utf8_lossy(&subject_output.stdout)
+        .trim()
+        .to_string();
+    if !subject.starts_with("fixup! ") {
+        return Ok(None);
+    }
+
+    let banner = |text: &str| {
+        Some(vec![FileDiff {
+            file_name: String::new(),
+            old_file_name: String::new(),
+            hunks: Vec::new(),
+            lines: vec![String::new(), text.to_string()],
+            status: FileStatus::Modified,
+            binary: None,
+        }])
+    };
+
+    let Some((target_hash, target_subject)) = find_fixup_target(repo_path, hash, &subject)? else {
+        return Ok(banner(
+            "Fixup target not found: no earlier commit matches this subject",
+        ));
+    };
+    let title = format!("Squashed into {target_hash} {target_subject}:");
+
+    let Some(tree) = squash_tree(repo_path, &target_hash, hash)? else {
+        return Ok(banner(&format!(
+            "The fixup does not apply cleanly to {target_hash} {target_subject}"
+        )));
+    };
+    let parent_output = git_command()
+        .arg("rev-parse")
+        .arg("--verify")
+        .arg("--quiet")
+        .arg(format!("{target_hash}^"))
+        .current_dir(repo_path)
+        .output()?;
+    let base = if parent_output.status.success() {
+        String::from_utf8_lossy(&parent_output.stdout)
+            .trim()
+            .to_string()
+    } else {
+        empty_tree(repo_path)?
+    };
+
+    let diff_output = git_command()
+        .arg("diff")
+        .arg("--stat")
+        .arg("--patch")
+        .arg(&base)
+        .arg(&tree)
+        .current_dir(repo_path)
+        .output()?;
+    let diff = String::from_utf8_lossy(&diff_output.stdout);
+    Ok(Some(parse_diff(&format!("\n{title}\n\n{diff}"))))
+}
+
+/// Tree of `target` with the changes of `fixup` applied, built in a
+/// temporary index. `None` if the changes do not apply.
+fn squash_tree(repo_path: &Path, target: &str, fixup: &str) -> Result<Option<String>> {
+    use std::io::Write;
+
+    let index_path = git_path(repo_path, "fixup_preview_index")?;
+    let with_index = || {
+        let mut command = git_command();
+        command
+            .env("GIT_INDEX_FILE", &index_path)
+            .current_dir(repo_path);
+        command
+    };
+
+    let result = (|| {
+        if !with_index()
+            .arg("read-tree")
+            .arg(target)
+            .output()?
+            .status
+            .success()
+        {
+            return Ok(None);
+        }
+        let patch = git_command()
+            .arg("diff")
+            .arg("--binary")
+            .arg("--no-textconv")
+            .arg(format!("{fixup}^"))
+            .arg(fixup)
+            .current_dir(repo_path)
+            .output()?
+            .stdout;
+        let mut apply = with_index()
+            .arg("apply")
+            .arg("--cached")
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .spawn()?;
+        if let Some(mut stdin) = apply.stdin.take() {
+            stdin.write_all(&patch)?;
+        }
+        if !apply.wait()?.success() {
+            return Ok(None);
+        }
+        let tree = with_index().arg("write-tree").output()?;
+        Ok(tree
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&tree.stdout).trim().to_string()))
+    })();
+
+    let _ = std::fs::remove_file(&index_path);
+    result
+}
+
+fn empty_tree(repo_path: &Path) -> Result<String> {
+    let output = git_command()
+        .arg("mktree")
+        .stdin(std::process::Stdio::null())
+        .current_dir(repo_path)
+        .output()?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// A commit diff rendered with `--word-diff`.
+#[derive(Debug, Clone, Default)]
+pub struct CommitWordDiff {
+    /// The files, with the plain `--word-diff` text of every line.
+    pub files: Vec<FileDiff>,
+    /// Word spans of every line of `files`, in display order. `None` for
+    /// header lines.
+    pub spans: Vec<Option<Vec<(String, WordChange)>>>,
+}
+
+pub fn get_commit_word_diff(repo_path: &Path, hash: &str) -> Result<CommitWordDiff> {
+    let output = git_command()
+        .arg("show")
+        .arg("--stat")
+        .arg("--patch")
+        .arg("--word-diff=porcelain")
+        .arg(hash)
+        .current_dir(repo_path)
+        .output()?;
+
+    let lines = parse_porcelain(&String::from_utf8_lossy(&output.stdout));
+    let text = lines
+        .iter()
+        .map(|line| line.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(CommitWordDiff {
+        files: parse_diff(&text),
+        spans: lines.into_iter().map(|line| line.spans).collect(),
+    })
+}
+
 pub fn has_unstaged_changes(repo_path: &Path) -> Result<bool> {
     let output = git_command()
         .arg("status")
@@ -279,13 +1058,65 @@ pub fn has_unstaged_changes(repo_path: &Path) -> Result<bool> {
     Ok(false)
 }
 
-pub fn commit(repo_path: &Path, message: &str) -> Result<()> {
-    git_command()
-        .arg("commit")
-        .arg("-m")
-        .arg(message)
-        .current_dir(repo_path)
-        .output()?;
+/// A commit refused by its `pre-commit` or `commit-msg` hook.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HookFailure {
+    /// The commit hooks installed, one of which failed, e.g. `pre-commit`.
+    pub hooks: Vec<String>,
+    /// What git and the hooks printed, which all goes to stderr.
+    pub output: String,
+}
+
+impl std::fmt::Display for HookFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "The {} hook failed", self.hooks.join(" or "))
+    }
+}
+
+impl std::error::Error for HookFailure {}
+
+/// The hooks `git commit` runs that can refuse it, if they are installed.
+/// `core.hooksPath` is taken into account.
+fn commit_hooks(repo_path: &Path) -> Vec<String> {
+    ["pre-commit", "commit-msg"]
+        .into_iter()
+        .filter(|hook| {
+            git_path(repo_path, &format!("hooks/{hook}")).is_ok_and(|path| is_executable(&path))
+        })
+        .map(str::to_string)
+        .collect()
+}
+
+/// Commits the staged changes. Unless `verify` is false, which passes
+/// `--no-verify`, a failing hook is reported as a [`HookFailure`].
+pub fn commit(repo_path: &Path, message: &str, verify: bool) -> Result<()> {
+    let mut cmd = git_command();
+    cmd.arg("commit").args(signing_args(repo_path));
+    if !verify {
+        cmd.arg("--no-verify");
+    }
+    let output = cmd.arg("-m").arg(message).current_dir(repo_path).output()?;
+    if !output.status.success() {
+        if let Some(e) = signing_error(repo_path, &output.stderr) {
+            return Err(e);
+        }
+        let hooks = if verify {
+            commit_hooks(repo_path)
+        } else {
+            Vec::new()
+        };
+        if !hooks.is_empty() {
+            return Err(HookFailure {
+                hooks,
+                output: String::from_utf8_lossy(&output.stderr).into_owned(),
+            }
+            .into());
+        }
+        anyhow::bail!(
+            "git commit failed. Stderr: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
     Ok(())
 }
 
@@ -294,11 +1125,15 @@ pub fn commit_amend_with_message(repo_path: &Path, message: &str) -> Result<()>
         .arg("commit")
         .arg("--amend")
         .arg("--allow-empty")
+        .args(signing_args(repo_path))
         .arg("-m")
         .arg(message)
         .current_dir(repo_path)
         .output()?;
     if !output.status.success() {
+        if let Some(e) = signing_error(repo_path, &output.stderr) {
+            return Err(e);
+        }
         anyhow::bail!(
             "Failed to amend commit with message. Stderr: {}",
             String::from_utf8_lossy(&output.stderr)
@@ -313,9 +1148,13 @@ pub fn commit_amend_no_edit(repo_path: &Path) -> Result<()> {
         .arg("--amend")
         .arg("--no-edit")
         .arg("--allow-empty")
+        .args(signing_args(repo_path))
         .current_dir(repo_path)
         .output()?;
     if !output.status.success() {
+        if let Some(e) = signing_error(repo_path, &output.stderr) {
+            return Err(e);
+        }
         anyhow::bail!(
             "Failed to commit --amend --no-edit. Stderr: {}",
             String::from_utf8_lossy(&output.stderr)
@@ -347,22 +1186,46 @@ pub fn amend_commit_with_staged_changes(
         .output()?;
     let original_message = String::from_utf8_lossy(&original_message_output.stdout);
 
-    // 2. Create a fixup! commit for the staged changes
+    // 2. Squash the staged changes into it
+    fixup_staged_changes(repo_path, target_hash)?;
+
+    // 3. If the message has changed, amend the now-squashed commit
+    if message.trim() != original_message.trim() {
+        let commits_after = get_local_commits(repo_path)?;
+        let rewritten_commit = commits_after
+            .get(target_index)
+            .ok_or_else(|| anyhow::anyhow!("Rewritten commit not found after amend rebase"))?;
+
+        reword_commit(repo_path, &rewritten_commit.hash, message)?;
+    }
+
+    Ok(())
+}
+
+/// Commits the staged changes as a `fixup!` of `target_hash` and squashes
+/// them into it with `rebase --autosquash`. Unstaged changes are stashed
+/// around the rebase.
+pub fn fixup_staged_changes(repo_path: &Path, target_hash: &str) -> Result<()> {
+    // 1. Create a fixup! commit for the staged changes
     let commit_output = git_command()
         .arg("commit")
+        .args(signing_args(repo_path))
         .arg("--fixup")
         .arg(target_hash)
         .current_dir(repo_path)
         .output()?;
 
     if !commit_output.status.success() {
+        if let Some(e) = signing_error(repo_path, &commit_output.stderr) {
+            return Err(e);
+        }
         anyhow::bail!(
             "git commit --fixup failed. This usually means there are no staged changes. Stderr: {}",
             String::from_utf8_lossy(&commit_output.stderr)
         );
     }
 
-    // 3. Rebase with autosquash
+    // 2. Rebase with autosquash
     let parent_hash_output = git_command()
         .arg("rev-parse")
         .arg(format!("{target_hash}^"))
@@ -398,16 +1261,6 @@ pub fn amend_commit_with_staged_changes(
         anyhow::bail!("git rebase for fixup failed. Aborting.");
     }
 
-    // 4. If the message has changed, amend the now-squashed commit
-    if message.trim() != original_message.trim() {
-        let commits_after = get_local_commits(repo_path)?;
-        let rewritten_commit = commits_after
-            .get(target_index)
-            .ok_or_else(|| anyhow::anyhow!("Rewritten commit not found after amend rebase"))?;
-
-        reword_commit(repo_path, &rewritten_commit.hash, message)?;
-    }
-
     Ok(())
 }
 
@@ -416,11 +1269,11 @@ pub fn reword_commit(repo_path: &Path, commit_hash: &str, message: &str) -> Resu
     use std::os::unix::fs::PermissionsExt;
 
     // Write the new message to a temporary file
-    let temp_message_path = repo_path.join(".git/COMMIT_EDITMSG_TEMP");
+    let temp_message_path = git_path(repo_path, "COMMIT_EDITMSG_TEMP")?;
     std::fs::write(&temp_message_path, message)?;
 
     // Create an editor script that will overwrite the real commit message file
-    let editor_script_path = repo_path.join(".git/reword_editor.sh");
+    let editor_script_path = git_path(repo_path, "reword_editor.sh")?;
     let script_content = format!(
         "#!/bin/sh\ncp '{}' \"$1\"",
         temp_message_path.to_str().unwrap()
@@ -475,15 +1328,424 @@ pub fn reword_commit(repo_path: &Path, commit_hash: &str, message: &str) -> Resu
     Ok(())
 }
 
-pub fn fixup_and_rebase_autosquash(repo_path: &Path, fixup_commit_hash: &str) -> Result<()> {
-    // 1. Create a fixup! commit
-    let commit_output = git_command()
-        .arg("commit")
-        .arg("--no-edit")
-        .arg("--fixup")
-        .arg(fixup_commit_hash)
-        .current_dir(repo_path)
-        .output()?;
+/// How [`amend_commit_metadata`] changes the author of a commit.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuthorChange {
+    /// `Name <email>`, keeping the author date.
+    Set(String),
+    /// The configured identity, dated now, like `--reset-author`.
+    Reset,
+    /// The same author, dated now.
+    ResetDate,
+}
+
+/// Rewrites the author or the author date of the local commit `commit_hash` and replays the
+/// newer commits on top. The message and the changes are kept.
+pub fn amend_commit_metadata(
+    repo_path: &Path,
+    commit_hash: &str,
+    change: &AuthorChange,
+) -> Result<()> {
+    #[cfg(unix)]
+    use std::os::unix::fs::PermissionsExt;
+
+    let commit = run_git_command(
+        repo_path,
+        &[
+            "rev-parse",
+            "--verify",
+            &format!("{commit_hash}^{{commit}}"),
+        ],
+    )?
+    .trim()
+    .to_string();
+    let parent = run_git_command(
+        repo_path,
+        &["rev-parse", "--verify", "--quiet", &format!("{commit}^")],
+    )
+    .ok()
+    .map(|parent| parent.trim().to_string());
+    let range = match &parent {
+        Some(parent) => format!("{parent}..HEAD"),
+        None => "HEAD".to_string(),
+    };
+    let commits = run_git_command(repo_path, &["rev-list", "--reverse", "--no-merges", &range])?;
+    if !commits.lines().any(|line| line == commit) {
+        anyhow::bail!("{commit_hash} is not on the current branch");
+    }
+
+    let amend = match change {
+        AuthorChange::Set(_) => "--author=\"$GIT_FULL_COMMIT_AUTHOR\"",
+        AuthorChange::Reset => "--reset-author",
+        AuthorChange::ResetDate => "--date=now",
+    };
+    let mut todo = String::new();
+    for line in commits.lines() {
+        todo.push_str(&format!("pick {line}\n"));
+        if line == commit {
+            todo.push_str(&format!(
+                "exec git commit --amend --no-edit --allow-empty --quiet {amend}\n"
+            ));
+        }
+    }
+    let todo_path = git_path(repo_path, "author_todo")?;
+    std::fs::write(&todo_path, todo)?;
+    let editor_script_path = git_path(repo_path, "author_editor.sh")?;
+    std::fs::write(
+        &editor_script_path,
+        format!("#!/bin/sh\ncp '{}' \"$1\"", todo_path.display()),
+    )?;
+    #[cfg(unix)]
+    std::fs::set_permissions(&editor_script_path, std::fs::Permissions::from_mode(0o755))?;
+
+    with_changes_set_aside(repo_path, "git-full-commit: author change", || {
+        let mut rebase_cmd = git_command();
+        rebase_cmd.env("GIT_SEQUENCE_EDITOR", &editor_script_path);
+        rebase_cmd.env("GIT_EDITOR", "true");
+        if let AuthorChange::Set(author) = change {
+            rebase_cmd.env("GIT_FULL_COMMIT_AUTHOR", author);
+        }
+        rebase_cmd.arg("rebase").arg("-i");
+        match &parent {
+            Some(parent) => rebase_cmd.arg(parent),
+            None => rebase_cmd.arg("--root"),
+        };
+        let rebase_output = rebase_cmd.current_dir(repo_path).output();
+
+        let _ = std::fs::remove_file(&todo_path);
+        let _ = std::fs::remove_file(&editor_script_path);
+
+        match rebase_output {
+            Ok(output) if output.status.success() => Ok(()),
+            Ok(output) => {
+                let _ = run_git_command(repo_path, &["rebase", "--abort"]);
+                Err(anyhow::anyhow!(
+                    "git rebase failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ))
+            }
+            Err(e) => Err(e.into()),
+        }
+    })
+}
+
+/// What to do with a commit when running a rebase plan.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RebaseAction {
+    Pick,
+    Reword,
+    Squash,
+    Fixup,
+    Drop,
+}
+
+impl RebaseAction {
+    pub fn label(self) -> &'static str {
+        match self {
+            RebaseAction::Pick => "pick",
+            RebaseAction::Reword => "reword",
+            RebaseAction::Squash => "squash",
+            RebaseAction::Fixup => "fixup",
+            RebaseAction::Drop => "drop",
+        }
+    }
+
+    /// Whether the commit is melded into the commit before it.
+    pub fn melds(self) -> bool {
+        matches!(self, RebaseAction::Squash | RebaseAction::Fixup)
+    }
+}
+
+/// One line of a rebase todo list.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RebaseStep {
+    pub hash: String,
+    pub message: String,
+    pub action: RebaseAction,
+    /// The message to use for [`RebaseAction::Reword`].
+    pub new_message: Option<String>,
+}
+
+/// Formats `steps` like a `git rebase -i` todo list, one `<action> <hash>
+/// <message>` line per step, to show what a history rewrite will do.
+/// Reworded steps show their new message.
+pub fn format_todo(steps: &[RebaseStep]) -> String {
+    steps
+        .iter()
+        .map(|step| {
+            let message = step.new_message.as_deref().unwrap_or(&step.message);
+            format!("{} {} {message}\n", step.action.label(), step.hash)
+        })
+        .collect()
+}
+
+/// Runs `git rebase -i` with `steps` as the todo list, oldest commit first,
+/// without opening an editor. Squashed commits keep the combined message.
+/// The rebase is aborted if anything fails.
+pub fn run_rebase_plan(repo_path: &Path, steps: &[RebaseStep]) -> Result<()> {
+    let Some(rebase_output) = start_rebase_plan(repo_path, steps, false)? else {
+        return Ok(());
+    };
+
+    if !rebase_output.status.success() {
+        git_command()
+            .arg("rebase")
+            .arg("--abort")
+            .current_dir(repo_path)
+            .output()?;
+        anyhow::bail!(
+            "git rebase failed. Stderr: {}. Aborting.",
+            String::from_utf8_lossy(&rebase_output.stderr).trim()
+        );
+    }
+
+    Ok(())
+}
+
+/// Where a rebase plan stops on a conflict.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RebaseConflict {
+    pub hash: String,
+    pub message: String,
+    pub files: Vec<String>,
+}
+
+/// Runs `steps` in a temporary worktree on a detached HEAD, to tell whether
+/// the plan conflicts before it is run on the branch. Nothing is signed, so
+/// no passphrase is asked for. The worktree is removed afterwards.
+pub fn predict_rebase_conflict(
+    repo_path: &Path,
+    steps: &[RebaseStep],
+) -> Result<Option<RebaseConflict>> {
+    static DRY_RUNS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let worktree = std::env::temp_dir().join(format!(
+        "git-full-commit-dry-run-{}-{}",
+        std::process::id(),
+        DRY_RUNS.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    ));
+    let worktree_arg = worktree.to_string_lossy().to_string();
+    run_git_command(
+        repo_path,
+        &[
+            "worktree",
+            "add",
+            "--detach",
+            "--quiet",
+            &worktree_arg,
+            "HEAD",
+        ],
+    )?;
+
+    let conflict = (|| -> Result<Option<RebaseConflict>> {
+        let Some(output) = start_rebase_plan(&worktree, steps, true)? else {
+            return Ok(None);
+        };
+        if output.status.success() {
+            return Ok(None);
+        }
+        let Ok(stopped) = run_git_command(&worktree, &["rev-parse", "--short", "REBASE_HEAD"])
+        else {
+            anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+        };
+        let hash = stopped.trim().to_string();
+        let message = run_git_command(&worktree, &["log", "-1", "--format=%s", &hash])?
+            .trim()
+            .to_string();
+        let files = run_git_command(&worktree, &["diff", "--name-only", "--diff-filter=U"])?
+            .lines()
+            .map(str::to_string)
+            .collect();
+        Ok(Some(RebaseConflict {
+            hash,
+            message,
+            files,
+        }))
+    })();
+
+    let _ = run_git_command(&worktree, &["rebase", "--abort"]);
+    let _ = run_git_command(repo_path, &["worktree", "remove", "--force", &worktree_arg]);
+    let _ = std::fs::remove_dir_all(&worktree);
+    let _ = run_git_command(repo_path, &["worktree", "prune"]);
+    conflict
+}
+
+/// Writes every staged file into a new temporary directory, to look at or
+/// test exactly what the next commit holds, and returns the directory. It
+/// is left in place.
+pub fn export_staged_tree(repo_path: &Path) -> Result<PathBuf> {
+    static EXPORTS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "git-full-commit-staged-{}-{}",
+        std::process::id(),
+        EXPORTS.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    ));
+    std::fs::create_dir_all(&dir)?;
+    let prefix = format!("{}/", dir.to_string_lossy());
+    run_git_command(
+        repo_path,
+        &["checkout-index", "-a", "-f", "--prefix", &prefix],
+    )?;
+    Ok(dir)
+}
+
+/// Runs `command` with `sh -c` in a temporary worktree holding exactly what
+/// is staged, to tell whether the commit builds on its own, without the
+/// unstaged changes. Returns what it printed if it failed. The worktree is
+/// removed afterwards.
+pub fn check_staged_tree(repo_path: &Path, command: &str) -> Result<Option<String>> {
+    check_tree(repo_path, &write_tree(repo_path)?, command)
+}
+
+/// Runs `command` like [`check_staged_tree`], on the tree `tree` written
+/// before.
+pub fn check_tree(repo_path: &Path, tree: &str, command: &str) -> Result<Option<String>> {
+    static CHECKS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+    let worktree = std::env::temp_dir().join(format!(
+        "git-full-commit-check-{}-{}",
+        std::process::id(),
+        CHECKS.fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+    ));
+    let worktree_arg = worktree.to_string_lossy().to_string();
+    let mut add = vec!["worktree", "add", "--detach", "--quiet", &worktree_arg];
+    if has_head(repo_path) {
+        add.push("HEAD");
+    }
+    run_git_command(repo_path, &add)?;
+
+    let failure = (|| -> Result<Option<String>> {
+        run_git_command(&worktree, &["read-tree", "-u", "--reset", tree])?;
+        let output = std::process::Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .current_dir(&worktree)
+            .stdin(std::process::Stdio::null())
+            .output()?;
+        if output.status.success() {
+            return Ok(None);
+        }
+        let printed = format!(
+            "{}{}",
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(Some(match printed.trim() {
+            "" => format!("{command} exited with {}", output.status),
+            _ => printed,
+        }))
+    })();
+
+    let _ = run_git_command(repo_path, &["worktree", "remove", "--force", &worktree_arg]);
+    let _ = std::fs::remove_dir_all(&worktree);
+    let _ = run_git_command(repo_path, &["worktree", "prune"]);
+    failure
+}
+
+/// Writes the todo list of `steps` and starts `git rebase -i` with it,
+/// returning its output, or `None` if there are no steps. Commits are not
+/// signed if `unsigned`.
+fn start_rebase_plan(
+    repo_path: &Path,
+    steps: &[RebaseStep],
+    unsigned: bool,
+) -> Result<Option<std::process::Output>> {
+    #[cfg(unix)]
+    use std::os::unix::fs::PermissionsExt;
+
+    let Some(first) = steps.first() else {
+        return Ok(None);
+    };
+    // A reordered plan does not start with the oldest commit, which is the
+    // one all the others descend from.
+    let mut merge_base = git_command();
+    merge_base.args(["merge-base", "--octopus"]);
+    merge_base.args(steps.iter().map(|step| step.hash.as_str()));
+    let merge_base_output = merge_base.current_dir(repo_path).output()?;
+    let oldest = if merge_base_output.status.success() {
+        String::from_utf8_lossy(&merge_base_output.stdout)
+            .trim()
+            .to_string()
+    } else {
+        first.hash.clone()
+    };
+    if let Some(step) = steps.iter().find(|s| s.action != RebaseAction::Drop)
+        && step.action.melds()
+    {
+        anyhow::bail!(
+            "Cannot {} {}: there is no earlier commit to meld into",
+            step.action.label(),
+            step.hash
+        );
+    }
+
+    let mut todo = String::new();
+    let mut temp_files = Vec::new();
+    for (i, step) in steps.iter().enumerate() {
+        match (step.action, &step.new_message) {
+            (RebaseAction::Reword, Some(message)) => {
+                let message_path = git_path(repo_path, &format!("rebase_plan_message_{i}"))?;
+                std::fs::write(&message_path, message)?;
+                todo.push_str(&format!(
+                    "pick {}\nexec git commit --amend --allow-empty --quiet --file '{}'\n",
+                    step.hash,
+                    message_path.display()
+                ));
+                temp_files.push(message_path);
+            }
+            (RebaseAction::Reword, None) => todo.push_str(&format!("pick {}\n", step.hash)),
+            (action, _) => todo.push_str(&format!("{} {}\n", action.label(), step.hash)),
+        }
+    }
+
+    let todo_path = git_path(repo_path, "rebase_plan_todo")?;
+    std::fs::write(&todo_path, todo)?;
+    let editor_script_path = git_path(repo_path, "rebase_plan_editor.sh")?;
+    std::fs::write(
+        &editor_script_path,
+        format!("#!/bin/sh\ncp '{}' \"$1\"", todo_path.display()),
+    )?;
+    #[cfg(unix)]
+    std::fs::set_permissions(&editor_script_path, std::fs::Permissions::from_mode(0o755))?;
+    temp_files.push(todo_path);
+    temp_files.push(editor_script_path.clone());
+
+    let parent_hash_output = git_command()
+        .arg("rev-parse")
+        .arg(format!("{oldest}^"))
+        .current_dir(repo_path)
+        .output()?;
+    let is_root_commit = !parent_hash_output.status.success();
+
+    let mut rebase_cmd = git_command();
+    rebase_cmd.env("GIT_SEQUENCE_EDITOR", &editor_script_path);
+    rebase_cmd.env("GIT_EDITOR", "true");
+    if unsigned {
+        // Passed on to the `git commit` run by `exec` lines too.
+        rebase_cmd.args(["-c", "commit.gpgsign=false"]);
+    }
+    rebase_cmd.arg("rebase").arg("-i").arg("--autostash");
+    if is_root_commit {
+        rebase_cmd.arg("--root");
+    } else {
+        rebase_cmd.arg(String::from_utf8_lossy(&parent_hash_output.stdout).trim());
+    }
+    let rebase_output = rebase_cmd.current_dir(repo_path).output()?;
+
+    for path in temp_files {
+        let _ = std::fs::remove_file(path);
+    }
+
+    Ok(Some(rebase_output))
+}
+
+pub fn fixup_and_rebase_autosquash(repo_path: &Path, fixup_commit_hash: &str) -> Result<()> {
+    // 1. Create a fixup! commit
+    let commit_output = git_command()
+        .arg("commit")
+        .arg("--no-edit")
+        .arg("--fixup")
+        .arg(fixup_commit_hash)
+        .current_dir(repo_path)
+        .output()?;
 
     if !commit_output.status.success() {
         // This can happen if there's nothing to commit. For now, we'll treat this as an error.
@@ -529,6 +1791,10 @@ pub fn fixup_and_rebase_autosquash(repo_path: &Path, fixup_commit_hash: &str) ->
     Ok(())
 }
 
+/// Untracked files larger than this are not staged by `add_all`, unless
+/// configured otherwise.
+pub const DEFAULT_MAX_FILE_SIZE: u64 = 100 * 1024 * 1024;
+
 pub fn add_all_with_size_limit(repo_path: &Path, size_limit: u64) -> Result<()> {
     // Stage all modified and deleted files
     git_command()
@@ -555,11 +1821,8 @@ pub fn add_all_with_size_limit(repo_path: &Path, size_limit: u64) -> Result<()>
             continue;
         }
 
-        let file_path = repo_path.join(file_name);
-        if let Ok(metadata) = std::fs::metadata(&file_path) {
-            if metadata.len() <= size_limit {
-                stage_file(repo_path, file_name)?;
-            }
+        if is_within_size_limit(repo_path, file_name, size_limit) {
+            stage_file(repo_path, file_name)?;
         }
     }
     Ok(())
@@ -582,18 +1845,18 @@ pub fn cherry_pick_no_commit(repo_path: &Path, commit_hash: &str) -> Result<()>
     Ok(())
 }
 
+/// Whether the untracked file at `path` is small enough for
+/// `add_all_with_size_limit` to stage it.
+pub fn is_within_size_limit(repo_path: &Path, path: &str, size_limit: u64) -> bool {
+    std::fs::metadata(repo_path.join(path)).is_ok_and(|metadata| metadata.len() <= size_limit)
+}
+
 pub fn add_all(repo_path: &Path) -> Result<()> {
-    add_all_with_size_limit(repo_path, 100 * 1024 * 1024)
+    add_all_with_size_limit(repo_path, DEFAULT_MAX_FILE_SIZE)
 }
 
 pub fn unstage_all(repo_path: &Path) -> Result<()> {
-    git_command()
-        .arg("reset")
-        .arg("HEAD")
-        .arg(".")
-        .current_dir(repo_path)
-        .output()?;
-    Ok(())
+    run_git_command(repo_path, &["reset", "-q", "--", "."]).map(|_| ())
 }
 
 pub fn get_staged_diff_output(repo_path: &Path) -> Result<std::process::Output> {
@@ -606,14 +1869,23 @@ pub fn get_staged_diff_output(repo_path: &Path) -> Result<std::process::Output>
 }
 
 pub fn get_unstaged_diff(repo_path: &Path) -> Vec<FileDiff> {
+    get_unstaged_diff_with_context(repo_path, None)
+}
+
+/// [`get_unstaged_diff`] with `context` lines around each change.
+pub fn get_unstaged_diff_with_context(repo_path: &Path, context: Option<usize>) -> Vec<FileDiff> {
     let output = git_command()
         .arg("diff")
+        .arg("--textconv")
+        .args(context_arg(context))
         .current_dir(repo_path)
         .output()
         .expect("Failed to execute git diff");
 
     let diff_str = String::from_utf8_lossy(&output.stdout);
-    parse_diff(&diff_str)
+    let mut files = parse_diff(&diff_str);
+    fill_binary_sizes(repo_path, &mut files);
+    files
 }
 
 pub fn has_unstaged_changes_in_file(repo_path: &Path, file_path: &str) -> Result<bool> {
@@ -662,8 +1934,111 @@ pub fn get_untracked_files(repo_path: &Path) -> Result<Vec<String>> {
     Ok(untracked_files)
 }
 
+/// What the index and the working tree hold for a path, to notice when it
+/// changes between being shown and being acted on.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FileFingerprint {
+    /// Blob id staged for the path, `None` when it is not in the index.
+    pub index: Option<String>,
+    /// Blob id of the working tree file, `None` when there is none.
+    pub worktree: Option<String>,
+}
+
+/// Fingerprints of `paths`, read with one `git ls-files` and one
+/// `git hash-object` for all of them.
+pub fn file_fingerprints(
+    repo_path: &Path,
+    paths: &[String],
+) -> Result<HashMap<String, FileFingerprint>> {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut fingerprints: HashMap<String, FileFingerprint> = paths
+        .iter()
+        .map(|path| (path.clone(), FileFingerprint::default()))
+        .collect();
+    if paths.is_empty() {
+        return Ok(fingerprints);
+    }
+
+    let output = git_command()
+        .arg("--literal-pathspecs")
+        .arg("ls-files")
+        .arg("--stage")
+        .arg("-z")
+        .arg("--")
+        .args(paths)
+        .current_dir(repo_path)
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!("git ls-files failed");
+    }
+    // `<mode> <blob> <stage>\t<path>`; a conflicted path has several stages
+    // and keeps the first.
+    for entry in String::from_utf8_lossy(&output.stdout).split('\0') {
+        if let Some((info, path)) = entry.split_once('\t')
+            && let Some(blob) = info.split(' ').nth(1)
+            && let Some(fingerprint) = fingerprints.get_mut(path)
+            && fingerprint.index.is_none()
+        {
+            fingerprint.index = Some(blob.to_string());
+        }
+    }
+
+    let files: Vec<&String> = paths
+        .iter()
+        .filter(|path| repo_path.join(path).is_file())
+        .collect();
+    if files.is_empty() {
+        return Ok(fingerprints);
+    }
+    let mut child = git_command()
+        .arg("hash-object")
+        .arg("--no-filters")
+        .arg("--stdin-paths")
+        .current_dir(repo_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        for path in &files {
+            writeln!(stdin, "{path}")?;
+        }
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!("git hash-object failed");
+    }
+    for (path, blob) in files
+        .iter()
+        .zip(String::from_utf8_lossy(&output.stdout).lines())
+    {
+        if let Some(fingerprint) = fingerprints.get_mut(*path) {
+            fingerprint.worktree = Some(blob.to_string());
+        }
+    }
+    Ok(fingerprints)
+}
+
+pub fn file_fingerprint(repo_path: &Path, path: &str) -> Result<FileFingerprint> {
+    Ok(file_fingerprints(repo_path, &[path.to_string()])?
+        .remove(path)
+        .unwrap_or_default())
+}
+
+/// Whether `path` still has the fingerprint `expected`. A fingerprint that
+/// can't be read counts as a change.
+pub fn is_file_unchanged(repo_path: &Path, path: &str, expected: &FileFingerprint) -> bool {
+    file_fingerprint(repo_path, path).is_ok_and(|current| &current == expected)
+}
+
 pub fn get_unstaged_diff_patch(repo_path: &Path) -> Result<String> {
-    let output = git_command().arg("diff").current_dir(repo_path).output()?;
+    let output = git_command()
+        .arg("diff")
+        .arg("--no-textconv")
+        .current_dir(repo_path)
+        .output()?;
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
@@ -671,14 +2046,57 @@ pub fn get_staged_diff_patch(repo_path: &Path) -> Result<String> {
     let output = git_command()
         .arg("diff")
         .arg("--staged")
+        .arg("--no-textconv")
         .current_dir(repo_path)
         .output()?;
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
+/// The paths among `paths` whose diff is shown through the `textconv`
+/// command of a diff driver set in `.gitattributes`, e.g. to turn notebooks
+/// into readable text. Their shown lines are not the file's own.
+pub fn get_textconv_files(repo_path: &Path, paths: &[String]) -> Result<HashSet<String>> {
+    let drivers_output = git_command()
+        .args(["config", "--get-regexp", r"^diff\..*\.textconv$"])
+        .current_dir(repo_path)
+        .output()?;
+    let drivers: HashSet<String> = String::from_utf8_lossy(&drivers_output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let key = line.split_whitespace().next()?;
+            Some(
+                key.strip_prefix("diff.")?
+                    .strip_suffix(".textconv")?
+                    .to_string(),
+            )
+        })
+        .collect();
+    if drivers.is_empty() || paths.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    let output = git_command()
+        .args(["check-attr", "-z", "diff", "--"])
+        .args(paths)
+        .current_dir(repo_path)
+        .output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let fields: Vec<&str> = stdout.split('\0').collect();
+    Ok(fields
+        .chunks_exact(3)
+        .filter(|entry| drivers.contains(entry[2]))
+        .map(|entry| entry[0].to_string())
+        .collect())
+}
+
+pub fn get_staged_diff_stat(repo_path: &Path) -> Result<String> {
+    run_git_command(repo_path, &["diff", "--staged", "--stat"])
+}
+
 pub fn get_unstaged_file_diff_patch(repo_path: &Path, file_name: &str) -> Result<String> {
     let output = git_command()
         .arg("diff")
+        .arg("--no-textconv")
         .arg("--")
         .arg(file_name)
         .current_dir(repo_path)
@@ -690,6 +2108,7 @@ pub fn get_file_diff_patch(repo_path: &Path, file_name: &str) -> Result<String>
     let output = git_command()
         .arg("diff")
         .arg("--staged")
+        .arg("--no-textconv")
         .arg("--")
         .arg(file_name)
         .current_dir(repo_path)
@@ -697,38 +2116,110 @@ pub fn get_file_diff_patch(repo_path: &Path, file_name: &str) -> Result<String>
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
-pub fn unstage_file(repo_path: &Path, file_name: &str) -> Result<()> {
-    git_command()
-        .arg("reset")
-        .arg("HEAD")
+/// Staged changes of all `paths` as one patch, with renames between them
+/// detected.
+pub fn get_paths_diff_patch(repo_path: &Path, paths: &[String]) -> Result<String> {
+    let output = git_command()
+        .arg("diff")
+        .arg("--staged")
+        .arg("--no-textconv")
+        .arg("-M")
         .arg("--")
-        .arg(file_name)
+        .args(paths)
         .current_dir(repo_path)
         .output()?;
-    Ok(())
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
-pub fn stage_file(repo_path: &Path, file_name: &str) -> Result<()> {
-    git_command()
-        .arg("add")
-        .arg(file_name)
+pub fn unstage_paths(repo_path: &Path, paths: &[String]) -> Result<()> {
+    let mut args = vec!["reset", "-q", "--"];
+    args.extend(paths.iter().map(String::as_str));
+    run_git_command(repo_path, &args).map(|_| ())
+}
+
+/// Stages additions, modifications and deletions of `paths`.
+pub fn stage_paths(repo_path: &Path, paths: &[String]) -> Result<()> {
+    let mut args = vec!["add", "-A", "--"];
+    args.extend(paths.iter().map(String::as_str));
+    run_git_command(repo_path, &args).map(|_| ())
+}
+
+/// The paths `git add -A` would stage for `pathspecs`: the changed, deleted
+/// and untracked files they match, without the ignored ones. A pathspec
+/// matching nothing adds nothing.
+pub fn get_pathspec_changes(repo_path: &Path, pathspecs: &[String]) -> Result<Vec<String>> {
+    let output = git_command()
+        .args(["add", "-A", "--dry-run", "--ignore-missing", "--"])
+        .args(pathspecs)
         .current_dir(repo_path)
         .output()?;
-    Ok(())
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // Naming an ignored file fails after listing the others.
+    if !output.status.success() && stdout.is_empty() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(stdout
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix("add '")
+                .or_else(|| line.strip_prefix("remove '"))
+        })
+        .filter_map(|path| path.strip_suffix('\''))
+        .map(String::from)
+        .collect())
+}
+
+pub fn unstage_file(repo_path: &Path, file_name: &str) -> Result<()> {
+    run_git_command(repo_path, &["reset", "-q", "--", file_name]).map(|_| ())
+}
+
+pub fn stage_file(repo_path: &Path, file_name: &str) -> Result<()> {
+    run_git_command(repo_path, &["add", "--", file_name]).map(|_| ())
+}
+
+/// Stages `file_name` even when an ignore rule matches it, as for a file
+/// that was tracked before.
+pub fn force_stage_file(repo_path: &Path, file_name: &str) -> Result<()> {
+    run_git_command(repo_path, &["add", "-f", "--", file_name]).map(|_| ())
 }
 
 pub fn apply_patch(repo_path: &Path, patch: &str, reverse: bool, cached: bool) -> Result<()> {
+    run_apply(repo_path, patch, reverse, cached, false)
+}
+
+/// Whether `patch` would apply, without applying it.
+pub fn can_apply_patch(repo_path: &Path, patch: &str, reverse: bool, cached: bool) -> bool {
+    run_apply(repo_path, patch, reverse, cached, true).is_ok()
+}
+
+/// Whether `patch` would apply, without applying it. The error is what
+/// `git apply --check` printed.
+pub fn check_apply_patch(
+    repo_path: &Path,
+    patch: &str,
+    reverse: bool,
+    cached: bool,
+) -> std::result::Result<(), String> {
+    run_apply(repo_path, patch, reverse, cached, true).map_err(|e| {
+        let message = e.to_string();
+        match message.split_once("--- stderr ---\n") {
+            Some((_, stderr)) => stderr.trim().to_string(),
+            None => message,
+        }
+    })
+}
+
+/// Whether `patch` applies to the working tree, or to the index and the
+/// working tree when `staged`. The error is the first line `git apply
+/// --check` printed.
+pub fn check_patch(repo_path: &Path, patch: &str, staged: bool) -> Result<()> {
     use std::io::Write;
     use std::process::Stdio;
 
-    let mut args = vec!["apply"];
-    if cached {
-        args.push("--cached");
-    }
-    if reverse {
-        args.push("--reverse");
+    let mut args = vec!["apply", "--check"];
+    if staged {
+        args.push("--index");
     }
-    args.push("--unidiff-zero");
     args.push("-");
 
     let mut child = git_command()
@@ -745,24 +2236,162 @@ pub fn apply_patch(repo_path: &Path, patch: &str, reverse: bool, cached: bool) -
 
     let output = child.wait_with_output()?;
     if !output.status.success() {
-        anyhow::bail!(
-            "git apply failed (reverse={}):
---- stderr ---
-{}",
-            reverse,
-            String::from_utf8_lossy(&output.stderr)
-        );
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("{}", stderr.lines().next().unwrap_or("git apply failed"));
     }
     Ok(())
 }
 
-pub fn checkout_file(repo_path: &Path, file_name: &str) -> Result<()> {
-    git_command()
-        .arg("checkout")
-        .arg("--")
-        .arg(file_name)
-        .current_dir(repo_path)
-        .output()?;
+fn run_apply(
+    repo_path: &Path,
+    patch: &str,
+    reverse: bool,
+    cached: bool,
+    check: bool,
+) -> Result<()> {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut args = vec!["apply"];
+    if check {
+        args.push("--check");
+    }
+    if cached {
+        args.push("--cached");
+    }
+    if reverse {
+        args.push("--reverse");
+    }
+    args.push("--unidiff-zero");
+    args.push("-");
+
+    let mut child = git_command()
+        .args(&args)
+        .current_dir(repo_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(patch.as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git apply failed (reverse={}):
+--- stderr ---
+{}",
+            reverse,
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(())
+}
+
+pub fn checkout_file(repo_path: &Path, file_name: &str) -> Result<()> {
+    git_command()
+        .arg("checkout")
+        .arg("--")
+        .arg(file_name)
+        .current_dir(repo_path)
+        .output()?;
+    Ok(())
+}
+
+/// The content `file_name` has in `HEAD`, e.g. to show a deleted file.
+pub fn read_head_file(repo_path: &Path, file_name: &str) -> Result<Vec<u8>> {
+    let output = git_command()
+        .arg("show")
+        .arg(format!("HEAD:{file_name}"))
+        .current_dir(repo_path)
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git show HEAD:{file_name} failed. Stderr: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output.stdout)
+}
+
+/// Brings `file_name` back to the index and the working tree as it is in
+/// `HEAD`, e.g. to undo its deletion.
+pub fn restore_from_head(repo_path: &Path, file_name: &str) -> Result<()> {
+    let output = git_command()
+        .arg("checkout")
+        .arg("HEAD")
+        .arg("--")
+        .arg(file_name)
+        .current_dir(repo_path)
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to restore {file_name}. Stderr: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Overwrites `file_name` in the working tree, and in the index too if
+/// `staged`, with its content in the commit `source`.
+pub fn restore_from_commit(
+    repo_path: &Path,
+    source: &str,
+    file_name: &str,
+    staged: bool,
+) -> Result<()> {
+    let mut cmd = git_command();
+    cmd.arg("restore").arg(format!("--source={source}"));
+    if staged {
+        cmd.arg("--staged").arg("--worktree");
+    }
+    let output = cmd
+        .arg("--")
+        .arg(file_name)
+        .current_dir(repo_path)
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to restore {file_name} from {source}. Stderr: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// The mode and blob hash recorded in the index for `path`, or `None` when
+/// the path is not tracked.
+pub fn index_entry(repo_path: &Path, path: &str) -> Result<Option<(String, String)>> {
+    let output = run_git_command(repo_path, &["ls-files", "--stage", "--", path])?;
+    let mut fields = output.split_whitespace();
+    Ok(fields
+        .next()
+        .zip(fields.next())
+        .map(|(mode, hash)| (mode.to_string(), hash.to_string())))
+}
+
+/// Puts an entry read with [`index_entry`] back, or removes `path` from the
+/// index when it had none.
+pub fn set_index_entry(
+    repo_path: &Path,
+    path: &str,
+    entry: Option<&(String, String)>,
+) -> Result<()> {
+    match entry {
+        Some((mode, hash)) => run_git_command(
+            repo_path,
+            &[
+                "update-index",
+                "--add",
+                "--cacheinfo",
+                &format!("{mode},{hash},{path}"),
+            ],
+        ),
+        None => run_git_command(repo_path, &["update-index", "--force-remove", "--", path]),
+    }?;
     Ok(())
 }
 
@@ -776,6 +2405,26 @@ pub fn rm_file(repo_path: &Path, file_name: &str) -> Result<()> {
     Ok(())
 }
 
+pub fn move_file(repo_path: &Path, from: &str, to: &str) -> Result<()> {
+    if let Some(parent) = Path::new(to).parent() {
+        std::fs::create_dir_all(repo_path.join(parent))?;
+    }
+    let output = git_command()
+        .arg("mv")
+        .arg("--")
+        .arg(from)
+        .arg(to)
+        .current_dir(repo_path)
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git mv failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
 pub fn stage_path(repo_path: &Path, path: &str) -> Result<()> {
     git_command()
         .arg("add")
@@ -785,16 +2434,41 @@ pub fn stage_path(repo_path: &Path, path: &str) -> Result<()> {
     Ok(())
 }
 
-pub fn rm_cached(repo_path: &Path, path: &str) -> Result<()> {
+pub fn is_tracked(repo_path: &Path, path: &str) -> bool {
     git_command()
-        .arg("rm")
-        .arg("--cached")
+        .arg("ls-files")
+        .arg("--error-unmatch")
+        .arg("--")
         .arg(path)
         .current_dir(repo_path)
-        .output()?;
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Returns the mode recorded in the index for `path` (e.g. `100644`), or
+/// `None` when the path is not tracked.
+pub fn index_mode(repo_path: &Path, path: &str) -> Result<Option<String>> {
+    let output = run_git_command(repo_path, &["ls-files", "--stage", "--", path])?;
+    Ok(output
+        .split_whitespace()
+        .next()
+        .map(|mode| mode.to_string()))
+}
+
+pub fn update_index_chmod(repo_path: &Path, path: &str, executable: bool) -> Result<()> {
+    let chmod = if executable {
+        "--chmod=+x"
+    } else {
+        "--chmod=-x"
+    };
+    run_git_command(repo_path, &["update-index", chmod, "--", path])?;
     Ok(())
 }
 
+pub fn rm_cached(repo_path: &Path, path: &str) -> Result<()> {
+    run_git_command(repo_path, &["rm", "--cached", "-q", "--", path]).map(|_| ())
+}
+
 pub fn read_file_content(repo_path: &Path, file_path: &str) -> Result<(Vec<u8>, usize)> {
     let full_path = repo_path.join(file_path);
     let metadata = std::fs::metadata(&full_path)?;
@@ -829,43 +2503,629 @@ pub fn is_commit_on_remote(repo_path: &Path, hash: &str) -> Result<bool> {
     Ok(!stdout.trim().is_empty())
 }
 
-pub fn stash_unstaged_changes(repo_path: &Path) -> Result<bool> {
+/// An entry of `git stash list`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StashEntry {
+    /// `stash@{<n>}`, which shifts whenever a stash is added or dropped.
+    pub name: String,
+    /// The stash commit, which stays valid even after the stash is dropped.
+    pub hash: String,
+    /// Like `On main: message`.
+    pub message: String,
+}
+
+/// Marks the stash made around a commit reorder so it can be found again.
+const REORDER_STASH_MESSAGE: &str = "git-branchless-reorder-stash";
+
+/// Lists the stashes, newest first.
+pub fn get_stashes(repo_path: &Path) -> Result<Vec<StashEntry>> {
     let output = git_command()
         .arg("stash")
-        .arg("push")
-        .arg("-u")
-        .arg("-m")
-        .arg("git-branchless-reorder-stash")
+        .arg("list")
+        .arg("--format=%gd%x1f%H%x1f%gs")
+        .current_dir(repo_path)
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!("Failed to list stashes");
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\x1f');
+            Some(StashEntry {
+                name: fields.next()?.to_string(),
+                hash: fields.next()?.to_string(),
+                message: fields.next()?.to_string(),
+            })
+        })
+        .collect())
+}
+
+/// The changes of a stash compared to the commit it was made on, untracked
+/// files included.
+pub fn get_stash_diff(repo_path: &Path, hash: &str) -> Result<Vec<FileDiff>> {
+    let output = git_command()
+        .arg("stash")
+        .arg("show")
+        .arg("--stat")
+        .arg("--patch")
+        .arg("--include-untracked")
+        .arg(hash)
         .current_dir(repo_path)
         .output()?;
+    Ok(parse_diff(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// The patch [`stash_apply`] applies to the working tree, to take it back.
+pub fn get_stash_patch(repo_path: &Path, hash: &str) -> Result<String> {
+    run_git_command(
+        repo_path,
+        &[
+            "stash",
+            "show",
+            "--patch",
+            "--binary",
+            "--include-untracked",
+            hash,
+        ],
+    )
+}
+
+/// Stashes the changes of the working tree, untracked files included, and
+/// returns whether there was anything to stash. With `keep_index` the staged
+/// changes are stashed too but also stay in the index and the working tree.
+/// An empty `message` gets git's `WIP on <branch>` one.
+pub fn stash_push(repo_path: &Path, message: &str, keep_index: bool) -> Result<bool> {
+    let mut command = git_command();
+    command.arg("stash").arg("push").arg("--include-untracked");
+    if keep_index {
+        command.arg("--keep-index");
+    }
+    if !message.is_empty() {
+        command.arg("-m").arg(message);
+    }
+    let output = command.current_dir(repo_path).output()?;
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
     let stdout = String::from_utf8_lossy(&output.stdout);
     Ok(!stdout.contains("No local changes to save"))
 }
 
-pub fn pop_stash(repo_path: &Path) -> Result<()> {
-    let stash_list_output = git_command()
+/// Applies the stash commit `hash` to the working tree, keeping the stash.
+pub fn stash_apply(repo_path: &Path, hash: &str) -> Result<()> {
+    let output = git_command()
         .arg("stash")
-        .arg("list")
+        .arg("apply")
+        .arg(hash)
         .current_dir(repo_path)
         .output()?;
-    let stash_list = String::from_utf8_lossy(&stash_list_output.stdout);
-    let stash_ref = stash_list
-        .lines()
-        .find(|line| line.contains("git-branchless-reorder-stash"))
-        .and_then(|line| line.split(':').next())
-        .map(|s| s.to_string());
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Removes the stash made from commit `hash` from the stash list.
+pub fn stash_drop(repo_path: &Path, hash: &str) -> Result<()> {
+    let Some(stash) = get_stashes(repo_path)?
+        .into_iter()
+        .find(|stash| stash.hash == hash)
+    else {
+        anyhow::bail!("No stash {hash}");
+    };
+    run_git_command(repo_path, &["stash", "drop", "--quiet", &stash.name])?;
+    Ok(())
+}
+
+/// Puts a dropped stash commit back on top of the stash list.
+pub fn stash_store(repo_path: &Path, hash: &str, message: &str) -> Result<()> {
+    run_git_command(repo_path, &["stash", "store", "-m", message, hash])?;
+    Ok(())
+}
+
+pub fn stash_unstaged_changes(repo_path: &Path) -> Result<bool> {
+    stash_push(repo_path, REORDER_STASH_MESSAGE, false)
+}
+
+pub fn pop_stash(repo_path: &Path) -> Result<()> {
+    let stash = get_stashes(repo_path)?
+        .into_iter()
+        .find(|stash| stash.message.contains(REORDER_STASH_MESSAGE));
 
-    if let Some(stash_ref) = stash_ref {
+    if let Some(stash) = stash {
         git_command()
             .arg("stash")
             .arg("pop")
-            .arg(&stash_ref)
+            .arg(&stash.name)
+            .current_dir(repo_path)
+            .output()?;
+    }
+    Ok(())
+}
+
+/// Pushes the current branch to its upstream. A branch without an upstream
+/// is pushed to `origin` under the same name and tracks it from then on.
+pub fn push(repo_path: &Path) -> Result<()> {
+    let has_upstream = git_command()
+        .arg("rev-parse")
+        .arg("--abbrev-ref")
+        .arg("@{upstream}")
+        .current_dir(repo_path)
+        .output()?
+        .status
+        .success();
+
+    let mut command = git_command();
+    command.arg("push");
+    if !has_upstream {
+        command.arg("--set-upstream").arg("origin").arg("HEAD");
+    }
+    // There is no terminal to ask for credentials on while the UI is up.
+    let output = command
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(std::process::Stdio::null())
+        .current_dir(repo_path)
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(())
+}
+
+/// Fetches from the remote of the current branch. `progress` gets each
+/// progress line git prints, as it comes.
+pub fn fetch(repo_path: &Path, progress: impl FnMut(&str)) -> Result<()> {
+    run_remote_command(repo_path, &["fetch", "--progress"], progress)
+}
+
+/// Fetches, then rebases the local commits onto the upstream of the
+/// current branch. What is staged and what is not stays as it is. When the
+/// local commits conflict with the upstream, the rebase is aborted and the
+/// branch is left as it was.
+pub fn pull_rebase(repo_path: &Path, mut progress: impl FnMut(&str)) -> Result<()> {
+    let Ok(upstream) = run_git_command(
+        repo_path,
+        &[
+            "rev-parse",
+            "--abbrev-ref",
+            "--symbolic-full-name",
+            "@{upstream}",
+        ],
+    ) else {
+        anyhow::bail!("the branch has no upstream");
+    };
+    let upstream = upstream.trim().to_string();
+    run_remote_command(repo_path, &["fetch", "--progress"], &mut progress)?;
+    progress(&format!("Rebasing onto {upstream}"));
+
+    with_changes_set_aside(repo_path, "git-full-commit: pull", || {
+        let output = git_command()
+            .env("GIT_EDITOR", "true")
+            .args(["rebase", "@{upstream}"])
             .current_dir(repo_path)
             .output()?;
+        if output.status.success() {
+            return Ok(());
+        }
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if is_rebase_in_progress(repo_path) {
+            run_git_command(repo_path, &["rebase", "--abort"])?;
+            let reason = stderr
+                .lines()
+                .find(|line| line.starts_with("error:") || line.starts_with("CONFLICT"))
+                .unwrap_or_default();
+            anyhow::bail!(
+                "the local commits conflict with {upstream}, the branch is unchanged. {reason}"
+            );
+        }
+        anyhow::bail!("git rebase failed: {}", stderr.trim())
+    })
+}
+
+/// Runs `git <args>` against a remote without a terminal to ask for
+/// credentials on. Progress lines, which git ends with `\r` while it
+/// updates them, are passed to `progress`.
+fn run_remote_command(
+    repo_path: &Path,
+    args: &[&str],
+    mut progress: impl FnMut(&str),
+) -> Result<()> {
+    use std::io::Read;
+
+    let mut child = git_command()
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .current_dir(repo_path)
+        .spawn()?;
+    let mut stderr = child.stderr.take().expect("stderr is piped");
+    let mut output = String::new();
+    let mut line = Vec::new();
+    let mut buffer = [0; 1024];
+    loop {
+        let read = stderr.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        for &byte in &buffer[..read] {
+            if byte != b'\r' && byte != b'\n' {
+                line.push(byte);
+                continue;
+            }
+            let text = String::from_utf8_lossy(&line);
+            if !text.trim().is_empty() {
+                progress(text.trim());
+            }
+            if byte == b'\n' {
+                output.push_str(&text);
+                output.push('\n');
+            }
+            line.clear();
+        }
+    }
+    output.push_str(&String::from_utf8_lossy(&line));
+    if !child.wait()?.success() {
+        anyhow::bail!("{}", output.trim());
+    }
+    Ok(())
+}
+
+/// Runs `f` with the working tree and the index reset to `HEAD`, and puts
+/// the changes back afterwards. Unlike `--autostash`, this keeps what was
+/// staged staged. If they cannot be put back, they are kept in the stash
+/// under `stash_message`.
+fn with_changes_set_aside(
+    repo_path: &Path,
+    stash_message: &str,
+    f: impl FnOnce() -> Result<()>,
+) -> Result<()> {
+    let stash = run_git_command(repo_path, &["stash", "create"])?
+        .trim()
+        .to_string();
+    if !stash.is_empty() {
+        run_git_command(repo_path, &["reset", "--hard", "-q", "HEAD"])?;
+    }
+    let result = f();
+    if !stash.is_empty()
+        && run_git_command(repo_path, &["stash", "apply", "--index", "-q", &stash]).is_err()
+    {
+        run_git_command(repo_path, &["stash", "store", "-m", stash_message, &stash])?;
+        anyhow::bail!("the changes could not be put back and are kept in the stash");
+    }
+    result
+}
+
+/// Where `name` lives in the git directory, e.g. `.git/<name>`. Also right
+/// in linked worktrees, where `.git` is a file.
+pub fn git_path(repo_path: &Path, name: &str) -> Result<PathBuf> {
+    let output = git_command()
+        .arg("rev-parse")
+        .arg("--git-path")
+        .arg(name)
+        .current_dir(repo_path)
+        .output()?;
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(repo_path.join(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+/// An `index.lock` in the git directory, left by another git process
+/// still at work or by one that crashed. Anything writing the index fails
+/// while it is there.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexLock {
+    pub path: PathBuf,
+    /// How long ago the lock was made, if the file system tells.
+    pub age: Option<std::time::Duration>,
+    /// The git processes running in the repository, like
+    /// `git commit -m wip (pid 123)`, or `None` where they cannot be found.
+    pub holders: Option<Vec<String>>,
+}
+
+/// The lock of the index, if it is locked.
+pub fn index_lock(repo_path: &Path) -> Option<IndexLock> {
+    let path = git_path(repo_path, "index.lock").ok()?;
+    let metadata = std::fs::metadata(&path).ok()?;
+    Some(IndexLock {
+        age: metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.elapsed().ok()),
+        holders: git_processes(repo_path),
+        path,
+    })
+}
+
+/// The git processes whose working directory is in the repository, found
+/// in `/proc`. `None` on systems without it.
+fn git_processes(repo_path: &Path) -> Option<Vec<String>> {
+    let repo_path = repo_path.canonicalize().ok()?;
+    let entries = std::fs::read_dir("/proc").ok()?;
+    let mut processes = Vec::new();
+    for entry in entries.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let proc_path = entry.path();
+        let is_git =
+            std::fs::read_to_string(proc_path.join("comm")).is_ok_and(|comm| comm.trim() == "git");
+        let in_repo =
+            std::fs::read_link(proc_path.join("cwd")).is_ok_and(|cwd| cwd.starts_with(&repo_path));
+        if !is_git || !in_repo {
+            continue;
+        }
+        let command_line = std::fs::read(proc_path.join("cmdline")).unwrap_or_default();
+        let command_line = String::from_utf8_lossy(&command_line)
+            .split('\0')
+            .filter(|arg| !arg.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        processes.push(format!("{command_line} (pid {pid})"));
+    }
+    Some(processes)
+}
+
+/// Loose objects and packs, as `git count-objects -v` counts them.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ObjectCounts {
+    pub loose: usize,
+    pub packs: usize,
+}
+
+pub fn count_objects(repo_path: &Path) -> Result<ObjectCounts> {
+    let output = run_git_command(repo_path, &["count-objects", "-v"])?;
+    let value = |name: &str| {
+        output
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(": "))
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(0)
+    };
+    Ok(ObjectCounts {
+        loose: value("count"),
+        packs: value("packs"),
+    })
+}
+
+/// The version of the index file and how many entries it has, read from
+/// its header. `None` if there is no index yet.
+pub fn read_index_header(repo_path: &Path) -> Option<(u32, u32)> {
+    let mut header = [0; 12];
+    let path = git_path(repo_path, "index").ok()?;
+    std::io::Read::read_exact(&mut std::fs::File::open(path).ok()?, &mut header).ok()?;
+    if &header[..4] != b"DIRC" {
+        return None;
+    }
+    let number = |bytes: &[u8]| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    Some((number(&header[4..8]), number(&header[8..12])))
+}
+
+/// The value of `key` in the git config as a number, with suffixes like
+/// `k` read by git. `None` if it is not set.
+pub fn get_config_int(repo_path: &Path, key: &str) -> Option<i64> {
+    run_git_command(repo_path, &["config", "--type=int", "--get", key])
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+fn get_config_bool(repo_path: &Path, key: &str) -> Option<bool> {
+    run_git_command(repo_path, &["config", "--type=bool", "--get", key])
+        .ok()
+        .map(|value| value.trim() == "true")
+}
+
+/// The caches keeping `git status` fast in large repositories: the file
+/// system monitor tells which files changed, and the untracked cache
+/// which directories need to be looked through for new files.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StatusCaches {
+    pub fsmonitor: bool,
+    pub untracked_cache: bool,
+}
+
+impl StatusCaches {
+    pub fn read(repo_path: &Path) -> Self {
+        Self {
+            fsmonitor: is_fsmonitor_enabled(repo_path),
+            // `feature.manyFiles` turns it on unless it is set otherwise.
+            untracked_cache: get_config_bool(repo_path, "core.untrackedCache")
+                .or_else(|| get_config_bool(repo_path, "feature.manyFiles"))
+                .unwrap_or(false),
+        }
+    }
+
+    pub fn any(self) -> bool {
+        self.fsmonitor || self.untracked_cache
+    }
+}
+
+/// The untracked files, like [`get_untracked_files`]. When `caches` are on,
+/// they are read from `git status`, which uses them where `git ls-files`
+/// looks through the whole working tree. It is told to skip what it would
+/// compute besides.
+pub fn list_untracked_files(repo_path: &Path, caches: StatusCaches) -> Result<Vec<String>> {
+    if !caches.any() {
+        return get_untracked_files(repo_path);
+    }
+    let output = git_command()
+        .args([
+            "status",
+            "--porcelain=v2",
+            "-z",
+            "--no-ahead-behind",
+            "--no-renames",
+            "--untracked-files=all",
+            "--ignore-submodules=all",
+        ])
+        .current_dir(repo_path)
+        .output()?;
+    if !output.status.success() {
+        return Ok(Vec::new());
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .split('\0')
+        .filter_map(|entry| entry.strip_prefix("? "))
+        .map(String::from)
+        .collect())
+}
+
+/// Whether `core.fsmonitor` asks for git's own file system monitor, or
+/// names a hook.
+pub fn is_fsmonitor_enabled(repo_path: &Path) -> bool {
+    run_git_command(repo_path, &["config", "--get", "core.fsmonitor"])
+        .is_ok_and(|value| !matches!(value.trim(), "" | "false" | "0" | "no" | "off"))
+}
+
+/// Whether git has its own file system monitor on this platform: it has
+/// none on Linux, and versions before 2.36 have none at all.
+pub fn has_fsmonitor_daemon(repo_path: &Path) -> bool {
+    git_command()
+        .args(["fsmonitor--daemon", "status"])
+        .current_dir(repo_path)
+        .output()
+        .is_ok_and(|output| {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            !stderr.contains("not supported") && !stderr.contains("is not a git command")
+        })
+}
+
+/// Runs `git <args>`, failing with the first line git printed about why.
+pub fn run_for_message(repo_path: &Path, args: &[&str]) -> Result<()> {
+    let output = git_command().args(args).current_dir(repo_path).output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!(
+            "{}",
+            stderr.lines().next().unwrap_or("git exited with an error")
+        );
     }
     Ok(())
 }
 
+/// The content of the file `commit.template` points to, if it is set.
+/// Relative paths are relative to the top of the worktree, as for `git
+/// commit`.
+pub fn get_commit_template(repo_path: &Path) -> Result<Option<String>> {
+    let output = git_command()
+        .arg("config")
+        .arg("--path")
+        .arg("--get")
+        .arg("commit.template")
+        .current_dir(repo_path)
+        .output()?;
+    // Exits with 1 when the key is not set.
+    if !output.status.success() {
+        return Ok(None);
+    }
+    let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    let content = std::fs::read_to_string(repo_path.join(path))?;
+    Ok(Some(content))
+}
+
+/// The message a new commit starts with, as `git commit` would open it in
+/// the editor: the commit template, then passed through the
+/// `prepare-commit-msg` hook if there is one. Comment lines are removed.
+/// `None` if that leaves nothing.
+pub fn prepare_commit_message(repo_path: &Path) -> Result<Option<String>> {
+    let template = get_commit_template(repo_path)?;
+    let mut message = template.clone().unwrap_or_default();
+
+    let hook = git_path(repo_path, "hooks/prepare-commit-msg")?;
+    if is_executable(&hook) {
+        let message_path = git_path(repo_path, "COMMIT_EDITMSG")?;
+        std::fs::write(&message_path, &message)?;
+        let mut command = OsCommand::new(&hook);
+        command.arg(&message_path);
+        if template.is_some() {
+            command.arg("template");
+        }
+        let output = command
+            .stdin(std::process::Stdio::null())
+            .current_dir(repo_path)
+            .output()?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "prepare-commit-msg failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        message = std::fs::read_to_string(&message_path)?;
+    }
+    Ok(strip_message_comments(&message))
+}
+
+/// `message` without its comment lines and the blank lines around it, as
+/// `git commit` reads it back from the editor. `None` if nothing is left.
+pub fn strip_message_comments(message: &str) -> Option<String> {
+    // Blank lines around the message go, but not the space a template may
+    // leave after a prefix like `feat: `.
+    let lines: Vec<&str> = message
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .collect();
+    let is_blank = |line: &&str| line.trim().is_empty();
+    let first = lines.iter().position(|line| !is_blank(line))?;
+    let last = lines
+        .iter()
+        .rposition(|line| !is_blank(line))
+        .unwrap_or(first);
+    Some(lines[first..=last].join("\n"))
+}
+
+/// The editor `git commit` would open: `GIT_EDITOR`, `core.editor`,
+/// `VISUAL`, `EDITOR`, or `vi`, as a shell command.
+pub fn editor(repo_path: &Path) -> Result<String> {
+    Ok(run_git_command(repo_path, &["var", "GIT_EDITOR"])?
+        .trim()
+        .to_string())
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::metadata(path)
+            .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+/// The output of `git --version`, e.g. `git version 2.43.0`.
+pub fn get_git_version() -> Result<String> {
+    let output = git_command().arg("--version").output()?;
+    if !output.status.success() {
+        anyhow::bail!("{}", String::from_utf8_lossy(&output.stderr).trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `git blame --line-porcelain` of the `ranges` of old lines of `path`, as
+/// of `HEAD`.
+pub fn blame_lines(repo_path: &Path, path: &str, ranges: &[(usize, usize)]) -> Result<String> {
+    let ranges: Vec<String> = ranges
+        .iter()
+        .map(|(first, last)| format!("-L{first},{last}"))
+        .collect();
+    let mut args = vec!["blame", "--line-porcelain"];
+    args.extend(ranges.iter().map(String::as_str));
+    args.extend(["HEAD", "--", path]);
+    run_git_command(repo_path, &args)
+}
+
 pub fn get_current_branch_name(repo_path: &Path) -> Result<String> {
     let output = git_command()
         .arg("rev-parse")
@@ -951,7 +3211,71 @@ pub fn checkout_orphan_branch(repo_path: &Path, branch_name: &str) -> Result<()>
     Ok(())
 }
 
+fn has_head(repo_path: &Path) -> bool {
+    git_command()
+        .arg("rev-parse")
+        .arg("--verify")
+        .arg("--quiet")
+        .arg("HEAD")
+        .current_dir(repo_path)
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+// Older git versions refuse to cherry-pick onto an unborn branch, which is
+// exactly where a root reorder starts. Replay the commit's patch and reuse
+// its metadata instead.
+fn cherry_pick_onto_unborn_branch(repo_path: &Path, commit_hash: &str) -> Result<()> {
+    let patch = run_git_command(
+        repo_path,
+        &[
+            "diff-tree",
+            "-p",
+            "--binary",
+            "--root",
+            "--no-commit-id",
+            commit_hash,
+        ],
+    )?;
+    if !patch.trim().is_empty() {
+        apply_patch_to_index_and_worktree(repo_path, &patch)?;
+    }
+    run_git_command(repo_path, &["commit", "--allow-empty", "-C", commit_hash])?;
+    Ok(())
+}
+
+fn apply_patch_to_index_and_worktree(repo_path: &Path, patch: &str) -> Result<()> {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let mut child = git_command()
+        .arg("apply")
+        .arg("--index")
+        .arg("-")
+        .current_dir(repo_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(patch.as_bytes())?;
+    }
+
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git apply --index failed. Stderr: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(())
+}
+
 pub fn cherry_pick(repo_path: &Path, commit_hash: &str) -> Result<()> {
+    if !has_head(repo_path) {
+        return cherry_pick_onto_unborn_branch(repo_path, commit_hash);
+    }
     let output = git_command()
         .arg("cherry-pick")
         .arg("--allow-empty")
@@ -994,6 +3318,44 @@ pub fn reset_hard(repo_path: &Path, target: &str) -> Result<()> {
     Ok(())
 }
 
+/// Moves the branch to `target`, keeping the index and the working tree.
+pub fn reset_soft(repo_path: &Path, target: &str) -> Result<()> {
+    run_git_command(repo_path, &["reset", "-q", "--soft", target]).map(|_| ())
+}
+
+/// Moves the branch to `target`, keeping the local changes. Fails without
+/// changing anything if they touch files that differ in `target`.
+pub fn reset_keep(repo_path: &Path, target: &str) -> Result<()> {
+    run_git_command(repo_path, &["reset", "-q", "--keep", target]).map(|_| ())
+}
+
+/// Whether a rebase stopped in the middle, e.g. because aborting it failed.
+pub fn is_rebase_in_progress(repo_path: &Path) -> bool {
+    ["rebase-merge", "rebase-apply"]
+        .iter()
+        .any(|name| git_path(repo_path, name).is_ok_and(|path| path.exists()))
+}
+
+/// The full hash of `HEAD`.
+pub fn head_hash(repo_path: &Path) -> Result<String> {
+    Ok(run_git_command(repo_path, &["rev-parse", "HEAD"])?
+        .trim()
+        .to_string())
+}
+
+/// Writes the index as a tree object and returns its hash, to put it back
+/// later with [`read_tree`].
+pub fn write_tree(repo_path: &Path) -> Result<String> {
+    Ok(run_git_command(repo_path, &["write-tree"])?
+        .trim()
+        .to_string())
+}
+
+/// Replaces the index with `tree`, leaving the working tree alone.
+pub fn read_tree(repo_path: &Path, tree: &str) -> Result<()> {
+    run_git_command(repo_path, &["read-tree", tree]).map(|_| ())
+}
+
 pub fn run_git_command(repo_path: &Path, args: &[&str]) -> Result<String> {
     let output = git_command().args(args).current_dir(repo_path).output()?;
     if !output.status.success() {
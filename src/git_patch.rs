@@ -8,6 +8,68 @@ pub fn find_hunk(file: &FileDiff, line_index: usize) -> Option<&Hunk> {
     })
 }
 
+/// The hunk of `file` sharing the most lines with `lines`, compared without
+/// their `+`/`-`/` ` marks, so a hunk is found again after the file was
+/// edited, shifting it, or after it moved to the other side of the index.
+/// `None` if no hunk shares a line.
+pub fn find_similar_hunk<'a>(file: &'a FileDiff, lines: &[String]) -> Option<&'a Hunk> {
+    fn content(line: &str) -> &str {
+        line.get(1..).unwrap_or_default()
+    }
+    let wanted: Vec<&str> = lines
+        .iter()
+        .filter(|line| !line.starts_with("@@"))
+        .map(|line| content(line))
+        .collect();
+    let mut best: Option<(usize, &Hunk)> = None;
+    for hunk in &file.hunks {
+        let shared = hunk
+            .lines
+            .iter()
+            .filter(|line| !line.starts_with("@@") && wanted.contains(&content(line)))
+            .count();
+        if shared > 0 && best.is_none_or(|(most, _)| shared > most) {
+            best = Some((shared, hunk));
+        }
+    }
+    best.map(|(_, hunk)| hunk)
+}
+
+/// Where the line at `line_index` of `before` is in `after`, the same file
+/// diffed again with more or fewer lines of context: the same line while it
+/// is still shown, otherwise the first change of its hunk. A hunk header
+/// stands for the first change below it.
+pub fn find_same_line(before: &FileDiff, line_index: usize, after: &FileDiff) -> Option<usize> {
+    fn first_change(hunk: &Hunk) -> usize {
+        hunk.lines
+            .iter()
+            .position(|line| line.starts_with(['+', '-']))
+            .unwrap_or(0)
+    }
+    // Line numbers and text together tell lines apart, changes are shown
+    // with any context.
+    let find = |hunk: &Hunk, offset: usize| {
+        let numbers = hunk.line_numbers.get(offset)?;
+        let text = hunk.lines.get(offset)?;
+        after.hunks.iter().find_map(|candidate| {
+            (1..candidate.lines.len())
+                .find(|&i| {
+                    candidate.line_numbers.get(i) == Some(numbers) && &candidate.lines[i] == text
+                })
+                .map(|i| candidate.start_line + i)
+        })
+    };
+
+    let hunk = find_hunk(before, line_index)?;
+    let offset = line_index - hunk.start_line;
+    let offset = if offset == 0 {
+        first_change(hunk)
+    } else {
+        offset
+    };
+    find(hunk, offset).or_else(|| find(hunk, first_change(hunk)))
+}
+
 pub fn create_unstage_line_patch(
     file: &FileDiff,
     line_index: usize,
@@ -57,6 +119,19 @@ pub fn create_unstage_hunk_patch(file: &FileDiff, hunk: &Hunk) -> String {
     patch
 }
 
+/// Several hunks of `file` as one patch, in the order of the diff.
+pub fn create_hunks_patch(file: &FileDiff, hunks: &[&Hunk]) -> String {
+    let mut patch = String::new();
+    patch.push_str(&format!("diff --git a/{0} b/{0}\n", file.file_name));
+    patch.push_str(&format!("--- a/{0}\n", file.file_name));
+    patch.push_str(&format!("+++ b/{0}\n", file.file_name));
+    for hunk in hunks {
+        patch.push_str(&hunk.lines.join("\n"));
+        patch.push('\n');
+    }
+    patch
+}
+
 // For staging, the logic is identical to unstaging
 pub fn create_stage_line_patch(file: &FileDiff, line_index: usize) -> Option<String> {
     create_unstage_line_patch(file, line_index, false)
@@ -66,6 +141,13 @@ pub fn create_stage_hunk_patch(file: &FileDiff, hunk: &Hunk) -> String {
     create_unstage_hunk_patch(file, hunk)
 }
 
+/// The whole diff of `file` as a patch, headers included.
+pub fn create_file_patch(file: &FileDiff) -> String {
+    let mut patch = file.lines.join("\n");
+    patch.push('\n');
+    patch
+}
+
 pub fn create_patch_for_new_file(file_name: &str, content: &str) -> String {
     let mut patch = String::new();
     patch.push_str(&format!("diff --git a/{file_name} b/{file_name}\n"));
@@ -107,3 +189,95 @@ pub fn get_line_number(file: &FileDiff, line_index: usize) -> Option<usize> {
         Some(*new_line_num)
     }
 }
+
+/// What `E` opens in the editor: the hunk, then a short guide like the one of
+/// `git add -p`. A staged hunk is unstaged by reverting it in the index, so
+/// its new side must keep matching the index; that flips the rules.
+pub fn create_hunk_edit_text(hunk: &Hunk, staging: bool) -> String {
+    let (action, kept, dropped) = if staging {
+        ("stage", '-', '+')
+    } else {
+        ("unstage", '+', '-')
+    };
+    let mut text = format!("# Manual hunk edit mode. Save and quit to {action} the hunk.\n");
+    for line in &hunk.lines {
+        text.push_str(line);
+        text.push('\n');
+    }
+    text.push_str(&format!(
+        "# ---\n\
+         # To remove '{kept}' lines, make them ' ' lines (context).\n\
+         # To remove '{dropped}' lines, delete them.\n\
+         # Lines starting with # will be removed.\n\
+         # If the patch doesn't apply, nothing is changed.\n"
+    ));
+    text
+}
+
+/// Turns the text saved by the editor back into a patch for `file_name`. The line
+/// counts of the hunk header are recounted, so lines can be added or removed
+/// freely. Empty lines are taken as empty context lines, as editors often
+/// strip the trailing space.
+pub fn create_edited_hunk_patch(file_name: &str, text: &str) -> Result<String, String> {
+    // Numbered like in the editor, for the error messages.
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.starts_with('#'));
+    let header = lines
+        .find(|(_, line)| !line.trim().is_empty())
+        .map(|(_, line)| line)
+        .filter(|line| line.starts_with("@@"))
+        .ok_or("the hunk header is missing")?;
+    let (old_start, new_start, section) =
+        parse_hunk_header(header).ok_or("the hunk header is invalid")?;
+
+    let mut body = Vec::new();
+    let (mut old_count, mut new_count, mut changes) = (0, 0, 0);
+    for (index, line) in lines {
+        let line = if line.is_empty() { " " } else { line };
+        match line.as_bytes()[0] {
+            b' ' => {
+                old_count += 1;
+                new_count += 1;
+            }
+            b'-' => {
+                old_count += 1;
+                changes += 1;
+            }
+            b'+' => {
+                new_count += 1;
+                changes += 1;
+            }
+            b'\\' => {}
+            _ => return Err(format!("line {} is not a diff line", index + 1)),
+        }
+        body.push(line);
+    }
+    if changes == 0 {
+        return Err("nothing is left to apply".to_string());
+    }
+
+    let mut patch = String::new();
+    patch.push_str(&format!("diff --git a/{file_name} b/{file_name}\n"));
+    patch.push_str(&format!("--- a/{file_name}\n"));
+    patch.push_str(&format!("+++ b/{file_name}\n"));
+    patch.push_str(&format!(
+        "@@ -{old_start},{old_count} +{new_start},{new_count} @@{section}\n"
+    ));
+    for line in body {
+        patch.push_str(line);
+        patch.push('\n');
+    }
+    Ok(patch)
+}
+
+/// The old and new start lines of `@@ -a,b +c,d @@ section`, and the
+/// section text after the closing `@@`.
+fn parse_hunk_header(header: &str) -> Option<(usize, usize, &str)> {
+    let rest = header.strip_prefix("@@ -")?;
+    let (ranges, section) = rest.split_once(" @@")?;
+    let (old, new) = ranges.split_once(" +")?;
+    let start = |range: &str| range.split(',').next()?.parse().ok();
+    Some((start(old)?, start(new)?, section))
+}
@@ -0,0 +1,116 @@
+use crate::git::{FileDiff, Hunk};
+
+/// The lines of the index a hunk changes, from `start` up to `end`. A hunk
+/// only adding lines changes none, `start == end` is where they go.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Span {
+    start: usize,
+    end: usize,
+}
+
+impl Span {
+    /// Whether the spans share a line or touch, in which case the context
+    /// of one patch holds lines the other changes.
+    fn meets(self, other: Span) -> bool {
+        self.start <= other.end && other.start <= self.end
+    }
+}
+
+/// Where `hunk` changes the index. A staged hunk counts its lines with the
+/// new side, which the index is, an unstaged hunk with the old side.
+fn index_span(hunk: &Hunk, staged: bool) -> Option<Span> {
+    let (mut line, index_side) = if staged {
+        (hunk.new_start, '+')
+    } else {
+        (hunk.old_start, '-')
+    };
+    let mut span: Option<Span> = None;
+    for diff_line in hunk.lines.iter().skip(1) {
+        let Some(kind) = diff_line.chars().next() else {
+            continue;
+        };
+        if kind == '+' || kind == '-' {
+            let end = if kind == index_side { line + 1 } else { line };
+            span = Some(match span {
+                Some(span) => Span {
+                    start: span.start.min(line),
+                    end: span.end.max(end),
+                },
+                None => Span { start: line, end },
+            });
+        }
+        if kind == ' ' || kind == index_side {
+            line += 1;
+        }
+    }
+    span
+}
+
+/// The staged hunks of `staged` whose lines overlap the unstaged hunks of
+/// `unstaged`, the same file's changes on the other side. Unstaging one of
+/// them mixes both changes in the working tree, and discarding it cannot
+/// leave the unstaged edits alone.
+pub fn overlapping_hunks<'a>(staged: &'a FileDiff, unstaged: &FileDiff) -> Vec<&'a Hunk> {
+    let unstaged_spans: Vec<Span> = unstaged
+        .hunks
+        .iter()
+        .filter_map(|hunk| index_span(hunk, false))
+        .collect();
+    staged
+        .hunks
+        .iter()
+        .filter(|hunk| {
+            index_span(hunk, true)
+                .is_some_and(|span| unstaged_spans.iter().any(|other| span.meets(*other)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::parse_diff;
+
+    fn diff(hunks: &str) -> FileDiff {
+        let text = format!("diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n{hunks}");
+        parse_diff(&text).remove(0)
+    }
+
+    #[test]
+    fn test_index_span_counts_the_index_side() {
+        let staged = diff("@@ -2,3 +2,4 @@\n 2\n-3\n+3 staged\n+new\n 4\n");
+        assert_eq!(
+            index_span(&staged.hunks[0], true),
+            Some(Span { start: 3, end: 5 })
+        );
+        let unstaged = diff("@@ -2,3 +2,2 @@\n 2\n-3\n-4\n");
+        assert_eq!(
+            index_span(&unstaged.hunks[0], false),
+            Some(Span { start: 3, end: 5 })
+        );
+        // Lines only added change nothing of the index
+        let unstaged = diff("@@ -2,2 +2,3 @@\n 2\n+added\n 3\n");
+        assert_eq!(
+            index_span(&unstaged.hunks[0], false),
+            Some(Span { start: 3, end: 3 })
+        );
+    }
+
+    #[test]
+    fn test_overlapping_hunks() {
+        let staged = diff(
+            "@@ -2,3 +2,3 @@\n 2\n-3\n+3 staged\n 4\n@@ -19,2 +19,2 @@\n-19\n+19 staged\n 20\n",
+        );
+        let unstaged = diff("@@ -3,2 +3,2 @@\n-3 staged\n+3 edited\n 4\n");
+        let overlapping = overlapping_hunks(&staged, &unstaged);
+        assert_eq!(overlapping.len(), 1);
+        assert_eq!(overlapping[0].new_start, 2);
+
+        // Right after the staged line, its context
+        let unstaged = diff("@@ -3,2 +3,2 @@\n 3 staged\n-4\n+4 edited\n");
+        assert_eq!(overlapping_hunks(&staged, &unstaged).len(), 1);
+
+        let unstaged = diff("@@ -10,1 +10,1 @@\n-10\n+10 edited\n");
+        assert!(overlapping_hunks(&staged, &unstaged).is_empty());
+    }
+}
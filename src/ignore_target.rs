@@ -0,0 +1,153 @@
+use crate::git;
+use anyhow::Result;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// The file an ignore pattern is written to.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum IgnoreTarget {
+    /// `.gitignore` at the root, staged so the pattern is committed.
+    #[default]
+    Gitignore,
+    /// `.git/info/exclude`, for this clone only.
+    InfoExclude,
+    /// `core.excludesFile`, for every repository of the user.
+    Global,
+}
+
+impl IgnoreTarget {
+    /// The next target, going around.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Gitignore => Self::InfoExclude,
+            Self::InfoExclude => Self::Global,
+            Self::Global => Self::Gitignore,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Gitignore => ".gitignore",
+            Self::InfoExclude => ".git/info/exclude",
+            Self::Global => "the global excludes file",
+        }
+    }
+
+    pub fn path(self, repo_path: &Path) -> PathBuf {
+        match self {
+            Self::Gitignore => repo_path.join(".gitignore"),
+            Self::InfoExclude => {
+                git::run_git_command(repo_path, &["rev-parse", "--git-path", "info/exclude"])
+                    .map(|path| repo_path.join(path.trim()))
+                    .unwrap_or_else(|_| repo_path.join(".git/info/exclude"))
+            }
+            Self::Global => global_excludes_file(repo_path),
+        }
+    }
+
+    /// Adds `pattern` to the file of this target, staging it when it is
+    /// `.gitignore`. Returns whether the file had to be made.
+    pub fn add(self, repo_path: &Path, pattern: &str) -> Result<bool> {
+        let path = self.path(repo_path);
+        let created = !path.exists();
+        append_pattern(&path, pattern)?;
+        self.stage(repo_path)?;
+        Ok(created)
+    }
+
+    /// Takes back the line [`IgnoreTarget::add`] wrote, and the file too
+    /// when `add` made it and nothing else was written to it since.
+    pub fn take_back(self, repo_path: &Path, pattern: &str, created: bool) -> Result<()> {
+        let path = self.path(repo_path);
+        remove_pattern(&path, pattern)?;
+        if created && fs::metadata(&path)?.len() == 0 {
+            fs::remove_file(&path)?;
+        }
+        self.stage(repo_path)
+    }
+
+    /// Stages `.gitignore`, or takes it out of the index once it is gone.
+    fn stage(self, repo_path: &Path) -> Result<()> {
+        if self == Self::Gitignore
+            && (self.path(repo_path).exists() || git::is_tracked(repo_path, ".gitignore"))
+        {
+            git::stage_paths(repo_path, &[".gitignore".to_string()])?;
+        }
+        Ok(())
+    }
+}
+
+/// `core.excludesFile`, or where git looks without it:
+/// `$XDG_CONFIG_HOME/git/ignore`, else `~/.config/git/ignore`.
+fn global_excludes_file(repo_path: &Path) -> PathBuf {
+    if let Ok(path) = git::run_git_command(
+        repo_path,
+        &["config", "--path", "--get", "core.excludesFile"],
+    ) && !path.trim().is_empty()
+    {
+        return repo_path.join(path.trim());
+    }
+    std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".config")))
+        .unwrap_or_default()
+        .join("git/ignore")
+}
+
+/// Adds `pattern` as the last line of `path`, making the file and its
+/// directory if needed.
+pub fn append_pattern(path: &Path, pattern: &str) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let content = fs::read_to_string(path).unwrap_or_default();
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    if !content.is_empty() && !content.ends_with('\n') {
+        writeln!(file)?;
+    }
+    writeln!(file, "{pattern}")?;
+    Ok(())
+}
+
+/// Takes the last line that is `pattern` out of `path`, leaving the other
+/// lines, comments and blank lines as they were.
+pub fn remove_pattern(path: &Path, pattern: &str) -> Result<()> {
+    let content = fs::read_to_string(path)?;
+    fs::write(path, without_last(&content, pattern))?;
+    Ok(())
+}
+
+fn without_last(content: &str, pattern: &str) -> String {
+    let mut lines: Vec<&str> = content.lines().collect();
+    if let Some(index) = lines.iter().rposition(|line| *line == pattern) {
+        lines.remove(index);
+    }
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_without_last_keeps_the_other_lines() {
+        let content = "# build output\n*.log\n\ntarget/\n*.log\n";
+        assert_eq!(
+            without_last(content, "*.log"),
+            "# build output\n*.log\n\ntarget/\n"
+        );
+        assert_eq!(without_last("a\n", "b"), "a\n");
+    }
+
+    #[test]
+    fn test_next_goes_around() {
+        let target = IgnoreTarget::default();
+        assert_eq!(target, IgnoreTarget::Gitignore);
+        assert_eq!(target.next().next().next(), target);
+    }
+}
@@ -0,0 +1,714 @@
+use pancurses::Input;
+
+/// An action of the staged and unstaged panes that can be bound to other
+/// keys in the `[keys]` section of the config.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Action {
+    /// The name used in the config, e.g. `stage`.
+    pub name: &'static str,
+    /// The keys it is bound to by default. The handlers match on the first.
+    pub default_keys: &'static [Input],
+    pub description: &'static str,
+}
+
+const fn action(
+    name: &'static str,
+    default_keys: &'static [Input],
+    description: &'static str,
+) -> Action {
+    Action {
+        name,
+        default_keys,
+        description,
+    }
+}
+
+/// Every action that can be rebound, in the order they are listed.
+pub const ACTIONS: &[Action] = &[
+    action(
+        "move_up",
+        &[Input::KeyUp, Input::Character('\u{10}')],
+        "Select the previous row",
+    ),
+    action(
+        "move_down",
+        &[Input::KeyDown, Input::Character('\u{e}')],
+        "Select the next row",
+    ),
+    action(
+        "diff_up",
+        &[Input::Character('k')],
+        "Move the diff cursor up",
+    ),
+    action(
+        "diff_down",
+        &[Input::Character('j')],
+        "Move the diff cursor down",
+    ),
+    action("scroll_left", &[Input::KeyLeft], "Scroll the diff left"),
+    action("scroll_right", &[Input::KeyRight], "Scroll the diff right"),
+    action(
+        "page_down",
+        &[Input::Character(' '), Input::Character('\u{16}')],
+        "Scroll a page down",
+    ),
+    action("page_up", &[Input::Character('\u{2}')], "Scroll a page up"),
+    action(
+        "half_page_down",
+        &[Input::Character('\u{4}')],
+        "Scroll half a page down",
+    ),
+    action(
+        "half_page_up",
+        &[Input::Character('\u{15}')],
+        "Scroll half a page up",
+    ),
+    action(
+        "switch_pane",
+        &[Input::Character('\t')],
+        "Switch between the staged and unstaged panes",
+    ),
+    action(
+        "stage",
+        &[Input::Character('u')],
+        "Stage or unstage the file or hunk",
+    ),
+    action(
+        "stage_line",
+        &[Input::Character('1')],
+        "Stage or unstage the line",
+    ),
+    action(
+        "stage_all",
+        &[Input::Character('R')],
+        "Stage everything again",
+    ),
+    action(
+        "stage_modifications",
+        &[Input::Character('M')],
+        "Stage the modified files, not the deletions",
+    ),
+    action(
+        "stage_deletions",
+        &[Input::Character('K')],
+        "Stage the deletions, not the modified files",
+    ),
+    action(
+        "export_staged_tree",
+        &[Input::Character('m')],
+        "Export the staged tree to a temporary directory and open it",
+    ),
+    action(
+        "format_staged",
+        &[Input::Character('Z')],
+        "Format the staged content and stage the fixes",
+    ),
+    action(
+        "test_staged_tree",
+        &[Input::Character('t')],
+        "Run staged_test_command on the staged tree",
+    ),
+    action(
+        "discard",
+        &[Input::Character('!')],
+        "Discard the file or hunk",
+    ),
+    action(
+        "fixup_hunk",
+        &[Input::Character('A')],
+        "Squash the unstaged hunk into a local commit",
+    ),
+    action(
+        "ignore",
+        &[Input::Character('i')],
+        "Add the file, its extension or its directory to .gitignore",
+    ),
+    action(
+        "rename",
+        &[Input::Character('r')],
+        "Rename the file, or restore a deleted one",
+    ),
+    action(
+        "toggle_executable",
+        &[Input::Character('x')],
+        "Toggle the executable bit",
+    ),
+    action("new_file", &[Input::Character('a')], "Create a new file"),
+    action(
+        "open_editor",
+        &[Input::Character('e')],
+        "Open the file in the editor",
+    ),
+    action(
+        "edit_hunk",
+        &[Input::Character('E')],
+        "Edit the hunk before staging it",
+    ),
+    action(
+        "toggle_diff_filter",
+        &[Input::Character('F')],
+        "Toggle the diff filter",
+    ),
+    action(
+        "toggle_file_order",
+        &[Input::Character('o')],
+        "Toggle the order of the staged files",
+    ),
+    action(
+        "toggle_word_diff",
+        &[Input::Character('w')],
+        "Toggle the word diff of the commit",
+    ),
+    action(
+        "collapse_files",
+        &[Input::Character('*')],
+        "Collapse or expand all files of the commit",
+    ),
+    action(
+        "toggle_rename_group",
+        &[Input::Character('z')],
+        "Expand or collapse renamed directories",
+    ),
+    action(
+        "toggle_path_abbreviation",
+        &[Input::Character('p')],
+        "Abbreviate directory names",
+    ),
+    action(
+        "toggle_patch_headers",
+        &[Input::Character('h')],
+        "Hide the patch headers",
+    ),
+    action(
+        "full_file_view",
+        &[Input::Character('V')],
+        "Show the whole file around the hunks",
+    ),
+    action(
+        "show_whitespace",
+        &[Input::Character('_')],
+        "Highlight trailing whitespace, mixed indents and missing newlines",
+    ),
+    action(
+        "more_context",
+        &[Input::Character('+')],
+        "Show more lines of context around the changes",
+    ),
+    action(
+        "less_context",
+        &[Input::Character('-')],
+        "Show fewer lines of context around the changes",
+    ),
+    action(
+        "other_side",
+        &[Input::Character('=')],
+        "Show the unstaged changes of a staged file, or the other way round",
+    ),
+    action(
+        "toggle_commit_hashes",
+        &[Input::Character('#')],
+        "Show no, short or full hashes in the log",
+    ),
+    action(
+        "copy_hash",
+        &[Input::Character('Y')],
+        "Copy the full hash of the commit",
+    ),
+    action("push", &[Input::Character('P')], "Push the branch"),
+    action(
+        "explain_hunk",
+        &[Input::Character('J')],
+        "Pipe the hunk to explain_command and show its output",
+    ),
+    action(
+        "preview_patch",
+        &[Input::Character('%')],
+        "Preview the patch staging or unstaging the hunk applies",
+    ),
+    action("fetch", &[Input::Character('G')], "Fetch from the remote"),
+    action(
+        "pull_rebase",
+        &[Input::Character('L')],
+        "Pull, rebasing the local commits onto the upstream",
+    ),
+    action(
+        "drop_commit",
+        &[Input::Character('d')],
+        "Drop the local commit from the branch",
+    ),
+    action(
+        "split_commit",
+        &[Input::Character('S')],
+        "Split the local commit, or restore it while splitting",
+    ),
+    action(
+        "change_author",
+        &[Input::Character('B')],
+        "Change the author of the local commit",
+    ),
+    action(
+        "grab_commit",
+        &[Input::Character('g')],
+        "Grab the commit to reorder it",
+    ),
+    action(
+        "history",
+        &[Input::Character('H')],
+        "Show the commit history",
+    ),
+    action(
+        "rebase_plan",
+        &[Input::Character('I')],
+        "Rebase the unpushed commits",
+    ),
+    action("stashes", &[Input::Character('s')], "Show the stashes"),
+    action(
+        "save_recipe",
+        &[Input::Character('c')],
+        "Save what is staged as a recipe",
+    ),
+    action(
+        "apply_recipe",
+        &[Input::Character('C')],
+        "Stage a saved recipe",
+    ),
+    action(
+        "copy_patch",
+        &[Input::Character('y')],
+        "Copy the hunk or the file as a patch",
+    ),
+    action(
+        "save_patch",
+        &[Input::Character('\u{17}')],
+        "Save the hunk or the file as a patch",
+    ),
+    action(
+        "apply_patch_file",
+        &[Input::Character('\u{12}')],
+        "Apply a patch file",
+    ),
+    action(
+        "never_commit",
+        &[Input::Character('X')],
+        "Mark the file or hunk never to commit",
+    ),
+    action(
+        "restore_from_commit",
+        &[Input::Character('O')],
+        "Restore the file from a commit",
+    ),
+    action(
+        "discard_bin",
+        &[Input::Character('T')],
+        "Show what was discarded",
+    ),
+    action(
+        "unified_list",
+        &[Input::Character('U')],
+        "Show all changed files in one list",
+    ),
+    action(
+        "worktrees",
+        &[Input::Character('W')],
+        "Switch to another worktree",
+    ),
+    action(
+        "maintenance",
+        &[Input::Character('\u{7}')],
+        "Show what the repository needs to stay fast",
+    ),
+    action(
+        "bookmark",
+        &[Input::Character('b')],
+        "Bookmark the file, or take its bookmark away",
+    ),
+    action(
+        "next_bookmark",
+        &[Input::Character('\'')],
+        "Jump to the next bookmarked file",
+    ),
+    action(
+        "previous_bookmark",
+        &[Input::Character('"')],
+        "Jump to the previous bookmarked file",
+    ),
+    action(
+        "next_hunk",
+        &[Input::Character(']')],
+        "Move the diff cursor to the next hunk",
+    ),
+    action(
+        "previous_hunk",
+        &[Input::Character('[')],
+        "Move the diff cursor to the previous hunk",
+    ),
+    action(
+        "next_file",
+        &[Input::Character('}')],
+        "Move the diff cursor to the next file",
+    ),
+    action(
+        "previous_file",
+        &[Input::Character('{')],
+        "Move the diff cursor to the previous file",
+    ),
+    action(
+        "messages",
+        &[Input::Character('l')],
+        "Show the recent messages",
+    ),
+    action(
+        "diagnostics",
+        &[Input::Character('D')],
+        "Show the diagnostics",
+    ),
+    action(
+        "final_review",
+        &[Input::Character('v')],
+        "Review what will be committed",
+    ),
+    action("search", &[Input::Character('/')], "Search the diff"),
+    action(
+        "command",
+        &[Input::Character(':')],
+        "Run a command, like :unstage 2-4 for hunks 2 to 4 or :add src/**",
+    ),
+    action(
+        "stat_panel",
+        &[Input::Character(';')],
+        "Show the diff stat of the staged changes",
+    ),
+    action(
+        "status_filter",
+        &[Input::Character('|')],
+        "Show only added, modified, deleted or renamed files",
+    ),
+    action(
+        "search_next",
+        &[Input::Character('n')],
+        "Go to the next match",
+    ),
+    action(
+        "search_previous",
+        &[Input::Character('N')],
+        "Go to the previous match",
+    ),
+    action("undo", &[Input::Character('<')], "Undo"),
+    action("redo", &[Input::Character('>')], "Redo"),
+    action(
+        "help",
+        &[Input::Character('?')],
+        "Show the keys, and the tutorial",
+    ),
+    action(
+        "quit",
+        &[Input::Character('q')],
+        "Leave the diff cursor, or quit",
+    ),
+    action("quit_all", &[Input::Character('Q')], "Quit"),
+];
+
+/// Reads a key as written in the config: a single character, a name like
+/// `Enter`, `Tab`, `Space`, `Esc`, `Up`, `PageDown`, or `Ctrl-x`.
+pub fn parse_key(text: &str) -> Option<Input> {
+    let mut chars = text.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(Input::Character(c));
+    }
+    if let Some(letter) = text
+        .strip_prefix("Ctrl-")
+        .or_else(|| text.strip_prefix("C-"))
+    {
+        let mut chars = letter.chars();
+        return match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphabetic() => Some(Input::Character(char::from(
+                c.to_ascii_lowercase() as u8 - b'a' + 1,
+            ))),
+            _ => None,
+        };
+    }
+    Some(match text {
+        "Enter" => Input::Character('\n'),
+        "Tab" => Input::Character('\t'),
+        "Space" => Input::Character(' '),
+        "Esc" => Input::Character('\u{1b}'),
+        "Up" => Input::KeyUp,
+        "Down" => Input::KeyDown,
+        "Left" => Input::KeyLeft,
+        "Right" => Input::KeyRight,
+        "PageUp" => Input::KeyPPage,
+        "PageDown" => Input::KeyNPage,
+        "Home" => Input::KeyHome,
+        "End" => Input::KeyEnd,
+        _ => return None,
+    })
+}
+
+/// How a key is written in the config, the reverse of [`parse_key`].
+pub fn key_name(key: &Input) -> String {
+    match key {
+        Input::Character('\n') => "Enter".to_string(),
+        Input::Character('\t') => "Tab".to_string(),
+        Input::Character(' ') => "Space".to_string(),
+        Input::Character('\u{1b}') => "Esc".to_string(),
+        Input::Character(c) if ('\u{1}'..='\u{1a}').contains(c) => {
+            format!("Ctrl-{}", char::from(*c as u8 - 1 + b'a'))
+        }
+        Input::Character(c) => c.to_string(),
+        Input::KeyUp => "Up".to_string(),
+        Input::KeyDown => "Down".to_string(),
+        Input::KeyLeft => "Left".to_string(),
+        Input::KeyRight => "Right".to_string(),
+        Input::KeyPPage => "PageUp".to_string(),
+        Input::KeyNPage => "PageDown".to_string(),
+        Input::KeyHome => "Home".to_string(),
+        Input::KeyEnd => "End".to_string(),
+        other => format!("{other:?}"),
+    }
+}
+
+/// The keys of every action, after the `[keys]` section of the config.
+///
+/// The handlers of the panes match on the default keys, so a key is
+/// translated to the default key of the action it is bound to before it
+/// reaches them. A default key whose action was bound elsewhere does
+/// nothing, unless another action is bound to it. Keys from the config win
+/// over the default keys of other actions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyMap {
+    /// The keys of each action of [`ACTIONS`], in the same order.
+    keys: Vec<Vec<Input>>,
+    /// Whether the keys of each action come from the config.
+    rebound: Vec<bool>,
+    /// What is wrong with the bindings, see [`KeyMap::problems`].
+    problems: Vec<String>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self {
+            keys: ACTIONS
+                .iter()
+                .map(|action| action.default_keys.to_vec())
+                .collect(),
+            rebound: vec![false; ACTIONS.len()],
+            problems: Vec::new(),
+        }
+    }
+}
+
+impl KeyMap {
+    /// Applies `action = "keys"` bindings, where the keys are separated by
+    /// spaces, e.g. `stage = "Space u"`. Unknown actions and keys are
+    /// ignored, and reported with the other problems.
+    pub fn new(bindings: &[(String, String)]) -> Self {
+        let mut keymap = Self::default();
+        for (name, keys) in bindings {
+            let Some(index) = ACTIONS.iter().position(|action| action.name == name) else {
+                keymap.problems.push(format!("Unknown action {name}"));
+                continue;
+            };
+            let mut parsed = Vec::new();
+            for key in keys.split_whitespace() {
+                match parse_key(key) {
+                    Some(input) => parsed.push(input),
+                    None => keymap
+                        .problems
+                        .push(format!("Unknown key {key} for {name}")),
+                }
+            }
+            if !parsed.is_empty() {
+                keymap.keys[index] = parsed;
+                keymap.rebound[index] = true;
+            }
+        }
+        keymap.check();
+        keymap
+    }
+
+    /// Finds the keys the config binds to several actions, and the actions
+    /// left without a key that reaches them.
+    fn check(&mut self) {
+        for (index, keys) in self.keys.iter().enumerate() {
+            for key in keys {
+                if !self.rebound[index] {
+                    continue;
+                }
+                if let Some(other) = (index + 1..ACTIONS.len())
+                    .find(|&other| self.rebound[other] && self.keys[other].contains(key))
+                {
+                    self.problems.push(format!(
+                        "{} is bound to both {} and {}, {} gets it",
+                        key_name(key),
+                        ACTIONS[index].name,
+                        ACTIONS[other].name,
+                        ACTIONS[index].name
+                    ));
+                }
+            }
+        }
+        for (index, action) in ACTIONS.iter().enumerate() {
+            if !self.keys[index]
+                .iter()
+                .any(|key| self.owner(key) == Some(index))
+            {
+                self.problems.push(format!(
+                    "{} has no key left, other actions are bound to its keys",
+                    action.name
+                ));
+            }
+        }
+    }
+
+    /// What is wrong with the bindings of the config, in the order found:
+    /// unknown actions and keys, keys bound to several actions, and
+    /// actions no key reaches any more.
+    pub fn problems(&self) -> &[String] {
+        &self.problems
+    }
+
+    /// How many actions the config binds to other keys.
+    pub fn rebound_count(&self) -> usize {
+        self.rebound.iter().filter(|rebound| **rebound).count()
+    }
+
+    /// The index of the action `input` triggers. Keys from the config win
+    /// over default keys, and earlier actions over later ones.
+    fn owner(&self, input: &Input) -> Option<usize> {
+        let bound_by = |rebound: bool| {
+            (0..ACTIONS.len())
+                .find(|&index| self.rebound[index] == rebound && self.keys[index].contains(input))
+        };
+        bound_by(true).or_else(|| bound_by(false))
+    }
+
+    /// The keys `action` is bound to.
+    pub fn keys(&self, action: &str) -> &[Input] {
+        ACTIONS
+            .iter()
+            .position(|candidate| candidate.name == action)
+            .map_or(&[], |index| &self.keys[index])
+    }
+
+    /// The name of the first key of `action`, to mention it in hints.
+    pub fn key_label(&self, action: &str) -> String {
+        self.keys(action).first().map_or_else(String::new, key_name)
+    }
+
+    /// The key the handlers know `input` by, or `None` if it was unbound.
+    pub fn translate(&self, input: Input) -> Option<Input> {
+        if let Some(index) = self.owner(&input) {
+            return ACTIONS[index].default_keys.first().copied();
+        }
+        let is_unbound = ACTIONS
+            .iter()
+            .any(|action| action.default_keys.contains(&input));
+        (!is_unbound).then_some(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bindings(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, keys)| (name.to_string(), keys.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_keys_round_trip() {
+        for name in [
+            "u", "Enter", "Tab", "Space", "Esc", "Ctrl-d", "PageDown", "Up",
+        ] {
+            assert_eq!(key_name(&parse_key(name).unwrap()), name);
+        }
+        assert_eq!(parse_key("C-D"), Some(Input::Character('\u{4}')));
+        assert_eq!(parse_key("Ctrl-1"), None);
+        assert_eq!(parse_key("Hyper"), None);
+    }
+
+    #[test]
+    fn test_rebound_keys_are_translated_to_the_defaults() {
+        let keymap = KeyMap::new(&bindings(&[
+            ("stage", "Space"),
+            ("page_down", "f Ctrl-f"),
+            ("bogus", "y"),
+            ("undo", "Hyper"),
+        ]));
+        assert_eq!(
+            keymap.translate(Input::Character(' ')),
+            Some(Input::Character('u'))
+        );
+        assert_eq!(
+            keymap.translate(Input::Character('f')),
+            Some(Input::Character(' '))
+        );
+        assert_eq!(
+            keymap.translate(Input::Character('\u{6}')),
+            Some(Input::Character(' '))
+        );
+        // The old keys do nothing, Ctrl-V was a default of page_down too
+        assert_eq!(keymap.translate(Input::Character('u')), None);
+        assert_eq!(keymap.translate(Input::Character('\u{16}')), None);
+        // Keys of no action pass through, invalid bindings keep the default
+        assert_eq!(
+            keymap.translate(Input::Character('y')),
+            Some(Input::Character('y'))
+        );
+        assert_eq!(
+            keymap.translate(Input::Character('<')),
+            Some(Input::Character('<'))
+        );
+        assert_eq!(keymap.keys("stage"), &[Input::Character(' ')]);
+    }
+
+    #[test]
+    fn test_default_keys_have_no_problems() {
+        assert!(KeyMap::default().problems().is_empty());
+        assert!(KeyMap::new(&[]).problems().is_empty());
+        assert_eq!(KeyMap::new(&[]).rebound_count(), 0);
+    }
+
+    #[test]
+    fn test_problems_of_the_bindings() {
+        let keymap = KeyMap::new(&bindings(&[
+            ("stage", "Space f"),
+            ("bogus", "y"),
+            ("undo", "Hyper"),
+            ("page_down", "f"),
+            ("page_up", "Ctrl-v"),
+        ]));
+        assert_eq!(
+            keymap.problems(),
+            [
+                "Unknown action bogus",
+                "Unknown key Hyper for undo",
+                "f is bound to both page_down and stage, page_down gets it",
+            ]
+        );
+        assert_eq!(keymap.rebound_count(), 3);
+
+        let keymap = KeyMap::new(&bindings(&[("stage", "Space"), ("move_down", "Ctrl-v")]));
+        assert_eq!(
+            keymap.problems(),
+            ["page_down has no key left, other actions are bound to its keys"]
+        );
+    }
+
+    #[test]
+    fn test_bound_keys_win_over_defaults_of_other_actions() {
+        let keymap = KeyMap::new(&bindings(&[("stage", "Space")]));
+        assert_eq!(
+            keymap.translate(Input::Character(' ')),
+            Some(Input::Character('u'))
+        );
+        // Page down keeps its other default key
+        assert_eq!(
+            keymap.translate(Input::Character('\u{16}')),
+            Some(Input::Character(' '))
+        );
+    }
+}
@@ -1,26 +1,108 @@
 use anyhow::Result;
 use std::path::PathBuf;
 
+pub mod alert;
 pub mod app_state;
 pub mod background;
+pub mod blame_summary;
 pub mod command;
+pub mod commit_lint;
+pub mod commit_scope;
 mod commit_storage;
+pub mod config;
 pub mod cursor_state;
+pub mod diagnostics;
+pub mod diff_filter;
+pub mod diff_stat;
+pub mod edit_stage;
+pub mod empty_commit;
 pub mod external_command;
+pub mod formatter;
 pub mod git;
 pub mod git_patch;
+pub mod hunk_overlap;
+pub mod ignore_target;
+pub mod keymap;
+pub mod maintenance;
+pub mod message_log;
+pub mod never_commit;
+pub mod notify;
+pub mod profile;
+pub mod recipe;
+pub mod repo_init;
+pub mod repo_selection;
+pub mod split_commit;
+pub mod staged_test;
 pub mod ui;
 pub mod util;
+pub mod whitespace;
 
-pub fn run(repo_path: PathBuf, debug: bool) -> Result<()> {
-    let staged_diff_output = git::get_staged_diff_output(&repo_path)?;
+/// Overrides `max_file_size` of the config, with a size like `--max-file-size`.
+pub const MAX_FILE_SIZE_ENV: &str = "GIT_FULL_COMMIT_MAX_FILE_SIZE";
 
-    if staged_diff_output.stdout.is_empty() {
-        git::add_all(&repo_path)?;
+/// Runs the UI on `repo_path`, and again on each worktree switched to. With
+/// `show_profile`, how long each startup phase took is printed once it
+/// exits. `max_file_size` wins over the environment and the config.
+pub fn run(
+    mut repo_path: PathBuf,
+    debug: bool,
+    show_profile: bool,
+    max_file_size: Option<u64>,
+) -> Result<()> {
+    if let Some(reason) = util::terminal::unusable_reason() {
+        print_staged_status(&repo_path)?;
+        anyhow::bail!(
+            "Cannot start the UI: {reason}. Run git-full-commit in a terminal, \
+             with TERM set to a type this system knows (e.g. TERM=xterm-256color)."
+        );
     }
 
-    let files = git::get_diff(repo_path.clone());
-    ui::tui_loop(repo_path.clone(), files, debug);
+    let mut config = config::Config::load();
+    if let Some(size) = max_file_size.or_else(|| {
+        std::env::var(MAX_FILE_SIZE_ENV)
+            .ok()
+            .and_then(|size| util::file_size::parse_size(&size))
+    }) {
+        config.max_file_size = Some(size);
+    }
+    let size_limit = config.max_file_size.unwrap_or(git::DEFAULT_MAX_FILE_SIZE);
+
+    loop {
+        let mut startup = profile::Phases::default();
+        let staged_diff_output = startup.time("status snapshot", || {
+            git::get_staged_diff_output(&repo_path)
+        })?;
 
+        if staged_diff_output.stdout.is_empty() {
+            startup.time("add all", || {
+                git::add_all_with_size_limit(&repo_path, size_limit)
+            })?;
+        }
+
+        let files = startup.time("diff parse", || {
+            git::get_diff_with_context(repo_path.clone(), config.diff_context)
+        });
+        let (profile, relaunch) =
+            ui::tui_loop(repo_path.clone(), files, config.clone(), debug, startup);
+        if show_profile {
+            eprintln!("{}", profile.report());
+        }
+        match relaunch {
+            Some(worktree) => repo_path = worktree,
+            None => return Ok(()),
+        }
+    }
+}
+
+/// What would be committed, printed when the UI cannot start. Nothing is
+/// staged for it, so the index is left as it was.
+fn print_staged_status(repo_path: &std::path::Path) -> Result<()> {
+    let stat = git::get_staged_diff_stat(repo_path)?;
+    if stat.is_empty() {
+        println!("Nothing is staged.");
+    } else {
+        println!("Staged changes:");
+        print!("{stat}");
+    }
     Ok(())
 }
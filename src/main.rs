@@ -1,8 +1,12 @@
-use anyhow::{Result, bail};
+use anyhow::Result;
 use clap::Parser;
+use git_full_commit::config::Config;
+use git_full_commit::diagnostics::Diagnostics;
+use git_full_commit::git;
+use git_full_commit::repo_selection::select_repo;
 use git_full_commit::run;
+use git_full_commit::util::file_size::parse_size;
 use std::path::PathBuf;
-use std::process::Command;
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -11,9 +15,30 @@ struct Args {
     #[arg(short, long)]
     repo: Option<PathBuf>,
 
+    /// Ask again which repository to use when started inside nested repositories
+    #[arg(long)]
+    choose_repo: bool,
+
     /// Enable debug logging
     #[arg(long)]
     debug: bool,
+
+    /// Print how long the startup phases took on exit
+    #[arg(long)]
+    profile: bool,
+
+    /// Print the version, the git version and the config paths, then exit
+    #[arg(long)]
+    doctor: bool,
+
+    /// Leave untracked files larger than this out when staging everything,
+    /// e.g. 500M or 2G (default 100M)
+    #[arg(long, value_name = "SIZE", value_parser = parse_max_file_size)]
+    max_file_size: Option<u64>,
+}
+
+fn parse_max_file_size(size: &str) -> Result<u64, String> {
+    parse_size(size).ok_or_else(|| format!("not a size: {size}"))
 }
 
 fn main() -> Result<()> {
@@ -22,19 +47,21 @@ fn main() -> Result<()> {
         // Truncate the log file
         let _ = std::fs::File::create("debug.log");
     }
+    if args.doctor {
+        let repo_path = args.repo.or_else(|| {
+            let cwd = std::env::current_dir().ok()?;
+            git::get_enclosing_repos(&cwd).into_iter().next()
+        });
+        let diagnostics = Diagnostics::collect(repo_path.as_deref(), &Config::load(), None);
+        for line in diagnostics.lines() {
+            println!("{line}");
+        }
+        return Ok(());
+    }
     let repo_path = match args.repo {
         Some(path) => path,
-        None => {
-            let output = Command::new("git")
-                .arg("rev-parse")
-                .arg("--show-toplevel")
-                .output()?;
-            if !output.status.success() {
-                bail!("fatal: not a git repository (or any of the parent directories): .git");
-            }
-            PathBuf::from(String::from_utf8(output.stdout)?.trim())
-        }
+        None => select_repo(&std::env::current_dir()?, args.choose_repo)?,
     };
-    run(repo_path, args.debug)?;
+    run(repo_path, args.debug, args.profile, args.max_file_size)?;
     Ok(())
 }
@@ -0,0 +1,269 @@
+use crate::git::{self, ObjectCounts, StatusCaches};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// Loose objects past which `git gc --auto` packs them, when `gc.auto` is
+/// not set.
+const DEFAULT_GC_AUTO: i64 = 6700;
+
+/// Packs past which `git gc --auto` combines them, when
+/// `gc.autoPackLimit` is not set.
+const DEFAULT_GC_AUTO_PACK_LIMIT: i64 = 50;
+
+/// Files in the index from which the repository counts as large, where
+/// index version 4 and the status caches pay off.
+pub const LARGE_INDEX_ENTRIES: u32 = 10_000;
+
+/// What is read about the repository to tell what maintenance it needs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RepoHealth {
+    pub objects: ObjectCounts,
+    /// `gc.auto`, 0 turning automatic packing off.
+    pub gc_auto: i64,
+    /// `gc.autoPackLimit`, 0 turning it off too.
+    pub gc_auto_pack_limit: i64,
+    /// The version and the entries of the index, if there is one.
+    pub index: Option<(u32, u32)>,
+    pub caches: StatusCaches,
+    pub has_fsmonitor_daemon: bool,
+}
+
+impl RepoHealth {
+    pub fn read(repo_path: &Path) -> Self {
+        let index = git::read_index_header(repo_path);
+        let is_large = index.is_some_and(|(_, entries)| entries >= LARGE_INDEX_ENTRIES);
+        Self {
+            objects: git::count_objects(repo_path).unwrap_or_default(),
+            gc_auto: git::get_config_int(repo_path, "gc.auto").unwrap_or(DEFAULT_GC_AUTO),
+            gc_auto_pack_limit: git::get_config_int(repo_path, "gc.autoPackLimit")
+                .unwrap_or(DEFAULT_GC_AUTO_PACK_LIMIT),
+            index,
+            caches: StatusCaches::read(repo_path),
+            // Only asked for when it matters, as it starts git once more.
+            has_fsmonitor_daemon: is_large && git::has_fsmonitor_daemon(repo_path),
+        }
+    }
+}
+
+/// A problem found in the repository, and the git commands fixing it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub problem: String,
+    /// The arguments of each git command, run in order.
+    pub commands: Vec<Vec<&'static str>>,
+    /// Whether reading the working tree is timed before and after, for
+    /// commands making refreshes faster.
+    pub timed: bool,
+}
+
+impl Suggestion {
+    fn new(problem: String, args: &[&'static str]) -> Self {
+        Self {
+            problem,
+            commands: vec![args.to_vec()],
+            timed: false,
+        }
+    }
+
+    /// The commands as typed in a shell.
+    pub fn command(&self) -> String {
+        let commands: Vec<String> = self
+            .commands
+            .iter()
+            .map(|args| format!("git {}", args.join(" ")))
+            .collect();
+        commands.join(" && ")
+    }
+}
+
+/// What `health` calls for, the most pressing first.
+pub fn suggestions(health: &RepoHealth) -> Vec<Suggestion> {
+    let mut suggestions = Vec::new();
+    let loose = health.objects.loose as i64;
+    if health.gc_auto > 0 && loose > health.gc_auto {
+        suggestions.push(Suggestion::new(
+            format!(
+                "Too many loose objects: {loose}, gc.auto packs them past {}",
+                health.gc_auto
+            ),
+            &["maintenance", "run"],
+        ));
+    }
+    let packs = health.objects.packs as i64;
+    if health.gc_auto_pack_limit > 0 && packs > health.gc_auto_pack_limit {
+        suggestions.push(Suggestion::new(
+            format!(
+                "gc is needed: {packs} packs, gc.autoPackLimit combines them past {}",
+                health.gc_auto_pack_limit
+            ),
+            &["maintenance", "run"],
+        ));
+    }
+    let Some((version, entries)) = health.index else {
+        return suggestions;
+    };
+    if entries < LARGE_INDEX_ENTRIES {
+        return suggestions;
+    }
+    if version < 4 {
+        suggestions.push(Suggestion::new(
+            format!(
+                "The index of {entries} files is version {version}, version 4 is smaller and faster to read"
+            ),
+            &["update-index", "--index-version", "4"],
+        ));
+    }
+
+    let mut off = Vec::new();
+    let mut commands = Vec::new();
+    // Where git has no monitor of its own, turning it on does nothing.
+    if !health.caches.fsmonitor && health.has_fsmonitor_daemon {
+        off.push("core.fsmonitor");
+        commands.push(vec!["config", "core.fsmonitor", "true"]);
+    }
+    if !health.caches.untracked_cache {
+        off.push("core.untrackedCache");
+        commands.push(vec!["config", "core.untrackedCache", "true"]);
+    }
+    if !off.is_empty() {
+        suggestions.push(Suggestion {
+            problem: format!(
+                "{} {} off, so each refresh reads all {entries} files",
+                off.join(" and "),
+                if off.len() == 1 { "is" } else { "are" }
+            ),
+            commands,
+            timed: true,
+        });
+    }
+    suggestions
+}
+
+/// How long a refresh takes to read the working tree: the unstaged diff,
+/// then the untracked files as `caches` allow.
+fn time_working_tree(repo_path: &Path, caches: StatusCaches) -> Duration {
+    let started = Instant::now();
+    git::get_unstaged_diff(repo_path);
+    let _ = git::list_untracked_files(repo_path, caches);
+    started.elapsed()
+}
+
+/// Runs the commands of `suggestion`. Returns what to tell, with how long
+/// reading the working tree took before and after if it is timed.
+pub fn run(repo_path: &Path, suggestion: &Suggestion) -> Result<String, String> {
+    let before = suggestion
+        .timed
+        .then(|| time_working_tree(repo_path, StatusCaches::read(repo_path)));
+    for args in &suggestion.commands {
+        git::run_for_message(repo_path, args).map_err(|e| e.to_string())?;
+    }
+    let Some(before) = before else {
+        return Ok(format!("Ran {}", suggestion.command()));
+    };
+    let caches = StatusCaches::read(repo_path);
+    // The first read fills the caches, the second takes as long as
+    // refreshes will from now on.
+    time_working_tree(repo_path, caches);
+    let after = time_working_tree(repo_path, caches);
+    Ok(format!(
+        "Ran {}: reading the working tree took {} ms, now {} ms",
+        suggestion.command(),
+        before.as_millis(),
+        after.as_millis()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn large_repo() -> RepoHealth {
+        RepoHealth {
+            gc_auto: DEFAULT_GC_AUTO,
+            gc_auto_pack_limit: DEFAULT_GC_AUTO_PACK_LIMIT,
+            index: Some((2, LARGE_INDEX_ENTRIES)),
+            has_fsmonitor_daemon: true,
+            ..Default::default()
+        }
+    }
+
+    fn commands(health: &RepoHealth) -> Vec<String> {
+        suggestions(health)
+            .iter()
+            .map(Suggestion::command)
+            .collect()
+    }
+
+    #[test]
+    fn test_large_repos_are_offered_index_version_4_and_the_caches() {
+        assert_eq!(
+            commands(&large_repo()),
+            [
+                "git update-index --index-version 4",
+                "git config core.fsmonitor true && git config core.untrackedCache true"
+            ]
+        );
+        assert_eq!(
+            suggestions(&large_repo())[1].problem,
+            "core.fsmonitor and core.untrackedCache are off, so each refresh reads all 10000 files"
+        );
+
+        let health = RepoHealth {
+            index: Some((4, LARGE_INDEX_ENTRIES)),
+            caches: StatusCaches {
+                fsmonitor: true,
+                untracked_cache: true,
+            },
+            ..large_repo()
+        };
+        assert!(commands(&health).is_empty());
+    }
+
+    #[test]
+    fn test_fsmonitor_is_left_out_where_git_has_none() {
+        let health = RepoHealth {
+            index: Some((4, LARGE_INDEX_ENTRIES)),
+            has_fsmonitor_daemon: false,
+            ..large_repo()
+        };
+        assert_eq!(commands(&health), ["git config core.untrackedCache true"]);
+        assert_eq!(
+            suggestions(&health)[0].problem,
+            "core.untrackedCache is off, so each refresh reads all 10000 files"
+        );
+    }
+
+    #[test]
+    fn test_small_repos_are_left_alone() {
+        let health = RepoHealth {
+            index: Some((2, LARGE_INDEX_ENTRIES - 1)),
+            ..large_repo()
+        };
+        assert!(commands(&health).is_empty());
+    }
+
+    #[test]
+    fn test_gc_is_offered_past_the_limits_of_gc_auto() {
+        let mut health = RepoHealth {
+            index: None,
+            ..large_repo()
+        };
+        health.objects = ObjectCounts {
+            loose: 6701,
+            packs: 51,
+        };
+        assert_eq!(
+            commands(&health),
+            ["git maintenance run", "git maintenance run"]
+        );
+        assert_eq!(
+            suggestions(&health)[0].problem,
+            "Too many loose objects: 6701, gc.auto packs them past 6700"
+        );
+
+        // 0 turns automatic packing off
+        health.gc_auto = 0;
+        health.gc_auto_pack_limit = 0;
+        assert!(commands(&health).is_empty());
+    }
+}
@@ -0,0 +1,150 @@
+use std::time::{Duration, Instant};
+
+/// How many status line messages are kept for the message log.
+pub const MESSAGE_LOG_LIMIT: usize = 200;
+
+/// How a status line message is colored, and labeled in the message log.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Success => "done",
+            Severity::Warning => "warn",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// A message on the status line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StatusMessage {
+    pub severity: Severity,
+    pub text: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub severity: Severity,
+    pub text: String,
+    pub at: Instant,
+}
+
+/// The status line messages of this session, oldest first.
+#[derive(Clone, Debug, Default)]
+pub struct MessageLog {
+    entries: Vec<Entry>,
+}
+
+impl MessageLog {
+    /// Appends `text` unless it repeats the last message, keeping at most
+    /// [`MESSAGE_LOG_LIMIT`].
+    pub fn push(&mut self, text: &str, severity: Severity) {
+        if self.entries.last().is_some_and(|entry| entry.text == text) {
+            return;
+        }
+        self.entries.push(Entry {
+            severity,
+            text: text.to_string(),
+            at: Instant::now(),
+        });
+        if self.entries.len() > MESSAGE_LOG_LIMIT {
+            self.entries.remove(0);
+        }
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// The texts of the last `count` messages, oldest first.
+    pub fn recent_texts(&self, count: usize) -> Vec<String> {
+        let skip = self.entries.len().saturating_sub(count);
+        self.entries[skip..]
+            .iter()
+            .map(|entry| entry.text.clone())
+            .collect()
+    }
+}
+
+/// The line of `e` to show on the status line: the first one git printed
+/// to stderr, or else the first line of the message.
+pub fn reason(e: &anyhow::Error) -> String {
+    let text = e.to_string();
+    text.split_once("stderr")
+        .and_then(|(_, stderr)| {
+            stderr
+                .lines()
+                .skip(1)
+                .map(str::trim)
+                .find(|line| !line.is_empty())
+        })
+        .or_else(|| text.lines().next())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// How long ago a message was shown, in the largest whole unit.
+pub fn age(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    match seconds {
+        0..60 => format!("{seconds}s"),
+        60..3600 => format!("{}m", seconds / 60),
+        _ => format!("{}h", seconds / 3600),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_skips_repeats_and_keeps_the_newest() {
+        let mut log = MessageLog::default();
+        log.push("a", Severity::Info);
+        log.push("a", Severity::Info);
+        log.push("b", Severity::Error);
+        assert_eq!(log.recent_texts(20), vec!["a", "b"]);
+        assert_eq!(log.recent_texts(1), vec!["b"]);
+        assert_eq!(log.entries()[1].severity, Severity::Error);
+
+        for i in 0..MESSAGE_LOG_LIMIT {
+            log.push(&i.to_string(), Severity::Info);
+        }
+        assert_eq!(log.entries().len(), MESSAGE_LOG_LIMIT);
+        assert_eq!(log.entries()[0].text, "0");
+    }
+
+    #[test]
+    fn test_reason_is_what_git_printed() {
+        let e = anyhow::anyhow!(
+            "Git command failed: [\"add\", \"a.txt\"]\n---\nstdout:\n\n---\nstderr:\nfatal: pathspec 'a.txt' did not match any files\n"
+        );
+        assert_eq!(
+            reason(&e),
+            "fatal: pathspec 'a.txt' did not match any files"
+        );
+        let e = anyhow::anyhow!(
+            "git apply failed (reverse=false):\n--- stderr ---\nerror: patch failed: a.txt:1\nerror: a.txt: patch does not apply\n"
+        );
+        assert_eq!(reason(&e), "error: patch failed: a.txt:1");
+        assert_eq!(
+            reason(&anyhow::anyhow!("No such file\nmore")),
+            "No such file"
+        );
+    }
+
+    #[test]
+    fn test_age() {
+        assert_eq!(age(Duration::from_secs(5)), "5s");
+        assert_eq!(age(Duration::from_secs(125)), "2m");
+        assert_eq!(age(Duration::from_secs(7300)), "2h");
+    }
+}
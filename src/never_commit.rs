@@ -0,0 +1,231 @@
+use crate::config::Config;
+use crate::diff_filter;
+use crate::git::FileDiff;
+use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// Where the rules of each repository are kept, next to the config file.
+const RULES_DIR: &str = "never_commit";
+
+/// A local change that is kept out of every commit, like a `DEBUG=true`
+/// only meant for this machine.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Rule {
+    /// Files matching the glob, as in `diff_filters`.
+    File(String),
+    /// Added lines with this text, ignoring the whitespace around it.
+    Line(String),
+}
+
+/// The never-commit rules of one repository, marked with `X`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NeverCommit {
+    pub rules: Vec<Rule>,
+}
+
+/// A staged change a rule keeps out: a whole file, or the added line at
+/// an index of its diff.
+#[derive(Debug)]
+pub enum Guarded<'a> {
+    File(&'a FileDiff),
+    Line(&'a FileDiff, usize),
+}
+
+/// The file the rules of `repo_path` are saved in, one per repository.
+pub fn rules_path(repo_path: &Path) -> Option<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    repo_path.hash(&mut hasher);
+    Config::path().map(|path| {
+        path.with_file_name(RULES_DIR)
+            .join(format!("{}", hasher.finish()))
+    })
+}
+
+/// The text a line rule compares, or `None` for lines that are not added
+/// or are blank, which are never guarded.
+fn added_text(line: &str) -> Option<&str> {
+    if line.starts_with("+++ ") {
+        return None;
+    }
+    let text = line.strip_prefix('+')?.trim();
+    (!text.is_empty()).then_some(text)
+}
+
+impl NeverCommit {
+    pub fn load(repo_path: &Path) -> Self {
+        rules_path(repo_path)
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|content| Self::parse(&content))
+            .unwrap_or_default()
+    }
+
+    /// Reads `file <glob>` and `line <text>` rules, one per line. Blank
+    /// lines and lines starting with `#` are skipped.
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .filter_map(|line| match line.split_once(' ') {
+                Some(("file", glob)) if !glob.trim().is_empty() => {
+                    Some(Rule::File(glob.trim().to_string()))
+                }
+                Some(("line", text)) if !text.trim().is_empty() => {
+                    Some(Rule::Line(text.trim().to_string()))
+                }
+                _ => None,
+            })
+            .collect();
+        Self { rules }
+    }
+
+    pub fn to_text(&self) -> String {
+        self.rules
+            .iter()
+            .map(|rule| match rule {
+                Rule::File(glob) => format!("file {glob}\n"),
+                Rule::Line(text) => format!("line {text}\n"),
+            })
+            .collect()
+    }
+
+    pub fn save(&self, repo_path: &Path) -> std::io::Result<()> {
+        let path = rules_path(repo_path).ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, "Home directory not found")
+        })?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, self.to_text())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Whether a file rule matches `path`.
+    pub fn matches_file(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .any(|rule| matches!(rule, Rule::File(glob) if diff_filter::matches(glob, path)))
+    }
+
+    /// Whether a line rule matches the diff line `line`.
+    pub fn matches_line(&self, line: &str) -> bool {
+        added_text(line).is_some_and(|text| {
+            self.rules
+                .iter()
+                .any(|rule| matches!(rule, Rule::Line(rule_text) if rule_text == text))
+        })
+    }
+
+    /// Adds a file rule for `path`, or removes the one matching it exactly.
+    /// Returns whether the file is guarded now.
+    pub fn toggle_file(&mut self, path: &str) -> bool {
+        let rule = Rule::File(path.to_string());
+        if self.rules.contains(&rule) {
+            self.rules.retain(|existing| *existing != rule);
+            false
+        } else {
+            self.rules.push(rule);
+            true
+        }
+    }
+
+    /// Adds line rules for the added ones of the diff `lines`, or removes
+    /// them if all of them were guarded already. Returns whether they are
+    /// guarded now, or `None` if none of them is an added line.
+    pub fn toggle_lines(&mut self, lines: &[String]) -> Option<bool> {
+        let texts: Vec<&str> = lines.iter().filter_map(|line| added_text(line)).collect();
+        if texts.is_empty() {
+            return None;
+        }
+        let is_guarded = |rules: &[Rule], text: &str| {
+            rules
+                .iter()
+                .any(|rule| matches!(rule, Rule::Line(rule_text) if rule_text == text))
+        };
+        if texts.iter().all(|text| is_guarded(&self.rules, text)) {
+            self.rules
+                .retain(|rule| !matches!(rule, Rule::Line(text) if texts.contains(&text.as_str())));
+            return Some(false);
+        }
+        for text in texts {
+            if !is_guarded(&self.rules, text) {
+                self.rules.push(Rule::Line(text.to_string()));
+            }
+        }
+        Some(true)
+    }
+
+    /// The staged changes of `files` the rules keep out, in the order they
+    /// are listed. Lines of files shown through a textconv driver are not
+    /// theirs, so only file rules apply to those.
+    pub fn guarded<'a>(
+        &self,
+        files: &'a [FileDiff],
+        textconv_files: &HashSet<String>,
+    ) -> Vec<Guarded<'a>> {
+        let mut guarded = Vec::new();
+        for file in files {
+            if self.matches_file(&file.file_name) {
+                guarded.push(Guarded::File(file));
+            } else if !textconv_files.contains(&file.file_name) {
+                guarded.extend(
+                    file.hunks
+                        .iter()
+                        .flat_map(|hunk| hunk.start_line..hunk.start_line + hunk.lines.len())
+                        .filter(|&index| {
+                            file.lines
+                                .get(index)
+                                .is_some_and(|line| self.matches_line(line))
+                        })
+                        .map(|index| Guarded::Line(file, index)),
+                );
+            }
+        }
+        guarded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules_round_trip_and_match() {
+        let rules = NeverCommit::parse("# local only\nfile *.local\nline DEBUG=true\nbogus\n");
+        assert_eq!(
+            rules.rules,
+            vec![
+                Rule::File("*.local".to_string()),
+                Rule::Line("DEBUG=true".to_string()),
+            ]
+        );
+        assert_eq!(NeverCommit::parse(&rules.to_text()), rules);
+
+        assert!(rules.matches_file("config/app.local"));
+        assert!(!rules.matches_file("app.rs"));
+        assert!(rules.matches_line("+  DEBUG=true "));
+        assert!(!rules.matches_line("-DEBUG=true"));
+        assert!(!rules.matches_line(" DEBUG=true"));
+    }
+
+    #[test]
+    fn test_toggling_lines_skips_blank_and_context_lines() {
+        let mut rules = NeverCommit::default();
+        let lines: Vec<String> = ["@@ -1 +1,3 @@", " keep", "+DEBUG=true", "+", "-old"]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert_eq!(rules.toggle_lines(&lines), Some(true));
+        assert_eq!(rules.rules, vec![Rule::Line("DEBUG=true".to_string())]);
+        assert_eq!(rules.toggle_lines(&lines), Some(false));
+        assert!(rules.is_empty());
+        assert_eq!(rules.toggle_lines(&lines[..2]), None);
+
+        assert!(rules.toggle_file("a.local"));
+        assert!(!rules.toggle_file("a.local"));
+        assert!(rules.is_empty());
+    }
+}
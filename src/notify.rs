@@ -0,0 +1,108 @@
+use crate::config::Config;
+use std::time::Duration;
+
+/// Operations that finish faster than this are not worth a notification, as
+/// the user is most likely still looking at the terminal.
+pub const DEFAULT_NOTIFY_AFTER_SECONDS: u64 = 10;
+
+const TITLE: &str = "git-full-commit";
+
+/// Builds the notify command line for an operation that took `elapsed`, or
+/// `None` if no notification should be sent. The configured command is split
+/// on whitespace and gets the title and the message as its last arguments,
+/// e.g. `notify-send git-full-commit "Reorder finished"`.
+pub fn notification_command(
+    config: &Config,
+    operation: &str,
+    elapsed: Duration,
+    outcome: &Result<(), String>,
+) -> Option<Vec<String>> {
+    let command = config.notify_command.as_deref()?;
+    let after = config
+        .notify_after_seconds
+        .unwrap_or(DEFAULT_NOTIFY_AFTER_SECONDS);
+    if elapsed < Duration::from_secs(after) {
+        return None;
+    }
+
+    let mut args: Vec<String> = command.split_whitespace().map(String::from).collect();
+    if args.is_empty() {
+        return None;
+    }
+    let message = match outcome {
+        Ok(()) => format!("{operation} finished"),
+        Err(e) => format!("{operation} failed: {e}"),
+    };
+    args.push(TITLE.to_string());
+    args.push(message);
+    Some(args)
+}
+
+/// Runs a command built by [`notification_command`] and waits for it, so
+/// it is meant to be called off the UI thread.
+pub fn send(args: &[String]) {
+    let Some((program, rest)) = args.split_first() else {
+        return;
+    };
+    let _ = std::process::Command::new(program)
+        .args(rest)
+        .stdin(std::process::Stdio::null())
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(command: Option<&str>, after: Option<u64>) -> Config {
+        Config {
+            notify_command: command.map(String::from),
+            notify_after_seconds: after,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_no_notification_without_command() {
+        let config = config(None, Some(0));
+        assert_eq!(
+            notification_command(&config, "Reorder", Duration::from_secs(60), &Ok(())),
+            None
+        );
+    }
+
+    #[test]
+    fn test_short_operations_are_not_notified() {
+        let config = config(Some("notify-send"), None);
+        assert_eq!(
+            notification_command(&config, "Reorder", Duration::from_secs(9), &Ok(())),
+            None
+        );
+        assert!(
+            notification_command(&config, "Reorder", Duration::from_secs(10), &Ok(())).is_some()
+        );
+    }
+
+    #[test]
+    fn test_command_gets_title_and_outcome() {
+        let config = config(Some("notify-send -u low"), Some(0));
+        assert_eq!(
+            notification_command(&config, "Amend", Duration::ZERO, &Ok(())).unwrap(),
+            vec!["notify-send", "-u", "low", TITLE, "Amend finished"]
+        );
+        assert_eq!(
+            notification_command(
+                &config,
+                "Amend",
+                Duration::ZERO,
+                &Err("conflict".to_string())
+            )
+            .unwrap()
+            .last()
+            .unwrap(),
+            "Amend failed: conflict"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use std::time::{Duration, Instant};
+
+/// Refreshes slower than this show a warning on the status line.
+pub const DEFAULT_SLOW_REFRESH_MS: u64 = 1000;
+
+/// How many of the slowest phases a slow refresh warning names.
+const WARNING_PHASES: usize = 2;
+
+/// Durations of the steps of one operation, in the order they ran.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Phases(pub Vec<(&'static str, Duration)>);
+
+impl Phases {
+    /// Runs `f` and records how long it took as `name`.
+    pub fn time<T>(&mut self, name: &'static str, f: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let result = f();
+        self.0.push((name, started.elapsed()));
+        result
+    }
+
+    pub fn total(&self) -> Duration {
+        self.0.iter().map(|(_, elapsed)| *elapsed).sum()
+    }
+
+    fn report_lines(&self, lines: &mut Vec<String>) {
+        let width = self.0.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        for (name, elapsed) in &self.0 {
+            lines.push(format!("  {name:width$}  {}", format_duration(*elapsed)));
+        }
+        lines.push(format!(
+            "  {:width$}  {}",
+            "total",
+            format_duration(self.total())
+        ));
+    }
+}
+
+/// What `--profile` prints on exit: the startup phases and the slowest of
+/// the refreshes that followed.
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    pub startup: Phases,
+    pub refresh_count: usize,
+    pub slowest_refresh: Option<Phases>,
+}
+
+impl Profile {
+    pub fn record_refresh(&mut self, refresh: &Phases) {
+        self.refresh_count += 1;
+        if self
+            .slowest_refresh
+            .as_ref()
+            .is_none_or(|slowest| refresh.total() > slowest.total())
+        {
+            self.slowest_refresh = Some(refresh.clone());
+        }
+    }
+
+    pub fn report(&self) -> String {
+        let mut lines = vec!["Startup:".to_string()];
+        self.startup.report_lines(&mut lines);
+        lines.push(format!("Refreshes: {}", self.refresh_count));
+        if let Some(slowest) = &self.slowest_refresh {
+            lines.push("Slowest refresh:".to_string());
+            slowest.report_lines(&mut lines);
+        }
+        lines.join("\n")
+    }
+}
+
+/// The status line warning for a refresh that took `threshold` or longer,
+/// naming the phases that took the most time.
+pub fn slow_refresh_warning(refresh: &Phases, threshold: Duration) -> Option<String> {
+    let total = refresh.total();
+    if total < threshold {
+        return None;
+    }
+    let mut phases = refresh.0.clone();
+    phases.sort_by_key(|(_, elapsed)| std::cmp::Reverse(*elapsed));
+    let slowest: Vec<String> = phases
+        .iter()
+        .take(WARNING_PHASES)
+        .map(|(name, elapsed)| format!("{name} {}", format_duration(*elapsed)))
+        .collect();
+    Some(format!(
+        "Slow refresh: {} ({})",
+        format_duration(total),
+        slowest.join(", ")
+    ))
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{} ms", duration.as_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phases(durations: &[(&'static str, u64)]) -> Phases {
+        Phases(
+            durations
+                .iter()
+                .map(|(name, ms)| (*name, Duration::from_millis(*ms)))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_report() {
+        let mut profile = Profile {
+            startup: phases(&[("status snapshot", 12), ("diff parse", 30)]),
+            ..Default::default()
+        };
+        profile.record_refresh(&phases(&[("diff parse", 5)]));
+        profile.record_refresh(&phases(&[("diff parse", 40), ("commit list", 3)]));
+        profile.record_refresh(&phases(&[("diff parse", 7)]));
+
+        assert_eq!(
+            profile.report().lines().collect::<Vec<_>>(),
+            vec![
+                "Startup:",
+                "  status snapshot  12 ms",
+                "  diff parse       30 ms",
+                "  total            42 ms",
+                "Refreshes: 3",
+                "Slowest refresh:",
+                "  diff parse   40 ms",
+                "  commit list  3 ms",
+                "  total        43 ms",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_slow_refresh_warning_names_the_slowest_phases() {
+        let refresh = phases(&[("diff parse", 300), ("commit list", 900), ("untracked", 50)]);
+        assert_eq!(
+            slow_refresh_warning(&refresh, Duration::from_millis(1250)).as_deref(),
+            Some("Slow refresh: 1250 ms (commit list 900 ms, diff parse 300 ms)")
+        );
+        assert_eq!(
+            slow_refresh_warning(&refresh, Duration::from_millis(1251)),
+            None
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use crate::git;
+use anyhow::{Result, bail};
+use std::path::{Path, PathBuf};
+
+/// Where the recipes are kept, inside the git directory so they are never
+/// committed and stay with the clone.
+const RECIPE_DIR: &str = "git-full-commit/recipes";
+
+const EXTENSION: &str = "patch";
+
+/// The file the staging recipe `name` is saved in: the patch of each file
+/// that was staged, in the order `git diff --staged` lists them.
+pub fn recipe_path(repo_path: &Path, name: &str) -> Result<PathBuf> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        bail!("invalid recipe name {name:?}");
+    }
+    Ok(git::git_path(repo_path, RECIPE_DIR)?.join(format!("{name}.{EXTENSION}")))
+}
+
+/// Saves what is staged as the recipe `name`, replacing one of the same
+/// name. Returns the number of files in it.
+pub fn save(repo_path: &Path, name: &str) -> Result<usize> {
+    let path = recipe_path(repo_path, name)?;
+    let patch = git::run_git_command(
+        repo_path,
+        &["diff", "--staged", "--no-textconv", "--binary"],
+    )?;
+    let files = split_patch(&patch).len();
+    if files == 0 {
+        bail!("nothing is staged");
+    }
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, patch)?;
+    Ok(files)
+}
+
+/// The patches of the recipe `name`, one per file.
+pub fn load(repo_path: &Path, name: &str) -> Result<Vec<String>> {
+    let path = recipe_path(repo_path, name)?;
+    match std::fs::read_to_string(path) {
+        Ok(patch) => Ok(split_patch(&patch)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            bail!("no recipe named {name}")
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// The names of the saved recipes, sorted.
+pub fn names(repo_path: &Path) -> Vec<String> {
+    let Ok(dir) = git::git_path(repo_path, RECIPE_DIR) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            (path.extension()? == EXTENSION)
+                .then(|| path.file_stem()?.to_str().map(String::from))?
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+/// Splits a patch of several files into one patch per file.
+pub fn split_patch(patch: &str) -> Vec<String> {
+    let mut patches: Vec<String> = Vec::new();
+    for line in patch.split_inclusive('\n') {
+        match patches.last_mut() {
+            Some(current) if !line.starts_with("diff --git ") => current.push_str(line),
+            _ if line.starts_with("diff --git ") => patches.push(line.to_string()),
+            // Anything before the first file header is not part of a file.
+            _ => {}
+        }
+    }
+    patches
+}
+
+/// The path a file patch changes, as named in its `diff --git` line.
+pub fn patch_file_name(patch: &str) -> &str {
+    let header = patch.lines().next().unwrap_or("");
+    header
+        .rsplit_once(" b/")
+        .map_or(header, |(_, file_name)| file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_patch() {
+        let patch = "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n@@ -1 +1 @@\n-a\n+b\n\
+                     diff --git a/dir/b c.txt b/dir/b c.txt\nnew file mode 100644\n";
+        let patches = split_patch(patch);
+        assert_eq!(patches.len(), 2);
+        assert!(patches[0].ends_with("+b\n"));
+        assert_eq!(patch_file_name(&patches[0]), "a.txt");
+        assert_eq!(patch_file_name(&patches[1]), "dir/b c.txt");
+        assert!(split_patch("").is_empty());
+    }
+
+    #[test]
+    fn test_recipe_names_are_plain_file_names() {
+        let repo = std::env::temp_dir();
+        for name in ["", ".hidden", "a/b", "..\\x"] {
+            assert!(recipe_path(&repo, name).is_err(), "{name}");
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use crate::git;
+use anyhow::{Result, bail};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+const INITIAL_COMMIT_MESSAGE: &str = "Initial commit";
+
+/// Ignored in every project: editor and operating system droppings.
+const COMMON_PATTERNS: &[&str] = &[".DS_Store", "Thumbs.db", "*.swp", "*~"];
+
+/// Build output ignored when a file telling the toolchain is found.
+const TOOLCHAIN_PATTERNS: &[(&str, &[&str])] = &[
+    ("Cargo.toml", &["/target"]),
+    ("package.json", &["node_modules/", "dist/"]),
+    ("pyproject.toml", &["__pycache__/", "*.pyc", ".venv/"]),
+    ("requirements.txt", &["__pycache__/", "*.pyc", ".venv/"]),
+    ("setup.py", &["__pycache__/", "*.pyc", ".venv/"]),
+    ("pom.xml", &["target/"]),
+    ("build.gradle", &[".gradle/", "build/"]),
+    ("go.mod", &["/vendor"]),
+];
+
+/// Offers to run `git init` in `cwd`, which is in no repository, and
+/// optionally to make a first commit of a generated `.gitignore`. The new
+/// repository, or the usual error when the offer is declined.
+pub fn offer(cwd: &Path) -> Result<PathBuf> {
+    let mut stderr = io::stderr();
+    writeln!(stderr, "{} is not in a git repository.", cwd.display())?;
+    if !confirm("Run `git init` here? [y/N]: ", false)? {
+        bail!("fatal: not a git repository (or any of the parent directories): .git");
+    }
+    let with_commit = confirm(
+        "Make an initial commit with a generated .gitignore? [Y/n]: ",
+        true,
+    )?;
+
+    init(cwd)?;
+    writeln!(
+        stderr,
+        "Initialized an empty git repository in {}",
+        cwd.display()
+    )?;
+    if with_commit {
+        match initial_commit(cwd) {
+            Ok(()) => writeln!(stderr, "Committed .gitignore")?,
+            // The repository is there all the same, the commit can be made
+            // from the screen.
+            Err(e) => writeln!(
+                stderr,
+                "Could not commit .gitignore, it is left staged: {e}"
+            )?,
+        }
+    }
+    git::get_enclosing_repos(cwd)
+        .into_iter()
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("git init did not make a repository in {}", cwd.display()))
+}
+
+fn confirm(question: &str, default: bool) -> Result<bool> {
+    let mut stderr = io::stderr();
+    let stdin = io::stdin();
+    loop {
+        write!(stderr, "{question}")?;
+        stderr.flush()?;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            return Ok(false);
+        }
+        match parse_yes_no(&line, default) {
+            Some(answer) => return Ok(answer),
+            None => writeln!(stderr, "Answer y or n.")?,
+        }
+    }
+}
+
+/// `y`/`yes` or `n`/`no`, any case. An empty answer takes `default`.
+fn parse_yes_no(input: &str, default: bool) -> Option<bool> {
+    match input.trim().to_lowercase().as_str() {
+        "" => Some(default),
+        "y" | "yes" => Some(true),
+        "n" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// Makes `dir` a new repository.
+pub fn init(dir: &Path) -> Result<()> {
+    git::run_git_command(dir, &["init", "-q"]).map(|_| ())
+}
+
+/// Commits a `.gitignore` as the first commit of a new repository, writing
+/// one for the files in it unless there is one already. Nothing else is
+/// staged, the rest is left to pick on the screen.
+pub fn initial_commit(repo_path: &Path) -> Result<()> {
+    let path = repo_path.join(".gitignore");
+    if !path.exists() {
+        let names = fs::read_dir(repo_path)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().into_owned())
+            .collect();
+        fs::write(&path, gitignore_template(&names))?;
+    }
+    git::stage_paths(repo_path, &[".gitignore".to_string()])?;
+    git::commit(repo_path, INITIAL_COMMIT_MESSAGE, true)
+}
+
+/// A `.gitignore` for a project with the files `names` at its root: the
+/// common patterns, then the build output of each toolchain found.
+pub fn gitignore_template(names: &HashSet<String>) -> String {
+    let mut patterns: Vec<&str> = COMMON_PATTERNS.to_vec();
+    for (marker, toolchain) in TOOLCHAIN_PATTERNS {
+        if names.contains(*marker) {
+            for pattern in *toolchain {
+                if !patterns.contains(pattern) {
+                    patterns.push(pattern);
+                }
+            }
+        }
+    }
+    patterns
+        .iter()
+        .map(|pattern| format!("{pattern}\n"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_yes_no() {
+        assert_eq!(parse_yes_no("\n", false), Some(false));
+        assert_eq!(parse_yes_no("\n", true), Some(true));
+        assert_eq!(parse_yes_no(" Y \n", false), Some(true));
+        assert_eq!(parse_yes_no("no", true), Some(false));
+        assert_eq!(parse_yes_no("maybe", true), None);
+    }
+
+    #[test]
+    fn test_gitignore_template_follows_the_toolchains() {
+        let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect();
+        assert_eq!(
+            gitignore_template(&names(&["README.md"])),
+            ".DS_Store\nThumbs.db\n*.swp\n*~\n"
+        );
+        let template = gitignore_template(&names(&["Cargo.toml", "setup.py", "pyproject.toml"]));
+        assert!(template.ends_with("*~\n/target\n__pycache__/\n*.pyc\n.venv/\n"));
+    }
+}
@@ -0,0 +1,145 @@
+use crate::commit_storage::get_storage_dir;
+use crate::git;
+use crate::repo_init;
+use anyhow::{Result, bail};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+
+const CHOICES_FILE: &str = "repo_choices";
+
+/// Picks the repository to operate on when started inside `cwd`. If `cwd`
+/// is inside nested repositories, the user is asked which one to use and
+/// the answer is remembered for `cwd`, unless `ask_again` is set. Outside
+/// of any, making one is offered.
+pub fn select_repo(cwd: &Path, ask_again: bool) -> Result<PathBuf> {
+    let repos = git::get_enclosing_repos(cwd);
+    if repos.is_empty() {
+        if io::stdin().is_terminal() {
+            return repo_init::offer(cwd);
+        }
+        bail!("fatal: not a git repository (or any of the parent directories): .git");
+    }
+    if repos.len() == 1 {
+        return Ok(repos[0].clone());
+    }
+
+    let mut choices = load_choices();
+    if !ask_again
+        && let Some(repo) = choices.get(cwd)
+        && repos.contains(repo)
+    {
+        return Ok(repo.clone());
+    }
+
+    // Without a terminal to ask on, behave like `git` and use the innermost.
+    if !io::stdin().is_terminal() {
+        return Ok(repos[0].clone());
+    }
+
+    let repo = repos[ask(&repos)?].clone();
+    choices.insert(cwd.to_path_buf(), repo.clone());
+    // Failing to remember the choice only means being asked again.
+    let _ = save_choices(&choices);
+    Ok(repo)
+}
+
+fn ask(repos: &[PathBuf]) -> Result<usize> {
+    let mut stderr = io::stderr();
+    writeln!(stderr, "Found nested git repositories:")?;
+    for (i, repo) in repos.iter().enumerate() {
+        let note = if i == 0 { " (innermost)" } else { "" };
+        writeln!(stderr, "  {}) {}{note}", i + 1, repo.display())?;
+    }
+
+    let stdin = io::stdin();
+    loop {
+        write!(stderr, "Which one? [1]: ")?;
+        stderr.flush()?;
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            bail!("no repository selected");
+        }
+        match parse_selection(&line, repos.len()) {
+            Some(index) => return Ok(index),
+            None => writeln!(stderr, "Enter a number from 1 to {}.", repos.len())?,
+        }
+    }
+}
+
+/// Turns the 1-based answer into an index. An empty answer picks the first.
+fn parse_selection(input: &str, count: usize) -> Option<usize> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Some(0);
+    }
+    match input.parse::<usize>() {
+        Ok(n) if (1..=count).contains(&n) => Some(n - 1),
+        _ => None,
+    }
+}
+
+fn choices_file_path() -> Result<PathBuf, io::Error> {
+    Ok(get_storage_dir()?.join(CHOICES_FILE))
+}
+
+fn load_choices() -> BTreeMap<PathBuf, PathBuf> {
+    choices_file_path()
+        .and_then(fs::read_to_string)
+        .map(|content| parse_choices(&content))
+        .unwrap_or_default()
+}
+
+fn save_choices(choices: &BTreeMap<PathBuf, PathBuf>) -> Result<(), io::Error> {
+    fs::create_dir_all(get_storage_dir()?)?;
+    fs::write(choices_file_path()?, format_choices(choices))
+}
+
+/// One `<directory>\t<repository>` pair per line.
+fn parse_choices(content: &str) -> BTreeMap<PathBuf, PathBuf> {
+    content
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(dir, repo)| (PathBuf::from(dir), PathBuf::from(repo)))
+        .collect()
+}
+
+fn format_choices(choices: &BTreeMap<PathBuf, PathBuf>) -> String {
+    choices
+        .iter()
+        .map(|(dir, repo)| format!("{}\t{}\n", dir.display(), repo.display()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_selection() {
+        assert_eq!(parse_selection("\n", 2), Some(0));
+        assert_eq!(parse_selection(" 2 \n", 2), Some(1));
+        assert_eq!(parse_selection("3", 2), None);
+        assert_eq!(parse_selection("0", 2), None);
+        assert_eq!(parse_selection("outer", 2), None);
+    }
+
+    #[test]
+    fn test_choices_round_trip() {
+        let mut choices = BTreeMap::new();
+        choices.insert(
+            PathBuf::from("/work/app/vendor"),
+            PathBuf::from("/work/app"),
+        );
+        choices.insert(PathBuf::from("/work/lib"), PathBuf::from("/work/lib"));
+
+        let content = format_choices(&choices);
+        assert_eq!(
+            content,
+            "/work/app/vendor\t/work/app\n/work/lib\t/work/lib\n"
+        );
+        assert_eq!(parse_choices(&content), choices);
+        assert!(parse_choices("garbage\n").is_empty());
+    }
+}
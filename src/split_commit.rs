@@ -0,0 +1,108 @@
+use crate::git;
+use anyhow::{Result, bail};
+use std::path::Path;
+
+/// Where the branch from before a split is kept until the split is done,
+/// so it can be restored, even after quitting in the middle of it.
+pub const BACKUP_REF: &str = "refs/git-full-commit/split";
+
+/// A local commit taken apart with `S`, its changes back in the working
+/// tree to be committed again in several parts.
+#[derive(Debug, Clone)]
+pub struct SplitCommit {
+    /// Abbreviated hash of the commit being split.
+    pub hash: String,
+    /// Its full message, offered for the first part.
+    pub message: String,
+    /// What `HEAD` was before, also kept at [`BACKUP_REF`].
+    pub original_head: String,
+    /// The newer commits not replayed yet, oldest first. They are put back
+    /// on top once all parts are committed.
+    pub descendants: Vec<String>,
+}
+
+impl SplitCommit {
+    /// Its subject, for messages.
+    pub fn subject(&self) -> &str {
+        self.message.lines().next().unwrap_or_default()
+    }
+
+    /// Replays the newer commits on top of the parts, and drops the backup.
+    /// Stops at the first one that does not apply, leaving the rest for
+    /// another try.
+    pub fn finish(&mut self, repo_path: &Path) -> Result<()> {
+        while let Some(hash) = self.descendants.first() {
+            if let Err(e) = git::cherry_pick(repo_path, hash) {
+                git::cherry_pick_abort(repo_path)?;
+                return Err(e);
+            }
+            self.descendants.remove(0);
+        }
+        delete_backup(repo_path)
+    }
+
+    /// Puts the branch back as it was before the split. The parts committed
+    /// so far and the changes not committed yet are dropped.
+    pub fn restore(&self, repo_path: &Path) -> Result<()> {
+        git::reset_hard(repo_path, &self.original_head)?;
+        delete_backup(repo_path)
+    }
+}
+
+/// Takes `hash` apart: the branch moves to its parent and its changes are
+/// left unstaged, after the commits newer than it are set aside. The
+/// working tree must have no changes.
+pub fn start(repo_path: &Path, hash: &str) -> Result<SplitCommit> {
+    let rev_parse = |rev: &str| -> Result<String> {
+        Ok(
+            git::run_git_command(repo_path, &["rev-parse", "--verify", "--quiet", rev])?
+                .trim()
+                .to_string(),
+        )
+    };
+    let original_head = rev_parse("HEAD")?;
+    let commit = rev_parse(&format!("{hash}^{{commit}}"))?;
+    let Ok(parent) = rev_parse(&format!("{commit}^")) else {
+        bail!("the first commit cannot be split");
+    };
+    if !git::run_git_command(
+        repo_path,
+        &["rev-list", "--merges", &format!("{parent}..HEAD")],
+    )?
+    .trim()
+    .is_empty()
+    {
+        bail!("a merge commit is in the way");
+    }
+    let descendants = git::run_git_command(
+        repo_path,
+        &["rev-list", "--reverse", &format!("{commit}..HEAD")],
+    )?
+    .lines()
+    .map(str::to_string)
+    .collect::<Vec<_>>();
+    let message = git::run_git_command(repo_path, &["log", "-1", "--format=%B", &commit])?
+        .trim_end()
+        .to_string();
+
+    git::run_git_command(repo_path, &["update-ref", BACKUP_REF, &original_head])?;
+    if !descendants.is_empty() {
+        git::reset_hard(repo_path, &commit)?;
+    }
+    git::run_git_command(repo_path, &["reset", "-q", &parent])?;
+    Ok(SplitCommit {
+        hash: hash.to_string(),
+        message,
+        original_head,
+        descendants,
+    })
+}
+
+/// Whether a split was left unfinished, e.g. by quitting in the middle.
+pub fn is_left_over(repo_path: &Path) -> bool {
+    git::run_git_command(repo_path, &["rev-parse", "--verify", "--quiet", BACKUP_REF]).is_ok()
+}
+
+fn delete_backup(repo_path: &Path) -> Result<()> {
+    git::run_git_command(repo_path, &["update-ref", "-d", BACKUP_REF]).map(|_| ())
+}
@@ -0,0 +1,68 @@
+/// How the last run of `staged_test_command` went.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Running,
+    Passed,
+    /// What it printed.
+    Failed(String),
+}
+
+/// A run of `staged_test_command` on a checkout of the staged tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StagedTest {
+    /// The staged tree it ran on.
+    pub tree: String,
+    pub status: Status,
+}
+
+impl StagedTest {
+    /// What is shown next to the commit input, as long as the run is for
+    /// `current_tree`, or what was staged before otherwise.
+    pub fn label(&self, current_tree: Option<&str>) -> &'static str {
+        match &self.status {
+            Status::Running => "tests running",
+            _ if current_tree != Some(self.tree.as_str()) => "tests outdated",
+            Status::Passed => "tests passed",
+            Status::Failed(_) => "tests failed",
+        }
+    }
+}
+
+/// The last non-blank line of what a failed run printed, for the status
+/// line.
+pub fn summary(output: &str) -> &str {
+    output
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_label_tells_an_outdated_result() {
+        let test = StagedTest {
+            tree: "abc".to_string(),
+            status: Status::Failed("error\n".to_string()),
+        };
+        assert_eq!(test.label(Some("abc")), "tests failed");
+        assert_eq!(test.label(Some("def")), "tests outdated");
+        assert_eq!(test.label(None), "tests outdated");
+
+        let running = StagedTest {
+            status: Status::Running,
+            ..test
+        };
+        assert_eq!(running.label(Some("def")), "tests running");
+    }
+
+    #[test]
+    fn test_summary_is_the_last_printed_line() {
+        assert_eq!(summary("compiling\nerror: boom\n\n"), "error: boom");
+        assert_eq!(summary(""), "");
+    }
+}
@@ -1,54 +1,144 @@
-mod color;
+pub mod bookmarks;
+pub mod chrome;
+pub mod color;
+pub mod command_prompt;
+pub mod commit_details;
+pub mod commit_folds;
 pub mod commit_view;
-mod diff_view;
+pub mod diagnostics_view;
+pub mod diff_search;
+pub mod diff_view;
+pub mod discard_bin;
+pub mod explain;
+pub mod final_review;
+pub mod fixup_picker;
+pub mod format_preview;
+pub mod help;
+pub mod history;
+pub mod hook_output;
+pub mod hunk_edit;
+pub mod hunk_jump;
+pub mod ignore_picker;
+pub mod index_lock;
 mod keyboard;
+pub mod layout;
 pub mod main_screen;
+pub mod maintenance;
+pub mod message_log_view;
+pub mod patch_preview;
+pub mod plan_preview;
+pub mod prompt;
+pub mod rebase_plan;
+pub mod rebase_recovery;
+pub mod recipes;
 mod render;
+pub mod restore_picker;
+pub mod reword_preview;
 pub mod scroll;
+pub mod stashes;
+pub mod stat_panel;
+pub mod status_filter;
+pub mod tutorial;
+pub mod unified_list;
+pub mod worktrees;
 
 pub mod update;
 use crate::app_state::AppState;
-use crate::external_command;
+use crate::external_command::{self, EditorMode};
+use crate::message_log::Severity;
 use color::setup_colors;
-use pancurses::{Input, curs_set, endwin, initscr, noecho, start_color};
+use pancurses::{
+    Input, curs_set, def_prog_mode, endwin, initscr, noecho, reset_prog_mode, start_color,
+};
 use render::render;
 use std::io::Write;
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use update::update_state;
 
-pub fn tui_loop(repo_path: std::path::PathBuf, files: Vec<crate::git::FileDiff>, debug: bool) {
-    let mut window = initscr();
+/// Runs the view until it quits. Returns the profile, and the worktree to
+/// run it on next when one was switched to.
+pub fn tui_loop(
+    repo_path: std::path::PathBuf,
+    files: Vec<crate::git::FileDiff>,
+    config: crate::config::Config,
+    debug: bool,
+    startup: crate::profile::Phases,
+) -> (crate::profile::Profile, Option<std::path::PathBuf>) {
+    let window = initscr();
     window.keypad(true);
     noecho();
     curs_set(0);
     window.timeout(50);
 
     start_color();
-    setup_colors();
+    setup_colors(config.palette);
 
-    let mut state = AppState::new(repo_path, files);
-    let mut needs_render = true;
+    let mut state = AppState::new_with_config(repo_path, files, config);
+    state.profile.startup.0.splice(0..0, startup.0);
+    let started = Instant::now();
+    render(&window, &state);
+    state
+        .profile
+        .startup
+        .0
+        .push(("first render", started.elapsed()));
+    tutorial::open_on_first_run(&mut state);
+    let mut needs_render = state.tutorial.is_some();
 
     while state.running {
         if needs_render {
+            state.remember_message();
             render(&window, &state);
+            if std::mem::take(&mut state.alert_pending) {
+                crate::alert::ring(state.config.alert);
+            }
             needs_render = false;
         }
 
-        if let Some(request) = state.editor_request.take() {
-            endwin();
-            let _ = external_command::open_editor(&request.file_path, request.line_number);
-
-            state.refresh_diff(false);
+        if let Some(mut request) = state.editor_request.take() {
+            let mode = state.config.editor_mode;
+            if request.detaches(mode) {
+                let opened =
+                    external_command::spawn_editor(&request.file_path, request.line_number);
+                state.finish_editor_request(request);
+                if let Err(e) = opened {
+                    state.set_message(Severity::Error, format!("Failed to open the editor: {e}"));
+                }
+                needs_render = true;
+                continue;
+            }
 
-            window = initscr();
-            window.keypad(true);
-            noecho();
+            // The terminal goes back to how the shell had it while the
+            // editor runs, and the screen is drawn again after.
+            def_prog_mode();
+            endwin();
+            let mut opened = Ok(());
+            match request.edit_message.as_mut() {
+                Some(pending) => {
+                    if let Err(e) =
+                        external_command::run_editor(&pending.editor, &request.file_path)
+                    {
+                        pending.error = Some(e.to_string());
+                    }
+                }
+                None => {
+                    opened = external_command::open_editor(
+                        &request.file_path,
+                        request.line_number,
+                        mode == EditorMode::Wait || request.reads_back(),
+                    );
+                }
+            }
+            reset_prog_mode();
             curs_set(0);
-            window.timeout(50);
-            start_color();
-            setup_colors();
+            window.clearok(true);
+            window.refresh();
+
+            state.finish_editor_request(request);
+            if let Err(e) = opened {
+                state.set_message(Severity::Error, format!("Failed to open the editor: {e}"));
+            }
             needs_render = true;
             continue;
         }
@@ -116,4 +206,5 @@ pub fn tui_loop(repo_path: std::path::PathBuf, files: Vec<crate::git::FileDiff>,
     }
 
     endwin();
+    (state.profile, state.relaunch)
 }
@@ -0,0 +1,144 @@
+use crate::app_state::{AppState, FocusedPane};
+use crate::message_log::Severity;
+use crate::ui::main_screen::{ListItem, UnstagedListItem};
+use crate::ui::scroll;
+
+/// The file under the cursor of the focused pane, if it is on a file.
+fn selected_file(state: &AppState) -> Option<String> {
+    match state.focused_pane {
+        FocusedPane::Main => match state.current_main_item()? {
+            ListItem::File(file) => Some(file.file_name.clone()),
+            _ => None,
+        },
+        FocusedPane::Unstaged => {
+            match state
+                .unstaged_pane
+                .list_items
+                .get(state.unstaged_pane.cursor)?
+            {
+                UnstagedListItem::File(file) => Some(file.file_name.clone()),
+                UnstagedListItem::UntrackedFile(file_name) => Some(file_name.clone()),
+                _ => None,
+            }
+        }
+    }
+}
+
+/// Where the bookmarked files are listed, the staged pane first, each
+/// with its file. A file changed in both panes is listed twice.
+fn listed(state: &AppState) -> Vec<(FocusedPane, usize, &str)> {
+    let main =
+        state
+            .main_screen
+            .list_items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| match item {
+                ListItem::File(file) => Some((FocusedPane::Main, index, file.file_name.as_str())),
+                _ => None,
+            });
+    let unstaged = state
+        .unstaged_pane
+        .list_items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, item)| match item {
+            UnstagedListItem::File(file) => {
+                Some((FocusedPane::Unstaged, index, file.file_name.as_str()))
+            }
+            UnstagedListItem::UntrackedFile(file_name) => {
+                Some((FocusedPane::Unstaged, index, file_name.as_str()))
+            }
+            _ => None,
+        });
+    main.chain(unstaged)
+        .filter(|(_, _, file_name)| state.is_bookmarked(file_name))
+        .collect()
+}
+
+/// Bookmarks the file under the cursor, or takes its bookmark away.
+pub fn toggle(state: &mut AppState) {
+    let Some(file_name) = selected_file(state) else {
+        state.set_message(Severity::Warning, "Only files can be bookmarked");
+        state.alert();
+        return;
+    };
+    if let Some(index) = state.bookmarks.iter().position(|name| *name == file_name) {
+        state.bookmarks.remove(index);
+        state.set_message(
+            Severity::Success,
+            format!("Removed the bookmark of {file_name}"),
+        );
+        return;
+    }
+    state.bookmarks.push(file_name.clone());
+    state.set_message(
+        Severity::Success,
+        format!(
+            "Bookmarked {file_name}, {} jumps to the next bookmark",
+            state.keymap.key_label("next_bookmark")
+        ),
+    );
+}
+
+/// Moves the cursor to the next bookmarked file in the lists, or to the
+/// previous one, going around at the ends.
+pub fn jump(state: &mut AppState, forward: bool, max_y: i32) {
+    let listed = listed(state);
+    if listed.is_empty() {
+        state.set_message(
+            Severity::Warning,
+            if state.bookmarks.is_empty() {
+                format!(
+                    "No bookmarks, {} bookmarks the file under the cursor",
+                    state.keymap.key_label("bookmark")
+                )
+            } else {
+                "No bookmarked file has changes".to_string()
+            },
+        );
+        state.alert();
+        return;
+    }
+    let order = |pane: FocusedPane, index: usize| match pane {
+        FocusedPane::Main => (0, index),
+        FocusedPane::Unstaged => (1, index),
+    };
+    let current = match state.focused_pane {
+        FocusedPane::Main => order(FocusedPane::Main, state.main_screen.file_cursor),
+        FocusedPane::Unstaged => order(FocusedPane::Unstaged, state.unstaged_pane.cursor),
+    };
+    let position = if forward {
+        listed
+            .iter()
+            .position(|(pane, index, _)| order(*pane, *index) > current)
+            .unwrap_or(0)
+    } else {
+        listed
+            .iter()
+            .rposition(|(pane, index, _)| order(*pane, *index) < current)
+            .unwrap_or(listed.len() - 1)
+    };
+    let (pane, index, file_name) = listed[position];
+    let message = format!("Bookmark {} of {}: {file_name}", position + 1, listed.len());
+
+    state.focused_pane = pane;
+    match pane {
+        FocusedPane::Main => {
+            state.main_screen.file_cursor = index;
+            state.main_screen.is_diff_cursor_active = false;
+            state.main_screen.diff_scroll = 0;
+            let height = state.main_header_height(max_y).0;
+            scroll::into_view(index, &mut state.main_screen.file_list_scroll, height);
+        }
+        FocusedPane::Unstaged => {
+            state.unstaged_pane.cursor = index;
+            state.unstaged_pane.is_diff_cursor_active = false;
+            state.unstaged_pane.diff_scroll = 0;
+            let height = state.unstaged_header_height(max_y).0;
+            scroll::into_view(index, &mut state.unstaged_pane.scroll, height);
+        }
+    }
+    state.main_screen.line_cursor = 0;
+    state.set_message(Severity::Info, message);
+}
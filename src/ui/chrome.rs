@@ -0,0 +1,43 @@
+use crate::ui::layout;
+use pancurses::{A_BOLD, COLOR_PAIR, Window};
+use std::path::Path;
+
+/// Between the parts of a breadcrumb.
+const SEPARATOR: &str = " ▸ ";
+
+/// The name the breadcrumbs start with: the directory of the repository.
+pub fn repo_name(repo_path: &Path) -> String {
+    repo_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| repo_path.display().to_string())
+}
+
+/// `repo ▸ Screen ▸ detail`, the place shown at the top of a screen.
+pub fn breadcrumb(repo: &str, crumbs: &[&str]) -> String {
+    std::iter::once(repo)
+        .chain(crumbs.iter().copied().filter(|crumb| !crumb.is_empty()))
+        .collect::<Vec<_>>()
+        .join(SEPARATOR)
+}
+
+/// Draws the title row of a full screen view: the breadcrumb of `crumbs`
+/// under `repo`, then the keys of the screen.
+pub fn render_title(window: &Window, repo: &str, crumbs: &[&str], hints: &str) {
+    let max_x = window.get_max_x();
+    let mut title = format!(" {} ", breadcrumb(repo, crumbs));
+    if !hints.is_empty() {
+        title.push_str(&format!(" {hints} "));
+    }
+
+    window.attron(COLOR_PAIR(8) | A_BOLD);
+    for x in 0..max_x {
+        window.mvaddch(0, x, ' ');
+    }
+    window.mvaddstr(
+        0,
+        0,
+        layout::truncate_to_width(&title, max_x.max(0) as usize),
+    );
+    window.attroff(COLOR_PAIR(8) | A_BOLD);
+}
@@ -1,6 +1,99 @@
-use pancurses::{COLOR_BLACK, init_color, init_pair};
+use crate::message_log::Severity;
+use pancurses::{COLOR_BLACK, chtype, init_color, init_pair};
+use std::sync::atomic::{AtomicBool, Ordering};
 
-pub fn setup_colors() {
+/// The colors the screen is drawn with, set with `palette` in the config.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Palette {
+    #[default]
+    Default,
+    /// Brighter text, greys and selections.
+    HighContrast,
+    /// Additions in sky blue and removals in orange instead of green and
+    /// red, from the Okabe-Ito colors, which stay apart with deuteranopia
+    /// and protanopia.
+    Colorblind,
+}
+
+/// RGB values, from 0 to 1000, of the colors of a palette.
+struct Colors {
+    white: (i16, i16, i16),
+    red: (i16, i16, i16),
+    green: (i16, i16, i16),
+    cyan: (i16, i16, i16),
+    grey: (i16, i16, i16),
+    yellow: (i16, i16, i16),
+    selected_bg: (i16, i16, i16),
+    inactive_cursor_bg: (i16, i16, i16),
+}
+
+impl Palette {
+    fn colors(self) -> Colors {
+        match self {
+            Palette::Default => Colors {
+                white: (968, 968, 941), // #F7F7F0
+                red: (1000, 0, 439),    // #FF0070
+                green: (525, 812, 0),   // #86CF00
+                cyan: (0, 769, 961),    // #00C4F5
+                grey: (266, 266, 266),  // #444444
+                yellow: (1000, 808, 0), // #FFCE00
+                selected_bg: (150, 150, 150),
+                inactive_cursor_bg: (80, 80, 80),
+            },
+            Palette::HighContrast => Colors {
+                white: (1000, 1000, 1000), // #FFFFFF
+                red: (1000, 333, 333),     // #FF5555
+                green: (333, 1000, 333),   // #55FF55
+                cyan: (333, 1000, 1000),   // #55FFFF
+                grey: (600, 600, 600),     // #999999
+                yellow: (1000, 1000, 333), // #FFFF55
+                selected_bg: (300, 300, 300),
+                inactive_cursor_bg: (180, 180, 180),
+            },
+            Palette::Colorblind => Colors {
+                white: (968, 968, 941),  // #F7F7F0
+                red: (902, 624, 0),      // #E69F00, orange
+                green: (337, 706, 914),  // #56B4E9, sky blue
+                cyan: (941, 894, 259),   // #F0E442, yellow
+                grey: (400, 400, 400),   // #666666
+                yellow: (800, 475, 655), // #CC79A7, reddish purple, as cyan is yellow here
+                selected_bg: (150, 150, 150),
+                inactive_cursor_bg: (80, 80, 80),
+            },
+        }
+    }
+
+    /// Whether added and removed lines also get a `+` or `-` in the line
+    /// number gutter, so they are not told apart by color alone.
+    pub fn marks_gutter(self) -> bool {
+        self != Palette::Default
+    }
+}
+
+static GUTTER_MARKS: AtomicBool = AtomicBool::new(false);
+
+/// Whether the palette set up last marks added and removed lines in the
+/// gutter.
+pub fn gutter_marks() -> bool {
+    GUTTER_MARKS.load(Ordering::Relaxed)
+}
+
+/// The color pair of a message of `severity`, on the status bar or else on
+/// black.
+pub fn message_pair(severity: Severity, on_status_bar: bool) -> chtype {
+    match (severity, on_status_bar) {
+        (Severity::Info, true) => 10,
+        (Severity::Success, true) => 7,
+        (Severity::Warning, true) => 17,
+        (Severity::Error, true) => 6,
+        (Severity::Info, false) => 1,
+        (Severity::Success, false) => 3,
+        (Severity::Warning, false) => 18,
+        (Severity::Error, false) => 2,
+    }
+}
+
+pub fn setup_colors(palette: Palette) {
     // Base colors
     let color_white = 20;
     let color_red = 21;
@@ -9,14 +102,22 @@ pub fn setup_colors() {
     let color_grey = 24;
     let color_selected_bg = 25;
     let color_inactive_cursor_bg = 26;
+    let color_yellow = 27;
 
-    init_color(color_white, 968, 968, 941); // #F7F7F0
-    init_color(color_red, 1000, 0, 439); // #FF0070
-    init_color(color_green, 525, 812, 0); // #86CF00
-    init_color(color_cyan, 0, 769, 961); // #00C4F5
-    init_color(color_grey, 266, 266, 266); // #444444
-    init_color(color_selected_bg, 150, 150, 150);
-    init_color(color_inactive_cursor_bg, 80, 80, 80);
+    let colors = palette.colors();
+    for (color, (r, g, b)) in [
+        (color_white, colors.white),
+        (color_red, colors.red),
+        (color_green, colors.green),
+        (color_cyan, colors.cyan),
+        (color_grey, colors.grey),
+        (color_selected_bg, colors.selected_bg),
+        (color_inactive_cursor_bg, colors.inactive_cursor_bg),
+        (color_yellow, colors.yellow),
+    ] {
+        init_color(color, r, g, b);
+    }
+    GUTTER_MARKS.store(palette.marks_gutter(), Ordering::Relaxed);
 
     // Color pairs
     init_pair(1, color_white, COLOR_BLACK); // Default: White on Black
@@ -39,4 +140,8 @@ pub fn setup_colors() {
     init_pair(14, color_cyan, color_inactive_cursor_bg);
     init_pair(15, color_grey, color_inactive_cursor_bg);
     init_pair(16, COLOR_BLACK, color_selected_bg); // Black on selected
+
+    // Warnings
+    init_pair(17, color_yellow, color_selected_bg);
+    init_pair(18, color_yellow, COLOR_BLACK);
 }
@@ -0,0 +1,160 @@
+use crate::app_state::{AppState, FocusedPane};
+use crate::command::{ApplyPatchCommand, Command, StagePatchCommand, StagePathspecCommand};
+use crate::git_patch;
+use crate::message_log::Severity;
+use crate::message_log::reason;
+use crate::ui::patch_preview;
+use crate::ui::prompt::{Prompt, PromptAction};
+
+/// Asks for a command to run on the selected file.
+pub fn open(state: &mut AppState) {
+    state.prompt = Some(Prompt::new(PromptAction::RunCommand, "Command", ""));
+}
+
+/// Runs a command typed after `:`: `unstage <hunks>` on a staged file,
+/// `stage <hunks>` on an unstaged one, `add <pathspec>` on any.
+pub fn run(state: &mut AppState, text: &str) {
+    let (name, arguments) = text.split_once(' ').unwrap_or((text, ""));
+    match name {
+        "" => {}
+        "unstage" => move_hunks(state, true, arguments.trim()),
+        "stage" => move_hunks(state, false, arguments.trim()),
+        "add" => stage_pathspec(state, arguments.trim()),
+        _ => {
+            state.set_message(
+                Severity::Warning,
+                format!("Unknown command: {name}, try unstage 2-4, stage 1,3 or add *.rs"),
+            );
+            state.alert();
+        }
+    }
+}
+
+/// Hunk numbers as the diff shows them, counted from 1, like `2-4`, `3`
+/// or `1,3-4`, as indexes in the order of the diff.
+pub fn parse_hunks(text: &str, count: usize) -> Result<Vec<usize>, String> {
+    if text.is_empty() {
+        return Err("Give the hunks, like 2-4".to_string());
+    }
+    let number = |part: &str| -> Result<usize, String> {
+        match part.trim().parse::<usize>() {
+            Ok(n) if (1..=count).contains(&n) => Ok(n),
+            Ok(n) => Err(format!("No hunk {n}, there are {count}")),
+            Err(_) => Err(format!("Not a hunk number: {}", part.trim())),
+        }
+    };
+    let mut hunks = Vec::new();
+    for part in text.split(',') {
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (number(first)?, number(last)?),
+            None => {
+                let n = number(part)?;
+                (n, n)
+            }
+        };
+        if first > last {
+            return Err(format!("{first} comes after {last}"));
+        }
+        hunks.extend(first - 1..last);
+    }
+    hunks.sort();
+    hunks.dedup();
+    Ok(hunks)
+}
+
+/// Unstages the `hunks` of the selected staged file, or stages those of
+/// the selected unstaged file, as one patch and one undo step.
+fn move_hunks(state: &mut AppState, unstaging: bool, hunks: &str) {
+    let file = match (unstaging, state.focused_pane) {
+        (true, FocusedPane::Main) => state.current_main_file(),
+        (false, FocusedPane::Unstaged) => state.get_unstaged_file(),
+        _ => None,
+    };
+    let Some(file) = file.cloned() else {
+        state.set_message(
+            Severity::Warning,
+            if unstaging {
+                "Select a staged file to unstage its hunks"
+            } else {
+                "Select an unstaged file to stage its hunks"
+            }
+            .to_string(),
+        );
+        state.alert();
+        return;
+    };
+    if state.refuse_partial_textconv(&file.file_name) {
+        return;
+    }
+    let indexes = match parse_hunks(hunks, file.hunks.len()) {
+        Ok(indexes) => indexes,
+        Err(e) => {
+            state.set_message(Severity::Warning, e);
+            state.alert();
+            return;
+        }
+    };
+    let selected: Vec<_> = indexes.iter().map(|&index| &file.hunks[index]).collect();
+    let patch = git_patch::create_hunks_patch(&file, &selected);
+    let label = if indexes.len() == 1 { "hunk" } else { "hunks" };
+    let (what, done) = if unstaging {
+        (
+            format!("Unstaging {label} {hunks} of {}", file.file_name),
+            format!("Unstaged {label} {hunks} of {}", file.file_name),
+        )
+    } else {
+        (
+            format!("Staging {label} {hunks} of {}", file.file_name),
+            format!("Staged {label} {hunks} of {}", file.file_name),
+        )
+    };
+    if !patch_preview::check(state, &what, &patch, unstaging, true) {
+        return;
+    }
+    let succeeded = if unstaging {
+        let command: Box<dyn Command> =
+            Box::new(ApplyPatchCommand::new(state.repo_path.clone(), patch));
+        state.execute_and_refresh(command)
+    } else {
+        let command: Box<dyn Command> =
+            Box::new(StagePatchCommand::new(state.repo_path.clone(), patch));
+        state.execute_and_guard(command)
+    };
+    if succeeded && state.message.is_none() {
+        state.set_message(Severity::Success, done);
+    }
+}
+
+/// Stages the files matching `pathspec`, like `*.rs` or `src/**`, as one
+/// undo step that unstages exactly the files it staged.
+fn stage_pathspec(state: &mut AppState, pathspec: &str) {
+    if pathspec.is_empty() {
+        state.set_message(Severity::Warning, "Give the files, like *.rs or src/**");
+        state.alert();
+        return;
+    }
+    let command = match StagePathspecCommand::new(state.repo_path.clone(), pathspec) {
+        Ok(command) => command,
+        Err(e) => {
+            state.set_message(
+                Severity::Error,
+                format!("Cannot stage {pathspec}: {}", reason(&e)),
+            );
+            state.alert();
+            return;
+        }
+    };
+    let count = command.files.len();
+    if count == 0 {
+        state.set_message(Severity::Warning, format!("No changes match {pathspec}"));
+        state.alert();
+        return;
+    }
+    if state.execute_and_guard(Box::new(command)) && state.message.is_none() {
+        let label = if count == 1 { "file" } else { "files" };
+        state.set_message(
+            Severity::Success,
+            format!("Staged {count} {label} matching {pathspec}"),
+        );
+    }
+}
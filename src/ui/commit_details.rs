@@ -0,0 +1,90 @@
+use crate::app_state::AppState;
+use crate::git::CommitInfo;
+use crate::ui::layout;
+use crate::ui::main_screen::ListItem;
+use pancurses::{A_BOLD, COLOR_PAIR, Window};
+
+/// Body lines shown before the rest is cut off.
+const MAX_BODY_LINES: usize = 8;
+
+/// The box is never narrower than this, unless the screen is.
+const MIN_WIDTH: usize = 30;
+
+/// The commit selected in the main pane, while its diff is not being
+/// navigated.
+pub fn selected_commit(state: &AppState) -> Option<&CommitInfo> {
+    if state.main_screen.is_diff_cursor_active {
+        return None;
+    }
+    match state.current_main_item() {
+        Some(ListItem::PreviousCommitInfo { hash, .. }) => state
+            .previous_commits
+            .iter()
+            .find(|commit| &commit.hash == hash),
+        _ => None,
+    }
+}
+
+/// The lines of the details box: the hash and the author, the date and
+/// whether it is signed, then the body below a blank line.
+pub fn lines(commit: &CommitInfo) -> Vec<String> {
+    let mut lines = vec![
+        format!("{}  {}", commit.hash, commit.author),
+        if commit.is_signed {
+            format!("{}, signed", commit.date)
+        } else {
+            commit.date.clone()
+        },
+    ];
+    if !commit.body.is_empty() {
+        lines.push(String::new());
+        let body: Vec<&str> = commit.body.lines().collect();
+        lines.extend(
+            body.iter()
+                .take(MAX_BODY_LINES)
+                .map(|line| line.to_string()),
+        );
+        if body.len() > MAX_BODY_LINES {
+            lines.push(format!("… {} more lines", body.len() - MAX_BODY_LINES));
+        }
+    }
+    lines
+}
+
+/// Draws the details of `commit` in the top right corner of the diff view.
+pub fn render(window: &Window, commit: &CommitInfo, top: i32) {
+    let (max_y, max_x) = window.get_max_yx();
+    let lines = lines(commit);
+    let longest = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let width = (longest + 2)
+        .clamp(MIN_WIDTH, (max_x.max(0) as usize * 2 / 3).max(MIN_WIDTH))
+        .min(max_x.max(0) as usize);
+    let left = max_x - width as i32;
+
+    for (row, line) in lines.iter().enumerate() {
+        let y = top + row as i32;
+        // Keep the status line free.
+        if y >= max_y - 1 {
+            break;
+        }
+        let attributes = if row == 0 {
+            COLOR_PAIR(8) | A_BOLD
+        } else {
+            COLOR_PAIR(5)
+        };
+        window.attron(attributes);
+        for x in left..max_x {
+            window.mvaddch(y, x, ' ');
+        }
+        window.mvaddstr(
+            y,
+            left + 1,
+            layout::truncate_to_width(line, width.saturating_sub(2)),
+        );
+        window.attroff(attributes);
+    }
+}
@@ -0,0 +1,188 @@
+use crate::app_state::AppState;
+use crate::git::FileDiff;
+use crate::message_log::Severity;
+use crate::ui::main_screen::ListItem;
+use crate::util::word_diff::WordChange;
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+/// Lines shown above a file header the cursor is put on.
+const CONTEXT_LINES: usize = 3;
+
+type WordSpans = Option<Vec<(String, WordChange)>>;
+
+/// The files of a commit's diff shown as their `diff --git` line only,
+/// toggled with Enter on that line, by their index in the diff since a
+/// fixup preview repeats names. They belong to the commit with `hash`, so
+/// selecting another commit shows it unfolded.
+#[derive(Default)]
+pub struct CommitFolds {
+    hash: String,
+    files: HashSet<usize>,
+}
+
+/// The files collapsed in the diff of the selected commit, if any.
+fn collapsed(state: &AppState) -> Option<&HashSet<usize>> {
+    let folds = &state.main_screen.commit_folds;
+    (!folds.files.is_empty()
+        && state.get_selected_commit_hash().as_deref() == Some(folds.hash.as_str()))
+    .then_some(&folds.files)
+}
+
+/// Where the `diff --git` line of `file` is. The first file of a commit
+/// starts with the commit's header and stat summary.
+fn header_line(file: &FileDiff) -> Option<usize> {
+    file.lines
+        .iter()
+        .position(|line| line.starts_with("diff --git "))
+}
+
+/// `file` cut down to its header, telling how many lines are hidden.
+fn fold(file: &FileDiff) -> FileDiff {
+    let Some(header) = header_line(file) else {
+        return file.clone();
+    };
+    let hidden = file.lines.len() - header - 1;
+    let mut lines = file.lines[..header].to_vec();
+    lines.push(format!(
+        "{}  (+{hidden} lines, Enter expands)",
+        file.lines[header]
+    ));
+    FileDiff {
+        file_name: file.file_name.clone(),
+        old_file_name: file.old_file_name.clone(),
+        hunks: Vec::new(),
+        lines,
+        status: file.status.clone(),
+        binary: file.binary.clone(),
+    }
+}
+
+/// The files of the selected commit as its diff shows them, the collapsed
+/// ones cut down to their header.
+pub fn shown_files(state: &AppState) -> Cow<'_, [FileDiff]> {
+    let Some(collapsed) = collapsed(state) else {
+        return Cow::Borrowed(&state.selected_commit_files);
+    };
+    Cow::Owned(
+        state
+            .selected_commit_files
+            .iter()
+            .enumerate()
+            .map(|(index, file)| {
+                if collapsed.contains(&index) {
+                    fold(file)
+                } else {
+                    file.clone()
+                }
+            })
+            .collect(),
+    )
+}
+
+/// The word spans of the lines [`shown_files`] shows. A collapsed header
+/// has none, so that its note is shown.
+pub fn shown_word_spans(state: &AppState) -> Cow<'_, [WordSpans]> {
+    let spans = &state.selected_commit_word_spans;
+    let Some(collapsed) = collapsed(state) else {
+        return Cow::Borrowed(spans);
+    };
+    let mut shown = Vec::new();
+    let mut offset = 0;
+    for (index, file) in state.selected_commit_files.iter().enumerate() {
+        let end = offset + file.lines.len();
+        match header_line(file).filter(|_| collapsed.contains(&index)) {
+            Some(header) => {
+                shown.extend(spans.iter().take(offset + header).skip(offset).cloned());
+                shown.push(None);
+            }
+            None => shown.extend(spans.iter().take(end).skip(offset).cloned()),
+        }
+        offset = end;
+    }
+    Cow::Owned(shown)
+}
+
+/// The index of the file shown at `line`, and where its header is.
+fn file_at(files: &[FileDiff], line: usize) -> Option<(usize, usize)> {
+    let mut offset = 0;
+    for (index, file) in files.iter().enumerate() {
+        if line < offset + file.lines.len() {
+            return Some((index, offset + header_line(file)?));
+        }
+        offset += file.lines.len();
+    }
+    None
+}
+
+/// The folds of the selected commit, emptied if they were another's.
+fn folds_of_selected(state: &mut AppState) -> Option<&mut HashSet<usize>> {
+    let hash = state.get_selected_commit_hash()?;
+    let folds = &mut state.main_screen.commit_folds;
+    if folds.hash != hash {
+        folds.hash = hash;
+        folds.files.clear();
+    }
+    Some(&mut folds.files)
+}
+
+/// Collapses or expands the file whose header the diff cursor is on.
+/// Whether the cursor was on one.
+pub fn toggle_at_cursor(state: &mut AppState) -> bool {
+    let cursor = state.main_screen.line_cursor;
+    let index = match file_at(&shown_files(state), cursor) {
+        Some((index, header)) if header == cursor => index,
+        _ => return false,
+    };
+    let Some(folds) = folds_of_selected(state) else {
+        return false;
+    };
+    if !folds.remove(&index) {
+        folds.insert(index);
+    }
+    true
+}
+
+/// Collapses every file of the selected commit's diff, or expands them all
+/// when some are collapsed. The diff cursor stays on its file's header.
+pub fn toggle_all(state: &mut AppState) {
+    if !matches!(
+        state.current_main_item(),
+        Some(ListItem::PreviousCommitInfo { .. })
+    ) {
+        state.set_message(Severity::Warning, "Select a commit to collapse its files");
+        state.alert();
+        return;
+    }
+    let cursor_file = file_at(&shown_files(state), state.main_screen.line_cursor)
+        .map(|(index, _)| index)
+        .filter(|_| state.main_screen.is_diff_cursor_active);
+    let count = state.selected_commit_files.len();
+    let Some(folds) = folds_of_selected(state) else {
+        return;
+    };
+    let message = if folds.is_empty() {
+        folds.extend(0..count);
+        format!("Collapsed {count} files, Enter on one expands it")
+    } else {
+        folds.clear();
+        format!("Expanded {count} files")
+    };
+
+    let line = cursor_file.and_then(|index| {
+        let files = shown_files(state);
+        let start: usize = files[..index].iter().map(|file| file.lines.len()).sum();
+        Some(start + header_line(&files[index])?)
+    });
+    match line {
+        Some(line) => {
+            state.main_screen.line_cursor = line;
+            state.main_screen.diff_scroll = line.saturating_sub(CONTEXT_LINES);
+        }
+        None => {
+            state.main_screen.line_cursor = 0;
+            state.main_screen.diff_scroll = 0;
+        }
+    }
+    state.set_message(Severity::Info, message);
+}
@@ -1,12 +1,500 @@
-use crate::app_state::AppState;
+use crate::app_state::{AppState, EditorRequest, MainScreenState};
+use crate::commit_lint::{self, Severity};
+use crate::commit_scope;
 use crate::commit_storage;
-use crate::git;
+use crate::empty_commit;
+use crate::git::{self, HookFailure};
+use crate::message_log;
+use crate::staged_test;
+use crate::ui::hook_output;
+use crate::ui::index_lock::{self, HeldAction};
+use crate::ui::layout;
+use crate::ui::main_screen::ListItem;
+use crate::ui::rebase_recovery::{self, Retry, Snapshot};
+use crate::ui::reword_preview;
 use pancurses::COLOR_PAIR;
 use pancurses::Input;
+use std::path::PathBuf;
+use std::time::Instant;
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 const COMMIT_INPUT_PREFIX: &str = " ○ ";
 
+/// Char index where the line of `text` with the char index `cursor` starts.
+fn line_start(text: &str, cursor: usize) -> usize {
+    let before: Vec<char> = text.chars().take(cursor).collect();
+    before.iter().rposition(|c| *c == '\n').map_or(0, |i| i + 1)
+}
+
+/// Char index of the end of the line with `cursor`, before its line break.
+fn line_end(text: &str, cursor: usize) -> usize {
+    let cursor = cursor.min(text.chars().count());
+    cursor + text.chars().skip(cursor).take_while(|c| *c != '\n').count()
+}
+
+/// The line the cursor is on and the cursor within it. The input row edits
+/// one line at a time.
+pub fn current_line(text: &str, cursor: usize) -> (String, usize) {
+    let cursor = cursor.min(text.chars().count());
+    let start = line_start(text, cursor);
+    let end = line_end(text, cursor);
+    (
+        text.chars().skip(start).take(end - start).collect(),
+        cursor - start,
+    )
+}
+
+/// Moves `cursor` to the line above or below, keeping its column where that
+/// line is long enough. Returns false on the first or last line, where Up
+/// and Down leave the input.
+pub fn move_cursor_vertically(text: &str, cursor: &mut usize, up: bool) -> bool {
+    let start = line_start(text, *cursor);
+    let column = *cursor - start;
+    let target_start = if up {
+        if start == 0 {
+            return false;
+        }
+        line_start(text, start - 1)
+    } else {
+        let end = line_end(text, *cursor);
+        if end >= text.chars().count() {
+            return false;
+        }
+        end + 1
+    };
+    let target_length = line_end(text, target_start) - target_start;
+    *cursor = target_start + column.min(target_length);
+    true
+}
+
+/// The message being edited in the main pane and its cursor: the amend
+/// message while amending, otherwise the one of the next commit.
+fn edited_message(main_screen: &mut MainScreenState) -> Option<(&mut String, &mut usize)> {
+    match main_screen.list_items.get_mut(main_screen.file_cursor) {
+        Some(ListItem::AmendingCommitMessageInput { message, .. }) => {
+            Some((message, &mut main_screen.commit_cursor))
+        }
+        Some(ListItem::CommitMessageInput) => Some((
+            &mut main_screen.commit_message,
+            &mut main_screen.commit_cursor,
+        )),
+        _ => None,
+    }
+}
+
+/// The message of the selected input in the main pane.
+fn selected_message(state: &AppState) -> Option<&str> {
+    match state.current_main_item() {
+        Some(ListItem::AmendingCommitMessageInput { message, .. }) => Some(message),
+        Some(ListItem::CommitMessageInput) => Some(&state.main_screen.commit_message),
+        _ => None,
+    }
+}
+
+/// The problems of the selected message for the status line, and the
+/// worst of them to color it by.
+pub fn lint_summary(state: &AppState) -> Option<(Severity, String)> {
+    let problems = commit_lint::check(&state.config, selected_message(state)?);
+    let worst = problems.first()?.severity;
+    let texts: Vec<&str> = problems
+        .iter()
+        .map(|problem| problem.text.as_str())
+        .collect();
+    Some((worst, texts.join(". ")))
+}
+
+/// The scope offered for the staged files while the cursor of the selected
+/// message is right after `type(` at the start of its subject.
+pub fn scope_completion(state: &AppState) -> Option<String> {
+    let message = selected_message(state)?;
+    let cursor = state.main_screen.commit_cursor;
+    if line_start(message, cursor) != 0 {
+        return None;
+    }
+    let (line, column) = current_line(message, cursor);
+    let before: String = line.chars().take(column).collect();
+    let after: String = line.chars().skip(column).collect();
+    if !commit_scope::wants_scope(&before, &after) {
+        return None;
+    }
+    let paths: Vec<&str> = state
+        .files
+        .iter()
+        .map(|file| file.file_name.as_str())
+        .collect();
+    commit_scope::infer(&state.config.scopes, &paths)
+}
+
+/// Tab inserts the offered scope, closing the parenthesis unless it is
+/// closed already, and moves the cursor to where the description goes.
+pub fn complete_scope(state: &mut AppState, max_x: i32) {
+    let Some(scope) = scope_completion(state) else {
+        return;
+    };
+    let is_amend = matches!(
+        state.current_main_item(),
+        Some(ListItem::AmendingCommitMessageInput { .. })
+    );
+    let Some((message, cursor)) = edited_message(&mut state.main_screen) else {
+        return;
+    };
+    let byte_offset = message
+        .char_indices()
+        .nth(*cursor)
+        .map_or(message.len(), |(idx, _)| idx);
+    let after = &message[byte_offset..];
+    let closing = after.len() - after.trim_start_matches([')', ':', ' ']).len();
+    if closing == 0 {
+        message.insert_str(byte_offset, &format!("{scope}): "));
+        *cursor += scope.chars().count() + 3;
+    } else {
+        message.insert_str(byte_offset, &scope);
+        *cursor += scope.chars().count() + closing;
+    }
+    let (cursor_position, message_snapshot) = (*cursor, message.clone());
+    if !is_amend {
+        let _ = commit_storage::save_draft(
+            &state.repo_path,
+            &message_snapshot,
+            state.main_screen.commit_template.as_deref(),
+        );
+    }
+    adjust_commit_scroll_state(state, &message_snapshot, cursor_position, max_x);
+}
+
+/// With `block_on_lint`, refuses a message with errors the first time it
+/// is committed, telling why. Returns whether to go ahead.
+fn confirm_lint(state: &mut AppState, message: &str) -> bool {
+    if !state.config.block_on_lint
+        || state.main_screen.lint_confirmed_message.as_deref() == Some(message)
+    {
+        return true;
+    }
+    let problems = commit_lint::check(&state.config, message);
+    let Some(problem) = problems
+        .iter()
+        .find(|problem| problem.severity == Severity::Error)
+    else {
+        return true;
+    };
+    state.set_message(
+        message_log::Severity::Warning,
+        format!("{}. Press Alt+Enter again to commit anyway", problem.text),
+    );
+    state.main_screen.lint_confirmed_message = Some(message.to_string());
+    state.alert();
+    false
+}
+
+/// Going through the messages committed earlier, like the history of a
+/// shell.
+#[derive(Debug, Clone)]
+pub struct HistoryRecall {
+    /// Oldest first.
+    pub entries: Vec<String>,
+    /// The entry shown. `entries.len()` stands for the draft.
+    pub index: usize,
+    /// The message typed before the recall started, back after the newest
+    /// entry.
+    pub draft: String,
+    /// The entry last put in the input. Once the message differs, it was
+    /// edited and a new recall starts from the newest entry.
+    shown: String,
+}
+
+/// Ctrl-P puts the previous message committed through the tool in the
+/// input, Ctrl-N the next one, and finally the draft again. Up and Down
+/// are left to move between the lines. Only the message of the next commit
+/// is recalled into, not the amend message.
+pub fn handle_history_recall(state: &mut AppState, input: &Input, max_x: i32) -> bool {
+    let older = match input {
+        Input::Character('\u{10}') => true,
+        Input::Character('\u{e}') => false,
+        _ => return false,
+    };
+    if !matches!(
+        state.current_main_item(),
+        Some(ListItem::CommitMessageInput)
+    ) {
+        return false;
+    }
+
+    let current = state.main_screen.commit_message.clone();
+    let recall = match state.main_screen.history_recall.take() {
+        Some(recall) if recall.shown == current => Some(recall),
+        _ if older => {
+            let entries = commit_storage::load_history(&state.repo_path);
+            Some(HistoryRecall {
+                index: entries.len(),
+                entries,
+                draft: current.clone(),
+                shown: current,
+            })
+        }
+        _ => None,
+    };
+    let Some(mut recall) = recall else {
+        state.alert();
+        return true;
+    };
+    if recall.entries.is_empty() {
+        state.set_message(
+            message_log::Severity::Warning,
+            "No earlier commit messages".to_string(),
+        );
+        state.alert();
+        return true;
+    }
+    if older && recall.index == 0 {
+        state.alert();
+        state.main_screen.history_recall = Some(recall);
+        return true;
+    }
+    if older {
+        recall.index -= 1;
+    } else {
+        recall.index += 1;
+    }
+
+    let count = recall.entries.len();
+    let message = recall
+        .entries
+        .get(recall.index)
+        .cloned()
+        .unwrap_or_else(|| recall.draft.clone());
+    state.main_screen.commit_message = message.clone();
+    state.main_screen.commit_cursor = line_end(&message, 0);
+    let _ = commit_storage::save_draft(
+        &state.repo_path,
+        &message,
+        state.main_screen.commit_template.as_deref(),
+    );
+    let cursor = state.main_screen.commit_cursor;
+    adjust_commit_scroll_state(state, &message, cursor, max_x);
+    if recall.index < count {
+        state.set_message(
+            message_log::Severity::Info,
+            format!(
+                "Message {}/{count} from history (Ctrl-P: older, Ctrl-N: newer)",
+                count - recall.index
+            ),
+        );
+        recall.shown = message;
+        state.main_screen.history_recall = Some(recall);
+    }
+    true
+}
+
+/// A message opened in the editor with Ctrl-X, read back once the editor
+/// exits.
+pub struct PendingMessageEdit {
+    /// The editor `git commit` would use, see [`git::editor`].
+    pub editor: String,
+    pub edit_file: PathBuf,
+    /// Whether it is the amend message rather than the next commit's.
+    pub amend: bool,
+    /// Why the editor failed, which leaves the message as it was.
+    pub error: Option<String>,
+}
+
+/// Written below the message in the editor, like `git commit` does.
+const EDIT_MESSAGE_HELP: &str = "\n\n# Edit the commit message, then save and quit. Lines starting\n# with '#' are ignored, and exiting the editor with an error\n# keeps the message as it was.\n";
+
+/// Ctrl-X opens the message being edited in the editor git uses for commit
+/// messages (`core.editor`, `$EDITOR`, ...), writing it to `COMMIT_EDITMSG`
+/// like `git commit` does.
+pub fn handle_open_in_editor(state: &mut AppState, input: &Input) -> bool {
+    if *input != Input::Character('\u{18}') {
+        return false;
+    }
+    let amend = matches!(
+        state.current_main_item(),
+        Some(ListItem::AmendingCommitMessageInput { .. })
+    );
+    let Some(message) = selected_message(state).map(str::to_string) else {
+        return false;
+    };
+    let opened = git::editor(&state.repo_path).and_then(|editor| {
+        let edit_file = git::git_path(&state.repo_path, "COMMIT_EDITMSG")?;
+        std::fs::write(&edit_file, format!("{message}{EDIT_MESSAGE_HELP}"))?;
+        Ok((editor, edit_file))
+    });
+    match opened {
+        Ok((editor, edit_file)) => {
+            state.editor_request = Some(EditorRequest {
+                file_path: edit_file.to_string_lossy().into_owned(),
+                line_number: None,
+                stage_if_saved: None,
+                edit_hunk: None,
+                edit_message: Some(PendingMessageEdit {
+                    editor,
+                    edit_file,
+                    amend,
+                    error: None,
+                }),
+                stage_edit: None,
+                return_to: None,
+            });
+        }
+        Err(e) => {
+            state.set_message(
+                message_log::Severity::Error,
+                format!("Failed to open the message in the editor: {e}"),
+            );
+            state.alert();
+        }
+    }
+    true
+}
+
+/// Puts the message saved in the editor in the input it was opened from,
+/// without its comment lines, with the cursor at its start.
+pub fn finish_message_edit(state: &mut AppState, pending: PendingMessageEdit) {
+    let text = std::fs::read_to_string(&pending.edit_file);
+    if let Some(error) = pending.error {
+        state.set_message(
+            message_log::Severity::Error,
+            format!("Editor failed, the message is kept: {error}"),
+        );
+        return;
+    }
+    let text = match text {
+        Ok(text) => text,
+        Err(e) => {
+            state.set_message(
+                message_log::Severity::Error,
+                format!("Failed to read the edited message: {e}"),
+            );
+            return;
+        }
+    };
+    let is_amend = matches!(
+        state.current_main_item(),
+        Some(ListItem::AmendingCommitMessageInput { .. })
+    );
+    if is_amend != pending.amend {
+        return;
+    }
+    let Some((message, cursor)) = edited_message(&mut state.main_screen) else {
+        return;
+    };
+    *message = git::strip_message_comments(&text).unwrap_or_default();
+    *cursor = 0;
+    let message = message.clone();
+    if !pending.amend {
+        let _ = commit_storage::save_draft(
+            &state.repo_path,
+            &message,
+            state.main_screen.commit_template.as_deref(),
+        );
+    }
+    state.main_screen.commit_scroll_offset = 0;
+    state.main_screen.commit_scroll_extra_space = false;
+}
+
+/// Up and Down in a message of several lines move between its lines.
+/// Returns false when the input should be left instead.
+pub fn handle_vertical_movement(state: &mut AppState, input: &Input, max_x: i32) -> bool {
+    let up = match input {
+        Input::KeyUp => true,
+        Input::KeyDown => false,
+        _ => return false,
+    };
+    let Some((message, cursor)) = edited_message(&mut state.main_screen) else {
+        return false;
+    };
+    if !move_cursor_vertically(message, cursor, up) {
+        return false;
+    }
+    let (cursor_position, message_snapshot) = (*cursor, message.clone());
+    adjust_commit_scroll_state(state, &message_snapshot, cursor_position, max_x);
+    true
+}
+
+/// Amends the commit `hash` with the staged changes and `message`, or only
+/// rewords it when nothing is staged.
+pub fn amend_commit(state: &mut AppState, hash: &str, message: &str) {
+    let has_staged_changes = !state.files.is_empty();
+    let snapshot = Snapshot::take(&state.repo_path);
+    let started = Instant::now();
+    let (operation, result) = if has_staged_changes {
+        (
+            "Amend",
+            git::amend_commit_with_staged_changes(&state.repo_path, hash, message),
+        )
+    } else {
+        (
+            "Reword",
+            git::reword_commit(&state.repo_path, hash, message),
+        )
+    };
+    state.main_screen.amending_commit_hash = None;
+    let outcome = result.as_ref().map(|_| ()).map_err(|e| e.to_string());
+    state.notify_finished(operation, started, outcome);
+
+    if let Err(e) = result {
+        state.set_message(
+            message_log::Severity::Error,
+            format!("Error committing: {e}"),
+        );
+        let retry = Retry::Amend {
+            hash: hash.to_string(),
+            message: message.to_string(),
+        };
+        rebase_recovery::open(state, operation, Some(e.to_string()), snapshot, &[], retry);
+        return;
+    }
+    let _ = commit_storage::add_to_history(&state.repo_path, message);
+    finish_commit(state);
+}
+
+/// Stages everything for the next commit, or quits once nothing is left.
+fn finish_commit(state: &mut AppState) {
+    state.command_history.clear();
+    let staged_diff_output = git::add_all_with_size_limit(&state.repo_path, state.max_file_size())
+        .and_then(|()| git::get_staged_diff_output(&state.repo_path));
+    let staged_diff_output = match staged_diff_output {
+        Ok(output) => output,
+        Err(e) => {
+            state.set_message(
+                message_log::Severity::Error,
+                format!(
+                    "Committed, but staging the rest failed: {}",
+                    message_log::reason(&e)
+                ),
+            );
+            state.refresh_diff(true);
+            return;
+        }
+    };
+
+    if staged_diff_output.stdout.is_empty() {
+        // The last part of a split is in, so the newer commits go back on top.
+        if let Some(mut split) = state.split.take()
+            && let Err(e) = split.finish(&state.repo_path)
+        {
+            state.set_message(
+                message_log::Severity::Error,
+                format!(
+                    "Could not put the commits after {} back on top, S restores the original: {e}",
+                    split.hash
+                ),
+            );
+            state.split = Some(split);
+            state.refresh_diff(true);
+            return;
+        }
+        state.running = false;
+    } else {
+        state.refresh_diff(true);
+        state.unstage_never_commit(false);
+        if state.main_screen.commit_message.is_empty() {
+            state.fill_commit_template();
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn render_editor(
     window: &pancurses::Window,
     text: &str,
@@ -27,18 +515,15 @@ pub fn render_editor(
     }
     window.mv(line_y, 0);
 
-    window.addstr(prefix);
-    let max_x_usize = if max_x < 0 { 0 } else { max_x as usize };
-    let prefix_width = prefix.width();
-    let available_width = max_x_usize.saturating_sub(prefix_width);
-
-    let (displayed_text, cursor_column_relative) = build_display_line(
+    let (displayed_prefix, displayed_text, caret_x) = layout_editor_line(
         text,
         cursor,
-        available_width,
+        max_x,
+        prefix,
         scroll_offset,
         scroll_extra_space,
     );
+    window.addstr(&displayed_prefix);
 
     if !displayed_text.is_empty() {
         window.addstr(&displayed_text);
@@ -46,10 +531,71 @@ pub fn render_editor(
 
     window.attroff(COLOR_PAIR(pair));
 
+    (caret_x as i32, line_y)
+}
+
+/// Computes what a single-line editor shows in `max_x` columns: the prefix
+/// (cut when the terminal is narrower than it), the visible part of the text
+/// and the caret column. The stored scroll offset is only used if it keeps the
+/// caret on screen, since it may have been computed for a wider terminal.
+pub fn layout_editor_line(
+    text: &str,
+    cursor: usize,
+    max_x: i32,
+    prefix: &str,
+    scroll_offset: usize,
+    scroll_extra_space: bool,
+) -> (String, String, usize) {
+    let max_x_usize = max_x.max(0) as usize;
+    let displayed_prefix = layout::truncate_to_width(prefix, max_x_usize.saturating_sub(1));
+    let prefix_width = displayed_prefix.width();
+    let available_width = max_x_usize.saturating_sub(prefix_width);
+
+    let (scroll_offset, scroll_extra_space) = if caret_fits(
+        text,
+        cursor,
+        available_width,
+        scroll_offset,
+        scroll_extra_space,
+    ) {
+        (scroll_offset, scroll_extra_space)
+    } else {
+        compute_scroll_for_prefix(text, cursor, max_x, &displayed_prefix)
+    };
+
+    let (displayed_text, cursor_column_relative) = build_display_line(
+        text,
+        cursor,
+        available_width,
+        scroll_offset,
+        scroll_extra_space,
+    );
+
     let cursor_display_pos = prefix_width.saturating_add(cursor_column_relative);
     let clamped_cursor = cursor_display_pos.min(max_x_usize.saturating_sub(1));
 
-    (clamped_cursor as i32, line_y)
+    (displayed_prefix, displayed_text, clamped_cursor)
+}
+
+fn caret_fits(
+    text: &str,
+    cursor: usize,
+    available_width: usize,
+    scroll_offset: usize,
+    scroll_extra_space: bool,
+) -> bool {
+    let chars: Vec<char> = text.chars().collect();
+    let cursor_index = cursor.min(chars.len());
+    if scroll_offset > cursor_index {
+        return false;
+    }
+    let prefix_widths = prefix_widths_for(&chars);
+    let ellipsis_width = match (scroll_offset > 0, scroll_extra_space) {
+        (false, _) => 0,
+        (true, false) => 1,
+        (true, true) => 2,
+    };
+    prefix_widths[cursor_index] - prefix_widths[scroll_offset] + ellipsis_width < available_width
 }
 
 pub fn render(
@@ -60,9 +606,8 @@ pub fn render(
     max_x: i32,
 ) -> (i32, i32) {
     let (message, placeholder) =
-        if let Some(crate::ui::main_screen::ListItem::AmendingCommitMessageInput {
-            message, ..
-        }) = state.current_main_item()
+        if let Some(ListItem::AmendingCommitMessageInput { message, .. }) =
+            state.current_main_item()
         {
             (message.as_str(), "Enter amend message...")
         } else {
@@ -72,6 +617,70 @@ pub fn render(
             )
         };
 
+    let Some((label, pair)) = staged_test_label(state, is_selected)
+        .filter(|(label, _)| (max_x.max(0) as usize) > label.width() + MIN_INPUT_WIDTH)
+    else {
+        return render_input(
+            window,
+            state,
+            message,
+            placeholder,
+            is_selected,
+            line_y,
+            max_x,
+        );
+    };
+    let label_x = max_x - label.width() as i32;
+    let caret = render_input(
+        window,
+        state,
+        message,
+        placeholder,
+        is_selected,
+        line_y,
+        label_x,
+    );
+    window.attron(COLOR_PAIR(pair));
+    window.mvaddstr(line_y, label_x, &label);
+    window.attroff(COLOR_PAIR(pair));
+    caret
+}
+
+/// Columns the commit input keeps before the staged test status is shown
+/// next to it.
+const MIN_INPUT_WIDTH: usize = 20;
+
+/// The status of `staged_test_command` for what is staged, and the color
+/// pair it is drawn in, unless there is nothing to show.
+fn staged_test_label(state: &AppState, is_selected: bool) -> Option<(String, u32)> {
+    if state.main_screen.amending_commit_hash.is_some() {
+        return None;
+    }
+    let test = state.staged_test.as_ref()?;
+    let is_current = state.staged_tree.as_deref() == Some(test.tree.as_str());
+    let (pair, selected_pair) = match &test.status {
+        staged_test::Status::Running => (9, 10),
+        _ if !is_current && state.staged_tree.is_none() => return None,
+        _ if !is_current => (9, 10),
+        staged_test::Status::Passed => (3, 7),
+        staged_test::Status::Failed(_) => (2, 6),
+    };
+    let label = test.label(state.staged_tree.as_deref());
+    Some((
+        format!(" {label} "),
+        if is_selected { selected_pair } else { pair },
+    ))
+}
+
+fn render_input(
+    window: &pancurses::Window,
+    state: &AppState,
+    message: &str,
+    placeholder: &str,
+    is_selected: bool,
+    line_y: i32,
+    max_x: i32,
+) -> (i32, i32) {
     if message.is_empty() {
         let pair = if is_selected { 5 } else { 1 };
         window.attron(COLOR_PAIR(pair));
@@ -84,7 +693,8 @@ pub fn render(
         window.addstr(COMMIT_INPUT_PREFIX);
         let placeholder_pair = if is_selected { 16 } else { 9 };
         window.attron(COLOR_PAIR(placeholder_pair));
-        window.addstr(placeholder);
+        let available_width = (max_x.max(0) as usize).saturating_sub(COMMIT_INPUT_PREFIX.width());
+        window.addstr(layout::truncate_to_width(placeholder, available_width));
         window.attroff(COLOR_PAIR(placeholder_pair));
         window.attroff(COLOR_PAIR(pair));
         (
@@ -92,10 +702,11 @@ pub fn render(
             line_y,
         )
     } else {
+        let (line, cursor) = current_line(message, state.main_screen.commit_cursor);
         render_editor(
             window,
-            message,
-            state.main_screen.commit_cursor,
+            &line,
+            cursor,
             is_selected,
             line_y,
             max_x,
@@ -106,6 +717,71 @@ pub fn render(
     }
 }
 
+/// Draws the whole message being edited in the diff view below the list,
+/// wrapped at the right edge, with the line the input row edits
+/// highlighted.
+pub fn render_message(window: &pancurses::Window, state: &AppState, top: usize, height: usize) {
+    let (message, title) = match state.current_main_item() {
+        Some(ListItem::AmendingCommitMessageInput { message, .. }) => (
+            message.as_str(),
+            " Amend message (Enter: new line, Alt+Enter: amend) ",
+        ),
+        Some(ListItem::CommitMessageInput) if state.main_screen.amending_commit_hash.is_none() => (
+            state.main_screen.commit_message.as_str(),
+            " Commit message (Enter: new line, Alt+Enter: commit) ",
+        ),
+        _ => return,
+    };
+    if height == 0 {
+        return;
+    }
+    let (_, max_x) = window.get_max_yx();
+    let width = (max_x.max(0) as usize).saturating_sub(2).max(1);
+    let cursor = state.main_screen.commit_cursor.min(message.chars().count());
+    let current = message.chars().take(cursor).filter(|c| *c == '\n').count();
+
+    let mut rows = Vec::new();
+    let mut current_rows = 0..0;
+    for (index, line) in message.split('\n').enumerate() {
+        let start = rows.len();
+        rows.extend(
+            layout::wrap_line(line, width)
+                .into_iter()
+                .map(|row| (row, index == current)),
+        );
+        if index == current {
+            current_rows = start..rows.len();
+        }
+    }
+
+    window.attron(COLOR_PAIR(8) | pancurses::A_BOLD);
+    window.mvaddstr(
+        top as i32,
+        0,
+        layout::truncate_to_width(title, max_x.max(0) as usize),
+    );
+    window.attroff(COLOR_PAIR(8) | pancurses::A_BOLD);
+
+    // Keep the edited line in view.
+    let visible = height - 1;
+    let first = current_rows
+        .end
+        .saturating_sub(visible)
+        .min(current_rows.start);
+    for (row, (text, is_current)) in rows.iter().skip(first).take(visible).enumerate() {
+        let y = (top + 1 + row) as i32;
+        let pair = if *is_current { 5 } else { 1 };
+        window.attron(COLOR_PAIR(pair));
+        if *is_current {
+            for x in 0..max_x {
+                window.mvaddch(y, x, ' ');
+            }
+        }
+        window.mvaddstr(y, 1, text);
+        window.attroff(COLOR_PAIR(pair));
+    }
+}
+
 pub fn compute_scroll_for_prefix(
     text: &str,
     cursor: usize,
@@ -188,10 +864,6 @@ fn prefix_widths_for(chars: &[char]) -> Vec<usize> {
 }
 
 fn compute_commit_scroll(text: &str, cursor: usize, available_width: usize) -> (usize, bool) {
-    if available_width <= 4 {
-        return (0, false);
-    }
-
     let chars: Vec<char> = text.chars().collect();
     if chars.is_empty() {
         return (0, false);
@@ -204,6 +876,18 @@ fn compute_commit_scroll(text: &str, cursor: usize, available_width: usize) -> (
     let prefix_widths = prefix_widths_for(&chars);
     let cursor_absolute_width = prefix_widths[cursor_index];
 
+    if available_width <= 4 {
+        // Too narrow for the usual look-ahead margin: scroll just enough for
+        // the caret to sit in the last column.
+        if cursor_absolute_width < available_width {
+            return (0, false);
+        }
+        let offset = (1..=cursor_index)
+            .find(|&i| cursor_absolute_width - prefix_widths[i] + 1 < available_width)
+            .unwrap_or(cursor_index);
+        return (offset, false);
+    }
+
     let right_scroll_trigger = available_width.saturating_sub(5);
     if cursor_absolute_width <= right_scroll_trigger {
         return (0, false);
@@ -271,8 +955,10 @@ fn compute_commit_scroll(text: &str, cursor: usize, available_width: usize) -> (
 }
 
 fn adjust_commit_scroll_state(state: &mut AppState, text: &str, cursor: usize, max_x: i32) {
-    let (offset, extra_space) = compute_scroll_for_prefix(text, cursor, max_x, COMMIT_INPUT_PREFIX);
-    state.main_screen.commit_scroll_offset = offset.min(text.chars().count());
+    let (line, cursor) = current_line(text, cursor);
+    let (offset, extra_space) =
+        compute_scroll_for_prefix(&line, cursor, max_x, COMMIT_INPUT_PREFIX);
+    state.main_screen.commit_scroll_offset = offset.min(line.chars().count());
     state.main_screen.commit_scroll_extra_space = extra_space;
 }
 
@@ -338,20 +1024,20 @@ pub fn handle_generic_text_input_with_alt(text: &mut String, cursor: &mut usize,
 
 pub fn handle_generic_text_input(text: &mut String, cursor: &mut usize, input: Input) {
     match input {
-        Input::KeyBackspace | Input::Character('\x7f') | Input::Character('\x08') => {
-            if *cursor > 0 {
-                let char_index_to_remove = *cursor - 1;
-                if let Some((byte_index, _)) = text.char_indices().nth(char_index_to_remove) {
-                    text.remove(byte_index);
-                    *cursor -= 1;
-                }
+        Input::KeyBackspace | Input::Character('\x7f') | Input::Character('\x08')
+            if *cursor > 0 =>
+        {
+            let char_index_to_remove = *cursor - 1;
+            if let Some((byte_index, _)) = text.char_indices().nth(char_index_to_remove) {
+                text.remove(byte_index);
+                *cursor -= 1;
             }
         }
         Input::KeyDC => {
-            if *cursor < text.chars().count() {
-                if let Some((byte_index, _)) = text.char_indices().nth(*cursor) {
-                    text.remove(byte_index);
-                }
+            if *cursor < text.chars().count()
+                && let Some((byte_index, _)) = text.char_indices().nth(*cursor)
+            {
+                text.remove(byte_index);
             }
         }
         Input::KeyLeft => {
@@ -364,19 +1050,21 @@ pub fn handle_generic_text_input(text: &mut String, cursor: &mut usize, input: I
         Input::Character(c) => {
             if c == '\u{1}' {
                 // Ctrl-A: beginning of line
-                *cursor = 0;
+                *cursor = line_start(text, *cursor);
             } else if c == '\u{5}' {
                 // Ctrl-E: end of line
-                *cursor = text.chars().count();
+                *cursor = line_end(text, *cursor);
             } else if c == '\u{b}' {
-                // Ctrl-K: kill to end of line
-                if *cursor < text.chars().count() {
-                    let byte_offset = text
-                        .char_indices()
-                        .nth(*cursor)
-                        .map_or(text.len(), |(idx, _)| idx);
-                    text.truncate(byte_offset);
-                }
+                // Ctrl-K: kill to end of line, or join the next one at its end
+                let end = line_end(text, *cursor);
+                let end = if end == *cursor { end + 1 } else { end };
+                let byte_at = |index: usize| {
+                    text.char_indices()
+                        .nth(index)
+                        .map_or(text.len(), |(idx, _)| idx)
+                };
+                let range = byte_at(*cursor)..byte_at(end);
+                text.replace_range(range, "");
             } else if !c.is_control() {
                 let byte_offset = text
                     .char_indices()
@@ -390,123 +1078,125 @@ pub fn handle_generic_text_input(text: &mut String, cursor: &mut usize, input: I
     }
 }
 
-pub fn handle_commit_input_with_alt(state: &mut AppState, input: Input, max_x: i32) {
-    let is_amend = matches!(
-        state.current_main_item(),
-        Some(crate::ui::main_screen::ListItem::AmendingCommitMessageInput { .. })
-    );
-
-    let (message_to_edit, cursor_to_edit) = if is_amend {
-        if let Some(crate::ui::main_screen::ListItem::AmendingCommitMessageInput {
-            message, ..
-        }) = state
-            .main_screen
-            .list_items
-            .get_mut(state.main_screen.file_cursor)
-        {
-            (Some(message), Some(&mut state.main_screen.commit_cursor))
-        } else {
-            (None, None)
-        }
-    } else {
-        (
-            Some(&mut state.main_screen.commit_message),
-            Some(&mut state.main_screen.commit_cursor),
-        )
+/// Commits with the message being edited, or amends the commit it is for.
+/// A message of nothing but blank space is ignored.
+fn submit_message(state: &mut AppState) {
+    let Some((message, _)) = edited_message(&mut state.main_screen) else {
+        return;
     };
+    if message.trim().is_empty() {
+        return;
+    }
+    let message = message.clone();
+    if !confirm_lint(state, &message) {
+        return;
+    }
 
-    if let (Some(message), Some(cursor)) = (message_to_edit, cursor_to_edit) {
-        let (cursor_position, message_snapshot) = {
-            handle_generic_text_input_with_alt(message, cursor, input);
-            (*cursor, message.clone())
+    if let Some(ListItem::AmendingCommitMessageInput { .. }) = state.current_main_item() {
+        let Some(hash) = state.main_screen.amending_commit_hash.clone() else {
+            // This case should not happen, but for safety...
+            return;
         };
-        adjust_commit_scroll_state(state, message_snapshot.as_str(), cursor_position, max_x);
+        reword_preview::open_or_amend(state, hash, message);
+        return;
+    }
+    commit(state, &message, true);
+}
+
+/// Shown as the name of the hook when `staged_check_command` fails.
+const STAGED_CHECK: &str = "staged tree check";
+
+/// Commits the staged changes with `message`, running the staged tree
+/// check and the commit hooks unless `verify` is false. When a hook fails its output is shown, and
+/// the message stays in the input field.
+pub fn commit(state: &mut AppState, message: &str, verify: bool) {
+    if let Some(lock) = git::index_lock(&state.repo_path) {
+        let action = HeldAction::Commit {
+            message: message.to_string(),
+            verify,
+        };
+        index_lock::open(state, lock, action);
+        return;
+    }
+    if empty_commit::refuse(state, message) {
+        return;
+    }
+    if verify && let Some(command) = state.config.staged_check_command.clone() {
+        let failure = match git::check_staged_tree(&state.repo_path, &command) {
+            Ok(failure) => failure,
+            Err(e) => Some(e.to_string()),
+        };
+        if let Some(output) = failure {
+            let failure = HookFailure {
+                hooks: vec![STAGED_CHECK.to_string()],
+                output,
+            };
+            hook_output::open(state, failure, message);
+            return;
+        }
+    }
+    if let Err(e) = git::commit(&state.repo_path, message, verify) {
+        match e.downcast::<HookFailure>() {
+            Ok(failure) => hook_output::open(state, failure, message),
+            Err(e) => state.set_message(
+                message_log::Severity::Error,
+                format!("Error committing: {e}"),
+            ),
+        }
+        return;
+    }
+    let _ = commit_storage::add_to_history(&state.repo_path, message);
+    state.main_screen.history_recall = None;
+    let _ = commit_storage::delete_commit_message(&state.repo_path);
+    state.main_screen.commit_message.clear();
+    state.main_screen.commit_cursor = 0;
+    state.main_screen.commit_scroll_offset = 0;
+    state.main_screen.commit_scroll_extra_space = false;
+    finish_commit(state);
+}
+
+pub fn handle_commit_input_with_alt(state: &mut AppState, input: Input, max_x: i32) {
+    if input == Input::Character('\n') {
+        submit_message(state);
+        return;
+    }
+    if let Some((message, cursor)) = edited_message(&mut state.main_screen) {
+        handle_generic_text_input_with_alt(message, cursor, input);
+        let (cursor_position, message_snapshot) = (*cursor, message.clone());
+        adjust_commit_scroll_state(state, &message_snapshot, cursor_position, max_x);
     }
 }
 
 pub fn handle_commit_input(state: &mut AppState, input: Input, _max_y: i32, max_x: i32) {
     let is_amend = matches!(
         state.current_main_item(),
-        Some(crate::ui::main_screen::ListItem::AmendingCommitMessageInput { .. })
+        Some(ListItem::AmendingCommitMessageInput { .. })
     );
-
-    let (message_to_edit, cursor_to_edit) = if is_amend {
-        if let Some(crate::ui::main_screen::ListItem::AmendingCommitMessageInput {
-            message, ..
-        }) = state
-            .main_screen
-            .list_items
-            .get_mut(state.main_screen.file_cursor)
-        {
-            (Some(message), Some(&mut state.main_screen.commit_cursor))
-        } else {
-            (None, None)
-        }
-    } else {
-        (
-            Some(&mut state.main_screen.commit_message),
-            Some(&mut state.main_screen.commit_cursor),
-        )
-    };
-
-    let (Some(message), Some(cursor)) = (message_to_edit, cursor_to_edit) else {
+    let Some((message, cursor)) = edited_message(&mut state.main_screen) else {
         return;
     };
 
     if input == Input::Character('\n') {
+        // A message does not start with a blank line.
         if message.is_empty() {
             return;
         }
-
-        let commit_result = if is_amend {
-            if let Some(hash) = state.main_screen.amending_commit_hash.clone() {
-                let has_staged_changes = !state.files.is_empty();
-                let result = if has_staged_changes {
-                    git::amend_commit_with_staged_changes(&state.repo_path, &hash, message)
-                } else {
-                    git::reword_commit(&state.repo_path, &hash, message)
-                };
-                state.main_screen.amending_commit_hash = None;
-                result
-            } else {
-                // This case should not happen, but for safety...
-                return;
-            }
-        } else {
-            let result = git::commit(&state.repo_path, message);
-            if result.is_ok() {
-                let _ = commit_storage::delete_commit_message(&state.repo_path);
-                message.clear();
-                state.main_screen.commit_scroll_offset = 0;
-                state.main_screen.commit_scroll_extra_space = false;
-            }
-            result
-        };
-
-        if let Err(e) = commit_result {
-            state.error_message = Some(format!("Error committing: {e}"));
-            return;
-        }
-
-        state.command_history.clear();
-        git::add_all(&state.repo_path).expect("Failed to git add -A.");
-
-        let staged_diff_output =
-            git::get_staged_diff_output(&state.repo_path).expect("Failed to git diff --staged.");
-
-        if staged_diff_output.stdout.is_empty() {
-            state.running = false;
-        } else {
-            state.refresh_diff(true);
-        }
+        let byte_offset = message
+            .char_indices()
+            .nth(*cursor)
+            .map_or(message.len(), |(idx, _)| idx);
+        message.insert(byte_offset, '\n');
+        *cursor += 1;
     } else {
-        let (cursor_position, message_snapshot) = {
-            handle_generic_text_input(message, cursor, input);
-            if !is_amend {
-                let _ = commit_storage::save_commit_message(&state.repo_path, message);
-            }
-            (*cursor, message.clone())
-        };
-        adjust_commit_scroll_state(state, message_snapshot.as_str(), cursor_position, max_x);
+        handle_generic_text_input(message, cursor, input);
+    }
+    let (cursor_position, message_snapshot) = (*cursor, message.clone());
+    if !is_amend {
+        let _ = commit_storage::save_draft(
+            &state.repo_path,
+            &message_snapshot,
+            state.main_screen.commit_template.as_deref(),
+        );
     }
+    adjust_commit_scroll_state(state, &message_snapshot, cursor_position, max_x);
 }
@@ -0,0 +1,87 @@
+use crate::app_state::{AppState, Screen};
+use crate::diagnostics::{Diagnostics, RECENT_MESSAGE_LIMIT};
+use crate::ui::chrome;
+use crate::ui::layout;
+use pancurses::{Input, Window};
+
+const TITLE: &str = "Diagnostics";
+
+const HINTS: &str = "q close";
+
+/// Lines above the diagnostics.
+const BANNER_HEIGHT: usize = 1;
+
+/// Screen showing the versions, the paths in use and the recent messages,
+/// the same as `--doctor` prints.
+pub struct DiagnosticsView {
+    pub lines: Vec<String>,
+    pub scroll: usize,
+}
+
+impl DiagnosticsView {
+    fn content_height(max_y: i32) -> usize {
+        (max_y.max(0) as usize).saturating_sub(BANNER_HEIGHT + 1)
+    }
+
+    fn scroll_by(&mut self, delta: isize, max_y: i32) {
+        let max_scroll = self.lines.len().saturating_sub(Self::content_height(max_y));
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max_scroll);
+    }
+}
+
+pub fn open(state: &mut AppState) {
+    let diagnostics = Diagnostics::collect(
+        Some(&state.repo_path),
+        &state.config,
+        Some(&state.message_log.recent_texts(RECENT_MESSAGE_LIMIT)),
+    );
+    state.screen = Some(Screen::Diagnostics(DiagnosticsView {
+        lines: diagnostics.lines(),
+        scroll: 0,
+    }));
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let Some(Screen::Diagnostics(view)) = state.screen.as_mut() else {
+        return;
+    };
+    let page = DiagnosticsView::content_height(max_y).max(1) as isize;
+
+    match input {
+        Input::Character('q')
+        | Input::Character('D')
+        | Input::Character('\u{1b}')
+        | Input::Character('\t') => state.screen = None,
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            view.scroll_by(1, max_y)
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            view.scroll_by(-1, max_y)
+        }
+        Input::Character(' ') | Input::Character('\u{16}') | Input::KeyNPage => {
+            view.scroll_by(page, max_y)
+        }
+        Input::Character('b') | Input::KeyPPage => view.scroll_by(-page, max_y),
+        _ => {}
+    }
+}
+
+pub fn render(window: &Window, repo: &str, view: &DiagnosticsView) {
+    let (max_y, max_x) = window.get_max_yx();
+    let width = max_x.max(0) as usize;
+
+    chrome::render_title(window, repo, &[TITLE], HINTS);
+
+    let visible = view
+        .lines
+        .iter()
+        .skip(view.scroll)
+        .take(DiagnosticsView::content_height(max_y));
+    for (row, line) in visible.enumerate() {
+        window.mvaddstr(
+            (BANNER_HEIGHT + row) as i32,
+            1,
+            layout::truncate_to_width(line, width.saturating_sub(1)),
+        );
+    }
+}
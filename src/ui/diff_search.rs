@@ -0,0 +1,163 @@
+use crate::app_state::{AppState, FocusedPane};
+use crate::message_log::Severity;
+use crate::ui::main_screen;
+use crate::ui::prompt::{Prompt, PromptAction};
+
+/// Lines kept above a match when the diff is scrolled to it.
+const CONTEXT_LINES: usize = 3;
+
+/// Text searched with `/` in the diff of the main or the unstaged pane.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffSearch {
+    pub query: String,
+}
+
+impl DiffSearch {
+    pub fn new(query: &str) -> Self {
+        Self {
+            query: query.to_string(),
+        }
+    }
+
+    /// Whether the query is matched ignoring case, like `less -i`: unless it
+    /// has an uppercase letter.
+    fn ignores_case(&self) -> bool {
+        !self.query.chars().any(char::is_uppercase)
+    }
+
+    /// Byte ranges of the matches in `line`, in order.
+    pub fn match_ranges(&self, line: &str) -> Vec<(usize, usize)> {
+        if self.query.is_empty() {
+            return Vec::new();
+        }
+        // ASCII case folding keeps the byte offsets of the original line.
+        let (haystack, needle) = if self.ignores_case() {
+            (line.to_ascii_lowercase(), self.query.to_ascii_lowercase())
+        } else {
+            (line.to_string(), self.query.clone())
+        };
+        haystack
+            .match_indices(&needle)
+            .map(|(start, found)| (start, start + found.len()))
+            .collect()
+    }
+
+    fn is_match(&self, line: &str) -> bool {
+        !self.match_ranges(line).is_empty()
+    }
+
+    /// The first matching line from `from` on, going backwards if `forward`
+    /// is false and wrapping around the ends.
+    fn find(&self, lines: &[String], from: usize, forward: bool) -> Option<usize> {
+        let len = lines.len();
+        (0..len)
+            .map(|step| {
+                if forward {
+                    (from + step) % len
+                } else {
+                    (from + len - step) % len
+                }
+            })
+            .find(|&index| self.is_match(&lines[index]))
+    }
+}
+
+pub fn open_prompt(state: &mut AppState) {
+    state.prompt = Some(Prompt::new(PromptAction::SearchDiff, "Search", ""));
+}
+
+fn focused_search(state: &AppState) -> Option<&DiffSearch> {
+    match state.focused_pane {
+        FocusedPane::Main => state.main_screen.search.as_ref(),
+        FocusedPane::Unstaged => state.unstaged_pane.search.as_ref(),
+    }
+}
+
+fn is_diff_cursor_active(state: &AppState) -> bool {
+    match state.focused_pane {
+        FocusedPane::Main => state.main_screen.is_diff_cursor_active,
+        FocusedPane::Unstaged => state.unstaged_pane.is_diff_cursor_active,
+    }
+}
+
+/// Searches the focused diff for `query` from the line under the cursor.
+/// An empty query ends the search.
+pub fn start(state: &mut AppState, query: &str) {
+    let search = (!query.is_empty()).then(|| DiffSearch::new(query));
+    match state.focused_pane {
+        FocusedPane::Main => state.main_screen.search = search,
+        FocusedPane::Unstaged => state.unstaged_pane.search = search,
+    }
+    let lines = main_screen::focused_diff_lines(state);
+    let from = if is_diff_cursor_active(state) {
+        state
+            .main_screen
+            .line_cursor
+            .min(lines.len().saturating_sub(1))
+    } else {
+        0
+    };
+    jump(state, &lines, from, true);
+}
+
+/// Moves to the next match with `n`, or the previous one with `N`.
+pub fn jump_to_next(state: &mut AppState, forward: bool) {
+    let lines = main_screen::focused_diff_lines(state);
+    let len = lines.len().max(1);
+    let cursor = state.main_screen.line_cursor.min(len - 1);
+    let from = if forward {
+        (cursor + 1) % len
+    } else {
+        (cursor + len - 1) % len
+    };
+    jump(state, &lines, from, forward);
+}
+
+fn jump(state: &mut AppState, lines: &[String], from: usize, forward: bool) {
+    let Some(search) = focused_search(state).cloned() else {
+        return;
+    };
+    let Some(index) = search.find(lines, from, forward) else {
+        state.set_message(
+            Severity::Warning,
+            format!("Pattern not found: {}", search.query),
+        );
+        return;
+    };
+
+    let matching_lines: Vec<usize> = (0..lines.len())
+        .filter(|&i| search.is_match(&lines[i]))
+        .collect();
+    let position = matching_lines.iter().position(|&i| i == index).unwrap_or(0);
+    state.set_message(
+        Severity::Info,
+        format!(
+            "/{}  {}/{}",
+            search.query,
+            position + 1,
+            matching_lines.len()
+        ),
+    );
+
+    state.main_screen.line_cursor = index;
+    let scroll = index.saturating_sub(CONTEXT_LINES);
+    match state.focused_pane {
+        FocusedPane::Main => {
+            state.main_screen.is_diff_cursor_active = true;
+            state.main_screen.diff_scroll = scroll;
+        }
+        FocusedPane::Unstaged => {
+            state.unstaged_pane.is_diff_cursor_active = true;
+            state.unstaged_pane.diff_scroll = scroll;
+        }
+    }
+}
+
+/// Ends the search of the focused pane. Returns whether there was one.
+pub fn clear(state: &mut AppState) -> bool {
+    let search = match state.focused_pane {
+        FocusedPane::Main => &mut state.main_screen.search,
+        FocusedPane::Unstaged => &mut state.unstaged_pane.search,
+    };
+    search.take().is_some()
+}
@@ -1,11 +1,85 @@
 use crate::{
-    git::{FileDiff, FileStatus},
-    util::word_diff::{WordDiffLine, compute_word_diffs},
+    git::{BinaryDiff, FileDiff, FileStatus},
+    ui::{color, diff_search::DiffSearch},
+    util::word_diff::{WordChange, WordDiffLine, compute_word_diffs},
+    whitespace::{self, Mark},
 };
-use pancurses::{A_REVERSE, COLOR_PAIR, Window, chtype};
+use pancurses::{A_BOLD, A_DIM, A_REVERSE, COLOR_PAIR, Window, chtype};
 use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 pub const LINE_CONTENT_OFFSET: usize = 10;
 
+/// `bytes` for the binary summary, e.g. `512 bytes` or `1.5 KiB`.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 3] = ["KiB", "MiB", "GiB"];
+    if bytes < 1024 {
+        return format!("{bytes} bytes");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// The rows shown for a binary file instead of its lines: the sizes of both
+/// sides, the mode if it changed and the blobs.
+pub fn binary_summary(file: &FileDiff, binary: &BinaryDiff) -> Vec<String> {
+    let mut lines = vec![format!("Binary file {}", file.file_name), String::new()];
+    let size = match (binary.old_size, binary.new_size) {
+        (Some(old), Some(new)) if old == new => format!("{}, same size", format_size(new)),
+        (Some(old), Some(new)) => {
+            let sign = if new > old { '+' } else { '-' };
+            format!(
+                "{} → {} ({sign}{})",
+                format_size(old),
+                format_size(new),
+                format_size(new.abs_diff(old))
+            )
+        }
+        (None, Some(new)) => format!("{}, new file", format_size(new)),
+        (Some(old), None) => format!("{}, deleted", format_size(old)),
+        (None, None) => "unknown".to_string(),
+    };
+    lines.push(format!("  Size  {size}"));
+    match (&binary.old_mode, &binary.new_mode) {
+        (Some(old), Some(new)) if old != new => lines.push(format!("  Mode  {old} → {new}")),
+        (_, Some(mode)) | (Some(mode), None) => lines.push(format!("  Mode  {mode}")),
+        (None, None) => {}
+    }
+    if !binary.old_blob.is_empty() {
+        lines.push(format!("  Blob  {} → {}", binary.old_blob, binary.new_blob));
+    }
+    lines
+}
+
+/// Draws `binary_summary` below the header, with the file name in bold.
+fn render_binary(
+    window: &Window,
+    file: &FileDiff,
+    binary: &BinaryDiff,
+    content_height: usize,
+    header_height: usize,
+) {
+    let max_x = window.get_max_x().max(0) as usize;
+    for (row, line) in binary_summary(file, binary)
+        .iter()
+        .take(content_height)
+        .enumerate()
+    {
+        let y = (header_height + row) as i32;
+        window.mv(y, 0);
+        window.clrtoeol();
+        let attr = if row == 0 { A_BOLD } else { 0 };
+        window.attron(COLOR_PAIR(1) | attr);
+        let text: String = line.chars().take(max_x.saturating_sub(1)).collect();
+        window.mvaddstr(y, 1, text);
+        window.attroff(COLOR_PAIR(1) | attr);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn render(
     window: &Window,
     file: &FileDiff,
@@ -16,9 +90,14 @@ pub fn render(
     cursor_position: usize,
     is_diff_cursor_active: bool,
 ) {
+    if let Some(binary) = &file.binary {
+        render_binary(window, file, binary, content_height, header_height);
+        return;
+    }
     let lines = &file.lines;
 
     let mut line_numbers: Vec<(usize, usize)> = vec![(0, 0); lines.len()];
+    let mut in_hunk = vec![false; lines.len()];
     for hunk in &file.hunks {
         for (hunk_line_index, (old, new)) in hunk.line_numbers.iter().enumerate() {
             let line_index = hunk.start_line + hunk_line_index;
@@ -26,6 +105,21 @@ pub fn render(
                 continue;
             }
             line_numbers[line_index] = (*old, *new);
+            in_hunk[line_index] = true;
+        }
+    }
+    // Context lines outside of the hunks, which only the full file view
+    // has, continue the numbers of the lines before them.
+    let (mut old_line, mut new_line) = (0, 0);
+    for (index, line) in lines.iter().enumerate() {
+        if in_hunk[index] {
+            let (old, new) = line_numbers[index];
+            old_line = old_line.max(old);
+            new_line = new_line.max(new);
+        } else if line.starts_with(' ') && !file.hunks.is_empty() {
+            old_line += 1;
+            new_line += 1;
+            line_numbers[index] = (old_line, new_line);
         }
     }
 
@@ -166,6 +260,147 @@ pub fn render(
     }
 }
 
+/// Redraws the matches of `search` in reverse video over diff lines that
+/// `render` or `render_multiple` already drew, one screen row per line.
+pub fn highlight_matches(
+    window: &Window,
+    lines: &[String],
+    search: &DiffSearch,
+    content_height: usize,
+    scroll: usize,
+    horizontal_scroll: usize,
+    header_height: usize,
+) {
+    let max_x = window.get_max_x();
+    for (row, line) in lines.iter().skip(scroll).take(content_height).enumerate() {
+        // Hunk headers start right after the line number columns.
+        let line_x = if line.starts_with("@@ ") {
+            LINE_CONTENT_OFFSET - 1
+        } else {
+            LINE_CONTENT_OFFSET
+        };
+        for (start, end) in search.match_ranges(line) {
+            let from = UnicodeWidthStr::width(&line[..start]);
+            let to = UnicodeWidthStr::width(&line[..end]);
+            if to <= horizontal_scroll {
+                continue;
+            }
+            let x = (line_x + from.saturating_sub(horizontal_scroll)) as i32;
+            let width = (to - from.max(horizontal_scroll)) as i32;
+            let width = width.min(max_x - x);
+            if width > 0 {
+                window.mvchgat(
+                    (header_height + row) as i32,
+                    x,
+                    width,
+                    A_REVERSE | A_BOLD,
+                    1,
+                );
+            }
+        }
+    }
+}
+
+/// Dims the added or removed lines for which `is_dimmed` holds over a diff
+/// `render` already drew, one screen row per line.
+#[allow(clippy::too_many_arguments)]
+pub fn dim_lines(
+    window: &Window,
+    lines: &[String],
+    is_dimmed: impl Fn(&str) -> bool,
+    content_height: usize,
+    scroll: usize,
+    header_height: usize,
+    cursor_position: usize,
+    is_diff_cursor_active: bool,
+) {
+    for (row, line) in lines.iter().skip(scroll).take(content_height).enumerate() {
+        if !is_dimmed(line) {
+            continue;
+        }
+        // The pairs `render_line` draws additions and removals with.
+        let is_cursor_line = scroll + row == cursor_position;
+        let pair = match (line.starts_with('-'), is_cursor_line, is_diff_cursor_active) {
+            (false, true, true) => 7,
+            (false, true, false) => 13,
+            (false, false, _) => 3,
+            (true, true, true) => 6,
+            (true, true, false) => 12,
+            (true, false, _) => 2,
+        };
+        window.mvchgat((header_height + row) as i32, 0, -1, A_DIM, pair);
+    }
+}
+
+/// Columns between tab stops, as curses expands tabs.
+const TAB_SIZE: usize = 8;
+
+/// Highlights the whitespace problems of the lines `render` already drew,
+/// one screen row per line: trailing whitespace as a red block, mixed
+/// indentation as a yellow one and `\ No newline at end of file` in bold.
+#[allow(clippy::too_many_arguments)]
+pub fn mark_whitespace(
+    window: &Window,
+    lines: &[String],
+    content_height: usize,
+    scroll: usize,
+    horizontal_scroll: usize,
+    header_height: usize,
+    cursor_position: usize,
+    is_diff_cursor_active: bool,
+) {
+    let max_x = window.get_max_x().max(0) as usize;
+    for (row, line) in lines.iter().skip(scroll).take(content_height).enumerate() {
+        let marks = whitespace::marks(line);
+        if marks.is_empty() {
+            continue;
+        }
+        let is_cursor = scroll + row == cursor_position && is_diff_cursor_active;
+        let shown = get_scrolled_line(line, horizontal_scroll);
+        let shown_start = line.len() - shown.len();
+        // Screen columns of the shown characters, tabs expanded.
+        let mut columns = Vec::new();
+        let mut x = LINE_CONTENT_OFFSET;
+        for (offset, ch) in shown.char_indices() {
+            let end = if ch == '\t' {
+                (x / TAB_SIZE + 1) * TAB_SIZE
+            } else {
+                x + ch.width().unwrap_or(0)
+            };
+            columns.push((shown_start + offset, x, end));
+            x = end;
+        }
+        for (range, mark) in marks {
+            let cells: Vec<_> = columns
+                .iter()
+                .filter(|(offset, _, _)| range.contains(offset))
+                .collect();
+            let (Some(&&(_, from, _)), Some(&&(_, _, to))) = (cells.first(), cells.last()) else {
+                continue;
+            };
+            let to = to.min(max_x);
+            if from >= to {
+                continue;
+            }
+            let (attr, pair) = match (mark, is_cursor) {
+                (Mark::TrailingWhitespace, true) => (A_REVERSE, 6),
+                (Mark::TrailingWhitespace, false) => (A_REVERSE, 2),
+                (Mark::MixedIndent, true) => (A_REVERSE, 17),
+                (Mark::MixedIndent, false) => (A_REVERSE, 18),
+                (Mark::MissingNewline, true) => (A_BOLD, 17),
+                (Mark::MissingNewline, false) => (A_BOLD, 18),
+            };
+            window.mvchgat(
+                (header_height + row) as i32,
+                from as i32,
+                (to - from) as i32,
+                attr,
+                pair,
+            );
+        }
+    }
+}
+
 pub fn get_scrolled_line(full_line: &str, scroll_offset: usize) -> &str {
     if scroll_offset == 0 {
         return full_line;
@@ -184,6 +419,63 @@ pub fn get_scrolled_line(full_line: &str, scroll_offset: usize) -> &str {
     ""
 }
 
+/// Draws `text` at the current position, skipping the first
+/// `remaining_scroll` columns of the line and clipping at the right edge.
+fn render_part(
+    win: &Window,
+    text: &str,
+    pair: chtype,
+    attr: pancurses::chtype,
+    remaining_scroll: &mut usize,
+) {
+    let max_x = win.get_max_x();
+    let current_x = win.get_cur_x();
+    let remaining_width = if max_x > current_x {
+        (max_x - current_x) as usize
+    } else {
+        0
+    };
+    if remaining_width == 0 {
+        return;
+    }
+
+    let truncate_and_add = |txt: &str| {
+        let mut current_width = 0;
+        let mut end_byte_index = txt.len();
+        for (byte_index, ch) in txt.char_indices() {
+            let char_width = UnicodeWidthChar::width(ch).unwrap_or(0);
+            if current_width + char_width > remaining_width {
+                end_byte_index = byte_index;
+                break;
+            }
+            current_width += char_width;
+        }
+        win.addstr(&txt[..end_byte_index]);
+    };
+
+    if *remaining_scroll == 0 {
+        win.attron(COLOR_PAIR(pair));
+        win.attron(attr);
+        truncate_and_add(text);
+        win.attroff(attr);
+        win.attroff(COLOR_PAIR(pair));
+    } else {
+        let width = UnicodeWidthStr::width(text);
+        if *remaining_scroll < width {
+            let scrolled_text = get_scrolled_line(text, *remaining_scroll);
+            win.attron(COLOR_PAIR(pair));
+            win.attron(attr);
+            truncate_and_add(scrolled_text);
+            win.attroff(attr);
+            win.attroff(COLOR_PAIR(pair));
+            *remaining_scroll = 0;
+        } else {
+            *remaining_scroll -= width;
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn render_line(
     window: &Window,
     line: &str,
@@ -241,6 +533,10 @@ fn render_line(
         (addition_pair, "+")
     } else if line.starts_with('-') {
         (deletion_pair, "-")
+    } else if line.starts_with("old mode ") {
+        (deletion_pair, "")
+    } else if line.starts_with("new mode ") {
+        (addition_pair, "")
     } else if line.starts_with("@@ ") {
         (hunk_header_pair, "")
     } else if line.starts_with("diff --git ") {
@@ -261,64 +557,17 @@ fn render_line(
         window.attron(COLOR_PAIR(num_pair));
         window.mvaddstr(line_render_index, 0, &line_num_str);
         window.attroff(COLOR_PAIR(num_pair));
+        if color::gutter_marks() && num_pair == base_pair {
+            window.attron(COLOR_PAIR(base_pair) | A_BOLD);
+            window.mvaddstr(line_render_index, line_content_offset - 1, line_prefix);
+            window.attroff(COLOR_PAIR(base_pair) | A_BOLD);
+        }
     }
 
     window.mv(line_render_index, line_content_offset);
 
     let mut remaining_scroll = horizontal_scroll;
 
-    let render_part = |win: &Window,
-                       text: &str,
-                       pair: chtype,
-                       attr: pancurses::chtype,
-                       remaining_scroll: &mut usize| {
-        let max_x = win.get_max_x();
-        let current_x = win.get_cur_x();
-        let remaining_width = if max_x > current_x {
-            (max_x - current_x) as usize
-        } else {
-            0
-        };
-        if remaining_width == 0 {
-            return;
-        }
-
-        let truncate_and_add = |txt: &str| {
-            let mut current_width = 0;
-            let mut end_byte_index = txt.len();
-            for (byte_index, ch) in txt.char_indices() {
-                let char_width = UnicodeWidthChar::width(ch).unwrap_or(0);
-                if current_width + char_width > remaining_width {
-                    end_byte_index = byte_index;
-                    break;
-                }
-                current_width += char_width;
-            }
-            win.addstr(&txt[..end_byte_index]);
-        };
-
-        if *remaining_scroll == 0 {
-            win.attron(COLOR_PAIR(pair));
-            win.attron(attr);
-            truncate_and_add(text);
-            win.attroff(attr);
-            win.attroff(COLOR_PAIR(pair));
-        } else {
-            let width = UnicodeWidthStr::width(text);
-            if *remaining_scroll < width {
-                let scrolled_text = get_scrolled_line(text, *remaining_scroll);
-                win.attron(COLOR_PAIR(pair));
-                win.attron(attr);
-                truncate_and_add(scrolled_text);
-                win.attroff(attr);
-                win.attroff(COLOR_PAIR(pair));
-                *remaining_scroll = 0;
-            } else {
-                *remaining_scroll -= width;
-            }
-        }
-    };
-
     if line.starts_with("@@ ") {
         window.attroff(COLOR_PAIR(base_pair));
         window.attron(COLOR_PAIR(grey_pair));
@@ -357,6 +606,7 @@ fn render_line(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn render_plain(
     window: &Window,
     lines: Vec<String>,
@@ -373,6 +623,7 @@ pub fn render_plain(
         hunks: Vec::new(),
         lines,
         status: FileStatus::Modified,
+        binary: None,
     };
 
     render(
@@ -387,9 +638,10 @@ pub fn render_plain(
     );
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn render_multiple(
     window: &Window,
-    file_diffs: &Vec<FileDiff>,
+    file_diffs: &[FileDiff],
     content_height: usize,
     scroll: usize,
     horizontal_scroll: usize,
@@ -438,3 +690,89 @@ pub fn render_multiple(
         }
     }
 }
+
+/// Renders a commit diff loaded with `git::get_commit_word_diff`: removed
+/// words are shown in red and added words in green, inside the line they
+/// belong to. Header lines are drawn like in `render_multiple`.
+#[allow(clippy::too_many_arguments)]
+pub fn render_word_diff(
+    window: &Window,
+    file_diffs: &[FileDiff],
+    spans: &[Option<Vec<(String, WordChange)>>],
+    content_height: usize,
+    scroll: usize,
+    horizontal_scroll: usize,
+    header_height: usize,
+    cursor_position: usize,
+    is_diff_cursor_active: bool,
+) {
+    let all_lines = file_diffs.iter().flat_map(|f| f.lines.iter());
+
+    for (i, line) in all_lines.skip(scroll).take(content_height).enumerate() {
+        let line_index = i + scroll;
+        let line_render_index = i as i32 + header_height as i32;
+        match spans.get(line_index) {
+            Some(Some(spans)) => render_word_line(
+                window,
+                spans,
+                line_render_index,
+                line_index == cursor_position,
+                horizontal_scroll,
+                is_diff_cursor_active,
+            ),
+            _ => render_line(
+                window,
+                line,
+                None,
+                line_index,
+                line_render_index,
+                cursor_position,
+                0,
+                0,
+                horizontal_scroll,
+                is_diff_cursor_active,
+            ),
+        }
+    }
+}
+
+fn render_word_line(
+    window: &Window,
+    spans: &[(String, WordChange)],
+    line_render_index: i32,
+    is_cursor_line: bool,
+    horizontal_scroll: usize,
+    is_diff_cursor_active: bool,
+) {
+    let (default_pair, deletion_pair, addition_pair) = if is_cursor_line {
+        if is_diff_cursor_active {
+            (5, 6, 7)
+        } else {
+            (11, 12, 13)
+        }
+    } else {
+        (1, 2, 3)
+    };
+
+    window.mv(line_render_index, 0);
+    window.clrtoeol();
+    if is_cursor_line {
+        window.attron(COLOR_PAIR(default_pair));
+        for i in 0..window.get_max_x() {
+            window.mvaddch(line_render_index, i, ' ');
+        }
+        window.attroff(COLOR_PAIR(default_pair));
+    }
+
+    window.mv(line_render_index, LINE_CONTENT_OFFSET as i32);
+    let mut remaining_scroll = horizontal_scroll;
+    render_part(window, " ", default_pair, 0, &mut remaining_scroll);
+    for (text, change) in spans {
+        let (pair, attr) = match change {
+            WordChange::Unchanged => (default_pair, 0),
+            WordChange::Added => (addition_pair, A_REVERSE),
+            WordChange::Removed => (deletion_pair, A_REVERSE),
+        };
+        render_part(window, text, pair, attr, &mut remaining_scroll);
+    }
+}
@@ -0,0 +1,378 @@
+use crate::app_state::{AppState, Screen};
+use crate::command::{
+    CreateFileCommand, RestoreDiscardedPatchCommand, RestoreDroppedCommitCommand,
+};
+use crate::git::{self, CommitInfo, FileDiff, FileStatus};
+use crate::git_patch;
+use crate::message_log::Severity;
+use crate::ui::chrome;
+use crate::ui::diff_view;
+use crate::ui::layout;
+use pancurses::{COLOR_PAIR, Input, Window};
+use std::path::Path;
+
+/// Lines above the list or the diff.
+const BANNER_HEIGHT: usize = 1;
+
+/// What was thrown away by a discard, so it can be put back.
+#[derive(Clone, Debug)]
+pub enum DiscardedContent {
+    /// A patch that brings the change back when applied to the working tree.
+    Patch(String),
+    /// The content of a deleted untracked file.
+    File(Vec<u8>),
+    /// The full hash of a commit dropped from the branch.
+    Commit(String),
+}
+
+/// One entry of the discard bin: a hunk, or a whole file when it can't be
+/// split into hunks.
+#[derive(Clone, Debug)]
+pub struct DiscardedChange {
+    pub file_name: String,
+    /// Where it was discarded from and which part, e.g. the hunk header.
+    pub summary: String,
+    pub content: DiscardedContent,
+}
+
+impl DiscardedChange {
+    fn patch(file_name: &str, summary: String, patch: String) -> Self {
+        Self {
+            file_name: file_name.to_string(),
+            summary,
+            content: DiscardedContent::Patch(patch),
+        }
+    }
+
+    /// The hunks of `file`, when it is a plain modification. Other files are
+    /// kept whole as `whole_patch`.
+    pub fn from_file(file: &FileDiff, origin: &str, whole_patch: String) -> Vec<Self> {
+        if file.status != FileStatus::Modified || file.hunks.is_empty() {
+            return Self::from_whole_file(file, origin, whole_patch);
+        }
+        file.hunks
+            .iter()
+            .map(|hunk| Self::from_hunk(file, hunk, origin))
+            .collect()
+    }
+
+    /// `file` kept whole as `whole_patch`, e.g. when its hunks are shown
+    /// through a textconv driver.
+    pub fn from_whole_file(file: &FileDiff, origin: &str, whole_patch: String) -> Vec<Self> {
+        vec![Self::patch(
+            &file.file_name,
+            format!("{origin}, whole file"),
+            whole_patch,
+        )]
+    }
+
+    pub fn from_hunk(file: &FileDiff, hunk: &git::Hunk, origin: &str) -> Self {
+        let header = hunk.lines.first().map(String::as_str).unwrap_or_default();
+        Self::patch(
+            &file.file_name,
+            format!("{origin} {header}"),
+            git_patch::create_unstage_hunk_patch(file, hunk),
+        )
+    }
+
+    pub fn from_untracked_file(file_name: &str, content: Vec<u8>) -> Self {
+        Self {
+            file_name: file_name.to_string(),
+            summary: "untracked file".to_string(),
+            content: DiscardedContent::File(content),
+        }
+    }
+
+    /// A commit dropped with `d`, listed under its abbreviated hash.
+    pub fn from_dropped_commit(commit: &CommitInfo) -> Self {
+        Self {
+            file_name: commit.hash.clone(),
+            summary: format!("dropped commit {}", commit.message),
+            content: DiscardedContent::Commit(commit.full_hash.clone()),
+        }
+    }
+
+    fn diff(&self, repo_path: &Path) -> Vec<FileDiff> {
+        match &self.content {
+            DiscardedContent::Commit(hash) => {
+                git::get_commit_diff(repo_path, hash).unwrap_or_default()
+            }
+            DiscardedContent::Patch(patch) => git::parse_diff(patch),
+            DiscardedContent::File(content) => vec![FileDiff {
+                file_name: self.file_name.clone(),
+                old_file_name: self.file_name.clone(),
+                hunks: Vec::new(),
+                lines: String::from_utf8_lossy(content)
+                    .lines()
+                    .map(|line| format!("+{line}"))
+                    .collect(),
+                status: FileStatus::Added,
+                binary: None,
+            }],
+        }
+    }
+}
+
+/// Panel listing what `!` discarded in this session, newest first.
+pub struct DiscardBin {
+    pub cursor: usize,
+    pub list_scroll: usize,
+    /// Diff of the entry under the cursor while it is opened with Enter.
+    pub diff: Option<Vec<FileDiff>>,
+    pub diff_scroll: usize,
+    pub horizontal_scroll: usize,
+}
+
+impl DiscardBin {
+    fn content_height(max_y: i32) -> usize {
+        (max_y.max(0) as usize).saturating_sub(BANNER_HEIGHT + 1)
+    }
+
+    fn move_cursor(&mut self, delta: isize, count: usize, max_y: i32) {
+        self.cursor = self
+            .cursor
+            .saturating_add_signed(delta)
+            .min(count.saturating_sub(1));
+
+        let height = Self::content_height(max_y).max(1);
+        if self.cursor < self.list_scroll {
+            self.list_scroll = self.cursor;
+        } else if self.cursor >= self.list_scroll + height {
+            self.list_scroll = self.cursor + 1 - height;
+        }
+    }
+
+    fn scroll_diff_by(&mut self, delta: isize, max_y: i32) {
+        let total_lines: usize = self.diff.iter().flatten().map(|f| f.lines.len()).sum();
+        let max_scroll = total_lines.saturating_sub(Self::content_height(max_y));
+        self.diff_scroll = self
+            .diff_scroll
+            .saturating_add_signed(delta)
+            .min(max_scroll);
+    }
+}
+
+pub fn open(state: &mut AppState) {
+    state.screen = Some(Screen::DiscardBin(DiscardBin {
+        cursor: 0,
+        list_scroll: 0,
+        diff: None,
+        diff_scroll: 0,
+        horizontal_scroll: 0,
+    }));
+}
+
+/// Keeps what a discard that just succeeded threw away.
+pub fn record(state: &mut AppState, changes: Vec<DiscardedChange>) {
+    state.discarded_changes.extend(changes);
+}
+
+/// The entry at `index` in the list, which shows the newest first.
+fn entry(state: &AppState, index: usize) -> Option<&DiscardedChange> {
+    state.discarded_changes.iter().rev().nth(index)
+}
+
+/// Puts the selected entry back into the working tree, or onto the branch
+/// for a dropped commit. The entry stays in the bin; restoring a change
+/// twice fails as it is already there.
+fn restore_selected(state: &mut AppState) {
+    let Some(Screen::DiscardBin(bin)) = &state.screen else {
+        return;
+    };
+    let Some(change) = entry(state, bin.cursor).cloned() else {
+        return;
+    };
+    let (command, restored, failed): (Box<dyn crate::command::Command>, _, _) = match change.content
+    {
+        DiscardedContent::Patch(patch) => (
+            Box::new(RestoreDiscardedPatchCommand::new(
+                state.repo_path.clone(),
+                patch,
+            )),
+            "to the working tree",
+            "it no longer applies to the working tree",
+        ),
+        DiscardedContent::File(content) => (
+            Box::new(CreateFileCommand::new_file(
+                state.repo_path.clone(),
+                change.file_name.clone(),
+                content,
+            )),
+            "to the working tree",
+            "it no longer applies to the working tree",
+        ),
+        DiscardedContent::Commit(hash) => (
+            Box::new(RestoreDroppedCommitCommand::new(
+                state.repo_path.clone(),
+                hash,
+            )),
+            "onto the branch",
+            "it does not apply onto the branch",
+        ),
+    };
+    if state.execute_and_refresh(command) {
+        state.set_message(
+            Severity::Success,
+            format!("Restored {} {restored}", change.file_name),
+        );
+    } else {
+        state.set_message(
+            Severity::Error,
+            format!("Cannot restore {}: {failed}", change.file_name),
+        );
+    }
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let count = state.discarded_changes.len();
+    let Some(Screen::DiscardBin(bin)) = state.screen.as_mut() else {
+        return;
+    };
+    let page = DiscardBin::content_height(max_y).max(1) as isize;
+
+    if matches!(input, Input::Character('\t')) {
+        state.screen = None;
+        return;
+    }
+
+    if bin.diff.is_some() {
+        match input {
+            Input::Character('q') | Input::Character('\n') | Input::Character('\u{1b}') => {
+                bin.diff = None;
+            }
+            Input::Character('r') => restore_selected(state),
+            Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+                bin.scroll_diff_by(1, max_y)
+            }
+            Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+                bin.scroll_diff_by(-1, max_y)
+            }
+            Input::Character(' ') | Input::Character('\u{16}') | Input::KeyNPage => {
+                bin.scroll_diff_by(page, max_y)
+            }
+            Input::Character('b') | Input::KeyPPage => bin.scroll_diff_by(-page, max_y),
+            Input::KeyLeft => {
+                bin.horizontal_scroll = bin.horizontal_scroll.saturating_sub(1);
+            }
+            Input::KeyRight => bin.horizontal_scroll += 1,
+            _ => {}
+        }
+        return;
+    }
+
+    match input {
+        Input::Character('q') | Input::Character('T') | Input::Character('\u{1b}') => {
+            state.screen = None;
+        }
+        Input::Character('\n') => {
+            let cursor = bin.cursor;
+            let diff = entry(state, cursor).map(|change| change.diff(&state.repo_path));
+            if let Some(Screen::DiscardBin(bin)) = state.screen.as_mut() {
+                bin.diff = diff;
+                bin.diff_scroll = 0;
+                bin.horizontal_scroll = 0;
+            }
+        }
+        Input::Character('r') => restore_selected(state),
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            bin.move_cursor(1, count, max_y)
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            bin.move_cursor(-1, count, max_y)
+        }
+        Input::Character(' ') | Input::Character('\u{16}') | Input::KeyNPage => {
+            bin.move_cursor(page, count, max_y)
+        }
+        Input::Character('b') | Input::KeyPPage => bin.move_cursor(-page, count, max_y),
+        _ => {}
+    }
+}
+
+pub fn render(window: &Window, state: &AppState, bin: &DiscardBin) {
+    let (max_y, max_x) = window.get_max_yx();
+
+    let repo = chrome::repo_name(&state.repo_path);
+    match (&bin.diff, entry(state, bin.cursor)) {
+        (Some(_), Some(change)) => {
+            let detail = format!("{} {}", change.file_name, change.summary);
+            chrome::render_title(
+                window,
+                &repo,
+                &["Discard bin", &detail],
+                "r restore  q back",
+            );
+        }
+        _ => {
+            let hints = format!(
+                "{} discarded  Enter diff  r restore  q close",
+                state.discarded_changes.len()
+            );
+            chrome::render_title(window, &repo, &["Discard bin"], &hints);
+        }
+    }
+
+    if let Some(files) = &bin.diff {
+        diff_view::render_multiple(
+            window,
+            files,
+            DiscardBin::content_height(max_y),
+            bin.diff_scroll,
+            bin.horizontal_scroll,
+            BANNER_HEIGHT,
+            usize::MAX,
+            false,
+        );
+    } else {
+        render_list(window, state, bin, max_y, max_x);
+    }
+
+    if let Some(error) = state.message_text() {
+        window.attron(COLOR_PAIR(2));
+        window.mvaddstr(
+            max_y - 1,
+            0,
+            layout::truncate_to_width(error, max_x.max(0) as usize),
+        );
+        window.attroff(COLOR_PAIR(2));
+    }
+}
+
+fn render_list(window: &Window, state: &AppState, bin: &DiscardBin, max_y: i32, max_x: i32) {
+    if state.discarded_changes.is_empty() {
+        window.mvaddstr(
+            BANNER_HEIGHT as i32,
+            0,
+            " Nothing was discarded in this session",
+        );
+        return;
+    }
+
+    let width = max_x.max(0) as usize;
+    let visible = state
+        .discarded_changes
+        .iter()
+        .rev()
+        .enumerate()
+        .skip(bin.list_scroll)
+        .take(DiscardBin::content_height(max_y));
+    for (row, (index, change)) in visible.enumerate() {
+        let y = (BANNER_HEIGHT + row) as i32;
+        let (pair, name_pair) = if index == bin.cursor { (5, 8) } else { (1, 4) };
+        window.attron(COLOR_PAIR(pair));
+        for x in 0..max_x {
+            window.mvaddch(y, x, ' ');
+        }
+
+        let name = format!(" {} ", change.file_name);
+        let name_width = unicode_width::UnicodeWidthStr::width(name.as_str());
+        window.attron(COLOR_PAIR(name_pair));
+        window.mvaddstr(y, 0, layout::truncate_to_width(&name, width));
+        window.attron(COLOR_PAIR(pair));
+        window.mvaddstr(
+            y,
+            name_width as i32,
+            layout::truncate_to_width(&change.summary, width.saturating_sub(name_width)),
+        );
+        window.attroff(COLOR_PAIR(pair));
+    }
+}
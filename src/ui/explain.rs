@@ -0,0 +1,162 @@
+use crate::app_state::{AppState, Modal};
+use crate::ui::layout;
+use pancurses::{A_BOLD, COLOR_PAIR, Input, Window};
+use unicode_width::UnicodeWidthStr;
+
+const TITLE: &str = " EXPLAIN  r run again  Esc/q close ";
+
+/// Output is wrapped at this many columns, so long paragraphs stay
+/// readable without a very wide popup.
+const WRAP_WIDTH: usize = 76;
+
+/// Popup showing what `explain_command` printed about a hunk.
+pub struct Explanation {
+    pub file: String,
+    /// The hunk as a patch, as it was piped to the command.
+    pub patch: String,
+    pub lines: Vec<String>,
+    pub running: bool,
+    pub scroll: usize,
+}
+
+/// Pipes `patch`, a hunk of `file`, to `explain_command` in the background
+/// and opens the popup, which shows the output once it is done.
+pub fn open(state: &mut AppState, file: &str, patch: String) {
+    let Some(command) = state.config.explain_command.clone() else {
+        return;
+    };
+    state.background_worker.explain(
+        state.repo_path.clone(),
+        command.clone(),
+        file.to_string(),
+        patch.clone(),
+    );
+    state.modal = Some(Modal::Explanation(Explanation {
+        file: file.to_string(),
+        patch,
+        lines: vec![format!("Running {command} on a hunk of {file}…")],
+        running: true,
+        scroll: 0,
+    }));
+}
+
+/// Shows the `result` of explaining `patch`, if the popup is still open
+/// for it.
+pub fn finish(state: &mut AppState, patch: &str, result: Result<(bool, String), String>) {
+    let command = state.config.explain_command.clone().unwrap_or_default();
+    let Some(Modal::Explanation(explanation)) = state.modal.as_mut() else {
+        return;
+    };
+    if explanation.patch != patch {
+        return;
+    }
+    let mut lines = match &result {
+        Ok((true, output)) if output.trim().is_empty() => {
+            vec![format!("{command} printed nothing.")]
+        }
+        Ok((true, _)) => Vec::new(),
+        Ok((false, _)) => vec![format!("{command} failed:"), String::new()],
+        Err(e) => vec![format!("Could not run {command}: {e}")],
+    };
+    if let Ok((_, output)) = &result {
+        lines.extend(
+            output
+                .trim_end()
+                .lines()
+                .flat_map(|line| layout::wrap_line(line, WRAP_WIDTH)),
+        );
+    }
+    explanation.lines = lines;
+    explanation.running = false;
+    explanation.scroll = 0;
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let Some(Modal::Explanation(explanation)) = state.modal.as_mut() else {
+        return;
+    };
+    let line_count = explanation.lines.len();
+    let max_scroll = line_count.saturating_sub(content_height(max_y, line_count));
+    let page = content_height(max_y, line_count).max(1);
+
+    match input {
+        Input::Character('q') | Input::Character('\u{1b}') => state.modal = None,
+        Input::Character('r') if !explanation.running => {
+            if let Some(Modal::Explanation(explanation)) = state.modal.take() {
+                open(state, &explanation.file, explanation.patch);
+            }
+        }
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            explanation.scroll = (explanation.scroll + 1).min(max_scroll);
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            explanation.scroll = explanation.scroll.saturating_sub(1);
+        }
+        Input::Character(' ') | Input::Character('\u{16}') | Input::KeyNPage => {
+            explanation.scroll = (explanation.scroll + page).min(max_scroll);
+        }
+        Input::Character('b') | Input::KeyPPage => {
+            explanation.scroll = explanation.scroll.saturating_sub(page);
+        }
+        _ => {}
+    }
+}
+
+/// Rows available for the output inside the frame.
+fn content_height(max_y: i32, line_count: usize) -> usize {
+    line_count.min((max_y.max(0) as usize).saturating_sub(4))
+}
+
+/// Draws the output in a frame over the main screen.
+pub fn render(window: &Window, explanation: &Explanation) {
+    let (max_y, max_x) = window.get_max_yx();
+    let longest = explanation
+        .lines
+        .iter()
+        .map(|line| line.width())
+        .chain(std::iter::once(TITLE.len()))
+        .max()
+        .unwrap_or(0);
+    let width = (longest + 4).min(max_x.max(0) as usize);
+    let height = content_height(max_y, explanation.lines.len()) + 2;
+    let left = layout::centered_x(width, max_x);
+    let top = ((max_y.max(0) as usize).saturating_sub(height) / 2) as i32;
+    let inner_width = width.saturating_sub(4);
+
+    window.attron(COLOR_PAIR(1));
+    for row in 0..height {
+        let y = top + row as i32;
+        let (edge, fill) = if row == 0 || row + 1 == height {
+            ('+', '-')
+        } else {
+            ('|', ' ')
+        };
+        window.mvaddch(y, left, edge);
+        for x in 1..width.saturating_sub(1) {
+            window.mvaddch(y, left + x as i32, fill);
+        }
+        window.mvaddch(y, left + width as i32 - 1, edge);
+    }
+    for (row, line) in explanation
+        .lines
+        .iter()
+        .skip(explanation.scroll)
+        .take(height.saturating_sub(2))
+        .enumerate()
+    {
+        window.mvaddstr(
+            top + 1 + row as i32,
+            left + 2,
+            layout::truncate_to_width(line, inner_width),
+        );
+    }
+    window.attroff(COLOR_PAIR(1));
+
+    window.attron(COLOR_PAIR(8) | A_BOLD);
+    window.mvaddstr(
+        top,
+        left + 1,
+        layout::truncate_to_width(TITLE, width.saturating_sub(2)),
+    );
+    window.attroff(COLOR_PAIR(8) | A_BOLD);
+}
@@ -0,0 +1,158 @@
+use crate::app_state::{AppState, Screen};
+use crate::git::{self, FileDiff, FileStatus};
+use crate::ui::chrome;
+use crate::ui::diff_view;
+use pancurses::{COLOR_PAIR, Input, Window};
+
+const TITLE: &str = "Final review";
+
+const HINTS: &str = "staged changes only, exactly what the commit will contain";
+
+/// Read-only view of everything that lands in the next commit: the message,
+/// a diffstat and the full staged patch of every file.
+pub struct FinalReview {
+    pub files: Vec<FileDiff>,
+    /// Staged files that also have unstaged edits, which are *not* shown.
+    pub overlapping_files: Vec<String>,
+    pub scroll: usize,
+    pub horizontal_scroll: usize,
+}
+
+impl FinalReview {
+    pub fn new(state: &AppState) -> Self {
+        let staged = git::get_diff_with_context(state.repo_path.clone(), state.diff_context);
+        let overlapping_files = staged
+            .iter()
+            .filter(|file| {
+                state
+                    .unstaged_pane
+                    .unstaged_files
+                    .iter()
+                    .any(|unstaged| unstaged.file_name == file.file_name)
+            })
+            .map(|file| file.file_name.clone())
+            .collect();
+
+        let mut summary = vec!["Commit message:".to_string()];
+        if state.main_screen.commit_message.is_empty() {
+            summary.push("    (empty)".to_string());
+        } else {
+            summary.extend(
+                state
+                    .main_screen
+                    .commit_message
+                    .lines()
+                    .map(|line| format!("    {line}")),
+            );
+        }
+        summary.push(String::new());
+        let stat = git::get_staged_diff_stat(&state.repo_path).unwrap_or_default();
+        summary.extend(stat.lines().map(String::from));
+        summary.push(String::new());
+
+        let mut files = vec![FileDiff {
+            file_name: String::new(),
+            old_file_name: String::new(),
+            hunks: Vec::new(),
+            lines: summary,
+            status: FileStatus::Modified,
+            binary: None,
+        }];
+        files.extend(staged);
+
+        Self {
+            files,
+            overlapping_files,
+            scroll: 0,
+            horizontal_scroll: 0,
+        }
+    }
+
+    pub fn total_lines(&self) -> usize {
+        self.files.iter().map(|f| f.lines.len()).sum()
+    }
+
+    fn banner_height(&self) -> usize {
+        if self.overlapping_files.is_empty() {
+            1
+        } else {
+            2
+        }
+    }
+
+    fn content_height(&self, max_y: i32) -> usize {
+        (max_y.max(0) as usize).saturating_sub(self.banner_height())
+    }
+
+    fn scroll_by(&mut self, delta: isize, max_y: i32) {
+        let max_scroll = self
+            .total_lines()
+            .saturating_sub(self.content_height(max_y));
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max_scroll);
+    }
+}
+
+pub fn open(state: &mut AppState) {
+    state.screen = Some(Screen::FinalReview(FinalReview::new(state)));
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let Some(Screen::FinalReview(review)) = state.screen.as_mut() else {
+        return;
+    };
+    let page = review.content_height(max_y).max(1) as isize;
+
+    match input {
+        Input::Character('q') | Input::Character('v') | Input::Character('\u{1b}') => {
+            state.screen = None;
+        }
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            review.scroll_by(1, max_y)
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            review.scroll_by(-1, max_y)
+        }
+        Input::Character(' ') | Input::Character('\u{16}') | Input::KeyNPage => {
+            review.scroll_by(page, max_y)
+        }
+        Input::Character('b') | Input::KeyPPage => review.scroll_by(-page, max_y),
+        Input::Character('\u{4}') => review.scroll_by(page / 2, max_y),
+        Input::Character('\u{15}') => review.scroll_by(-page / 2, max_y),
+        Input::KeyLeft => {
+            review.horizontal_scroll = review.horizontal_scroll.saturating_sub(1);
+        }
+        Input::KeyRight => review.horizontal_scroll += 1,
+        _ => {}
+    }
+}
+
+pub fn render(window: &Window, repo: &str, review: &FinalReview) {
+    let (max_y, max_x) = window.get_max_yx();
+
+    chrome::render_title(window, repo, &[TITLE], HINTS);
+
+    if !review.overlapping_files.is_empty() {
+        let warning = format!(
+            " Unstaged edits not included in: {}",
+            review.overlapping_files.join(", ")
+        );
+        window.attron(COLOR_PAIR(6));
+        for x in 0..max_x {
+            window.mvaddch(1, x, ' ');
+        }
+        let warning: String = warning.chars().take(max_x.max(0) as usize).collect();
+        window.mvaddstr(1, 0, &warning);
+        window.attroff(COLOR_PAIR(6));
+    }
+
+    diff_view::render_multiple(
+        window,
+        &review.files,
+        review.content_height(max_y),
+        review.scroll,
+        review.horizontal_scroll,
+        review.banner_height(),
+        usize::MAX,
+        false,
+    );
+}
@@ -0,0 +1,189 @@
+use crate::app_state::{AppState, Screen};
+use crate::command::FixupHunkCommand;
+use crate::git::CommitInfo;
+use crate::message_log::Severity;
+use crate::ui::chrome;
+use crate::ui::layout;
+use crate::ui::rebase_recovery::{self, Retry, Snapshot};
+use pancurses::{COLOR_PAIR, Input, Window};
+
+/// Lines above the commit list.
+const BANNER_HEIGHT: usize = 1;
+
+/// Screen picking the local commit an unstaged hunk is squashed into,
+/// opened with `A`.
+pub struct FixupPicker {
+    pub file_name: String,
+    /// The hunk, as a patch staging it.
+    pub patch: String,
+    pub commits: Vec<CommitInfo>,
+    pub cursor: usize,
+    pub list_scroll: usize,
+}
+
+impl FixupPicker {
+    pub fn selected_commit(&self) -> Option<&CommitInfo> {
+        self.commits.get(self.cursor)
+    }
+
+    fn content_height(max_y: i32) -> usize {
+        (max_y.max(0) as usize).saturating_sub(BANNER_HEIGHT + 1)
+    }
+
+    fn move_cursor(&mut self, delta: isize, max_y: i32) {
+        let last = self.commits.len().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+
+        let height = Self::content_height(max_y).max(1);
+        if self.cursor < self.list_scroll {
+            self.list_scroll = self.cursor;
+        } else if self.cursor >= self.list_scroll + height {
+            self.list_scroll = self.cursor + 1 - height;
+        }
+    }
+}
+
+/// Lists the commits not pushed yet, newest first, or says there are none.
+pub fn open(state: &mut AppState, file_name: &str, patch: String) {
+    let commits: Vec<CommitInfo> = state
+        .previous_commits
+        .iter()
+        .filter(|commit| !commit.is_on_remote)
+        .cloned()
+        .collect();
+    if commits.is_empty() {
+        state.set_message(Severity::Warning, "No local commit to fix up the hunk into");
+        state.alert();
+        return;
+    }
+    state.screen = Some(Screen::FixupPicker(FixupPicker {
+        file_name: file_name.to_string(),
+        patch,
+        commits,
+        cursor: 0,
+        list_scroll: 0,
+    }));
+}
+
+/// Squashes the hunk into the selected commit, then closes the picker.
+fn fixup_selected(state: &mut AppState) {
+    let Some(Screen::FixupPicker(picker)) = &state.screen else {
+        return;
+    };
+    let Some(commit) = picker.selected_commit() else {
+        return;
+    };
+    let (patch, hash, subject) = (
+        picker.patch.clone(),
+        commit.hash.clone(),
+        commit.message.clone(),
+    );
+    let command = FixupHunkCommand::new(state.repo_path.clone(), patch, hash);
+    run_fixup(state, command, &subject);
+}
+
+/// Runs the fixup, closing the picker if it worked and explaining what
+/// happened otherwise.
+pub fn run_fixup(state: &mut AppState, command: FixupHunkCommand, subject: &str) {
+    let (hash, patch) = (command.target_hash.clone(), command.patch.clone());
+    let snapshot = Snapshot::take(&state.repo_path);
+    if !state.execute_and_refresh(Box::new(command)) {
+        state.set_message(
+            Severity::Error,
+            format!("Cannot fix up the hunk into {hash} {subject}"),
+        );
+        let retry = Retry::FixupHunk {
+            patch,
+            hash,
+            subject: subject.to_string(),
+        };
+        rebase_recovery::open(state, "Fixup", None, snapshot, &[], retry);
+        return;
+    }
+    state.screen = None;
+    state.set_message(
+        Severity::Success,
+        format!("Fixed up the hunk into {hash} {subject}"),
+    );
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let Some(Screen::FixupPicker(picker)) = state.screen.as_mut() else {
+        return;
+    };
+    let page = FixupPicker::content_height(max_y).max(1) as isize;
+
+    match input {
+        Input::Character('q')
+        | Input::Character('A')
+        | Input::Character('\t')
+        | Input::Character('\u{1b}') => {
+            state.screen = None;
+        }
+        Input::Character('\n') => fixup_selected(state),
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            picker.move_cursor(1, max_y)
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            picker.move_cursor(-1, max_y)
+        }
+        Input::Character(' ') | Input::Character('\u{16}') | Input::KeyNPage => {
+            picker.move_cursor(page, max_y)
+        }
+        Input::Character('b') | Input::KeyPPage => picker.move_cursor(-page, max_y),
+        _ => {}
+    }
+}
+
+pub fn render(window: &Window, repo: &str, picker: &FixupPicker, error_message: Option<&str>) {
+    let (max_y, max_x) = window.get_max_yx();
+
+    chrome::render_title(
+        window,
+        repo,
+        &["Fix up", &picker.file_name],
+        "the hunk into:  Enter fix up  q close",
+    );
+
+    let width = max_x.max(0) as usize;
+    let visible = picker
+        .commits
+        .iter()
+        .enumerate()
+        .skip(picker.list_scroll)
+        .take(FixupPicker::content_height(max_y));
+    for (row, (index, commit)) in visible.enumerate() {
+        let y = (BANNER_HEIGHT + row) as i32;
+        let (pair, hash_pair) = if index == picker.cursor {
+            (5, 7)
+        } else {
+            (1, 3)
+        };
+        window.attron(COLOR_PAIR(pair));
+        for x in 0..max_x {
+            window.mvaddch(y, x, ' ');
+        }
+
+        let hash = format!(" {} ", commit.hash);
+        window.attron(COLOR_PAIR(hash_pair));
+        window.mvaddstr(y, 0, layout::truncate_to_width(&hash, width));
+        window.attron(COLOR_PAIR(pair));
+        let line = format!("{} {}", commit.date, commit.message);
+        window.mvaddstr(
+            y,
+            hash.len() as i32,
+            layout::truncate_to_width(&line, width.saturating_sub(hash.len())),
+        );
+        window.attroff(COLOR_PAIR(pair));
+    }
+
+    if let Some(error) = error_message {
+        window.attron(COLOR_PAIR(2));
+        window.mvaddstr(
+            max_y - 1,
+            0,
+            layout::truncate_to_width(error, max_x.max(0) as usize),
+        );
+        window.attroff(COLOR_PAIR(2));
+    }
+}
@@ -0,0 +1,197 @@
+use crate::app_state::{AppState, Modal};
+use crate::command::FormatStagedCommand;
+use crate::formatter::{self, Fix};
+use crate::git;
+use crate::message_log::Severity;
+use crate::ui::layout;
+use pancurses::{A_BOLD, COLOR_PAIR, Input, Window};
+use unicode_width::UnicodeWidthStr;
+
+const TITLE: &str = " FORMAT STAGED  Enter stage the fixes  Esc/q back ";
+
+/// Modal showing what the formatters would change in the staged content,
+/// before it is staged.
+pub struct FormatPreview {
+    pub fixes: Vec<Fix>,
+    /// The files a formatter failed on, and why.
+    pub errors: Vec<String>,
+    pub scroll: usize,
+}
+
+impl FormatPreview {
+    fn lines(&self) -> Vec<&str> {
+        let mut lines: Vec<&str> = self
+            .errors
+            .iter()
+            .map(String::as_str)
+            .chain(self.fixes.iter().flat_map(|fix| fix.patch.lines()))
+            .collect();
+        if self.fixes.is_empty() {
+            lines.push("");
+            lines.push("No formatting fixes to stage.");
+        }
+        lines
+    }
+}
+
+/// Formats the staged content of the staged files, and shows the fixes
+/// unless there is nothing to show.
+pub fn open(state: &mut AppState) {
+    if state.config.formatters.is_empty() {
+        state.alert();
+        state.set_message(
+            Severity::Warning,
+            "Add formatters to the [formatters] section of the config",
+        );
+        return;
+    }
+    if !state
+        .files
+        .iter()
+        .any(|file| formatter::find(&state.config.formatters, &file.file_name).is_some())
+    {
+        state.alert();
+        state.set_message(Severity::Warning, "No formatter matches the staged files");
+        return;
+    }
+    let (fixes, errors) =
+        formatter::format_staged(&state.repo_path, &state.config.formatters, &state.files);
+    if fixes.is_empty() && errors.is_empty() {
+        state.set_message(Severity::Info, "The staged changes are formatted already");
+        return;
+    }
+    state.modal = Some(Modal::FormatPreview(FormatPreview {
+        fixes,
+        errors,
+        scroll: 0,
+    }));
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let Some(Modal::FormatPreview(preview)) = state.modal.as_mut() else {
+        return;
+    };
+    let line_count = preview.lines().len();
+    let max_scroll = line_count.saturating_sub(content_height(max_y, line_count));
+    let page = content_height(max_y, line_count).max(1);
+
+    match input {
+        Input::Character('q') | Input::Character('\u{1b}') => state.modal = None,
+        Input::Character('\n') | Input::KeyEnter if !preview.fixes.is_empty() => {
+            if let Some(Modal::FormatPreview(preview)) = state.modal.take() {
+                stage(state, preview.fixes);
+            }
+        }
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            preview.scroll = (preview.scroll + 1).min(max_scroll);
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            preview.scroll = preview.scroll.saturating_sub(1);
+        }
+        Input::Character(' ') | Input::Character('\u{16}') | Input::KeyNPage => {
+            preview.scroll = (preview.scroll + page).min(max_scroll);
+        }
+        Input::Character('b') | Input::KeyPPage => {
+            preview.scroll = preview.scroll.saturating_sub(page);
+        }
+        _ => {}
+    }
+}
+
+fn stage(state: &mut AppState, fixes: Vec<Fix>) {
+    // Working trees that changed the formatted lines keep their version.
+    let kept: Vec<String> = fixes
+        .iter()
+        .filter(|fix| !git::can_apply_patch(&state.repo_path, &fix.patch, false, false))
+        .map(|fix| fix.file.clone())
+        .collect();
+    let count = fixes.len();
+    let command = Box::new(FormatStagedCommand::new(
+        state.repo_path.clone(),
+        fixes.into_iter().map(|fix| fix.patch).collect(),
+    ));
+    if !state.execute_and_refresh(command) {
+        return;
+    }
+    let files = if count == 1 { "file" } else { "files" };
+    let message = if kept.is_empty() {
+        format!("Staged the formatting fixes of {count} {files}")
+    } else {
+        format!(
+            "Staged the formatting fixes of {count} {files}, not made in the working tree of {}",
+            kept.join(", ")
+        )
+    };
+    state.set_message(Severity::Success, message);
+}
+
+/// Rows available for the lines inside the frame.
+fn content_height(max_y: i32, line_count: usize) -> usize {
+    line_count.min((max_y.max(0) as usize).saturating_sub(4))
+}
+
+/// Draws the fixes in a frame over the main screen, colored like the diff.
+pub fn render(window: &Window, preview: &FormatPreview) {
+    let (max_y, max_x) = window.get_max_yx();
+    let lines = preview.lines();
+    let longest = lines
+        .iter()
+        .map(|line| line.width())
+        .chain(std::iter::once(TITLE.len()))
+        .max()
+        .unwrap_or(0);
+    let width = (longest + 4).min(max_x.max(0) as usize);
+    let height = content_height(max_y, lines.len()) + 2;
+    let left = layout::centered_x(width, max_x);
+    let top = ((max_y.max(0) as usize).saturating_sub(height) / 2) as i32;
+    let inner_width = width.saturating_sub(4);
+
+    window.attron(COLOR_PAIR(1));
+    for row in 0..height {
+        let y = top + row as i32;
+        let (edge, fill) = if row == 0 || row + 1 == height {
+            ('+', '-')
+        } else {
+            ('|', ' ')
+        };
+        window.mvaddch(y, left, edge);
+        for x in 1..width.saturating_sub(1) {
+            window.mvaddch(y, left + x as i32, fill);
+        }
+        window.mvaddch(y, left + width as i32 - 1, edge);
+    }
+    window.attroff(COLOR_PAIR(1));
+    for (row, line) in lines
+        .iter()
+        .skip(preview.scroll)
+        .take(height.saturating_sub(2))
+        .enumerate()
+    {
+        let pair = if line.starts_with("+++") || line.starts_with("---") {
+            1
+        } else if line.starts_with("@@") {
+            4
+        } else if line.starts_with('+') {
+            3
+        } else if line.starts_with('-') {
+            2
+        } else {
+            1
+        };
+        window.attron(COLOR_PAIR(pair));
+        window.mvaddstr(
+            top + 1 + row as i32,
+            left + 2,
+            layout::truncate_to_width(line, inner_width),
+        );
+        window.attroff(COLOR_PAIR(pair));
+    }
+
+    window.attron(COLOR_PAIR(8) | A_BOLD);
+    window.mvaddstr(
+        top,
+        left + 1,
+        layout::truncate_to_width(TITLE, width.saturating_sub(2)),
+    );
+    window.attroff(COLOR_PAIR(8) | A_BOLD);
+}
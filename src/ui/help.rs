@@ -0,0 +1,171 @@
+use crate::app_state::{AppState, Screen};
+use crate::keymap::{self, ACTIONS, KeyMap};
+use crate::ui::chrome;
+use crate::ui::layout;
+use crate::ui::tutorial;
+use pancurses::{A_BOLD, Input, Window};
+
+const TITLE: &str = "Keys";
+
+const HINTS: &str = "t tutorial  q close";
+
+/// Lines above the list.
+const BANNER_HEIGHT: usize = 1;
+
+/// Keys of the commit message input, where letters are typed instead.
+const COMMIT_MESSAGE_KEYS: &[(&str, &str)] = &[
+    ("Enter", "New line"),
+    ("Alt+Enter", "Commit, or amend the commit being edited"),
+    ("Ctrl-A, Ctrl-E", "Go to the start or end of the line"),
+    ("Ctrl-K", "Delete to the end of the line"),
+    ("Ctrl-P, Ctrl-N", "Recall an older or newer message"),
+    ("Alt+Left, Alt+Right", "Move by word"),
+    ("Alt+Backspace", "Delete the word before the cursor"),
+    ("Up, Down", "Move between lines, or leave the input"),
+];
+
+/// Keys on a commit of the log.
+const COMMIT_LOG_KEYS: &[(&str, &str)] = &[
+    ("Enter", "Reword or amend the local commit"),
+    ("Alt+Up, Alt+Down", "Reorder the local commits"),
+    ("w", "Toggle the word diff"),
+];
+
+/// Keys while reordering commits.
+const REORDER_KEYS: &[(&str, &str)] = &[
+    ("Up, Down", "Select a commit, or move the grabbed one"),
+    ("Alt+Up, Alt+Down", "Swap the commit with its neighbour"),
+    ("g", "Grab or release the commit"),
+    ("f", "Toggle fixup into the commit before"),
+    ("!", "Drop the commit"),
+    ("Alt+Enter", "Edit the commit message"),
+    ("<, >", "Undo or redo"),
+    ("Enter", "Confirm the new order"),
+    ("Esc, q", "Cancel"),
+];
+
+/// Screen listing the keys of the main screen, taken from the key map so
+/// rebound keys show as they are bound.
+pub struct HelpView {
+    pub lines: Vec<String>,
+    pub scroll: usize,
+}
+
+impl HelpView {
+    fn content_height(max_y: i32) -> usize {
+        (max_y.max(0) as usize).saturating_sub(BANNER_HEIGHT + 1)
+    }
+
+    fn scroll_by(&mut self, delta: isize, max_y: i32) {
+        let max_scroll = self.lines.len().saturating_sub(Self::content_height(max_y));
+        self.scroll = self.scroll.saturating_add_signed(delta).min(max_scroll);
+    }
+}
+
+/// Section titles are not indented, so `render` can tell them apart.
+fn section(lines: &mut Vec<String>, title: &str, keys: &[(String, &str)]) {
+    let width = keys
+        .iter()
+        .map(|(key, _)| key.chars().count())
+        .max()
+        .unwrap_or(0);
+    if !lines.is_empty() {
+        lines.push(String::new());
+    }
+    lines.push(title.to_string());
+    lines.extend(
+        keys.iter()
+            .map(|(key, description)| format!("  {key:<width$}  {description}")),
+    );
+}
+
+fn fixed(keys: &[(&str, &'static str)]) -> Vec<(String, &'static str)> {
+    keys.iter()
+        .map(|(key, description)| (key.to_string(), *description))
+        .collect()
+}
+
+/// The lines of the help screen for the keys of `keymap`.
+pub fn help_lines(keymap: &KeyMap) -> Vec<String> {
+    let actions: Vec<(String, &str)> = ACTIONS
+        .iter()
+        .map(|action| {
+            let keys: Vec<String> = keymap
+                .keys(action.name)
+                .iter()
+                .map(keymap::key_name)
+                .collect();
+            (keys.join(", "), action.description)
+        })
+        .collect();
+
+    let mut lines = Vec::new();
+    section(&mut lines, "Staged and unstaged changes", &actions);
+    section(&mut lines, "Commit message", &fixed(COMMIT_MESSAGE_KEYS));
+    section(&mut lines, "Commit log", &fixed(COMMIT_LOG_KEYS));
+    section(&mut lines, "Reordering commits", &fixed(REORDER_KEYS));
+    lines
+}
+
+pub fn open(state: &mut AppState) {
+    state.screen = Some(Screen::Help(HelpView {
+        lines: help_lines(&state.keymap),
+        scroll: 0,
+    }));
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let Some(Screen::Help(view)) = state.screen.as_mut() else {
+        return;
+    };
+    let page = HelpView::content_height(max_y).max(1) as isize;
+
+    match input {
+        Input::Character('q')
+        | Input::Character('?')
+        | Input::Character('\u{1b}')
+        | Input::Character('\t') => state.screen = None,
+        Input::Character('t') => {
+            state.screen = None;
+            tutorial::open(state);
+        }
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            view.scroll_by(1, max_y)
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            view.scroll_by(-1, max_y)
+        }
+        Input::Character(' ') | Input::Character('\u{16}') | Input::KeyNPage => {
+            view.scroll_by(page, max_y)
+        }
+        Input::Character('b') | Input::KeyPPage => view.scroll_by(-page, max_y),
+        _ => {}
+    }
+}
+
+pub fn render(window: &Window, repo: &str, view: &HelpView) {
+    let (max_y, max_x) = window.get_max_yx();
+    let width = max_x.max(0) as usize;
+
+    chrome::render_title(window, repo, &[TITLE], HINTS);
+
+    let visible = view
+        .lines
+        .iter()
+        .skip(view.scroll)
+        .take(HelpView::content_height(max_y));
+    for (row, line) in visible.enumerate() {
+        let is_title = !line.is_empty() && !line.starts_with(' ');
+        if is_title {
+            window.attron(A_BOLD);
+        }
+        window.mvaddstr(
+            (BANNER_HEIGHT + row) as i32,
+            1,
+            layout::truncate_to_width(line, width.saturating_sub(1)),
+        );
+        if is_title {
+            window.attroff(A_BOLD);
+        }
+    }
+}
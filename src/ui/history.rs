@@ -0,0 +1,220 @@
+use crate::app_state::{AppState, Screen};
+use crate::git::{self, FileDiff, HistoryCommit};
+use crate::message_log::Severity;
+use crate::ui::chrome;
+use crate::ui::diff_view;
+use crate::ui::layout;
+use pancurses::{COLOR_PAIR, Input, Window};
+
+/// Lines above the commit list or the diff.
+const BANNER_HEIGHT: usize = 1;
+
+/// Screen listing every commit reachable from HEAD. Enter shows the full diff
+/// of the selected commit.
+pub struct History {
+    pub commits: Vec<HistoryCommit>,
+    pub cursor: usize,
+    pub list_scroll: usize,
+    /// Diff of the commit under the cursor while it is opened with Enter.
+    pub diff: Option<Vec<FileDiff>>,
+    pub diff_scroll: usize,
+    pub horizontal_scroll: usize,
+}
+
+impl History {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            commits: git::get_commit_history(&state.repo_path).unwrap_or_default(),
+            cursor: 0,
+            list_scroll: 0,
+            diff: None,
+            diff_scroll: 0,
+            horizontal_scroll: 0,
+        }
+    }
+
+    pub fn selected_commit(&self) -> Option<&HistoryCommit> {
+        self.commits.get(self.cursor)
+    }
+
+    fn content_height(max_y: i32) -> usize {
+        (max_y.max(0) as usize).saturating_sub(BANNER_HEIGHT)
+    }
+
+    fn move_cursor(&mut self, delta: isize, max_y: i32) {
+        let last = self.commits.len().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+
+        let height = Self::content_height(max_y).max(1);
+        if self.cursor < self.list_scroll {
+            self.list_scroll = self.cursor;
+        } else if self.cursor >= self.list_scroll + height {
+            self.list_scroll = self.cursor + 1 - height;
+        }
+    }
+
+    fn scroll_diff_by(&mut self, delta: isize, max_y: i32) {
+        let total_lines: usize = self.diff.iter().flatten().map(|f| f.lines.len()).sum();
+        let max_scroll = total_lines.saturating_sub(Self::content_height(max_y));
+        self.diff_scroll = self
+            .diff_scroll
+            .saturating_add_signed(delta)
+            .min(max_scroll);
+    }
+
+    fn open_diff(&mut self, repo_path: &std::path::Path) -> anyhow::Result<()> {
+        let Some(commit) = self.selected_commit() else {
+            return Ok(());
+        };
+        self.diff = Some(git::get_commit_diff(repo_path, &commit.hash)?);
+        self.diff_scroll = 0;
+        self.horizontal_scroll = 0;
+        Ok(())
+    }
+}
+
+pub fn open(state: &mut AppState) {
+    state.screen = Some(Screen::History(History::new(state)));
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let repo_path = state.repo_path.clone();
+    let Some(Screen::History(history)) = state.screen.as_mut() else {
+        return;
+    };
+    let page = History::content_height(max_y).max(1) as isize;
+
+    if matches!(input, Input::Character('\t')) {
+        state.screen = None;
+        return;
+    }
+
+    if history.diff.is_some() {
+        match input {
+            Input::Character('q') | Input::Character('\n') | Input::Character('\u{1b}') => {
+                history.diff = None;
+            }
+            Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+                history.scroll_diff_by(1, max_y)
+            }
+            Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+                history.scroll_diff_by(-1, max_y)
+            }
+            Input::Character(' ') | Input::Character('\u{16}') | Input::KeyNPage => {
+                history.scroll_diff_by(page, max_y)
+            }
+            Input::Character('b') | Input::KeyPPage => history.scroll_diff_by(-page, max_y),
+            Input::Character('\u{4}') => history.scroll_diff_by(page / 2, max_y),
+            Input::Character('\u{15}') => history.scroll_diff_by(-page / 2, max_y),
+            Input::KeyLeft => {
+                history.horizontal_scroll = history.horizontal_scroll.saturating_sub(1);
+            }
+            Input::KeyRight => history.horizontal_scroll += 1,
+            _ => {}
+        }
+        return;
+    }
+
+    match input {
+        Input::Character('q') | Input::Character('H') | Input::Character('\u{1b}') => {
+            state.screen = None;
+        }
+        Input::Character('\n') => {
+            if let Err(e) = history.open_diff(&repo_path) {
+                state.set_message(Severity::Error, format!("Failed to load commit: {e}"));
+            }
+        }
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            history.move_cursor(1, max_y)
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            history.move_cursor(-1, max_y)
+        }
+        Input::Character(' ') | Input::Character('\u{16}') | Input::KeyNPage => {
+            history.move_cursor(page, max_y)
+        }
+        Input::Character('b') | Input::KeyPPage => history.move_cursor(-page, max_y),
+        Input::Character('\u{4}') => history.move_cursor(page / 2, max_y),
+        Input::Character('\u{15}') => history.move_cursor(-page / 2, max_y),
+        _ => {}
+    }
+}
+
+pub fn render(window: &Window, repo: &str, history: &History, error_message: Option<&str>) {
+    let (max_y, max_x) = window.get_max_yx();
+
+    match (&history.diff, history.selected_commit()) {
+        (Some(_), Some(commit)) => {
+            let detail = format!("{} {}", commit.hash, commit.message);
+            chrome::render_title(window, repo, &["History", &detail], "q back");
+        }
+        _ => {
+            let count = format!("{} commits", history.commits.len());
+            chrome::render_title(window, repo, &["History"], &count);
+        }
+    }
+
+    if let Some(files) = &history.diff {
+        diff_view::render_multiple(
+            window,
+            files,
+            History::content_height(max_y),
+            history.diff_scroll,
+            history.horizontal_scroll,
+            BANNER_HEIGHT,
+            usize::MAX,
+            false,
+        );
+    } else {
+        render_commit_list(window, history, max_y, max_x);
+    }
+
+    if let Some(error) = error_message {
+        window.attron(COLOR_PAIR(2));
+        window.mvaddstr(
+            max_y - 1,
+            0,
+            layout::truncate_to_width(error, max_x.max(0) as usize),
+        );
+        window.attroff(COLOR_PAIR(2));
+    }
+}
+
+fn render_commit_list(window: &Window, history: &History, max_y: i32, max_x: i32) {
+    if history.commits.is_empty() {
+        window.mvaddstr(BANNER_HEIGHT as i32, 0, " No commits yet");
+        return;
+    }
+
+    let width = max_x.max(0) as usize;
+    let visible = history
+        .commits
+        .iter()
+        .enumerate()
+        .skip(history.list_scroll)
+        .take(History::content_height(max_y));
+    for (row, (index, commit)) in visible.enumerate() {
+        let y = (BANNER_HEIGHT + row) as i32;
+        let (pair, hash_pair) = if index == history.cursor {
+            (5, 8)
+        } else {
+            (1, 4)
+        };
+        window.attron(COLOR_PAIR(pair));
+        for x in 0..max_x {
+            window.mvaddch(y, x, ' ');
+        }
+
+        let hash = format!(" {} ", commit.hash);
+        window.attron(COLOR_PAIR(hash_pair));
+        window.mvaddstr(y, 0, layout::truncate_to_width(&hash, width));
+        window.attron(COLOR_PAIR(pair));
+        let rest = format!("{} {}  {}", commit.date, commit.author, commit.message);
+        window.mvaddstr(
+            y,
+            hash.len() as i32,
+            layout::truncate_to_width(&rest, width.saturating_sub(hash.len())),
+        );
+        window.attroff(COLOR_PAIR(pair));
+    }
+}
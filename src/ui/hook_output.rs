@@ -0,0 +1,129 @@
+use crate::app_state::{AppState, Modal};
+use crate::git::HookFailure;
+use crate::message_log::Severity;
+use crate::ui::commit_view;
+use crate::ui::layout;
+use pancurses::{A_BOLD, COLOR_PAIR, Input, Window};
+use unicode_width::UnicodeWidthStr;
+
+const TITLE: &str = " COMMIT HOOK FAILED  r retry  n skip hooks  Esc/q abort ";
+
+/// Modal showing what a failing `pre-commit` or `commit-msg` hook printed,
+/// with the message that was to be committed.
+pub struct HookOutput {
+    pub failure: HookFailure,
+    pub message: String,
+    pub scroll: usize,
+}
+
+impl HookOutput {
+    fn lines(&self) -> Vec<String> {
+        std::iter::once(format!("{}:", self.failure))
+            .chain(std::iter::once(String::new()))
+            .chain(self.failure.output.lines().map(str::to_string))
+            .collect()
+    }
+}
+
+pub fn open(state: &mut AppState, failure: HookFailure, message: &str) {
+    state.modal = Some(Modal::HookOutput(HookOutput {
+        failure,
+        message: message.to_string(),
+        scroll: 0,
+    }));
+    state.alert();
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let Some(Modal::HookOutput(output)) = state.modal.as_mut() else {
+        return;
+    };
+    let line_count = output.lines().len();
+    let max_scroll = line_count.saturating_sub(content_height(max_y, line_count));
+    let page = content_height(max_y, line_count).max(1);
+
+    match input {
+        Input::Character('q') | Input::Character('\u{1b}') => {
+            state.modal = None;
+            state.set_message(Severity::Warning, "Commit aborted, the message is kept");
+        }
+        Input::Character('r') | Input::Character('n') => {
+            let verify = input == Input::Character('r');
+            if let Some(Modal::HookOutput(output)) = state.modal.take() {
+                commit_view::commit(state, &output.message, verify);
+            }
+        }
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            output.scroll = (output.scroll + 1).min(max_scroll);
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            output.scroll = output.scroll.saturating_sub(1);
+        }
+        Input::Character(' ') | Input::Character('\u{16}') | Input::KeyNPage => {
+            output.scroll = (output.scroll + page).min(max_scroll);
+        }
+        Input::Character('b') | Input::KeyPPage => {
+            output.scroll = output.scroll.saturating_sub(page);
+        }
+        _ => {}
+    }
+}
+
+/// Rows available for the output inside the frame.
+fn content_height(max_y: i32, line_count: usize) -> usize {
+    line_count.min((max_y.max(0) as usize).saturating_sub(4))
+}
+
+/// Draws the hook output in a frame over the main screen, which shows the
+/// messages on its bottom line.
+pub fn render(window: &Window, output: &HookOutput) {
+    let (max_y, max_x) = window.get_max_yx();
+    let lines = output.lines();
+    let longest = lines
+        .iter()
+        .map(|line| line.width())
+        .chain(std::iter::once(TITLE.len()))
+        .max()
+        .unwrap_or(0);
+    let width = (longest + 4).min(max_x.max(0) as usize);
+    let height = content_height(max_y, lines.len()) + 2;
+    let left = layout::centered_x(width, max_x);
+    let top = ((max_y.max(0) as usize).saturating_sub(height) / 2) as i32;
+    let inner_width = width.saturating_sub(4);
+
+    window.attron(COLOR_PAIR(1));
+    for row in 0..height {
+        let y = top + row as i32;
+        let (edge, fill) = if row == 0 || row + 1 == height {
+            ('+', '-')
+        } else {
+            ('|', ' ')
+        };
+        window.mvaddch(y, left, edge);
+        for x in 1..width.saturating_sub(1) {
+            window.mvaddch(y, left + x as i32, fill);
+        }
+        window.mvaddch(y, left + width as i32 - 1, edge);
+    }
+    for (row, line) in lines
+        .iter()
+        .skip(output.scroll)
+        .take(height.saturating_sub(2))
+        .enumerate()
+    {
+        window.mvaddstr(
+            top + 1 + row as i32,
+            left + 2,
+            layout::truncate_to_width(line, inner_width),
+        );
+    }
+    window.attroff(COLOR_PAIR(1));
+
+    window.attron(COLOR_PAIR(8) | A_BOLD);
+    window.mvaddstr(
+        top,
+        left + 1,
+        layout::truncate_to_width(TITLE, width.saturating_sub(2)),
+    );
+    window.attroff(COLOR_PAIR(8) | A_BOLD);
+}
@@ -0,0 +1,105 @@
+use crate::app_state::{AppState, EditorRequest};
+use crate::command::{ApplyPatchCommand, Command, StagePatchCommand};
+use crate::git::{self, FileDiff, FileStatus};
+use crate::git_patch;
+use crate::message_log::Severity;
+use std::path::PathBuf;
+
+/// Name of the file the hunk is edited in, inside the git directory. The
+/// same as `git add -p` uses.
+const EDIT_FILE_NAME: &str = "addp-hunk-edit.diff";
+
+/// A hunk opened in the editor with `E`, applied once the editor exits.
+pub struct PendingHunkEdit {
+    pub file_name: String,
+    pub edit_file: PathBuf,
+    /// Whether the edited hunk is staged, or unstaged if it came from the
+    /// staged changes.
+    pub staging: bool,
+}
+
+/// Opens the hunk of `file` under the line cursor in the editor, or its
+/// first hunk when the cursor is not on one.
+pub fn open(state: &mut AppState, file: &FileDiff, staging: bool) {
+    if file.status != FileStatus::Modified {
+        state.set_message(
+            Severity::Warning,
+            "Only hunks of modified files can be edited",
+        );
+        return;
+    }
+    if state.refuse_partial_textconv(&file.file_name) {
+        return;
+    }
+    let Some(hunk) =
+        git_patch::find_hunk(file, state.main_screen.line_cursor).or_else(|| file.hunks.first())
+    else {
+        return;
+    };
+
+    let text = git_patch::create_hunk_edit_text(hunk, staging);
+    let edit_file = match git::git_path(&state.repo_path, EDIT_FILE_NAME)
+        .and_then(|path| Ok(std::fs::write(&path, text).map(|()| path)?))
+    {
+        Ok(path) => path,
+        Err(e) => {
+            state.set_message(Severity::Error, format!("Failed to open the hunk: {e}"));
+            return;
+        }
+    };
+    state.editor_request = Some(EditorRequest {
+        file_path: edit_file.to_string_lossy().into_owned(),
+        // The line below the comment on top.
+        line_number: Some(2),
+        stage_if_saved: None,
+        edit_hunk: Some(PendingHunkEdit {
+            file_name: file.file_name.clone(),
+            edit_file,
+            staging,
+        }),
+        edit_message: None,
+        stage_edit: None,
+        return_to: None,
+    });
+}
+
+/// Applies the hunk saved by the editor, unless it is invalid or no longer
+/// applies. Nothing is changed then.
+pub fn finish(state: &mut AppState, pending: PendingHunkEdit) {
+    let text = std::fs::read_to_string(&pending.edit_file);
+    let _ = std::fs::remove_file(&pending.edit_file);
+    state.refresh_diff(false);
+
+    let patch = match text
+        .map_err(|e| e.to_string())
+        .and_then(|text| git_patch::create_edited_hunk_patch(&pending.file_name, &text))
+    {
+        Ok(patch) => patch,
+        Err(reason) => {
+            state.set_message(
+                Severity::Error,
+                format!("Edited hunk not applied: {reason}"),
+            );
+            return;
+        }
+    };
+
+    // Staging applies the hunk to the index, unstaging reverts it there.
+    let reverse = !pending.staging;
+    if !git::can_apply_patch(&state.repo_path, &patch, reverse, true) {
+        state.set_message(
+            Severity::Error,
+            format!(
+                "Edited hunk not applied: it does not apply to the index of {}",
+                pending.file_name
+            ),
+        );
+        return;
+    }
+    let command: Box<dyn Command> = if pending.staging {
+        Box::new(StagePatchCommand::new(state.repo_path.clone(), patch))
+    } else {
+        Box::new(ApplyPatchCommand::new(state.repo_path.clone(), patch))
+    };
+    state.execute_and_refresh(command);
+}
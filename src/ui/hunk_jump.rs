@@ -0,0 +1,243 @@
+use crate::app_state::{AppState, FocusedPane};
+use crate::git::FileDiff;
+use crate::message_log::Severity;
+use crate::ui::commit_folds;
+use crate::ui::main_screen::{ListItem, UnstagedListItem};
+use crate::ui::scroll;
+use std::borrow::Cow;
+
+/// Lines shown above the line jumped to.
+const CONTEXT_LINES: usize = 3;
+
+/// The files the diff of the focused pane shows one after the other: the
+/// selected file, the files of a rename group or those of a commit.
+fn shown_files(state: &AppState) -> Cow<'_, [FileDiff]> {
+    Cow::Borrowed(match state.focused_pane {
+        FocusedPane::Main => match state.current_main_item() {
+            Some(ListItem::File(file)) => std::slice::from_ref(file),
+            Some(ListItem::RenameGroup { files, .. }) => files,
+            Some(ListItem::PreviousCommitInfo { .. }) => return commit_folds::shown_files(state),
+            _ => &[],
+        },
+        FocusedPane::Unstaged => match state
+            .unstaged_pane
+            .list_items
+            .get(state.unstaged_pane.cursor)
+        {
+            Some(UnstagedListItem::File(file)) => std::slice::from_ref(file),
+            _ => &[],
+        },
+    })
+}
+
+/// Where the files start in the shown diff, and where their hunks do.
+fn boundaries(files: &[FileDiff]) -> (Vec<usize>, Vec<usize>) {
+    let mut file_starts = Vec::new();
+    let mut hunk_starts = Vec::new();
+    let mut offset = 0;
+    for file in files {
+        file_starts.push(offset);
+        hunk_starts.extend(file.hunks.iter().map(|hunk| offset + hunk.start_line));
+        offset += file.lines.len();
+    }
+    (file_starts, hunk_starts)
+}
+
+/// The first of `positions` after `cursor`, or the last one before it,
+/// with its index.
+fn next_position(positions: &[usize], cursor: usize, forward: bool) -> Option<(usize, usize)> {
+    let index = if forward {
+        positions.iter().position(|&line| line > cursor)
+    } else {
+        positions.iter().rposition(|&line| line < cursor)
+    }?;
+    Some((index, positions[index]))
+}
+
+/// Puts the diff cursor of the focused pane on `line`, a few lines below
+/// the top.
+fn move_diff_cursor(state: &mut AppState, line: usize) {
+    state.main_screen.line_cursor = line;
+    let scroll = line.saturating_sub(CONTEXT_LINES);
+    match state.focused_pane {
+        FocusedPane::Main => {
+            state.main_screen.is_diff_cursor_active = true;
+            state.main_screen.diff_scroll = scroll;
+        }
+        FocusedPane::Unstaged => {
+            state.unstaged_pane.is_diff_cursor_active = true;
+            state.unstaged_pane.diff_scroll = scroll;
+        }
+    }
+}
+
+/// The line the diff cursor is on, or the top of the diff without it.
+fn diff_cursor(state: &AppState) -> Option<usize> {
+    let is_active = match state.focused_pane {
+        FocusedPane::Main => state.main_screen.is_diff_cursor_active,
+        FocusedPane::Unstaged => state.unstaged_pane.is_diff_cursor_active,
+    };
+    is_active.then_some(state.main_screen.line_cursor)
+}
+
+/// Moves the diff cursor to the header of the next hunk, or of the
+/// previous one.
+pub fn jump_to_hunk(state: &mut AppState, forward: bool) {
+    let (_, hunk_starts) = boundaries(&shown_files(state));
+    if hunk_starts.is_empty() {
+        state.set_message(Severity::Warning, "No hunks in this diff");
+        state.alert();
+        return;
+    }
+    let target = match diff_cursor(state) {
+        Some(cursor) => next_position(&hunk_starts, cursor, forward),
+        None if forward => Some((0, hunk_starts[0])),
+        None => None,
+    };
+    let Some((index, line)) = target else {
+        state.set_message(
+            Severity::Warning,
+            if forward {
+                format!(
+                    "No hunk below, {} goes to the next file",
+                    state.keymap.key_label("next_file")
+                )
+            } else {
+                format!(
+                    "No hunk above, {} goes to the previous file",
+                    state.keymap.key_label("previous_file")
+                )
+            },
+        );
+        state.alert();
+        return;
+    };
+    move_diff_cursor(state, line);
+    state.set_message(
+        Severity::Info,
+        format!("Hunk {} of {}", index + 1, hunk_starts.len()),
+    );
+}
+
+/// Moves the diff cursor to the start of the next file of a diff showing
+/// several, or selects the next file of the focused list, the diff
+/// cursor on its first hunk. Backwards likewise.
+pub fn jump_to_file(state: &mut AppState, forward: bool, max_y: i32) {
+    let files = shown_files(state);
+    if files.len() > 1 {
+        let (file_starts, _) = boundaries(&files);
+        let cursor = diff_cursor(state).unwrap_or(0);
+        let Some((index, line)) = next_position(&file_starts, cursor, forward) else {
+            state.set_message(
+                Severity::Warning,
+                if forward {
+                    "This is the last file of the diff"
+                } else {
+                    "This is the first file of the diff"
+                }
+                .to_string(),
+            );
+            state.alert();
+            return;
+        };
+        let message = format!(
+            "File {} of {}: {}",
+            index + 1,
+            files.len(),
+            files[index].file_name
+        );
+        move_diff_cursor(state, line);
+        state.set_message(Severity::Info, message);
+        return;
+    }
+    select_next_file(state, forward, max_y);
+}
+
+/// The rows of the focused list holding files, with their names.
+fn listed_files(state: &AppState) -> Vec<(usize, &str)> {
+    match state.focused_pane {
+        FocusedPane::Main => state
+            .main_screen
+            .list_items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| match item {
+                ListItem::File(file) => Some((index, file.file_name.as_str())),
+                _ => None,
+            })
+            .collect(),
+        FocusedPane::Unstaged => state
+            .unstaged_pane
+            .list_items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| match item {
+                UnstagedListItem::File(file) => Some((index, file.file_name.as_str())),
+                UnstagedListItem::UntrackedFile(file_name) => Some((index, file_name.as_str())),
+                _ => None,
+            })
+            .collect(),
+    }
+}
+
+fn select_next_file(state: &mut AppState, forward: bool, max_y: i32) {
+    let listed = listed_files(state);
+    let cursor = match state.focused_pane {
+        FocusedPane::Main => state.main_screen.file_cursor,
+        FocusedPane::Unstaged => state.unstaged_pane.cursor,
+    };
+    let rows: Vec<usize> = listed.iter().map(|(row, _)| *row).collect();
+    let Some((index, row)) = next_position(&rows, cursor, forward) else {
+        state.set_message(
+            Severity::Warning,
+            if forward {
+                "No file below"
+            } else {
+                "No file above"
+            }
+            .to_string(),
+        );
+        state.alert();
+        return;
+    };
+    let message = format!(
+        "File {} of {}: {}",
+        index + 1,
+        listed.len(),
+        listed[index].1
+    );
+
+    match state.focused_pane {
+        FocusedPane::Main => {
+            state.main_screen.file_cursor = row;
+            let height = state.main_header_height(max_y).0;
+            scroll::into_view(row, &mut state.main_screen.file_list_scroll, height);
+        }
+        FocusedPane::Unstaged => {
+            state.unstaged_pane.cursor = row;
+            let height = state.unstaged_header_height(max_y).0;
+            scroll::into_view(row, &mut state.unstaged_pane.scroll, height);
+        }
+    }
+    let first_hunk = shown_files(state)
+        .first()
+        .and_then(|file| file.hunks.first())
+        .map(|hunk| hunk.start_line);
+    match first_hunk {
+        Some(line) => move_diff_cursor(state, line),
+        None => {
+            state.main_screen.line_cursor = 0;
+            match state.focused_pane {
+                FocusedPane::Main => {
+                    state.main_screen.is_diff_cursor_active = false;
+                    state.main_screen.diff_scroll = 0;
+                }
+                FocusedPane::Unstaged => {
+                    state.unstaged_pane.is_diff_cursor_active = false;
+                    state.unstaged_pane.diff_scroll = 0;
+                }
+            }
+        }
+    }
+    state.set_message(Severity::Info, message);
+}
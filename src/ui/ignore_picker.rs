@@ -0,0 +1,235 @@
+use crate::app_state::{AppState, Modal};
+use crate::command::{
+    Command, IgnoreFileCommand, IgnoreUnstagedTrackedFileCommand, IgnoreUntrackedFileCommand,
+};
+use crate::ignore_target::IgnoreTarget;
+use crate::message_log::Severity;
+use crate::ui::layout;
+use pancurses::{A_BOLD, COLOR_PAIR, Input, Window};
+use std::path::Path;
+use unicode_width::UnicodeWidthStr;
+
+const TITLE: &str = " IGNORE  Enter/1-3 add  Tab where  Esc/q cancel ";
+
+/// The list the file to ignore is in, which tells how it is taken out of
+/// the index.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Listed {
+    Staged,
+    Unstaged,
+    Untracked,
+}
+
+/// A line an ignore file could get, and what it ignores.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Choice {
+    pub pattern: String,
+    pub description: String,
+}
+
+/// Popup offering the patterns that ignore a file: its path, every file
+/// with its extension, or its directory, and the file to write it to.
+pub struct IgnorePicker {
+    pub file_name: String,
+    pub listed: Listed,
+    pub choices: Vec<Choice>,
+    pub cursor: usize,
+    pub target: IgnoreTarget,
+}
+
+/// The patterns offered for `file_name`: the path itself, `*.<ext>` when
+/// it has an extension and `<dir>/` when it is in a directory.
+pub fn choices(file_name: &str) -> Vec<Choice> {
+    let mut choices = vec![Choice {
+        pattern: file_name.to_string(),
+        description: "this file only".to_string(),
+    }];
+    if let Some(extension) = Path::new(file_name).extension() {
+        let extension = extension.to_string_lossy();
+        choices.push(Choice {
+            pattern: format!("*.{extension}"),
+            description: format!("every .{extension} file"),
+        });
+    }
+    if let Some((directory, _)) = file_name.rsplit_once('/') {
+        choices.push(Choice {
+            pattern: format!("{directory}/"),
+            description: format!("everything in {directory}"),
+        });
+    }
+    choices
+}
+
+pub fn open(state: &mut AppState, file_name: String, listed: Listed) {
+    state.modal = Some(Modal::IgnorePicker(IgnorePicker {
+        choices: choices(&file_name),
+        file_name,
+        listed,
+        cursor: 0,
+        target: IgnoreTarget::default(),
+    }));
+}
+
+pub fn handle_input(state: &mut AppState, input: Input) {
+    let Some(Modal::IgnorePicker(picker)) = state.modal.as_mut() else {
+        return;
+    };
+    match input {
+        Input::Character('q') | Input::Character('\u{1b}') => {
+            state.modal = None;
+        }
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            picker.cursor = (picker.cursor + 1).min(picker.choices.len() - 1);
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            picker.cursor = picker.cursor.saturating_sub(1);
+        }
+        Input::Character('\t') => {
+            picker.target = picker.target.next();
+        }
+        Input::Character('\n') | Input::KeyEnter => {
+            let index = picker.cursor;
+            choose(state, index);
+        }
+        Input::Character(c @ '1'..='9') => {
+            let index = c as usize - '1' as usize;
+            if index < picker.choices.len() {
+                choose(state, index);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Adds the pattern of choice `index` to the chosen ignore file as one undo
+/// step, taking the file out of the index if it is tracked.
+fn choose(state: &mut AppState, index: usize) {
+    let Some(Modal::IgnorePicker(picker)) = state.modal.take() else {
+        return;
+    };
+    let pattern = picker.choices[index].pattern.clone();
+    let repo_path = state.repo_path.clone();
+    let file_name = picker.file_name;
+    let target = picker.target;
+    let command: Box<dyn Command> = match picker.listed {
+        Listed::Staged => Box::new(IgnoreFileCommand::new(
+            repo_path,
+            file_name,
+            pattern.clone(),
+            target,
+        )),
+        Listed::Unstaged => Box::new(IgnoreUnstagedTrackedFileCommand::new(
+            repo_path,
+            file_name,
+            pattern.clone(),
+            target,
+        )),
+        Listed::Untracked => Box::new(IgnoreUntrackedFileCommand::new(
+            repo_path,
+            file_name,
+            pattern.clone(),
+            target,
+        )),
+    };
+    if state.execute_and_refresh(command) && state.message.is_none() {
+        state.set_message(
+            Severity::Success,
+            format!("Added {pattern} to {}", target.label()),
+        );
+    }
+}
+
+/// Draws the numbered choices with the cursor on one of them.
+pub fn render(window: &Window, picker: &IgnorePicker) {
+    let (max_y, max_x) = window.get_max_yx();
+    let pattern_width = picker
+        .choices
+        .iter()
+        .map(|choice| choice.pattern.width())
+        .max()
+        .unwrap_or(0);
+    let lines: Vec<String> = picker
+        .choices
+        .iter()
+        .enumerate()
+        .map(|(index, choice)| {
+            let padding = " ".repeat(pattern_width - choice.pattern.width());
+            format!(
+                "{}  {}{padding}  {}",
+                index + 1,
+                choice.pattern,
+                choice.description
+            )
+        })
+        .collect();
+    let heading = format!("Ignore {}", picker.file_name);
+    let destination = format!("Add to {}", picker.target.label());
+
+    let width = lines
+        .iter()
+        .map(|line| line.width() + 4)
+        .chain([
+            heading.width() + 4,
+            destination.width() + 4,
+            TITLE.len() + 2,
+        ])
+        .max()
+        .unwrap_or(0)
+        .min(max_x.max(0) as usize);
+    let height = lines.len() + 6;
+    let left = layout::centered_x(width, max_x);
+    let top = ((max_y.max(0) as usize).saturating_sub(height) / 2) as i32;
+    let inner_width = width.saturating_sub(4);
+
+    window.attron(COLOR_PAIR(1));
+    for row in 0..height {
+        let y = top + row as i32;
+        let (edge, fill) = if row == 0 || row + 1 == height {
+            ('+', '-')
+        } else {
+            ('|', ' ')
+        };
+        window.mvaddch(y, left, edge);
+        for x in 1..width.saturating_sub(1) {
+            window.mvaddch(y, left + x as i32, fill);
+        }
+        window.mvaddch(y, left + width as i32 - 1, edge);
+    }
+    window.mvaddstr(
+        top + 1,
+        left + 2,
+        layout::truncate_to_width(&heading, inner_width),
+    );
+    window.attroff(COLOR_PAIR(1));
+    // Where the pattern goes stands out once it is not the shared file.
+    let pair = if picker.target == IgnoreTarget::Gitignore {
+        1
+    } else {
+        18
+    };
+    window.attron(COLOR_PAIR(pair));
+    window.mvaddstr(
+        top + height as i32 - 2,
+        left + 2,
+        layout::truncate_to_width(&destination, inner_width),
+    );
+    window.attroff(COLOR_PAIR(pair));
+
+    for (index, line) in lines.iter().enumerate() {
+        let y = top + 3 + index as i32;
+        let pair = if index == picker.cursor { 5 } else { 1 };
+        let line = layout::truncate_to_width(line, inner_width);
+        let padding = " ".repeat(inner_width.saturating_sub(line.width()));
+        window.attron(COLOR_PAIR(pair));
+        window.mvaddstr(y, left + 2, format!("{line}{padding}"));
+        window.attroff(COLOR_PAIR(pair));
+    }
+
+    window.attron(COLOR_PAIR(8) | A_BOLD);
+    window.mvaddstr(
+        top,
+        left + 1,
+        layout::truncate_to_width(TITLE, width.saturating_sub(2)),
+    );
+    window.attroff(COLOR_PAIR(8) | A_BOLD);
+}
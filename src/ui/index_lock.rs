@@ -0,0 +1,272 @@
+use crate::app_state::{AppState, Modal};
+use crate::command::Command;
+use crate::git::{self, IndexLock};
+use crate::message_log::Severity;
+use crate::ui::commit_view;
+use crate::ui::layout;
+use pancurses::{A_BOLD, COLOR_PAIR, Input, Window};
+use std::time::Duration;
+use unicode_width::UnicodeWidthStr;
+
+const TITLE: &str = " INDEX LOCKED  r retry  d remove  Esc/q cancel ";
+
+const FREED: &str = "The index is free again, the held action ran";
+
+/// A lock this old with no git process about is taken as left over from a
+/// crash. Younger ones may belong to a git that is just starting.
+const STALE_AFTER: Duration = Duration::from_secs(10);
+
+/// What was about to write the index.
+pub enum HeldAction {
+    /// Run with [`AppState::execute_and_guard`] if `guarded`, else with
+    /// [`AppState::execute_and_refresh`].
+    Command {
+        command: Box<dyn Command>,
+        guarded: bool,
+    },
+    Commit {
+        message: String,
+        verify: bool,
+    },
+}
+
+/// Modal shown when an action would write the index while another process
+/// holds its lock. The action waits, held here, and runs once the lock is
+/// gone.
+pub struct IndexLockWait {
+    pub lock: IndexLock,
+    pub action: HeldAction,
+    /// `d` was pressed, the next key confirms removing the lock.
+    pub confirming_removal: bool,
+    pub lines: Vec<String>,
+}
+
+/// Holds `action` back while the index is locked, telling who locked it.
+pub fn open(state: &mut AppState, lock: IndexLock, action: HeldAction) {
+    let lines = describe(&lock);
+    state.modal = Some(Modal::IndexLockWait(IndexLockWait {
+        lock,
+        action,
+        confirming_removal: false,
+        lines,
+    }));
+    state.set_message(
+        Severity::Warning,
+        "The index is locked, the action waits for it",
+    );
+    state.alert();
+}
+
+fn describe(lock: &IndexLock) -> Vec<String> {
+    let mut lines = vec![format!(
+        "Another git process is using the index: {} exists.",
+        lock.path.display()
+    )];
+    if let Some(age) = lock.age {
+        lines.push(format!("It was made {} ago.", format_age(age)));
+    }
+    match &lock.holders {
+        Some(holders) if holders.is_empty() => lines.push(
+            "No git process is running in this repository, the lock looks left over from a crash."
+                .to_string(),
+        ),
+        Some(holders) => {
+            lines.push("Git running in this repository:".to_string());
+            lines.extend(holders.iter().map(|holder| format!("  {holder}")));
+        }
+        None => lines.push("Which process holds it cannot be told here.".to_string()),
+    }
+    lines.push(String::new());
+    lines.push("The action waits and runs as soon as the lock is gone.".to_string());
+    lines.push("  r  Retry now".to_string());
+    lines.push("  d  Remove the lock, if it is stale".to_string());
+    lines.push("  q  Cancel the action".to_string());
+    lines
+}
+
+fn format_age(age: Duration) -> String {
+    let seconds = age.as_secs();
+    match seconds {
+        0..60 => format!("{seconds} s"),
+        60..3600 => format!("{} min", seconds / 60),
+        _ => format!("{} h", seconds / 3600),
+    }
+}
+
+/// Why the lock must not be removed: a git process may still be writing
+/// the index, or is about to.
+fn removal_refusal(lock: &IndexLock) -> Option<String> {
+    if let Some(holder) = lock.holders.as_ref().and_then(|holders| holders.first()) {
+        return Some(format!("{holder} may hold the lock, wait for it to finish"));
+    }
+    match lock.age {
+        Some(age) if age < STALE_AFTER => Some(format!(
+            "The lock is only {} old, wait a moment",
+            format_age(age)
+        )),
+        _ => None,
+    }
+}
+
+/// Runs the held action once the lock is gone. Whether it ran.
+pub fn poll(state: &mut AppState) -> bool {
+    let is_free =
+        matches!(&state.modal, Some(Modal::IndexLockWait(wait)) if !wait.lock.path.exists());
+    if is_free {
+        run_held(state, FREED.to_string());
+    }
+    is_free
+}
+
+/// Runs the held action, telling `done` if it succeeds.
+fn run_held(state: &mut AppState, done: String) {
+    let Some(Modal::IndexLockWait(wait)) = state.modal.take() else {
+        return;
+    };
+    state.message = None;
+    match wait.action {
+        HeldAction::Command { command, guarded } => {
+            let succeeded = if guarded {
+                state.execute_and_guard(command)
+            } else {
+                state.execute_and_refresh(command)
+            };
+            if succeeded && state.message.is_none() {
+                state.set_message(Severity::Success, done);
+            }
+        }
+        HeldAction::Commit { message, verify } => {
+            commit_view::commit(state, &message, verify);
+            if state.message.is_none() && !matches!(state.modal, Some(Modal::IndexLockWait(_))) {
+                state.set_message(Severity::Success, done);
+            }
+        }
+    }
+}
+
+pub fn handle_input(state: &mut AppState, input: Input) {
+    let Some(Modal::IndexLockWait(wait)) = state.modal.as_mut() else {
+        return;
+    };
+    if wait.confirming_removal {
+        wait.confirming_removal = false;
+        if input != Input::Character('y') {
+            state.set_message(Severity::Info, "Kept the lock");
+            return;
+        }
+        // Checked again, a git may have started since the question.
+        let Some(lock) = git::index_lock(&state.repo_path) else {
+            run_held(state, FREED.to_string());
+            return;
+        };
+        if let Some(refusal) = removal_refusal(&lock) {
+            state.set_message(Severity::Warning, refusal);
+            state.alert();
+            return;
+        }
+        match std::fs::remove_file(&lock.path) {
+            Ok(()) => run_held(
+                state,
+                format!("Removed {}, the held action ran", lock.path.display()),
+            ),
+            Err(e) => {
+                state.set_message(Severity::Error, format!("Could not remove the lock: {e}"));
+                state.alert();
+            }
+        }
+        return;
+    }
+
+    match input {
+        Input::Character('q') | Input::Character('\u{1b}') => {
+            state.modal = None;
+            state.set_message(Severity::Info, "Cancelled the action, the index is locked");
+        }
+        Input::Character('r') => match git::index_lock(&state.repo_path) {
+            None => run_held(state, FREED.to_string()),
+            Some(lock) => {
+                wait.lines = describe(&lock);
+                wait.lock = lock;
+                state.set_message(Severity::Warning, "The index is still locked");
+                state.alert();
+            }
+        },
+        Input::Character('d') => {
+            let lock = git::index_lock(&state.repo_path);
+            let Some(lock) = lock else {
+                run_held(state, FREED.to_string());
+                return;
+            };
+            let refusal = removal_refusal(&lock);
+            wait.lines = describe(&lock);
+            wait.lock = lock;
+            match refusal {
+                Some(refusal) => {
+                    state.set_message(Severity::Warning, refusal);
+                    state.alert();
+                }
+                None => {
+                    wait.confirming_removal = true;
+                    let question = format!(
+                        "Remove {}? y removes it, any other key keeps it",
+                        wait.lock.path.display()
+                    );
+                    state.set_message(Severity::Info, question);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Draws what holds the index in a frame over the main screen.
+pub fn render(window: &Window, wait: &IndexLockWait) {
+    let (max_y, max_x) = window.get_max_yx();
+    let longest = wait
+        .lines
+        .iter()
+        .map(|line| line.width())
+        .chain(std::iter::once(TITLE.len()))
+        .max()
+        .unwrap_or(0);
+    let width = (longest + 4).min(max_x.max(0) as usize);
+    let height = wait
+        .lines
+        .len()
+        .min((max_y.max(0) as usize).saturating_sub(4))
+        + 2;
+    let left = layout::centered_x(width, max_x);
+    let top = ((max_y.max(0) as usize).saturating_sub(height) / 2) as i32;
+    let inner_width = width.saturating_sub(4);
+
+    window.attron(COLOR_PAIR(1));
+    for row in 0..height {
+        let y = top + row as i32;
+        let (edge, fill) = if row == 0 || row + 1 == height {
+            ('+', '-')
+        } else {
+            ('|', ' ')
+        };
+        window.mvaddch(y, left, edge);
+        for x in 1..width.saturating_sub(1) {
+            window.mvaddch(y, left + x as i32, fill);
+        }
+        window.mvaddch(y, left + width as i32 - 1, edge);
+    }
+    for (row, line) in wait.lines.iter().take(height.saturating_sub(2)).enumerate() {
+        window.mvaddstr(
+            top + 1 + row as i32,
+            left + 2,
+            layout::truncate_to_width(line, inner_width),
+        );
+    }
+    window.attroff(COLOR_PAIR(1));
+
+    window.attron(COLOR_PAIR(8) | A_BOLD);
+    window.mvaddstr(
+        top,
+        left + 1,
+        layout::truncate_to_width(TITLE, width.saturating_sub(2)),
+    );
+    window.attroff(COLOR_PAIR(8) | A_BOLD);
+}
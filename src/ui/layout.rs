@@ -0,0 +1,195 @@
+use std::collections::{BTreeSet, HashMap};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Below this width the file lists drop the status column and long paths are
+/// shortened in the middle instead of being cut off at the right edge.
+pub const NARROW_WIDTH: usize = 40;
+
+const ELLIPSIS: char = '…';
+
+pub fn is_narrow(max_x: i32) -> bool {
+    (max_x.max(0) as usize) < NARROW_WIDTH
+}
+
+/// Cuts `text` so that it fits into `max_width` columns.
+pub fn truncate_to_width(text: &str, max_width: usize) -> String {
+    let mut result = String::new();
+    let mut width = 0;
+    for ch in text.chars() {
+        let ch_width = ch.width().unwrap_or(0);
+        if width + ch_width > max_width {
+            break;
+        }
+        result.push(ch);
+        width += ch_width;
+    }
+    result
+}
+
+/// Splits `line` into rows of at most `width` columns.
+pub fn wrap_line(line: &str, width: usize) -> Vec<String> {
+    let mut rows = vec![String::new()];
+    let mut row_width = 0;
+    for ch in line.chars() {
+        let ch_width = ch.width().unwrap_or(0);
+        if row_width + ch_width > width && row_width > 0 {
+            rows.push(String::new());
+            row_width = 0;
+        }
+        if let Some(row) = rows.last_mut() {
+            row.push(ch);
+        }
+        row_width += ch_width;
+    }
+    rows
+}
+
+/// Shortens `text` to `max_width` columns by replacing its middle with `…`,
+/// keeping the start and the (usually more telling) end of a path.
+pub fn middle_ellipsis(text: &str, max_width: usize) -> String {
+    if text.width() <= max_width {
+        return text.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+
+    let budget = max_width - 1;
+    let head_budget = budget / 2;
+    let tail_budget = budget - head_budget;
+
+    let head = truncate_to_width(text, head_budget);
+
+    let mut tail_chars = Vec::new();
+    let mut tail_width = 0;
+    for ch in text.chars().rev() {
+        let ch_width = ch.width().unwrap_or(0);
+        if tail_width + ch_width > tail_budget {
+            break;
+        }
+        tail_chars.push(ch);
+        tail_width += ch_width;
+    }
+    let tail: String = tail_chars.into_iter().rev().collect();
+
+    format!("{head}{ELLIPSIS}{tail}")
+}
+
+/// Text of one row in a file list, split into the parts that are drawn with
+/// different colors.
+#[derive(Debug, PartialEq)]
+pub struct FileRow {
+    pub indent: &'static str,
+    /// `None` when the status column is hidden on narrow terminals.
+    pub status: Option<char>,
+    pub name: String,
+}
+
+impl FileRow {
+    pub fn width(&self) -> usize {
+        let status_width = if self.status.is_some() { 2 } else { 0 };
+        self.indent.width() + status_width + self.name.width()
+    }
+}
+
+/// Lays out a file list row for a terminal `max_x` columns wide. `old_name`
+/// is set for renames; on narrow terminals only the new name is shown.
+pub fn file_row(status: char, name: &str, old_name: Option<&str>, max_x: i32) -> FileRow {
+    row_with_indent(status, name, old_name, max_x, (" ", "   "))
+}
+
+/// Like [`file_row`] for an untracked file, whose `?` sits one column
+/// further right than the status of tracked files.
+pub fn untracked_file_row(name: &str, max_x: i32) -> FileRow {
+    row_with_indent('?', name, None, max_x, (" ", "    "))
+}
+
+/// Like [`file_row`], indented one level deeper for rows listed below a
+/// collapsible entry.
+pub fn nested_file_row(status: char, name: &str, old_name: Option<&str>, max_x: i32) -> FileRow {
+    row_with_indent(status, name, old_name, max_x, ("   ", "     "))
+}
+
+fn row_with_indent(
+    status: char,
+    name: &str,
+    old_name: Option<&str>,
+    max_x: i32,
+    (narrow_indent, wide_indent): (&'static str, &'static str),
+) -> FileRow {
+    let max_x = max_x.max(0) as usize;
+    if max_x < NARROW_WIDTH {
+        let indent = narrow_indent;
+        FileRow {
+            indent,
+            status: None,
+            name: middle_ellipsis(name, max_x.saturating_sub(indent.width())),
+        }
+    } else {
+        let indent = wide_indent;
+        let label = match old_name {
+            Some(old_name) => format!("{old_name} -> {name}"),
+            None => name.to_string(),
+        };
+        FileRow {
+            indent,
+            status: Some(status),
+            name: middle_ellipsis(&label, max_x.saturating_sub(indent.width() + 2)),
+        }
+    }
+}
+
+/// Returns the column at which text of `text_width` columns has to start to
+/// be centered, never negative.
+pub fn centered_x(text_width: usize, max_x: i32) -> i32 {
+    ((max_x.max(0) as usize).saturating_sub(text_width) / 2) as i32
+}
+
+/// Abbreviates the directory components of every path like the fish shell
+/// does, keeping the file name intact. Each directory is cut to the shortest
+/// prefix that no sibling directory in `paths` shares, so two different
+/// directories never end up with the same abbreviation.
+pub fn abbreviate_paths<'a>(paths: impl IntoIterator<Item = &'a str>) -> HashMap<String, String> {
+    let paths: BTreeSet<&str> = paths.into_iter().collect();
+
+    // Directory names found below each parent directory.
+    let mut children: HashMap<Vec<&str>, BTreeSet<&str>> = HashMap::new();
+    for path in &paths {
+        let components: Vec<&str> = path.split('/').collect();
+        for depth in 0..components.len() - 1 {
+            children
+                .entry(components[..depth].to_vec())
+                .or_default()
+                .insert(components[depth]);
+        }
+    }
+
+    paths
+        .iter()
+        .map(|path| {
+            let components: Vec<&str> = path.split('/').collect();
+            let (file_name, dirs) = components.split_last().expect("split yields one item");
+            let mut abbreviated: Vec<String> = (0..dirs.len())
+                .map(|depth| unique_prefix(dirs[depth], &children[&dirs[..depth].to_vec()]))
+                .collect();
+            abbreviated.push(file_name.to_string());
+            (path.to_string(), abbreviated.join("/"))
+        })
+        .collect()
+}
+
+fn unique_prefix(name: &str, siblings: &BTreeSet<&str>) -> String {
+    // Hidden directories keep their dot plus at least one character.
+    let min_len = if name.starts_with('.') { 2 } else { 1 };
+    let char_count = name.chars().count();
+    for len in min_len..char_count {
+        let prefix: String = name.chars().take(len).collect();
+        if siblings
+            .iter()
+            .all(|sibling| *sibling == name || !sibling.starts_with(&prefix))
+        {
+            return prefix;
+        }
+    }
+    name.to_string()
+}
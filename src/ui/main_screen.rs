@@ -1,17 +1,67 @@
-use crate::app_state::{AppState, EditorRequest, FocusedPane};
+use crate::app_state::{
+    AppState, EditorRequest, FileListOrder, FocusedPane, HashDisplay, HunkAnchor,
+};
+use crate::background::RemoteOperation;
+use crate::blame_summary;
 use crate::command::{
-    ApplyPatchCommand, CheckoutFileCommand, CommandHistory, DeleteUntrackedFileCommand,
-    DiscardCommitCommand, DiscardFileCommand, DiscardHunkCommand, DiscardUnstagedHunkCommand,
-    IgnoreFileCommand, IgnoreUnstagedTrackedFileCommand, IgnoreUntrackedFileCommand,
-    StageAllCommand, StageFileCommand, StagePatchCommand, StageUnstagedCommand,
-    StageUntrackedCommand, UnstageAllCommand, UnstageFileCommand,
+    ApplyExternalPatchCommand, ApplyPatchCommand, CheckoutFileCommand, CommandHistory,
+    DeleteUntrackedFileCommand, DiscardCommitCommand, DiscardFileCommand, DiscardHunkCommand,
+    DiscardUnstagedHunkCommand, DropCommitCommand, GroupCommand, ReorderCommitsCommand,
+    RestoreDeletedFileCommand, StageAllCommand, StageFileCommand, StagePatchCommand,
+    StagePathsCommand, StageUnstagedCommand, StageUntrackedCommand, SwapCommitCommand,
+    ToggleExecutableCommand, UnstageAllCommand, UnstageFileCommand, UnstagePathsCommand,
 };
+use crate::commit_lint;
 use crate::commit_storage;
-use crate::git::{self, FileStatus};
+use crate::edit_stage;
+use crate::external_command;
+use crate::git::{
+    self, BranchStatus, CommitInfo, FileDiff, FileStatus, PushStatus, RebaseAction, RebaseStep,
+};
+use crate::hunk_overlap;
+use crate::message_log::Severity;
+use crate::split_commit;
+use crate::ui::bookmarks;
+use crate::ui::chrome;
+use crate::ui::color;
+use crate::ui::command_prompt;
+use crate::ui::commit_details;
+use crate::ui::commit_folds;
 use crate::ui::commit_view;
+use crate::ui::diagnostics_view;
+use crate::ui::diff_search;
 use crate::ui::diff_view;
 use crate::ui::diff_view::LINE_CONTENT_OFFSET;
+use crate::ui::discard_bin::{self, DiscardedChange};
+use crate::ui::explain;
+use crate::ui::final_review;
+use crate::ui::fixup_picker;
+use crate::ui::format_preview;
+use crate::ui::help;
+use crate::ui::history;
+use crate::ui::hunk_edit;
+use crate::ui::hunk_jump;
+use crate::ui::ignore_picker::{self, Listed};
+use crate::ui::layout;
+use crate::ui::maintenance;
+use crate::ui::message_log_view;
+use crate::ui::patch_preview;
+use crate::ui::plan_preview::{self, PlanSource};
+use crate::ui::prompt::{self, Prompt, PromptAction};
+use crate::ui::rebase_plan;
+use crate::ui::rebase_recovery::{self, Retry, Snapshot};
+use crate::ui::recipes;
+use crate::ui::restore_picker;
+use crate::ui::reword_preview;
 use crate::ui::scroll;
+use crate::ui::stashes;
+use crate::ui::stat_panel;
+use crate::ui::status_filter;
+use crate::ui::unified_list;
+use crate::ui::worktrees;
+use crate::util::file_size::format_size;
+use crate::util::repo_path;
+use crate::whitespace;
 use pancurses::Input;
 
 use super::keyboard::{
@@ -19,7 +69,10 @@ use super::keyboard::{
     is_move_up, is_stage_toggle, is_vertical_navigation,
 };
 use crate::git_patch;
-use pancurses::{COLOR_PAIR, Window};
+use crate::util::rename_groups::RenameGroup;
+use pancurses::{A_BOLD, A_DIM, COLOR_PAIR, Window};
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
 
 fn is_binary(content: &[u8]) -> bool {
     content.contains(&0x00)
@@ -29,14 +82,27 @@ fn is_binary(content: &[u8]) -> bool {
 pub enum UnstagedListItem {
     UnstagedChangesHeader,
     File(crate::git::FileDiff),
+    /// Deleted files and untracked files with the same names in another
+    /// directory: a directory moved without `git mv`.
+    RenameGroup(RenameGroup),
     UntrackedFilesHeader,
     UntrackedFile(String),
+    /// Heads the untracked files that are too large to be staged with
+    /// everything else. They are `UntrackedFile` items too.
+    LargeFilesHeader,
 }
 
 #[derive(Debug, Clone)]
 pub enum ListItem {
     StagedChangesHeader,
     File(crate::git::FileDiff),
+    /// Staged renames of a whole directory, collapsed into one entry. When
+    /// expanded, the `File` items of the group follow it.
+    RenameGroup {
+        group: RenameGroup,
+        files: Vec<crate::git::FileDiff>,
+        expanded: bool,
+    },
     CommitMessageInput,
     PreviousCommitInfo {
         hash: String,
@@ -74,9 +140,13 @@ pub fn render(window: &Window, state: &AppState) {
 
     if state.main_screen.is_reordering_commits {
         window.attron(COLOR_PAIR(1));
-        let title = " Commit Reordering (Up/Down: move, Enter: confirm, Esc/q: cancel) ";
-        let title_x = (max_x - title.len() as i32) / 2;
-        window.mvaddstr(0, title_x, title);
+        let title = if state.main_screen.is_grabbing_commit {
+            " Grabbed Commit (Up/Down: move it, g: release, Enter: confirm, Esc/q: cancel) "
+        } else {
+            " Commit Reordering (Up/Down: move, Enter: confirm, Esc/q: cancel) "
+        };
+        let title = layout::truncate_to_width(title, max_x as usize);
+        window.mvaddstr(0, layout::centered_x(title.len(), max_x), &title);
         window.attroff(COLOR_PAIR(1));
     }
 
@@ -101,20 +171,185 @@ pub fn render(window: &Window, state: &AppState) {
         pancurses::curs_set(0);
     }
 
-    if let Some(error) = &state.error_message {
+    if let Some(message) = &state.message {
         let error_y = max_y - 1;
-        window.attron(COLOR_PAIR(10));
+        let pair = COLOR_PAIR(color::message_pair(message.severity, true));
+        window.attron(pair);
         for x in 0..max_x {
             window.mvaddch(error_y, x, ' ');
         }
-        window.mvaddstr(error_y, 0, error);
+        window.mvaddstr(
+            error_y,
+            0,
+            layout::truncate_to_width(&message.text, max_x.max(0) as usize),
+        );
+        window.attroff(pair);
+    } else if state.push_started.is_some() {
+        let status_y = max_y - 1;
+        window.attron(COLOR_PAIR(10));
+        for x in 0..max_x {
+            window.mvaddch(status_y, x, ' ');
+        }
+        window.mvaddstr(
+            status_y,
+            0,
+            layout::truncate_to_width("Pushing…", max_x.max(0) as usize),
+        );
+        window.attroff(COLOR_PAIR(10));
+    } else if let Some(sync) = &state.remote_sync {
+        let status_y = max_y - 1;
+        window.attron(COLOR_PAIR(10));
+        for x in 0..max_x {
+            window.mvaddch(status_y, x, ' ');
+        }
+        window.mvaddstr(
+            status_y,
+            0,
+            layout::truncate_to_width(
+                &format!("{} {}", sync.operation.running_label(), sync.progress),
+                max_x.max(0) as usize,
+            ),
+        );
+        window.attroff(COLOR_PAIR(10));
+    } else if state.focused_pane == FocusedPane::Main
+        && state.prompt.is_none()
+        && let Some(scope) = commit_view::scope_completion(state)
+    {
+        let status_y = max_y - 1;
+        window.attron(COLOR_PAIR(8));
+        for x in 0..max_x {
+            window.mvaddch(status_y, x, ' ');
+        }
+        window.mvaddstr(
+            status_y,
+            0,
+            layout::truncate_to_width(
+                &format!("Tab completes the scope: {scope}"),
+                max_x.max(0) as usize,
+            ),
+        );
+        window.attroff(COLOR_PAIR(8));
+    } else if state.focused_pane == FocusedPane::Main
+        && state.prompt.is_none()
+        && let Some((severity, problems)) = commit_view::lint_summary(state)
+    {
+        let status_y = max_y - 1;
+        let pair = match severity {
+            commit_lint::Severity::Error => 6,
+            commit_lint::Severity::Warning => 8,
+        };
+        window.attron(COLOR_PAIR(pair));
+        for x in 0..max_x {
+            window.mvaddch(status_y, x, ' ');
+        }
+        window.mvaddstr(
+            status_y,
+            0,
+            layout::truncate_to_width(&problems, max_x.max(0) as usize),
+        );
+        window.attroff(COLOR_PAIR(pair));
+    } else if state.abbreviate_paths
+        && state.prompt.is_none()
+        && let Some(path) = state.selected_file_path()
+    {
+        let status_y = max_y - 1;
+        window.attron(COLOR_PAIR(5));
+        for x in 0..max_x {
+            window.mvaddch(status_y, x, ' ');
+        }
+        window.mvaddstr(
+            status_y,
+            0,
+            format!(
+                " {}",
+                layout::middle_ellipsis(&path, max_x.max(1) as usize - 1)
+            ),
+        );
+        window.attroff(COLOR_PAIR(5));
+    } else if state.focused_pane == FocusedPane::Main
+        && state.prompt.is_none()
+        && let Some(label) = selected_blame_label(state)
+    {
+        let status_y = max_y - 1;
+        window.attron(COLOR_PAIR(5));
+        for x in 0..max_x {
+            window.mvaddch(status_y, x, ' ');
+        }
+        window.mvaddstr(
+            status_y,
+            0,
+            layout::truncate_to_width(&format!(" {label}"), max_x.max(0) as usize),
+        );
+        window.attroff(COLOR_PAIR(5));
+    } else if state.focused_pane == FocusedPane::Main
+        && state.prompt.is_none()
+        && let Some(commit) = selected_commit(state)
+    {
+        let status_y = max_y - 1;
+        window.attron(COLOR_PAIR(5));
+        for x in 0..max_x {
+            window.mvaddch(status_y, x, ' ');
+        }
+        window.mvaddstr(
+            status_y,
+            0,
+            layout::truncate_to_width(
+                &format!(
+                    " {}  {}  Y: copy",
+                    commit.full_hash,
+                    push_label(commit.push)
+                ),
+                max_x.max(0) as usize,
+            ),
+        );
+        window.attroff(COLOR_PAIR(5));
+    } else if state.prompt.is_none() {
+        let status_y = max_y - 1;
+        window.attron(COLOR_PAIR(10));
+        for x in 0..max_x {
+            window.mvaddch(status_y, x, ' ');
+        }
+        window.mvaddstr(
+            status_y,
+            0,
+            layout::truncate_to_width(
+                &format!(
+                    " {}   {}",
+                    breadcrumb(state),
+                    branch_status_label(&state.branch_status)
+                ),
+                max_x.max(0) as usize,
+            ),
+        );
         window.attroff(COLOR_PAIR(10));
     }
+
+    if let Some(prompt) = &state.prompt {
+        let (prompt_y, prompt_x) = prompt::render(window, prompt, max_y, max_x);
+        window.mv(prompt_y, prompt_x);
+        #[cfg(not(test))]
+        pancurses::curs_set(1);
+    }
 }
 
 fn render_unstaged_pane(window: &Window, state: &AppState, max_y: i32, max_x: i32) -> usize {
     let (file_list_height, file_list_total_items) = state.unstaged_header_height(max_y);
     let is_focused = state.focused_pane == FocusedPane::Unstaged;
+    let names = display_names(
+        state,
+        state
+            .unstaged_pane
+            .unstaged_files
+            .iter()
+            .flat_map(|f| [f.file_name.as_str(), f.old_file_name.as_str()])
+            .chain(
+                state
+                    .unstaged_pane
+                    .untracked_files
+                    .iter()
+                    .map(String::as_str),
+            ),
+    );
 
     for i in 0..file_list_height {
         let item_index = state.unstaged_pane.scroll + i;
@@ -136,7 +371,11 @@ fn render_unstaged_pane(window: &Window, state: &AppState, max_y: i32, max_x: i3
                     }
                 }
                 window.mv(line_y, 0);
-                window.addstr(" Unstaged changes");
+                let header = match status_filter::header_note(state) {
+                    Some(note) => format!(" Unstaged changes ({note})"),
+                    None => " Unstaged changes".to_string(),
+                };
+                window.addstr(layout::truncate_to_width(&header, max_x as usize));
                 window.attroff(COLOR_PAIR(pair));
             }
             UnstagedListItem::File(file) => {
@@ -152,27 +391,46 @@ fn render_unstaged_pane(window: &Window, state: &AppState, max_y: i32, max_x: i3
                 window.mv(line_y, 0);
                 window.attroff(COLOR_PAIR(pair));
 
-                let status_char = match file.status {
-                    FileStatus::Added => 'A',
-                    FileStatus::Modified => 'M',
-                    FileStatus::Renamed => 'R',
-                    FileStatus::Deleted => 'D',
-                };
+                let row = file_row_for(file, &names, max_x);
+                render_file_row(window, &row, pair, status_pair);
+                render_textconv_tag(window, state, &file.file_name, &row, line_y, max_x, pair);
+                dim_never_commit_row(window, state, &file.file_name, &row, line_y, pair);
+                mark_bookmark(window, state, &file.file_name, line_y, pair);
+            }
+            UnstagedListItem::RenameGroup(group) => {
+                let pair = if is_selected { 5 } else { 1 };
+                let status_pair = if is_selected { 6 } else { 2 };
                 window.attron(COLOR_PAIR(pair));
-                window.addstr("   ");
+                if is_selected {
+                    for x in 0..max_x {
+                        window.mvaddch(line_y, x, ' ');
+                    }
+                }
+                window.mv(line_y, 0);
                 window.attroff(COLOR_PAIR(pair));
-                window.attron(COLOR_PAIR(status_pair));
-                window.addstr(format!("{status_char}"));
-                window.attroff(COLOR_PAIR(status_pair));
+                render_file_row(
+                    window,
+                    &layout::file_row('R', &group.label(), None, max_x),
+                    pair,
+                    status_pair,
+                );
+            }
+            UnstagedListItem::UntrackedFilesHeader => {
+                let pair = if is_selected { 5 } else { 1 };
                 window.attron(COLOR_PAIR(pair));
-                if file.status == FileStatus::Renamed {
-                    window.addstr(format!(" {} -> {}", file.old_file_name, file.file_name));
-                } else {
-                    window.addstr(format!(" {}", file.file_name));
+                if is_selected {
+                    for x in 0..max_x {
+                        window.mvaddch(line_y, x, ' ');
+                    }
                 }
+                window.mv(line_y, 0);
+                window.addstr(layout::truncate_to_width(
+                    " Untracked files",
+                    max_x as usize,
+                ));
                 window.attroff(COLOR_PAIR(pair));
             }
-            UnstagedListItem::UntrackedFilesHeader => {
+            UnstagedListItem::LargeFilesHeader => {
                 let pair = if is_selected { 5 } else { 1 };
                 window.attron(COLOR_PAIR(pair));
                 if is_selected {
@@ -181,7 +439,13 @@ fn render_unstaged_pane(window: &Window, state: &AppState, max_y: i32, max_x: i3
                     }
                 }
                 window.mv(line_y, 0);
-                window.addstr(" Untracked files");
+                window.addstr(layout::truncate_to_width(
+                    &format!(
+                        " Too large to stage automatically (over {})",
+                        format_size(state.max_file_size())
+                    ),
+                    max_x as usize,
+                ));
                 window.attroff(COLOR_PAIR(pair));
             }
             UnstagedListItem::UntrackedFile(file_name) => {
@@ -193,14 +457,357 @@ fn render_unstaged_pane(window: &Window, state: &AppState, max_y: i32, max_x: i3
                     }
                 }
                 window.mv(line_y, 0);
-                window.addstr(format!("    ? {file_name}"));
                 window.attroff(COLOR_PAIR(pair));
+                let size = state
+                    .unstaged_pane
+                    .large_files
+                    .iter()
+                    .find(|(name, _)| name == file_name)
+                    .map(|(_, size)| *size);
+                let name = match size {
+                    Some(size) => {
+                        format!(
+                            "{} ({})",
+                            display_name(&names, file_name),
+                            format_size(size)
+                        )
+                    }
+                    None => display_name(&names, file_name).to_string(),
+                };
+                let row = layout::untracked_file_row(&name, max_x);
+                render_file_row(window, &row, pair, pair);
+                dim_never_commit_row(window, state, file_name, &row, line_y, pair);
+                mark_bookmark(window, state, file_name, line_y, pair);
             }
         }
     }
     file_list_height
 }
 
+/// Abbreviated names for the paths of one pane, or `None` when abbreviation
+/// is turned off.
+fn display_names<'a>(
+    state: &AppState,
+    paths: impl Iterator<Item = &'a str>,
+) -> Option<HashMap<String, String>> {
+    state
+        .abbreviate_paths
+        .then(|| layout::abbreviate_paths(paths.filter(|path| !path.is_empty())))
+}
+
+fn display_name<'a>(names: &'a Option<HashMap<String, String>>, path: &'a str) -> &'a str {
+    names
+        .as_ref()
+        .and_then(|names| names.get(path))
+        .map_or(path, String::as_str)
+}
+
+fn file_row_for(
+    file: &FileDiff,
+    names: &Option<HashMap<String, String>>,
+    max_x: i32,
+) -> layout::FileRow {
+    let (status, name, old_name) = file_row_parts(file, names);
+    layout::file_row(status, name, old_name, max_x)
+}
+
+fn nested_file_row_for(
+    file: &FileDiff,
+    names: &Option<HashMap<String, String>>,
+    max_x: i32,
+) -> layout::FileRow {
+    let (status, name, old_name) = file_row_parts(file, names);
+    layout::nested_file_row(status, name, old_name, max_x)
+}
+
+fn file_row_parts<'a>(
+    file: &'a FileDiff,
+    names: &'a Option<HashMap<String, String>>,
+) -> (char, &'a str, Option<&'a str>) {
+    let status_char = match file.status {
+        FileStatus::Added => 'A',
+        FileStatus::Modified => 'M',
+        FileStatus::Renamed => 'R',
+        FileStatus::Deleted => 'D',
+    };
+    let old_name =
+        (file.status == FileStatus::Renamed).then_some(display_name(names, &file.old_file_name));
+    (status_char, display_name(names, &file.file_name), old_name)
+}
+
+/// Draws a file row at the current cursor position.
+fn render_file_row(window: &Window, row: &layout::FileRow, pair: u32, status_pair: u32) {
+    window.attron(COLOR_PAIR(pair));
+    window.addstr(row.indent);
+    window.attroff(COLOR_PAIR(pair));
+    if let Some(status) = row.status {
+        window.attron(COLOR_PAIR(status_pair));
+        window.addstr(status.to_string());
+        window.attroff(COLOR_PAIR(status_pair));
+        window.attron(COLOR_PAIR(pair));
+        window.addstr(" ");
+        window.attroff(COLOR_PAIR(pair));
+    }
+    window.attron(COLOR_PAIR(pair));
+    window.addstr(&row.name);
+    window.attroff(COLOR_PAIR(pair));
+}
+
+/// Shown at the end of the rows of files diffed through a textconv driver.
+const TEXTCONV_TAG: &str = "textconv ";
+
+/// Marks a file row on `line_y` as shown through a textconv driver, if
+/// there is room left after its name.
+fn render_textconv_tag(
+    window: &Window,
+    state: &AppState,
+    file_name: &str,
+    row: &layout::FileRow,
+    line_y: i32,
+    max_x: i32,
+    pair: u32,
+) {
+    let tag_width = TEXTCONV_TAG.len();
+    if !state.textconv_files.contains(file_name) || row.width() + tag_width + 1 > max_x as usize {
+        return;
+    }
+    window.attron(COLOR_PAIR(pair) | A_DIM);
+    window.mvaddstr(line_y, max_x - tag_width as i32, TEXTCONV_TAG);
+    window.attroff(COLOR_PAIR(pair) | A_DIM);
+}
+
+/// Shown at the end of the rows of staged files that add trailing
+/// whitespace, before the textconv tag.
+const WHITESPACE_TAG: &str = "trailing whitespace ";
+
+/// Warns on a staged file row on `line_y` that committing it adds trailing
+/// whitespace, if there is room left after its name.
+fn render_whitespace_tag(
+    window: &Window,
+    state: &AppState,
+    file: &FileDiff,
+    row: &layout::FileRow,
+    line_y: i32,
+    max_x: i32,
+    is_selected: bool,
+) {
+    let after = if state.textconv_files.contains(&file.file_name) {
+        TEXTCONV_TAG.len()
+    } else {
+        0
+    };
+    let tag_width = WHITESPACE_TAG.len() + after;
+    if row.width() + tag_width + 1 > max_x as usize || !whitespace::adds_trailing_whitespace(file) {
+        return;
+    }
+    let pair = if is_selected { 17 } else { 18 };
+    window.attron(COLOR_PAIR(pair));
+    window.mvaddstr(line_y, max_x - tag_width as i32, WHITESPACE_TAG);
+    window.attroff(COLOR_PAIR(pair));
+}
+
+/// Marks a bookmarked file with a `*` in the first column of its row.
+fn mark_bookmark(window: &Window, state: &AppState, file_name: &str, line_y: i32, pair: u32) {
+    if state.is_bookmarked(file_name) {
+        let mark_pair = if pair == 5 { 8 } else { 4 };
+        window.attron(COLOR_PAIR(mark_pair) | A_BOLD);
+        window.mvaddch(line_y, 0, '*');
+        window.attroff(COLOR_PAIR(mark_pair) | A_BOLD);
+    }
+}
+
+/// Dims a file row on `line_y` when a never-commit rule matches the file.
+fn dim_never_commit_row(
+    window: &Window,
+    state: &AppState,
+    file_name: &str,
+    row: &layout::FileRow,
+    line_y: i32,
+    pair: u32,
+) {
+    if state.never_commit.matches_file(file_name) {
+        window.mvchgat(line_y, 0, row.width() as i32, A_DIM, pair as i16);
+    }
+}
+
+/// Shown at the end of the rows of signed commits.
+const SIGNED_TAG: &str = "signed ";
+
+/// Marks a commit row on `line_y` as signed, if there is room left after
+/// its subject.
+/// The hash shown before the subject of a commit of the log, with a space
+/// after it, or nothing.
+fn hash_label(state: &AppState, hash: &str) -> String {
+    match state.main_screen.commit_hashes {
+        HashDisplay::Hidden => String::new(),
+        HashDisplay::Short => format!("{hash} "),
+        HashDisplay::Full => state
+            .previous_commits
+            .iter()
+            .find(|commit| commit.hash == hash)
+            .map_or_else(
+                || format!("{hash} "),
+                |commit| format!("{} ", commit.full_hash),
+            ),
+    }
+}
+
+/// The commit selected in the main pane, whether or not its diff is being
+/// navigated.
+pub fn selected_commit(state: &AppState) -> Option<&CommitInfo> {
+    match state.current_main_item() {
+        Some(ListItem::PreviousCommitInfo { hash, .. }) => state
+            .previous_commits
+            .iter()
+            .find(|commit| &commit.hash == hash),
+        _ => None,
+    }
+}
+
+/// The paths both commits change, in the order of the first one.
+pub fn shared_files(state: &AppState, hash: &str, other: &str) -> Vec<String> {
+    let files = |hash: &str| {
+        state
+            .previous_commits
+            .iter()
+            .find(|commit| commit.hash == hash)
+            .map(|commit| commit.files.as_slice())
+            .unwrap_or_default()
+    };
+    let other_files = files(other);
+    files(hash)
+        .iter()
+        .filter(|path| other_files.contains(path))
+        .cloned()
+        .collect()
+}
+
+/// What a commit of the log has in common with the selected one, e.g.
+/// `⇅ src/app.rs +2`, or `None` if they change different files.
+pub fn overlap_tag(state: &AppState, hash: &str) -> Option<String> {
+    let Some(ListItem::PreviousCommitInfo { hash: selected, .. }) = state.current_main_item()
+    else {
+        return None;
+    };
+    if selected == hash {
+        return None;
+    }
+    let shared = shared_files(state, selected, hash);
+    let first = shared.first()?;
+    Some(match shared.len() {
+        1 => format!("⇅ {first} "),
+        count => format!("⇅ {first} +{} ", count - 1),
+    })
+}
+
+/// Marks a commit changing the same files as the selected one, so moving
+/// them past each other is seen to risk a conflict. Returns whether it was
+/// drawn.
+fn render_overlap_tag(
+    window: &Window,
+    state: &AppState,
+    hash: &str,
+    message: &str,
+    line_y: i32,
+    max_x: i32,
+    pair: u32,
+) -> bool {
+    use unicode_width::UnicodeWidthStr;
+    let Some(tag) = overlap_tag(state, hash) else {
+        return false;
+    };
+    let tag_width = tag.width();
+    if " ● ".width() + message.width() + tag_width + 1 > max_x as usize {
+        return false;
+    }
+    window.attron(COLOR_PAIR(pair) | A_DIM);
+    window.mvaddstr(line_y, max_x - tag_width as i32, &tag);
+    window.attroff(COLOR_PAIR(pair) | A_DIM);
+    true
+}
+
+/// What the next push does with a commit, for the bottom line.
+pub fn push_label(push: PushStatus) -> &'static str {
+    match push {
+        PushStatus::Pushed => "pushed",
+        PushStatus::WillPush => "will be pushed",
+        PushStatus::NeedsForce => "needs a force push, the upstream has other commits",
+    }
+}
+
+/// Who last touched the lines the selected staged file changes, once it
+/// was read.
+pub fn selected_blame_label(state: &AppState) -> Option<String> {
+    let key = state.selected_blame_key()?;
+    blame_summary::label(state.blame_summaries.get(&key)?.as_ref()?)
+}
+
+/// Where the cursor is, `repo ▸ Unstaged ▸ src/git.rs`. The main screen
+/// keeps every row for its panes, so it goes on the bottom line.
+pub fn breadcrumb(state: &AppState) -> String {
+    let path = state.selected_file_path().unwrap_or_default();
+    let (place, detail) = match state.focused_pane {
+        FocusedPane::Unstaged => ("Unstaged", path.as_str()),
+        FocusedPane::Main => match state.current_main_item() {
+            Some(ListItem::CommitMessageInput) => ("Commit message", ""),
+            Some(ListItem::PreviousCommitInfo { hash, .. }) => ("Commits", hash.as_str()),
+            Some(ListItem::AmendingCommitMessageInput { hash, .. }) => ("Amend", hash.as_str()),
+            Some(ListItem::EditingReorderCommit { .. }) => ("Reorder", ""),
+            _ => ("Staged", path.as_str()),
+        },
+    };
+    chrome::breadcrumb(&chrome::repo_name(&state.repo_path), &[place, detail])
+}
+
+/// The branch, its upstream and how far apart they are, for the bottom line
+/// when it has nothing else to show.
+pub fn branch_status_label(status: &BranchStatus) -> String {
+    let branch = status.branch.as_deref().unwrap_or("detached HEAD");
+    let Some(upstream) = &status.upstream else {
+        return format!("{branch}  no upstream");
+    };
+    let distance = match (status.ahead, status.behind) {
+        (0, 0) => "up to date".to_string(),
+        (ahead, 0) => format!("{ahead} ahead"),
+        (0, behind) => format!("{behind} behind"),
+        (ahead, behind) => format!("{ahead} ahead, {behind} behind"),
+    };
+    format!("{branch} → {upstream}  {distance}")
+}
+
+/// The status indicator of a commit row, with its color pair unselected
+/// and selected.
+fn push_indicator(push: PushStatus) -> (&'static str, u32, u32) {
+    match push {
+        PushStatus::Pushed => (" ● ", 4, 8),
+        PushStatus::WillPush => (" ↑ ", 3, 7),
+        PushStatus::NeedsForce => (" ! ", 2, 6),
+    }
+}
+
+fn render_signed_tag(
+    window: &Window,
+    state: &AppState,
+    hash: &str,
+    message: &str,
+    line_y: i32,
+    max_x: i32,
+    pair: u32,
+) {
+    use unicode_width::UnicodeWidthStr;
+    let is_signed = state
+        .previous_commits
+        .iter()
+        .any(|commit| commit.hash == hash && commit.is_signed);
+    let tag_width = SIGNED_TAG.len();
+    if !is_signed || " ● ".width() + message.width() + tag_width + 1 > max_x as usize {
+        return;
+    }
+    window.attron(COLOR_PAIR(pair) | A_DIM);
+    window.mvaddstr(line_y, max_x - tag_width as i32, SIGNED_TAG);
+    window.attroff(COLOR_PAIR(pair) | A_DIM);
+}
+
 fn render_main_pane(
     window: &Window,
     state: &AppState,
@@ -213,6 +820,28 @@ fn render_main_pane(
     let is_focused = state.focused_pane == FocusedPane::Main;
     let mut carret_y = 0;
     let mut carret_x = 0;
+    let names = display_names(
+        state,
+        state
+            .files
+            .iter()
+            .flat_map(|f| [f.file_name.as_str(), f.old_file_name.as_str()]),
+    );
+    // Files listed below an expanded directory rename are indented.
+    let nested_files: HashSet<&str> = state
+        .main_screen
+        .list_items
+        .iter()
+        .filter_map(|item| match item {
+            ListItem::RenameGroup {
+                files,
+                expanded: true,
+                ..
+            } => Some(files.iter().map(|f| f.file_name.as_str())),
+            _ => None,
+        })
+        .flatten()
+        .collect();
 
     for i in 0..file_list_height {
         let item_index = state.main_screen.file_list_scroll + i;
@@ -234,7 +863,17 @@ fn render_main_pane(
                     }
                 }
                 window.mv(line_y, 0);
-                window.addstr(" Staged changes");
+                let notes: Vec<String> = (state.main_screen.file_list_order == FileListOrder::Tree)
+                    .then(|| "tree order".to_string())
+                    .into_iter()
+                    .chain(status_filter::header_note(state))
+                    .collect();
+                let header = if notes.is_empty() {
+                    " Staged changes".to_string()
+                } else {
+                    format!(" Staged changes ({})", notes.join(", "))
+                };
+                window.addstr(layout::truncate_to_width(&header, max_x as usize));
                 window.attroff(COLOR_PAIR(pair));
             }
             ListItem::File(file) => {
@@ -250,25 +889,40 @@ fn render_main_pane(
                 window.mv(line_y, 0);
                 window.attroff(COLOR_PAIR(pair));
 
-                let status_char = match file.status {
-                    FileStatus::Added => 'A',
-                    FileStatus::Modified => 'M',
-                    FileStatus::Renamed => 'R',
-                    FileStatus::Deleted => 'D',
+                let row = if nested_files.contains(file.file_name.as_str()) {
+                    nested_file_row_for(file, &names, max_x)
+                } else {
+                    file_row_for(file, &names, max_x)
                 };
+                render_file_row(window, &row, pair, status_pair);
+                render_textconv_tag(window, state, &file.file_name, &row, line_y, max_x, pair);
+                render_whitespace_tag(window, state, file, &row, line_y, max_x, is_selected);
+                dim_never_commit_row(window, state, &file.file_name, &row, line_y, pair);
+                mark_bookmark(window, state, &file.file_name, line_y, pair);
+            }
+            ListItem::RenameGroup {
+                group, expanded, ..
+            } => {
+                let pair = if is_selected { 5 } else { 1 };
+                let status_pair = if is_selected { 6 } else { 2 };
+
                 window.attron(COLOR_PAIR(pair));
-                window.addstr("   ");
-                window.attroff(COLOR_PAIR(pair));
-                window.attron(COLOR_PAIR(status_pair));
-                window.addstr(format!("{status_char}"));
-                window.attroff(COLOR_PAIR(status_pair));
-                window.attron(COLOR_PAIR(pair));
-                if file.status == FileStatus::Renamed {
-                    window.addstr(format!(" {} -> {}", file.old_file_name, file.file_name));
-                } else {
-                    window.addstr(format!(" {}", file.file_name));
+                if is_selected {
+                    for x in 0..max_x {
+                        window.mvaddch(line_y, x, ' ');
+                    }
                 }
+                window.mv(line_y, 0);
                 window.attroff(COLOR_PAIR(pair));
+
+                let marker = if *expanded { '▾' } else { '▸' };
+                let label = format!("{marker} {}", group.label());
+                render_file_row(
+                    window,
+                    &layout::file_row('R', &label, None, max_x),
+                    pair,
+                    status_pair,
+                );
             }
             ListItem::CommitMessageInput => {
                 if state.main_screen.amending_commit_hash.is_none() {
@@ -284,7 +938,7 @@ fn render_main_pane(
                 }
             }
             ListItem::PreviousCommitInfo {
-                hash: _,
+                hash,
                 message,
                 is_on_remote,
                 is_fixup,
@@ -299,23 +953,37 @@ fn render_main_pane(
                 window.attroff(COLOR_PAIR(pair));
 
                 window.mv(line_y, 0);
-                let status_pair = if *is_on_remote {
-                    if is_selected { 8 } else { 4 }
-                } else if is_selected {
-                    7
+                let push = state
+                    .previous_commits
+                    .iter()
+                    .find(|commit| commit.hash == *hash)
+                    .map(|commit| commit.push)
+                    .unwrap_or(if *is_on_remote {
+                        PushStatus::Pushed
+                    } else {
+                        PushStatus::WillPush
+                    });
+                let (indicator, pair_unselected, pair_selected) = push_indicator(push);
+                let status_pair = if is_selected {
+                    pair_selected
                 } else {
-                    3
+                    pair_unselected
                 };
                 window.attron(COLOR_PAIR(status_pair));
-                window.addstr(" ● ");
+                window.addstr(indicator);
                 window.attroff(COLOR_PAIR(status_pair));
 
+                let hash_label = hash_label(state, hash);
+                window.attron(COLOR_PAIR(pair) | A_DIM);
+                window.addstr(&hash_label);
+                window.attroff(COLOR_PAIR(pair) | A_DIM);
+
                 window.attron(COLOR_PAIR(pair));
                 if *is_fixup {
                     window.addstr("fixup!");
                 } else {
                     use unicode_width::UnicodeWidthStr;
-                    let prefix_width = " ● ".width();
+                    let prefix_width = " ● ".width() + hash_label.len();
                     let available_width = (max_x as usize).saturating_sub(prefix_width);
                     let mut truncated_message = String::new();
                     let mut current_width = 0;
@@ -330,6 +998,10 @@ fn render_main_pane(
                     window.addstr(&truncated_message);
                 }
                 window.attroff(COLOR_PAIR(pair));
+                let shown = format!("{hash_label}{message}");
+                if !render_overlap_tag(window, state, hash, &shown, line_y, max_x, pair) {
+                    render_signed_tag(window, state, hash, &shown, line_y, max_x, pair);
+                }
             }
             ListItem::AmendingCommitMessageInput { .. } => {
                 (carret_x, carret_y) =
@@ -359,6 +1031,97 @@ fn render_main_pane(
     (carret_y, carret_x)
 }
 
+/// What staging a directory moved without `git mv` would record.
+pub fn pending_rename_lines(group: &RenameGroup) -> Vec<String> {
+    group
+        .renames
+        .iter()
+        .map(|(old, new)| format!(" rename {old} → {new}"))
+        .collect()
+}
+
+/// What to do next when an empty section is selected, so a first look at
+/// a clean or fresh repository is not just blank panes. The keys follow the
+/// `[keys]` config.
+pub fn empty_state_hints(state: &AppState) -> Vec<String> {
+    let keymap = &state.keymap;
+    let mut hints = Vec::new();
+    match state.focused_pane {
+        FocusedPane::Main => {
+            if !matches!(
+                state.current_main_item(),
+                Some(ListItem::StagedChangesHeader)
+            ) {
+                return hints;
+            }
+            if state.files.is_empty() {
+                hints.push("Nothing is staged yet.".to_string());
+                if state.main_screen.has_unstaged_changes {
+                    hints.push(format!(
+                        "Press {} to view unstaged changes, or {} to stage everything.",
+                        keymap.key_label("switch_pane"),
+                        keymap.key_label("stage_all")
+                    ));
+                } else {
+                    hints
+                        .push("The working tree is clean, there is nothing to commit.".to_string());
+                }
+            }
+            if state.previous_commits.is_empty() {
+                hints.push("There are no commits yet, the next one is the first.".to_string());
+            } else if state
+                .previous_commits
+                .iter()
+                .all(|commit| commit.is_on_remote)
+            {
+                hints.push(
+                    "There are no local commits, the ones listed are pushed already.".to_string(),
+                );
+            }
+        }
+        FocusedPane::Unstaged => {
+            let items = &state.unstaged_pane.list_items;
+            let has_tracked_changes = items.iter().any(|item| {
+                matches!(
+                    item,
+                    UnstagedListItem::File(_) | UnstagedListItem::RenameGroup(_)
+                )
+            });
+            if matches!(
+                items.get(state.unstaged_pane.cursor),
+                Some(UnstagedListItem::UnstagedChangesHeader)
+            ) && !has_tracked_changes
+            {
+                hints.push("No tracked file has unstaged changes.".to_string());
+                hints.push(format!(
+                    "Press {} to pick an untracked file, or {} to stage everything.",
+                    keymap.key_label("move_down"),
+                    keymap.key_label("stage_all")
+                ));
+            }
+        }
+    }
+    hints
+}
+
+fn render_empty_state_hints(window: &Window, state: &AppState, content_height: usize, top: usize) {
+    let (_, max_x) = window.get_max_yx();
+    let width = (max_x.max(0) as usize).saturating_sub(2);
+    window.attron(A_DIM);
+    for (row, hint) in empty_state_hints(state)
+        .iter()
+        .take(content_height.saturating_sub(1))
+        .enumerate()
+    {
+        window.mvaddstr(
+            (top + 1 + row) as i32,
+            2,
+            layout::truncate_to_width(hint, width),
+        );
+    }
+    window.attroff(A_DIM);
+}
+
 fn render_diff_view(window: &Window, state: &AppState, max_y: i32, top_offset: usize) {
     let content_height = (max_y as usize).saturating_sub(top_offset);
 
@@ -367,12 +1130,13 @@ fn render_diff_view(window: &Window, state: &AppState, max_y: i32, top_offset: u
             let cursor_position = state.get_cursor_line_index();
             match state.current_main_item() {
                 Some(ListItem::StagedChangesHeader) => {
-                    // "Staged changes" is selected, do nothing for now.
+                    render_empty_state_hints(window, state, content_height, top_offset);
                 }
-                Some(ListItem::PreviousCommitInfo { .. }) => {
-                    diff_view::render_multiple(
+                Some(ListItem::PreviousCommitInfo { .. }) if state.main_screen.commit_word_diff => {
+                    diff_view::render_word_diff(
                         window,
-                        &state.selected_commit_files,
+                        &commit_folds::shown_files(state),
+                        &commit_folds::shown_word_spans(state),
                         content_height,
                         state.main_screen.diff_scroll,
                         state.main_screen.horizontal_scroll,
@@ -381,10 +1145,10 @@ fn render_diff_view(window: &Window, state: &AppState, max_y: i32, top_offset: u
                         state.main_screen.is_diff_cursor_active,
                     );
                 }
-                Some(ListItem::File(selected_file)) => {
-                    diff_view::render(
+                Some(ListItem::PreviousCommitInfo { .. }) => {
+                    diff_view::render_multiple(
                         window,
-                        selected_file,
+                        &commit_folds::shown_files(state),
                         content_height,
                         state.main_screen.diff_scroll,
                         state.main_screen.horizontal_scroll,
@@ -393,46 +1157,79 @@ fn render_diff_view(window: &Window, state: &AppState, max_y: i32, top_offset: u
                         state.main_screen.is_diff_cursor_active,
                     );
                 }
-                _ => {}
-            }
-        }
-        FocusedPane::Unstaged => {
-            let cursor_position = state.main_screen.line_cursor;
-            match state
-                .unstaged_pane
-                .list_items
-                .get(state.unstaged_pane.cursor)
-            {
-                Some(UnstagedListItem::File(selected_file)) => {
-                    diff_view::render(
+                Some(ListItem::File(_)) if other_side_file(state).is_some() => {
+                    render_other_side(window, state, content_height, top_offset);
+                }
+                Some(ListItem::File(selected_file)) if is_deleted_without_hunks(selected_file) => {
+                    diff_view::render_plain(
                         window,
-                        selected_file,
+                        deleted_file_lines(state, selected_file),
                         content_height,
-                        state.unstaged_pane.diff_scroll,
-                        state.unstaged_pane.horizontal_scroll,
+                        state.main_screen.diff_scroll,
+                        state.main_screen.horizontal_scroll,
                         top_offset,
                         cursor_position,
-                        state.unstaged_pane.is_diff_cursor_active,
+                        state.main_screen.is_diff_cursor_active,
                     );
                 }
-                Some(UnstagedListItem::UntrackedFile(file_name)) => {
-                    let lines = match git::read_file_content(&state.repo_path, file_name) {
-                        Ok((content, size)) => {
-                            if is_binary(&content) {
-                                vec![format!("  Binary file (size: {} bytes)", size)]
-                            } else {
-                                String::from_utf8_lossy(&content)
-                                    .lines()
-                                    .map(|l| format!(" {l}"))
-                                    .collect()
-                            }
-                        }
-                        Err(e) => vec![format!("  Error reading file: {}", e)],
-                    };
-
+                Some(ListItem::File(selected_file)) => {
+                    diff_view::render(
+                        window,
+                        &state.displayed_diff(selected_file),
+                        content_height,
+                        state.main_screen.diff_scroll,
+                        state.main_screen.horizontal_scroll,
+                        top_offset,
+                        cursor_position,
+                        state.main_screen.is_diff_cursor_active,
+                    );
+                }
+                Some(ListItem::RenameGroup { files, .. }) => {
+                    diff_view::render_multiple(
+                        window,
+                        files,
+                        content_height,
+                        state.main_screen.diff_scroll,
+                        state.main_screen.horizontal_scroll,
+                        top_offset,
+                        cursor_position,
+                        state.main_screen.is_diff_cursor_active,
+                    );
+                }
+                Some(
+                    ListItem::CommitMessageInput | ListItem::AmendingCommitMessageInput { .. },
+                ) => {
+                    commit_view::render_message(window, state, top_offset, content_height);
+                }
+                _ => {}
+            }
+        }
+        FocusedPane::Unstaged => {
+            let cursor_position = state.main_screen.line_cursor;
+            match state
+                .unstaged_pane
+                .list_items
+                .get(state.unstaged_pane.cursor)
+            {
+                Some(UnstagedListItem::File(_)) if other_side_file(state).is_some() => {
+                    render_other_side(window, state, content_height, top_offset);
+                }
+                Some(UnstagedListItem::File(selected_file)) => {
+                    diff_view::render(
+                        window,
+                        &state.displayed_diff(selected_file),
+                        content_height,
+                        state.unstaged_pane.diff_scroll,
+                        state.unstaged_pane.horizontal_scroll,
+                        top_offset,
+                        cursor_position,
+                        state.unstaged_pane.is_diff_cursor_active,
+                    );
+                }
+                Some(UnstagedListItem::UntrackedFile(file_name)) => {
                     diff_view::render_plain(
                         window,
-                        lines,
+                        untracked_file_lines(state, file_name),
                         content_height,
                         state.unstaged_pane.diff_scroll,
                         state.unstaged_pane.horizontal_scroll,
@@ -441,12 +1238,491 @@ fn render_diff_view(window: &Window, state: &AppState, max_y: i32, top_offset: u
                         state.unstaged_pane.is_diff_cursor_active,
                     );
                 }
-                _ => {}
+                Some(UnstagedListItem::RenameGroup(group)) => {
+                    diff_view::render_plain(
+                        window,
+                        pending_rename_lines(group),
+                        content_height,
+                        state.unstaged_pane.diff_scroll,
+                        state.unstaged_pane.horizontal_scroll,
+                        top_offset,
+                        cursor_position,
+                        state.unstaged_pane.is_diff_cursor_active,
+                    );
+                }
+                _ => render_empty_state_hints(window, state, content_height, top_offset),
+            }
+        }
+    }
+
+    // Both are laid out over the lines of the selected side.
+    if other_side_file(state).is_none() {
+        render_dimmed_lines(window, state, content_height, top_offset);
+        render_whitespace_marks(window, state, content_height, top_offset);
+        render_search_matches(window, state, content_height, top_offset);
+        render_hunk_numbers(window, state, content_height, top_offset);
+        render_overlap_markers(window, state, content_height, top_offset);
+    }
+
+    if state.focused_pane == FocusedPane::Main
+        && let Some(commit) = commit_details::selected_commit(state)
+    {
+        commit_details::render(window, commit, top_offset as i32);
+    }
+}
+
+/// Dims the lines of the file diff drawn by `render_diff_view` that a
+/// never-commit rule matches, and the removed lines of the full file view.
+fn render_dimmed_lines(
+    window: &Window,
+    state: &AppState,
+    content_height: usize,
+    top_offset: usize,
+) {
+    if state.never_commit.is_empty() && !state.full_file_view {
+        return;
+    }
+    let (file, scroll, is_diff_cursor_active) = match state.focused_pane {
+        FocusedPane::Main => match state.current_main_item() {
+            Some(ListItem::File(file)) => (
+                file,
+                state.main_screen.diff_scroll,
+                state.main_screen.is_diff_cursor_active,
+            ),
+            _ => return,
+        },
+        FocusedPane::Unstaged => match state
+            .unstaged_pane
+            .list_items
+            .get(state.unstaged_pane.cursor)
+        {
+            Some(UnstagedListItem::File(file)) => (
+                file,
+                state.unstaged_pane.diff_scroll,
+                state.unstaged_pane.is_diff_cursor_active,
+            ),
+            _ => return,
+        },
+    };
+    diff_view::dim_lines(
+        window,
+        &state.displayed_diff(file).lines,
+        |line| {
+            state.never_commit.matches_line(line)
+                || (state.full_file_view && line.starts_with('-') && !line.starts_with("--- "))
+        },
+        content_height,
+        scroll,
+        top_offset,
+        state.main_screen.line_cursor,
+        is_diff_cursor_active,
+    );
+}
+
+/// Numbers the hunk headers of the selected file in the line number
+/// columns, as `:unstage` and `:stage` take them.
+fn render_hunk_numbers(
+    window: &Window,
+    state: &AppState,
+    content_height: usize,
+    top_offset: usize,
+) {
+    let (file, scroll, is_diff_cursor_active) = match state.focused_pane {
+        FocusedPane::Main => (
+            state.current_main_file(),
+            state.main_screen.diff_scroll,
+            state.main_screen.is_diff_cursor_active,
+        ),
+        FocusedPane::Unstaged => (
+            state.get_unstaged_file(),
+            state.unstaged_pane.diff_scroll,
+            state.unstaged_pane.is_diff_cursor_active,
+        ),
+    };
+    let Some(file) = file.filter(|file| file.binary.is_none()) else {
+        return;
+    };
+    for (number, hunk) in file.hunks.iter().enumerate() {
+        let index = hunk.start_line;
+        if index < scroll || index >= scroll + content_height {
+            continue;
+        }
+        // The grey pairs `render_line` draws the line numbers with.
+        let pair = match (
+            index == state.main_screen.line_cursor,
+            is_diff_cursor_active,
+        ) {
+            (true, true) => 10,
+            (true, false) => 15,
+            (false, _) => 9,
+        };
+        window.attron(COLOR_PAIR(pair));
+        window.mvaddstr(
+            (top_offset + index - scroll) as i32,
+            1,
+            format!("{:>8}", format!("hunk {}", number + 1)),
+        );
+        window.attroff(COLOR_PAIR(pair));
+    }
+}
+
+/// Told on the header of a staged hunk that unstaged edits overlap.
+const OVERLAP_NOTE: &str = "overlaps unstaged edits, unstage/discard may surprise";
+
+/// Marks the headers of the staged hunks that unstaged edits of the same
+/// file overlap, with `!` and a note as far as the header leaves room.
+fn render_overlap_markers(
+    window: &Window,
+    state: &AppState,
+    content_height: usize,
+    top_offset: usize,
+) {
+    if state.focused_pane != FocusedPane::Main {
+        return;
+    }
+    let Some(file) = state.current_main_file() else {
+        return;
+    };
+    let Some(unstaged) = state
+        .unstaged_pane
+        .unstaged_files
+        .iter()
+        .find(|unstaged| unstaged.file_name == file.file_name)
+    else {
+        return;
+    };
+    use unicode_width::UnicodeWidthStr;
+
+    let scroll = state.main_screen.diff_scroll;
+    let max_x = window.get_max_x().max(0) as usize;
+    for hunk in hunk_overlap::overlapping_hunks(file, unstaged) {
+        let index = hunk.start_line;
+        if index < scroll || index >= scroll + content_height {
+            continue;
+        }
+        let y = (top_offset + index - scroll) as i32;
+        let pair =
+            if index == state.main_screen.line_cursor && state.main_screen.is_diff_cursor_active {
+                17
+            } else {
+                18
+            };
+        window.attron(COLOR_PAIR(pair) | A_BOLD);
+        window.mvaddstr(y, 0, "!");
+        let header_end = LINE_CONTENT_OFFSET
+            + UnicodeWidthStr::width(hunk.lines[0].as_str())
+                .saturating_sub(state.main_screen.horizontal_scroll);
+        let note_width = OVERLAP_NOTE.len() + 2;
+        if header_end + note_width < max_x {
+            window.mvaddstr(y, (max_x - note_width) as i32, format!("! {OVERLAP_NOTE}"));
+        }
+        window.attroff(COLOR_PAIR(pair) | A_BOLD);
+    }
+}
+
+/// Highlights the whitespace problems over the diff drawn by
+/// `render_diff_view`, while `_` shows them. Word diffs are left as is.
+fn render_whitespace_marks(
+    window: &Window,
+    state: &AppState,
+    content_height: usize,
+    top_offset: usize,
+) {
+    if !state.show_whitespace {
+        return;
+    }
+    let (scroll, horizontal_scroll, is_diff_cursor_active) = match state.focused_pane {
+        FocusedPane::Main => (
+            state.main_screen.diff_scroll,
+            state.main_screen.horizontal_scroll,
+            state.main_screen.is_diff_cursor_active,
+        ),
+        FocusedPane::Unstaged => (
+            state.unstaged_pane.diff_scroll,
+            state.unstaged_pane.horizontal_scroll,
+            state.unstaged_pane.is_diff_cursor_active,
+        ),
+    };
+    if state.focused_pane == FocusedPane::Main
+        && state.main_screen.commit_word_diff
+        && matches!(
+            state.current_main_item(),
+            Some(ListItem::PreviousCommitInfo { .. })
+        )
+    {
+        return;
+    }
+    diff_view::mark_whitespace(
+        window,
+        &focused_diff_lines(state),
+        content_height,
+        scroll,
+        horizontal_scroll,
+        top_offset,
+        state.main_screen.line_cursor,
+        is_diff_cursor_active,
+    );
+}
+
+/// Highlights the matches of the `/` search over the diff drawn by
+/// `render_diff_view`. Word diffs are laid out differently and are left as is.
+fn render_search_matches(
+    window: &Window,
+    state: &AppState,
+    content_height: usize,
+    top_offset: usize,
+) {
+    let (search, scroll, horizontal_scroll) = match state.focused_pane {
+        FocusedPane::Main => (
+            state.main_screen.search.as_ref(),
+            state.main_screen.diff_scroll,
+            state.main_screen.horizontal_scroll,
+        ),
+        FocusedPane::Unstaged => (
+            state.unstaged_pane.search.as_ref(),
+            state.unstaged_pane.diff_scroll,
+            state.unstaged_pane.horizontal_scroll,
+        ),
+    };
+    let Some(search) = search else {
+        return;
+    };
+    if state.focused_pane == FocusedPane::Main
+        && state.main_screen.commit_word_diff
+        && matches!(
+            state.current_main_item(),
+            Some(ListItem::PreviousCommitInfo { .. })
+        )
+    {
+        return;
+    }
+    diff_view::highlight_matches(
+        window,
+        &focused_diff_lines(state),
+        search,
+        content_height,
+        scroll,
+        horizontal_scroll,
+        top_offset,
+    );
+}
+
+/// A deleted file whose diff shows nothing of what it held, like a binary
+/// or an empty file.
+fn is_deleted_without_hunks(file: &FileDiff) -> bool {
+    file.status == FileStatus::Deleted && file.hunks.is_empty()
+}
+
+/// The other side of the file selected in the focused pane, while `=`
+/// shows it: its unstaged changes for a staged file, its staged changes
+/// for an unstaged one.
+pub fn other_side_file(state: &AppState) -> Option<&FileDiff> {
+    let name = state.other_side.as_ref()?;
+    let (selected, other_side) = match state.focused_pane {
+        FocusedPane::Main => (
+            state.current_main_file(),
+            &state.unstaged_pane.unstaged_files,
+        ),
+        FocusedPane::Unstaged => (state.get_unstaged_file(), &state.files),
+    };
+    selected.filter(|file| file.file_name == *name)?;
+    other_side.iter().find(|file| file.file_name == *name)
+}
+
+/// What the diff shows while `=` shows the other side of a file.
+fn other_side_label(state: &AppState, file_name: &str) -> String {
+    match state.focused_pane {
+        FocusedPane::Main => {
+            format!(" Unstaged changes of {file_name} (working tree vs index)  = staged ones ")
+        }
+        FocusedPane::Unstaged => {
+            format!(" Staged changes of {file_name} (index vs HEAD)  = unstaged ones ")
+        }
+    }
+}
+
+/// Draws the other side of the selected file below a banner telling which
+/// comparison it is.
+fn render_other_side(window: &Window, state: &AppState, content_height: usize, top_offset: usize) {
+    let Some(file) = other_side_file(state) else {
+        return;
+    };
+    let max_x = window.get_max_x();
+    window.attron(COLOR_PAIR(8) | A_BOLD);
+    for x in 0..max_x {
+        window.mvaddch(top_offset as i32, x, ' ');
+    }
+    window.mvaddstr(
+        top_offset as i32,
+        0,
+        layout::truncate_to_width(
+            &other_side_label(state, &file.file_name),
+            max_x.max(0) as usize,
+        ),
+    );
+    window.attroff(COLOR_PAIR(8) | A_BOLD);
+
+    let (scroll, horizontal_scroll) = match state.focused_pane {
+        FocusedPane::Main => (
+            state.main_screen.diff_scroll,
+            state.main_screen.horizontal_scroll,
+        ),
+        FocusedPane::Unstaged => (
+            state.unstaged_pane.diff_scroll,
+            state.unstaged_pane.horizontal_scroll,
+        ),
+    };
+    diff_view::render(
+        window,
+        &state.displayed_diff(file),
+        content_height.saturating_sub(1),
+        scroll,
+        horizontal_scroll,
+        top_offset + 1,
+        // No cursor, its lines cannot be staged from here
+        usize::MAX,
+        false,
+    );
+}
+
+fn focused_diff_scroll(state: &mut AppState) -> &mut usize {
+    match state.focused_pane {
+        FocusedPane::Main => &mut state.main_screen.diff_scroll,
+        FocusedPane::Unstaged => &mut state.unstaged_pane.diff_scroll,
+    }
+}
+
+/// Flips the diff of the selected file between its staged and unstaged
+/// changes on `=`. While the other side is shown, `j`/`k` and `Ctrl-D`/
+/// `Ctrl-U` scroll it, and any other key goes back to the selected side
+/// before it does what it does.
+fn handle_other_side(state: &mut AppState, input: &Input) -> bool {
+    let shown_lines = other_side_file(state).map(|file| file.lines.len());
+    if !matches!(input, Input::Character('=')) {
+        let Some(line_count) = shown_lines else {
+            state.other_side = None;
+            return false;
+        };
+        let delta: isize = match input {
+            Input::Character('j') => 1,
+            Input::Character('k') => -1,
+            Input::Character('\u{4}') => 10,
+            Input::Character('\u{15}') => -10,
+            _ => {
+                *focused_diff_scroll(state) = 0;
+                state.other_side = None;
+                return false;
+            }
+        };
+        let scroll = focused_diff_scroll(state);
+        *scroll = scroll
+            .saturating_add_signed(delta)
+            .min(line_count.saturating_sub(1));
+        return true;
+    }
+
+    if shown_lines.is_some() {
+        *focused_diff_scroll(state) = 0;
+        state.other_side = None;
+        return true;
+    }
+    let selected = match state.focused_pane {
+        FocusedPane::Main => state.current_main_file(),
+        FocusedPane::Unstaged => state.get_unstaged_file(),
+    };
+    let Some(file_name) = selected.map(|file| file.file_name.clone()) else {
+        return false;
+    };
+    state.other_side = Some(file_name.clone());
+    if other_side_file(state).is_none() {
+        state.other_side = None;
+        state.set_message(
+            Severity::Warning,
+            match state.focused_pane {
+                FocusedPane::Main => format!("{file_name} has no unstaged changes"),
+                FocusedPane::Unstaged => format!("{file_name} has no staged changes"),
+            },
+        );
+        state.alert();
+        return true;
+    }
+    *focused_diff_scroll(state) = 0;
+    state.main_screen.is_diff_cursor_active = false;
+    state.unstaged_pane.is_diff_cursor_active = false;
+    true
+}
+
+/// What a deleted file held in `HEAD`, below a banner telling how to get
+/// it back.
+fn deleted_file_lines(state: &AppState, file: &FileDiff) -> Vec<String> {
+    let mut lines = vec![
+        format!("Deleted file {} (r: restore it)", file.file_name),
+        String::new(),
+    ];
+    match git::read_head_file(&state.repo_path, &file.file_name) {
+        Ok(content) if is_binary(&content) => {
+            lines.push(format!("  Binary file (size: {} bytes)", content.len()));
+        }
+        Ok(content) => lines.extend(
+            String::from_utf8_lossy(&content)
+                .lines()
+                .map(|line| format!("-{line}")),
+        ),
+        Err(e) => lines.push(format!("  Error reading file: {e}")),
+    }
+    lines
+}
+
+fn untracked_file_lines(state: &AppState, file_name: &str) -> Vec<String> {
+    match git::read_file_content(&state.repo_path, file_name) {
+        Ok((content, size)) => {
+            if is_binary(&content) {
+                vec![format!("  Binary file (size: {} bytes)", size)]
+            } else {
+                String::from_utf8_lossy(&content)
+                    .lines()
+                    .map(|l| format!(" {l}"))
+                    .collect()
             }
         }
+        Err(e) => vec![format!("  Error reading file: {}", e)],
     }
 }
 
+/// The lines of the diff shown for the focused pane, indexed like the line
+/// cursor.
+pub fn focused_diff_lines(state: &AppState) -> Vec<String> {
+    let files: &[FileDiff] = match state.focused_pane {
+        FocusedPane::Main => match state.current_main_item() {
+            Some(ListItem::File(file)) if is_deleted_without_hunks(file) => {
+                return deleted_file_lines(state, file);
+            }
+            Some(ListItem::File(file)) => return state.displayed_diff(file).lines.clone(),
+            Some(ListItem::RenameGroup { files, .. }) => files,
+            Some(ListItem::PreviousCommitInfo { .. }) => {
+                return commit_folds::shown_files(state)
+                    .iter()
+                    .flat_map(|file| file.lines.clone())
+                    .collect();
+            }
+            _ => &[],
+        },
+        FocusedPane::Unstaged => match state
+            .unstaged_pane
+            .list_items
+            .get(state.unstaged_pane.cursor)
+        {
+            Some(UnstagedListItem::File(file)) => return state.displayed_diff(file).lines.clone(),
+            Some(UnstagedListItem::UntrackedFile(file_name)) => {
+                return untracked_file_lines(state, file_name);
+            }
+            Some(UnstagedListItem::RenameGroup(group)) => return pending_rename_lines(group),
+            _ => &[],
+        },
+    };
+    files.iter().flat_map(|file| file.lines.clone()).collect()
+}
+
 pub fn handle_alt_input(state: &mut AppState, input: Input, _max_y: i32, max_x: i32) {
     if let Some(item) = state
         .main_screen
@@ -477,6 +1753,9 @@ pub fn handle_alt_input(state: &mut AppState, input: Input, _max_y: i32, max_x:
 }
 
 pub fn handle_input(state: &mut AppState, input: Input, max_y: i32, max_x: i32) {
+    if handle_other_side(state, &input) {
+        return;
+    }
     match state.focused_pane {
         crate::app_state::FocusedPane::Main => {
             handle_main_pane_input(state, input, max_y, max_x);
@@ -518,72 +1797,237 @@ fn handle_unstaged_pane_input(state: &mut AppState, input: Input, max_y: i32, ma
         return;
     }
 
-    if handle_unstaged_open_editor(state, &input) {
+    if handle_stage_by_status(state, &input) {
         return;
     }
 
-    if handle_unstaged_discard(state, &input) {
+    if handle_export_staged_tree(state, &input) {
         return;
     }
 
-    if handle_unstaged_ignore(state, &input) {
+    if handle_format_staged(state, &input) {
+        return;
+    }
+    if handle_test_staged_tree(state, &input) {
+        return;
+    }
+    if handle_remote_sync(state, &input) {
+        return;
+    }
+    if handle_explain_hunk(state, &input) {
+        return;
+    }
+    if handle_preview_patch(state, &input) {
         return;
     }
 
-    scroll::handle_scroll(state, input, max_y);
-}
+    if handle_copy_patch(state, &input) {
+        return;
+    }
 
-fn handle_unstaged_quit(state: &mut AppState, input: &Input) -> bool {
-    if matches!(input, Input::Character('q')) {
-        if state.unstaged_pane.is_diff_cursor_active {
-            state.unstaged_pane.is_diff_cursor_active = false;
-        } else {
-            let _ = commit_storage::save_commit_message(
-                &state.repo_path,
-                &state.main_screen.commit_message,
-            );
-            state.running = false;
-        }
-        return true;
+    if handle_save_patch(state, &input) {
+        return;
     }
-    false
-}
 
-fn handle_unstaged_vertical_navigation(
-    state: &mut AppState,
-    input: &Input,
-    file_list_height: usize,
-    total_items: usize,
-) -> bool {
-    if is_move_up(input) {
-        state.unstaged_pane.cursor = state.unstaged_pane.cursor.saturating_sub(1);
-        state.unstaged_pane.diff_scroll = 0;
-        state.main_screen.line_cursor = 0;
-        state.unstaged_pane.is_diff_cursor_active = false;
-        if state.unstaged_pane.cursor < state.unstaged_pane.scroll {
-            state.unstaged_pane.scroll = state.unstaged_pane.cursor;
-        }
-        return true;
+    if handle_apply_patch_file(state, &input) {
+        return;
     }
 
-    if is_move_down(input) {
-        if total_items > 0
-            && state.unstaged_pane.cursor == total_items - 1
-            && !state.main_screen.list_items.is_empty()
-        {
-            state.focused_pane = FocusedPane::Main;
-            state.main_screen.file_cursor = 0;
-            state.main_screen.file_list_scroll = 0;
-            return true;
-        }
+    if handle_split_commit(state, &input) {
+        return;
+    }
 
-        state.unstaged_pane.cursor = state
-            .unstaged_pane
-            .cursor
-            .saturating_add(1)
-            .min(total_items.saturating_sub(1));
-        state.unstaged_pane.diff_scroll = 0;
-        state.main_screen.line_cursor = 0;
+    if handle_change_author(state, &input) {
+        return;
+    }
+
+    if handle_unstaged_fixup_hunk(state, &input) {
+        return;
+    }
+
+    if handle_unstaged_open_editor(state, &input) {
+        return;
+    }
+
+    if handle_unstaged_edit_hunk(state, &input) {
+        return;
+    }
+
+    if handle_unstaged_toggle_raw_diff(state, &input) {
+        return;
+    }
+
+    if handle_unstaged_discard(state, &input) {
+        return;
+    }
+
+    if handle_unstaged_ignore(state, &input) {
+        return;
+    }
+
+    if handle_unstaged_rename(state, &input) {
+        return;
+    }
+
+    if handle_new_file(state, &input) {
+        return;
+    }
+
+    if handle_unstaged_toggle_executable(state, &input) {
+        return;
+    }
+
+    if handle_open_history(state, &input) {
+        return;
+    }
+
+    if handle_open_rebase_plan(state, &input) {
+        return;
+    }
+
+    if handle_open_stashes(state, &input) {
+        return;
+    }
+
+    if handle_recipes(state, &input) {
+        return;
+    }
+
+    if handle_never_commit(state, &input) {
+        return;
+    }
+
+    if handle_open_discard_bin(state, &input) {
+        return;
+    }
+
+    if handle_open_restore_picker(state, &input) {
+        return;
+    }
+
+    if handle_open_worktrees(state, &input) {
+        return;
+    }
+
+    if handle_open_maintenance(state, &input) {
+        return;
+    }
+
+    if handle_open_unified_list(state, &input) {
+        return;
+    }
+
+    if handle_open_diagnostics(state, &input) {
+        return;
+    }
+
+    if handle_open_message_log(state, &input) {
+        return;
+    }
+
+    if handle_bookmarks(state, &input, max_y) {
+        return;
+    }
+    if handle_hunk_jump(state, &input, max_y) {
+        return;
+    }
+    if handle_command_prompt(state, &input) {
+        return;
+    }
+    if handle_stat_panel(state, &input) {
+        return;
+    }
+
+    if handle_open_help(state, &input) {
+        return;
+    }
+
+    if handle_open_final_review(state, &input) {
+        return;
+    }
+
+    if handle_toggle_path_abbreviation(state, &input) {
+        return;
+    }
+
+    if handle_toggle_patch_headers(state, &input) {
+        return;
+    }
+
+    if handle_toggle_full_file_view(state, &input) {
+        return;
+    }
+
+    if handle_toggle_whitespace(state, &input) {
+        return;
+    }
+
+    if handle_diff_context(state, &input) {
+        return;
+    }
+
+    if handle_diff_search(state, &input) {
+        return;
+    }
+    if handle_status_filter(state, &input) {
+        return;
+    }
+
+    scroll::handle_scroll(state, input, max_y);
+}
+
+fn handle_unstaged_quit(state: &mut AppState, input: &Input) -> bool {
+    if matches!(input, Input::Character('q')) {
+        if state.unstaged_pane.is_diff_cursor_active {
+            state.unstaged_pane.is_diff_cursor_active = false;
+        } else {
+            let _ = commit_storage::save_draft(
+                &state.repo_path,
+                &state.main_screen.commit_message,
+                state.main_screen.commit_template.as_deref(),
+            );
+            state.running = false;
+        }
+        return true;
+    }
+    false
+}
+
+fn handle_unstaged_vertical_navigation(
+    state: &mut AppState,
+    input: &Input,
+    file_list_height: usize,
+    total_items: usize,
+) -> bool {
+    if is_move_up(input) {
+        state.unstaged_pane.cursor = state.unstaged_pane.cursor.saturating_sub(1);
+        state.unstaged_pane.diff_scroll = 0;
+        state.main_screen.line_cursor = 0;
+        state.unstaged_pane.is_diff_cursor_active = false;
+        if state.unstaged_pane.cursor < state.unstaged_pane.scroll {
+            state.unstaged_pane.scroll = state.unstaged_pane.cursor;
+        }
+        return true;
+    }
+
+    if is_move_down(input) {
+        if total_items > 0
+            && state.unstaged_pane.cursor == total_items - 1
+            && !state.main_screen.list_items.is_empty()
+        {
+            state.focused_pane = FocusedPane::Main;
+            state.main_screen.file_cursor = 0;
+            state.main_screen.file_list_scroll = 0;
+            return true;
+        }
+
+        state.unstaged_pane.cursor = state
+            .unstaged_pane
+            .cursor
+            .saturating_add(1)
+            .min(total_items.saturating_sub(1));
+        state.unstaged_pane.diff_scroll = 0;
+        state.main_screen.line_cursor = 0;
         state.unstaged_pane.is_diff_cursor_active = false;
         if state.unstaged_pane.cursor >= state.unstaged_pane.scroll + file_list_height {
             state.unstaged_pane.scroll = state.unstaged_pane.cursor - file_list_height + 1;
@@ -612,6 +2056,7 @@ fn handle_unstaged_diff_navigation(state: &mut AppState, input: &Input, max_y: i
             .get(state.unstaged_pane.cursor)
         {
             Some(UnstagedListItem::File(file)) => file.lines.len(),
+            Some(UnstagedListItem::RenameGroup(group)) => group.renames.len(),
             Some(UnstagedListItem::UntrackedFile(file_name)) => {
                 if let Ok((content, _)) = git::read_file_content(&state.repo_path, file_name) {
                     if is_binary(&content) {
@@ -670,10 +2115,52 @@ fn handle_unstaged_horizontal_scroll(state: &mut AppState, input: &Input, max_x:
     false
 }
 
+/// [`AppState::refuse_partial_textconv`] for the selected unstaged file.
+fn refuse_partial_unstaged_textconv(state: &mut AppState) -> bool {
+    let file_name = match state
+        .unstaged_pane
+        .list_items
+        .get(state.unstaged_pane.cursor)
+    {
+        Some(UnstagedListItem::File(file)) => file.file_name.clone(),
+        _ => return false,
+    };
+    state.refuse_partial_textconv(&file_name)
+}
+
+/// Refuses to stage the selected unstaged file when a never-commit rule
+/// matches it, telling why.
+fn refuse_never_commit_file(state: &mut AppState) -> bool {
+    let file_name = match state
+        .unstaged_pane
+        .list_items
+        .get(state.unstaged_pane.cursor)
+    {
+        Some(UnstagedListItem::File(file)) => file.file_name.clone(),
+        Some(UnstagedListItem::UntrackedFile(file_name)) => file_name.clone(),
+        _ => return false,
+    };
+    if !state.never_commit.matches_file(&file_name) {
+        return false;
+    }
+    state.set_message(
+        Severity::Warning,
+        format!("{file_name} is marked never to commit, press X to unmark it"),
+    );
+    state.alert();
+    true
+}
+
 fn handle_unstaged_stage_action(state: &mut AppState, input: &Input, max_y: i32) -> bool {
     if !is_stage_toggle(input) {
         return false;
     }
+    if state.unstaged_pane.is_diff_cursor_active && refuse_partial_unstaged_textconv(state) {
+        return true;
+    }
+    if refuse_never_commit_file(state) {
+        return true;
+    }
 
     match state
         .unstaged_pane
@@ -682,16 +2169,20 @@ fn handle_unstaged_stage_action(state: &mut AppState, input: &Input, max_y: i32)
     {
         Some(UnstagedListItem::UnstagedChangesHeader) => {
             let command = Box::new(StageUnstagedCommand::new(state.repo_path.clone()));
-            state.execute_and_refresh(command);
+            state.execute_and_guard(command);
         }
         Some(UnstagedListItem::File(file)) => {
             if state.unstaged_pane.is_diff_cursor_active {
                 if let Some(hunk) = git_patch::find_hunk(file, state.main_screen.line_cursor) {
                     let patch = git_patch::create_stage_hunk_patch(file, hunk);
+                    let what = format!("Staging the hunk of {}", file.file_name);
+                    if !patch_preview::check(state, &what, &patch, false, true) {
+                        return true;
+                    }
                     let command = Box::new(StagePatchCommand::new(state.repo_path.clone(), patch));
 
                     let old_line_cursor = state.main_screen.line_cursor;
-                    state.execute_and_refresh(command);
+                    state.execute_and_guard(command);
 
                     if let Some(updated_file) = state.get_unstaged_file() {
                         state.main_screen.line_cursor =
@@ -712,26 +2203,58 @@ fn handle_unstaged_stage_action(state: &mut AppState, input: &Input, max_y: i32)
                         state.repo_path.clone(),
                         file.file_name.clone(),
                     ));
-                    state.execute_and_refresh(command);
+                    state.execute_and_guard(command);
                 }
             } else {
                 let command = Box::new(StageFileCommand::new(
                     state.repo_path.clone(),
                     file.file_name.clone(),
                 ));
-                state.execute_and_refresh(command);
+                state.execute_and_guard(command);
             }
         }
-        Some(UnstagedListItem::UntrackedFilesHeader) => {
+        Some(UnstagedListItem::RenameGroup(group)) => {
+            let command = Box::new(StagePathsCommand::new(
+                state.repo_path.clone(),
+                group.paths(),
+            ));
+            state.execute_and_guard(command);
+        }
+        Some(UnstagedListItem::UntrackedFilesHeader)
+            if state.unstaged_pane.large_files.is_empty() =>
+        {
             let command = Box::new(StageUntrackedCommand::new(state.repo_path.clone()));
-            state.execute_and_refresh(command);
+            state.execute_and_guard(command);
+        }
+        Some(UnstagedListItem::UntrackedFilesHeader) => {
+            // The large files have a header of their own.
+            let large_files = &state.unstaged_pane.large_files;
+            let paths = state
+                .unstaged_pane
+                .untracked_files
+                .iter()
+                .filter(|name| large_files.iter().all(|(large, _)| large != *name))
+                .cloned()
+                .collect();
+            let command = Box::new(StagePathsCommand::new(state.repo_path.clone(), paths));
+            state.execute_and_guard(command);
+        }
+        Some(UnstagedListItem::LargeFilesHeader) => {
+            let paths = state
+                .unstaged_pane
+                .large_files
+                .iter()
+                .map(|(name, _)| name.clone())
+                .collect();
+            let command = Box::new(StagePathsCommand::new(state.repo_path.clone(), paths));
+            state.execute_and_guard(command);
         }
         Some(UnstagedListItem::UntrackedFile(file_name)) => {
             let command = Box::new(StageFileCommand::new(
                 state.repo_path.clone(),
                 file_name.clone(),
             ));
-            state.execute_and_refresh(command);
+            state.execute_and_guard(command);
         }
         _ => {}
     }
@@ -739,36 +2262,98 @@ fn handle_unstaged_stage_action(state: &mut AppState, input: &Input, max_y: i32)
     true
 }
 
+/// Opens the picker of the commit the hunk under the diff cursor is
+/// squashed into.
+fn handle_unstaged_fixup_hunk(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('A')) {
+        return false;
+    }
+    let Some(file) = state.get_unstaged_file().cloned() else {
+        return true;
+    };
+    let hunk = state
+        .unstaged_pane
+        .is_diff_cursor_active
+        .then(|| git_patch::find_hunk(&file, state.main_screen.line_cursor))
+        .flatten();
+    let Some(hunk) = hunk else {
+        state.set_message(
+            Severity::Warning,
+            "Move to a hunk with j/k to fix it up into a commit",
+        );
+        state.alert();
+        return true;
+    };
+    if refuse_partial_unstaged_textconv(state) || refuse_never_commit_file(state) {
+        return true;
+    }
+    if hunk
+        .lines
+        .iter()
+        .any(|line| state.never_commit.matches_line(line))
+    {
+        state.set_message(
+            Severity::Warning,
+            "This hunk has a line marked never to commit, press X to unmark it",
+        );
+        state.alert();
+        return true;
+    }
+    let patch = git_patch::create_stage_hunk_patch(&file, hunk);
+    fixup_picker::open(state, &file.file_name, patch);
+    true
+}
+
 fn handle_unstaged_stage_line(state: &mut AppState, input: &Input, max_y: i32) -> bool {
     if !matches!(input, Input::Character('1')) {
         return false;
     }
+    if refuse_partial_unstaged_textconv(state) {
+        return true;
+    }
+    if let Some(UnstagedListItem::File(file)) = state
+        .unstaged_pane
+        .list_items
+        .get(state.unstaged_pane.cursor)
+        && file
+            .lines
+            .get(state.main_screen.line_cursor)
+            .is_some_and(|line| state.never_commit.matches_line(line))
+    {
+        state.set_message(
+            Severity::Warning,
+            "This line is marked never to commit, press X to unmark it",
+        );
+        state.alert();
+        return true;
+    }
 
     if let Some(UnstagedListItem::File(file)) = state
         .unstaged_pane
         .list_items
         .get(state.unstaged_pane.cursor)
+        && let Some(patch) = git_patch::create_stage_line_patch(file, state.main_screen.line_cursor)
     {
-        if let Some(patch) = git_patch::create_stage_line_patch(file, state.main_screen.line_cursor)
-        {
-            let command = Box::new(StagePatchCommand::new(state.repo_path.clone(), patch));
+        let what = format!("Staging the line of {}", file.file_name);
+        if !patch_preview::check(state, &what, &patch, false, true) {
+            return true;
+        }
+        let command = Box::new(StagePatchCommand::new(state.repo_path.clone(), patch));
 
-            let old_line_cursor = state.main_screen.line_cursor;
-            state.execute_and_refresh(command);
+        let old_line_cursor = state.main_screen.line_cursor;
+        state.execute_and_guard(command);
 
-            if let Some(updated_file) = state.get_unstaged_file() {
-                state.main_screen.line_cursor =
-                    old_line_cursor.min(updated_file.lines.len().saturating_sub(1));
-                let (file_list_height, _) = state.unstaged_header_height(max_y);
-                let content_height = (max_y as usize).saturating_sub(file_list_height + 1);
-                if state.main_screen.line_cursor >= state.unstaged_pane.diff_scroll + content_height
-                {
-                    state.unstaged_pane.diff_scroll =
-                        state.main_screen.line_cursor - content_height + 1;
-                }
-            } else {
-                state.main_screen.line_cursor = 0;
+        if let Some(updated_file) = state.get_unstaged_file() {
+            state.main_screen.line_cursor =
+                old_line_cursor.min(updated_file.lines.len().saturating_sub(1));
+            let (file_list_height, _) = state.unstaged_header_height(max_y);
+            let content_height = (max_y as usize).saturating_sub(file_list_height + 1);
+            if state.main_screen.line_cursor >= state.unstaged_pane.diff_scroll + content_height {
+                state.unstaged_pane.diff_scroll =
+                    state.main_screen.line_cursor - content_height + 1;
             }
+        } else {
+            state.main_screen.line_cursor = 0;
         }
     }
 
@@ -777,13 +2362,35 @@ fn handle_unstaged_stage_line(state: &mut AppState, input: &Input, max_y: i32) -
 
 fn handle_unstaged_stage_all(state: &mut AppState, input: &Input) -> bool {
     if matches!(input, Input::Character('R')) {
-        let command = Box::new(StageAllCommand::new(state.repo_path.clone()));
-        state.execute_and_refresh(command);
+        let command = Box::new(StageAllCommand::new_with_size_limit(
+            state.repo_path.clone(),
+            state.max_file_size(),
+        ));
+        state.execute_and_guard(command);
         return true;
     }
     false
 }
 
+/// Where `e` returns the cursor to: the hunk under it, if the diff cursor
+/// is on one.
+fn hunk_anchor(
+    file: &FileDiff,
+    pane: FocusedPane,
+    line_cursor: usize,
+    is_diff_cursor_active: bool,
+) -> HunkAnchor {
+    let hunk = is_diff_cursor_active
+        .then(|| git_patch::find_hunk(file, line_cursor))
+        .flatten();
+    HunkAnchor {
+        file_name: file.file_name.clone(),
+        pane,
+        lines: hunk.map(|hunk| hunk.lines.clone()).unwrap_or_default(),
+        offset: hunk.map_or(0, |hunk| line_cursor - hunk.start_line),
+    }
+}
+
 fn handle_unstaged_open_editor(state: &mut AppState, input: &Input) -> bool {
     if !matches!(input, Input::Character('e')) {
         return false;
@@ -801,6 +2408,16 @@ fn handle_unstaged_open_editor(state: &mut AppState, input: &Input) -> bool {
                 state.editor_request = Some(EditorRequest {
                     file_path: path_str.to_string(),
                     line_number,
+                    stage_if_saved: None,
+                    edit_hunk: None,
+                    edit_message: None,
+                    stage_edit: edit_stage::snapshot(state, &file.file_name),
+                    return_to: Some(hunk_anchor(
+                        file,
+                        FocusedPane::Unstaged,
+                        state.main_screen.line_cursor,
+                        state.unstaged_pane.is_diff_cursor_active,
+                    )),
                 });
             }
         }
@@ -810,6 +2427,11 @@ fn handle_unstaged_open_editor(state: &mut AppState, input: &Input) -> bool {
                 state.editor_request = Some(EditorRequest {
                     file_path: path_str.to_string(),
                     line_number: None,
+                    stage_if_saved: None,
+                    edit_hunk: None,
+                    edit_message: None,
+                    stage_edit: None,
+                    return_to: None,
                 });
             }
         }
@@ -819,8 +2441,8 @@ fn handle_unstaged_open_editor(state: &mut AppState, input: &Input) -> bool {
     true
 }
 
-fn handle_unstaged_discard(state: &mut AppState, input: &Input) -> bool {
-    if !matches!(input, Input::Character('!')) {
+fn handle_unstaged_edit_hunk(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('E')) {
         return false;
     }
 
@@ -828,39 +2450,117 @@ fn handle_unstaged_discard(state: &mut AppState, input: &Input) -> bool {
         .unstaged_pane
         .list_items
         .get(state.unstaged_pane.cursor)
+        .cloned()
     {
-        Some(UnstagedListItem::File(file)) => {
-            if state.unstaged_pane.is_diff_cursor_active {
-                if let Some(hunk) = git_patch::find_hunk(file, state.main_screen.line_cursor) {
-                    let patch = git_patch::create_unstage_hunk_patch(file, hunk);
-                    let command = Box::new(DiscardUnstagedHunkCommand::new(
-                        state.repo_path.clone(),
-                        patch,
-                    ));
-                    state.execute_and_refresh(command);
-                }
-            } else {
-                let patch = git::get_unstaged_file_diff_patch(&state.repo_path, &file.file_name)
-                    .unwrap_or_default();
-                let command = Box::new(CheckoutFileCommand::new(
+        Some(UnstagedListItem::File(file)) => hunk_edit::open(state, &file, true),
+        Some(UnstagedListItem::UntrackedFile(_)) => {
+            state.set_message(
+                Severity::Warning,
+                "Only hunks of modified files can be edited",
+            );
+        }
+        _ => {}
+    }
+    true
+}
+
+fn handle_unstaged_toggle_raw_diff(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('F')) {
+        return false;
+    }
+
+    if let Some(UnstagedListItem::File(file)) = state
+        .unstaged_pane
+        .list_items
+        .get(state.unstaged_pane.cursor)
+    {
+        let file_name = file.file_name.clone();
+        state.toggle_raw_diff(&file_name);
+    }
+    true
+}
+
+fn handle_unstaged_discard(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('!')) {
+        return false;
+    }
+
+    let file_name = match state
+        .unstaged_pane
+        .list_items
+        .get(state.unstaged_pane.cursor)
+    {
+        Some(UnstagedListItem::File(file)) => Some(file.file_name.clone()),
+        Some(UnstagedListItem::UntrackedFile(file_name)) => Some(file_name.clone()),
+        _ => None,
+    };
+    if let Some(file_name) = file_name
+        && !state.is_file_unchanged_since_refresh(&file_name)
+    {
+        return true;
+    }
+    if state.unstaged_pane.is_diff_cursor_active && refuse_partial_unstaged_textconv(state) {
+        return true;
+    }
+
+    match state
+        .unstaged_pane
+        .list_items
+        .get(state.unstaged_pane.cursor)
+    {
+        Some(UnstagedListItem::File(file)) => {
+            if state.unstaged_pane.is_diff_cursor_active {
+                if let Some(hunk) = git_patch::find_hunk(file, state.main_screen.line_cursor) {
+                    let patch = git_patch::create_unstage_hunk_patch(file, hunk);
+                    let discarded = vec![DiscardedChange::from_hunk(file, hunk, "unstaged")];
+                    let what = format!("Discarding the hunk of {}", file.file_name);
+                    if !patch_preview::check(state, &what, &patch, true, false) {
+                        return true;
+                    }
+                    let command = Box::new(DiscardUnstagedHunkCommand::new(
+                        state.repo_path.clone(),
+                        patch,
+                    ));
+                    if state.execute_and_refresh(command) {
+                        discard_bin::record(state, discarded);
+                    }
+                }
+            } else {
+                let patch = git::get_unstaged_file_diff_patch(&state.repo_path, &file.file_name)
+                    .unwrap_or_default();
+                let discarded = if state.textconv_files.contains(&file.file_name) {
+                    DiscardedChange::from_whole_file(file, "unstaged", patch.clone())
+                } else {
+                    DiscardedChange::from_file(file, "unstaged", patch.clone())
+                };
+                let command = Box::new(CheckoutFileCommand::new(
                     state.repo_path.clone(),
                     file.file_name.clone(),
                     patch,
                 ));
-                state.execute_and_refresh(command);
+                if state.execute_and_refresh(command) {
+                    discard_bin::record(state, discarded);
+                }
             }
         }
         Some(UnstagedListItem::UntrackedFile(file_name)) => {
             if let Ok((content, _)) = git::read_file_content(&state.repo_path, file_name) {
                 if is_binary(&content) {
+                    state.alert();
                     return true;
                 }
+                let discarded = vec![DiscardedChange::from_untracked_file(
+                    file_name,
+                    content.clone(),
+                )];
                 let command = Box::new(DeleteUntrackedFileCommand::new(
                     state.repo_path.clone(),
                     file_name.clone(),
                     content,
                 ));
-                state.execute_and_refresh(command);
+                if state.execute_and_refresh(command) {
+                    discard_bin::record(state, discarded);
+                }
             }
         }
         _ => {}
@@ -874,42 +2574,62 @@ fn handle_unstaged_ignore(state: &mut AppState, input: &Input) -> bool {
         return false;
     }
 
-    let mut file_to_ignore: Option<String> = None;
-    let mut is_tracked = false;
-
-    match state
+    let file_to_ignore = match state
         .unstaged_pane
         .list_items
         .get(state.unstaged_pane.cursor)
     {
-        Some(UnstagedListItem::File(file)) => {
-            file_to_ignore = Some(file.file_name.clone());
-            is_tracked = true;
-        }
+        Some(UnstagedListItem::File(file)) => Some((file.file_name.clone(), Listed::Unstaged)),
         Some(UnstagedListItem::UntrackedFile(file_name)) => {
-            file_to_ignore = Some(file_name.clone());
-            is_tracked = false;
+            Some((file_name.clone(), Listed::Untracked))
         }
-        _ => {}
+        _ => None,
+    };
+
+    if let Some((file_name, listed)) = file_to_ignore
+        && file_name != ".gitignore"
+    {
+        ignore_picker::open(state, file_name, listed);
     }
 
-    if let Some(file_name) = file_to_ignore {
-        if file_name != ".gitignore" {
-            let command: Box<dyn crate::command::Command> = if is_tracked {
-                Box::new(IgnoreUnstagedTrackedFileCommand::new(
-                    state.repo_path.clone(),
-                    file_name,
-                ))
-            } else {
-                Box::new(IgnoreUntrackedFileCommand::new(
-                    state.repo_path.clone(),
-                    file_name,
-                ))
-            };
-            state.execute_and_refresh(command);
-        }
+    true
+}
+
+fn handle_unstaged_rename(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('r')) {
+        return false;
+    }
+    if let Some(file) = state.get_unstaged_file()
+        && file.status != FileStatus::Deleted
+    {
+        state.prompt = Some(rename_prompt(&file.file_name));
+    }
+    true
+}
+
+fn handle_unstaged_toggle_executable(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('x')) {
+        return false;
     }
 
+    let file_name = match state
+        .unstaged_pane
+        .list_items
+        .get(state.unstaged_pane.cursor)
+    {
+        Some(UnstagedListItem::File(file)) if file.status != FileStatus::Deleted => {
+            Some(file.file_name.clone())
+        }
+        Some(UnstagedListItem::UntrackedFile(file_name)) => Some(file_name.clone()),
+        _ => None,
+    };
+    if let Some(file_name) = file_name {
+        let command = Box::new(ToggleExecutableCommand::new(
+            state.repo_path.clone(),
+            file_name,
+        ));
+        state.execute_and_refresh(command);
+    }
     true
 }
 
@@ -920,7 +2640,13 @@ fn handle_main_pane_input(state: &mut AppState, input: Input, max_y: i32, max_x:
     }
 
     if state.is_in_input_mode() {
-        if is_vertical_navigation(&input) {
+        if commit_view::handle_history_recall(state, &input, max_x) {
+            // Put an earlier message in the input
+        } else if commit_view::handle_open_in_editor(state, &input) {
+            // Edit the message in the editor
+        } else if commit_view::handle_vertical_movement(state, &input, max_x) {
+            // Moved to another line of the message
+        } else if is_vertical_navigation(&input) {
             handle_navigation(state, input, max_y, max_x);
         } else {
             // Other keys go to the text editor
@@ -932,9 +2658,18 @@ fn handle_main_pane_input(state: &mut AppState, input: Input, max_y: i32, max_x:
 }
 
 fn unstage_line(state: &mut AppState, max_y: i32) {
+    if let Some(file_name) = state.current_main_file().map(|file| file.file_name.clone())
+        && state.refuse_partial_textconv(&file_name)
+    {
+        return;
+    }
     if let Some(file) = state.current_main_file() {
         let line_index = state.main_screen.line_cursor;
         if let Some(patch) = git_patch::create_unstage_line_patch(file, line_index, true) {
+            let what = format!("Unstaging the line of {}", file.file_name);
+            if !patch_preview::check(state, &what, &patch, true, true) {
+                return;
+            }
             let command = Box::new(ApplyPatchCommand::new(state.repo_path.clone(), patch));
             let old_line_cursor = state.main_screen.line_cursor;
             state.execute_and_refresh(command);
@@ -978,10 +2713,205 @@ fn handle_commands(state: &mut AppState, input: &Input, max_y: i32) -> bool {
         return true;
     }
 
+    if handle_stage_by_status(state, input) {
+        return true;
+    }
+
+    if handle_export_staged_tree(state, input) {
+        return true;
+    }
+
+    if handle_format_staged(state, input) {
+        return true;
+    }
+    if handle_test_staged_tree(state, input) {
+        return true;
+    }
+    if handle_remote_sync(state, input) {
+        return true;
+    }
+    if handle_explain_hunk(state, input) {
+        return true;
+    }
+    if handle_preview_patch(state, input) {
+        return true;
+    }
+
+    if handle_copy_patch(state, input) {
+        return true;
+    }
+
+    if handle_save_patch(state, input) {
+        return true;
+    }
+
+    if handle_apply_patch_file(state, input) {
+        return true;
+    }
+
+    if handle_split_commit(state, input) {
+        return true;
+    }
+
+    if handle_change_author(state, input) {
+        return true;
+    }
+
+    if handle_drop_commit(state, input) {
+        return true;
+    }
+
     if handle_main_open_editor(state, input) {
         return true;
     }
 
+    if handle_main_edit_hunk(state, input) {
+        return true;
+    }
+
+    if handle_main_toggle_raw_diff(state, input) {
+        return true;
+    }
+
+    if handle_main_toggle_file_order(state, input) {
+        return true;
+    }
+
+    if handle_main_rename(state, input) {
+        return true;
+    }
+
+    if handle_new_file(state, input) {
+        return true;
+    }
+
+    if handle_main_toggle_executable(state, input) {
+        return true;
+    }
+
+    if handle_main_push(state, input) {
+        return true;
+    }
+
+    if handle_main_grab_commit(state, input) {
+        return true;
+    }
+
+    if handle_open_history(state, input) {
+        return true;
+    }
+
+    if handle_open_rebase_plan(state, input) {
+        return true;
+    }
+
+    if handle_open_stashes(state, input) {
+        return true;
+    }
+
+    if handle_recipes(state, input) {
+        return true;
+    }
+
+    if handle_never_commit(state, input) {
+        return true;
+    }
+
+    if handle_open_discard_bin(state, input) {
+        return true;
+    }
+
+    if handle_open_restore_picker(state, input) {
+        return true;
+    }
+
+    if handle_open_worktrees(state, input) {
+        return true;
+    }
+
+    if handle_open_maintenance(state, input) {
+        return true;
+    }
+
+    if handle_open_unified_list(state, input) {
+        return true;
+    }
+
+    if handle_open_diagnostics(state, input) {
+        return true;
+    }
+
+    if handle_open_message_log(state, input) {
+        return true;
+    }
+
+    if handle_bookmarks(state, input, max_y) {
+        return true;
+    }
+    if handle_hunk_jump(state, input, max_y) {
+        return true;
+    }
+    if handle_command_prompt(state, input) {
+        return true;
+    }
+    if handle_commit_folds(state, input) {
+        return true;
+    }
+    if handle_stat_panel(state, input) {
+        return true;
+    }
+
+    if handle_open_help(state, input) {
+        return true;
+    }
+
+    if handle_open_final_review(state, input) {
+        return true;
+    }
+
+    if handle_toggle_path_abbreviation(state, input) {
+        return true;
+    }
+
+    if handle_toggle_patch_headers(state, input) {
+        return true;
+    }
+
+    if handle_toggle_full_file_view(state, input) {
+        return true;
+    }
+
+    if handle_toggle_whitespace(state, input) {
+        return true;
+    }
+
+    if handle_diff_context(state, input) {
+        return true;
+    }
+
+    if handle_diff_search(state, input) {
+        return true;
+    }
+    if handle_status_filter(state, input) {
+        return true;
+    }
+
+    if handle_main_toggle_word_diff(state, input) {
+        return true;
+    }
+
+    if handle_main_toggle_commit_hashes(state, input) {
+        return true;
+    }
+
+    if handle_main_copy_hash(state, input) {
+        return true;
+    }
+
+    if handle_main_toggle_rename_group(state, input) {
+        return true;
+    }
+
     false
 }
 
@@ -990,9 +2920,10 @@ fn handle_main_quit(state: &mut AppState, input: &Input) -> bool {
         if state.main_screen.is_diff_cursor_active {
             state.main_screen.is_diff_cursor_active = false;
         } else {
-            let _ = commit_storage::save_commit_message(
+            let _ = commit_storage::save_draft(
                 &state.repo_path,
                 &state.main_screen.commit_message,
+                state.main_screen.commit_template.as_deref(),
             );
             state.running = false;
         }
@@ -1006,154 +2937,1192 @@ fn handle_main_ignore(state: &mut AppState, input: &Input) -> bool {
         return false;
     }
 
-    if let Some(file) = state.current_main_file().cloned() {
-        if file.file_name != ".gitignore" {
-            let command = Box::new(IgnoreFileCommand::new(
-                state.repo_path.clone(),
-                file.file_name.clone(),
-            ));
-            state.execute_and_refresh(command);
-        }
+    if let Some(file) = state.current_main_file().cloned()
+        && file.file_name != ".gitignore"
+    {
+        ignore_picker::open(state, file.file_name, Listed::Staged);
+    }
+
+    true
+}
+
+fn handle_main_discard(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('!')) {
+        return false;
+    }
+
+    if let Some(file_name) = state.current_main_file().map(|file| file.file_name.clone())
+        && !state.is_file_unchanged_since_refresh(&file_name)
+    {
+        return true;
+    }
+
+    if state.main_screen.is_diff_cursor_active {
+        if let Some(file_name) = state.current_main_file().map(|file| file.file_name.clone())
+            && state.refuse_partial_textconv(&file_name)
+        {
+            return true;
+        }
+        if let Some(file) = state.current_main_file() {
+            let line_index = state.main_screen.line_cursor;
+            if let Some(hunk) = git_patch::find_hunk(file, line_index) {
+                let patch = git_patch::create_unstage_hunk_patch(file, hunk);
+                let discarded = vec![DiscardedChange::from_hunk(file, hunk, "staged")];
+                let what = format!("Discarding the hunk of {}", file.file_name);
+                if !patch_preview::check(state, &what, &patch, true, true) {
+                    return true;
+                }
+                let command = Box::new(DiscardHunkCommand::new(state.repo_path.clone(), patch));
+                if state.execute_and_refresh(command) {
+                    discard_bin::record(state, discarded);
+                }
+            }
+        }
+    } else if let Some(file) = state.current_main_file().cloned() {
+        let is_new = file.status == FileStatus::Added;
+        let whole_patch =
+            git::get_file_diff_patch(&state.repo_path, &file.file_name).unwrap_or_default();
+        let command = Box::new(DiscardFileCommand::new(
+            state.repo_path.clone(),
+            file.file_name.clone(),
+            is_new,
+        ));
+        let discarded = if state.textconv_files.contains(&file.file_name) {
+            DiscardedChange::from_whole_file(&file, "staged", whole_patch)
+        } else {
+            DiscardedChange::from_file(&file, "staged", whole_patch)
+        };
+        if state.execute_and_refresh(command) {
+            discard_bin::record(state, discarded);
+        }
+    }
+
+    true
+}
+
+fn handle_main_stage_toggle(state: &mut AppState, input: &Input) -> bool {
+    if !is_stage_toggle(input) {
+        return false;
+    }
+
+    match state
+        .main_screen
+        .list_items
+        .get(state.main_screen.file_cursor)
+        .cloned()
+    {
+        Some(ListItem::StagedChangesHeader) => {
+            let command = Box::new(UnstageAllCommand::new(state.repo_path.clone()));
+            state.execute_and_refresh(command);
+        }
+        Some(ListItem::File(file)) => {
+            let line_index = state.main_screen.line_cursor;
+            if let Some(hunk) = git_patch::find_hunk(&file, line_index) {
+                if state.refuse_partial_textconv(&file.file_name) {
+                    return true;
+                }
+                let patch = git_patch::create_unstage_hunk_patch(&file, hunk);
+                let what = format!("Unstaging the hunk of {}", file.file_name);
+                if !patch_preview::check(state, &what, &patch, true, true) {
+                    return true;
+                }
+                let command = Box::new(ApplyPatchCommand::new(state.repo_path.clone(), patch));
+                state.execute_and_refresh(command);
+            } else {
+                let command = Box::new(UnstageFileCommand::new(
+                    state.repo_path.clone(),
+                    file.file_name.clone(),
+                ));
+                state.execute_and_refresh(command);
+            }
+        }
+        Some(ListItem::RenameGroup { group, .. }) => {
+            let command = Box::new(UnstagePathsCommand::new(
+                state.repo_path.clone(),
+                group.paths(),
+            ));
+            state.execute_and_refresh(command);
+        }
+        Some(ListItem::PreviousCommitInfo {
+            hash,
+            message,
+            is_on_remote,
+            is_fixup: _,
+        }) => {
+            if state.main_screen.is_diff_cursor_active
+                && (commit_folds::toggle_at_cursor(state) || state.jump_to_file_in_diff())
+            {
+                return true;
+            }
+            if !is_on_remote {
+                state.main_screen.amending_commit_hash = Some(hash.clone());
+
+                let full_message =
+                    reword_preview::full_message(state, &hash).unwrap_or_else(|| message.clone());
+                let current_index = state.main_screen.file_cursor;
+                if let Some(item) = state.main_screen.list_items.get_mut(current_index) {
+                    *item = ListItem::AmendingCommitMessageInput {
+                        hash: hash.clone(),
+                        message: full_message,
+                    };
+                }
+
+                if let Some(commit_input_index) = state
+                    .main_screen
+                    .list_items
+                    .iter()
+                    .position(|item| matches!(item, ListItem::CommitMessageInput))
+                    && let Some(ListItem::CommitMessageInput) =
+                        state.main_screen.list_items.get_mut(commit_input_index)
+                {
+                    state.main_screen.commit_message.clear();
+                }
+                state.main_screen.commit_cursor = message.chars().count();
+            } else {
+                state.alert();
+            }
+        }
+        _ => {}
+    }
+
+    true
+}
+
+fn handle_main_stage_line(state: &mut AppState, input: &Input, max_y: i32) -> bool {
+    if !matches!(input, Input::Character('1')) {
+        return false;
+    }
+    unstage_line(state, max_y);
+    true
+}
+
+fn handle_main_stage_all(state: &mut AppState, input: &Input) -> bool {
+    if matches!(input, Input::Character('R')) {
+        let command = Box::new(StageAllCommand::new_with_size_limit(
+            state.repo_path.clone(),
+            state.max_file_size(),
+        ));
+        state.execute_and_guard(command);
+        return true;
+    }
+    false
+}
+
+fn plural(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
+/// Stages every modified file and leaves the deletions unstaged (`M`), or
+/// the other way around (`K`), so a mass deletion by a bad script does not
+/// slip into a commit along with the real changes. Untracked files are
+/// left out by both, and the files are staged as one undo step.
+fn handle_stage_by_status(state: &mut AppState, input: &Input) -> bool {
+    let deletions = match input {
+        Input::Character('M') => false,
+        Input::Character('K') => true,
+        _ => return false,
+    };
+    let (chosen, left): (Vec<&FileDiff>, Vec<&FileDiff>) = state
+        .unstaged_pane
+        .unstaged_files
+        .iter()
+        .filter(|file| !state.never_commit.matches_file(&file.file_name))
+        .partition(|file| (file.status == FileStatus::Deleted) == deletions);
+    let (chosen_noun, left_noun) = if deletions {
+        ("deletion", "modified file")
+    } else {
+        ("modified file", "deletion")
+    };
+    if chosen.is_empty() {
+        state.set_message(Severity::Warning, format!("No {chosen_noun}s to stage"));
+        state.alert();
+        return true;
+    }
+
+    let mut message = format!("Staged {}", plural(chosen.len(), chosen_noun));
+    if !left.is_empty() {
+        message.push_str(&format!(
+            ", left {} unstaged",
+            plural(left.len(), left_noun)
+        ));
+    }
+    let commands = chosen
+        .iter()
+        .map(|file| {
+            Box::new(StageFileCommand::new(
+                state.repo_path.clone(),
+                file.file_name.clone(),
+            )) as Box<dyn crate::command::Command>
+        })
+        .collect();
+    if state.execute_and_guard(Box::new(GroupCommand::new(commands))) && state.message.is_none() {
+        state.set_message(Severity::Success, message);
+    }
+    true
+}
+
+/// Writes the staged tree to a temporary directory and opens it, to run the
+/// tests against exactly what is about to be committed (`m`).
+fn handle_export_staged_tree(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('m')) {
+        return false;
+    }
+
+    let dir = match git::export_staged_tree(&state.repo_path) {
+        Ok(dir) => dir,
+        Err(e) => {
+            state.set_message(
+                Severity::Error,
+                format!("Could not export the staged tree: {e}"),
+            );
+            return true;
+        }
+    };
+    let command = state.config.staged_tree_command.as_deref();
+    match external_command::open_directory(&dir, command) {
+        Ok(()) => state.set_message(
+            Severity::Success,
+            format!("Exported the staged tree to {}", dir.display()),
+        ),
+        Err(e) => state.set_message(
+            Severity::Error,
+            format!(
+                "Exported the staged tree to {}, could not open it: {e}",
+                dir.display()
+            ),
+        ),
+    }
+    true
+}
+
+/// Previews the formatting fixes of the staged content (`Z`).
+fn handle_format_staged(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('Z')) {
+        return false;
+    }
+    format_preview::open(state);
+    true
+}
+
+/// Runs `staged_test_command` on the staged tree in the background (`t`).
+fn handle_test_staged_tree(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('t')) {
+        return false;
+    }
+
+    let Some(command) = state.config.staged_test_command.clone() else {
+        state.alert();
+        state.set_message(
+            Severity::Warning,
+            "Set staged_test_command to test the staged tree",
+        );
+        return true;
+    };
+    if state.files.is_empty() {
+        state.alert();
+        state.set_message(Severity::Warning, "Nothing is staged to test");
+    } else if state.start_staged_test() {
+        state.set_message(
+            Severity::Info,
+            format!("Testing the staged tree with {command}"),
+        );
+    } else {
+        state.alert();
+        state.set_message(Severity::Warning, "The staged tests are already running");
+    }
+    true
+}
+
+fn handle_remote_sync(state: &mut AppState, input: &Input) -> bool {
+    let operation = match input {
+        Input::Character('G') => RemoteOperation::Fetch,
+        Input::Character('L') => RemoteOperation::PullRebase,
+        _ => return false,
+    };
+    if state.start_remote_sync(operation) {
+        // The bottom line shows the progress instead.
+        state.message = None;
+    } else {
+        state.alert();
+        state.set_message(
+            Severity::Warning,
+            "Wait for the running fetch or pull to finish",
+        );
+    }
+    true
+}
+
+fn handle_explain_hunk(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('J')) {
+        return false;
+    }
+    if state.config.explain_command.is_none() {
+        state.alert();
+        state.set_message(Severity::Warning, "Set explain_command to explain hunks");
+        return true;
+    }
+    let (file, is_diff_cursor_active) = match state.focused_pane {
+        FocusedPane::Unstaged => (
+            state.get_unstaged_file(),
+            state.unstaged_pane.is_diff_cursor_active,
+        ),
+        FocusedPane::Main => (
+            state.current_main_file(),
+            state.main_screen.is_diff_cursor_active,
+        ),
+    };
+    let patch = file.filter(|_| is_diff_cursor_active).and_then(|file| {
+        git_patch::find_hunk(file, state.main_screen.line_cursor).map(|hunk| {
+            (
+                file.file_name.clone(),
+                git_patch::create_stage_hunk_patch(file, hunk),
+            )
+        })
+    });
+    match patch {
+        Some((file_name, patch)) => explain::open(state, &file_name, patch),
+        None => {
+            state.alert();
+            state.set_message(Severity::Warning, "Move to a hunk with j/k to explain it");
+        }
+    }
+    true
+}
+
+fn handle_preview_patch(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('%')) {
+        return false;
+    }
+    patch_preview::open_at_cursor(state);
+    true
+}
+
+/// The hunk under the diff cursor of the focused pane as a patch, or the
+/// whole file without the diff cursor, with what it is for the messages.
+fn patch_to_export(state: &mut AppState) -> Option<(String, String)> {
+    let (file, is_diff_cursor_active) = match state.focused_pane {
+        FocusedPane::Unstaged => (
+            state.get_unstaged_file(),
+            state.unstaged_pane.is_diff_cursor_active,
+        ),
+        FocusedPane::Main => (
+            state.current_main_file(),
+            state.main_screen.is_diff_cursor_active,
+        ),
+    };
+    let Some(file) = file.cloned() else {
+        state.alert();
+        state.set_message(
+            Severity::Warning,
+            "Select a file or a hunk to export it as a patch",
+        );
+        return None;
+    };
+    let refusal = if file.binary.is_some() {
+        Some(format!(
+            "{} is binary, it has no patch to export",
+            file.file_name
+        ))
+    } else if state.textconv_files.contains(&file.file_name) {
+        Some(format!(
+            "{} is shown through a textconv driver, its diff is not a patch",
+            file.file_name
+        ))
+    } else {
+        None
+    };
+    if let Some(refusal) = refusal {
+        state.alert();
+        state.set_message(Severity::Warning, refusal);
+        return None;
+    }
+    if !is_diff_cursor_active {
+        return Some((file.file_name.clone(), git_patch::create_file_patch(&file)));
+    }
+    match git_patch::find_hunk(&file, state.main_screen.line_cursor) {
+        Some(hunk) => Some((
+            format!("the hunk of {}", file.file_name),
+            git_patch::create_stage_hunk_patch(&file, hunk),
+        )),
+        None => {
+            state.alert();
+            state.set_message(Severity::Warning, "Move to a hunk with j/k to export it");
+            None
+        }
+    }
+}
+
+/// `y` copies the hunk under the diff cursor, or the whole file, to the
+/// clipboard as a patch.
+fn handle_copy_patch(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('y')) {
+        return false;
+    }
+    if let Some((what, patch)) = patch_to_export(state) {
+        match external_command::copy_to_clipboard(&patch) {
+            Ok(()) => state.set_message(
+                Severity::Success,
+                format!("Copied {what} to the clipboard as a patch"),
+            ),
+            Err(e) => state.set_message(
+                Severity::Error,
+                format!("Could not copy {what} to the clipboard: {e}"),
+            ),
+        }
+    }
+    true
+}
+
+/// Ctrl-W asks where to write the hunk under the diff cursor, or the whole
+/// file, as a patch.
+fn handle_save_patch(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('\u{17}')) {
+        return false;
+    }
+    if let Some((what, patch)) = patch_to_export(state) {
+        let file_name = match state.focused_pane {
+            FocusedPane::Unstaged => state.get_unstaged_file(),
+            FocusedPane::Main => state.current_main_file(),
+        }
+        .map(|file| file.file_name.clone())
+        .unwrap_or_default();
+        let name = file_name.rsplit('/').next().unwrap_or(&file_name);
+        state.prompt = Some(Prompt::new(
+            PromptAction::SavePatch { what, patch },
+            "Save patch to",
+            &format!("{name}.patch"),
+        ));
+    }
+    true
+}
+
+/// Writes `patch` to `path`, relative to the repository root, creating the
+/// missing directories.
+pub fn save_patch(state: &mut AppState, what: &str, patch: &str, path: &str) {
+    if path.is_empty() {
+        return;
+    }
+    if let Some(refusal) = repo_path::new_path_refusal(&state.repo_path, path) {
+        state.set_message(
+            Severity::Warning,
+            format!("Cannot save the patch: {refusal}"),
+        );
+        state.alert();
+        return;
+    }
+    let full_path = state.repo_path.join(path);
+    let written = full_path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|()| std::fs::write(&full_path, patch));
+    match written {
+        Ok(()) => state.set_message(Severity::Success, format!("Saved {what} to {path}")),
+        Err(e) => state.set_message(Severity::Error, format!("Failed to save the patch: {e}")),
+    }
+}
+
+/// Ctrl-R asks for a patch file to apply, to the working tree from the
+/// unstaged pane and to the index as well from the staged one.
+fn handle_apply_patch_file(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('\u{12}')) {
+        return false;
+    }
+    let staged = state.focused_pane == FocusedPane::Main;
+    let label = if staged {
+        "Stage patch from"
+    } else {
+        "Apply patch to the working tree from"
+    };
+    state.prompt = Some(Prompt::new(PromptAction::ApplyPatch { staged }, label, ""));
+    true
+}
+
+/// Applies the patch in `path`, relative to the repository root, as one
+/// undo step, once `git apply --check` accepted it.
+pub fn apply_patch_file(state: &mut AppState, path: &str, staged: bool) {
+    if path.is_empty() {
+        return;
+    }
+    let patch = match std::fs::read_to_string(state.repo_path.join(path)) {
+        Ok(patch) => patch,
+        Err(e) => {
+            state.set_message(Severity::Error, format!("Cannot read {path}: {e}"));
+            state.alert();
+            return;
+        }
+    };
+    if let Err(e) = git::check_patch(&state.repo_path, &patch, staged) {
+        state.set_message(Severity::Error, format!("{path} does not apply: {e}"));
+        state.alert();
+        return;
+    }
+    let command = Box::new(ApplyExternalPatchCommand::new(
+        state.repo_path.clone(),
+        patch,
+        staged,
+    ));
+    if !state.execute_and_refresh(command) {
+        return;
+    }
+    state.set_message(
+        Severity::Success,
+        if staged {
+            format!("Staged {path}")
+        } else {
+            format!("Applied {path} to the working tree")
+        },
+    );
+}
+
+fn handle_main_edit_hunk(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('E')) {
+        return false;
+    }
+
+    if let Some(file) = state.current_main_file().cloned() {
+        hunk_edit::open(state, &file, false);
+    }
+    true
+}
+
+fn handle_main_toggle_raw_diff(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('F')) {
+        return false;
+    }
+
+    if let Some(file_name) = state.current_main_file().map(|file| file.file_name.clone()) {
+        state.toggle_raw_diff(&file_name);
+    }
+    true
+}
+
+fn handle_main_open_editor(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('e')) {
+        return false;
+    }
+
+    if let Some(file) = state.current_main_file() {
+        let line_number = if state.main_screen.is_diff_cursor_active {
+            git_patch::get_line_number(file, state.main_screen.line_cursor)
+        } else {
+            None
+        };
+        let file_path = state.repo_path.join(&file.file_name);
+        if let Some(path_str) = file_path.to_str() {
+            state.editor_request = Some(EditorRequest {
+                file_path: path_str.to_string(),
+                line_number,
+                stage_if_saved: None,
+                edit_hunk: None,
+                edit_message: None,
+                stage_edit: edit_stage::snapshot(state, &file.file_name),
+                return_to: Some(hunk_anchor(
+                    file,
+                    FocusedPane::Main,
+                    state.main_screen.line_cursor,
+                    state.main_screen.is_diff_cursor_active,
+                )),
+            });
+        }
+    }
+
+    true
+}
+
+fn handle_main_toggle_file_order(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('o')) {
+        return false;
+    }
+    state.toggle_file_list_order();
+    true
+}
+
+/// `r` renames the selected file, or restores it if it is deleted.
+fn handle_main_rename(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('r')) {
+        return false;
+    }
+    let Some(file) = state.current_main_file() else {
+        return true;
+    };
+    if file.status != FileStatus::Deleted {
+        state.prompt = Some(rename_prompt(&file.file_name));
+        return true;
+    }
+
+    let file_name = file.file_name.clone();
+    if state.repo_path.join(&file_name).exists() {
+        state.set_message(
+            Severity::Warning,
+            format!("{file_name} exists in the working tree again, stage or discard it first"),
+        );
+        state.alert();
+        return true;
+    }
+    let command = Box::new(RestoreDeletedFileCommand::new(
+        state.repo_path.clone(),
+        file_name.clone(),
+    ));
+    if state.execute_and_refresh(command) {
+        state.set_message(Severity::Success, format!("Restored {file_name}"));
+    }
+    true
+}
+
+fn handle_main_toggle_executable(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('x')) {
+        return false;
+    }
+    if let Some(file) = state.current_main_file()
+        && file.status != FileStatus::Deleted
+    {
+        let command = Box::new(ToggleExecutableCommand::new(
+            state.repo_path.clone(),
+            file.file_name.clone(),
+        ));
+        state.execute_and_refresh(command);
+    }
+    true
+}
+
+fn rename_prompt(file_name: &str) -> Prompt {
+    Prompt::new(
+        PromptAction::RenameFile {
+            from: file_name.to_string(),
+        },
+        "Rename to",
+        file_name,
+    )
+}
+
+fn handle_new_file(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('a')) {
+        return false;
+    }
+
+    // Start from the directory of the selected file.
+    let selected = match state.focused_pane {
+        FocusedPane::Main => state.current_main_file().map(|f| f.file_name.clone()),
+        FocusedPane::Unstaged => match state
+            .unstaged_pane
+            .list_items
+            .get(state.unstaged_pane.cursor)
+        {
+            Some(UnstagedListItem::File(file)) => Some(file.file_name.clone()),
+            Some(UnstagedListItem::UntrackedFile(name)) => Some(name.clone()),
+            _ => None,
+        },
+    };
+    let initial_dir = selected
+        .as_deref()
+        .and_then(|name| name.rfind('/').map(|i| &name[..=i]))
+        .unwrap_or("");
+
+    state.prompt = Some(Prompt::new(
+        PromptAction::CreateFile,
+        "New file (end with / for a directory)",
+        initial_dir,
+    ));
+    true
+}
+
+fn handle_main_toggle_word_diff(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('w'))
+        || !matches!(
+            state.current_main_item(),
+            Some(ListItem::PreviousCommitInfo { .. })
+        )
+    {
+        return false;
+    }
+    state.toggle_commit_word_diff();
+    true
+}
+
+fn handle_main_toggle_commit_hashes(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('#')) {
+        return false;
+    }
+    state.main_screen.commit_hashes = state.main_screen.commit_hashes.next();
+    true
+}
+
+fn handle_main_copy_hash(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('Y')) {
+        return false;
+    }
+    let Some(hash) = selected_commit(state).map(|commit| commit.full_hash.clone()) else {
+        state.alert();
+        state.set_message(Severity::Warning, "Select a commit to copy its hash");
+        return true;
+    };
+    match external_command::copy_to_clipboard(&hash) {
+        Ok(()) => state.set_message(Severity::Success, format!("Copied {hash} to the clipboard")),
+        Err(e) => state.set_message(
+            Severity::Error,
+            format!("Could not copy {hash} to the clipboard: {e}"),
+        ),
+    }
+    true
+}
+
+/// Takes the selected local commit apart to commit its changes again in
+/// several parts, or restores the branch from before the split while one
+/// is going on.
+fn handle_split_commit(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('S')) {
+        return false;
+    }
+    if let Some(split) = state.split.take() {
+        match split.restore(&state.repo_path) {
+            Ok(()) => {
+                state.set_message(
+                    Severity::Success,
+                    format!(
+                        "Restored {} {} from before the split",
+                        split.hash,
+                        split.subject()
+                    ),
+                );
+                state.command_history.clear();
+                state.refresh_diff(true);
+            }
+            Err(e) => {
+                state.set_message(
+                    Severity::Error,
+                    format!("Could not restore the branch: {e}"),
+                );
+                state.split = Some(split);
+            }
+        }
+        return true;
+    }
+
+    let Some(commit) = selected_commit(state).cloned() else {
+        state.alert();
+        state.set_message(Severity::Warning, "Select a local commit to split it");
+        return true;
+    };
+    if commit.is_on_remote {
+        state.alert();
+        state.set_message(Severity::Warning, "Commits on a remote cannot be split");
+        return true;
+    }
+    if !state.files.is_empty() || state.main_screen.has_unstaged_changes {
+        state.alert();
+        state.set_message(
+            Severity::Warning,
+            "Commit or stash the changes before splitting a commit",
+        );
+        return true;
+    }
+
+    match split_commit::start(&state.repo_path, &commit.hash) {
+        Ok(split) => {
+            state.command_history.clear();
+            state.main_screen.commit_message = split.message.clone();
+            state.main_screen.commit_cursor = split.subject().chars().count();
+            state.refresh_diff(true);
+            state.focused_pane = FocusedPane::Unstaged;
+            state.set_message(
+                Severity::Warning,
+                format!(
+                    "Stage and commit the first part of {} {}, S restores the original",
+                    split.hash,
+                    split.subject()
+                ),
+            );
+            state.split = Some(split);
+        }
+        Err(e) => {
+            state.alert();
+            state.set_message(
+                Severity::Error,
+                format!("Could not split {}: {e}", commit.hash),
+            );
+        }
+    }
+    true
+}
+
+/// Expands a collapsed directory rename, or collapses the group the cursor is
+/// on or inside of.
+fn handle_main_toggle_rename_group(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('z')) {
+        return false;
+    }
+    let cursor = state.main_screen.file_cursor;
+    let key = match state.main_screen.list_items.get(cursor) {
+        Some(ListItem::RenameGroup { group, .. }) => Some(group.key()),
+        Some(ListItem::File(file)) => state.main_screen.list_items[..cursor]
+            .iter()
+            .rev()
+            .find_map(|item| match item {
+                ListItem::RenameGroup {
+                    group,
+                    expanded: true,
+                    ..
+                } if group.contains(&file.file_name) => Some(group.key()),
+                _ => None,
+            }),
+        _ => None,
+    };
+    let Some(key) = key else {
+        return false;
+    };
+    state.toggle_rename_group(key);
+    true
+}
+
+fn handle_toggle_path_abbreviation(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('p')) {
+        return false;
+    }
+    state.abbreviate_paths = !state.abbreviate_paths;
+    true
+}
+
+fn handle_toggle_patch_headers(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('h')) {
+        return false;
+    }
+    state.toggle_patch_headers();
+    true
+}
+
+fn handle_toggle_full_file_view(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('V')) {
+        return false;
+    }
+    state.toggle_full_file_view();
+    true
+}
+
+fn handle_diff_context(state: &mut AppState, input: &Input) -> bool {
+    let delta = match input {
+        Input::Character('+') => 1,
+        Input::Character('-') => -1,
+        _ => return false,
+    };
+    state.change_diff_context(delta);
+    true
+}
+
+fn handle_toggle_whitespace(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('_')) {
+        return false;
+    }
+    state.show_whitespace = !state.show_whitespace;
+    state.set_message(
+        Severity::Info,
+        if state.show_whitespace {
+            "Highlighting whitespace problems, _ hides them"
+        } else {
+            "Not highlighting whitespace problems"
+        }
+        .to_string(),
+    );
+    true
+}
+
+fn handle_main_push(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('P')) {
+        return false;
+    }
+    if !matches!(
+        state.current_main_item(),
+        Some(ListItem::PreviousCommitInfo { .. })
+    ) {
+        return false;
+    }
+    state.start_push();
+    true
+}
+
+fn handle_main_grab_commit(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('g')) {
+        return false;
+    }
+    if !matches!(
+        state.current_main_item(),
+        Some(ListItem::PreviousCommitInfo {
+            is_on_remote: false,
+            ..
+        })
+    ) {
+        return false;
+    }
+    start_reorder_mode(state);
+    state.main_screen.is_grabbing_commit = true;
+    true
+}
+
+fn handle_open_history(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('H')) {
+        return false;
+    }
+    history::open(state);
+    true
+}
+
+fn handle_open_rebase_plan(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('I')) {
+        return false;
+    }
+    rebase_plan::open(state);
+    true
+}
+
+fn handle_open_stashes(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('s')) {
+        return false;
+    }
+    stashes::open(state);
+    true
+}
+
+/// `c` saves what is staged as a staging recipe, `C` stages a saved one.
+fn handle_recipes(state: &mut AppState, input: &Input) -> bool {
+    match input {
+        Input::Character('c') => recipes::open_save(state),
+        Input::Character('C') => recipes::open_apply(state),
+        _ => return false,
+    }
+    true
+}
+
+/// `X` marks the selected file, or the hunk under the diff cursor, never to
+/// commit, or unmarks it. Whatever of it is staged is unstaged right away.
+fn handle_never_commit(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('X')) {
+        return false;
+    }
+    let line_cursor = state.main_screen.line_cursor;
+    let hunk_lines =
+        |file: &FileDiff| git_patch::find_hunk(file, line_cursor).map(|hunk| hunk.lines.clone());
+    let (file_name, hunk) = match state.focused_pane {
+        FocusedPane::Main => match state.current_main_item() {
+            Some(ListItem::File(file)) => (
+                file.file_name.clone(),
+                state
+                    .main_screen
+                    .is_diff_cursor_active
+                    .then(|| hunk_lines(file)),
+            ),
+            _ => return true,
+        },
+        FocusedPane::Unstaged => match state
+            .unstaged_pane
+            .list_items
+            .get(state.unstaged_pane.cursor)
+        {
+            Some(UnstagedListItem::File(file)) => (
+                file.file_name.clone(),
+                state
+                    .unstaged_pane
+                    .is_diff_cursor_active
+                    .then(|| hunk_lines(file)),
+            ),
+            Some(UnstagedListItem::UntrackedFile(file_name)) => (file_name.clone(), None),
+            _ => return true,
+        },
+    };
+
+    let message = match hunk {
+        Some(_) if state.refuse_partial_textconv(&file_name) => return true,
+        Some(lines) => match lines.and_then(|lines| state.never_commit.toggle_lines(&lines)) {
+            Some(true) => "Marked the added lines of this hunk never to commit".to_string(),
+            Some(false) => "Unmarked the lines of this hunk".to_string(),
+            None => {
+                state.set_message(Severity::Warning, "No added lines here to mark");
+                state.alert();
+                return true;
+            }
+        },
+        None if state.never_commit.toggle_file(&file_name) => {
+            format!("Marked {file_name} never to commit")
+        }
+        None => format!("Unmarked {file_name}"),
+    };
+    if let Err(e) = state.never_commit.save(&state.repo_path) {
+        state.set_message(
+            Severity::Error,
+            format!("Failed to save the never-commit rules: {e}"),
+        );
+        state.alert();
+        return true;
+    }
+
+    state.command_history.begin_group();
+    state.unstage_never_commit(true);
+    state.command_history.end_group();
+    // Tells how many changes were unstaged, if any.
+    let message = match state.message.take() {
+        Some(unstaged) => format!("{message}. {}", unstaged.text),
+        None => message,
+    };
+    state.set_message(Severity::Success, message);
+    true
+}
+
+/// `/` searches the diff of the focused pane. While a search is active, `n`
+/// and `N` move to the next and previous match and Esc ends it.
+fn handle_diff_search(state: &mut AppState, input: &Input) -> bool {
+    let has_search = match state.focused_pane {
+        FocusedPane::Main => state.main_screen.search.is_some(),
+        FocusedPane::Unstaged => state.unstaged_pane.search.is_some(),
+    };
+    match input {
+        Input::Character('/') => diff_search::open_prompt(state),
+        Input::Character('n') if has_search => diff_search::jump_to_next(state, true),
+        Input::Character('N') if has_search => diff_search::jump_to_next(state, false),
+        Input::Character('\u{1b}') if has_search => {
+            diff_search::clear(state);
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// `|` asks which status of files to show, and Esc shows all of them again.
+fn handle_status_filter(state: &mut AppState, input: &Input) -> bool {
+    match input {
+        Input::Character('|') => status_filter::open_menu(state),
+        Input::Character('\u{1b}') if state.status_filter.is_some() => {
+            status_filter::set(state, None);
+        }
+        _ => return false,
+    }
+    true
+}
+
+fn handle_open_discard_bin(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('T')) {
+        return false;
+    }
+    discard_bin::open(state);
+    true
+}
+
+/// `O` picks a commit to restore the selected file from.
+fn handle_open_restore_picker(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('O')) {
+        return false;
+    }
+    let file_name = match state.focused_pane {
+        FocusedPane::Main => state.current_main_file().map(|file| file.file_name.clone()),
+        FocusedPane::Unstaged => state.get_unstaged_file().map(|file| file.file_name.clone()),
+    };
+    if let Some(file_name) = file_name {
+        restore_picker::open(state, &file_name);
+    }
+    true
+}
+
+/// `W` lists the worktrees to switch to another one.
+fn handle_open_worktrees(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('W')) {
+        return false;
+    }
+    worktrees::open(state);
+    true
+}
+
+/// Ctrl-G lists what the repository needs to stay fast.
+fn handle_open_maintenance(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('\u{7}')) {
+        return false;
     }
-
+    maintenance::open(state);
     true
 }
 
-fn handle_main_discard(state: &mut AppState, input: &Input) -> bool {
-    if !matches!(input, Input::Character('!')) {
+fn handle_open_unified_list(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('U')) {
         return false;
     }
+    unified_list::open(state);
+    true
+}
 
-    if state.main_screen.is_diff_cursor_active {
-        if let Some(file) = state.current_main_file() {
-            let line_index = state.main_screen.line_cursor;
-            if let Some(hunk) = git_patch::find_hunk(file, line_index) {
-                let patch = git_patch::create_unstage_hunk_patch(file, hunk);
-                let command = Box::new(DiscardHunkCommand::new(state.repo_path.clone(), patch));
-                state.execute_and_refresh(command);
-            }
-        }
-    } else if let Some(file) = state.current_main_file().cloned() {
-        let is_new = file.status == FileStatus::Added;
-        let command = Box::new(DiscardFileCommand::new(
-            state.repo_path.clone(),
-            file.file_name.clone(),
-            is_new,
-        ));
-        state.execute_and_refresh(command);
+fn handle_open_diagnostics(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('D')) {
+        return false;
     }
-
+    diagnostics_view::open(state);
     true
 }
 
-fn handle_main_stage_toggle(state: &mut AppState, input: &Input) -> bool {
-    if !is_stage_toggle(input) {
+fn handle_open_message_log(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('l')) {
         return false;
     }
+    message_log_view::open(state);
+    true
+}
 
-    match state
-        .main_screen
-        .list_items
-        .get(state.main_screen.file_cursor)
-        .cloned()
-    {
-        Some(ListItem::StagedChangesHeader) => {
-            let command = Box::new(UnstageAllCommand::new(state.repo_path.clone()));
-            state.execute_and_refresh(command);
-        }
-        Some(ListItem::File(file)) => {
-            let line_index = state.main_screen.line_cursor;
-            if let Some(hunk) = git_patch::find_hunk(&file, line_index) {
-                let patch = git_patch::create_unstage_hunk_patch(&file, hunk);
-                let command = Box::new(ApplyPatchCommand::new(state.repo_path.clone(), patch));
-                state.execute_and_refresh(command);
-            } else {
-                let command = Box::new(UnstageFileCommand::new(
-                    state.repo_path.clone(),
-                    file.file_name.clone(),
-                ));
-                state.execute_and_refresh(command);
-            }
-        }
-        Some(ListItem::PreviousCommitInfo {
-            hash,
-            message,
-            is_on_remote,
-            is_fixup: _,
-        }) => {
-            if state.main_screen.is_diff_cursor_active
-                && state.jump_to_file_in_diff() {
-                    return true;
-                }
-            if !is_on_remote {
-                state.main_screen.amending_commit_hash = Some(hash.clone());
-
-                let current_index = state.main_screen.file_cursor;
-                if let Some(item) = state.main_screen.list_items.get_mut(current_index) {
-                    *item = ListItem::AmendingCommitMessageInput {
-                        hash: hash.clone(),
-                        message: message.clone(),
-                    };
-                }
-
-                if let Some(commit_input_index) = state
-                    .main_screen
-                    .list_items
-                    .iter()
-                    .position(|item| matches!(item, ListItem::CommitMessageInput))
-                {
-                    if let Some(ListItem::CommitMessageInput) =
-                        state.main_screen.list_items.get_mut(commit_input_index)
-                    {
-                        state.main_screen.commit_message.clear();
-                    }
-                }
-                state.main_screen.commit_cursor = message.chars().count();
-            }
-        }
-        _ => {}
+fn handle_bookmarks(state: &mut AppState, input: &Input, max_y: i32) -> bool {
+    match input {
+        Input::Character('b') => bookmarks::toggle(state),
+        Input::Character('\'') => bookmarks::jump(state, true, max_y),
+        Input::Character('"') => bookmarks::jump(state, false, max_y),
+        _ => return false,
     }
+    true
+}
 
+fn handle_hunk_jump(state: &mut AppState, input: &Input, max_y: i32) -> bool {
+    match input {
+        Input::Character(']') => hunk_jump::jump_to_hunk(state, true),
+        Input::Character('[') => hunk_jump::jump_to_hunk(state, false),
+        Input::Character('}') => hunk_jump::jump_to_file(state, true, max_y),
+        Input::Character('{') => hunk_jump::jump_to_file(state, false, max_y),
+        _ => return false,
+    }
     true
 }
 
-fn handle_main_stage_line(state: &mut AppState, input: &Input, max_y: i32) -> bool {
-    if !matches!(input, Input::Character('1')) {
+fn handle_command_prompt(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character(':')) {
         return false;
     }
-    unstage_line(state, max_y);
+    command_prompt::open(state);
     true
 }
 
-fn handle_main_stage_all(state: &mut AppState, input: &Input) -> bool {
-    if matches!(input, Input::Character('R')) {
-        let command = Box::new(StageAllCommand::new(state.repo_path.clone()));
-        state.execute_and_refresh(command);
-        return true;
+fn handle_stat_panel(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character(';')) {
+        return false;
     }
-    false
+    stat_panel::open(state);
+    true
 }
 
-fn handle_main_open_editor(state: &mut AppState, input: &Input) -> bool {
-    if !matches!(input, Input::Character('e')) {
+fn handle_commit_folds(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('*')) {
         return false;
     }
+    commit_folds::toggle_all(state);
+    true
+}
 
-    if let Some(file) = state.current_main_file() {
-        let line_number = if state.main_screen.is_diff_cursor_active {
-            git_patch::get_line_number(file, state.main_screen.line_cursor)
-        } else {
-            None
-        };
-        let file_path = state.repo_path.join(&file.file_name);
-        if let Some(path_str) = file_path.to_str() {
-            state.editor_request = Some(EditorRequest {
-                file_path: path_str.to_string(),
-                line_number,
-            });
-        }
+fn handle_open_help(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('?')) {
+        return false;
     }
+    help::open(state);
+    true
+}
 
+fn handle_open_final_review(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('v')) {
+        return false;
+    }
+    final_review::open(state);
     true
 }
 
@@ -1162,8 +4131,7 @@ fn handle_reorder_mode_input(state: &mut AppState, input: Input, max_y: i32, max
         .main_screen
         .list_items
         .get_mut(state.main_screen.file_cursor)
-    {
-        if let ListItem::EditingReorderCommit {
+        && let ListItem::EditingReorderCommit {
             current_text,
             cursor,
             original_message,
@@ -1173,44 +4141,43 @@ fn handle_reorder_mode_input(state: &mut AppState, input: Input, max_y: i32, max
             scroll_offset,
             scroll_extra_space,
         } = item
-        {
-            match input {
-                Input::Character('\n') => {
-                    // Enter
-                    *item = ListItem::PreviousCommitInfo {
-                        hash: hash.clone(),
-                        message: current_text.clone(),
-                        is_on_remote: *is_on_remote,
-                        is_fixup: *is_fixup, // Preserve fixup status
-                    };
-                }
-                Input::Character('\u{1b}') | Input::Character('\u{3}') => {
-                    // Esc or Ctrl+C
-                    *item = ListItem::PreviousCommitInfo {
-                        hash: hash.clone(),
-                        message: original_message.clone(),
-                        is_on_remote: *is_on_remote,
-                        is_fixup: *is_fixup, // Preserve fixup status
-                    };
-                }
-                _ => {
-                    commit_view::handle_generic_text_input(current_text, cursor, input);
-                    let (offset, extra_space) = commit_view::compute_scroll_for_prefix(
-                        current_text.as_str(),
-                        *cursor,
-                        max_x,
-                        " ● ",
-                    );
-                    *scroll_offset = offset;
-                    *scroll_extra_space = extra_space;
-                }
+    {
+        match input {
+            Input::Character('\n') => {
+                // Enter
+                *item = ListItem::PreviousCommitInfo {
+                    hash: hash.clone(),
+                    message: current_text.clone(),
+                    is_on_remote: *is_on_remote,
+                    is_fixup: *is_fixup, // Preserve fixup status
+                };
+            }
+            Input::Character('\u{1b}') | Input::Character('\u{3}') => {
+                // Esc or Ctrl+C
+                *item = ListItem::PreviousCommitInfo {
+                    hash: hash.clone(),
+                    message: original_message.clone(),
+                    is_on_remote: *is_on_remote,
+                    is_fixup: *is_fixup, // Preserve fixup status
+                };
+            }
+            _ => {
+                commit_view::handle_generic_text_input(current_text, cursor, input);
+                let (offset, extra_space) = commit_view::compute_scroll_for_prefix(
+                    current_text.as_str(),
+                    *cursor,
+                    max_x,
+                    " ● ",
+                );
+                *scroll_offset = offset;
+                *scroll_extra_space = extra_space;
             }
-            return;
         }
+        return;
     }
 
     match input {
-        Input::Character('q') => {
+        Input::Character('q') | Input::Character('\u{1b}') => {
             let current_hash = if let Some(item) = state
                 .main_screen
                 .list_items
@@ -1228,37 +4195,46 @@ fn handle_reorder_mode_input(state: &mut AppState, input: Input, max_y: i32, max
             state.main_screen.list_items =
                 state.main_screen.original_list_items_for_reorder.clone();
 
-            if let Some(hash) = current_hash {
-                if let Some(pos) = state.main_screen.list_items.iter().position(|item| {
+            if let Some(hash) = current_hash
+                && let Some(pos) = state.main_screen.list_items.iter().position(|item| {
                     if let ListItem::PreviousCommitInfo { hash: h, .. } = item {
                         h == &hash
                     } else {
                         false
                     }
-                }) {
-                    state.main_screen.file_cursor = pos;
-                }
+                })
+            {
+                state.main_screen.file_cursor = pos;
             }
 
             state.main_screen.is_reordering_commits = false;
+            state.main_screen.is_grabbing_commit = false;
             state.reorder_command_history = None;
         }
         Input::Character('\n') => {
             // Enter
-            let original_commits =
-                get_commits_from_list(&state.main_screen.original_list_items_for_reorder);
-            let reordered_commits = get_commits_from_list(&state.main_screen.list_items);
-
-            if original_commits != reordered_commits {
-                let command = Box::new(crate::command::ReorderCommitsCommand::new(
-                    state.repo_path.clone(),
-                    original_commits,
-                    reordered_commits,
-                ));
-                state.execute_and_refresh(command);
+            let command = reorder_command(state);
+            if command.original_commits == command.reordered_commits {
+                apply_reorder(state);
+            } else {
+                plan_preview::open(state, PlanSource::Reorder, &command.plan());
             }
-            state.main_screen.is_reordering_commits = false;
-            state.reorder_command_history = None;
+        }
+        Input::Character('g') => {
+            state.main_screen.is_grabbing_commit = !state.main_screen.is_grabbing_commit
+                && matches!(
+                    state.current_main_item(),
+                    Some(ListItem::PreviousCommitInfo {
+                        is_on_remote: false,
+                        ..
+                    })
+                );
+        }
+        Input::KeyUp | Input::KeyDown if state.main_screen.is_grabbing_commit => {
+            let delta = if input == Input::KeyUp { -1 } else { 1 };
+            move_reorder_commit(state, delta);
+            state.main_screen.diff_scroll = 0;
+            state.main_screen.line_cursor = 0;
         }
         Input::KeyUp | Input::Character('\u{10}') => {
             state.main_screen.file_cursor = state.main_screen.file_cursor.saturating_sub(1);
@@ -1310,24 +4286,332 @@ fn handle_reorder_mode_input(state: &mut AppState, input: Input, max_y: i32, max
         }
         Input::Character('<') => {
             let cursor_state = crate::cursor_state::CursorState::from_app_state(state);
-            if let Some(history) = &mut state.reorder_command_history {
-                if let Some(cursor) = history.undo(cursor_state) {
-                    cursor.apply_to_app_state(state);
-                }
+            if let Some(history) = &mut state.reorder_command_history
+                && let Some(cursor) = history.undo(cursor_state)
+            {
+                cursor.apply_to_app_state(state);
             }
         }
         Input::Character('>') => {
             let cursor_state = crate::cursor_state::CursorState::from_app_state(state);
-            if let Some(history) = &mut state.reorder_command_history {
-                if let Some(cursor) = history.redo(cursor_state) {
-                    cursor.apply_to_app_state(state);
-                }
+            if let Some(history) = &mut state.reorder_command_history
+                && let Some(cursor) = history.redo(cursor_state)
+            {
+                cursor.apply_to_app_state(state);
             }
         }
         _ => handle_navigation(state, input, max_y, max_x),
     }
 }
 
+fn reorder_command(state: &AppState) -> ReorderCommitsCommand {
+    ReorderCommitsCommand::new(
+        state.repo_path.clone(),
+        get_commits_from_list(&state.main_screen.original_list_items_for_reorder),
+        get_commits_from_list(&state.main_screen.list_items),
+    )
+}
+
+/// Rewrites the history into the order edited in reordering mode and leaves
+/// the mode.
+/// The steps that drop the local commit `hash`: the commit itself and the
+/// newer ones picked onto its parent, oldest first.
+fn drop_plan(state: &AppState, hash: &str) -> Vec<RebaseStep> {
+    let Some(index) = state
+        .previous_commits
+        .iter()
+        .position(|commit| commit.hash == hash)
+    else {
+        return Vec::new();
+    };
+    state.previous_commits[..=index]
+        .iter()
+        .rev()
+        .map(|commit| RebaseStep {
+            hash: commit.hash.clone(),
+            message: commit.message.clone(),
+            action: if commit.hash == hash {
+                RebaseAction::Drop
+            } else {
+                RebaseAction::Pick
+            },
+            new_message: None,
+        })
+        .collect()
+}
+
+/// Shows what dropping the selected local commit does to the history, to
+/// be confirmed with Enter.
+fn handle_drop_commit(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('d')) || state.main_screen.is_reordering_commits {
+        return false;
+    }
+    let Some(commit) = selected_commit(state).cloned() else {
+        state.alert();
+        state.set_message(Severity::Warning, "Select a local commit to drop it");
+        return true;
+    };
+    if commit.is_on_remote {
+        state.alert();
+        state.set_message(Severity::Warning, "Commits on a remote cannot be dropped");
+        return true;
+    }
+    let steps = drop_plan(state, &commit.hash);
+    plan_preview::open(state, PlanSource::DropCommit(commit.hash), &steps);
+    true
+}
+
+/// Asks for a new author of the selected local commit (`B`), starting from
+/// the current one.
+fn handle_change_author(state: &mut AppState, input: &Input) -> bool {
+    if !matches!(input, Input::Character('B')) || state.main_screen.is_reordering_commits {
+        return false;
+    }
+    let Some(commit) = selected_commit(state).cloned() else {
+        state.alert();
+        state.set_message(
+            Severity::Warning,
+            "Select a local commit to change its author",
+        );
+        return true;
+    };
+    if commit.is_on_remote {
+        state.alert();
+        state.set_message(Severity::Warning, "Commits on a remote cannot be changed");
+        return true;
+    }
+    state.prompt = Some(Prompt::new(
+        PromptAction::ChangeAuthor { hash: commit.hash },
+        "Author (empty: you, dated now; now: only dated now)",
+        &commit.author,
+    ));
+    true
+}
+
+/// Sets the author of the local commit `hash` to `author`, typed as
+/// `Name <email>`, keeping its date. An empty `author` resets it to the
+/// configured identity and the current date instead, and `now` only dates
+/// it now.
+pub fn change_author(state: &mut AppState, hash: &str, author: &str) {
+    let Some(index) = state
+        .previous_commits
+        .iter()
+        .position(|commit| commit.hash == hash)
+    else {
+        return;
+    };
+    let change = if author.is_empty() {
+        git::AuthorChange::Reset
+    } else if author == "now" {
+        git::AuthorChange::ResetDate
+    } else if author == state.previous_commits[index].author {
+        return;
+    } else if author
+        .strip_suffix('>')
+        .and_then(|rest| rest.split_once(" <"))
+        .is_some_and(|(name, email)| !name.trim().is_empty() && !email.is_empty())
+    {
+        git::AuthorChange::Set(author.to_string())
+    } else {
+        state.alert();
+        state.set_message(Severity::Warning, "Type the author as Name <email>");
+        return;
+    };
+
+    let snapshot = Snapshot::take(&state.repo_path);
+    let started = Instant::now();
+    let result = git::amend_commit_metadata(&state.repo_path, hash, &change);
+    let outcome = result.as_ref().map(|_| ()).map_err(|e| e.to_string());
+    state.notify_finished("Author change", started, outcome);
+    if let Err(e) = result {
+        state.set_message(
+            Severity::Error,
+            format!("Could not change the author of {hash}: {e}"),
+        );
+        let retry = Retry::ChangeAuthor {
+            hash: hash.to_string(),
+            author: author.to_string(),
+        };
+        rebase_recovery::open(
+            state,
+            "Author change",
+            Some(e.to_string()),
+            snapshot,
+            &[],
+            retry,
+        );
+        return;
+    }
+
+    state.refresh_diff(false);
+    if let Some(commit) = state.previous_commits.get(index).cloned() {
+        if let Some(position) = state.main_screen.list_items.iter().position(
+            |item| matches!(item, ListItem::PreviousCommitInfo { hash, .. } if *hash == commit.hash),
+        ) {
+            state.main_screen.file_cursor = position;
+            state.update_selected_commit_diff();
+        }
+        state.set_message(
+            Severity::Success,
+            match change {
+                git::AuthorChange::Set(_) => {
+                    format!("Set the author of {} to {}", commit.hash, commit.author)
+                }
+                git::AuthorChange::Reset => format!(
+                    "Reset the author of {} to {}, dated now",
+                    commit.hash, commit.author
+                ),
+                git::AuthorChange::ResetDate => format!("Dated {} now", commit.hash),
+            },
+        );
+    }
+}
+
+/// Drops the local commit `hash`, once its plan is confirmed. It is kept in
+/// the discard bin, so it can be picked back after `<` stops reaching it.
+pub fn apply_drop(state: &mut AppState, hash: &str) {
+    let Some(commit) = state
+        .previous_commits
+        .iter()
+        .find(|commit| commit.hash == hash)
+        .cloned()
+    else {
+        return;
+    };
+    let steps = drop_plan(state, hash);
+    let command = Box::new(DropCommitCommand::new(
+        state.repo_path.clone(),
+        steps.clone(),
+        commit.full_hash.clone(),
+    ));
+    let snapshot = Snapshot::take(&state.repo_path);
+    let started = Instant::now();
+    let succeeded = state.execute_and_refresh(command);
+    let outcome = if succeeded {
+        Ok(())
+    } else {
+        Err("history left unchanged".to_string())
+    };
+    state.notify_finished("Drop", started, outcome);
+    if succeeded {
+        discard_bin::record(state, vec![DiscardedChange::from_dropped_commit(&commit)]);
+        state.set_message(
+            Severity::Success,
+            format!(
+                "Dropped {} {}, < or the discard bin (T) bring it back",
+                commit.hash, commit.message
+            ),
+        );
+    } else {
+        state.set_message(
+            Severity::Error,
+            format!(
+                "Could not drop {} {}, the history is left unchanged",
+                commit.hash, commit.message
+            ),
+        );
+    }
+    if !succeeded {
+        let retry = Retry::Drop(hash.to_string());
+        rebase_recovery::open(state, "Drop", None, snapshot, &steps, retry);
+    }
+}
+
+pub fn apply_reorder(state: &mut AppState) {
+    let command = reorder_command(state);
+    state.main_screen.is_reordering_commits = false;
+    state.main_screen.is_grabbing_commit = false;
+    state.reorder_command_history = None;
+    if command.original_commits != command.reordered_commits {
+        run_reorder(state, command);
+    }
+}
+
+/// Rewrites the history into the new order, offering a way out if the
+/// rebase fails.
+pub fn run_reorder(state: &mut AppState, command: ReorderCommitsCommand) {
+    let retry = Retry::Reorder {
+        original: command.original_commits.clone(),
+        reordered: command.reordered_commits.clone(),
+    };
+    let steps = command.plan();
+    let snapshot = Snapshot::take(&state.repo_path);
+    let started = Instant::now();
+    let outcome = if state.execute_and_refresh(Box::new(command)) {
+        Ok(())
+    } else {
+        Err("history left unchanged".to_string())
+    };
+    let failed = outcome.is_err();
+    state.notify_finished("Reorder", started, outcome);
+    if failed {
+        rebase_recovery::open(state, "Reorder", None, snapshot, &steps, retry);
+    }
+}
+
+fn is_item_on_remote(item: &ListItem) -> bool {
+    match item {
+        ListItem::PreviousCommitInfo { is_on_remote, .. } => *is_on_remote,
+        ListItem::EditingReorderCommit { is_on_remote, .. } => *is_on_remote,
+        _ => false,
+    }
+}
+
+/// Swaps the selected commit with its neighbour above (`-1`) or below (`1`)
+/// and keeps it selected. Commits on a remote stay where they are.
+pub fn move_reorder_commit(state: &mut AppState, delta: isize) {
+    let cursor = state.main_screen.file_cursor;
+    let Some(target) = cursor.checked_add_signed(delta) else {
+        return;
+    };
+    let can_swap = match (
+        state.main_screen.list_items.get(cursor),
+        state.main_screen.list_items.get(target),
+    ) {
+        (Some(item1), Some(item2)) => !is_item_on_remote(item1) && !is_item_on_remote(item2),
+        _ => false,
+    };
+
+    if can_swap {
+        let command = Box::new(SwapCommitCommand::new(
+            &mut state.main_screen.list_items,
+            cursor,
+            target,
+        ));
+        state.execute_reorder_command(command);
+        state.main_screen.file_cursor = target;
+        warn_of_overlap(state, cursor);
+    }
+}
+
+/// Tells when the commit just moved past the one at `other` changes the
+/// same files, as the new order may then conflict.
+fn warn_of_overlap(state: &mut AppState, other: usize) {
+    let hash_at = |index: usize| match state.main_screen.list_items.get(index) {
+        Some(
+            ListItem::PreviousCommitInfo { hash, .. } | ListItem::EditingReorderCommit { hash, .. },
+        ) => Some(hash.clone()),
+        _ => None,
+    };
+    let (Some(moved), Some(other)) = (hash_at(state.main_screen.file_cursor), hash_at(other))
+    else {
+        return;
+    };
+    let shared = shared_files(state, &moved, &other);
+    let Some(first) = shared.first() else {
+        return;
+    };
+    let files = match shared.len() {
+        1 => first.clone(),
+        2 => format!("{first} and 1 more file"),
+        count => format!("{first} and {} more files", count - 1),
+    };
+    state.set_message(
+        Severity::Warning,
+        format!("{moved} and {other} both change {files}, the new order may conflict"),
+    );
+}
+
 fn get_commits_from_list(list: &[ListItem]) -> Vec<crate::git::CommitInfo> {
     list.iter()
         .filter_map(|item| match item {
@@ -1341,6 +4625,7 @@ fn get_commits_from_list(list: &[ListItem]) -> Vec<crate::git::CommitInfo> {
                 message: message.clone(),
                 is_on_remote: *is_on_remote,
                 is_fixup: *is_fixup,
+                ..Default::default()
             }),
             ListItem::EditingReorderCommit {
                 hash,
@@ -1352,6 +4637,7 @@ fn get_commits_from_list(list: &[ListItem]) -> Vec<crate::git::CommitInfo> {
                 message: current_text.clone(),
                 is_on_remote: *is_on_remote,
                 is_fixup: false, // Editing resets fixup status
+                ..Default::default()
             }),
             _ => None,
         })
@@ -1410,15 +4696,14 @@ fn handle_navigation(state: &mut AppState, input: Input, max_y: i32, max_x: i32)
             .list_items
             .iter()
             .position(|item| matches!(item, ListItem::AmendingCommitMessageInput { .. }))
+            && let Some(commit) = state.previous_commits.iter().find(|c| c.hash == hash)
         {
-            if let Some(commit) = state.previous_commits.iter().find(|c| c.hash == hash) {
-                state.main_screen.list_items[index] = ListItem::PreviousCommitInfo {
-                    hash: commit.hash.clone(),
-                    message: commit.message.clone(),
-                    is_on_remote: commit.is_on_remote,
-                    is_fixup: commit.is_fixup,
-                };
-            }
+            state.main_screen.list_items[index] = ListItem::PreviousCommitInfo {
+                hash: commit.hash.clone(),
+                message: commit.message.clone(),
+                is_on_remote: commit.is_on_remote,
+                is_fixup: commit.is_fixup,
+            };
         }
         state.main_screen.amending_commit_hash = None;
     }
@@ -1528,8 +4813,8 @@ fn handle_main_diff_move_down(state: &mut AppState, max_y: i32) {
         .get(state.main_screen.file_cursor)
     {
         Some(ListItem::File(file)) => file.lines.len(),
-        Some(ListItem::PreviousCommitInfo { .. }) => state
-            .selected_commit_files
+        Some(ListItem::RenameGroup { files, .. }) => files.iter().map(|f| f.lines.len()).sum(),
+        Some(ListItem::PreviousCommitInfo { .. }) => commit_folds::shown_files(state)
             .iter()
             .map(|f| f.lines.len())
             .sum(),
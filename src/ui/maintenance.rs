@@ -0,0 +1,159 @@
+use crate::app_state::{AppState, Screen};
+use crate::git::StatusCaches;
+use crate::maintenance::{self, RepoHealth, Suggestion};
+use crate::message_log::Severity;
+use crate::ui::chrome;
+use crate::ui::layout;
+use pancurses::{COLOR_PAIR, Input, Window};
+
+/// Lines above the suggestions.
+const BANNER_HEIGHT: usize = 1;
+
+const TITLE: &str = "Maintenance";
+
+const HINTS: &str = "Enter run  q close";
+
+/// Rows of each suggestion: the problem, then the command.
+const SUGGESTION_HEIGHT: usize = 2;
+
+/// Screen listing what slows the repository down or risks it, opened with
+/// Ctrl-G, each with the git command fixing it.
+pub struct MaintenancePanel {
+    pub suggestions: Vec<Suggestion>,
+    pub cursor: usize,
+}
+
+impl MaintenancePanel {
+    pub fn selected(&self) -> Option<&Suggestion> {
+        self.suggestions.get(self.cursor)
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        let last = self.suggestions.len().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+    }
+}
+
+pub fn open(state: &mut AppState) {
+    state.screen = Some(Screen::Maintenance(MaintenancePanel {
+        suggestions: maintenance::suggestions(&RepoHealth::read(&state.repo_path)),
+        cursor: 0,
+    }));
+}
+
+/// The status line message telling about the most pressing problem when
+/// the view starts, if there is one.
+pub fn startup_hint(state: &AppState) -> Option<String> {
+    let suggestions = maintenance::suggestions(&RepoHealth::read(&state.repo_path));
+    let first = suggestions.first()?;
+    Some(format!(
+        "{}. {} fixes it ({} shows the maintenance)",
+        first.problem,
+        first.command(),
+        state.keymap.key_label("maintenance")
+    ))
+}
+
+/// Starts the command of the selected suggestion in the background, one
+/// at a time.
+fn run_selected(state: &mut AppState) {
+    let Some(Screen::Maintenance(panel)) = &state.screen else {
+        return;
+    };
+    let Some(suggestion) = panel.selected().cloned() else {
+        return;
+    };
+    if let Some(running) = &state.maintenance_running {
+        state.set_message(Severity::Warning, format!("{running} is still running"));
+        state.alert();
+        return;
+    }
+    state.maintenance_running = Some(suggestion.command());
+    state.set_message(Severity::Info, format!("Running {}…", suggestion.command()));
+    state
+        .background_worker
+        .maintain(state.repo_path.clone(), suggestion);
+}
+
+/// Tells how the command went, and checks the repository again if the
+/// panel is still open.
+pub fn finish(state: &mut AppState, command: String, result: Result<String, String>) {
+    state.maintenance_running = None;
+    match result {
+        Ok(message) => state.set_message(Severity::Success, message),
+        Err(e) => state.set_message(Severity::Error, format!("{command} failed: {e}")),
+    }
+    state.status_caches = StatusCaches::read(&state.repo_path);
+    if let Some(Screen::Maintenance(panel)) = &mut state.screen {
+        panel.suggestions = maintenance::suggestions(&RepoHealth::read(&state.repo_path));
+        panel.move_cursor(0);
+    }
+}
+
+pub fn handle_input(state: &mut AppState, input: Input) {
+    let Some(Screen::Maintenance(panel)) = state.screen.as_mut() else {
+        return;
+    };
+
+    match input {
+        Input::Character('q')
+        | Input::Character('\u{7}')
+        | Input::Character('\t')
+        | Input::Character('\u{1b}') => {
+            state.screen = None;
+        }
+        Input::Character('\n') => run_selected(state),
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => panel.move_cursor(1),
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => panel.move_cursor(-1),
+        _ => {}
+    }
+}
+
+pub fn render(window: &Window, repo: &str, panel: &MaintenancePanel, error_message: Option<&str>) {
+    let (max_y, max_x) = window.get_max_yx();
+    let width = max_x.max(0) as usize;
+
+    chrome::render_title(window, repo, &[TITLE], HINTS);
+
+    if panel.suggestions.is_empty() {
+        window.mvaddstr(
+            BANNER_HEIGHT as i32,
+            1,
+            layout::truncate_to_width(
+                "Nothing to do, the repository needs no maintenance",
+                width.saturating_sub(1),
+            ),
+        );
+    }
+    let rows = (max_y.max(0) as usize).saturating_sub(BANNER_HEIGHT + 1) / SUGGESTION_HEIGHT;
+    // Keeps the selection on the screen.
+    let scroll = (panel.cursor + 1).saturating_sub(rows.max(1));
+    let visible = panel.suggestions.iter().enumerate().skip(scroll).take(rows);
+    for (row, (index, suggestion)) in visible.enumerate() {
+        let y = (BANNER_HEIGHT + row * SUGGESTION_HEIGHT) as i32;
+        let pair = if index == panel.cursor { 5 } else { 1 };
+        window.attron(COLOR_PAIR(pair));
+        for line in 0..SUGGESTION_HEIGHT as i32 {
+            for x in 0..max_x {
+                window.mvaddch(y + line, x, ' ');
+            }
+        }
+        window.mvaddstr(
+            y,
+            1,
+            layout::truncate_to_width(&suggestion.problem, width.saturating_sub(1)),
+        );
+        window.mvaddstr(
+            y + 1,
+            3,
+            layout::truncate_to_width(&suggestion.command(), width.saturating_sub(3)),
+        );
+        window.attroff(COLOR_PAIR(pair));
+    }
+
+    if let Some(error) = error_message {
+        window.attron(COLOR_PAIR(2));
+        window.mvaddstr(max_y - 1, 0, layout::truncate_to_width(error, width));
+        window.attroff(COLOR_PAIR(2));
+    }
+}
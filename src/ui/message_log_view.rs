@@ -0,0 +1,105 @@
+use crate::app_state::{AppState, Screen};
+use crate::message_log::{self, MessageLog};
+use crate::ui::chrome;
+use crate::ui::color;
+use crate::ui::layout;
+use pancurses::{COLOR_PAIR, Input, Window};
+
+const TITLE: &str = "Messages";
+
+const HINTS: &str = "q close";
+
+/// Lines above the messages.
+const BANNER_HEIGHT: usize = 1;
+
+/// Screen listing the status line messages of this session, the newest at
+/// the bottom, colored by severity.
+pub struct MessageLogView {
+    /// Lines scrolled up from the newest message, so messages coming in
+    /// while it is open show up at the bottom.
+    pub from_end: usize,
+}
+
+impl MessageLogView {
+    fn content_height(max_y: i32) -> usize {
+        (max_y.max(0) as usize).saturating_sub(BANNER_HEIGHT + 1)
+    }
+
+    fn scroll_by(&mut self, delta: isize, log: &MessageLog, max_y: i32) {
+        let max_from_end = log
+            .entries()
+            .len()
+            .saturating_sub(Self::content_height(max_y));
+        self.from_end = self
+            .from_end
+            .saturating_add_signed(-delta)
+            .min(max_from_end);
+    }
+}
+
+pub fn open(state: &mut AppState) {
+    state.screen = Some(Screen::MessageLog(MessageLogView { from_end: 0 }));
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let Some(Screen::MessageLog(view)) = state.screen.as_mut() else {
+        return;
+    };
+    let page = MessageLogView::content_height(max_y).max(1) as isize;
+    let log = &state.message_log;
+
+    match input {
+        Input::Character('q')
+        | Input::Character('l')
+        | Input::Character('\u{1b}')
+        | Input::Character('\t') => state.screen = None,
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            view.scroll_by(1, log, max_y)
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            view.scroll_by(-1, log, max_y)
+        }
+        Input::Character(' ') | Input::Character('\u{16}') | Input::KeyNPage => {
+            view.scroll_by(page, log, max_y)
+        }
+        Input::Character('b') | Input::KeyPPage => view.scroll_by(-page, log, max_y),
+        _ => {}
+    }
+}
+
+pub fn render(window: &Window, repo: &str, view: &MessageLogView, log: &MessageLog) {
+    let (max_y, max_x) = window.get_max_yx();
+    let width = max_x.max(0) as usize;
+
+    chrome::render_title(window, repo, &[TITLE], HINTS);
+
+    let entries = log.entries();
+    if entries.is_empty() {
+        window.mvaddstr(
+            BANNER_HEIGHT as i32,
+            1,
+            layout::truncate_to_width("No messages yet", width.saturating_sub(1)),
+        );
+        return;
+    }
+    let height = MessageLogView::content_height(max_y);
+    let end = entries.len().saturating_sub(view.from_end);
+    let start = end.saturating_sub(height);
+    for (row, entry) in entries[start..end].iter().enumerate() {
+        let y = (BANNER_HEIGHT + row) as i32;
+        let age = message_log::age(entry.at.elapsed());
+        window.attron(COLOR_PAIR(9));
+        window.mvaddstr(y, 1, format!("{age:>4}"));
+        window.attroff(COLOR_PAIR(9));
+
+        let pair = COLOR_PAIR(color::message_pair(entry.severity, false));
+        window.attron(pair);
+        let line = format!("{:<5} {}", entry.severity.label(), entry.text);
+        window.mvaddstr(
+            y,
+            6,
+            layout::truncate_to_width(&line, width.saturating_sub(6)),
+        );
+        window.attroff(pair);
+    }
+}
@@ -0,0 +1,212 @@
+use crate::app_state::{AppState, FocusedPane, Modal};
+use crate::git;
+use crate::git_patch;
+use crate::message_log::Severity;
+use crate::ui::layout;
+use pancurses::{A_BOLD, COLOR_PAIR, Input, Window};
+use unicode_width::UnicodeWidthStr;
+
+const TITLE: &str = " PATCH  Esc/q close ";
+
+/// Popup showing a patch generated from the diff, how it would be applied
+/// and what `git apply --check` says about it.
+pub struct PatchPreview {
+    pub lines: Vec<String>,
+    pub scroll: usize,
+}
+
+/// The `git apply` command applying a patch with these flags, as
+/// [`git::apply_patch`] runs it.
+fn apply_command(reverse: bool, cached: bool) -> String {
+    let mut command = "git apply".to_string();
+    if cached {
+        command.push_str(" --cached");
+    }
+    if reverse {
+        command.push_str(" --reverse");
+    }
+    command.push_str(" --unidiff-zero");
+    command
+}
+
+fn open(
+    state: &mut AppState,
+    what: &str,
+    patch: &str,
+    (reverse, cached): (bool, bool),
+    checked: &Result<(), String>,
+) {
+    let mut lines = vec![format!("{what}: {}", apply_command(reverse, cached))];
+    match checked {
+        Ok(()) => lines.push("git apply --check: the patch applies".to_string()),
+        Err(e) => {
+            lines.push("git apply --check failed:".to_string());
+            lines.extend(e.lines().map(str::to_string));
+        }
+    }
+    lines.push(String::new());
+    lines.extend(patch.lines().map(str::to_string));
+    state.modal = Some(Modal::PatchPreview(PatchPreview { lines, scroll: 0 }));
+}
+
+/// Whether `patch` applies with `reverse` and `cached`, checked with
+/// `git apply --check` before anything is changed. If not, the patch and
+/// git's error are shown, telling that `what` is refused.
+pub fn check(state: &mut AppState, what: &str, patch: &str, reverse: bool, cached: bool) -> bool {
+    let checked = git::check_apply_patch(&state.repo_path, patch, reverse, cached);
+    let Err(e) = &checked else {
+        return true;
+    };
+    state.set_message(
+        Severity::Error,
+        format!(
+            "{what} failed, the patch does not apply: {}",
+            e.lines().next().unwrap_or_default()
+        ),
+    );
+    state.alert();
+    open(state, what, patch, (reverse, cached), &checked);
+    false
+}
+
+/// Shows the patch staging or unstaging the hunk under the diff cursor
+/// would apply, and whether it applies.
+pub fn open_at_cursor(state: &mut AppState) {
+    let (file, is_diff_cursor_active, unstaging) = match state.focused_pane {
+        FocusedPane::Unstaged => (
+            state.get_unstaged_file(),
+            state.unstaged_pane.is_diff_cursor_active,
+            false,
+        ),
+        FocusedPane::Main => (
+            state.current_main_file(),
+            state.main_screen.is_diff_cursor_active,
+            true,
+        ),
+    };
+    let preview = file.filter(|_| is_diff_cursor_active).and_then(|file| {
+        let hunk = git_patch::find_hunk(file, state.main_screen.line_cursor)?;
+        Some(if unstaging {
+            (
+                format!("Unstaging the hunk of {}", file.file_name),
+                git_patch::create_unstage_hunk_patch(file, hunk),
+            )
+        } else {
+            (
+                format!("Staging the hunk of {}", file.file_name),
+                git_patch::create_stage_hunk_patch(file, hunk),
+            )
+        })
+    });
+    let Some((what, patch)) = preview else {
+        state.alert();
+        state.set_message(
+            Severity::Warning,
+            "Move to a hunk with j/k to preview its patch",
+        );
+        return;
+    };
+    let checked = git::check_apply_patch(&state.repo_path, &patch, unstaging, true);
+    open(state, &what, &patch, (unstaging, true), &checked);
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let Some(Modal::PatchPreview(preview)) = state.modal.as_mut() else {
+        return;
+    };
+    let line_count = preview.lines.len();
+    let max_scroll = line_count.saturating_sub(content_height(max_y, line_count));
+    let page = content_height(max_y, line_count).max(1);
+
+    match input {
+        Input::Character('q') | Input::Character('\u{1b}') => state.modal = None,
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            preview.scroll = (preview.scroll + 1).min(max_scroll);
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            preview.scroll = preview.scroll.saturating_sub(1);
+        }
+        Input::Character(' ') | Input::Character('\u{16}') | Input::KeyNPage => {
+            preview.scroll = (preview.scroll + page).min(max_scroll);
+        }
+        Input::Character('b') | Input::KeyPPage => {
+            preview.scroll = preview.scroll.saturating_sub(page);
+        }
+        _ => {}
+    }
+}
+
+/// Rows available for the lines inside the frame.
+fn content_height(max_y: i32, line_count: usize) -> usize {
+    line_count.min((max_y.max(0) as usize).saturating_sub(4))
+}
+
+/// Colors patch lines like the diff, and git's errors in red.
+fn line_pair(line: &str) -> u32 {
+    if line.starts_with("@@") {
+        4
+    } else if line.starts_with('+') {
+        3
+    } else if line.starts_with('-') || line.starts_with("error:") || line.starts_with("fatal:") {
+        2
+    } else {
+        1
+    }
+}
+
+/// Draws the patch in a frame over the main screen.
+pub fn render(window: &Window, preview: &PatchPreview) {
+    let (max_y, max_x) = window.get_max_yx();
+    let longest = preview
+        .lines
+        .iter()
+        .map(|line| line.width())
+        .chain(std::iter::once(TITLE.len()))
+        .max()
+        .unwrap_or(0);
+    let width = (longest + 4).min(max_x.max(0) as usize);
+    let height = content_height(max_y, preview.lines.len()) + 2;
+    let left = layout::centered_x(width, max_x);
+    let top = ((max_y.max(0) as usize).saturating_sub(height) / 2) as i32;
+    let inner_width = width.saturating_sub(4);
+
+    window.attron(COLOR_PAIR(1));
+    for row in 0..height {
+        let y = top + row as i32;
+        let (edge, fill) = if row == 0 || row + 1 == height {
+            ('+', '-')
+        } else {
+            ('|', ' ')
+        };
+        window.mvaddch(y, left, edge);
+        for x in 1..width.saturating_sub(1) {
+            window.mvaddch(y, left + x as i32, fill);
+        }
+        window.mvaddch(y, left + width as i32 - 1, edge);
+    }
+    window.attroff(COLOR_PAIR(1));
+    for (row, line) in preview
+        .lines
+        .iter()
+        .skip(preview.scroll)
+        .take(height.saturating_sub(2))
+        .enumerate()
+    {
+        let pair = line_pair(line);
+        window.attron(COLOR_PAIR(pair));
+        window.mvaddstr(
+            top + 1 + row as i32,
+            left + 2,
+            layout::truncate_to_width(line, inner_width),
+        );
+        window.attroff(COLOR_PAIR(pair));
+    }
+
+    window.attron(COLOR_PAIR(8) | A_BOLD);
+    window.mvaddstr(
+        top,
+        left + 1,
+        layout::truncate_to_width(TITLE, width.saturating_sub(2)),
+    );
+    window.attroff(COLOR_PAIR(8) | A_BOLD);
+}
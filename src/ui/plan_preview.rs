@@ -0,0 +1,217 @@
+use crate::app_state::{AppState, Modal};
+use crate::git::{self, RebaseStep};
+use crate::message_log::Severity;
+use crate::ui::layout;
+use crate::ui::main_screen;
+use crate::ui::prompt::{Prompt, PromptAction};
+use crate::ui::rebase_plan;
+use crate::util::repo_path;
+use pancurses::{A_BOLD, COLOR_PAIR, Input, Window};
+use std::path::Path;
+use unicode_width::UnicodeWidthStr;
+
+const TITLE: &str = " REWRITE HISTORY? Enter run  w save  Esc/q back ";
+
+/// Where the plan is saved unless another path is typed, relative to the
+/// repository root.
+pub const DEFAULT_SAVE_PATH: &str = ".git/rebase-plan.txt";
+
+/// The same file inside the git directory of `repo_path`, which is not
+/// `.git` in a linked worktree. Relative to the root when it lies below it.
+pub fn default_save_path(repo_path: &Path) -> String {
+    match git::git_path(repo_path, "rebase-plan.txt") {
+        Ok(path) => path
+            .strip_prefix(repo_path)
+            .unwrap_or(&path)
+            .display()
+            .to_string(),
+        Err(_) => DEFAULT_SAVE_PATH.to_string(),
+    }
+}
+
+/// The operation that generated the plan and runs once it is confirmed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlanSource {
+    /// The commit order edited in reordering mode.
+    Reorder,
+    /// The rebase plan view.
+    RebasePlan,
+    /// The local commit with this abbreviated hash, dropped with `d`.
+    DropCommit(String),
+}
+
+/// Modal showing the todo list of a history rewrite before it runs.
+pub struct PlanPreview {
+    pub source: PlanSource,
+    pub todo: String,
+    /// What running the plan in a temporary worktree came to, shown below
+    /// the todo lines.
+    pub prediction: String,
+    pub scroll: usize,
+}
+
+impl PlanPreview {
+    fn lines(&self) -> Vec<&str> {
+        let mut lines: Vec<&str> = self.todo.lines().collect();
+        lines.push("");
+        lines.push(&self.prediction);
+        lines
+    }
+}
+
+pub fn open(state: &mut AppState, source: PlanSource, steps: &[RebaseStep]) {
+    state.modal = Some(Modal::PlanPreview(PlanPreview {
+        source,
+        todo: git::format_todo(steps),
+        prediction: prediction(&state.repo_path, steps),
+        scroll: 0,
+    }));
+}
+
+/// Runs `steps` away from the branch and tells where they would conflict.
+pub fn prediction(repo_path: &Path, steps: &[RebaseStep]) -> String {
+    match git::predict_rebase_conflict(repo_path, steps) {
+        Ok(None) => "Trial run: applies cleanly".to_string(),
+        Ok(Some(conflict)) if conflict.files.is_empty() => {
+            format!("Trial run: stops at {} {}", conflict.hash, conflict.message)
+        }
+        Ok(Some(conflict)) => format!(
+            "Trial run: {} {} conflicts in {}",
+            conflict.hash,
+            conflict.message,
+            conflict.files.join(", ")
+        ),
+        Err(e) => format!("Trial run failed: {e}"),
+    }
+}
+
+/// Writes the previewed plan to `path`, relative to the repository root.
+pub fn save(state: &mut AppState, path: &str) {
+    let Some(Modal::PlanPreview(preview)) = state.modal.as_ref() else {
+        return;
+    };
+    if path.is_empty() {
+        return;
+    }
+    // The default may lie in the git directory of a linked worktree, out
+    // of the worktree itself.
+    let refusal = if path == default_save_path(&state.repo_path) {
+        repo_path::existing_refusal(&state.repo_path, path)
+    } else {
+        repo_path::new_path_refusal(&state.repo_path, path)
+    };
+    if let Some(refusal) = refusal {
+        state.set_message(
+            Severity::Warning,
+            format!("Cannot save the plan: {refusal}"),
+        );
+        state.alert();
+        return;
+    }
+    let full_path = state.repo_path.join(path);
+    match write_plan(&full_path, &preview.todo) {
+        Ok(()) => state.set_message(Severity::Success, format!("Saved the plan to {path}")),
+        Err(e) => state.set_message(Severity::Error, format!("Failed to save the plan: {e}")),
+    }
+}
+
+fn write_plan(path: &Path, todo: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, todo)
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let Some(Modal::PlanPreview(preview)) = state.modal.as_mut() else {
+        return;
+    };
+    let line_count = preview.lines().len();
+    let max_scroll = line_count.saturating_sub(content_height(max_y, line_count));
+
+    match input {
+        Input::Character('q') | Input::Character('\u{1b}') => state.modal = None,
+        Input::Character('\n') => {
+            let source = preview.source.clone();
+            state.modal = None;
+            match source {
+                PlanSource::Reorder => main_screen::apply_reorder(state),
+                PlanSource::RebasePlan => rebase_plan::run(state),
+                PlanSource::DropCommit(hash) => main_screen::apply_drop(state, &hash),
+            }
+        }
+        Input::Character('w') => {
+            state.prompt = Some(Prompt::new(
+                PromptAction::SavePlan,
+                "Save plan to",
+                &default_save_path(&state.repo_path),
+            ));
+        }
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            preview.scroll = (preview.scroll + 1).min(max_scroll);
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            preview.scroll = preview.scroll.saturating_sub(1);
+        }
+        _ => {}
+    }
+}
+
+/// Rows available for the lines inside the frame.
+fn content_height(max_y: i32, line_count: usize) -> usize {
+    line_count.min((max_y.max(0) as usize).saturating_sub(4))
+}
+
+/// Draws the plan in a frame over whatever screen is below it, which also
+/// shows the messages on its bottom line.
+pub fn render(window: &Window, preview: &PlanPreview) {
+    let (max_y, max_x) = window.get_max_yx();
+    let lines = preview.lines();
+    let longest = lines
+        .iter()
+        .map(|line| line.width())
+        .chain(std::iter::once(TITLE.len()))
+        .max()
+        .unwrap_or(0);
+    let width = (longest + 4).min(max_x.max(0) as usize);
+    let height = content_height(max_y, lines.len()) + 2;
+    let left = layout::centered_x(width, max_x);
+    let top = ((max_y.max(0) as usize).saturating_sub(height) / 2) as i32;
+    let inner_width = width.saturating_sub(4);
+
+    window.attron(COLOR_PAIR(1));
+    for row in 0..height {
+        let y = top + row as i32;
+        let (edge, fill) = if row == 0 || row + 1 == height {
+            ('+', '-')
+        } else {
+            ('|', ' ')
+        };
+        window.mvaddch(y, left, edge);
+        for x in 1..width.saturating_sub(1) {
+            window.mvaddch(y, left + x as i32, fill);
+        }
+        window.mvaddch(y, left + width as i32 - 1, edge);
+    }
+    for (row, line) in lines
+        .iter()
+        .skip(preview.scroll)
+        .take(height.saturating_sub(2))
+        .enumerate()
+    {
+        window.mvaddstr(
+            top + 1 + row as i32,
+            left + 2,
+            layout::truncate_to_width(line, inner_width),
+        );
+    }
+    window.attroff(COLOR_PAIR(1));
+
+    window.attron(COLOR_PAIR(8) | A_BOLD);
+    window.mvaddstr(
+        top,
+        left + 1,
+        layout::truncate_to_width(TITLE, width.saturating_sub(2)),
+    );
+    window.attroff(COLOR_PAIR(8) | A_BOLD);
+}
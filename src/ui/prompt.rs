@@ -0,0 +1,234 @@
+use crate::app_state::{AppState, EditorRequest, PendingStage};
+use crate::command::{CreateFileCommand, RenameFileCommand};
+use crate::message_log::Severity;
+use crate::ui::command_prompt;
+use crate::ui::commit_view;
+use crate::ui::diff_search;
+use crate::ui::main_screen::{self, ListItem, UnstagedListItem};
+use crate::ui::plan_preview;
+use crate::ui::rebase_plan;
+use crate::ui::recipes;
+use crate::ui::stashes;
+use crate::util::repo_path;
+use pancurses::{Input, Window};
+
+/// What to do with the text once the prompt is confirmed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PromptAction {
+    RenameFile {
+        from: String,
+    },
+    /// Creates a file, or a directory when the path ends with `/`.
+    CreateFile,
+    /// Sets the new message of a step in the rebase plan.
+    RewordPlanStep {
+        index: usize,
+    },
+    /// Saves the previewed rebase plan to a file.
+    SavePlan,
+    /// Stashes the unstaged changes with the typed message.
+    CreateStash,
+    /// Searches the diff of the focused pane.
+    SearchDiff,
+    /// Saves what is staged as a staging recipe with the typed name.
+    SaveRecipe,
+    /// Stages the staging recipe with the typed name.
+    ApplyRecipe,
+    /// Sets the author of a local commit, or resets it when left empty.
+    ChangeAuthor {
+        hash: String,
+    },
+    /// Applies the patch file at the typed path, to the index as well when
+    /// `staged`.
+    ApplyPatch {
+        staged: bool,
+    },
+    /// Writes an exported patch to the typed path.
+    SavePatch {
+        what: String,
+        patch: String,
+    },
+    /// Runs the typed command, like `unstage 2-4`.
+    RunCommand,
+}
+
+/// A single-line text prompt shown at the bottom of the screen.
+#[derive(Clone, Debug)]
+pub struct Prompt {
+    pub action: PromptAction,
+    pub label: String,
+    pub text: String,
+    pub cursor: usize,
+}
+
+impl Prompt {
+    pub fn new(action: PromptAction, label: &str, initial_text: &str) -> Self {
+        Self {
+            action,
+            label: label.to_string(),
+            text: initial_text.to_string(),
+            cursor: initial_text.chars().count(),
+        }
+    }
+
+    fn prefix(&self) -> String {
+        format!(" {}: ", self.label)
+    }
+}
+
+pub fn handle_input(state: &mut AppState, input: Input) {
+    let Some(prompt) = state.prompt.as_mut() else {
+        return;
+    };
+
+    match input {
+        Input::Character('\u{1b}') | Input::Character('\u{3}') => {
+            state.prompt = None;
+        }
+        Input::Character('\n') => {
+            if let Some(prompt) = state.prompt.take() {
+                submit(state, prompt);
+            }
+        }
+        _ => commit_view::handle_generic_text_input(&mut prompt.text, &mut prompt.cursor, input),
+    }
+}
+
+pub fn handle_alt_input(state: &mut AppState, input: Input) {
+    if let Some(prompt) = state.prompt.as_mut() {
+        commit_view::handle_generic_text_input_with_alt(
+            &mut prompt.text,
+            &mut prompt.cursor,
+            input,
+        );
+    }
+}
+
+fn submit(state: &mut AppState, prompt: Prompt) {
+    match prompt.action {
+        PromptAction::RenameFile { from } => rename_file(state, &from, prompt.text.trim()),
+        PromptAction::CreateFile => create_file(state, prompt.text.trim()),
+        PromptAction::RewordPlanStep { index } => {
+            rebase_plan::set_reword_message(state, index, prompt.text.trim())
+        }
+        PromptAction::SavePlan => plan_preview::save(state, prompt.text.trim()),
+        PromptAction::CreateStash => stashes::create(state, prompt.text.trim()),
+        PromptAction::SearchDiff => diff_search::start(state, &prompt.text),
+        PromptAction::SaveRecipe => recipes::save(state, prompt.text.trim()),
+        PromptAction::ApplyRecipe => recipes::apply(state, prompt.text.trim()),
+        PromptAction::ChangeAuthor { hash } => {
+            main_screen::change_author(state, &hash, prompt.text.trim())
+        }
+        PromptAction::ApplyPatch { staged } => {
+            main_screen::apply_patch_file(state, prompt.text.trim(), staged)
+        }
+        PromptAction::SavePatch { what, patch } => {
+            main_screen::save_patch(state, &what, &patch, prompt.text.trim())
+        }
+        PromptAction::RunCommand => command_prompt::run(state, prompt.text.trim()),
+    }
+}
+
+fn rename_file(state: &mut AppState, from: &str, to: &str) {
+    if to.is_empty() || to == from {
+        return;
+    }
+    if let Some(refusal) = repo_path::new_path_refusal(&state.repo_path, to) {
+        state.set_message(Severity::Warning, format!("Cannot rename: {refusal}"));
+        state.alert();
+        return;
+    }
+
+    let command = Box::new(RenameFileCommand::new(
+        state.repo_path.clone(),
+        from.to_string(),
+        to.to_string(),
+    ));
+    state.execute_and_refresh(command);
+
+    if let Some(index) = state
+        .main_screen
+        .list_items
+        .iter()
+        .position(|item| matches!(item, ListItem::File(f) if f.file_name == to))
+    {
+        state.main_screen.file_cursor = index;
+    }
+    if let Some(index) = state
+        .unstaged_pane
+        .list_items
+        .iter()
+        .position(|item| matches!(item, UnstagedListItem::File(f) if f.file_name == to))
+    {
+        state.unstaged_pane.cursor = index;
+    }
+}
+
+fn create_file(state: &mut AppState, path: &str) {
+    let is_dir = path.ends_with('/');
+    let path = path.trim_end_matches('/');
+    if path.is_empty() {
+        return;
+    }
+    if let Some(refusal) = repo_path::new_path_refusal(&state.repo_path, path) {
+        state.set_message(Severity::Warning, format!("Cannot create: {refusal}"));
+        state.alert();
+        return;
+    }
+    let full_path = state.repo_path.join(path);
+
+    if is_dir {
+        let command = Box::new(CreateFileCommand::new_dir(
+            state.repo_path.clone(),
+            path.to_string(),
+        ));
+        state.execute_and_refresh(command);
+        return;
+    }
+
+    let content = state.config.template_for(path);
+    let command = Box::new(CreateFileCommand::new_file(
+        state.repo_path.clone(),
+        path.to_string(),
+        content.clone(),
+    ));
+    state.execute_and_refresh(command);
+
+    if full_path.exists()
+        && let Some(path_str) = full_path.to_str()
+    {
+        state.editor_request = Some(EditorRequest {
+            file_path: path_str.to_string(),
+            line_number: None,
+            stage_if_saved: Some(PendingStage {
+                file_name: path.to_string(),
+                initial_content: content,
+            }),
+            edit_hunk: None,
+            edit_message: None,
+            stage_edit: None,
+            return_to: None,
+        });
+    } else {
+        state.set_message(Severity::Error, format!("Failed to create {path}"));
+    }
+}
+
+/// Draws the prompt on the last line and returns the caret position.
+pub fn render(window: &Window, prompt: &Prompt, max_y: i32, max_x: i32) -> (i32, i32) {
+    let prefix = prompt.prefix();
+    let (scroll_offset, scroll_extra_space) =
+        commit_view::compute_scroll_for_prefix(prompt.text.as_str(), prompt.cursor, max_x, &prefix);
+    let (x, y) = commit_view::render_editor(
+        window,
+        &prompt.text,
+        prompt.cursor,
+        true,
+        max_y - 1,
+        max_x,
+        &prefix,
+        scroll_offset,
+        scroll_extra_space,
+    );
+    (y, x)
+}
@@ -0,0 +1,235 @@
+use crate::app_state::{AppState, Screen};
+use crate::git::{self, RebaseAction, RebaseStep};
+use crate::message_log::Severity;
+use crate::ui::chrome;
+use crate::ui::layout;
+use crate::ui::plan_preview::{self, PlanSource};
+use crate::ui::prompt::{Prompt, PromptAction};
+use crate::ui::rebase_recovery::{self, Retry, Snapshot};
+use pancurses::{COLOR_PAIR, Input, Window};
+use std::time::Instant;
+
+const TITLE: &str = "Rebase plan";
+
+const HINTS: &str = "p pick  r reword  s squash  f fixup  d drop  Enter run  q cancel";
+
+/// Lines above the commit list.
+const BANNER_HEIGHT: usize = 1;
+
+/// Todo list editor for the commits that are not on a remote yet.
+pub struct RebasePlan {
+    /// Newest commit first, like the commit log on the main screen.
+    pub steps: Vec<RebaseStep>,
+    pub cursor: usize,
+    pub scroll: usize,
+}
+
+impl RebasePlan {
+    pub fn new(state: &AppState) -> Self {
+        let steps = state
+            .previous_commits
+            .iter()
+            .filter(|commit| !commit.is_on_remote)
+            .map(|commit| RebaseStep {
+                hash: commit.hash.clone(),
+                message: commit.message.clone(),
+                action: RebaseAction::Pick,
+                new_message: None,
+            })
+            .collect();
+        Self {
+            steps,
+            cursor: 0,
+            scroll: 0,
+        }
+    }
+
+    fn content_height(max_y: i32) -> usize {
+        (max_y.max(0) as usize).saturating_sub(BANNER_HEIGHT + 1)
+    }
+
+    fn move_cursor(&mut self, delta: isize, max_y: i32) {
+        let last = self.steps.len().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+
+        let height = Self::content_height(max_y).max(1);
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + height {
+            self.scroll = self.cursor + 1 - height;
+        }
+    }
+
+    fn set_action(&mut self, action: RebaseAction) {
+        if let Some(step) = self.steps.get_mut(self.cursor) {
+            step.action = action;
+            if action != RebaseAction::Reword {
+                step.new_message = None;
+            }
+        }
+    }
+
+    /// The todo list in the order git applies it, oldest commit first.
+    pub fn todo(&self) -> Vec<RebaseStep> {
+        self.steps.iter().rev().cloned().collect()
+    }
+
+    fn is_unchanged(&self) -> bool {
+        self.steps
+            .iter()
+            .all(|step| step.action == RebaseAction::Pick)
+    }
+}
+
+pub fn open(state: &mut AppState) {
+    let plan = RebasePlan::new(state);
+    if plan.steps.is_empty() {
+        state.set_message(Severity::Warning, "No local commits to rebase");
+        return;
+    }
+    state.screen = Some(Screen::RebasePlan(plan));
+}
+
+/// Sets the message of a step marked with `r`, once the prompt is confirmed.
+pub fn set_reword_message(state: &mut AppState, index: usize, message: &str) {
+    let Some(Screen::RebasePlan(plan)) = state.screen.as_mut() else {
+        return;
+    };
+    let Some(step) = plan.steps.get_mut(index) else {
+        return;
+    };
+    if message.is_empty() || message == step.message {
+        step.action = RebaseAction::Pick;
+        step.new_message = None;
+    } else {
+        step.action = RebaseAction::Reword;
+        step.new_message = Some(message.to_string());
+    }
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let Some(Screen::RebasePlan(plan)) = state.screen.as_mut() else {
+        return;
+    };
+    let page = RebasePlan::content_height(max_y).max(1) as isize;
+
+    match input {
+        Input::Character('q') | Input::Character('\u{1b}') | Input::Character('\t') => {
+            state.screen = None;
+        }
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            plan.move_cursor(1, max_y)
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            plan.move_cursor(-1, max_y)
+        }
+        Input::Character(' ') | Input::Character('\u{16}') | Input::KeyNPage => {
+            plan.move_cursor(page, max_y)
+        }
+        Input::Character('b') | Input::KeyPPage => plan.move_cursor(-page, max_y),
+        Input::Character('p') => plan.set_action(RebaseAction::Pick),
+        Input::Character('s') => plan.set_action(RebaseAction::Squash),
+        Input::Character('f') => plan.set_action(RebaseAction::Fixup),
+        Input::Character('d') => plan.set_action(RebaseAction::Drop),
+        Input::Character('r') => {
+            if let Some(step) = plan.steps.get(plan.cursor) {
+                let initial = step.new_message.as_deref().unwrap_or(&step.message);
+                state.prompt = Some(Prompt::new(
+                    PromptAction::RewordPlanStep { index: plan.cursor },
+                    "Reword",
+                    initial,
+                ));
+            }
+        }
+        Input::Character('\n') => {
+            if plan.is_unchanged() {
+                state.screen = None;
+            } else {
+                let todo = plan.todo();
+                plan_preview::open(state, PlanSource::RebasePlan, &todo);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Runs the plan, once its preview is confirmed.
+pub fn run(state: &mut AppState) {
+    let Some(Screen::RebasePlan(plan)) = state.screen.as_ref() else {
+        return;
+    };
+
+    let steps = plan.todo();
+    let snapshot = Snapshot::take(&state.repo_path);
+    let started = Instant::now();
+    let result = git::run_rebase_plan(&state.repo_path, &steps);
+    state.notify_finished(
+        "Rebase",
+        started,
+        result.as_ref().map_err(|e| e.to_string()).copied(),
+    );
+    match result {
+        Ok(()) => {
+            state.screen = None;
+            state.command_history.clear();
+            state.refresh_diff(false);
+        }
+        Err(e) => {
+            state.set_message(Severity::Error, format!("Error rebasing: {e}"));
+            let error = Some(e.to_string());
+            rebase_recovery::open(state, "Rebase", error, snapshot, &steps, Retry::RebasePlan);
+        }
+    }
+}
+
+pub fn render(window: &Window, repo: &str, plan: &RebasePlan, error_message: Option<&str>) {
+    let (max_y, max_x) = window.get_max_yx();
+    let width = max_x.max(0) as usize;
+
+    chrome::render_title(window, repo, &[TITLE], HINTS);
+
+    let visible = plan
+        .steps
+        .iter()
+        .enumerate()
+        .skip(plan.scroll)
+        .take(RebasePlan::content_height(max_y));
+    for (row, (index, step)) in visible.enumerate() {
+        let y = (BANNER_HEIGHT + row) as i32;
+        let selected = index == plan.cursor;
+        let action_pair = match (step.action, selected) {
+            (RebaseAction::Pick, false) => 1,
+            (RebaseAction::Pick, true) => 5,
+            (RebaseAction::Drop, false) => 2,
+            (RebaseAction::Drop, true) => 6,
+            (_, false) => 4,
+            (_, true) => 8,
+        };
+        let pair = if selected { 5 } else { 1 };
+        window.attron(COLOR_PAIR(pair));
+        for x in 0..max_x {
+            window.mvaddch(y, x, ' ');
+        }
+
+        let action = format!(" {:<7}", step.action.label());
+        window.attron(COLOR_PAIR(action_pair));
+        window.mvaddstr(y, 0, layout::truncate_to_width(&action, width));
+        window.attron(COLOR_PAIR(pair));
+        let message = match &step.new_message {
+            Some(new_message) => format!("{} {} → {new_message}", step.hash, step.message),
+            None => format!("{} {}", step.hash, step.message),
+        };
+        window.mvaddstr(
+            y,
+            action.len() as i32,
+            layout::truncate_to_width(&message, width.saturating_sub(action.len())),
+        );
+        window.attroff(COLOR_PAIR(pair));
+    }
+
+    if let Some(error) = error_message {
+        window.attron(COLOR_PAIR(2));
+        window.mvaddstr(max_y - 1, 0, layout::truncate_to_width(error, width));
+        window.attroff(COLOR_PAIR(2));
+    }
+}
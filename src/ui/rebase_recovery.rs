@@ -0,0 +1,280 @@
+use crate::app_state::{AppState, Modal};
+use crate::command::{FixupHunkCommand, ReorderCommitsCommand};
+use crate::git::{self, CommitInfo, RebaseStep};
+use crate::message_log::Severity;
+use crate::ui::commit_view;
+use crate::ui::fixup_picker;
+use crate::ui::layout;
+use crate::ui::main_screen;
+use crate::ui::plan_preview;
+use crate::ui::rebase_plan;
+use pancurses::{A_BOLD, COLOR_PAIR, Input, Window};
+use std::path::Path;
+use unicode_width::UnicodeWidthStr;
+
+const TITLE: &str = " REBASE FAILED  r retry  a restore  Esc/q close ";
+
+/// The branch and the index right before a rebase-driven operation, to put
+/// back if it fails half way.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub head: String,
+    /// The index, written as a tree.
+    pub index: String,
+}
+
+impl Snapshot {
+    pub fn take(repo_path: &Path) -> Option<Self> {
+        Some(Self {
+            head: git::head_hash(repo_path).ok()?,
+            index: git::write_tree(repo_path).ok()?,
+        })
+    }
+}
+
+/// How to run the failed operation again.
+#[derive(Debug, Clone)]
+pub enum Retry {
+    Reorder {
+        original: Vec<CommitInfo>,
+        reordered: Vec<CommitInfo>,
+    },
+    Drop(String),
+    RebasePlan,
+    Amend {
+        hash: String,
+        message: String,
+    },
+    ChangeAuthor {
+        hash: String,
+        author: String,
+    },
+    FixupHunk {
+        patch: String,
+        hash: String,
+        subject: String,
+    },
+}
+
+/// Modal shown when a rebase-driven operation fails, telling what state it
+/// left the repository in and how to go on from there.
+pub struct RebaseRecovery {
+    /// What failed, e.g. `Reorder`.
+    pub operation: &'static str,
+    pub retry: Retry,
+    pub snapshot: Option<Snapshot>,
+    pub lines: Vec<String>,
+    pub scroll: usize,
+}
+
+/// Opens the modal for `operation`, which failed with `error` after
+/// `snapshot` was taken. `steps` are its rebase steps, if it ran a plan,
+/// to tell where they conflict.
+pub fn open(
+    state: &mut AppState,
+    operation: &'static str,
+    error: Option<String>,
+    snapshot: Option<Snapshot>,
+    steps: &[RebaseStep],
+    retry: Retry,
+) {
+    let repo_path = &state.repo_path;
+    let mut lines = vec![match &error {
+        Some(error) => format!("{operation} failed: {}", error.trim()),
+        None => format!("{operation} failed."),
+    }];
+    lines.push(String::new());
+    let head = git::head_hash(repo_path).ok();
+    lines.push(if git::is_rebase_in_progress(repo_path) {
+        "A rebase is still in progress. a aborts it and restores the branch and the index from before."
+            .to_string()
+    } else {
+        match (&snapshot, &head) {
+            (Some(snapshot), Some(head)) if *head != snapshot.head => format!(
+                "The branch moved to {}, it was at {} before. a restores it and the index.",
+                short(head),
+                short(&snapshot.head)
+            ),
+            _ => "The rebase was aborted, the branch is as it was before.".to_string(),
+        }
+    });
+    if !steps.is_empty() {
+        lines.push(plan_preview::prediction(repo_path, steps));
+    }
+    lines.push(String::new());
+    lines.push("git status:".to_string());
+    let status = git::run_git_command(repo_path, &["status"]).unwrap_or_else(|e| e.to_string());
+    lines.extend(status.lines().map(|line| format!("  {line}")));
+    lines.push(String::new());
+    lines.push("Next steps:".to_string());
+    lines.push(format!(
+        "  r  Restore, then retry the {}",
+        operation.to_lowercase()
+    ));
+    lines.push("  a  Abort and restore the branch and the index from before".to_string());
+    lines.push("  q  Close, leaving everything as it is".to_string());
+
+    state.modal = Some(Modal::RebaseRecovery(RebaseRecovery {
+        operation,
+        retry,
+        snapshot,
+        lines,
+        scroll: 0,
+    }));
+    state.alert();
+}
+
+fn short(hash: &str) -> &str {
+    &hash[..hash.len().min(7)]
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let Some(Modal::RebaseRecovery(recovery)) = state.modal.as_mut() else {
+        return;
+    };
+    let max_scroll = recovery
+        .lines
+        .len()
+        .saturating_sub(content_height(max_y, recovery.lines.len()));
+
+    match input {
+        Input::Character('q') | Input::Character('\u{1b}') => state.modal = None,
+        Input::Character('r') => {
+            if let Some(Modal::RebaseRecovery(recovery)) = state.modal.take() {
+                match restore(state, &recovery) {
+                    Ok(()) => retry(state, recovery.retry),
+                    Err(e) => state.set_message(
+                        Severity::Error,
+                        format!("Could not restore the branch: {e}"),
+                    ),
+                }
+            }
+        }
+        Input::Character('a') => {
+            if let Some(Modal::RebaseRecovery(recovery)) = state.modal.take() {
+                let result = restore(state, &recovery);
+                match result {
+                    Ok(()) => state.set_message(
+                        Severity::Success,
+                        format!(
+                            "Restored the branch and the index from before the {}",
+                            recovery.operation.to_lowercase()
+                        ),
+                    ),
+                    Err(e) => state.set_message(
+                        Severity::Error,
+                        format!("Could not restore the branch: {e}"),
+                    ),
+                }
+            }
+        }
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            recovery.scroll = (recovery.scroll + 1).min(max_scroll);
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            recovery.scroll = recovery.scroll.saturating_sub(1);
+        }
+        _ => {}
+    }
+}
+
+fn retry(state: &mut AppState, retry: Retry) {
+    match retry {
+        Retry::Reorder {
+            original,
+            reordered,
+        } => main_screen::run_reorder(
+            state,
+            ReorderCommitsCommand::new(state.repo_path.clone(), original, reordered),
+        ),
+        Retry::Drop(hash) => main_screen::apply_drop(state, &hash),
+        Retry::RebasePlan => rebase_plan::run(state),
+        Retry::Amend { hash, message } => commit_view::amend_commit(state, &hash, &message),
+        Retry::ChangeAuthor { hash, author } => main_screen::change_author(state, &hash, &author),
+        Retry::FixupHunk {
+            patch,
+            hash,
+            subject,
+        } => {
+            let command = FixupHunkCommand::new(state.repo_path.clone(), patch, hash);
+            fixup_picker::run_fixup(state, command, &subject);
+        }
+    }
+}
+
+/// Aborts the rebase if it is still going on, and puts the branch and the
+/// index back as they were before the operation. The working tree is left
+/// alone.
+fn restore(state: &mut AppState, recovery: &RebaseRecovery) -> anyhow::Result<()> {
+    let result = (|| -> anyhow::Result<()> {
+        if git::is_rebase_in_progress(&state.repo_path) {
+            git::run_git_command(&state.repo_path, &["rebase", "--abort"])?;
+        }
+        if let Some(snapshot) = &recovery.snapshot {
+            git::reset_soft(&state.repo_path, &snapshot.head)?;
+            git::read_tree(&state.repo_path, &snapshot.index)?;
+        }
+        Ok(())
+    })();
+    state.refresh_diff(false);
+    result
+}
+
+/// Rows available for the lines inside the frame.
+fn content_height(max_y: i32, line_count: usize) -> usize {
+    line_count.min((max_y.max(0) as usize).saturating_sub(4))
+}
+
+/// Draws the explanation in a frame over whatever screen is below it.
+pub fn render(window: &Window, recovery: &RebaseRecovery) {
+    let (max_y, max_x) = window.get_max_yx();
+    let longest = recovery
+        .lines
+        .iter()
+        .map(|line| line.width())
+        .chain(std::iter::once(TITLE.len()))
+        .max()
+        .unwrap_or(0);
+    let width = (longest + 4).min(max_x.max(0) as usize);
+    let height = content_height(max_y, recovery.lines.len()) + 2;
+    let left = layout::centered_x(width, max_x);
+    let top = ((max_y.max(0) as usize).saturating_sub(height) / 2) as i32;
+    let inner_width = width.saturating_sub(4);
+
+    window.attron(COLOR_PAIR(1));
+    for row in 0..height {
+        let y = top + row as i32;
+        let (edge, fill) = if row == 0 || row + 1 == height {
+            ('+', '-')
+        } else {
+            ('|', ' ')
+        };
+        window.mvaddch(y, left, edge);
+        for x in 1..width.saturating_sub(1) {
+            window.mvaddch(y, left + x as i32, fill);
+        }
+        window.mvaddch(y, left + width as i32 - 1, edge);
+    }
+    for (row, line) in recovery
+        .lines
+        .iter()
+        .skip(recovery.scroll)
+        .take(height.saturating_sub(2))
+        .enumerate()
+    {
+        window.mvaddstr(
+            top + 1 + row as i32,
+            left + 2,
+            layout::truncate_to_width(line, inner_width),
+        );
+    }
+    window.attroff(COLOR_PAIR(1));
+
+    window.attron(COLOR_PAIR(8) | A_BOLD);
+    window.mvaddstr(
+        top,
+        left + 1,
+        layout::truncate_to_width(TITLE, width.saturating_sub(2)),
+    );
+    window.attroff(COLOR_PAIR(8) | A_BOLD);
+}
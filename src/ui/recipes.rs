@@ -0,0 +1,96 @@
+use crate::app_state::AppState;
+use crate::command::ApplyRecipeCommand;
+use crate::git;
+use crate::message_log::Severity;
+use crate::recipe;
+use crate::ui::prompt::{Prompt, PromptAction};
+
+/// Asks for the name to save what is staged under.
+pub fn open_save(state: &mut AppState) {
+    if state.files.is_empty() {
+        state.set_message(Severity::Warning, "Nothing is staged to save as a recipe");
+        state.alert();
+        return;
+    }
+    state.prompt = Some(Prompt::new(
+        PromptAction::SaveRecipe,
+        "Save staging recipe as",
+        "",
+    ));
+}
+
+/// Asks for the recipe to apply, listing the saved ones. A single recipe
+/// is filled in.
+pub fn open_apply(state: &mut AppState) {
+    let names = recipe::names(&state.repo_path);
+    if names.is_empty() {
+        state.set_message(Severity::Warning, "No staging recipes yet, save one with c");
+        state.alert();
+        return;
+    }
+    let initial = if names.len() == 1 { &names[0] } else { "" };
+    state.prompt = Some(Prompt::new(
+        PromptAction::ApplyRecipe,
+        &format!("Apply recipe ({})", names.join(", ")),
+        initial,
+    ));
+}
+
+pub fn save(state: &mut AppState, name: &str) {
+    if name.is_empty() {
+        return;
+    }
+    match recipe::save(&state.repo_path, name) {
+        Ok(files) => state.set_message(
+            Severity::Success,
+            format!("Saved recipe {name} ({files} files)"),
+        ),
+        Err(e) => state.set_message(Severity::Error, format!("Failed to save the recipe: {e}")),
+    }
+}
+
+/// Stages the recipe `name` instead of what is staged, telling which of
+/// its files could not be staged.
+pub fn apply(state: &mut AppState, name: &str) {
+    if name.is_empty() {
+        return;
+    }
+    let patches = match recipe::load(&state.repo_path, name) {
+        Ok(patches) => patches,
+        Err(e) => {
+            state.set_message(Severity::Error, format!("Failed to apply the recipe: {e}"));
+            state.alert();
+            return;
+        }
+    };
+    let command = Box::new(ApplyRecipeCommand::new(
+        state.repo_path.clone(),
+        patches.clone(),
+    ));
+    if !state.execute_and_refresh(command) {
+        return;
+    }
+
+    // Staged patches apply in reverse to the index.
+    let skipped: Vec<&str> = patches
+        .iter()
+        .filter(|patch| !git::can_apply_patch(&state.repo_path, patch, true, true))
+        .map(|patch| recipe::patch_file_name(patch))
+        .collect();
+    let staged = patches.len() - skipped.len();
+    if skipped.is_empty() {
+        state.set_message(
+            Severity::Success,
+            format!("Applied recipe {name} ({staged} files)"),
+        );
+    } else {
+        state.set_message(
+            Severity::Warning,
+            format!(
+                "Applied recipe {name} ({staged} files). Not staged, as the working tree differs: {}",
+                skipped.join(", ")
+            ),
+        );
+        state.alert();
+    }
+}
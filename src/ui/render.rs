@@ -1,9 +1,90 @@
-use crate::app_state::AppState;
-use crate::ui::main_screen;
+use crate::app_state::{AppState, Modal, Screen};
+use crate::ui::{
+    chrome, diagnostics_view, discard_bin, explain, final_review, fixup_picker, format_preview,
+    help, history, hook_output, ignore_picker, index_lock, main_screen, maintenance,
+    message_log_view, patch_preview, plan_preview, prompt, rebase_plan, rebase_recovery,
+    restore_picker, reword_preview, stashes, stat_panel, tutorial, unified_list, worktrees,
+};
 use pancurses::Window;
 
 pub fn render(window: &Window, state: &AppState) {
+    if let Some(guide) = &state.tutorial {
+        // The sandbox is drawn like the real screen, the guide on top.
+        render(window, &guide.sandbox);
+        tutorial::render(window, guide);
+        window.refresh();
+        return;
+    }
     window.erase();
-    main_screen::render(window, state);
+    let repo = chrome::repo_name(&state.repo_path);
+    match &state.screen {
+        Some(Screen::FinalReview(review)) => final_review::render(window, &repo, review),
+        Some(Screen::History(commits)) => {
+            history::render(window, &repo, commits, state.message_text());
+        }
+        Some(Screen::RebasePlan(plan)) => {
+            rebase_plan::render(window, &repo, plan, state.message_text());
+            render_prompt(window, state);
+        }
+        Some(Screen::DiscardBin(bin)) => discard_bin::render(window, state, bin),
+        Some(Screen::RestorePicker(picker)) => {
+            restore_picker::render(window, &repo, picker, state.message_text());
+        }
+        Some(Screen::FixupPicker(picker)) => {
+            fixup_picker::render(window, &repo, picker, state.message_text());
+        }
+        Some(Screen::Worktrees(picker)) => {
+            worktrees::render(window, &repo, picker, state.message_text());
+        }
+        Some(Screen::Maintenance(panel)) => {
+            maintenance::render(window, &repo, panel, state.message_text());
+        }
+        Some(Screen::UnifiedList(list)) => unified_list::render(window, state, list),
+        Some(Screen::Diagnostics(view)) => diagnostics_view::render(window, &repo, view),
+        Some(Screen::MessageLog(view)) => {
+            message_log_view::render(window, &repo, view, &state.message_log);
+        }
+        Some(Screen::Help(view)) => help::render(window, &repo, view),
+        Some(Screen::Stashes(list)) => {
+            stashes::render(window, &repo, list, state.message_text());
+            render_prompt(window, state);
+        }
+        None => main_screen::render(window, state),
+    }
+    match &state.modal {
+        Some(Modal::PlanPreview(preview)) => {
+            plan_preview::render(window, preview);
+            // The prompt for the file name goes on top of the modal.
+            if let Some(prompt) = &state.prompt {
+                let (max_y, max_x) = window.get_max_yx();
+                let (prompt_y, prompt_x) = prompt::render(window, prompt, max_y, max_x);
+                window.mv(prompt_y, prompt_x);
+            }
+        }
+        Some(Modal::RewordPreview(preview)) => reword_preview::render(window, preview),
+        Some(Modal::HookOutput(output)) => hook_output::render(window, output),
+        Some(Modal::RebaseRecovery(recovery)) => rebase_recovery::render(window, recovery),
+        Some(Modal::Explanation(explanation)) => explain::render(window, explanation),
+        Some(Modal::IndexLockWait(wait)) => index_lock::render(window, wait),
+        Some(Modal::PatchPreview(preview)) => patch_preview::render(window, preview),
+        Some(Modal::StatPanel(panel)) => stat_panel::render(window, panel),
+        Some(Modal::IgnorePicker(picker)) => ignore_picker::render(window, picker),
+        Some(Modal::FormatPreview(preview)) => format_preview::render(window, preview),
+        None => {}
+    }
     window.refresh();
 }
+
+/// Draws the prompt over a full-screen view, which has no caret of its own.
+fn render_prompt(window: &Window, state: &AppState) {
+    if let Some(prompt) = &state.prompt {
+        let (max_y, max_x) = window.get_max_yx();
+        let (prompt_y, prompt_x) = prompt::render(window, prompt, max_y, max_x);
+        window.mv(prompt_y, prompt_x);
+        #[cfg(not(test))]
+        pancurses::curs_set(1);
+    } else {
+        #[cfg(not(test))]
+        pancurses::curs_set(0);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::app_state::{AppState, Screen};
+use crate::command::RestoreFileFromCommitCommand;
+use crate::git::{self, HistoryCommit};
+use crate::message_log::Severity;
+use crate::ui::chrome;
+use crate::ui::layout;
+use pancurses::{COLOR_PAIR, Input, Window};
+
+/// Lines above the commit list.
+const BANNER_HEIGHT: usize = 1;
+
+/// How many of the commits touching the file are offered.
+const COMMIT_COUNT: usize = 50;
+
+/// Screen picking the commit a file is restored from, opened with `O`.
+pub struct RestorePicker {
+    pub file_name: String,
+    pub commits: Vec<HistoryCommit>,
+    pub cursor: usize,
+    pub list_scroll: usize,
+}
+
+impl RestorePicker {
+    pub fn selected_commit(&self) -> Option<&HistoryCommit> {
+        self.commits.get(self.cursor)
+    }
+
+    fn content_height(max_y: i32) -> usize {
+        (max_y.max(0) as usize).saturating_sub(BANNER_HEIGHT + 1)
+    }
+
+    fn move_cursor(&mut self, delta: isize, max_y: i32) {
+        let last = self.commits.len().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+
+        let height = Self::content_height(max_y).max(1);
+        if self.cursor < self.list_scroll {
+            self.list_scroll = self.cursor;
+        } else if self.cursor >= self.list_scroll + height {
+            self.list_scroll = self.cursor + 1 - height;
+        }
+    }
+}
+
+/// Lists the recent commits that had `file_name`, or says there are none.
+pub fn open(state: &mut AppState, file_name: &str) {
+    let commits =
+        git::get_file_history(&state.repo_path, file_name, COMMIT_COUNT).unwrap_or_default();
+    if commits.is_empty() {
+        state.set_message(
+            Severity::Warning,
+            format!("No commit has {file_name} to restore it from"),
+        );
+        state.alert();
+        return;
+    }
+    state.screen = Some(Screen::RestorePicker(RestorePicker {
+        file_name: file_name.to_string(),
+        commits,
+        cursor: 0,
+        list_scroll: 0,
+    }));
+}
+
+/// Restores the file from the selected commit into the working tree, and
+/// into the index too if `staged`, then closes the picker.
+fn restore_selected(state: &mut AppState, staged: bool) {
+    let Some(Screen::RestorePicker(picker)) = &state.screen else {
+        return;
+    };
+    let Some(commit) = picker.selected_commit() else {
+        return;
+    };
+    let (file_name, hash) = (picker.file_name.clone(), commit.hash.clone());
+    let command = Box::new(RestoreFileFromCommitCommand::new(
+        state.repo_path.clone(),
+        file_name.clone(),
+        hash.clone(),
+        staged,
+    ));
+    if !state.execute_and_refresh(command) {
+        state.set_message(
+            Severity::Error,
+            format!("Cannot restore {file_name} from {hash}"),
+        );
+        return;
+    }
+    state.screen = None;
+    state.set_message(
+        Severity::Success,
+        if staged {
+            format!("Restored {file_name} from {hash} to the working tree and the index")
+        } else {
+            format!("Restored {file_name} from {hash} to the working tree")
+        },
+    );
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let Some(Screen::RestorePicker(picker)) = state.screen.as_mut() else {
+        return;
+    };
+    let page = RestorePicker::content_height(max_y).max(1) as isize;
+
+    match input {
+        Input::Character('q')
+        | Input::Character('O')
+        | Input::Character('\t')
+        | Input::Character('\u{1b}') => {
+            state.screen = None;
+        }
+        Input::Character('\n') => restore_selected(state, false),
+        Input::Character('s') => restore_selected(state, true),
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            picker.move_cursor(1, max_y)
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            picker.move_cursor(-1, max_y)
+        }
+        Input::Character(' ') | Input::Character('\u{16}') | Input::KeyNPage => {
+            picker.move_cursor(page, max_y)
+        }
+        Input::Character('b') | Input::KeyPPage => picker.move_cursor(-page, max_y),
+        _ => {}
+    }
+}
+
+pub fn render(window: &Window, repo: &str, picker: &RestorePicker, error_message: Option<&str>) {
+    let (max_y, max_x) = window.get_max_yx();
+
+    chrome::render_title(
+        window,
+        repo,
+        &["Restore", &picker.file_name],
+        "from:  Enter working tree  s working tree and index  q close",
+    );
+
+    let width = max_x.max(0) as usize;
+    let visible = picker
+        .commits
+        .iter()
+        .enumerate()
+        .skip(picker.list_scroll)
+        .take(RestorePicker::content_height(max_y));
+    for (row, (index, commit)) in visible.enumerate() {
+        let y = (BANNER_HEIGHT + row) as i32;
+        let (pair, hash_pair) = if index == picker.cursor {
+            (5, 8)
+        } else {
+            (1, 4)
+        };
+        window.attron(COLOR_PAIR(pair));
+        for x in 0..max_x {
+            window.mvaddch(y, x, ' ');
+        }
+
+        let hash = format!(" {} ", commit.hash);
+        window.attron(COLOR_PAIR(hash_pair));
+        window.mvaddstr(y, 0, layout::truncate_to_width(&hash, width));
+        window.attron(COLOR_PAIR(pair));
+        let line = format!("{} {}", commit.date, commit.message);
+        window.mvaddstr(
+            y,
+            hash.len() as i32,
+            layout::truncate_to_width(&line, width.saturating_sub(hash.len())),
+        );
+        window.attroff(COLOR_PAIR(pair));
+    }
+
+    if let Some(error) = error_message {
+        window.attron(COLOR_PAIR(2));
+        window.mvaddstr(
+            max_y - 1,
+            0,
+            layout::truncate_to_width(error, max_x.max(0) as usize),
+        );
+        window.attroff(COLOR_PAIR(2));
+    }
+}
@@ -0,0 +1,207 @@
+use crate::app_state::{AppState, Modal};
+use crate::ui::commit_view;
+use crate::ui::layout;
+use crate::util::word_diff::{WordDiffLine, mark_changed_words};
+use pancurses::{A_BOLD, A_REVERSE, COLOR_PAIR, Input, Window};
+use unicode_width::UnicodeWidthStr;
+
+const TITLE: &str = " REWORD? Enter confirm  Esc/q keep editing ";
+
+/// Modal comparing the message of a commit with the one it is about to be
+/// rewritten with.
+pub struct RewordPreview {
+    pub hash: String,
+    pub old_message: String,
+    pub new_message: String,
+    pub scroll: usize,
+}
+
+/// The full message of the listed commit `hash`: the subject, then the
+/// body below a blank line.
+pub fn full_message(state: &AppState, hash: &str) -> Option<String> {
+    let commit = state
+        .previous_commits
+        .iter()
+        .find(|commit| commit.hash == hash)?;
+    Some(if commit.body.is_empty() {
+        commit.message.clone()
+    } else {
+        format!("{}\n\n{}", commit.message, commit.body)
+    })
+}
+
+/// Confirms `new_message` for the commit `hash` first if it changes the
+/// message, otherwise amends right away.
+pub fn open_or_amend(state: &mut AppState, hash: String, new_message: String) {
+    match full_message(state, &hash) {
+        Some(old_message) if old_message != new_message => {
+            state.modal = Some(Modal::RewordPreview(RewordPreview {
+                hash,
+                old_message,
+                new_message,
+                scroll: 0,
+            }));
+        }
+        _ => commit_view::amend_commit(state, &hash, &new_message),
+    }
+}
+
+impl RewordPreview {
+    /// Rows of the old and the new column.
+    fn rows(&self) -> (Vec<WordDiffLine>, Vec<WordDiffLine>) {
+        mark_changed_words(&self.old_message, &self.new_message)
+    }
+
+    /// Lines of the old body that the new message has no body to replace.
+    pub fn lost_body_lines(&self) -> usize {
+        match (
+            self.old_message.split_once("\n\n"),
+            self.new_message.contains('\n'),
+        ) {
+            (Some((_, body)), false) => body.lines().count(),
+            _ => 0,
+        }
+    }
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let Some(Modal::RewordPreview(preview)) = state.modal.as_mut() else {
+        return;
+    };
+    let (old_rows, new_rows) = preview.rows();
+    let line_count = old_rows.len().max(new_rows.len());
+    let max_scroll = line_count.saturating_sub(content_height(max_y, line_count));
+
+    match input {
+        Input::Character('q') | Input::Character('\u{1b}') => state.modal = None,
+        Input::Character('\n') => {
+            if let Some(Modal::RewordPreview(preview)) = state.modal.take() {
+                commit_view::amend_commit(state, &preview.hash, &preview.new_message);
+            }
+        }
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            preview.scroll = (preview.scroll + 1).min(max_scroll);
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            preview.scroll = preview.scroll.saturating_sub(1);
+        }
+        _ => {}
+    }
+}
+
+/// Rows available for the message lines inside the frame, below the column
+/// titles and the warning line.
+fn content_height(max_y: i32, line_count: usize) -> usize {
+    line_count.min((max_y.max(0) as usize).saturating_sub(6))
+}
+
+/// Draws `line` from column `x`, at most `width` columns wide, with the
+/// changed words in `pair` and reversed.
+fn render_line(window: &Window, y: i32, x: i32, line: &WordDiffLine, width: usize, pair: u32) {
+    let mut used = 0;
+    window.mv(y, x);
+    for (text, is_changed) in &line.0 {
+        let part = layout::truncate_to_width(text, width.saturating_sub(used));
+        if part.is_empty() {
+            break;
+        }
+        used += part.width();
+        let attributes = if *is_changed {
+            COLOR_PAIR(pair) | A_REVERSE
+        } else {
+            COLOR_PAIR(1)
+        };
+        window.attron(attributes);
+        window.addstr(&part);
+        window.attroff(attributes);
+    }
+}
+
+/// Draws the old and the new message side by side in a frame over the
+/// main screen.
+pub fn render(window: &Window, preview: &RewordPreview) {
+    let (max_y, max_x) = window.get_max_yx();
+    let (old_rows, new_rows) = preview.rows();
+    let line_count = old_rows.len().max(new_rows.len());
+    let longest = preview
+        .old_message
+        .lines()
+        .chain(preview.new_message.lines())
+        .map(|line| line.width())
+        .max()
+        .unwrap_or(0);
+    let width = (longest * 2 + 7)
+        .max(TITLE.len() + 2)
+        .min(max_x.max(0) as usize);
+    let height = content_height(max_y, line_count) + 4;
+    let left = layout::centered_x(width, max_x);
+    let top = ((max_y.max(0) as usize).saturating_sub(height) / 2) as i32;
+    let column_width = width.saturating_sub(7) / 2;
+    let new_left = left + 4 + column_width as i32;
+
+    window.attron(COLOR_PAIR(1));
+    for row in 0..height {
+        let y = top + row as i32;
+        let (edge, fill) = if row == 0 || row + 1 == height {
+            ('+', '-')
+        } else {
+            ('|', ' ')
+        };
+        window.mvaddch(y, left, edge);
+        for x in 1..width.saturating_sub(1) {
+            window.mvaddch(y, left + x as i32, fill);
+        }
+        window.mvaddch(y, left + width as i32 - 1, edge);
+        if row > 0 && row + 1 < height {
+            window.mvaddch(y, new_left - 2, '|');
+        }
+    }
+    window.attroff(COLOR_PAIR(1));
+
+    window.attron(COLOR_PAIR(1) | A_BOLD);
+    window.mvaddstr(
+        top + 1,
+        left + 2,
+        layout::truncate_to_width("Old", column_width),
+    );
+    window.mvaddstr(
+        top + 1,
+        new_left,
+        layout::truncate_to_width("New", column_width),
+    );
+    window.attroff(COLOR_PAIR(1) | A_BOLD);
+
+    let rows = content_height(max_y, line_count);
+    for row in 0..rows {
+        let index = preview.scroll + row;
+        let y = top + 2 + row as i32;
+        if let Some(line) = old_rows.get(index) {
+            render_line(window, y, left + 2, line, column_width, 2);
+        }
+        if let Some(line) = new_rows.get(index) {
+            render_line(window, y, new_left, line, column_width, 3);
+        }
+    }
+
+    let lost = preview.lost_body_lines();
+    if lost > 0 {
+        window.attron(COLOR_PAIR(2) | A_BOLD);
+        window.mvaddstr(
+            top + height as i32 - 2,
+            left + 2,
+            layout::truncate_to_width(
+                &format!("The body ({lost} lines) is not kept"),
+                width.saturating_sub(4),
+            ),
+        );
+        window.attroff(COLOR_PAIR(2) | A_BOLD);
+    }
+
+    window.attron(COLOR_PAIR(8) | A_BOLD);
+    window.mvaddstr(
+        top,
+        left + 1,
+        layout::truncate_to_width(TITLE, width.saturating_sub(2)),
+    );
+    window.attroff(COLOR_PAIR(8) | A_BOLD);
+}
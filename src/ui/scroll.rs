@@ -1,5 +1,7 @@
 use crate::app_state::{AppState, FocusedPane};
 use crate::git;
+use crate::ui::commit_folds;
+use crate::ui::main_screen::{ListItem, UnstagedListItem};
 use pancurses::Input;
 
 enum ScrollDirection {
@@ -65,26 +67,19 @@ fn scroll_view(state: &mut AppState, direction: ScrollDirection, amount: ScrollA
     let content_height = (max_y as usize).saturating_sub(diff_view_top);
 
     let lines_count = if state.main_screen.is_reordering_commits {
-        state
-            .selected_commit_files
+        commit_folds::shown_files(state)
             .iter()
             .map(|f| f.lines.len())
             .sum()
     } else {
-        let num_files = state.files.len();
-        if state.main_screen.file_cursor > 0 && state.main_screen.file_cursor <= num_files {
-            state
-                .files
-                .get(state.main_screen.file_cursor - 1)
-                .map_or(0, |f| f.lines.len())
-        } else if state.main_screen.file_cursor > num_files + 1 {
-            state
-                .selected_commit_files
+        match state.current_main_item() {
+            Some(ListItem::File(file)) => file.lines.len(),
+            Some(ListItem::RenameGroup { files, .. }) => files.iter().map(|f| f.lines.len()).sum(),
+            Some(ListItem::PreviousCommitInfo { .. }) => commit_folds::shown_files(state)
                 .iter()
                 .map(|f| f.lines.len())
-                .sum()
-        } else {
-            0
+                .sum(),
+            _ => 0,
         }
     };
 
@@ -106,32 +101,26 @@ fn scroll_unstaged_diff_view(
     amount: ScrollAmount,
     max_y: i32,
 ) {
-    let unstaged_file_count = state.unstaged_pane.unstaged_files.len();
-    let untracked_file_count = state.unstaged_pane.untracked_files.len();
-
-    let lines_count =
-        if state.unstaged_pane.cursor > 0 && state.unstaged_pane.cursor <= unstaged_file_count {
-            state.get_unstaged_file().map_or(0, |f| f.lines.len())
-        } else if state.unstaged_pane.cursor > unstaged_file_count + 1
-            && state.unstaged_pane.cursor <= unstaged_file_count + 1 + untracked_file_count
-        {
-            let file_index = state.unstaged_pane.cursor - unstaged_file_count - 2;
-            if let Some(file_path) = state.unstaged_pane.untracked_files.get(file_index) {
-                if let Ok((content, _)) = git::read_file_content(&state.repo_path, file_path) {
-                    if content.contains(&0x00) {
-                        1
-                    } else {
-                        String::from_utf8_lossy(&content).lines().count()
-                    }
-                } else {
+    let lines_count = match state
+        .unstaged_pane
+        .list_items
+        .get(state.unstaged_pane.cursor)
+    {
+        Some(UnstagedListItem::File(file)) => file.lines.len(),
+        Some(UnstagedListItem::RenameGroup(group)) => group.renames.len(),
+        Some(UnstagedListItem::UntrackedFile(file_path)) => {
+            if let Ok((content, _)) = git::read_file_content(&state.repo_path, file_path) {
+                if content.contains(&0x00) {
                     1
+                } else {
+                    String::from_utf8_lossy(&content).lines().count()
                 }
             } else {
-                0
+                1
             }
-        } else {
-            0
-        };
+        }
+        _ => 0,
+    };
 
     if lines_count > 0 {
         let mut main_pane_offset = 0;
@@ -155,14 +144,21 @@ fn scroll_unstaged_diff_view(
     }
 }
 
+/// Scrolls a list of `height` rows so that `cursor` is on it.
+pub fn into_view(cursor: usize, scroll: &mut usize, height: usize) {
+    if cursor < *scroll {
+        *scroll = cursor;
+    } else if cursor >= *scroll + height {
+        *scroll = cursor + 1 - height.max(1);
+    }
+}
+
 pub fn handle_scroll(state: &mut AppState, input: Input, max_y: i32) {
     let (direction, amount) = match input {
         Input::Character(' ') | Input::Character('\u{16}') => {
             (ScrollDirection::Down, ScrollAmount::Full)
         }
-        Input::Character('b') | Input::Character('\u{2}') => {
-            (ScrollDirection::Up, ScrollAmount::Full)
-        }
+        Input::Character('\u{2}') => (ScrollDirection::Up, ScrollAmount::Full),
         Input::Character('\u{4}') => (ScrollDirection::Down, ScrollAmount::Half),
         Input::Character('\u{15}') => (ScrollDirection::Up, ScrollAmount::Half),
         _ => return,
@@ -0,0 +1,308 @@
+use crate::app_state::{AppState, Screen};
+use crate::command::{Command, GroupCommand, StashApplyCommand, StashDropCommand};
+use crate::git::{self, FileDiff, StashEntry};
+use crate::message_log::Severity;
+use crate::ui::chrome;
+use crate::ui::diff_view;
+use crate::ui::layout;
+use crate::ui::prompt::{Prompt, PromptAction};
+use pancurses::{COLOR_PAIR, Input, Window};
+
+/// Lines above the stash list or the diff.
+const BANNER_HEIGHT: usize = 1;
+
+/// Screen listing the stashes. Enter shows the diff of the selected one.
+pub struct Stashes {
+    pub entries: Vec<StashEntry>,
+    pub cursor: usize,
+    pub list_scroll: usize,
+    /// Diff of the stash under the cursor while it is opened with Enter.
+    pub diff: Option<Vec<FileDiff>>,
+    pub diff_scroll: usize,
+    pub horizontal_scroll: usize,
+}
+
+impl Stashes {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            entries: git::get_stashes(&state.repo_path).unwrap_or_default(),
+            cursor: 0,
+            list_scroll: 0,
+            diff: None,
+            diff_scroll: 0,
+            horizontal_scroll: 0,
+        }
+    }
+
+    pub fn selected_stash(&self) -> Option<&StashEntry> {
+        self.entries.get(self.cursor)
+    }
+
+    fn content_height(max_y: i32) -> usize {
+        (max_y.max(0) as usize).saturating_sub(BANNER_HEIGHT + 1)
+    }
+
+    fn move_cursor(&mut self, delta: isize, max_y: i32) {
+        let last = self.entries.len().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+
+        let height = Self::content_height(max_y).max(1);
+        if self.cursor < self.list_scroll {
+            self.list_scroll = self.cursor;
+        } else if self.cursor >= self.list_scroll + height {
+            self.list_scroll = self.cursor + 1 - height;
+        }
+    }
+
+    fn scroll_diff_by(&mut self, delta: isize, max_y: i32) {
+        let total_lines: usize = self.diff.iter().flatten().map(|f| f.lines.len()).sum();
+        let max_scroll = total_lines.saturating_sub(Self::content_height(max_y));
+        self.diff_scroll = self
+            .diff_scroll
+            .saturating_add_signed(delta)
+            .min(max_scroll);
+    }
+
+    fn open_diff(&mut self, repo_path: &std::path::Path) -> anyhow::Result<()> {
+        let Some(stash) = self.selected_stash() else {
+            return Ok(());
+        };
+        self.diff = Some(git::get_stash_diff(repo_path, &stash.hash)?);
+        self.diff_scroll = 0;
+        self.horizontal_scroll = 0;
+        Ok(())
+    }
+}
+
+pub fn open(state: &mut AppState) {
+    state.screen = Some(Screen::Stashes(Stashes::new(state)));
+}
+
+/// Re-reads the stash list after it changed, keeping the cursor in range.
+fn reload(state: &mut AppState) {
+    let entries = git::get_stashes(&state.repo_path).unwrap_or_default();
+    if let Some(Screen::Stashes(stashes)) = state.screen.as_mut() {
+        stashes.cursor = stashes.cursor.min(entries.len().saturating_sub(1));
+        stashes.list_scroll = stashes.list_scroll.min(stashes.cursor);
+        stashes.entries = entries;
+    }
+}
+
+/// Stashes the unstaged changes and untracked files. Staged changes stay
+/// where they are.
+pub fn create(state: &mut AppState, message: &str) {
+    if !state.main_screen.has_unstaged_changes {
+        state.set_message(Severity::Warning, "No unstaged changes to stash");
+        return;
+    }
+    match git::stash_push(&state.repo_path, message, true) {
+        Ok(_) => {
+            state.refresh_diff(false);
+            reload(state);
+            if let Some(Screen::Stashes(stashes)) = state.screen.as_mut() {
+                stashes.cursor = 0;
+                stashes.list_scroll = 0;
+            }
+        }
+        Err(e) => state.set_message(Severity::Error, format!("Failed to stash: {e}")),
+    }
+}
+
+fn apply_selected(state: &mut AppState, pop: bool) {
+    let Some(Screen::Stashes(stashes)) = &state.screen else {
+        return;
+    };
+    let Some(stash) = stashes.selected_stash().cloned() else {
+        return;
+    };
+    let apply = Box::new(StashApplyCommand::new(
+        state.repo_path.clone(),
+        stash.hash.clone(),
+    ));
+    let command: Box<dyn Command> = if pop {
+        let drop = Box::new(StashDropCommand::new(
+            state.repo_path.clone(),
+            stash.hash,
+            stash.message,
+        ));
+        Box::new(GroupCommand::new(vec![apply, drop]))
+    } else {
+        apply
+    };
+    if !state.execute_and_refresh(command) {
+        state.set_message(
+            Severity::Warning,
+            format!(
+                "Cannot apply {}: it conflicts with the working tree",
+                stash.name
+            ),
+        );
+    }
+    reload(state);
+}
+
+fn drop_selected(state: &mut AppState) {
+    let Some(Screen::Stashes(stashes)) = &state.screen else {
+        return;
+    };
+    let Some(stash) = stashes.selected_stash().cloned() else {
+        return;
+    };
+    let command = Box::new(StashDropCommand::new(
+        state.repo_path.clone(),
+        stash.hash,
+        stash.message,
+    ));
+    if !state.execute_and_refresh(command) {
+        state.set_message(Severity::Error, format!("Failed to drop {}", stash.name));
+    }
+    reload(state);
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let repo_path = state.repo_path.clone();
+    let Some(Screen::Stashes(stashes)) = state.screen.as_mut() else {
+        return;
+    };
+    let page = Stashes::content_height(max_y).max(1) as isize;
+
+    if matches!(input, Input::Character('\t')) {
+        state.screen = None;
+        return;
+    }
+
+    if stashes.diff.is_some() {
+        match input {
+            Input::Character('q') | Input::Character('\n') | Input::Character('\u{1b}') => {
+                stashes.diff = None;
+            }
+            Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+                stashes.scroll_diff_by(1, max_y)
+            }
+            Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+                stashes.scroll_diff_by(-1, max_y)
+            }
+            Input::Character(' ') | Input::Character('\u{16}') | Input::KeyNPage => {
+                stashes.scroll_diff_by(page, max_y)
+            }
+            Input::Character('b') | Input::KeyPPage => stashes.scroll_diff_by(-page, max_y),
+            Input::Character('\u{4}') => stashes.scroll_diff_by(page / 2, max_y),
+            Input::Character('\u{15}') => stashes.scroll_diff_by(-page / 2, max_y),
+            Input::KeyLeft => {
+                stashes.horizontal_scroll = stashes.horizontal_scroll.saturating_sub(1);
+            }
+            Input::KeyRight => stashes.horizontal_scroll += 1,
+            _ => {}
+        }
+        return;
+    }
+
+    match input {
+        Input::Character('q') | Input::Character('s') | Input::Character('\u{1b}') => {
+            state.screen = None;
+        }
+        Input::Character('\n') => {
+            if let Err(e) = stashes.open_diff(&repo_path) {
+                state.set_message(Severity::Error, format!("Failed to load stash: {e}"));
+            }
+        }
+        Input::Character('a') => apply_selected(state, false),
+        Input::Character('p') => apply_selected(state, true),
+        Input::Character('d') => drop_selected(state),
+        Input::Character('n') => {
+            state.prompt = Some(Prompt::new(PromptAction::CreateStash, "Stash message", ""));
+        }
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            stashes.move_cursor(1, max_y)
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            stashes.move_cursor(-1, max_y)
+        }
+        Input::Character(' ') | Input::Character('\u{16}') | Input::KeyNPage => {
+            stashes.move_cursor(page, max_y)
+        }
+        Input::Character('b') | Input::KeyPPage => stashes.move_cursor(-page, max_y),
+        _ => {}
+    }
+}
+
+pub fn render(window: &Window, repo: &str, stashes: &Stashes, error_message: Option<&str>) {
+    let (max_y, max_x) = window.get_max_yx();
+
+    match (&stashes.diff, stashes.selected_stash()) {
+        (Some(_), Some(stash)) => {
+            let detail = format!("{} {}", stash.name, stash.message);
+            chrome::render_title(window, repo, &["Stashes", &detail], "q back");
+        }
+        _ => {
+            let hints = format!(
+                "{} stashes  Enter diff  a apply  p pop  d drop  n new  q close",
+                stashes.entries.len()
+            );
+            chrome::render_title(window, repo, &["Stashes"], &hints);
+        }
+    }
+
+    if let Some(files) = &stashes.diff {
+        diff_view::render_multiple(
+            window,
+            files,
+            Stashes::content_height(max_y),
+            stashes.diff_scroll,
+            stashes.horizontal_scroll,
+            BANNER_HEIGHT,
+            usize::MAX,
+            false,
+        );
+    } else {
+        render_stash_list(window, stashes, max_y, max_x);
+    }
+
+    if let Some(error) = error_message {
+        window.attron(COLOR_PAIR(2));
+        window.mvaddstr(
+            max_y - 1,
+            0,
+            layout::truncate_to_width(error, max_x.max(0) as usize),
+        );
+        window.attroff(COLOR_PAIR(2));
+    }
+}
+
+fn render_stash_list(window: &Window, stashes: &Stashes, max_y: i32, max_x: i32) {
+    if stashes.entries.is_empty() {
+        window.mvaddstr(BANNER_HEIGHT as i32, 0, " No stashes");
+        return;
+    }
+
+    let width = max_x.max(0) as usize;
+    let visible = stashes
+        .entries
+        .iter()
+        .enumerate()
+        .skip(stashes.list_scroll)
+        .take(Stashes::content_height(max_y));
+    for (row, (index, stash)) in visible.enumerate() {
+        let y = (BANNER_HEIGHT + row) as i32;
+        let (pair, name_pair) = if index == stashes.cursor {
+            (5, 8)
+        } else {
+            (1, 4)
+        };
+        window.attron(COLOR_PAIR(pair));
+        for x in 0..max_x {
+            window.mvaddch(y, x, ' ');
+        }
+
+        let name = format!(" {} ", stash.name);
+        window.attron(COLOR_PAIR(name_pair));
+        window.mvaddstr(y, 0, layout::truncate_to_width(&name, width));
+        window.attron(COLOR_PAIR(pair));
+        window.mvaddstr(
+            y,
+            name.len() as i32,
+            layout::truncate_to_width(&stash.message, width.saturating_sub(name.len())),
+        );
+        window.attroff(COLOR_PAIR(pair));
+    }
+}
@@ -0,0 +1,161 @@
+use crate::app_state::{AppState, Modal};
+use crate::diff_stat::{self, FileStat};
+use crate::message_log::Severity;
+use crate::ui::layout;
+use pancurses::{A_BOLD, COLOR_PAIR, Input, Window};
+use unicode_width::UnicodeWidthStr;
+
+const TITLE: &str = " STAGED STAT  ;/Esc/q close ";
+
+/// Widest the bars get, like `git diff --stat` in a wide terminal.
+const MAX_BAR_WIDTH: usize = 50;
+
+/// Popup summing up the staged changes like `git diff --cached --stat`,
+/// counted from the staged diff already loaded.
+pub struct StatPanel {
+    pub stats: Vec<FileStat>,
+    pub summary: String,
+    pub scroll: usize,
+}
+
+pub fn open(state: &mut AppState) {
+    if state.files.is_empty() {
+        state.set_message(Severity::Warning, "Nothing is staged");
+        state.alert();
+        return;
+    }
+    let stats: Vec<FileStat> = state.files.iter().map(diff_stat::file_stat).collect();
+    let summary = diff_stat::summary(&stats);
+    state.modal = Some(Modal::StatPanel(StatPanel {
+        stats,
+        summary,
+        scroll: 0,
+    }));
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let Some(Modal::StatPanel(panel)) = state.modal.as_mut() else {
+        return;
+    };
+    let line_count = panel.stats.len() + 2;
+    let max_scroll = line_count.saturating_sub(content_height(max_y, line_count));
+
+    match input {
+        Input::Character(';') | Input::Character('q') | Input::Character('\u{1b}') => {
+            state.modal = None;
+        }
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            panel.scroll = (panel.scroll + 1).min(max_scroll);
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            panel.scroll = panel.scroll.saturating_sub(1);
+        }
+        _ => {}
+    }
+}
+
+/// Rows available for the lines inside the frame.
+fn content_height(max_y: i32, line_count: usize) -> usize {
+    line_count.min((max_y.max(0) as usize).saturating_sub(4))
+}
+
+/// The count column of `stat`, `Bin` for a binary file.
+fn count(stat: &FileStat) -> String {
+    if stat.binary {
+        "Bin".to_string()
+    } else {
+        stat.changes().to_string()
+    }
+}
+
+/// Draws a `name | count +++--` line for each file, then the totals.
+pub fn render(window: &Window, panel: &StatPanel) {
+    let (max_y, max_x) = window.get_max_yx();
+    let max_width = (max_x.max(0) as usize).saturating_sub(2);
+    let count_width = panel
+        .stats
+        .iter()
+        .map(|s| count(s).len())
+        .max()
+        .unwrap_or(1);
+    let name_width = panel
+        .stats
+        .iter()
+        .map(|stat| stat.name.width())
+        .max()
+        .unwrap_or(0)
+        .min(max_width / 2);
+    let most = panel.stats.iter().map(FileStat::changes).max().unwrap_or(0);
+    // Frame, margins, " | " and the space after the count
+    let bar_room = max_width.saturating_sub(name_width + count_width + 8);
+    let bar_width = most.min(MAX_BAR_WIDTH).min(bar_room);
+
+    let line_count = panel.stats.len() + 2;
+    let width = (name_width + count_width + bar_width + 8)
+        .max(panel.summary.width() + 4)
+        .max(TITLE.len() + 2)
+        .min(max_x.max(0) as usize);
+    let height = content_height(max_y, line_count) + 2;
+    let left = layout::centered_x(width, max_x);
+    let top = ((max_y.max(0) as usize).saturating_sub(height) / 2) as i32;
+    let inner_width = width.saturating_sub(4);
+
+    window.attron(COLOR_PAIR(1));
+    for row in 0..height {
+        let y = top + row as i32;
+        let (edge, fill) = if row == 0 || row + 1 == height {
+            ('+', '-')
+        } else {
+            ('|', ' ')
+        };
+        window.mvaddch(y, left, edge);
+        for x in 1..width.saturating_sub(1) {
+            window.mvaddch(y, left + x as i32, fill);
+        }
+        window.mvaddch(y, left + width as i32 - 1, edge);
+    }
+    window.attroff(COLOR_PAIR(1));
+
+    let rows = height.saturating_sub(2);
+    for row in 0..rows {
+        let index = panel.scroll + row;
+        let y = top + 1 + row as i32;
+        if let Some(stat) = panel.stats.get(index) {
+            let name = layout::truncate_to_width(&stat.name, name_width);
+            let padding = " ".repeat(name_width.saturating_sub(name.width()));
+            let line = format!("{name}{padding} | {:>count_width$} ", count(stat));
+            window.attron(COLOR_PAIR(1));
+            window.mvaddstr(y, left + 2, layout::truncate_to_width(&line, inner_width));
+            window.attroff(COLOR_PAIR(1));
+            if stat.binary {
+                continue;
+            }
+            let (added, removed) = diff_stat::bar(stat, most, bar_width);
+            let x = left + 2 + line.width() as i32;
+            let room = inner_width.saturating_sub(line.width());
+            let added = added.min(room);
+            window.attron(COLOR_PAIR(3));
+            window.mvaddstr(y, x, "+".repeat(added));
+            window.attroff(COLOR_PAIR(3));
+            window.attron(COLOR_PAIR(2));
+            window.mvaddstr(y, x + added as i32, "-".repeat(removed.min(room - added)));
+            window.attroff(COLOR_PAIR(2));
+        } else if index == panel.stats.len() + 1 {
+            window.attron(COLOR_PAIR(1) | A_BOLD);
+            window.mvaddstr(
+                y,
+                left + 2,
+                layout::truncate_to_width(&panel.summary, inner_width),
+            );
+            window.attroff(COLOR_PAIR(1) | A_BOLD);
+        }
+    }
+
+    window.attron(COLOR_PAIR(8) | A_BOLD);
+    window.mvaddstr(
+        top,
+        left + 1,
+        layout::truncate_to_width(TITLE, width.saturating_sub(2)),
+    );
+    window.attroff(COLOR_PAIR(8) | A_BOLD);
+}
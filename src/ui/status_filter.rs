@@ -0,0 +1,109 @@
+use crate::app_state::AppState;
+use crate::git::FileStatus;
+use crate::message_log::Severity;
+use crate::ui::main_screen::{ListItem, UnstagedListItem};
+use pancurses::Input;
+
+/// The statuses the menu offers, with their keys.
+const CHOICES: [(char, FileStatus); 4] = [
+    ('a', FileStatus::Added),
+    ('m', FileStatus::Modified),
+    ('d', FileStatus::Deleted),
+    ('r', FileStatus::Renamed),
+];
+
+pub fn label(status: &FileStatus) -> &'static str {
+    match status {
+        FileStatus::Added => "added",
+        FileStatus::Modified => "modified",
+        FileStatus::Deleted => "deleted",
+        FileStatus::Renamed => "renamed",
+    }
+}
+
+/// What the headers of both lists add while a filter is on.
+pub fn header_note(state: &AppState) -> Option<String> {
+    state
+        .status_filter
+        .as_ref()
+        .map(|status| format!("only {}, Esc shows all", label(status)))
+}
+
+/// Asks which status to show, taken from the next key.
+pub fn open_menu(state: &mut AppState) {
+    state.status_filter_menu = true;
+    state.set_message(
+        Severity::Info,
+        "Show only: a added  m modified  d deleted  r renamed  Esc all files",
+    );
+}
+
+pub fn handle_menu_input(state: &mut AppState, input: Input) {
+    state.status_filter_menu = false;
+    let status = match input {
+        Input::Character('\u{1b}') => None,
+        Input::Character(c) => match CHOICES.iter().find(|(key, _)| *key == c) {
+            Some((_, status)) => Some(status.clone()),
+            None => {
+                state.set_message(
+                    Severity::Warning,
+                    format!("No status for {c}, nothing filtered"),
+                );
+                state.alert();
+                return;
+            }
+        },
+        _ => return,
+    };
+    set(state, status);
+}
+
+/// Shows only the files with `status` in both lists, or all of them.
+pub fn set(state: &mut AppState, status: Option<FileStatus>) {
+    let message = match &status {
+        Some(status) => format!("Showing only {} files", label(status)),
+        None => "Showing all files".to_string(),
+    };
+    state.status_filter = status;
+    state.refresh_diff(false);
+    state.set_message(Severity::Info, message);
+}
+
+/// Drops the files of the staged list without the status of the filter.
+/// Directory renames count as renamed.
+pub fn filter_main(items: &mut Vec<ListItem>, status: &FileStatus) {
+    items.retain(|item| match item {
+        ListItem::File(file) => file.status == *status,
+        ListItem::RenameGroup { .. } => *status == FileStatus::Renamed,
+        _ => true,
+    });
+}
+
+/// Drops the files of the unstaged list without the status of the filter,
+/// and the headers of the untracked files left empty. Untracked files
+/// count as added, and directories moved without `git mv` as renamed.
+pub fn filter_unstaged(items: &mut Vec<UnstagedListItem>, status: &FileStatus) {
+    items.retain(|item| match item {
+        UnstagedListItem::File(file) => file.status == *status,
+        UnstagedListItem::UntrackedFile(_) => *status == FileStatus::Added,
+        UnstagedListItem::RenameGroup(_) => *status == FileStatus::Renamed,
+        _ => true,
+    });
+    let mut index = 0;
+    while index < items.len() {
+        let is_header = matches!(
+            items[index],
+            UnstagedListItem::UntrackedFilesHeader | UnstagedListItem::LargeFilesHeader
+        );
+        if is_header
+            && !matches!(
+                items.get(index + 1),
+                Some(UnstagedListItem::UntrackedFile(_))
+            )
+        {
+            items.remove(index);
+        } else {
+            index += 1;
+        }
+    }
+}
@@ -0,0 +1,290 @@
+use crate::app_state::AppState;
+use crate::commit_storage;
+use crate::config::Config;
+use crate::git;
+use crate::message_log::Severity;
+use crate::ui::layout;
+use crate::ui::update::{update_state, update_state_with_alt};
+use pancurses::{A_BOLD, COLOR_PAIR, Input, Window};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Written next to the config file once the tutorial was shown on the
+/// first launch, so it is not shown again.
+const SHOWN_MARKER: &str = "tutorial_shown";
+
+/// The file changed in the sandbox, with two changes far enough apart to
+/// make two hunks.
+const SANDBOX_FILE: &str = "shopping.txt";
+const SANDBOX_BEFORE: &str =
+    "apples\nbread\nmilk\neggs\nbutter\ncheese\nrice\npasta\ntomatoes\nonions\ncoffee\ntea\n";
+const SANDBOX_AFTER: &str = "apples\nrye bread\noat milk\neggs\nbutter\ncheese\nrice\npasta\ntomatoes\nonions\ncoffee\ngreen tea\n";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Step {
+    StageHunk,
+    UnstageLine,
+    Undo,
+    Commit,
+    Done,
+}
+
+impl Step {
+    fn title(self) -> &'static str {
+        match self {
+            Step::StageHunk => "Stage a hunk",
+            Step::UnstageLine => "Unstage a line",
+            Step::Undo => "Undo",
+            Step::Commit => "Commit",
+            Step::Done => "Done",
+        }
+    }
+
+    fn text(self) -> &'static [&'static str] {
+        match self {
+            Step::StageHunk => &[
+                "This is a scratch repository with one changed file.",
+                "Press Tab to go to the unstaged changes, Down to pick",
+                "the file, j to move to a change and u to stage its hunk.",
+            ],
+            Step::UnstageLine => &[
+                "Press Tab to go back to the staged file, j or k to move",
+                "to a changed line and 1 to unstage just that line.",
+            ],
+            Step::Undo => &["Changed your mind? Press < to undo the last change."],
+            Step::Commit => &[
+                "Move down to the commit message, type a message and",
+                "press Alt+Enter. What is left is staged for the next one.",
+            ],
+            Step::Done => &[
+                "That's it. Everything happened in a scratch repository,",
+                "yours was not touched. Press ? and t to see this again.",
+            ],
+        }
+    }
+
+    fn next(self) -> Step {
+        match self {
+            Step::StageHunk => Step::UnstageLine,
+            Step::UnstageLine => Step::Undo,
+            Step::Undo => Step::Commit,
+            Step::Commit | Step::Done => Step::Done,
+        }
+    }
+}
+
+/// Removes the sandbox repository once the tutorial is closed.
+struct SandboxDir(PathBuf);
+
+impl Drop for SandboxDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// A guided tour run against a scratch repository: keys go to the real
+/// handlers of a second app state, and a box on top explains what to press.
+pub struct Tutorial {
+    pub sandbox: Box<AppState>,
+    pub step: Step,
+    /// Staged changed lines and commits when the step started, to tell
+    /// when it was done.
+    staged_lines: usize,
+    commits: usize,
+    _dir: SandboxDir,
+}
+
+fn staged_lines(state: &AppState) -> usize {
+    state
+        .files
+        .iter()
+        .flat_map(|file| &file.hunks)
+        .flat_map(|hunk| &hunk.lines)
+        .filter(|line| line.starts_with('+') || line.starts_with('-'))
+        .count()
+}
+
+fn commit_count(repo_path: &Path) -> usize {
+    git::run_git_command(repo_path, &["rev-list", "--count", "HEAD"])
+        .ok()
+        .and_then(|count| count.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+fn create_sandbox(path: &Path) -> anyhow::Result<()> {
+    let _ = std::fs::remove_dir_all(path);
+    std::fs::create_dir_all(path)?;
+    git::run_git_command(path, &["init", "-q"])?;
+    for (key, value) in [
+        ("user.name", "Tutorial"),
+        ("user.email", "tutorial@example.com"),
+        ("commit.gpgsign", "false"),
+    ] {
+        git::run_git_command(path, &["config", key, value])?;
+    }
+    std::fs::write(path.join(SANDBOX_FILE), SANDBOX_BEFORE)?;
+    git::run_git_command(path, &["add", SANDBOX_FILE])?;
+    git::run_git_command(path, &["commit", "-q", "-m", "Add the shopping list"])?;
+    std::fs::write(path.join(SANDBOX_FILE), SANDBOX_AFTER)?;
+    Ok(())
+}
+
+pub fn open(state: &mut AppState) {
+    static OPENED: AtomicUsize = AtomicUsize::new(0);
+    let path = std::env::temp_dir().join(format!(
+        "git-full-commit-tutorial-{}-{}",
+        std::process::id(),
+        OPENED.fetch_add(1, Ordering::Relaxed)
+    ));
+    if let Err(e) = create_sandbox(&path) {
+        let _ = std::fs::remove_dir_all(&path);
+        state.set_message(
+            Severity::Error,
+            format!("Failed to start the tutorial: {e}"),
+        );
+        return;
+    }
+    let sandbox = AppState::new(path.clone(), git::get_diff(path.clone()));
+    state.tutorial = Some(Tutorial {
+        sandbox: Box::new(sandbox),
+        step: Step::StageHunk,
+        staged_lines: 0,
+        commits: commit_count(&path),
+        _dir: SandboxDir(path),
+    });
+}
+
+/// Opens the tutorial on the very first launch, before a config file was
+/// ever written.
+pub fn open_on_first_run(state: &mut AppState) {
+    let Some(config_path) = Config::path() else {
+        return;
+    };
+    let marker = config_path.with_file_name(SHOWN_MARKER);
+    if config_path.exists() || marker.exists() {
+        return;
+    }
+    if let Some(dir) = marker.parent() {
+        let _ = std::fs::create_dir_all(dir);
+    }
+    let _ = std::fs::write(&marker, "");
+    open(state);
+}
+
+impl Tutorial {
+    fn is_step_done(&self) -> bool {
+        let repo_path = &self.sandbox.repo_path;
+        match self.step {
+            Step::StageHunk | Step::Undo => staged_lines(&self.sandbox) > self.staged_lines,
+            Step::UnstageLine => staged_lines(&self.sandbox) < self.staged_lines,
+            Step::Commit => commit_count(repo_path) > self.commits,
+            Step::Done => false,
+        }
+    }
+
+    fn advance(&mut self) {
+        self.step = self.step.next();
+        self.staged_lines = staged_lines(&self.sandbox);
+        self.commits = commit_count(&self.sandbox.repo_path);
+    }
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32, max_x: i32) {
+    forward(state, |sandbox| {
+        update_state(sandbox, Some(input), max_y, max_x)
+    });
+}
+
+/// Alt-modified keys, so `Alt+Enter` commits in the sandbox too.
+pub fn handle_alt_input(state: &mut AppState, input: Input, max_y: i32, max_x: i32) {
+    forward(state, |sandbox| {
+        update_state_with_alt(sandbox, Some(input), max_y, max_x)
+    });
+}
+
+/// Runs the key through `update` on the sandbox and moves to the next step
+/// once the current one is done.
+fn forward(state: &mut AppState, update: impl FnOnce(AppState) -> AppState) {
+    let Some(mut tutorial) = state.tutorial.take() else {
+        return;
+    };
+    if tutorial.step == Step::Done {
+        return;
+    }
+
+    let mut sandbox = update(*tutorial.sandbox);
+    if sandbox.editor_request.take().is_some() {
+        sandbox.set_message(
+            Severity::Warning,
+            "The editor is not opened in the tutorial",
+        );
+    }
+    tutorial.sandbox = Box::new(sandbox);
+
+    if tutorial.is_step_done() {
+        tutorial.advance();
+        // Committing everything quits, which only ends the sandbox here.
+        tutorial.sandbox.running = true;
+    }
+    if tutorial.sandbox.running {
+        state.tutorial = Some(tutorial);
+    } else {
+        // Quitting keeps the commit message of the scratch repository,
+        // which is gone with the tutorial.
+        let _ = commit_storage::delete_commit_message(&tutorial.sandbox.repo_path);
+    }
+}
+
+pub fn render(window: &Window, tutorial: &Tutorial) {
+    let (max_y, max_x) = window.get_max_yx();
+    let steps = Step::Done as usize;
+    let title = match tutorial.step {
+        Step::Done => format!(" Tutorial: {} ", tutorial.step.title()),
+        step => format!(" Tutorial {}/{steps}: {} ", step as usize + 1, step.title()),
+    };
+    let footer = match tutorial.step {
+        Step::Done => "Press any key to close the tutorial",
+        _ => "Q closes the tutorial",
+    };
+    let text = tutorial.step.text();
+
+    let content_width = text
+        .iter()
+        .map(|line| line.chars().count())
+        .chain([title.chars().count(), footer.chars().count()])
+        .max()
+        .unwrap_or(0);
+    let width = (content_width + 2).min(max_x.max(0) as usize);
+    let height = text.len() + 3;
+    let top = (max_y - 1 - height as i32).max(0);
+    let left = (max_x - width as i32).max(0);
+
+    for row in 0..height as i32 {
+        let pair = if row == 0 { 8 } else { 5 };
+        window.attron(COLOR_PAIR(pair));
+        for x in left..max_x {
+            window.mvaddch(top + row, x, ' ');
+        }
+        window.attroff(COLOR_PAIR(pair));
+    }
+
+    window.attron(COLOR_PAIR(8) | A_BOLD);
+    window.mvaddstr(top, left, layout::truncate_to_width(&title, width));
+    window.attroff(COLOR_PAIR(8) | A_BOLD);
+    window.attron(COLOR_PAIR(5));
+    for (row, line) in text.iter().enumerate() {
+        window.mvaddstr(
+            top + 1 + row as i32,
+            left + 1,
+            layout::truncate_to_width(line, width.saturating_sub(1)),
+        );
+    }
+    window.attroff(COLOR_PAIR(5));
+    window.attron(COLOR_PAIR(5) | A_BOLD);
+    window.mvaddstr(
+        top + height as i32 - 1,
+        left + 1,
+        layout::truncate_to_width(footer, width.saturating_sub(1)),
+    );
+    window.attroff(COLOR_PAIR(5) | A_BOLD);
+}
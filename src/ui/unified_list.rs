@@ -0,0 +1,260 @@
+use crate::app_state::{AppState, Screen};
+use crate::command::{
+    ApplyPatchCommand, Command, StageFileCommand, StagePatchCommand, UnstageFileCommand,
+};
+use crate::git::{FileDiff, FileStatus, Hunk};
+use crate::git_patch;
+use crate::ui::chrome;
+use crate::ui::layout;
+use pancurses::{A_BOLD, COLOR_PAIR, Input, Window};
+use std::collections::BTreeMap;
+
+const TITLE: &str = "Files";
+
+const HINTS: &str = "staged | unstaged hunks  → stage a hunk  ← unstage a hunk  q close";
+
+/// Lines above the file list.
+const BANNER_HEIGHT: usize = 1;
+
+/// Width of the `S<n> U<n>` badges in front of the file names.
+const BADGE_WIDTH: usize = 9;
+
+/// Every changed file in one list, staged or not, for moving hunks between
+/// the index and the working tree without switching panes.
+pub struct UnifiedList {
+    pub cursor: usize,
+    pub scroll: usize,
+}
+
+/// A row of the list: the staged and the unstaged side of one file.
+pub struct UnifiedEntry<'a> {
+    pub file_name: &'a str,
+    pub staged: Option<&'a FileDiff>,
+    pub unstaged: Option<&'a FileDiff>,
+    pub is_untracked: bool,
+}
+
+impl UnifiedEntry<'_> {
+    pub fn staged_hunks(&self) -> usize {
+        hunk_count(self.staged)
+    }
+
+    pub fn unstaged_hunks(&self) -> usize {
+        if self.is_untracked {
+            1
+        } else {
+            hunk_count(self.unstaged)
+        }
+    }
+}
+
+/// Changes without hunks, like a binary file, count as one.
+fn hunk_count(file: Option<&FileDiff>) -> usize {
+    file.map_or(0, |file| file.hunks.len().max(1))
+}
+
+/// The files of both panes, sorted by path.
+pub fn entries(state: &AppState) -> Vec<UnifiedEntry<'_>> {
+    let mut entries: BTreeMap<&str, UnifiedEntry> = BTreeMap::new();
+    let entry = |file_name| UnifiedEntry {
+        file_name,
+        staged: None,
+        unstaged: None,
+        is_untracked: false,
+    };
+    for file in &state.files {
+        let name = file.file_name.as_str();
+        entries.entry(name).or_insert_with(|| entry(name)).staged = Some(file);
+    }
+    for file in &state.unstaged_pane.unstaged_files {
+        let name = file.file_name.as_str();
+        entries.entry(name).or_insert_with(|| entry(name)).unstaged = Some(file);
+    }
+    for name in &state.unstaged_pane.untracked_files {
+        let name = name.as_str();
+        entries
+            .entry(name)
+            .or_insert_with(|| entry(name))
+            .is_untracked = true;
+    }
+    entries.into_values().collect()
+}
+
+impl UnifiedList {
+    fn content_height(max_y: i32) -> usize {
+        (max_y.max(0) as usize).saturating_sub(BANNER_HEIGHT + 1)
+    }
+
+    fn move_cursor(&mut self, delta: isize, count: usize, max_y: i32) {
+        self.cursor = self
+            .cursor
+            .saturating_add_signed(delta)
+            .min(count.saturating_sub(1));
+
+        let height = Self::content_height(max_y).max(1);
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + height {
+            self.scroll = self.cursor + 1 - height;
+        }
+    }
+}
+
+pub fn open(state: &mut AppState) {
+    state.screen = Some(Screen::UnifiedList(UnifiedList {
+        cursor: 0,
+        scroll: 0,
+    }));
+}
+
+/// The first hunk of `file`, when it can be moved on its own. Other changes,
+/// like a deleted or a binary file, move as a whole.
+fn first_hunk(file: &FileDiff) -> Option<&Hunk> {
+    if file.status == FileStatus::Modified {
+        file.hunks.first()
+    } else {
+        None
+    }
+}
+
+/// Stages the first unstaged hunk of the selected file.
+fn stage_hunk(state: &mut AppState) {
+    let Some(command) = selected(state).and_then(|entry| -> Option<Box<dyn Command>> {
+        let repo_path = state.repo_path.clone();
+        if entry.is_untracked {
+            return Some(Box::new(StageFileCommand::new(
+                repo_path,
+                entry.file_name.to_string(),
+            )));
+        }
+        let file = entry.unstaged?;
+        let hunk = first_hunk(file).filter(|_| !state.textconv_files.contains(&file.file_name));
+        Some(match hunk {
+            Some(hunk) => Box::new(StagePatchCommand::new(
+                repo_path,
+                git_patch::create_stage_hunk_patch(file, hunk),
+            )),
+            None => Box::new(StageFileCommand::new(repo_path, file.file_name.clone())),
+        })
+    }) else {
+        return;
+    };
+    state.execute_and_guard(command);
+}
+
+/// Moves the first staged hunk of the selected file back to the working tree.
+fn unstage_hunk(state: &mut AppState) {
+    let Some(command) = selected(state).and_then(|entry| -> Option<Box<dyn Command>> {
+        let file = entry.staged?;
+        let repo_path = state.repo_path.clone();
+        let hunk = first_hunk(file).filter(|_| !state.textconv_files.contains(&file.file_name));
+        Some(match hunk {
+            Some(hunk) => Box::new(ApplyPatchCommand::new(
+                repo_path,
+                git_patch::create_unstage_hunk_patch(file, hunk),
+            )),
+            None => Box::new(UnstageFileCommand::new(repo_path, file.file_name.clone())),
+        })
+    }) else {
+        return;
+    };
+    state.execute_and_refresh(command);
+}
+
+fn selected(state: &AppState) -> Option<UnifiedEntry<'_>> {
+    let Some(Screen::UnifiedList(list)) = &state.screen else {
+        return None;
+    };
+    let cursor = list.cursor;
+    entries(state).into_iter().nth(cursor)
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let count = entries(state).len();
+    let Some(Screen::UnifiedList(list)) = state.screen.as_mut() else {
+        return;
+    };
+    let page = UnifiedList::content_height(max_y).max(1) as isize;
+
+    match input {
+        Input::Character('q')
+        | Input::Character('U')
+        | Input::Character('\u{1b}')
+        | Input::Character('\t') => {
+            state.screen = None;
+            return;
+        }
+        Input::KeyRight | Input::Character('l') => stage_hunk(state),
+        Input::KeyLeft | Input::Character('h') => unstage_hunk(state),
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            list.move_cursor(1, count, max_y)
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            list.move_cursor(-1, count, max_y)
+        }
+        Input::Character(' ') | Input::Character('\u{16}') | Input::KeyNPage => {
+            list.move_cursor(page, count, max_y)
+        }
+        Input::Character('b') | Input::KeyPPage => list.move_cursor(-page, count, max_y),
+        _ => {}
+    }
+
+    // Files drop out of the list once nothing is left to stage or unstage.
+    let count = entries(state).len();
+    if let Some(Screen::UnifiedList(list)) = state.screen.as_mut() {
+        list.move_cursor(0, count, max_y);
+    }
+}
+
+pub fn render(window: &Window, state: &AppState, list: &UnifiedList) {
+    let (max_y, max_x) = window.get_max_yx();
+    let width = max_x.max(0) as usize;
+
+    let repo = chrome::repo_name(&state.repo_path);
+    chrome::render_title(window, &repo, &[TITLE], HINTS);
+
+    let entries = entries(state);
+    if entries.is_empty() {
+        window.mvaddstr(BANNER_HEIGHT as i32, 0, " No changes");
+    }
+    let visible = entries
+        .iter()
+        .enumerate()
+        .skip(list.scroll)
+        .take(UnifiedList::content_height(max_y));
+    for (row, (index, entry)) in visible.enumerate() {
+        let y = (BANNER_HEIGHT + row) as i32;
+        let selected = index == list.cursor;
+        let (pair, staged_pair, unstaged_pair) = if selected { (5, 7, 6) } else { (1, 3, 2) };
+        window.attron(COLOR_PAIR(pair));
+        for x in 0..max_x {
+            window.mvaddch(y, x, ' ');
+        }
+        window.attroff(COLOR_PAIR(pair));
+
+        for (x, count, label, badge_pair) in [
+            (1, entry.staged_hunks(), 'S', staged_pair),
+            (5, entry.unstaged_hunks(), 'U', unstaged_pair),
+        ] {
+            if count > 0 {
+                window.attron(COLOR_PAIR(badge_pair) | A_BOLD);
+                window.mvaddstr(y, x, format!("{label}{count}"));
+                window.attroff(COLOR_PAIR(badge_pair) | A_BOLD);
+            }
+        }
+
+        window.attron(COLOR_PAIR(pair));
+        window.mvaddstr(
+            y,
+            BADGE_WIDTH as i32,
+            layout::truncate_to_width(entry.file_name, width.saturating_sub(BADGE_WIDTH)),
+        );
+        window.attroff(COLOR_PAIR(pair));
+    }
+
+    if let Some(error) = state.message_text() {
+        window.attron(COLOR_PAIR(2));
+        window.mvaddstr(max_y - 1, 0, layout::truncate_to_width(error, width));
+        window.attroff(COLOR_PAIR(2));
+    }
+}
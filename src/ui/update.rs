@@ -1,14 +1,107 @@
-use crate::app_state::{AppState, FocusedPane};
+use crate::app_state::{AppState, FocusedPane, Modal, Screen};
 use crate::commit_storage;
 use crate::cursor_state::CursorState;
+use crate::edit_stage;
+use crate::empty_commit;
 use crate::ui::commit_view;
 use crate::ui::main_screen::{self, ListItem as MainScreenListItem};
+use crate::ui::{
+    diagnostics_view, discard_bin, explain, final_review, fixup_picker, format_preview, help,
+    history, hook_output, ignore_picker, index_lock, maintenance, message_log_view, patch_preview,
+    plan_preview, prompt, rebase_plan, rebase_recovery, restore_picker, reword_preview, stashes,
+    stat_panel, status_filter, tutorial, unified_list, worktrees,
+};
 use pancurses::Input;
 
 pub fn update_state(mut state: AppState, input: Option<Input>, max_y: i32, max_x: i32) -> AppState {
-    state.error_message = None;
+    state.message = None;
 
     if let Some(input) = input {
+        if state.tutorial.is_some() {
+            tutorial::handle_input(&mut state, input, max_y, max_x);
+            return state;
+        }
+
+        if state.prompt.is_some() {
+            prompt::handle_input(&mut state, input);
+            return state;
+        }
+
+        if state.status_filter_menu {
+            status_filter::handle_menu_input(&mut state, input);
+            return state;
+        }
+
+        // Any other key leaves the edit unstaged.
+        if let Some(offer) = state.edit_stage_offer.take() {
+            match input {
+                Input::Character('y') => {
+                    edit_stage::stage(&mut state, offer);
+                    return state;
+                }
+                Input::Character('n') | Input::Character('\u{1b}') => return state,
+                _ => {}
+            }
+        }
+
+        // Any other key leaves them staged.
+        if let Some(files) = state.empty_commit_cleanup.take() {
+            match input {
+                Input::Character('u') => {
+                    empty_commit::unstage(&mut state, files);
+                    return state;
+                }
+                Input::Character('\u{1b}') => return state,
+                _ => {}
+            }
+        }
+
+        if let Some(modal) = &state.modal {
+            match modal {
+                Modal::PlanPreview(_) => plan_preview::handle_input(&mut state, input, max_y),
+                Modal::RewordPreview(_) => reword_preview::handle_input(&mut state, input, max_y),
+                Modal::HookOutput(_) => hook_output::handle_input(&mut state, input, max_y),
+                Modal::RebaseRecovery(_) => rebase_recovery::handle_input(&mut state, input, max_y),
+                Modal::Explanation(_) => explain::handle_input(&mut state, input, max_y),
+                Modal::IndexLockWait(_) => index_lock::handle_input(&mut state, input),
+                Modal::PatchPreview(_) => patch_preview::handle_input(&mut state, input, max_y),
+                Modal::StatPanel(_) => stat_panel::handle_input(&mut state, input, max_y),
+                Modal::IgnorePicker(_) => ignore_picker::handle_input(&mut state, input),
+                Modal::FormatPreview(_) => format_preview::handle_input(&mut state, input, max_y),
+            }
+            return state;
+        }
+
+        if let Some(screen) = &state.screen {
+            match screen {
+                Screen::FinalReview(_) => final_review::handle_input(&mut state, input, max_y),
+                Screen::History(_) => history::handle_input(&mut state, input, max_y),
+                Screen::RebasePlan(_) => rebase_plan::handle_input(&mut state, input, max_y),
+                Screen::DiscardBin(_) => discard_bin::handle_input(&mut state, input, max_y),
+                Screen::RestorePicker(_) => restore_picker::handle_input(&mut state, input, max_y),
+                Screen::FixupPicker(_) => fixup_picker::handle_input(&mut state, input, max_y),
+                Screen::Worktrees(_) => worktrees::handle_input(&mut state, input, max_y),
+                Screen::Maintenance(_) => maintenance::handle_input(&mut state, input),
+                Screen::UnifiedList(_) => unified_list::handle_input(&mut state, input, max_y),
+                Screen::Diagnostics(_) => diagnostics_view::handle_input(&mut state, input, max_y),
+                Screen::MessageLog(_) => message_log_view::handle_input(&mut state, input, max_y),
+                Screen::Help(_) => help::handle_input(&mut state, input, max_y),
+                Screen::Stashes(_) => stashes::handle_input(&mut state, input, max_y),
+            }
+            return state;
+        }
+
+        // Rebound keys reach the handlers as the default keys of their
+        // actions. Text input and reordering have keys of their own.
+        let input = if state.is_in_input_mode() || state.main_screen.is_reordering_commits {
+            input
+        } else {
+            match state.keymap.translate(input) {
+                Some(input) => input,
+                None => return state,
+            }
+        };
+
         // Global commands
         match input {
             Input::Character('\t') => {
@@ -57,6 +150,8 @@ pub fn update_state(mut state: AppState, input: Option<Input>, max_y: i32, max_x
                             }
                         }
                     }
+                } else if state.focused_pane == FocusedPane::Main {
+                    commit_view::complete_scope(&mut state, max_x);
                 }
             }
             Input::Character('\u{3}') => {
@@ -66,25 +161,23 @@ pub fn update_state(mut state: AppState, input: Option<Input>, max_y: i32, max_x
                     main_screen::handle_input(&mut state, input, max_y, max_x);
                 } else {
                     // Otherwise, it's a global quit command.
-                    let _ = commit_storage::save_commit_message(
+                    let _ = commit_storage::save_draft(
                         &state.repo_path,
                         &state.main_screen.commit_message,
+                        state.main_screen.commit_template.as_deref(),
                     );
                     state.running = false;
                     return state;
                 }
             }
-            Input::Character('Q') => {
-                if !state.is_in_input_mode() {
-                    let _ = commit_storage::save_commit_message(
-                        &state.repo_path,
-                        &state.main_screen.commit_message,
-                    );
-                    state.running = false;
-                    return state;
-                } else {
-                    main_screen::handle_input(&mut state, input, max_y, max_x);
-                }
+            Input::Character('Q') if !state.is_in_input_mode() => {
+                let _ = commit_storage::save_draft(
+                    &state.repo_path,
+                    &state.main_screen.commit_message,
+                    state.main_screen.commit_template.as_deref(),
+                );
+                state.running = false;
+                return state;
             }
             Input::Character('<') => {
                 if !state.is_in_input_mode() {
@@ -95,6 +188,7 @@ pub fn update_state(mut state: AppState, input: Option<Input>, max_y: i32, max_x
                     } else {
                         state.refresh_diff(false);
                     }
+                    state.show_command_failure();
                     return state;
                 }
             }
@@ -107,6 +201,7 @@ pub fn update_state(mut state: AppState, input: Option<Input>, max_y: i32, max_x
                     } else {
                         state.refresh_diff(false);
                     }
+                    state.show_command_failure();
                     return state;
                 }
             }
@@ -123,16 +218,6 @@ pub fn update_state(mut state: AppState, input: Option<Input>, max_y: i32, max_x
     state
 }
 
-use crate::command::SwapCommitCommand;
-
-fn is_item_on_remote(item: &MainScreenListItem) -> bool {
-    match item {
-        MainScreenListItem::PreviousCommitInfo { is_on_remote, .. } => *is_on_remote,
-        MainScreenListItem::EditingReorderCommit { is_on_remote, .. } => *is_on_remote,
-        _ => false,
-    }
-}
-
 pub fn update_state_with_alt(
     mut state: AppState,
     input: Option<Input>,
@@ -140,109 +225,74 @@ pub fn update_state_with_alt(
     max_x: i32,
 ) -> AppState {
     if let Some(input) = input {
+        if state.prompt.is_some() {
+            prompt::handle_alt_input(&mut state, input);
+            return state;
+        }
+
+        if state.tutorial.is_some() {
+            tutorial::handle_alt_input(&mut state, input, max_y, max_x);
+            return state;
+        }
+
+        if state.screen.is_some() || state.modal.is_some() || state.status_filter_menu {
+            return state;
+        }
+
         if state.is_in_input_mode() {
             main_screen::handle_alt_input(&mut state, input, max_y, max_x);
             return state;
         }
 
-        if !state.main_screen.is_reordering_commits {
-            if let Some(MainScreenListItem::PreviousCommitInfo { .. }) = state.current_main_item() {
-                match input {
-                    Input::KeyUp | Input::KeyDown => {
+        if !state.main_screen.is_reordering_commits
+            && let Some(MainScreenListItem::PreviousCommitInfo { .. }) = state.current_main_item()
+        {
+            match input {
+                Input::KeyUp | Input::KeyDown => {
+                    main_screen::start_reorder_mode(&mut state);
+                }
+                Input::Character('\n') => {
+                    if let Some(MainScreenListItem::PreviousCommitInfo {
+                        hash,
+                        message,
+                        is_on_remote,
+                        is_fixup,
+                    }) = state.current_main_item().cloned()
+                        && !is_on_remote
+                    {
                         main_screen::start_reorder_mode(&mut state);
-                    }
-                    Input::Character('\n') => {
-                        if let Some(MainScreenListItem::PreviousCommitInfo {
-                            hash,
-                            message,
-                            is_on_remote,
-                            is_fixup,
-                        }) = state.current_main_item().cloned()
-                        {
-                            if !is_on_remote {
-                                main_screen::start_reorder_mode(&mut state);
-                                let current_index = state.main_screen.file_cursor;
-                                if let Some(item) =
-                                    state.main_screen.list_items.get_mut(current_index)
-                                {
-                                    let cursor = message.chars().count();
-                                    let (scroll_offset, scroll_extra_space) =
-                                        commit_view::compute_scroll_for_prefix(
-                                            message.as_str(),
-                                            cursor,
-                                            max_x,
-                                            " ● ",
-                                        );
-                                    *item = MainScreenListItem::EditingReorderCommit {
-                                        hash,
-                                        original_message: message.clone(),
-                                        current_text: message.clone(),
-                                        cursor,
-                                        scroll_offset,
-                                        scroll_extra_space,
-                                        is_on_remote,
-                                        is_fixup,
-                                    };
-                                }
-                            }
+                        let current_index = state.main_screen.file_cursor;
+                        if let Some(item) = state.main_screen.list_items.get_mut(current_index) {
+                            let cursor = message.chars().count();
+                            let (scroll_offset, scroll_extra_space) =
+                                commit_view::compute_scroll_for_prefix(
+                                    message.as_str(),
+                                    cursor,
+                                    max_x,
+                                    " ● ",
+                                );
+                            *item = MainScreenListItem::EditingReorderCommit {
+                                hash,
+                                original_message: message.clone(),
+                                current_text: message.clone(),
+                                cursor,
+                                scroll_offset,
+                                scroll_extra_space,
+                                is_on_remote,
+                                is_fixup,
+                            };
                         }
-                        return state;
                     }
-                    _ => {}
+                    return state;
                 }
+                _ => {}
             }
         }
 
         if state.main_screen.is_reordering_commits {
             match input {
-                Input::KeyUp => {
-                    let cursor = state.main_screen.file_cursor;
-                    if cursor > 0 {
-                        let can_swap = match (
-                            state.main_screen.list_items.get(cursor),
-                            state.main_screen.list_items.get(cursor - 1),
-                        ) {
-                            (Some(item1), Some(item2)) => {
-                                !is_item_on_remote(item1) && !is_item_on_remote(item2)
-                            }
-                            _ => false,
-                        };
-
-                        if can_swap {
-                            let command = Box::new(SwapCommitCommand::new(
-                                &mut state.main_screen.list_items,
-                                cursor,
-                                cursor - 1,
-                            ));
-                            state.execute_reorder_command(command);
-                            state.main_screen.file_cursor -= 1;
-                        }
-                    }
-                }
-                Input::KeyDown => {
-                    let cursor = state.main_screen.file_cursor;
-                    if cursor < state.main_screen.list_items.len() - 1 {
-                        let can_swap = match (
-                            state.main_screen.list_items.get(cursor),
-                            state.main_screen.list_items.get(cursor + 1),
-                        ) {
-                            (Some(item1), Some(item2)) => {
-                                !is_item_on_remote(item1) && !is_item_on_remote(item2)
-                            }
-                            _ => false,
-                        };
-
-                        if can_swap {
-                            let command = Box::new(SwapCommitCommand::new(
-                                &mut state.main_screen.list_items,
-                                cursor,
-                                cursor + 1,
-                            ));
-                            state.execute_reorder_command(command);
-                            state.main_screen.file_cursor += 1;
-                        }
-                    }
-                }
+                Input::KeyUp => main_screen::move_reorder_commit(&mut state, -1),
+                Input::KeyDown => main_screen::move_reorder_commit(&mut state, 1),
                 Input::Character('\n') => {
                     let current_index = state.main_screen.file_cursor;
                     if let Some(MainScreenListItem::PreviousCommitInfo {
@@ -251,30 +301,27 @@ pub fn update_state_with_alt(
                         is_on_remote,
                         is_fixup,
                     }) = state.main_screen.list_items.get(current_index).cloned()
+                        && !is_on_remote
+                        && let Some(item) = state.main_screen.list_items.get_mut(current_index)
                     {
-                        if !is_on_remote {
-                            if let Some(item) = state.main_screen.list_items.get_mut(current_index)
-                            {
-                                let cursor = message.chars().count();
-                                let (scroll_offset, scroll_extra_space) =
-                                    commit_view::compute_scroll_for_prefix(
-                                        message.as_str(),
-                                        cursor,
-                                        max_x,
-                                        " ● ",
-                                    );
-                                *item = MainScreenListItem::EditingReorderCommit {
-                                    hash,
-                                    original_message: message.clone(),
-                                    current_text: message.clone(),
-                                    cursor,
-                                    scroll_offset,
-                                    scroll_extra_space,
-                                    is_on_remote,
-                                    is_fixup,
-                                };
-                            }
-                        }
+                        let cursor = message.chars().count();
+                        let (scroll_offset, scroll_extra_space) =
+                            commit_view::compute_scroll_for_prefix(
+                                message.as_str(),
+                                cursor,
+                                max_x,
+                                " ● ",
+                            );
+                        *item = MainScreenListItem::EditingReorderCommit {
+                            hash,
+                            original_message: message.clone(),
+                            current_text: message.clone(),
+                            cursor,
+                            scroll_offset,
+                            scroll_extra_space,
+                            is_on_remote,
+                            is_fixup,
+                        };
                     }
                 }
                 _ => {}
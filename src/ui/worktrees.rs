@@ -0,0 +1,203 @@
+use crate::app_state::{AppState, Screen};
+use crate::commit_storage;
+use crate::git::{self, Worktree};
+use crate::message_log::Severity;
+use crate::ui::chrome;
+use crate::ui::layout;
+use pancurses::{COLOR_PAIR, Input, Window};
+
+/// Lines above the worktree list.
+const BANNER_HEIGHT: usize = 1;
+
+const TITLE: &str = "Worktrees";
+
+const HINTS: &str = "Enter switch  q close";
+
+/// Screen listing the worktrees of the repository, opened with `W`, to
+/// switch the view to another one.
+pub struct WorktreePicker {
+    pub worktrees: Vec<Worktree>,
+    pub cursor: usize,
+    pub list_scroll: usize,
+}
+
+impl WorktreePicker {
+    pub fn selected(&self) -> Option<&Worktree> {
+        self.worktrees.get(self.cursor)
+    }
+
+    fn content_height(max_y: i32) -> usize {
+        (max_y.max(0) as usize).saturating_sub(BANNER_HEIGHT + 1)
+    }
+
+    fn move_cursor(&mut self, delta: isize, max_y: i32) {
+        let last = self.worktrees.len().saturating_sub(1);
+        self.cursor = self.cursor.saturating_add_signed(delta).min(last);
+
+        let height = Self::content_height(max_y).max(1);
+        if self.cursor < self.list_scroll {
+            self.list_scroll = self.cursor;
+        } else if self.cursor >= self.list_scroll + height {
+            self.list_scroll = self.cursor + 1 - height;
+        }
+    }
+}
+
+/// Lists the worktrees with the cursor on the current one, unless there
+/// is no other one to switch to.
+pub fn open(state: &mut AppState) {
+    let worktrees = match git::list_worktrees(&state.repo_path) {
+        Ok(worktrees) => worktrees,
+        Err(e) => {
+            state.set_message(
+                Severity::Error,
+                format!("Failed to list the worktrees: {e}"),
+            );
+            state.alert();
+            return;
+        }
+    };
+    if worktrees.len() < 2 {
+        state.set_message(
+            Severity::Warning,
+            "No other worktree, git worktree add creates one",
+        );
+        state.alert();
+        return;
+    }
+    state.screen = Some(Screen::Worktrees(WorktreePicker {
+        cursor: worktrees
+            .iter()
+            .position(|worktree| worktree.current)
+            .unwrap_or(0),
+        worktrees,
+        list_scroll: 0,
+    }));
+}
+
+/// Leaves the view, saving the draft like quitting does, to start it again
+/// on the selected worktree.
+fn switch_to_selected(state: &mut AppState) {
+    let Some(Screen::Worktrees(picker)) = &state.screen else {
+        return;
+    };
+    let Some(worktree) = picker.selected().cloned() else {
+        return;
+    };
+    let path = worktree.path.display();
+    let refusal = if worktree.current {
+        Some(format!("Already in {path}"))
+    } else if worktree.bare {
+        Some(format!(
+            "{path} is a bare repository, without a working tree"
+        ))
+    } else if worktree.prunable || !worktree.path.is_dir() {
+        Some(format!("{path} is missing, git worktree prune forgets it"))
+    } else {
+        None
+    };
+    if let Some(refusal) = refusal {
+        state.set_message(Severity::Warning, refusal);
+        state.alert();
+        return;
+    }
+
+    let _ = commit_storage::save_draft(
+        &state.repo_path,
+        &state.main_screen.commit_message,
+        state.main_screen.commit_template.as_deref(),
+    );
+    state.screen = None;
+    state.relaunch = Some(worktree.path);
+    state.running = false;
+}
+
+pub fn handle_input(state: &mut AppState, input: Input, max_y: i32) {
+    let Some(Screen::Worktrees(picker)) = state.screen.as_mut() else {
+        return;
+    };
+    let page = WorktreePicker::content_height(max_y).max(1) as isize;
+
+    match input {
+        Input::Character('q')
+        | Input::Character('W')
+        | Input::Character('\t')
+        | Input::Character('\u{1b}') => {
+            state.screen = None;
+        }
+        Input::Character('\n') => switch_to_selected(state),
+        Input::Character('j') | Input::KeyDown | Input::Character('\u{e}') => {
+            picker.move_cursor(1, max_y)
+        }
+        Input::Character('k') | Input::KeyUp | Input::Character('\u{10}') => {
+            picker.move_cursor(-1, max_y)
+        }
+        Input::Character(' ') | Input::Character('\u{16}') | Input::KeyNPage => {
+            picker.move_cursor(page, max_y)
+        }
+        Input::Character('b') | Input::KeyPPage => picker.move_cursor(-page, max_y),
+        _ => {}
+    }
+}
+
+/// The row of `worktree`: a `*` on the current one, the path, then what
+/// is checked out.
+pub fn worktree_line(worktree: &Worktree) -> String {
+    let checked_out = if worktree.bare {
+        "(bare)".to_string()
+    } else {
+        match &worktree.branch {
+            Some(branch) => branch.clone(),
+            None => format!(
+                "(detached at {})",
+                &worktree.head[..worktree.head.len().min(7)]
+            ),
+        }
+    };
+    format!(
+        "{} {}  {checked_out}{}",
+        if worktree.current { '*' } else { ' ' },
+        worktree.path.display(),
+        if worktree.prunable { "  (missing)" } else { "" }
+    )
+}
+
+pub fn render(window: &Window, repo: &str, picker: &WorktreePicker, error_message: Option<&str>) {
+    let (max_y, max_x) = window.get_max_yx();
+    let width = max_x.max(0) as usize;
+
+    chrome::render_title(window, repo, &[TITLE], HINTS);
+
+    let visible = picker
+        .worktrees
+        .iter()
+        .enumerate()
+        .skip(picker.list_scroll)
+        .take(WorktreePicker::content_height(max_y));
+    for (row, (index, worktree)) in visible.enumerate() {
+        let y = (BANNER_HEIGHT + row) as i32;
+        let pair = if index == picker.cursor {
+            5
+        } else if worktree.prunable {
+            9
+        } else {
+            1
+        };
+        window.attron(COLOR_PAIR(pair));
+        for x in 0..max_x {
+            window.mvaddch(y, x, ' ');
+        }
+        window.mvaddstr(
+            y,
+            0,
+            layout::truncate_to_width(&worktree_line(worktree), width),
+        );
+        window.attroff(COLOR_PAIR(pair));
+    }
+
+    if let Some(error) = error_message {
+        window.attron(COLOR_PAIR(2));
+        window.mvaddstr(max_y - 1, 0, layout::truncate_to_width(error, width));
+        window.attroff(COLOR_PAIR(2));
+    }
+}
@@ -1 +1,6 @@
+pub mod file_size;
+pub mod path_order;
+pub mod rename_groups;
+pub mod repo_path;
+pub mod terminal;
 pub mod word_diff;
@@ -0,0 +1,66 @@
+const UNITS: [(&str, u64); 4] = [
+    ("G", 1024 * 1024 * 1024),
+    ("M", 1024 * 1024),
+    ("K", 1024),
+    ("", 1),
+];
+
+/// Parses a size like `100M`, `512k`, `2GB` or a plain number of bytes.
+/// Units are powers of 1024 and case-insensitive.
+pub fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim().to_ascii_uppercase();
+    let text = text.strip_suffix('B').unwrap_or(&text);
+    UNITS.iter().find_map(|(unit, factor)| {
+        let number = text.strip_suffix(unit)?.trim();
+        if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        number.parse::<u64>().ok()?.checked_mul(*factor)
+    })
+}
+
+/// Formats `bytes` with the largest unit that keeps the number above one,
+/// e.g. `1.5 MB`.
+pub fn format_size(bytes: u64) -> String {
+    match UNITS.iter().find(|(_, factor)| bytes >= *factor) {
+        Some((unit, factor)) if *factor > 1 => {
+            let value = bytes as f64 / *factor as f64;
+            if value < 10.0 && !bytes.is_multiple_of(*factor) {
+                format!("{value:.1} {unit}B")
+            } else {
+                format!("{} {unit}B", bytes / factor)
+            }
+        }
+        _ => format!("{bytes} B"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("100M"), Some(100 * 1024 * 1024));
+        assert_eq!(parse_size("100MB"), Some(100 * 1024 * 1024));
+        assert_eq!(parse_size(" 512k "), Some(512 * 1024));
+        assert_eq!(parse_size("2G"), Some(2 * 1024 * 1024 * 1024));
+        assert_eq!(parse_size("1234"), Some(1234));
+        assert_eq!(parse_size("1234B"), Some(1234));
+        assert_eq!(parse_size(""), None);
+        assert_eq!(parse_size("M"), None);
+        assert_eq!(parse_size("-5M"), None);
+        assert_eq!(parse_size("1.5M"), None);
+        assert_eq!(parse_size("lots"), None);
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(999), "999 B");
+        assert_eq!(format_size(1024), "1 KB");
+        assert_eq!(format_size(1536 * 1024), "1.5 MB");
+        assert_eq!(format_size(150 * 1024 * 1024 + 1), "150 MB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3 GB");
+    }
+}
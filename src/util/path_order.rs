@@ -0,0 +1,28 @@
+use std::cmp::Ordering;
+
+/// Compares two repository-relative paths so that, at every directory level,
+/// subdirectories come before the files next to them and siblings are sorted
+/// by name. This mirrors how a file tree is laid out and keeps the order stable
+/// no matter which subset of paths is present.
+pub fn compare_directories_first(a: &str, b: &str) -> Ordering {
+    let a_parts: Vec<&str> = a.split('/').collect();
+    let b_parts: Vec<&str> = b.split('/').collect();
+
+    for (i, (a_part, b_part)) in a_parts.iter().zip(b_parts.iter()).enumerate() {
+        let a_is_dir = i + 1 < a_parts.len();
+        let b_is_dir = i + 1 < b_parts.len();
+        if a_is_dir != b_is_dir {
+            return if a_is_dir {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            };
+        }
+        match a_part.cmp(b_part) {
+            Ordering::Equal => continue,
+            other => return other,
+        }
+    }
+
+    a_parts.len().cmp(&b_parts.len())
+}
@@ -0,0 +1,195 @@
+use std::collections::{HashMap, HashSet};
+
+/// Fewest files moved between the same two directories that are collapsed
+/// into one entry.
+pub const MIN_GROUP_SIZE: usize = 2;
+
+/// Files moved from one directory to another, shown as a single entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenameGroup {
+    pub old_dir: String,
+    pub new_dir: String,
+    /// `(old path, new path)` of every file in the group, in list order.
+    pub renames: Vec<(String, String)>,
+}
+
+impl RenameGroup {
+    pub fn key(&self) -> (String, String) {
+        (self.old_dir.clone(), self.new_dir.clone())
+    }
+
+    pub fn contains(&self, path: &str) -> bool {
+        self.renames
+            .iter()
+            .any(|(old, new)| old == path || new == path)
+    }
+
+    /// All old and new paths, e.g. to pass them as a pathspec.
+    pub fn paths(&self) -> Vec<String> {
+        self.renames
+            .iter()
+            .flat_map(|(old, new)| [old.clone(), new.clone()])
+            .collect()
+    }
+
+    pub fn label(&self) -> String {
+        format!(
+            "{}/ → {}/ ({} files)",
+            self.old_dir,
+            self.new_dir,
+            self.renames.len()
+        )
+    }
+}
+
+/// Splits a rename into the directories that changed, by stripping the path
+/// components both sides end with. Returns `None` unless at least the file
+/// name is shared and both sides are inside a directory.
+pub fn directory_rename(old: &str, new: &str) -> Option<(String, String)> {
+    let old_components: Vec<&str> = old.split('/').collect();
+    let new_components: Vec<&str> = new.split('/').collect();
+
+    let common_suffix = old_components
+        .iter()
+        .rev()
+        .zip(new_components.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_dir_len = old_components.len() - common_suffix;
+    let new_dir_len = new_components.len() - common_suffix;
+    if common_suffix == 0 || old_dir_len == 0 || new_dir_len == 0 {
+        return None;
+    }
+
+    Some((
+        old_components[..old_dir_len].join("/"),
+        new_components[..new_dir_len].join("/"),
+    ))
+}
+
+/// Groups renames that moved files between the same pair of directories.
+/// Only groups with at least [`MIN_GROUP_SIZE`] files are returned, ordered
+/// by their first file.
+pub fn find_rename_groups<'a>(
+    renames: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> Vec<RenameGroup> {
+    let mut groups: Vec<RenameGroup> = Vec::new();
+    for (old, new) in renames {
+        let Some((old_dir, new_dir)) = directory_rename(old, new) else {
+            continue;
+        };
+        let rename = (old.to_string(), new.to_string());
+        match groups
+            .iter_mut()
+            .find(|g| g.old_dir == old_dir && g.new_dir == new_dir)
+        {
+            Some(group) => group.renames.push(rename),
+            None => groups.push(RenameGroup {
+                old_dir,
+                new_dir,
+                renames: vec![rename],
+            }),
+        }
+    }
+    groups.retain(|group| group.renames.len() >= MIN_GROUP_SIZE);
+    groups
+}
+
+/// Pairs deleted files with untracked files that have the same name in
+/// another directory, i.e. a directory moved without `git mv`, and groups
+/// them like [`find_rename_groups`]. When a deleted file matches several
+/// untracked files, the directory pair shared by the most files wins.
+pub fn find_pending_rename_groups(deleted: &[&str], untracked: &[&str]) -> Vec<RenameGroup> {
+    let file_name = |path: &str| path.rsplit('/').next().unwrap_or(path).to_string();
+    let mut untracked_by_name: HashMap<String, Vec<&str>> = HashMap::new();
+    for path in untracked {
+        untracked_by_name
+            .entry(file_name(path))
+            .or_default()
+            .push(path);
+    }
+
+    let candidates: Vec<(&str, &str, (String, String))> = deleted
+        .iter()
+        .flat_map(|old| {
+            untracked_by_name
+                .get(&file_name(old))
+                .into_iter()
+                .flatten()
+                .filter_map(move |new| directory_rename(old, new).map(|dirs| (*old, *new, dirs)))
+        })
+        .collect();
+    let mut dir_pair_counts: HashMap<&(String, String), usize> = HashMap::new();
+    for (_, _, dirs) in &candidates {
+        *dir_pair_counts.entry(dirs).or_default() += 1;
+    }
+
+    let mut used_untracked = HashSet::new();
+    let mut pairs = Vec::new();
+    for old in deleted {
+        let best = candidates
+            .iter()
+            .filter(|(o, new, _)| o == old && !used_untracked.contains(new))
+            .max_by_key(|(_, _, dirs)| dir_pair_counts[dirs]);
+        if let Some((old, new, _)) = best {
+            used_untracked.insert(*new);
+            pairs.push((*old, *new));
+        }
+    }
+
+    find_rename_groups(pairs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directory_rename() {
+        assert_eq!(
+            directory_rename("src/old/a/b.rs", "src/new/a/b.rs"),
+            Some(("src/old".to_string(), "src/new".to_string()))
+        );
+        assert_eq!(
+            directory_rename("old/b.rs", "lib/new/b.rs"),
+            Some(("old".to_string(), "lib/new".to_string()))
+        );
+        assert_eq!(directory_rename("dir/a.rs", "dir/b.rs"), None);
+        assert_eq!(directory_rename("a.rs", "dir/a.rs"), None);
+    }
+
+    #[test]
+    fn test_find_rename_groups() {
+        let groups = find_rename_groups([
+            ("old/a.rs", "new/a.rs"),
+            ("other.rs", "renamed.rs"),
+            ("old/sub/b.rs", "new/sub/b.rs"),
+            ("x/c.rs", "y/c.rs"),
+        ]);
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(groups[0].old_dir, "old");
+        assert_eq!(groups[0].new_dir, "new");
+        assert_eq!(groups[0].renames.len(), 2);
+        assert_eq!(groups[0].label(), "old/ → new/ (2 files)");
+        assert!(groups[0].contains("new/sub/b.rs"));
+        assert!(!groups[0].contains("y/c.rs"));
+    }
+
+    #[test]
+    fn test_find_pending_rename_groups() {
+        let groups = find_pending_rename_groups(
+            &["old/a.rs", "old/mod.rs", "lonely.rs"],
+            &["new/a.rs", "other/mod.rs", "new/mod.rs", "unrelated.rs"],
+        );
+
+        assert_eq!(groups.len(), 1);
+        assert_eq!(
+            groups[0].renames,
+            vec![
+                ("old/a.rs".to_string(), "new/a.rs".to_string()),
+                ("old/mod.rs".to_string(), "new/mod.rs".to_string()),
+            ]
+        );
+    }
+}
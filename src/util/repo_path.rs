@@ -0,0 +1,56 @@
+use std::path::{Component, Path};
+
+/// Tells why the typed `path` cannot name a new file in the repository at
+/// `repo_path`: it points outside of it, or something is there already.
+pub fn new_path_refusal(repo_path: &Path, path: &str) -> Option<String> {
+    outside_refusal(path).or_else(|| existing_refusal(repo_path, path))
+}
+
+/// Refuses absolute paths and those going up with `..`, which would point
+/// anywhere on disk once joined to the repository path.
+pub fn outside_refusal(path: &str) -> Option<String> {
+    let outside = Path::new(path).components().any(|component| {
+        matches!(
+            component,
+            Component::ParentDir | Component::RootDir | Component::Prefix(_)
+        )
+    });
+    outside.then(|| format!("{path} is outside the repository"))
+}
+
+/// Refuses to write over what `path` names, so nothing is lost to a typo.
+pub fn existing_refusal(repo_path: &Path, path: &str) -> Option<String> {
+    // A dangling symlink is there too, and writing would follow it.
+    let exists = repo_path.join(path).symlink_metadata().is_ok();
+    exists.then(|| format!("{path} already exists"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_path_refusal() {
+        let dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "").unwrap();
+
+        assert_eq!(new_path_refusal(dir.path(), "b.txt"), None);
+        assert_eq!(new_path_refusal(dir.path(), "src/b.txt"), None);
+        assert_eq!(
+            new_path_refusal(dir.path(), "a.txt").as_deref(),
+            Some("a.txt already exists")
+        );
+        assert_eq!(
+            new_path_refusal(dir.path(), "../b.txt").as_deref(),
+            Some("../b.txt is outside the repository")
+        );
+        assert_eq!(
+            new_path_refusal(dir.path(), "src/../../b.txt").as_deref(),
+            Some("src/../../b.txt is outside the repository")
+        );
+        assert_eq!(
+            new_path_refusal(dir.path(), "/tmp/b.txt").as_deref(),
+            Some("/tmp/b.txt is outside the repository")
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Where ncurses looks for terminal descriptions when `TERMINFO` and
+/// `TERMINFO_DIRS` do not say otherwise, across common builds.
+const DEFAULT_TERMINFO_DIRS: [&str; 7] = [
+    "/etc/terminfo",
+    "/lib/terminfo",
+    "/usr/share/terminfo",
+    "/usr/lib/terminfo",
+    "/usr/local/share/terminfo",
+    "/opt/homebrew/share/terminfo",
+    "/usr/share/lib/terminfo",
+];
+
+/// Why curses cannot start here, if it cannot. `initscr` does not return an
+/// error in that case but ends the process with a bare `Error opening
+/// terminal`, so this is checked before.
+pub fn unusable_reason() -> Option<String> {
+    if !std::io::stdin().is_terminal() || !std::io::stdout().is_terminal() {
+        return Some("stdin and stdout are not a terminal".to_string());
+    }
+    // PDCurses on Windows does not use TERM.
+    if cfg!(windows) {
+        return None;
+    }
+    check_term(
+        &std::env::var("TERM").unwrap_or_default(),
+        is_known_terminal,
+    )
+}
+
+fn check_term(term: &str, is_known: impl Fn(&str) -> bool) -> Option<String> {
+    if term.is_empty() {
+        Some("TERM is not set".to_string())
+    } else if !is_known(term) {
+        Some(format!(
+            "the terminal type {term} is not known to this system"
+        ))
+    } else {
+        None
+    }
+}
+
+fn terminfo_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(dir) = std::env::var_os("TERMINFO") {
+        dirs.push(PathBuf::from(dir));
+    }
+    if let Some(home) = dirs::home_dir() {
+        dirs.push(home.join(".terminfo"));
+    }
+    if let Some(list) = std::env::var_os("TERMINFO_DIRS") {
+        dirs.extend(std::env::split_paths(&list).filter(|dir| !dir.as_os_str().is_empty()));
+    }
+    dirs.extend(DEFAULT_TERMINFO_DIRS.iter().map(PathBuf::from));
+    dirs
+}
+
+/// Whether `dir` has a description of `term`, filed under its first letter
+/// or, on macOS, under the hex code of it.
+fn has_entry(dir: &Path, term: &str) -> bool {
+    let Some(first) = term.bytes().next() else {
+        return false;
+    };
+    [(first as char).to_string(), format!("{first:02x}")]
+        .iter()
+        .any(|subdir| dir.join(subdir).join(term).is_file())
+}
+
+/// Whether `term` has a terminfo description. When none is found where
+/// ncurses usually looks, `infocmp` is asked, since builds can be
+/// configured with other places. Without `infocmp` the terminal is trusted.
+fn is_known_terminal(term: &str) -> bool {
+    if terminfo_dirs().iter().any(|dir| has_entry(dir, term)) {
+        return true;
+    }
+    Command::new("infocmp")
+        .arg(term)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_or(true, |status| status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_term() {
+        assert_eq!(check_term("", |_| true).as_deref(), Some("TERM is not set"));
+        assert_eq!(
+            check_term("foo", |_| false).as_deref(),
+            Some("the terminal type foo is not known to this system")
+        );
+        assert_eq!(check_term("xterm", |term| term == "xterm"), None);
+    }
+
+    #[test]
+    fn test_has_entry() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert!(!has_entry(dir.path(), "xterm"));
+        std::fs::create_dir(dir.path().join("x")).unwrap();
+        std::fs::write(dir.path().join("x/xterm"), "").unwrap();
+        assert!(has_entry(dir.path(), "xterm"));
+
+        std::fs::create_dir(dir.path().join("73")).unwrap();
+        std::fs::write(dir.path().join("73/screen"), "").unwrap();
+        assert!(has_entry(dir.path(), "screen"));
+        assert!(!has_entry(dir.path(), ""));
+    }
+}
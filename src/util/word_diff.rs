@@ -18,9 +18,7 @@ pub fn compute_word_diffs(old: &str, new: &str) -> (Vec<WordDiffLine>, Vec<WordD
         return (old_lines, new_lines);
     }
 
-    let diff = TextDiff::from_unicode_words(old, new);
-
-    if diff.ratio() < 0.7 {
+    if TextDiff::from_unicode_words(old, new).ratio() < 0.7 {
         let old_lines = old
             .lines()
             .map(|l| WordDiffLine(vec![(l.to_string(), false)]))
@@ -32,6 +30,13 @@ pub fn compute_word_diffs(old: &str, new: &str) -> (Vec<WordDiffLine>, Vec<WordD
         return (old_lines, new_lines);
     }
 
+    mark_changed_words(old, new)
+}
+
+/// Splits `old` and `new` into lines with the words that differ marked,
+/// however little they have in common.
+pub fn mark_changed_words(old: &str, new: &str) -> (Vec<WordDiffLine>, Vec<WordDiffLine>) {
+    let diff = TextDiff::from_unicode_words(old, new);
     let mut old_line_parts = Vec::new();
     let mut new_line_parts = Vec::new();
 
@@ -88,6 +93,79 @@ pub fn compute_word_diffs(old: &str, new: &str) -> (Vec<WordDiffLine>, Vec<WordD
     (old_lines, new_lines)
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WordChange {
+    Unchanged,
+    Added,
+    Removed,
+}
+
+// A line of `git diff --word-diff=porcelain` output, regrouped into the words
+// that make up one line of the new file. `spans` is `None` for header lines
+// (commit info, stat, `diff --git`, `@@ ...`), which are kept as they are.
+// `text` is the plain `--word-diff` rendering of the line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PorcelainLine {
+    pub text: String,
+    pub spans: Option<Vec<(String, WordChange)>>,
+}
+
+pub fn parse_porcelain(output: &str) -> Vec<PorcelainLine> {
+    let mut lines = Vec::new();
+    let mut spans: Vec<(String, WordChange)> = Vec::new();
+    let mut in_hunk = false;
+
+    for raw in output.lines() {
+        if raw.starts_with("diff --git ") || !in_hunk {
+            in_hunk = raw.starts_with("@@ ");
+            lines.push(PorcelainLine {
+                text: raw.to_string(),
+                spans: None,
+            });
+            continue;
+        }
+        if raw.starts_with("@@ ") {
+            lines.push(PorcelainLine {
+                text: raw.to_string(),
+                spans: None,
+            });
+            continue;
+        }
+
+        let (marker, text) = raw.split_at(raw.chars().next().map_or(0, char::len_utf8));
+        let change = match marker {
+            "+" => WordChange::Added,
+            "-" => WordChange::Removed,
+            "~" => {
+                let spans = std::mem::take(&mut spans);
+                lines.push(PorcelainLine {
+                    text: plain_text(&spans),
+                    spans: Some(spans),
+                });
+                continue;
+            }
+            _ => WordChange::Unchanged,
+        };
+        if !text.is_empty() {
+            spans.push((text.to_string(), change));
+        }
+    }
+
+    lines
+}
+
+fn plain_text(spans: &[(String, WordChange)]) -> String {
+    let mut text = String::from(" ");
+    for (part, change) in spans {
+        match change {
+            WordChange::Unchanged => text.push_str(part),
+            WordChange::Added => text.push_str(&format!("{{+{part}+}}")),
+            WordChange::Removed => text.push_str(&format!("[-{part}-]")),
+        }
+    }
+    text
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -172,4 +250,102 @@ mod tests {
         assert_eq!(new_diff.len(), 1);
         assert_eq!(new_diff[0], expected_new);
     }
+
+    #[test]
+    fn test_parse_porcelain() {
+        let output = "\
+diff --git a/doc.md b/doc.md
+index 1111111..2222222 100644
+--- a/doc.md
++++ b/doc.md
+@@ -1,2 +1,2 @@
+ The
+-quick
++slow
+  brown fox
+~
+ jumps
+~
+";
+        let lines = parse_porcelain(output);
+
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[0].spans, None);
+        assert_eq!(lines[4].text, "@@ -1,2 +1,2 @@");
+        assert_eq!(lines[4].spans, None);
+        assert_eq!(lines[5].text, " The[-quick-]{+slow+} brown fox");
+        assert_eq!(
+            lines[5].spans,
+            Some(vec![
+                ("The".to_string(), WordChange::Unchanged),
+                ("quick".to_string(), WordChange::Removed),
+                ("slow".to_string(), WordChange::Added),
+                (" brown fox".to_string(), WordChange::Unchanged),
+            ])
+        );
+        assert_eq!(lines[6].text, " jumps");
+    }
+
+    #[test]
+    fn test_parse_porcelain_keeps_header_before_first_hunk() {
+        let output = "\
+commit abc
+Author: A <a@example.com>
+
+    message
+
+ doc.md | 2 +-
+diff --git a/doc.md b/doc.md
+@@ -1 +1 @@
++new
+~
+diff --git a/other.md b/other.md
+@@ -1 +1 @@
+-+old
+~
+";
+        let lines = parse_porcelain(output);
+        let texts: Vec<&str> = lines.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "commit abc",
+                "Author: A <a@example.com>",
+                "",
+                "    message",
+                "",
+                " doc.md | 2 +-",
+                "diff --git a/doc.md b/doc.md",
+                "@@ -1 +1 @@",
+                " {+new+}",
+                "diff --git a/other.md b/other.md",
+                "@@ -1 +1 @@",
+                " [-+old-]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mark_changed_words_ignores_the_ratio() {
+        let (old_diff, new_diff) = mark_changed_words("Fix it\n\nLong body here", "Fix");
+        assert_eq!(
+            old_diff[0],
+            WordDiffLine(vec![
+                ("Fix".to_string(), false),
+                (" ".to_string(), true),
+                ("it".to_string(), true),
+            ])
+        );
+        assert_eq!(old_diff.len(), 3);
+        assert_eq!(
+            new_diff,
+            vec![WordDiffLine(vec![("Fix".to_string(), false)])]
+        );
+        assert_eq!(
+            compute_word_diffs("Fix it\n\nLong body here", "Fix").0[0]
+                .0
+                .len(),
+            1
+        );
+    }
 }
@@ -0,0 +1,97 @@
+use crate::git::FileDiff;
+use std::ops::Range;
+
+/// A whitespace problem git would warn about, shown while the diff view
+/// shows whitespace.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mark {
+    /// Spaces or tabs at the end of a line.
+    TrailingWhitespace,
+    /// An indentation made of both tabs and spaces.
+    MixedIndent,
+    /// `\ No newline at end of file`, the line above has no newline.
+    MissingNewline,
+}
+
+/// Whether `line` of a diff is an added or removed line, not a file header.
+fn is_change(line: &str) -> bool {
+    (line.starts_with('+') && !line.starts_with("+++ "))
+        || (line.starts_with('-') && !line.starts_with("--- "))
+}
+
+/// The byte ranges of `line`, a diff line with its `+`/`-` prefix, that
+/// have a whitespace problem. Only added and removed lines are looked at,
+/// so that both sides of a whitespace fix show.
+pub fn marks(line: &str) -> Vec<(Range<usize>, Mark)> {
+    if line.starts_with("\\ ") {
+        return vec![(0..line.len(), Mark::MissingNewline)];
+    }
+    if !is_change(line) {
+        return Vec::new();
+    }
+    let content = &line[1..];
+    let mut marks = Vec::new();
+    let indent = content.len() - content.trim_start_matches([' ', '\t']).len();
+    let text_end = content.trim_end_matches([' ', '\t']).len();
+    // A line of whitespace only is all trailing, like git counts it.
+    if indent < text_end && content[..indent].contains(' ') && content[..indent].contains('\t') {
+        marks.push((1..1 + indent, Mark::MixedIndent));
+    }
+    if text_end < content.len() {
+        marks.push((1 + text_end..line.len(), Mark::TrailingWhitespace));
+    }
+    marks
+}
+
+/// Whether committing `file` would add lines ending in whitespace.
+pub fn adds_trailing_whitespace(file: &FileDiff) -> bool {
+    file.hunks
+        .iter()
+        .flat_map(|hunk| hunk.lines.iter().skip(1))
+        .filter(|line| line.starts_with('+'))
+        .any(|line| {
+            marks(line)
+                .iter()
+                .any(|(_, mark)| *mark == Mark::TrailingWhitespace)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::parse_diff;
+
+    #[test]
+    fn test_marks_trailing_whitespace_and_mixed_indents() {
+        assert_eq!(marks("+a  "), vec![(2..4, Mark::TrailingWhitespace)]);
+        assert_eq!(marks("- \tb"), vec![(1..3, Mark::MixedIndent)]);
+        assert_eq!(
+            marks("+\t  c\t"),
+            vec![(1..4, Mark::MixedIndent), (5..6, Mark::TrailingWhitespace)]
+        );
+        assert_eq!(marks("+ \t"), vec![(1..3, Mark::TrailingWhitespace)]);
+        assert_eq!(marks("+\tclean"), Vec::new());
+        // Context lines and file headers are left alone
+        assert_eq!(marks(" a  "), Vec::new());
+        assert_eq!(marks("+++ b/a.txt "), Vec::new());
+    }
+
+    #[test]
+    fn test_marks_missing_newline() {
+        let line = "\\ No newline at end of file";
+        assert_eq!(marks(line), vec![(0..line.len(), Mark::MissingNewline)]);
+    }
+
+    #[test]
+    fn test_adds_trailing_whitespace_looks_at_added_lines() {
+        let diff = |hunk: &str| {
+            parse_diff(&format!(
+                "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n{hunk}"
+            ))
+            .remove(0)
+        };
+        assert!(adds_trailing_whitespace(&diff("@@ -1 +1 @@\n-a\n+a \n")));
+        // Removing it is a fix
+        assert!(!adds_trailing_whitespace(&diff("@@ -1 +1 @@\n-a \n+a\n")));
+    }
+}
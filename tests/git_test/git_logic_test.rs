@@ -111,6 +111,150 @@ fn test_get_local_commits() {
     assert!(commits[2].is_on_remote);
 }
 
+#[test]
+fn test_get_local_commits_reads_author_date_and_body() {
+    let setup = TestSetup::new();
+    let repo_path = &setup.repo_path;
+    run_git(
+        repo_path,
+        &[
+            "commit",
+            "-m",
+            "Subject line",
+            "-m",
+            "First paragraph\nstill first\n\nSecond",
+        ],
+    );
+    run_git(repo_path, &["commit", "--allow-empty", "-m", "No body"]);
+
+    let commits = git::get_local_commits(repo_path).unwrap();
+    assert_eq!(commits.len(), 3);
+    assert_eq!(commits[0].message, "No body");
+    assert_eq!(commits[0].body, "");
+    assert_eq!(commits[1].message, "Subject line");
+    assert_eq!(commits[1].author, "Test <test@example.com>");
+    assert!(commits[1].date.ends_with(" ago"), "{}", commits[1].date);
+    assert_eq!(commits[1].body, "First paragraph\nstill first\n\nSecond");
+    assert_eq!(commits[2].message, "initial commit");
+}
+
+#[test]
+fn test_get_textconv_files() {
+    let setup = TestSetup::new();
+    let repo_path = &setup.repo_path;
+    let paths = vec![
+        "a.ipynb".to_string(),
+        "b.txt".to_string(),
+        "c.csv".to_string(),
+    ];
+    assert!(
+        git::get_textconv_files(repo_path, &paths)
+            .unwrap()
+            .is_empty()
+    );
+
+    fs::write(
+        repo_path.join(".gitattributes"),
+        "*.ipynb diff=notebook\n*.csv diff=nothing\n",
+    )
+    .unwrap();
+    run_git(repo_path, &["config", "diff.notebook.textconv", "cat"]);
+    let textconv = git::get_textconv_files(repo_path, &paths).unwrap();
+    assert_eq!(textconv.into_iter().collect::<Vec<_>>(), vec!["a.ipynb"]);
+}
+
+#[test]
+fn test_prepare_commit_message() {
+    let setup = TestSetup::new();
+    let repo_path = &setup.repo_path;
+    assert_eq!(git::prepare_commit_message(repo_path).unwrap(), None);
+
+    fs::write(repo_path.join(".gitmessage"), "\nfeat: \n\n# Say why\n").unwrap();
+    run_git(repo_path, &["config", "commit.template", ".gitmessage"]);
+    assert_eq!(
+        git::prepare_commit_message(repo_path).unwrap().as_deref(),
+        Some("feat: ")
+    );
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let hook = repo_path.join(".git/hooks/prepare-commit-msg");
+        fs::write(&hook, "#!/bin/sh\nsed -i \"2s/^/[$2] /\" \"$1\"\n").unwrap();
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+        assert_eq!(
+            git::prepare_commit_message(repo_path).unwrap().as_deref(),
+            Some("[template] feat: ")
+        );
+
+        fs::write(&hook, "#!/bin/sh\necho nope >&2\nexit 1\n").unwrap();
+        let error = git::prepare_commit_message(repo_path).unwrap_err();
+        assert!(error.to_string().contains("nope"), "{error}");
+    }
+}
+
+#[test]
+fn test_strip_patch_headers() {
+    let diff = "diff --git a/a.txt b/a.txt\n\
+index 1111111..2222222 100644\n\
+--- a/a.txt\n\
++++ b/a.txt\n\
+@@ -1 +1 @@\n\
+-one\n\
++two\n\
+diff --git a/run.sh b/run.sh\n\
+old mode 100644\n\
+new mode 100755\n";
+    let mut files = git::parse_diff(diff);
+    git::strip_patch_headers(&mut files);
+
+    assert_eq!(files[0].lines, vec!["@@ -1 +1 @@", "-one", "+two"]);
+    assert_eq!(files[0].hunks[0].start_line, 0);
+    assert_eq!(files[0].hunks[0].lines, files[0].lines);
+    // A mode change has nothing but its header to show
+    assert_eq!(files[1].lines, vec!["old mode 100644", "new mode 100755"]);
+}
+
+#[test]
+fn test_expand_to_full_file() {
+    let diff = "diff --git a/a.txt b/a.txt\n\
+index 1111111..2222222 100644\n\
+--- a/a.txt\n\
++++ b/a.txt\n\
+@@ -2 +2 @@\n\
+-b\n\
++B\n\
+@@ -5 +4,0 @@\n\
+-e\n";
+    let mut files = git::parse_diff(diff);
+    let hunks: Vec<Vec<String>> = files[0].hunks.iter().map(|h| h.lines.clone()).collect();
+    git::expand_to_full_file(&mut files, |_| Some("a\nB\nc\nd\nf\n".to_string()));
+
+    assert_eq!(
+        files[0].lines[4..],
+        [
+            " a",
+            "@@ -2 +2 @@",
+            "-b",
+            "+B",
+            " c",
+            " d",
+            "@@ -5 +4,0 @@",
+            "-e",
+            " f"
+        ]
+    );
+    // The hunks are the same, only moved
+    assert_eq!(files[0].hunks[0].start_line, 5);
+    assert_eq!(files[0].hunks[1].start_line, 10);
+    for (hunk, lines) in files[0].hunks.iter().zip(&hunks) {
+        assert_eq!(
+            &files[0].lines[hunk.start_line..][..hunk.lines.len()],
+            lines
+        );
+    }
+}
+
 #[test]
 #[serial]
 fn test_run_with_unstaged_changes() {
@@ -441,3 +585,176 @@ fn test_add_all_with_size_limit() {
     // Assert that the modified file is staged
     assert!(status.contains("M  test.txt"));
 }
+
+#[test]
+#[serial]
+fn test_get_enclosing_repos_lists_nested_repos_innermost_first() {
+    let setup = TestSetup::new();
+    let outer = setup.repo_path.canonicalize().unwrap();
+    let inner = outer.join("vendor/lib");
+    fs::create_dir_all(inner.join("src")).unwrap();
+    run_git(&inner, &["init"]);
+
+    assert_eq!(
+        git::get_enclosing_repos(&inner.join("src")),
+        vec![inner.clone(), outer.clone()]
+    );
+    assert_eq!(git::get_enclosing_repos(&outer), vec![outer]);
+
+    let not_a_repo = TempDir::new().unwrap();
+    assert!(git::get_enclosing_repos(not_a_repo.path()).is_empty());
+}
+
+#[test]
+#[serial]
+fn test_file_fingerprints_follow_index_and_worktree() {
+    let setup = TestSetup::new();
+    let repo_path = &setup.repo_path;
+    fs::write(repo_path.join("new.txt"), "untracked").unwrap();
+    let paths = vec![
+        "test.txt".to_string(),
+        "new.txt".to_string(),
+        "gone.txt".to_string(),
+    ];
+
+    let before = git::file_fingerprints(repo_path, &paths).unwrap();
+    assert!(before["test.txt"].index.is_some());
+    assert_eq!(before["test.txt"].index, before["test.txt"].worktree);
+    assert_eq!(before["new.txt"].index, None);
+    assert!(before["new.txt"].worktree.is_some());
+    assert_eq!(before["gone.txt"], git::FileFingerprint::default());
+
+    fs::write(repo_path.join("test.txt"), "edited").unwrap();
+    assert!(!git::is_file_unchanged(
+        repo_path,
+        "test.txt",
+        &before["test.txt"]
+    ));
+    assert!(git::is_file_unchanged(
+        repo_path,
+        "new.txt",
+        &before["new.txt"]
+    ));
+
+    run_git(repo_path, &["add", "test.txt"]);
+    let staged = git::file_fingerprint(repo_path, "test.txt").unwrap();
+    assert_ne!(staged.index, before["test.txt"].index);
+    assert_eq!(staged.index, staged.worktree);
+}
+
+/// Points `gpg.program` at a script standing in for gpg, which makes a
+/// fake signature or fails.
+fn use_fake_gpg(repo_path: &std::path::Path, works: bool) {
+    let script = repo_path.join(".git/fake-gpg.sh");
+    let body = if works {
+        "cat >/dev/null\nprintf '\\n[GNUPG:] SIG_CREATED D 1 8 00 0 0 0\\n' >&2\nprintf -- '-----BEGIN PGP SIGNATURE-----\\n\\nfake\\n-----END PGP SIGNATURE-----\\n'\n"
+    } else {
+        "cat >/dev/null\nexit 1\n"
+    };
+    fs::write(&script, format!("#!/bin/sh\n{body}")).unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    run_git(
+        repo_path,
+        &["config", "gpg.program", script.to_str().unwrap()],
+    );
+    run_git(repo_path, &["config", "commit.gpgsign", "true"]);
+}
+
+#[test]
+#[serial]
+fn test_commits_are_signed_when_commit_gpgsign_is_set() {
+    let setup = TestSetup::new();
+    let repo_path = &setup.repo_path;
+    assert!(!git::is_signing_enabled(repo_path));
+    use_fake_gpg(repo_path, true);
+    assert!(git::is_signing_enabled(repo_path));
+
+    git::commit(repo_path, "signed", true).unwrap();
+    let commits = git::get_local_commits(repo_path).unwrap();
+    assert_eq!(commits[0].message, "signed");
+    assert!(commits[0].is_signed);
+    assert!(!commits[1].is_signed);
+
+    git::commit_amend_with_message(repo_path, "still signed").unwrap();
+    assert!(git::get_local_commits(repo_path).unwrap()[0].is_signed);
+}
+
+#[test]
+#[serial]
+fn test_signing_failure_is_reported() {
+    let setup = TestSetup::new();
+    let repo_path = &setup.repo_path;
+    use_fake_gpg(repo_path, false);
+
+    let error = git::commit(repo_path, "unsigned", true)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        error.starts_with("Signing failed (commit.gpgsign is set): "),
+        "{error}"
+    );
+    assert!(!error.contains('\n'), "{error}");
+    assert_eq!(git::get_local_commits(repo_path).unwrap().len(), 1);
+}
+
+#[test]
+fn test_reordered_rebase_plan_starts_from_its_oldest_commit() {
+    let setup = TestSetup::new();
+    let repo_path = &setup.repo_path;
+    run_git(repo_path, &["commit", "-m", "base"]);
+    for name in ["one", "two"] {
+        fs::write(repo_path.join(format!("{name}.txt")), name).unwrap();
+        run_git(repo_path, &["add", "."]);
+        run_git(repo_path, &["commit", "-m", name]);
+    }
+    let commits = git::get_local_commits(repo_path).unwrap();
+    let step = |index: usize| git::RebaseStep {
+        hash: commits[index].hash.clone(),
+        message: commits[index].message.clone(),
+        action: git::RebaseAction::Pick,
+        new_message: None,
+    };
+
+    git::run_rebase_plan(repo_path, &[step(0), step(1)]).unwrap();
+
+    let messages: Vec<_> = git::get_local_commits(repo_path)
+        .unwrap()
+        .into_iter()
+        .map(|commit| commit.message)
+        .collect();
+    assert_eq!(messages, vec!["one", "two", "base", "initial commit"]);
+}
+
+#[test]
+fn test_rebase_plan_runs_in_a_linked_worktree() {
+    let setup = TestSetup::new();
+    run_git(&setup.repo_path, &["commit", "-m", "base"]);
+    let worktree = TempDir::new().unwrap();
+    let worktree_path = worktree.path().join("linked");
+    run_git(
+        &setup.repo_path,
+        &[
+            "worktree",
+            "add",
+            "-b",
+            "linked",
+            worktree_path.to_str().unwrap(),
+        ],
+    );
+    let commits = git::get_local_commits(&worktree_path).unwrap();
+    let steps = vec![git::RebaseStep {
+        hash: commits[0].hash.clone(),
+        message: commits[0].message.clone(),
+        action: git::RebaseAction::Reword,
+        new_message: Some("reworded".to_string()),
+    }];
+
+    git::run_rebase_plan(&worktree_path, &steps).unwrap();
+
+    let commits = git::get_local_commits(&worktree_path).unwrap();
+    assert_eq!(commits[0].message, "reworded");
+}
@@ -35,6 +35,7 @@ fn create_test_file_diff() -> FileDiff {
         hunks,
         lines,
         status: FileStatus::Modified,
+        binary: None,
     }
 }
 
@@ -57,3 +58,54 @@ fn test_get_line_number() {
     // Out of bounds -> None
     assert_eq!(git_patch::get_line_number(&file, 7), None);
 }
+
+#[test]
+fn test_edited_hunk_patch_is_recounted() {
+    let file = create_test_file_diff();
+    let text = git_patch::create_hunk_edit_text(&file.hunks[0], true);
+    assert!(text.starts_with(
+        "# Manual hunk edit mode. Save and quit to stage the hunk.\n@@ -1,5 +1,6 @@\n"
+    ));
+
+    // Keep `line 3`, drop `line 3 new` and add a line
+    let edited = text
+        .replace("-line 3\n", " line 3\n")
+        .replace("+line 3 new\n", "+extra\n+more\n");
+    assert_eq!(
+        git_patch::create_edited_hunk_patch("test.txt", &edited).unwrap(),
+        "diff --git a/test.txt b/test.txt\n\
+         --- a/test.txt\n\
+         +++ b/test.txt\n\
+         @@ -1,4 +1,6 @@\n\
+         \x20line 1\n\
+         -line 2\n\
+         \x20line 3\n\
+         +line 2 new\n\
+         +extra\n\
+         +more\n\
+         \x20line 4\n"
+    );
+}
+
+#[test]
+fn test_invalid_edited_hunks_are_rejected() {
+    let file = create_test_file_diff();
+    let text = git_patch::create_hunk_edit_text(&file.hunks[0], false);
+
+    let no_header = text.replace("@@ -1,5 +1,6 @@\n", "");
+    assert_eq!(
+        git_patch::create_edited_hunk_patch("test.txt", &no_header),
+        Err("the hunk header is missing".to_string())
+    );
+
+    let garbage = text.replace(" line 4\n", "line 4\n");
+    assert_eq!(
+        git_patch::create_edited_hunk_patch("test.txt", &garbage),
+        Err("line 8 is not a diff line".to_string())
+    );
+
+    assert_eq!(
+        git_patch::create_edited_hunk_patch("test.txt", "@@ -1,1 +1,1 @@\n line 1\n\n"),
+        Err("nothing is left to apply".to_string())
+    );
+}
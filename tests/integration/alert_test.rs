@@ -0,0 +1,89 @@
+use crate::integration::common::{TestRepo, select_commit_in_log};
+use git_full_commit::alert::AlertStyle;
+use git_full_commit::app_state::AppState;
+use git_full_commit::config::Config;
+use git_full_commit::git;
+use git_full_commit::ui::update::{update_state, update_state_with_alt};
+use pancurses::Input;
+
+fn bell() -> Config {
+    Config {
+        alert: AlertStyle::Bell,
+        ..Default::default()
+    }
+}
+
+/// A staged change to `a.txt` with another unstaged change on top, so
+/// discarding the staged one is refused.
+fn setup_blocked_discard(config: Config) -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "two\n");
+    repo.add_all();
+    repo.create_file("a.txt", "three\n");
+
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new_with_config(repo.path.clone(), files, config);
+    state.main_screen.file_cursor = 1;
+    (repo, state)
+}
+
+#[test]
+fn test_refused_discard_alerts() {
+    let (_repo, state) = setup_blocked_discard(bell());
+    let state = update_state(state, Some(Input::Character('!')), 40, 80);
+    assert_eq!(state.files.len(), 1);
+    assert!(state.alert_pending);
+
+    // Moving on does not alert again
+    let mut state = state;
+    state.alert_pending = false;
+    let state = update_state(state, Some(Input::KeyDown), 40, 80);
+    assert!(!state.alert_pending);
+}
+
+#[test]
+fn test_no_alert_unless_configured() {
+    let (_repo, state) = setup_blocked_discard(Config::default());
+    let state = update_state(state, Some(Input::Character('!')), 40, 80);
+    assert_eq!(state.files.len(), 1);
+    assert!(!state.alert_pending);
+}
+
+#[test]
+fn test_long_operation_alerts_when_finished() {
+    let repo = TestRepo::new();
+    for i in 0..2 {
+        repo.create_file(&format!("{i}.txt"), "content\n");
+        repo.add_all();
+        repo.commit(&format!("commit {i}"));
+    }
+    let files = git::get_diff(repo.path.clone());
+    let config = Config {
+        alert_after_seconds: Some(0),
+        ..bell()
+    };
+    let mut state = AppState::new_with_config(repo.path.clone(), files, config);
+
+    select_commit_in_log(&mut state, 1);
+    let state = update_state(state, Some(Input::Character('\n')), 40, 80);
+    let state = update_state(state, Some(Input::Character('!')), 40, 80);
+    assert!(!state.alert_pending);
+    let state = update_state_with_alt(state, Some(Input::Character('\n')), 40, 80);
+    let state = update_state(state, Some(Input::Character('\n')), 40, 80);
+    let log = git::run_git_command(&repo.path, &["log", "-1", "--format=%s"]).unwrap();
+    assert_eq!(log, "commit 1!\n");
+    assert!(state.alert_pending);
+}
+
+#[test]
+fn test_alert_config_keys() {
+    assert_eq!(Config::parse("alert = \"bell\"\n").alert, AlertStyle::Bell);
+    assert_eq!(Config::parse("alert = flash\n").alert, AlertStyle::Flash);
+    assert_eq!(Config::parse("alert = loud\n").alert, AlertStyle::Off);
+
+    let config = Config::parse("alert_after_seconds = 5\n");
+    assert_eq!(config.alert_after_seconds, Some(5));
+}
@@ -0,0 +1,76 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::{AppState, FocusedPane};
+use git_full_commit::git;
+use std::fs;
+
+const PATCH: &str =
+    "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n@@ -1,2 +1,2 @@\n one\n-two\n+2\n";
+
+/// `a.txt` committed, and `fix.patch` changing it lying untracked.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\ntwo\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("fix.patch", PATCH);
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    (repo, state)
+}
+
+#[test]
+fn test_stage_a_patch_file_and_undo() {
+    let (repo, state) = setup();
+    let state = press(state, "\u{12}");
+    assert_eq!(state.prompt.as_ref().unwrap().label, "Stage patch from");
+
+    let state = press(state, "fix.patch\n");
+    assert_eq!(state.message_text(), Some("Staged fix.patch"));
+    assert_eq!(repo.get_status(), "M  a.txt\n?? fix.patch\n");
+    assert_eq!(state.files.len(), 1);
+
+    let state = press(state, "<");
+    assert_eq!(repo.get_status(), "?? fix.patch\n");
+    assert!(state.files.is_empty());
+}
+
+#[test]
+fn test_apply_a_patch_file_to_the_working_tree() {
+    let (repo, state) = setup();
+    let state = press(state, "\t");
+    assert_eq!(state.focused_pane, FocusedPane::Unstaged);
+    let state = press(state, "\u{12}fix.patch\n");
+    assert_eq!(
+        state.message_text(),
+        Some("Applied fix.patch to the working tree")
+    );
+    assert_eq!(repo.get_status(), " M a.txt\n?? fix.patch\n");
+    assert_eq!(
+        fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+        "one\n2\n"
+    );
+}
+
+#[test]
+fn test_a_patch_that_does_not_apply_is_refused() {
+    let (repo, state) = setup();
+    repo.create_file("a.txt", "one\nzwei\n");
+    let state = press(state, "\t\u{12}fix.patch\n");
+    let message = state.message_text().unwrap().to_string();
+    assert!(
+        message.starts_with("fix.patch does not apply: error: "),
+        "{message}"
+    );
+    assert_eq!(
+        fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+        "one\nzwei\n"
+    );
+
+    let state = press(state, "\u{12}missing.patch\n");
+    assert!(
+        state
+            .message_text()
+            .unwrap()
+            .starts_with("Cannot read missing.patch: ")
+    );
+}
@@ -0,0 +1,73 @@
+use crate::git_test::common::TestRepo;
+use git_full_commit::git;
+use git_full_commit::ui::diff_view;
+
+fn setup() -> TestRepo {
+    let repo = TestRepo::new();
+    std::fs::write(repo.path.join("logo.png"), [0u8; 2048]).unwrap();
+    repo.add_all();
+    repo.commit("initial");
+    std::fs::write(repo.path.join("logo.png"), [1u8; 3072]).unwrap();
+    repo
+}
+
+#[test]
+fn test_staged_binary_file_is_summarized() {
+    let repo = setup();
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let file = &files[0];
+    let binary = file.binary.as_ref().expect("the file should be binary");
+    assert_eq!(binary.old_size, Some(2048));
+    assert_eq!(binary.new_size, Some(3072));
+    assert_eq!(
+        diff_view::binary_summary(file, binary),
+        vec![
+            "Binary file logo.png".to_string(),
+            String::new(),
+            "  Size  2.0 KiB → 3.0 KiB (+1.0 KiB)".to_string(),
+            "  Mode  100644".to_string(),
+            format!("  Blob  {} → {}", binary.old_blob, binary.new_blob),
+        ]
+    );
+}
+
+#[test]
+fn test_unstaged_binary_file_is_measured_in_the_working_tree() {
+    let repo = setup();
+
+    let files = git::get_unstaged_diff(&repo.path);
+    let binary = files[0].binary.as_ref().expect("the file should be binary");
+    assert_eq!(binary.old_size, Some(2048));
+    assert_eq!(binary.new_size, Some(3072));
+}
+
+#[test]
+fn test_added_binary_file_and_mode_change() {
+    let repo = TestRepo::new();
+    repo.commit("initial");
+    std::fs::write(repo.path.join("tool.bin"), [0u8; 10]).unwrap();
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let binary = files[0].binary.as_ref().unwrap();
+    assert_eq!(
+        diff_view::binary_summary(&files[0], binary)[2],
+        "  Size  10 bytes, new file"
+    );
+
+    repo.commit("add tool");
+    std::fs::write(repo.path.join("tool.bin"), [1u8; 10]).unwrap();
+    git::run_git_command(
+        &repo.path,
+        &["update-index", "--add", "--chmod=+x", "tool.bin"],
+    )
+    .unwrap();
+    let files = git::get_diff(repo.path.clone());
+    let binary = files[0].binary.as_ref().unwrap();
+    assert_eq!(
+        diff_view::binary_summary(&files[0], binary)[2..4],
+        ["  Size  10 bytes, same size", "  Mode  100644 → 100755"]
+    );
+}
@@ -0,0 +1,82 @@
+use crate::git_test::common::{TestRepo, run_git};
+use git_full_commit::app_state::AppState;
+use git_full_commit::ui::main_screen::{ListItem, selected_blame_label};
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn select_staged_file(state: &mut AppState, name: &str) {
+    state.main_screen.file_cursor = state
+        .main_screen
+        .list_items
+        .iter()
+        .position(|item| matches!(item, ListItem::File(file) if file.file_name == name))
+        .unwrap();
+}
+
+fn wait_for_blame_label(state: &mut AppState) -> String {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        state.check_diff_update();
+        state.poll_background();
+        if let Some(label) = selected_blame_label(state) {
+            return label;
+        }
+        assert!(Instant::now() < deadline, "blame did not finish");
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn test_blame_summary_names_who_touched_the_changed_lines() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\ntwo\nthree\n");
+    repo.add_all();
+    repo.commit("first");
+    repo.create_file("a.txt", "one\ntwo\nTHREE\n");
+    repo.add_all();
+    run_git(
+        &repo.path,
+        &[
+            "commit",
+            "-q",
+            "-m",
+            "second",
+            "--author",
+            "Bob <bob@example.com>",
+        ],
+    );
+    repo.create_file("a.txt", "ONE\ntwo\nthree!\n");
+    repo.create_file("new.txt", "new\n");
+    repo.add_all();
+    let mut state = repo.create_initial_state();
+
+    select_staged_file(&mut state, "a.txt");
+    assert_eq!(
+        wait_for_blame_label(&mut state),
+        "Last touched by Bob (1), Test (1)"
+    );
+    assert_eq!(state.blame_summaries.len(), 1);
+
+    select_staged_file(&mut state, "new.txt");
+    state.check_diff_update();
+    assert_eq!(state.selected_blame_key(), None);
+    assert_eq!(state.blame_summaries.len(), 1);
+}
+
+#[test]
+fn test_blame_summaries_are_dropped_when_head_moves() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("first");
+    repo.create_file("a.txt", "two\n");
+    repo.add_all();
+    let mut state = repo.create_initial_state();
+    select_staged_file(&mut state, "a.txt");
+    wait_for_blame_label(&mut state);
+
+    repo.commit("second");
+    state.refresh_diff(false);
+
+    assert!(state.blame_summaries.is_empty());
+}
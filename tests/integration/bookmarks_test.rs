@@ -0,0 +1,134 @@
+use crate::git_test::common::{TestRepo, run_git};
+use crate::integration::common::press;
+use git_full_commit::app_state::{AppState, FocusedPane};
+use git_full_commit::git;
+use git_full_commit::ui::main_screen::{ListItem, UnstagedListItem};
+
+/// `a.txt` and `b.txt` staged, `c.txt` changed and `d.txt` untracked.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        repo.create_file(name, "one\n");
+    }
+    repo.add_all();
+    repo.commit("initial");
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        repo.create_file(name, "two\n");
+    }
+    run_git(&repo.path, &["add", "a.txt", "b.txt"]);
+    repo.create_file("d.txt", "new\n");
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    (repo, state)
+}
+
+fn select_main(mut state: AppState, name: &str) -> AppState {
+    state.focused_pane = FocusedPane::Main;
+    state.main_screen.file_cursor = state
+        .main_screen
+        .list_items
+        .iter()
+        .position(|item| matches!(item, ListItem::File(file) if file.file_name == name))
+        .unwrap();
+    state
+}
+
+fn select_unstaged(mut state: AppState, name: &str) -> AppState {
+    state.focused_pane = FocusedPane::Unstaged;
+    state.unstaged_pane.cursor = state
+        .unstaged_pane
+        .list_items
+        .iter()
+        .position(|item| match item {
+            UnstagedListItem::File(file) => file.file_name == name,
+            UnstagedListItem::UntrackedFile(file_name) => file_name == name,
+            _ => false,
+        })
+        .unwrap();
+    state
+}
+
+/// The file under the cursor and the pane it is in.
+fn selected(state: &AppState) -> (FocusedPane, String) {
+    let name = match state.focused_pane {
+        FocusedPane::Main => match state.current_main_item() {
+            Some(ListItem::File(file)) => file.file_name.clone(),
+            _ => String::new(),
+        },
+        FocusedPane::Unstaged => {
+            match &state.unstaged_pane.list_items[state.unstaged_pane.cursor] {
+                UnstagedListItem::File(file) => file.file_name.clone(),
+                UnstagedListItem::UntrackedFile(file_name) => file_name.clone(),
+                _ => String::new(),
+            }
+        }
+    };
+    (state.focused_pane, name)
+}
+
+#[test]
+fn test_jump_between_bookmarks_of_both_panes() {
+    let (_repo, state) = setup();
+    let state = press(select_main(state, "b.txt"), "b");
+    assert_eq!(
+        state.message_text(),
+        Some("Bookmarked b.txt, ' jumps to the next bookmark")
+    );
+    let state = press(select_unstaged(state, "d.txt"), "b");
+    assert_eq!(state.bookmarks, ["b.txt", "d.txt"]);
+
+    let state = press(select_main(state, "a.txt"), "'");
+    assert_eq!(selected(&state), (FocusedPane::Main, "b.txt".to_string()));
+    assert_eq!(state.message_text(), Some("Bookmark 1 of 2: b.txt"));
+    let state = press(state, "'");
+    assert_eq!(
+        selected(&state),
+        (FocusedPane::Unstaged, "d.txt".to_string())
+    );
+    // Around the end, and back
+    let state = press(state, "'");
+    assert_eq!(selected(&state), (FocusedPane::Main, "b.txt".to_string()));
+    let state = press(state, "\"");
+    assert_eq!(
+        selected(&state),
+        (FocusedPane::Unstaged, "d.txt".to_string())
+    );
+}
+
+#[test]
+fn test_bookmarks_survive_staging() {
+    let (_repo, state) = setup();
+    let state = press(select_unstaged(state, "c.txt"), "b");
+    // Staging moves the file to the staged pane, its bookmark goes along
+    let state = press(state, "u");
+    assert!(
+        state
+            .unstaged_pane
+            .list_items
+            .iter()
+            .all(|item| !matches!(item, UnstagedListItem::File(file) if file.file_name == "c.txt"))
+    );
+    let state = press(select_main(state, "a.txt"), "'");
+    assert_eq!(selected(&state), (FocusedPane::Main, "c.txt".to_string()));
+
+    // Pressing b again takes the bookmark away
+    let state = press(state, "b");
+    assert_eq!(state.message_text(), Some("Removed the bookmark of c.txt"));
+    assert!(state.bookmarks.is_empty());
+}
+
+#[test]
+fn test_without_bookmarks_nothing_moves() {
+    let (_repo, state) = setup();
+    let state = select_main(state, "a.txt");
+    let state = press(state, "'");
+    assert_eq!(
+        state.message_text(),
+        Some("No bookmarks, b bookmarks the file under the cursor")
+    );
+    assert_eq!(selected(&state), (FocusedPane::Main, "a.txt".to_string()));
+
+    let mut state = state;
+    state.main_screen.file_cursor = 0;
+    let state = press(state, "b");
+    assert_eq!(state.message_text(), Some("Only files can be bookmarked"));
+}
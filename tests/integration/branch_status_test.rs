@@ -0,0 +1,52 @@
+use crate::git_test::common::{TestRepo, run_git};
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use git_full_commit::ui::main_screen::branch_status_label;
+
+fn label(state: &AppState) -> String {
+    branch_status_label(&state.branch_status)
+}
+
+fn branch(repo: &TestRepo) -> String {
+    git::get_current_branch_name(&repo.path).unwrap()
+}
+
+#[test]
+fn test_branch_status_follows_the_upstream() {
+    let repo = TestRepo::new();
+    repo.commit("first");
+    let mut state = repo.create_initial_state();
+    let name = branch(&repo);
+    assert_eq!(label(&state), format!("{name}  no upstream"));
+
+    repo.push();
+    state.refresh_diff(false);
+    assert_eq!(label(&state), format!("{name} → origin/{name}  up to date"));
+
+    repo.commit("second");
+    repo.commit("third");
+    state.refresh_diff(false);
+    assert_eq!(state.branch_status.ahead, 2);
+    assert_eq!(label(&state), format!("{name} → origin/{name}  2 ahead"));
+
+    repo.push();
+    run_git(&repo.path, &["reset", "-q", "--hard", "HEAD~1"]);
+    state.refresh_diff(false);
+    assert_eq!(label(&state), format!("{name} → origin/{name}  1 behind"));
+
+    repo.commit("other third");
+    state.refresh_diff(false);
+    assert_eq!(
+        label(&state),
+        format!("{name} → origin/{name}  1 ahead, 1 behind")
+    );
+}
+
+#[test]
+fn test_branch_status_of_a_detached_head() {
+    let repo = TestRepo::new();
+    repo.commit("first");
+    run_git(&repo.path, &["checkout", "-q", "--detach"]);
+    let state = repo.create_initial_state();
+    assert_eq!(label(&state), "detached HEAD  no upstream");
+}
@@ -0,0 +1,120 @@
+use crate::git_test::common::{TestRepo, run_git};
+use crate::integration::common::{press, select_commit_in_log};
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+
+/// Replaces the text of the open prompt and confirms it.
+fn answer(mut state: AppState, text: &str) -> AppState {
+    let prompt = state.prompt.as_mut().unwrap();
+    prompt.text = text.to_string();
+    prompt.cursor = text.chars().count();
+    press(state, "\n")
+}
+
+fn log(repo: &TestRepo, format: &str) -> String {
+    git::run_git_command(&repo.path, &["log", &format!("--format={format}")]).unwrap()
+}
+
+/// `old` by someone else in 2000, then `newer` on top, and a staged change.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "a\n");
+    repo.add_all();
+    run_git(
+        &repo.path,
+        &[
+            "commit",
+            "-m",
+            "old",
+            "--author=Other <other@example.com>",
+            "--date=2000-01-01T00:00:00+0000",
+        ],
+    );
+    repo.create_file("b.txt", "b\n");
+    repo.add_all();
+    repo.commit("newer");
+    repo.create_file("a.txt", "staged\n");
+    repo.add_all();
+
+    let mut state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    select_commit_in_log(&mut state, 0);
+    (repo, state)
+}
+
+#[test]
+fn test_author_is_set_and_the_date_kept() {
+    let (repo, state) = setup();
+    let state = press(state, "B");
+    assert_eq!(
+        state.prompt.as_ref().unwrap().text,
+        "Other <other@example.com>"
+    );
+
+    let state = answer(state, "Someone Else <else@example.com>");
+    assert_eq!(
+        log(&repo, "%s|%an <%ae>|%aI"),
+        format!(
+            "newer|Test <test@example.com>|{}\nold|Someone Else <else@example.com>|2000-01-01T00:00:00+00:00\n",
+            log(&repo, "%aI").lines().next().unwrap()
+        )
+    );
+    let hash = &state.previous_commits.last().unwrap().hash;
+    assert_eq!(
+        state.message_text(),
+        Some(format!("Set the author of {hash} to Someone Else <else@example.com>").as_str())
+    );
+    // The rewritten commit stays selected, and the staged change is kept
+    assert_eq!(
+        git_full_commit::ui::main_screen::selected_commit(&state).map(|c| &c.hash),
+        Some(hash)
+    );
+    assert_eq!(repo.get_status(), "M  a.txt\n");
+}
+
+#[test]
+fn test_empty_author_resets_it_to_you_dated_now() {
+    let (repo, state) = setup();
+    let state = answer(press(state, "B"), "");
+
+    let old = log(&repo, "%an <%ae>|%aI|%s");
+    let old = old.lines().nth(1).unwrap();
+    assert!(old.starts_with("Test <test@example.com>|"), "{old}");
+    assert!(!old.contains("2000-01-01"), "{old}");
+    assert!(old.ends_with("|old"), "{old}");
+    let hash = &state.previous_commits.last().unwrap().hash;
+    assert_eq!(
+        state.message_text(),
+        Some(format!("Reset the author of {hash} to Test <test@example.com>, dated now").as_str())
+    );
+}
+
+#[test]
+fn test_now_only_dates_the_commit_now() {
+    let (repo, state) = setup();
+    let state = answer(press(state, "B"), "now");
+
+    let old = log(&repo, "%an <%ae>|%aI|%s");
+    let old = old.lines().nth(1).unwrap();
+    assert!(old.starts_with("Other <other@example.com>|"), "{old}");
+    assert!(!old.contains("2000-01-01"), "{old}");
+    assert!(old.ends_with("|old"), "{old}");
+    let hash = &state.previous_commits.last().unwrap().hash;
+    assert_eq!(
+        state.message_text(),
+        Some(format!("Dated {hash} now").as_str())
+    );
+    assert_eq!(repo.get_status(), "M  a.txt\n");
+}
+
+#[test]
+fn test_malformed_author_is_refused() {
+    let (repo, state) = setup();
+    let before = log(&repo, "%H");
+    let state = answer(press(state, "B"), "just a name");
+
+    assert_eq!(
+        state.message_text(),
+        Some("Type the author as Name <email>")
+    );
+    assert_eq!(log(&repo, "%H"), before);
+}
@@ -0,0 +1,101 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::{AppState, FocusedPane};
+use git_full_commit::git;
+use git_full_commit::ui::command_prompt::parse_hunks;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+/// `a.txt` with lines 1 to 30, of which 2, 15 and 28 are changed, making
+/// three hunks, all staged. The staged file is selected.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    let lines = |changed: bool| -> String {
+        (1..=30)
+            .map(|n| match n {
+                2 | 15 | 28 if changed => format!("{n} changed\n"),
+                _ => format!("{n}\n"),
+            })
+            .collect()
+    };
+    repo.create_file("a.txt", &lines(false));
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", &lines(true));
+    repo.add_all();
+
+    let mut state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    state.focused_pane = FocusedPane::Main;
+    state.main_screen.file_cursor = 1;
+    assert_eq!(state.current_main_file().unwrap().hunks.len(), 3);
+    (repo, state)
+}
+
+fn staged_diff(repo: &TestRepo) -> String {
+    git::run_git_command(&repo.path, &["diff", "--cached"]).unwrap()
+}
+
+#[test]
+fn test_parse_hunks() {
+    assert_eq!(parse_hunks("2-4", 5), Ok(vec![1, 2, 3]));
+    assert_eq!(parse_hunks("3", 5), Ok(vec![2]));
+    assert_eq!(parse_hunks("1,3-4,3", 5), Ok(vec![0, 2, 3]));
+    assert_eq!(
+        parse_hunks("2-6", 5),
+        Err("No hunk 6, there are 5".to_string())
+    );
+    assert_eq!(parse_hunks("4-2", 5), Err("4 comes after 2".to_string()));
+    assert_eq!(
+        parse_hunks("two", 5),
+        Err("Not a hunk number: two".to_string())
+    );
+}
+
+#[test]
+fn test_unstage_a_range_of_hunks() {
+    let (repo, state) = setup();
+    let state = press(state, ":");
+    assert!(state.prompt.is_some());
+    let state = press(state, "unstage 2-3\n");
+    assert_eq!(state.message_text(), Some("Unstaged hunks 2-3 of a.txt"));
+    let staged = staged_diff(&repo);
+    assert!(staged.contains("+2 changed"), "{staged}");
+    assert!(!staged.contains("+15 changed"), "{staged}");
+    assert!(!staged.contains("+28 changed"), "{staged}");
+
+    // One undo step
+    let state = press(state, "<");
+    assert_eq!(state.files[0].hunks.len(), 3);
+
+    // And staged again from the unstaged pane
+    let state = press(state, ":unstage 1,3\n");
+    assert_eq!(staged_diff(&repo).matches("changed").count(), 1);
+    let mut state = state;
+    state.focused_pane = FocusedPane::Unstaged;
+    while state.get_unstaged_file().is_none() {
+        state = update_state(state, Some(Input::KeyDown), 40, 80);
+    }
+    let state = press(state, ":stage 2\n");
+    assert_eq!(state.message_text(), Some("Staged hunk 2 of a.txt"));
+    let staged = staged_diff(&repo);
+    assert!(staged.contains("+15 changed"), "{staged}");
+    assert!(staged.contains("+28 changed"), "{staged}");
+}
+
+#[test]
+fn test_commands_that_cannot_run_are_refused() {
+    let (_repo, state) = setup();
+    let state = press(state, ":unstage 4\n");
+    assert_eq!(state.message_text(), Some("No hunk 4, there are 3"));
+    let state = press(state, ":stage 1\n");
+    assert_eq!(
+        state.message_text(),
+        Some("Select an unstaged file to stage its hunks")
+    );
+    let state = press(state, ":reset\n");
+    assert_eq!(
+        state.message_text(),
+        Some("Unknown command: reset, try unstage 2-4, stage 1,3 or add *.rs")
+    );
+    assert_eq!(state.files[0].hunks.len(), 3);
+}
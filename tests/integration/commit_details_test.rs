@@ -0,0 +1,69 @@
+use crate::git_test::common::TestRepo;
+use git_full_commit::app_state::AppState;
+use git_full_commit::git::{self, CommitInfo};
+use git_full_commit::ui::commit_details;
+use git_full_commit::ui::main_screen::ListItem;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+fn commit(body: &str) -> CommitInfo {
+    CommitInfo {
+        hash: "abc1234".to_string(),
+        message: "Subject".to_string(),
+        author: "Test <test@example.com>".to_string(),
+        date: "2 hours ago".to_string(),
+        body: body.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_details_lines() {
+    assert_eq!(
+        commit_details::lines(&commit("")),
+        vec!["abc1234  Test <test@example.com>", "2 hours ago"]
+    );
+
+    let signed = CommitInfo {
+        is_signed: true,
+        ..commit("")
+    };
+    assert_eq!(commit_details::lines(&signed)[1], "2 hours ago, signed");
+
+    let body: Vec<String> = (1..=10).map(|n| format!("line {n}")).collect();
+    let lines = commit_details::lines(&commit(&body.join("\n")));
+    assert_eq!(lines[2], "");
+    assert_eq!(lines[3], "line 1");
+    assert_eq!(lines[10], "line 8");
+    assert_eq!(lines[11], "… 2 more lines");
+    assert_eq!(lines.len(), 12);
+}
+
+#[test]
+fn test_details_follow_the_selected_commit() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "two\n");
+    repo.add_all();
+    repo.commit("second");
+
+    let mut state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    assert!(commit_details::selected_commit(&state).is_none());
+
+    state.main_screen.file_cursor = state
+        .main_screen
+        .list_items
+        .iter()
+        .position(|item| matches!(item, ListItem::PreviousCommitInfo { .. }))
+        .unwrap();
+    let selected = commit_details::selected_commit(&state).unwrap();
+    assert_eq!(selected.message, "second");
+    assert_eq!(selected.author, "Test <test@example.com>");
+
+    // Hidden while the diff is navigated
+    let state = update_state(state, Some(Input::Character('j')), 40, 80);
+    assert!(state.main_screen.is_diff_cursor_active);
+    assert!(commit_details::selected_commit(&state).is_none());
+}
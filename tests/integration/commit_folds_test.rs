@@ -0,0 +1,113 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use git_full_commit::ui::commit_folds;
+use git_full_commit::ui::main_screen::focused_diff_lines;
+
+/// A commit adding `a.txt` and `b.txt` with three lines each, selected in
+/// the log.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "1\n2\n3\n");
+    repo.create_file("b.txt", "1\n2\n3\n");
+    repo.add_all();
+    repo.commit("two files");
+
+    let mut state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    // [StagedHeader, CommitMessageInput, the commit]
+    state.main_screen.file_cursor = 2;
+    state.update_selected_commit_diff();
+    (repo, state)
+}
+
+fn header_of(state: &AppState, name: &str) -> usize {
+    focused_diff_lines(state)
+        .iter()
+        .position(|line| line.starts_with(&format!("diff --git a/{name} b/{name}")))
+        .unwrap()
+}
+
+#[test]
+fn test_enter_on_a_file_header_collapses_and_expands_it() {
+    let (_repo, mut state) = setup();
+    let full = focused_diff_lines(&state).len();
+    state.main_screen.is_diff_cursor_active = true;
+    state.main_screen.line_cursor = header_of(&state, "a.txt");
+
+    state = press(state, "\n");
+    let lines = focused_diff_lines(&state);
+    let header = &lines[state.main_screen.line_cursor];
+    assert!(
+        header.starts_with("diff --git a/a.txt b/a.txt  (+"),
+        "{header}"
+    );
+    assert!(header.ends_with("lines, Enter expands)"));
+    assert!(lines.len() < full);
+    // The other file is still shown in full, its hunk after the header
+    assert!(lines.iter().any(|line| line == "+3"));
+    assert_eq!(commit_folds::shown_files(&state)[0].hunks.len(), 0);
+    assert_eq!(commit_folds::shown_files(&state)[1].hunks.len(), 1);
+    // Not amending the commit
+    assert!(state.main_screen.amending_commit_hash.is_none());
+
+    state = press(state, "\n");
+    assert_eq!(focused_diff_lines(&state).len(), full);
+}
+
+#[test]
+fn test_collapse_all_and_expand_all() {
+    let (_repo, mut state) = setup();
+    let full = focused_diff_lines(&state).len();
+    state.main_screen.is_diff_cursor_active = true;
+    state.main_screen.line_cursor = header_of(&state, "b.txt") + 3;
+
+    state = press(state, "*");
+    assert_eq!(
+        state.message_text(),
+        Some("Collapsed 2 files, Enter on one expands it")
+    );
+    let lines = focused_diff_lines(&state);
+    assert!(
+        !lines
+            .iter()
+            .any(|line| line.starts_with('+') && !line.starts_with("+++"))
+    );
+    // The cursor went to the header of the file it was in
+    assert!(lines[state.main_screen.line_cursor].starts_with("diff --git a/b.txt"));
+    // Hunk jumps find nothing left to go to
+    state = press(state, "]");
+    assert_eq!(state.message_text(), Some("No hunks in this diff"));
+
+    state = press(state, "*");
+    assert_eq!(state.message_text(), Some("Expanded 2 files"));
+    assert_eq!(focused_diff_lines(&state).len(), full);
+    assert!(
+        focused_diff_lines(&state)[state.main_screen.line_cursor].starts_with("diff --git a/b.txt")
+    );
+}
+
+#[test]
+fn test_folds_belong_to_the_commit() {
+    let (repo, mut state) = setup();
+    repo.create_file("c.txt", "c\n");
+    repo.add_all();
+    repo.commit("another");
+    state.refresh_diff(false);
+    // [StagedHeader, CommitMessageInput, another, two files]
+    state.main_screen.file_cursor = 3;
+    state.update_selected_commit_diff();
+    state = press(state, "*");
+    assert!(focused_diff_lines(&state).iter().all(|line| line != "+3"));
+
+    state.main_screen.file_cursor = 2;
+    state.update_selected_commit_diff();
+    assert!(focused_diff_lines(&state).iter().any(|line| line == "+c"));
+
+    state.main_screen.file_cursor = 0;
+    state = press(state, "*");
+    assert_eq!(
+        state.message_text(),
+        Some("Select a commit to collapse its files")
+    );
+}
@@ -0,0 +1,53 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{select_newest_commit, send};
+use git_full_commit::app_state::{AppState, HashDisplay};
+use git_full_commit::git;
+use git_full_commit::ui::main_screen;
+use pancurses::Input;
+
+fn setup() -> (TestRepo, AppState, String) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "a\n");
+    repo.add_all();
+    repo.commit("initial");
+    let full_hash = git::run_git_command(&repo.path, &["rev-parse", "HEAD"])
+        .unwrap()
+        .trim()
+        .to_string();
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    (repo, state, full_hash)
+}
+
+#[test]
+fn test_selected_commit_has_its_full_hash() {
+    let (_repo, state, full_hash) = setup();
+    assert_eq!(full_hash.len(), 40);
+    let state = select_newest_commit(state);
+    let commit = main_screen::selected_commit(&state).unwrap();
+    assert_eq!(commit.full_hash, full_hash);
+    assert!(full_hash.starts_with(&commit.hash));
+
+    // The clipboard may not be available here, the hash is shown either way
+    let state = send(state, Input::Character('Y'));
+    let message = state.message_text().unwrap().to_string();
+    assert!(message.contains(&full_hash), "{message}");
+}
+
+#[test]
+fn test_hash_display_cycles() {
+    let (_repo, state, _full_hash) = setup();
+    assert_eq!(state.main_screen.commit_hashes, HashDisplay::Hidden);
+    let state = send(state, Input::Character('#'));
+    assert_eq!(state.main_screen.commit_hashes, HashDisplay::Short);
+    let state = send(state, Input::Character('#'));
+    assert_eq!(state.main_screen.commit_hashes, HashDisplay::Full);
+    let state = send(state, Input::Character('#'));
+    assert_eq!(state.main_screen.commit_hashes, HashDisplay::Hidden);
+
+    // Without a commit selected there is nothing to copy
+    let state = send(state, Input::Character('Y'));
+    assert_eq!(
+        state.message_text(),
+        Some("Select a commit to copy its hash")
+    );
+}
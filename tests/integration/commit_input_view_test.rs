@@ -1,5 +1,5 @@
 use crate::git_test::common::TestRepo;
-use git_full_commit::app_state::AppState;
+use git_full_commit::app_state::{AppState, Modal};
 use git_full_commit::git;
 use git_full_commit::ui::main_screen::ListItem as MainScreenListItem;
 use git_full_commit::ui::update::{update_state, update_state_with_alt};
@@ -29,8 +29,8 @@ fn test_commit_message_input_and_commit() {
     }
     assert_eq!(app_state.main_screen.commit_message, commit_message);
 
-    // Press enter to commit
-    update_state(app_state, Some(Input::Character('\n')), 80, 80);
+    // Press Alt+Enter to commit
+    update_state_with_alt(app_state, Some(Input::Character('\n')), 80, 80);
 
     // Check git log
     let log = repo.get_log(1);
@@ -70,7 +70,9 @@ fn test_amend_commit_reword_only() {
         app_state = update_state(app_state, Some(Input::Character(ch)), 80, 80);
     }
 
-    // Press enter to finalize
+    // Press Alt+Enter to finalize, and confirm the new message
+    app_state = update_state_with_alt(app_state, Some(Input::Character('\n')), 80, 80);
+    assert!(matches!(app_state.modal, Some(Modal::RewordPreview(_))));
     update_state(app_state, Some(Input::Character('\n')), 80, 80);
 
     let log = repo.get_log(1);
@@ -112,7 +114,9 @@ fn test_amend_non_head_commit_rewords_only_target() {
         app_state = update_state(app_state, Some(Input::Character(ch)), 80, 80);
     }
 
-    // Confirm the amend
+    // Confirm the amend, and the new message
+    app_state = update_state_with_alt(app_state, Some(Input::Character('\n')), 80, 80);
+    assert!(matches!(app_state.modal, Some(Modal::RewordPreview(_))));
     update_state(app_state, Some(Input::Character('\n')), 80, 80);
 
     // HEAD should still be the second commit
@@ -158,7 +162,9 @@ fn test_amend_commit_with_staged_changes() {
         app_state = update_state(app_state, Some(Input::Character(ch)), 80, 80);
     }
 
-    // Press enter to finalize
+    // Press Alt+Enter to finalize, and confirm the new message
+    app_state = update_state_with_alt(app_state, Some(Input::Character('\n')), 80, 80);
+    assert!(matches!(app_state.modal, Some(Modal::RewordPreview(_))));
     update_state(app_state, Some(Input::Character('\n')), 80, 80);
 
     let log = repo.get_log(1);
@@ -402,3 +408,81 @@ fn test_commit_message_horizontal_scroll_with_wide_characters() {
         "When no scrolling is needed the ellipsis padding should reset"
     );
 }
+
+fn multi_line_setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "hello");
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+    app_state.main_screen.file_cursor = 2;
+    (repo, app_state)
+}
+
+fn type_keys(mut app_state: AppState, keys: &str) -> AppState {
+    for ch in keys.chars() {
+        app_state = update_state(app_state, Some(Input::Character(ch)), 80, 80);
+    }
+    app_state
+}
+
+#[test]
+fn test_commit_message_enter_inserts_line_break() {
+    let (repo, app_state) = multi_line_setup();
+
+    // Enter on the empty message only starts typing
+    let app_state = type_keys(app_state, "\nAdd a\n\nBecause it is needed");
+    assert_eq!(
+        app_state.main_screen.commit_message,
+        "Add a\n\nBecause it is needed"
+    );
+    assert!(app_state.is_in_input_mode());
+
+    update_state_with_alt(app_state, Some(Input::Character('\n')), 80, 80);
+    let log = git::run_git_command(&repo.path, &["log", "-1", "--format=%s|%b"]).unwrap();
+    assert_eq!(log, "Add a|Because it is needed\n\n");
+}
+
+#[test]
+fn test_commit_message_up_down_move_between_lines() {
+    let (_repo, app_state) = multi_line_setup();
+    let mut app_state = type_keys(app_state, "Subject\n\nLong body");
+
+    // Up goes to the blank line, then to the subject
+    app_state = update_state(app_state, Some(Input::KeyUp), 80, 80);
+    assert_eq!(app_state.main_screen.commit_cursor, 8);
+    app_state = update_state(app_state, Some(Input::KeyUp), 80, 80);
+    assert_eq!(app_state.main_screen.commit_cursor, 0);
+    assert!(app_state.is_in_input_mode());
+
+    // Ctrl-A and Ctrl-E stay on the current line
+    app_state = type_keys(app_state, "\u{5}");
+    assert_eq!(app_state.main_screen.commit_cursor, 7);
+    app_state = update_state(app_state, Some(Input::KeyDown), 80, 80);
+    app_state = update_state(app_state, Some(Input::KeyDown), 80, 80);
+    app_state = type_keys(app_state, "\u{5}");
+    assert_eq!(app_state.main_screen.commit_cursor, 18);
+
+    // Up on the first line leaves the input
+    for _ in 0..3 {
+        app_state = update_state(app_state, Some(Input::KeyUp), 80, 80);
+    }
+    assert!(!app_state.is_in_input_mode());
+    assert_eq!(app_state.main_screen.commit_message, "Subject\n\nLong body");
+}
+
+#[test]
+fn test_commit_message_ctrl_k_joins_lines_at_line_end() {
+    let (_repo, app_state) = multi_line_setup();
+    let mut app_state = type_keys(app_state, "Subject\nrest");
+    app_state = update_state(app_state, Some(Input::KeyUp), 80, 80);
+    app_state = type_keys(app_state, "\u{5}");
+
+    // At the end of the line, Ctrl-K removes the line break
+    app_state = type_keys(app_state, "\u{b}");
+    assert_eq!(app_state.main_screen.commit_message, "Subjectrest");
+    // Otherwise it cuts the rest of the line
+    app_state = type_keys(app_state, "\u{b}");
+    assert_eq!(app_state.main_screen.commit_message, "Subject");
+}
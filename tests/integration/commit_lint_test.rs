@@ -0,0 +1,118 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::AppState;
+use git_full_commit::commit_lint::Severity;
+use git_full_commit::config::Config;
+use git_full_commit::git;
+use git_full_commit::ui::commit_view;
+use git_full_commit::ui::update::{update_state, update_state_with_alt};
+use pancurses::Input;
+
+fn setup(config: Config) -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "hello");
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new_with_config(repo.path.clone(), files, config);
+    // [Header, File, Input]
+    state.main_screen.file_cursor = 2;
+    (repo, state)
+}
+
+fn commit(state: AppState) -> AppState {
+    update_state_with_alt(state, Some(Input::Character('\n')), 40, 80)
+}
+
+fn commit_count(repo: &TestRepo) -> usize {
+    git::run_git_command(&repo.path, &["rev-list", "--all", "--count"])
+        .map(|count| count.trim().parse().unwrap())
+        .unwrap_or(0)
+}
+
+#[test]
+fn test_problems_of_the_selected_message_are_shown() {
+    let config = Config::parse("subject_pattern = \"^(feat|fix): \"\n");
+    let (_repo, state) = setup(config);
+    assert_eq!(commit_view::lint_summary(&state), None);
+
+    let state = press(state, "Add a");
+    assert_eq!(
+        commit_view::lint_summary(&state),
+        Some((
+            Severity::Error,
+            "Subject does not match ^(feat|fix): ".to_string()
+        ))
+    );
+
+    let state = press(state, "\u{1}feat: ");
+    let state = press(state, &" more".repeat(10));
+    assert_eq!(
+        commit_view::lint_summary(&state),
+        Some((
+            Severity::Warning,
+            "Subject is 61 characters, longer than 50".to_string()
+        ))
+    );
+
+    // Only while the input is selected
+    let state = update_state(state, Some(Input::KeyUp), 40, 80);
+    assert_eq!(commit_view::lint_summary(&state), None);
+}
+
+#[test]
+fn test_lint_does_not_block_by_default() {
+    let (repo, state) = setup(Config::default());
+    let state = press(state, &"a".repeat(80));
+    commit(state);
+    assert_eq!(commit_count(&repo), 1);
+}
+
+#[test]
+fn test_block_on_lint_asks_for_confirmation() {
+    let config = Config::parse("block_on_lint = true\nsubject_max_length = 10\n");
+    let (repo, state) = setup(config);
+    let state = press(state, "Add a files");
+    let state = commit(state);
+    assert_eq!(
+        state.message_text(),
+        Some("Subject is 11 characters, longer than 10. Press Alt+Enter again to commit anyway")
+    );
+    assert_eq!(commit_count(&repo), 0);
+
+    // Changing the message asks again
+    let state = press(state, "!");
+    let state = commit(state);
+    assert_eq!(commit_count(&repo), 0);
+
+    commit(state);
+    assert_eq!(commit_count(&repo), 1);
+}
+
+#[test]
+fn test_block_on_lint_commits_with_warnings() {
+    let config = Config::parse("block_on_lint = true\nsubject_warn_length = 5\n");
+    let (repo, state) = setup(config);
+    let state = press(state, "Add a file");
+    assert_eq!(
+        commit_view::lint_summary(&state).map(|(severity, _)| severity),
+        Some(Severity::Warning)
+    );
+    commit(state);
+    assert_eq!(commit_count(&repo), 1);
+}
+
+#[test]
+fn test_lint_config_keys() {
+    let config = Config::parse(
+        "subject_warn_length = 60\nsubject_max_length = 80\nsubject_pattern = '^\\w+: '\n",
+    );
+    assert_eq!(config.subject_warn_length, Some(60));
+    assert_eq!(config.subject_max_length, Some(80));
+    assert_eq!(config.subject_pattern.unwrap().as_str(), "^\\w+: ");
+    assert!(!config.block_on_lint);
+
+    // An invalid pattern is ignored
+    let config = Config::parse("subject_pattern = \"(feat\"\n");
+    assert!(config.subject_pattern.is_none());
+}
@@ -0,0 +1,73 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::select_newest_commit;
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use git_full_commit::ui::main_screen;
+use git_full_commit::ui::update::update_state_with_alt;
+use pancurses::Input;
+
+/// Three local commits, the newest and the oldest changing `a.txt`.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "a\n");
+    repo.create_file("c.txt", "c\n");
+    repo.add_all();
+    repo.commit("Add a and c");
+    repo.create_file("b.txt", "b\n");
+    repo.add_all();
+    repo.commit("Add b");
+    repo.create_file("a.txt", "a2\n");
+    repo.create_file("c.txt", "c2\n");
+    repo.add_all();
+    repo.commit("Change a and c");
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    (repo, state)
+}
+
+fn hashes(state: &AppState) -> Vec<String> {
+    state
+        .previous_commits
+        .iter()
+        .map(|commit| commit.hash.clone())
+        .collect()
+}
+
+#[test]
+fn test_commits_changing_the_same_files_are_marked() {
+    let (_repo, state) = setup();
+    assert_eq!(state.previous_commits[0].files, vec!["a.txt", "c.txt"]);
+    assert_eq!(state.previous_commits[1].files, vec!["b.txt"]);
+
+    let state = select_newest_commit(state);
+    let hashes = hashes(&state);
+    assert_eq!(main_screen::overlap_tag(&state, &hashes[0]), None);
+    assert_eq!(main_screen::overlap_tag(&state, &hashes[1]), None);
+    assert_eq!(
+        main_screen::overlap_tag(&state, &hashes[2]).as_deref(),
+        Some("⇅ a.txt +1 ")
+    );
+}
+
+#[test]
+fn test_moving_past_a_commit_changing_the_same_files_warns() {
+    let (_repo, state) = setup();
+    let hashes = hashes(&state);
+    let state = select_newest_commit(state);
+
+    // Past a commit changing other files
+    let state = update_state_with_alt(state, Some(Input::KeyDown), 40, 80);
+    assert!(state.main_screen.is_reordering_commits);
+    assert_eq!(state.message_text(), None);
+
+    let state = update_state_with_alt(state, Some(Input::KeyDown), 40, 80);
+    assert_eq!(
+        state.message_text(),
+        Some(
+            format!(
+                "{} and {} both change a.txt and 1 more file, the new order may conflict",
+                hashes[0], hashes[2]
+            )
+            .as_str()
+        )
+    );
+}
@@ -0,0 +1,78 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::AppState;
+use git_full_commit::config::Config;
+use git_full_commit::git;
+use git_full_commit::ui::commit_view;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+/// Stages `paths` and selects the commit message input below them.
+fn setup(paths: &[&str], config: Config) -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    for path in paths {
+        if let Some(dir) = std::path::Path::new(path).parent() {
+            std::fs::create_dir_all(repo.path.join(dir)).unwrap();
+        }
+        repo.create_file(path, "hello");
+    }
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new_with_config(repo.path.clone(), files, config);
+    // [Header, Files..., Input]
+    state.main_screen.file_cursor = paths.len() + 1;
+    (repo, state)
+}
+
+#[test]
+fn test_scope_is_offered_after_the_type_and_completed_with_tab() {
+    let (_repo, state) = setup(&["src/ui/a.rs", "src/ui/b.rs"], Config::default());
+
+    let state = press(state, "feat");
+    assert_eq!(commit_view::scope_completion(&state), None);
+    let state = press(state, "(");
+    assert_eq!(commit_view::scope_completion(&state).as_deref(), Some("ui"));
+
+    let state = press(state, "\tadd a view");
+    assert_eq!(state.main_screen.commit_message, "feat(ui): add a view");
+    assert_eq!(commit_view::scope_completion(&state), None);
+}
+
+#[test]
+fn test_tab_keeps_a_closed_parenthesis() {
+    let (_repo, state) = setup(&["src/git.rs"], Config::default());
+
+    let state = press(state, "fix(): x");
+    let state = press(state, "\u{1}");
+    let state = (0..4).fold(state, |state, _| {
+        update_state(state, Some(Input::KeyRight), 40, 80)
+    });
+    let state = press(state, "\t");
+
+    assert_eq!(state.main_screen.commit_message, "fix(git): x");
+    assert_eq!(state.main_screen.commit_cursor, "fix(git): ".len());
+}
+
+#[test]
+fn test_scope_comes_from_the_config_mapping() {
+    let config = Config::parse("[scopes]\n\"src/ui\" = \"tui\"\n\"*.md\" = \"docs\"\n");
+    let (_repo, state) = setup(&["src/ui/a.rs", "src/ui/deep/b.rs"], config.clone());
+    let state = press(state, "feat(");
+    assert_eq!(
+        commit_view::scope_completion(&state).as_deref(),
+        Some("tui")
+    );
+
+    let (_repo, state) = setup(&["src/ui/a.rs", "README.md"], config);
+    let state = press(state, "feat(\t");
+    assert_eq!(commit_view::scope_completion(&state), None);
+    assert_eq!(state.main_screen.commit_message, "feat(");
+}
+
+#[test]
+fn test_no_scope_for_files_without_a_common_directory() {
+    let (_repo, state) = setup(&["src/git.rs", "src/config.rs"], Config::default());
+    let state = press(state, "feat(");
+    assert_eq!(commit_view::scope_completion(&state), None);
+}
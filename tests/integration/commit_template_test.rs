@@ -0,0 +1,76 @@
+use crate::git_test::common::{TestRepo, run_git};
+use crate::integration::common::{select_commit_input, send};
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use git_full_commit::ui::update::update_state_with_alt;
+use pancurses::Input;
+
+fn setup() -> TestRepo {
+    let repo = TestRepo::new();
+    repo.create_file(".gitmessage", "feat: \n\n# Why was this needed?\n");
+    run_git(&repo.path, &["config", "commit.template", ".gitmessage"]);
+    repo.create_file("a.txt", "one\n");
+    repo.create_file("b.txt", "one\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo
+}
+
+#[test]
+fn test_commit_input_starts_from_the_template() {
+    let repo = setup();
+    repo.create_file("a.txt", "two\n");
+    repo.add_all();
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new(repo.path.clone(), files);
+    assert_eq!(state.main_screen.commit_message, "feat: ");
+    assert_eq!(state.main_screen.commit_cursor, 6);
+
+    // Quitting with the template unchanged keeps no draft, so a changed
+    // template shows next time
+    select_commit_input(&mut state);
+    let state = send(state, Input::Character('x'));
+    let state = send(state, Input::KeyBackspace);
+    let state = send(state, Input::Character('\u{3}'));
+    assert!(!state.running);
+    repo.create_file(".gitmessage", "fix: \n");
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    assert_eq!(state.main_screen.commit_message, "fix: ");
+}
+
+#[test]
+fn test_draft_wins_over_the_template() {
+    let repo = setup();
+    repo.create_file("a.txt", "two\n");
+    repo.add_all();
+    let mut state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    select_commit_input(&mut state);
+    let state = send(state, Input::Character('x'));
+    let state = send(state, Input::Character('\u{3}'));
+    assert!(!state.running);
+
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    assert_eq!(state.main_screen.commit_message, "feat: x");
+    assert_eq!(state.main_screen.commit_template, None);
+}
+
+#[test]
+fn test_template_is_filled_again_after_a_commit() {
+    let repo = setup();
+    repo.create_file("a.txt", "two\n");
+    repo.create_file("b.txt", "two\n");
+    repo.add_all();
+    run_git(&repo.path, &["reset", "-q", "b.txt"]);
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new(repo.path.clone(), files);
+    select_commit_input(&mut state);
+    let state = "change a"
+        .chars()
+        .fold(state, |state, c| send(state, Input::Character(c)));
+    let state = update_state_with_alt(state, Some(Input::Character('\n')), 40, 80);
+
+    assert!(state.running);
+    let log = git::run_git_command(&repo.path, &["log", "-1", "--format=%s"]).unwrap();
+    assert_eq!(log, "feat: change a\n");
+    assert_eq!(state.main_screen.commit_message, "feat: ");
+}
@@ -2,6 +2,67 @@ pub use crate::git_test::common::TestRepo;
 use git_full_commit::app_state::AppState;
 use git_full_commit::git;
 use git_full_commit::ui::main_screen::ListItem;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+/// The view `$variant` open in `$open`, `state.screen` or `state.modal`.
+/// Fails the test when another one or none is open.
+macro_rules! open_view {
+    ($open:expr, $variant:path) => {
+        match &$open {
+            Some($variant(view)) => view,
+            _ => panic!("{} is not open", stringify!($variant)),
+        }
+    };
+}
+pub(crate) use open_view;
+
+/// Sends `input` as it comes from a 40x80 terminal.
+pub fn send(state: AppState, input: Input) -> AppState {
+    update_state(state, Some(input), 40, 80)
+}
+
+/// Types `keys` one character after another.
+pub fn press(state: AppState, keys: &str) -> AppState {
+    keys.chars()
+        .fold(state, |state, c| send(state, Input::Character(c)))
+}
+
+/// Like [`send`], then keeps the message for the message log, as the
+/// screen's loop does before each render.
+pub fn send_remembering(state: AppState, input: Input) -> AppState {
+    let mut state = send(state, input);
+    state.remember_message();
+    state
+}
+
+/// Like [`press`], keeping the message of each key for the message log.
+pub fn press_remembering(state: AppState, keys: &str) -> AppState {
+    keys.chars().fold(state, |state, c| {
+        send_remembering(state, Input::Character(c))
+    })
+}
+
+/// Moves the cursor to the commit message input.
+pub fn select_commit_input(state: &mut AppState) {
+    state.main_screen.file_cursor = state
+        .main_screen
+        .list_items
+        .iter()
+        .position(|item| matches!(item, ListItem::CommitMessageInput))
+        .unwrap();
+}
+
+/// Moves the cursor down to the first commit in the log, the newest one.
+pub fn select_newest_commit(mut state: AppState) -> AppState {
+    while !matches!(
+        state.current_main_item(),
+        Some(ListItem::PreviousCommitInfo { .. })
+    ) {
+        state = send(state, Input::KeyDown);
+    }
+    state
+}
 
 pub fn select_commit_in_log(state: &mut AppState, index: usize) {
     let commit_count = state.previous_commits.len();
@@ -14,6 +75,24 @@ pub fn select_commit_in_log(state: &mut AppState, index: usize) {
     state.main_screen.file_cursor = commit_input_index + 1 + (commit_count - 1 - index);
 }
 
+/// The subjects of the local commits in the state, newest first.
+pub fn commit_subjects(state: &AppState) -> Vec<&str> {
+    state
+        .previous_commits
+        .iter()
+        .map(|commit| commit.message.as_str())
+        .collect()
+}
+
+/// The names of the staged files in the state.
+pub fn staged_files(state: &AppState) -> Vec<&str> {
+    state
+        .files
+        .iter()
+        .map(|file| file.file_name.as_str())
+        .collect()
+}
+
 pub fn assert_commit_list(list_items: &[ListItem], expected: &[&str]) {
     let mut actual = Vec::new();
     for item in list_items {
@@ -34,6 +113,11 @@ pub fn get_log(repo_path: &std::path::Path) -> Vec<git::CommitInfo> {
     git::get_local_commits(repo_path).unwrap()
 }
 
+/// `git log --format=%s` of the repository, newest first.
+pub fn log_subjects(repo: &TestRepo) -> String {
+    git::run_git_command(&repo.path, &["log", "--format=%s"]).unwrap()
+}
+
 impl TestRepo {
     pub fn create_initial_state(&self) -> AppState {
         let files = git::get_diff(self.path.clone());
@@ -0,0 +1,201 @@
+use crate::git_test::common::{TestRepo, run_git};
+use git_full_commit::app_state::{AppState, CursorFallback, FileListOrder, FocusedPane};
+use git_full_commit::config::Config;
+use git_full_commit::git;
+use git_full_commit::ui::main_screen::{ListItem, UnstagedListItem};
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+fn selected_main_file(app_state: &AppState) -> Option<String> {
+    match app_state
+        .main_screen
+        .list_items
+        .get(app_state.main_screen.file_cursor)
+    {
+        Some(ListItem::File(file)) => Some(file.file_name.clone()),
+        _ => None,
+    }
+}
+
+fn selected_unstaged_file(app_state: &AppState) -> Option<String> {
+    match app_state
+        .unstaged_pane
+        .list_items
+        .get(app_state.unstaged_pane.cursor)
+    {
+        Some(UnstagedListItem::File(file)) => Some(file.file_name.clone()),
+        Some(UnstagedListItem::UntrackedFile(name)) => Some(name.clone()),
+        _ => None,
+    }
+}
+
+fn setup_three_staged_files() -> TestRepo {
+    let repo = TestRepo::new();
+    repo.create_file("init.txt", "init");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "a");
+    repo.create_file("b.txt", "b");
+    repo.create_file("c.txt", "c");
+    repo.add_all();
+    repo
+}
+
+#[test]
+fn test_cursor_follows_file_when_earlier_file_is_unstaged_externally() {
+    let repo = setup_three_staged_files();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    // [Header, a, b, c, ...]: select c.txt
+    app_state.main_screen.file_cursor = 3;
+    assert_eq!(selected_main_file(&app_state).as_deref(), Some("c.txt"));
+
+    run_git(&repo.path, &["reset", "-q", "a.txt"]);
+    app_state.refresh_diff(false);
+
+    assert_eq!(selected_main_file(&app_state).as_deref(), Some("c.txt"));
+    assert_eq!(app_state.main_screen.file_cursor, 2);
+}
+
+#[test]
+fn test_unstage_middle_file_moves_to_next_file() {
+    let repo = setup_three_staged_files();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    app_state.main_screen.file_cursor = 2;
+    app_state = update_state(app_state, Some(Input::Character('u')), 80, 80);
+
+    assert_eq!(selected_main_file(&app_state).as_deref(), Some("c.txt"));
+}
+
+#[test]
+fn test_unstage_last_file_moves_to_previous_file_instead_of_commit_input() {
+    let repo = setup_three_staged_files();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    app_state.main_screen.file_cursor = 3;
+    app_state = update_state(app_state, Some(Input::Character('u')), 80, 80);
+
+    assert_eq!(selected_main_file(&app_state).as_deref(), Some("b.txt"));
+}
+
+#[test]
+fn test_previous_fallback_moves_to_preceding_file() {
+    let repo = setup_three_staged_files();
+    let files = git::get_diff(repo.path.clone());
+    let config = Config {
+        cursor_fallback: CursorFallback::Previous,
+        ..Default::default()
+    };
+    let mut app_state = AppState::new_with_config(repo.path.clone(), files, config);
+
+    app_state.main_screen.file_cursor = 2;
+    app_state = update_state(app_state, Some(Input::Character('u')), 80, 80);
+
+    assert_eq!(selected_main_file(&app_state).as_deref(), Some("a.txt"));
+}
+
+#[test]
+fn test_unstage_only_file_moves_to_header() {
+    let repo = TestRepo::new();
+    repo.create_file("init.txt", "init");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "a");
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    app_state = update_state(app_state, Some(Input::Character('u')), 80, 80);
+
+    assert_eq!(app_state.main_screen.file_cursor, 0);
+    assert!(matches!(
+        app_state.main_screen.list_items[0],
+        ListItem::StagedChangesHeader
+    ));
+}
+
+#[test]
+fn test_cursor_stays_on_commit_when_file_is_unstaged_externally() {
+    let repo = setup_three_staged_files();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    // [Header, a, b, c, Input, initial]
+    app_state.main_screen.file_cursor = 5;
+    let hash = app_state.previous_commits[0].hash.clone();
+
+    run_git(&repo.path, &["reset", "-q", "a.txt"]);
+    app_state.refresh_diff(false);
+
+    assert_eq!(app_state.main_screen.file_cursor, 4);
+    assert!(matches!(
+        &app_state.main_screen.list_items[4],
+        ListItem::PreviousCommitInfo { hash: h, .. } if *h == hash
+    ));
+}
+
+#[test]
+fn test_unstaged_cursor_follows_file_when_earlier_file_is_staged() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "a");
+    repo.create_file("b.txt", "b");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "a2");
+    repo.create_file("b.txt", "b2");
+
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+    app_state.focused_pane = FocusedPane::Unstaged;
+
+    // [UnstagedHeader, a, b]: select b.txt
+    app_state.unstaged_pane.cursor = 2;
+
+    run_git(&repo.path, &["add", "a.txt"]);
+    app_state.refresh_diff(false);
+
+    assert_eq!(selected_unstaged_file(&app_state).as_deref(), Some("b.txt"));
+    assert_eq!(app_state.unstaged_pane.cursor, 1);
+}
+
+#[test]
+fn test_config_parse() {
+    let config = Config::parse(
+        "# settings\nfile_list_order = \"tree\"\ncursor_fallback = 'previous' # inline\n",
+    );
+    assert_eq!(config.file_list_order, FileListOrder::Tree);
+    assert_eq!(config.cursor_fallback, CursorFallback::Previous);
+
+    let config = Config::parse("file_list_order = \"sideways\"\nunknown = 1\n");
+    assert_eq!(config.file_list_order, FileListOrder::Diff);
+    assert_eq!(config.cursor_fallback, CursorFallback::Next);
+}
+
+#[test]
+fn test_config_file_list_order_applies_on_start() {
+    let repo = TestRepo::new();
+    std::fs::create_dir_all(repo.path.join("z")).unwrap();
+    repo.create_file("a.txt", "a");
+    repo.create_file("z/b.txt", "b");
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let config = Config {
+        file_list_order: FileListOrder::Tree,
+        ..Default::default()
+    };
+    let app_state = AppState::new_with_config(repo.path.clone(), files, config);
+
+    assert_eq!(app_state.main_screen.file_list_order, FileListOrder::Tree);
+    let names: Vec<&str> = app_state
+        .files
+        .iter()
+        .map(|f| f.file_name.as_str())
+        .collect();
+    assert_eq!(names, vec!["z/b.txt", "a.txt"]);
+}
@@ -0,0 +1,77 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use git_full_commit::ui::main_screen::focused_diff_lines;
+
+/// `logo.png`, a binary file, deleted and the deletion staged.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("logo.png", "\0png\0data");
+    repo.add_all();
+    repo.commit("initial");
+    std::fs::remove_file(repo.path.join("logo.png")).unwrap();
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let state = AppState::new(repo.path.clone(), files);
+    (repo, state)
+}
+
+#[test]
+fn test_deleted_binary_file_shows_what_it_was() {
+    let (_repo, state) = setup();
+    assert_eq!(
+        focused_diff_lines(&state),
+        vec![
+            "Deleted file logo.png (r: restore it)",
+            "",
+            "  Binary file (size: 9 bytes)",
+        ]
+    );
+}
+
+#[test]
+fn test_deleted_text_file_shows_its_lines() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\ntwo\n");
+    repo.add_all();
+    repo.commit("initial");
+    std::fs::remove_file(repo.path.join("a.txt")).unwrap();
+    repo.add_all();
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+
+    // The removal hunk shows it already
+    assert!(focused_diff_lines(&state).contains(&"-two".to_string()));
+}
+
+#[test]
+fn test_r_restores_a_deleted_file() {
+    let (repo, state) = setup();
+    let state = press(state, "r");
+    assert_eq!(state.message_text(), Some("Restored logo.png"));
+    assert!(state.files.is_empty());
+    assert_eq!(
+        std::fs::read(repo.path.join("logo.png")).unwrap(),
+        b"\0png\0data"
+    );
+
+    let state = press(state, "<");
+    assert_eq!(state.files.len(), 1);
+    assert!(!repo.path.join("logo.png").exists());
+}
+
+#[test]
+fn test_r_keeps_a_file_that_took_the_place_of_the_deleted_one() {
+    let (repo, state) = setup();
+    repo.create_file("logo.png", "other");
+    let state = press(state, "r");
+    assert_eq!(
+        state.message_text(),
+        Some("logo.png exists in the working tree again, stage or discard it first")
+    );
+    assert_eq!(
+        std::fs::read_to_string(repo.path.join("logo.png")).unwrap(),
+        "other"
+    );
+}
@@ -0,0 +1,34 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{open_view, press};
+use git_full_commit::app_state::{AppState, Screen};
+use git_full_commit::git;
+use git_full_commit::message_log::Severity;
+
+#[test]
+fn test_diagnostics_show_version_and_recent_messages() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "a");
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new(repo.path.clone(), files);
+    state.set_message(Severity::Error, "Push failed: rejected");
+    state.remember_message();
+
+    state = press(state, "D");
+    let lines = &open_view!(state.screen, Screen::Diagnostics).lines;
+    assert_eq!(
+        lines[0],
+        format!("git-full-commit {}", env!("CARGO_PKG_VERSION"))
+    );
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.starts_with("git:        git version"))
+    );
+    assert!(lines.contains(&format!("repository: {}", repo.path.display())));
+    assert_eq!(lines.last().unwrap(), "  Push failed: rejected");
+
+    state = press(state, "q");
+    assert!(!matches!(state.screen, Some(Screen::Diagnostics(_))));
+}
@@ -0,0 +1,140 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::AppState;
+use git_full_commit::config::Config;
+use git_full_commit::git;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+/// `a.txt` with lines 1 to 20, of which 2 and 19 are changed, far enough
+/// apart to make two hunks. Nothing is staged.
+fn setup_repo() -> TestRepo {
+    let repo = TestRepo::new();
+    let lines = |changed: bool| -> String {
+        (1..=20)
+            .map(|n| match n {
+                2 | 19 if changed => format!("{n} changed\n"),
+                _ => format!("{n}\n"),
+            })
+            .collect()
+    };
+    repo.create_file("a.txt", &lines(false));
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", &lines(true));
+    repo
+}
+
+/// The unstaged pane focused on `a.txt`.
+fn focus_unstaged(state: AppState) -> AppState {
+    let mut state = press(state, "\t");
+    while state.get_unstaged_file().is_none() {
+        state = update_state(state, Some(Input::KeyDown), 40, 80);
+    }
+    state
+}
+
+fn context_lines(state: &AppState) -> usize {
+    let file = state.get_unstaged_file().unwrap();
+    file.lines
+        .iter()
+        .filter(|line| line.starts_with(' '))
+        .count()
+}
+
+fn cursor_line(state: &AppState) -> &str {
+    &state.get_unstaged_file().unwrap().lines[state.main_screen.line_cursor]
+}
+
+#[test]
+fn test_plus_and_minus_change_the_context() {
+    let repo = setup_repo();
+    let state = focus_unstaged(AppState::new(
+        repo.path.clone(),
+        git::get_diff(repo.path.clone()),
+    ));
+    // Line 2 has one line above it; line 19 has one line below it
+    assert_eq!(context_lines(&state), 3 + 1 + 3 + 1);
+
+    let state = press(state, "-");
+    assert_eq!(state.diff_context, Some(2));
+    assert_eq!(context_lines(&state), 2 + 1 + 2 + 1);
+    assert_eq!(
+        state.message_text(),
+        Some("Showing 2 lines of context, + and - change it")
+    );
+
+    let state = press(state, "--");
+    assert_eq!(context_lines(&state), 0);
+    let state = press(state, "-");
+    assert_eq!(state.diff_context, Some(0));
+    assert_eq!(state.message_text(), Some("Already showing no context"));
+
+    let state = press(state, "++++++++");
+    let file = state.get_unstaged_file().unwrap();
+    assert_eq!(file.hunks.len(), 1, "{:?}", file.lines);
+}
+
+#[test]
+fn test_cursor_stays_on_its_hunk() {
+    let repo = setup_repo();
+    let mut state = focus_unstaged(AppState::new(
+        repo.path.clone(),
+        git::get_diff(repo.path.clone()),
+    ));
+    state.unstaged_pane.is_diff_cursor_active = true;
+    let file = state.get_unstaged_file().unwrap();
+    state.main_screen.line_cursor = file.lines.iter().position(|l| l == "+19 changed").unwrap();
+
+    let state = press(state, "++++++");
+    assert_eq!(state.get_unstaged_file().unwrap().hunks.len(), 1);
+    assert_eq!(cursor_line(&state), "+19 changed");
+
+    let state = press(state, "---------");
+    assert_eq!(cursor_line(&state), "+19 changed");
+
+    // A context line no longer shown leaves the cursor on its hunk
+    let mut state = press(state, "+++");
+    let file = state.get_unstaged_file().unwrap();
+    state.main_screen.line_cursor = file.lines.iter().position(|l| l == " 16").unwrap();
+    let state = press(state, "---");
+    assert_eq!(cursor_line(&state), "-19");
+}
+
+#[test]
+fn test_staging_a_hunk_without_context() {
+    let repo = setup_repo();
+    let mut state = focus_unstaged(AppState::new(
+        repo.path.clone(),
+        git::get_diff(repo.path.clone()),
+    ));
+    state.unstaged_pane.is_diff_cursor_active = true;
+    let mut state = press(state, "---");
+    let file = state.get_unstaged_file().unwrap();
+    state.main_screen.line_cursor = file.hunks[1].start_line + 1;
+
+    let state = press(state, "u");
+
+    let staged = git::run_git_command(&repo.path, &["diff", "--cached"]).unwrap();
+    assert!(staged.contains("+19 changed"), "{staged}");
+    assert!(!staged.contains("+2 changed"), "{staged}");
+    // The staged diff is shown without context too
+    assert!(
+        !state.files[0]
+            .lines
+            .iter()
+            .any(|line| line.starts_with(' '))
+    );
+}
+
+#[test]
+fn test_config_sets_the_context() {
+    let repo = setup_repo();
+    let config = Config::parse("diff_context = 1\n");
+    let state = focus_unstaged(AppState::new_with_config(
+        repo.path.clone(),
+        git::get_diff(repo.path.clone()),
+        config,
+    ));
+    assert_eq!(context_lines(&state), 1 + 1 + 1 + 1);
+}
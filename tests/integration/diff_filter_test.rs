@@ -0,0 +1,117 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::{AppState, FocusedPane};
+use git_full_commit::config::Config;
+use git_full_commit::diff_filter::DiffFilter;
+use git_full_commit::git;
+use git_full_commit::ui::main_screen::focused_diff_lines;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+use std::process::Command;
+
+fn next_file(state: AppState) -> AppState {
+    update_state(state, Some(Input::KeyDown), 40, 80)
+}
+
+fn setup(config: &str) -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("app.min.js", "one\n");
+    repo.create_file("notes.txt", "one\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("app.min.js", "two\n");
+    repo.create_file("notes.txt", "two\n");
+
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new_with_config(repo.path.clone(), files, Config::parse(config));
+    state.focused_pane = FocusedPane::Unstaged;
+    state.unstaged_pane.cursor = 1;
+    (repo, state)
+}
+
+fn shown_changes(state: &AppState) -> Vec<String> {
+    focused_diff_lines(state)
+        .into_iter()
+        .filter(|line| line.starts_with('+') || line.starts_with('-'))
+        .filter(|line| !line.starts_with("+++") && !line.starts_with("---"))
+        .collect()
+}
+
+#[test]
+fn test_diff_filters_config_section() {
+    let config = Config::parse(
+        "[diff_filters]\n\"*.min.js\" = \"cut -c1-80\"\n\"dist/*\" = \"\"\n\"*.lock\" = 'sed s/x/y/'\n",
+    );
+    assert_eq!(
+        config.diff_filters,
+        vec![
+            DiffFilter {
+                pattern: "*.lock".to_string(),
+                command: "sed s/x/y/".to_string(),
+            },
+            DiffFilter {
+                pattern: "*.min.js".to_string(),
+                command: "cut -c1-80".to_string(),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_filter_changes_the_shown_diff_only() {
+    let (repo, state) = setup("[diff_filters]\n\"*.js\" = \"tr a-z A-Z\"\n");
+    assert_eq!(state.message_text(), None);
+    assert_eq!(shown_changes(&state), vec!["-ONE", "+TWO"]);
+
+    // The next file has no filter
+    let state = next_file(state);
+    assert_eq!(shown_changes(&state), vec!["-one", "+two"]);
+
+    // Staging uses the real diff
+    let state = update_state(state, Some(Input::KeyUp), 40, 80);
+    let _ = press(state, "u");
+    let output = Command::new("git")
+        .args(["diff", "--cached"])
+        .current_dir(&repo.path)
+        .output()
+        .unwrap();
+    let staged = String::from_utf8_lossy(&output.stdout);
+    assert!(staged.contains("+two"), "{staged}");
+}
+
+#[test]
+fn test_raw_diff_toggle() {
+    let (_repo, state) = setup("[diff_filters]\n\"*.js\" = \"tr a-z A-Z\"\n");
+
+    let state = press(state, "F");
+    assert_eq!(
+        state.message_text(),
+        Some("Showing the raw diff of app.min.js")
+    );
+    assert_eq!(shown_changes(&state), vec!["-one", "+two"]);
+
+    let state = press(state, "F");
+    assert_eq!(shown_changes(&state), vec!["-ONE", "+TWO"]);
+
+    let state = next_file(state);
+    let state = press(state, "F");
+    assert_eq!(
+        state.message_text(),
+        Some("No diff filter is configured for notes.txt")
+    );
+}
+
+#[test]
+fn test_failing_filter_shows_the_raw_diff() {
+    let (_repo, state) = setup("[diff_filters]\n\"*.js\" = \"head -n 1\"\n");
+    assert!(
+        state
+            .message_text()
+            .is_some_and(|message| message.starts_with(
+                "Diff filter failed for app.min.js, showing the raw diff: printed 1 lines"
+            )),
+        "{:?}",
+        state.message
+    );
+    assert_eq!(shown_changes(&state), vec!["-one", "+two"]);
+}
@@ -0,0 +1,116 @@
+use crate::git_test::common::TestRepo;
+use git_full_commit::app_state::{AppState, FocusedPane};
+use git_full_commit::git;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+fn setup_repo(stage: bool) -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    let initial_content: String = (0..100).map(|i| format!("line {i}\n")).collect();
+    repo.create_file("a.txt", &initial_content);
+    repo.add_all();
+    repo.commit("initial");
+
+    let modified_content: String = (0..100).map(|i| format!("changed {i}\n")).collect();
+    repo.create_file("a.txt", &modified_content);
+    if stage {
+        repo.add_all();
+    }
+
+    let files = git::get_diff(repo.path.clone());
+    let app_state = AppState::new(repo.path.clone(), files);
+    (repo, app_state)
+}
+
+fn search(mut state: AppState, query: &str) -> AppState {
+    state = update_state(state, Some(Input::Character('/')), 30, 80);
+    assert!(state.prompt.is_some());
+    for c in query.chars() {
+        state = update_state(state, Some(Input::Character(c)), 30, 80);
+    }
+    update_state(state, Some(Input::Character('\n')), 30, 80)
+}
+
+fn cursor_line(state: &AppState) -> &str {
+    &state.files[0].lines[state.main_screen.line_cursor]
+}
+
+#[test]
+fn test_search_moves_to_first_match_and_scrolls() {
+    let (_repo, mut state) = setup_repo(true);
+    state.main_screen.file_cursor = 1;
+
+    state = search(state, "+changed 5");
+
+    assert!(state.main_screen.is_diff_cursor_active);
+    assert_eq!(cursor_line(&state), "+changed 5");
+    assert_eq!(
+        state.main_screen.diff_scroll,
+        state.main_screen.line_cursor - 3
+    );
+    assert_eq!(state.message_text(), Some("/+changed 5  1/11"));
+}
+
+#[test]
+fn test_n_and_shift_n_move_between_matches_and_wrap() {
+    let (_repo, mut state) = setup_repo(true);
+    state.main_screen.file_cursor = 1;
+    state = search(state, "+changed 9");
+    assert_eq!(cursor_line(&state), "+changed 9");
+
+    state = update_state(state, Some(Input::Character('n')), 30, 80);
+    assert_eq!(cursor_line(&state), "+changed 90");
+
+    state = update_state(state, Some(Input::Character('N')), 30, 80);
+    state = update_state(state, Some(Input::Character('N')), 30, 80);
+    assert_eq!(cursor_line(&state), "+changed 99");
+    assert_eq!(state.message_text(), Some("/+changed 9  11/11"));
+}
+
+#[test]
+fn test_search_ignores_case_unless_query_has_uppercase() {
+    let (_repo, mut state) = setup_repo(true);
+    state.main_screen.file_cursor = 1;
+
+    state = search(state, "-line 42");
+    assert_eq!(cursor_line(&state), "-line 42");
+
+    let line_cursor = state.main_screen.line_cursor;
+    state = search(state, "LINE 7");
+    assert_eq!(state.message_text(), Some("Pattern not found: LINE 7"));
+    assert_eq!(state.main_screen.line_cursor, line_cursor);
+}
+
+#[test]
+fn test_escape_ends_search() {
+    let (_repo, mut state) = setup_repo(true);
+    state.main_screen.file_cursor = 1;
+    state = search(state, "changed 1");
+    let line_cursor = state.main_screen.line_cursor;
+
+    state = update_state(state, Some(Input::Character('\u{1b}')), 30, 80);
+    assert!(state.main_screen.search.is_none());
+
+    state = update_state(state, Some(Input::Character('n')), 30, 80);
+    assert_eq!(state.main_screen.line_cursor, line_cursor);
+}
+
+#[test]
+fn test_search_in_unstaged_diff() {
+    let (_repo, mut state) = setup_repo(false);
+    state = update_state(state, Some(Input::Character('\t')), 30, 80);
+    assert_eq!(state.focused_pane, FocusedPane::Unstaged);
+    state.unstaged_pane.cursor = 1;
+
+    state = search(state, "+changed 80");
+
+    assert!(state.unstaged_pane.is_diff_cursor_active);
+    assert!(state.unstaged_pane.search.is_some());
+    assert!(state.main_screen.search.is_none());
+    let file = &state.unstaged_pane.unstaged_files[0];
+    assert_eq!(file.lines[state.main_screen.line_cursor], "+changed 80");
+    assert_eq!(
+        state.unstaged_pane.diff_scroll,
+        state.main_screen.line_cursor - 3
+    );
+}
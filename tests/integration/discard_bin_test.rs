@@ -0,0 +1,144 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{open_view, press};
+use git_full_commit::app_state::{AppState, Screen};
+use git_full_commit::git;
+use git_full_commit::ui::discard_bin::DiscardedContent;
+use git_full_commit::ui::update::update_state_with_alt;
+use pancurses::Input;
+use std::fs;
+
+fn lines(changed: &[usize]) -> String {
+    (1..=20)
+        .map(|i| {
+            if changed.contains(&i) {
+                format!("changed{i}\n")
+            } else {
+                format!("line{i}\n")
+            }
+        })
+        .collect()
+}
+
+#[test]
+fn test_discarded_hunks_can_be_restored_after_commit() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", &lines(&[]));
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", &lines(&[1, 20]));
+    repo.create_file("b.txt", "b");
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new(repo.path.clone(), files);
+    state.main_screen.file_cursor = 1;
+    state = press(state, "!");
+    assert_eq!(
+        fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+        lines(&[])
+    );
+    assert_eq!(state.discarded_changes.len(), 2);
+
+    // Committing clears the undo history
+    state.main_screen.file_cursor = 2;
+    state = press(state, "\n");
+    state = press(state, "add b");
+    state = update_state_with_alt(state, Some(Input::Character('\n')), 40, 80);
+    assert!(repo.get_log(1).contains("add b"));
+    state.main_screen.file_cursor = 0;
+    state = press(state, "<");
+    assert_eq!(
+        fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+        lines(&[])
+    );
+
+    // The newest entry, the second hunk, is listed first
+    state = press(state, "T");
+    assert!(matches!(state.screen, Some(Screen::DiscardBin(_))));
+    state = press(state, "r");
+    assert_eq!(
+        state.message_text(),
+        Some("Restored a.txt to the working tree")
+    );
+    assert_eq!(
+        fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+        lines(&[20])
+    );
+
+    state = press(state, "r");
+    assert_eq!(
+        state.message_text(),
+        Some("Cannot restore a.txt: it no longer applies to the working tree")
+    );
+
+    state = press(state, "j");
+    state = press(state, "r");
+    assert_eq!(
+        fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+        lines(&[1, 20])
+    );
+    assert_eq!(state.unstaged_pane.unstaged_files.len(), 1);
+
+    // Restoring goes through the undo history
+    state = press(state, "\t");
+    assert!(!matches!(state.screen, Some(Screen::DiscardBin(_))));
+    press(state, "<");
+    assert_eq!(
+        fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+        lines(&[20])
+    );
+}
+
+#[test]
+fn test_deleted_untracked_file_can_be_restored() {
+    let repo = TestRepo::new();
+    repo.commit("initial");
+    repo.create_file("notes.txt", "keep me\n");
+
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new(repo.path.clone(), files);
+    state = press(state, "\t");
+    state.unstaged_pane.cursor = 2;
+    state = press(state, "!");
+    assert!(!repo.path.join("notes.txt").exists());
+    assert!(matches!(
+        state.discarded_changes[0].content,
+        DiscardedContent::File(_)
+    ));
+
+    state = press(state, "T");
+    state = press(state, "\n");
+    let diff = open_view!(state.screen, Screen::DiscardBin)
+        .diff
+        .as_ref()
+        .unwrap();
+    assert_eq!(diff[0].lines, vec!["+keep me"]);
+
+    state = press(state, "r");
+    assert_eq!(
+        fs::read_to_string(repo.path.join("notes.txt")).unwrap(),
+        "keep me\n"
+    );
+    state = press(state, "q");
+    state = press(state, "q");
+    assert!(!matches!(state.screen, Some(Screen::DiscardBin(_))));
+}
+
+#[test]
+fn test_failed_discard_is_not_recorded() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "hello\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "staged\n");
+    repo.add_all();
+    repo.create_file("a.txt", "unstaged\n");
+
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new(repo.path.clone(), files);
+    state.main_screen.file_cursor = 1;
+    state = press(state, "!");
+
+    assert_eq!(state.files.len(), 1);
+    assert!(state.discarded_changes.is_empty());
+}
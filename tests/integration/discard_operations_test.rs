@@ -109,3 +109,54 @@ fn test_discard_untracked_file() {
     let status = repo.get_status();
     assert!(!status.contains("a.txt"));
 }
+
+#[test]
+fn test_discard_staged_file_changed_after_refresh_is_refused() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "hello");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "world");
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+    app_state.main_screen.file_cursor = 1;
+
+    // Edited in the editor while the screen still shows the old diff
+    repo.create_file("a.txt", "world, edited");
+    app_state = update_state(app_state, Some(Input::Character('!')), 80, 80);
+
+    assert_eq!(
+        fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+        "world, edited"
+    );
+    assert_eq!(app_state.files.len(), 1);
+    assert!(
+        app_state
+            .message_text()
+            .is_some_and(|message| message.starts_with("a.txt changed on disk"))
+    );
+    assert_eq!(app_state.unstaged_pane.unstaged_files.len(), 1);
+}
+
+#[test]
+fn test_discard_untracked_file_changed_after_refresh_is_refused() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "hello");
+
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+    app_state = update_state(app_state, Some(Input::Character('\t')), 80, 80);
+    app_state.unstaged_pane.cursor = 2;
+
+    repo.create_file("a.txt", "hello, edited");
+    app_state = update_state(app_state, Some(Input::Character('!')), 80, 80);
+
+    assert_eq!(
+        fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+        "hello, edited"
+    );
+    assert!(app_state.message.is_some());
+    assert_eq!(app_state.unstaged_pane.untracked_files, vec!["a.txt"]);
+}
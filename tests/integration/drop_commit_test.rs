@@ -0,0 +1,111 @@
+use crate::integration::common::{
+    TestRepo, commit_subjects, open_view, press, select_commit_in_log,
+};
+use git_full_commit::app_state::{AppState, Modal};
+use git_full_commit::ui::discard_bin::DiscardedContent;
+use git_full_commit::ui::plan_preview::PlanSource;
+
+/// `first`, `second` adding `a.txt` and `third` adding `b.txt`, with
+/// `second` selected.
+fn setup() -> (TestRepo, AppState, String) {
+    let repo = TestRepo::new();
+    repo.commit("first");
+    repo.create_file("a.txt", "a\n");
+    repo.add_all();
+    repo.commit("second");
+    repo.create_file("b.txt", "b\n");
+    repo.add_all();
+    repo.commit("third");
+    let mut state = repo.create_initial_state();
+    select_commit_in_log(&mut state, 1);
+    let hash = state.previous_commits[1].hash.clone();
+    (repo, state, hash)
+}
+
+#[test]
+fn test_drop_commit_after_confirming_its_plan() {
+    let (repo, state, hash) = setup();
+
+    let state = press(state, "d");
+    let preview = open_view!(state.modal, Modal::PlanPreview);
+    assert_eq!(preview.source, PlanSource::DropCommit(hash.clone()));
+    assert!(
+        preview
+            .todo
+            .starts_with(&format!("drop {hash} second\npick ")),
+        "{}",
+        preview.todo
+    );
+    assert_eq!(preview.prediction, "Trial run: applies cleanly");
+
+    // Esc leaves the history alone
+    let state = press(state, "\u{1b}");
+    assert_eq!(commit_subjects(&state), vec!["third", "second", "first"]);
+
+    let state = press(state, "d\n");
+    assert_eq!(commit_subjects(&state), vec!["third", "first"]);
+    assert!(!repo.path.join("a.txt").exists());
+    assert_eq!(
+        state.message_text(),
+        Some(format!("Dropped {hash} second, < or the discard bin (T) bring it back").as_str())
+    );
+    assert!(matches!(
+        state.discarded_changes.last().map(|change| &change.content),
+        Some(DiscardedContent::Commit(_))
+    ));
+
+    let state = press(state, "<");
+    assert_eq!(commit_subjects(&state), vec!["third", "second", "first"]);
+    assert!(repo.path.join("a.txt").exists());
+}
+
+#[test]
+fn test_dropped_commit_is_restored_from_the_discard_bin() {
+    let (repo, state, hash) = setup();
+    let mut state = press(state, "d\n");
+    state.command_history.clear();
+
+    let state = press(state, "Tr");
+    assert_eq!(
+        state.message_text(),
+        Some(format!("Restored {hash} onto the branch").as_str())
+    );
+    assert!(repo.path.join("a.txt").exists());
+    let state = press(state, "q");
+    assert_eq!(commit_subjects(&state), vec!["second", "third", "first"]);
+}
+
+#[test]
+fn test_drop_is_refused_on_a_remote_commit() {
+    let (repo, _state, _hash) = setup();
+    repo.push();
+    let mut state = repo.create_initial_state();
+    let newest = state.previous_commits.len() - 1;
+    select_commit_in_log(&mut state, newest);
+
+    let state = press(state, "d");
+    assert!(!matches!(state.modal, Some(Modal::PlanPreview(_))));
+    assert_eq!(
+        state.message_text(),
+        Some("Commits on a remote cannot be dropped")
+    );
+}
+
+#[test]
+fn test_restored_commit_undo_keeps_a_newer_commit() {
+    let (repo, state, _hash) = setup();
+    let mut state = press(state, "d\n");
+    state.command_history.clear();
+    let state = press(state, "Trq");
+    repo.create_file("c.txt", "c\n");
+    repo.add_all();
+    repo.commit("fourth");
+
+    let state = press(state, "<");
+    assert_eq!(
+        state.message_text(),
+        Some("Failed to drop the commit again: the branch moved on since")
+    );
+    assert!(repo.path.join("a.txt").exists());
+    assert!(repo.path.join("c.txt").exists());
+}
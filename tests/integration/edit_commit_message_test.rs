@@ -42,7 +42,8 @@ fn test_edit_commit_message() {
         &["commit 2", new_message, "commit 0"],
     );
 
-    // Press Enter again to confirm the reorder
+    // Press Enter again to confirm the reorder, and once more for the plan
+    state = update_state(state, Some(Input::Character('\n')), 1024, 1024);
     let _ = update_state(state, Some(Input::Character('\n')), 1024, 1024);
 
     // Now the reorder is complete, and the git history should be updated.
@@ -0,0 +1,128 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{press, send};
+use git_full_commit::app_state::AppState;
+use git_full_commit::config::Config;
+use git_full_commit::git;
+use pancurses::Input;
+use std::fs;
+use std::process::Command;
+
+fn staged_diff(repo: &TestRepo) -> String {
+    let output = Command::new("git")
+        .args(["diff", "--cached", "--", "a.txt"])
+        .current_dir(&repo.path)
+        .output()
+        .unwrap();
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn lines(count: usize) -> Vec<String> {
+    (1..=count).map(|n| format!("line {n}")).collect()
+}
+
+/// `a.txt` of 20 lines with its first line changed but not staged, and
+/// the unstaged pane on it.
+fn setup(stage_after_edit: &str) -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", &(lines(20).join("\n") + "\n"));
+    repo.create_file("b.txt", "b\n");
+    repo.add_all();
+    repo.commit("initial");
+    let mut content = lines(20);
+    content[0] = "not staged".to_string();
+    repo.create_file("a.txt", &(content.join("\n") + "\n"));
+    repo.create_file("b.txt", "staged\n");
+    repo.add_all();
+    Command::new("git")
+        .args(["reset", "-q", "a.txt"])
+        .current_dir(&repo.path)
+        .output()
+        .unwrap();
+
+    let config = Config::parse(&format!("stage_after_edit = \"{stage_after_edit}\"\n"));
+    let files = git::get_diff(repo.path.clone());
+    let state = AppState::new_with_config(repo.path.clone(), files, config);
+    let state = send(press(state, "\t"), Input::KeyDown);
+    assert_eq!(
+        state
+            .get_unstaged_file()
+            .map(|file| file.file_name.as_str()),
+        Some("a.txt")
+    );
+    (repo, state)
+}
+
+/// Presses `e`, replaces line `line` (from 1) of the file in the editor,
+/// then returns to the app like the editor exited.
+fn edit_line(mut state: AppState, line: usize, text: &str) -> AppState {
+    state = press(state, "e");
+    let request = state.editor_request.take().expect("the editor opens");
+    let content = fs::read_to_string(&request.file_path).unwrap();
+    let mut edited: Vec<&str> = content.lines().collect();
+    edited[line - 1] = text;
+    fs::write(&request.file_path, edited.join("\n") + "\n").unwrap();
+    state.finish_editor_request(request);
+    state
+}
+
+#[test]
+fn test_stage_only_the_edited_lines() {
+    let (repo, state) = setup("stage");
+    let state = edit_line(state, 15, "edited");
+    assert_eq!(
+        state.message_text(),
+        Some("Staged your edit of a.txt (+1 -1)")
+    );
+    let staged = staged_diff(&repo);
+    assert!(staged.contains("-line 15\n+edited\n"));
+    assert!(!staged.contains("not staged"));
+
+    // One undo step takes it back
+    press(state, "<");
+    assert_eq!(staged_diff(&repo), "");
+    assert!(
+        fs::read_to_string(repo.path.join("a.txt"))
+            .unwrap()
+            .contains("edited")
+    );
+}
+
+#[test]
+fn test_edit_next_to_unstaged_lines_stays_unstaged() {
+    let (repo, state) = setup("stage");
+    let state = edit_line(state, 2, "edited");
+    assert_eq!(
+        state.message_text(),
+        Some("Left your edit of a.txt (+1 -1) unstaged, it touches lines that are not staged")
+    );
+    assert_eq!(staged_diff(&repo), "");
+}
+
+#[test]
+fn test_ask_before_staging_the_edit() {
+    let (repo, state) = setup("ask");
+    let state = edit_line(state, 15, "edited");
+    assert_eq!(
+        state.message_text(),
+        Some("Stage your edit of a.txt (+1 -1)? y stages it")
+    );
+    assert_eq!(staged_diff(&repo), "");
+    let state = press(state, "y");
+    assert!(staged_diff(&repo).contains("+edited\n"));
+    assert!(state.edit_stage_offer.is_none());
+
+    // n declines, so a later y does nothing
+    let (repo, state) = setup("ask");
+    let state = press(edit_line(state, 15, "edited"), "n");
+    assert!(state.edit_stage_offer.is_none());
+    press(state, "y");
+    assert_eq!(staged_diff(&repo), "");
+}
+
+#[test]
+fn test_edits_stay_unstaged_by_default() {
+    let (repo, state) = setup("off");
+    let state = press(state, "e");
+    assert!(state.editor_request.as_ref().unwrap().stage_edit.is_none());
+    assert_eq!(staged_diff(&repo), "");
+}
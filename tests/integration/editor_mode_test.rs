@@ -0,0 +1,63 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::AppState;
+use git_full_commit::config::Config;
+use git_full_commit::external_command::EditorMode;
+use git_full_commit::git;
+
+/// `a.txt` with a staged change, opened with `config`.
+fn setup(config: &str) -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "two\n");
+    repo.add_all();
+    let files = git::get_diff(repo.path.clone());
+    let state = AppState::new_with_config(repo.path.clone(), files, Config::parse(config));
+    (repo, state)
+}
+
+#[test]
+fn test_editor_mode_is_read_from_the_config() {
+    assert_eq!(Config::parse("").editor_mode, EditorMode::Auto);
+    assert_eq!(
+        Config::parse("editor_mode = \"wait\"").editor_mode,
+        EditorMode::Wait
+    );
+    assert_eq!(
+        Config::parse("editor_mode = \"detach\"").editor_mode,
+        EditorMode::Detach
+    );
+    assert_eq!(
+        Config::parse("editor_mode = \"later\"").editor_mode,
+        EditorMode::Auto
+    );
+}
+
+#[test]
+fn test_detach_only_when_the_file_is_not_read_back() {
+    let (_repo, state) = setup("editor_mode = \"detach\"");
+    let state = press(state, "e");
+    let request = state.editor_request.as_ref().unwrap();
+    assert!(!request.reads_back());
+    assert!(request.detaches(EditorMode::Detach));
+    assert!(!request.detaches(EditorMode::Wait));
+    assert!(!request.detaches(EditorMode::Auto));
+
+    // Hunks edited with E are applied once the editor exits
+    let (_repo, state) = setup("editor_mode = \"detach\"");
+    let state = press(state, "jE");
+    let request = state.editor_request.as_ref().unwrap();
+    assert!(request.reads_back());
+    assert!(!request.detaches(EditorMode::Detach));
+}
+
+#[test]
+fn test_edits_to_stage_are_waited_for() {
+    let (_repo, state) = setup("editor_mode = \"detach\"\nstage_after_edit = \"stage\"\n");
+    let state = press(state, "e");
+    let request = state.editor_request.as_ref().unwrap();
+    assert!(request.reads_back());
+    assert!(!request.detaches(EditorMode::Detach));
+}
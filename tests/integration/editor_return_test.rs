@@ -0,0 +1,83 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{press, send};
+use git_full_commit::app_state::{AppState, FocusedPane};
+use git_full_commit::git;
+use pancurses::Input;
+use std::fs;
+
+fn lines(count: usize) -> Vec<String> {
+    (1..=count).map(|n| format!("line {n}")).collect()
+}
+
+/// `a.txt` of 20 lines with its first and last lines changed, not staged,
+/// and the unstaged pane on it.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", &(lines(20).join("\n") + "\n"));
+    repo.add_all();
+    repo.commit("initial");
+    let mut content = lines(20);
+    content[0] = "first".to_string();
+    content[19] = "last".to_string();
+    repo.create_file("a.txt", &(content.join("\n") + "\n"));
+
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    let state = send(press(state, "\t"), Input::KeyDown);
+    assert_eq!(
+        state
+            .get_unstaged_file()
+            .map(|file| file.file_name.as_str()),
+        Some("a.txt")
+    );
+    (repo, state)
+}
+
+fn cursor_line(state: &AppState) -> &str {
+    let file = state.get_unstaged_file().unwrap();
+    &file.lines[state.main_screen.line_cursor]
+}
+
+/// Presses `e`, lets `edit` change the file like the editor would, then
+/// returns to the app.
+fn edit(mut state: AppState, edit: impl Fn(&str) -> String) -> AppState {
+    state = press(state, "e");
+    let request = state.editor_request.take().expect("the editor opens");
+    let content = fs::read_to_string(&request.file_path).unwrap();
+    fs::write(&request.file_path, edit(&content)).unwrap();
+    state.finish_editor_request(request);
+    state
+}
+
+#[test]
+fn test_return_to_the_hunk_after_lines_shifted() {
+    let (_repo, state) = setup();
+    let state = (0..50).fold(state, |state, _| press(state, "j"));
+    assert_eq!(cursor_line(&state), "+last");
+    let old_line_cursor = state.main_screen.line_cursor;
+
+    let state = edit(state, |content| {
+        content.replacen("first\n", "first\nnew 1\nnew 2\nnew 3\n", 1)
+    });
+    assert_eq!(state.focused_pane, FocusedPane::Unstaged);
+    assert!(state.unstaged_pane.is_diff_cursor_active);
+    assert_eq!(cursor_line(&state), "+last");
+    assert_eq!(state.main_screen.line_cursor, old_line_cursor + 3);
+    assert_eq!(state.message_text(), Some("a.txt: not staged"));
+}
+
+#[test]
+fn test_an_edited_hunk_is_found_by_its_other_lines() {
+    let (_repo, state) = setup();
+    let state = (0..50).fold(state, |state, _| press(state, "j"));
+    let state = edit(state, |content| content.replace("last", "the last"));
+    assert_eq!(cursor_line(&state), "+the last");
+}
+
+#[test]
+fn test_without_a_hunk_the_file_stays_selected() {
+    let (repo, state) = setup();
+    repo.add_all();
+    let state = edit(state, |content| content.to_string());
+    assert_eq!(state.message_text(), Some("a.txt: staged"));
+    assert!(!state.unstaged_pane.is_diff_cursor_active);
+}
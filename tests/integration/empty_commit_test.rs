@@ -0,0 +1,59 @@
+use crate::git_test::common::TestRepo;
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use git_full_commit::ui::commit_view;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+/// `a.txt` committed, then staged again with only its indentation and a
+/// blank line changed.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "fn main() {\n    run();\n}\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "fn main() {\n\n  run();\n}\n");
+    repo.add_all();
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    (repo, state)
+}
+
+#[test]
+fn test_whitespace_only_commit_is_refused_and_cleaned_up() {
+    let (repo, mut state) = setup();
+    commit_view::commit(&mut state, "reindent", false);
+    assert_eq!(
+        state.message_text(),
+        Some(
+            "Nothing meaningful to commit, a.txt only changes whitespace. u unstages, Alt+Enter commits anyway"
+        )
+    );
+    assert!(!repo.get_log(5).contains("reindent"));
+
+    let state = update_state(state, Some(Input::Character('u')), 40, 80);
+    assert_eq!(
+        state.message_text(),
+        Some("Unstaged the file that only changed whitespace")
+    );
+    assert_eq!(repo.get_status(), " M a.txt\n");
+}
+
+#[test]
+fn test_committing_again_goes_ahead() {
+    let (repo, mut state) = setup();
+    commit_view::commit(&mut state, "reindent", false);
+    let mut state = update_state(state, Some(Input::Character('x')), 40, 80);
+    assert!(state.empty_commit_cleanup.is_none());
+    commit_view::commit(&mut state, "reindent", false);
+    assert!(repo.get_log(1).contains("reindent"));
+}
+
+#[test]
+fn test_real_changes_commit_at_once() {
+    let (repo, mut state) = setup();
+    repo.create_file("b.txt", "new\n");
+    repo.add_all();
+    state.refresh_diff(false);
+    commit_view::commit(&mut state, "add b", false);
+    assert!(repo.get_log(1).contains("add b"));
+}
@@ -0,0 +1,71 @@
+use crate::git_test::common::TestRepo;
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use git_full_commit::keymap::KeyMap;
+use git_full_commit::ui::main_screen::empty_state_hints;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+fn new_state(repo: &TestRepo) -> AppState {
+    AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()))
+}
+
+#[test]
+fn test_hints_when_nothing_is_staged() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "a\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "a2\n");
+
+    let state = new_state(&repo);
+    assert_eq!(
+        empty_state_hints(&state),
+        vec![
+            "Nothing is staged yet.",
+            "Press Tab to view unstaged changes, or R to stage everything.",
+        ]
+    );
+
+    // The keys follow the config
+    let mut state = state;
+    state.keymap = KeyMap::new(&[("stage_all".to_string(), "A".to_string())]);
+    assert_eq!(
+        empty_state_hints(&state)[1],
+        "Press Tab to view unstaged changes, or A to stage everything."
+    );
+
+    // Only the untracked files are left to stage
+    repo.add_all();
+    repo.create_file("new.txt", "new\n");
+    let state = update_state(new_state(&repo), Some(Input::Character('\t')), 40, 80);
+    assert_eq!(
+        empty_state_hints(&state),
+        vec![
+            "No tracked file has unstaged changes.",
+            "Press Down to pick an untracked file, or R to stage everything.",
+        ]
+    );
+}
+
+#[test]
+fn test_hints_for_a_fresh_repository() {
+    let repo = TestRepo::new();
+    let state = new_state(&repo);
+    assert_eq!(
+        empty_state_hints(&state),
+        vec![
+            "Nothing is staged yet.",
+            "The working tree is clean, there is nothing to commit.",
+            "There are no commits yet, the next one is the first.",
+        ]
+    );
+
+    repo.create_file("a.txt", "a\n");
+    repo.add_all();
+    let state = update_state(new_state(&repo), Some(Input::KeyUp), 40, 80);
+    assert_eq!(
+        empty_state_hints(&state),
+        vec!["There are no commits yet, the next one is the first."]
+    );
+}
@@ -0,0 +1,51 @@
+use crate::git_test::common::TestRepo;
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+#[test]
+fn test_toggle_executable_bit_shows_mode_change() {
+    let repo = TestRepo::new();
+    repo.create_file("run.sh", "echo hi\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("run.sh", "echo hello\n");
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+    assert_eq!(app_state.main_screen.file_cursor, 1);
+
+    app_state = update_state(app_state, Some(Input::Character('x')), 80, 80);
+
+    let lines = &app_state.files[0].lines;
+    assert!(lines.iter().any(|l| l == "old mode 100644"));
+    assert!(lines.iter().any(|l| l == "new mode 100755"));
+    assert_eq!(app_state.files[0].hunks.len(), 1);
+    assert!(!app_state.main_screen.has_unstaged_changes);
+
+    // Undo removes the mode change again.
+    app_state = update_state(app_state, Some(Input::Character('<')), 80, 80);
+    let lines = &app_state.files[0].lines;
+    assert!(!lines.iter().any(|l| l.starts_with("new mode")));
+}
+
+#[test]
+fn test_toggle_executable_bit_on_new_script() {
+    let repo = TestRepo::new();
+    repo.create_file("run.sh", "echo hi\n");
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    app_state = update_state(app_state, Some(Input::Character('x')), 80, 80);
+
+    assert!(
+        app_state.files[0]
+            .lines
+            .iter()
+            .any(|l| l == "new file mode 100755")
+    );
+}
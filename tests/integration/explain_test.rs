@@ -0,0 +1,98 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{open_view, press, send};
+use git_full_commit::app_state::{AppState, Modal};
+use git_full_commit::git;
+use git_full_commit::ui::main_screen::UnstagedListItem;
+use pancurses::Input;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// `a.txt` changed, not staged, with `command` as `explain_command`.
+fn setup(command: Option<&str>) -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "a\n");
+    repo.add_all();
+    repo.commit("Add a");
+    repo.create_file("a.txt", "a2\n");
+    let mut state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    state.config.explain_command = command.map(str::to_string);
+    (repo, state)
+}
+
+/// Focuses the unstaged file and moves the diff cursor to the added line.
+fn select_hunk(mut state: AppState) -> AppState {
+    state = press(state, "\t");
+    while !matches!(
+        state
+            .unstaged_pane
+            .list_items
+            .get(state.unstaged_pane.cursor),
+        Some(UnstagedListItem::File(_))
+    ) {
+        state = send(state, Input::KeyDown);
+    }
+    state = press(state, "j");
+    while state
+        .get_unstaged_file()
+        .and_then(|file| file.lines.get(state.main_screen.line_cursor))
+        .is_none_or(|line| line != "+a2")
+    {
+        state = press(state, "j");
+    }
+    state
+}
+
+fn wait_for_explanation(state: &mut AppState) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while matches!(&state.modal, Some(Modal::Explanation(explanation)) if explanation.running) {
+        assert!(Instant::now() < deadline, "explain_command did not finish");
+        state.poll_background();
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn test_hunk_is_piped_to_explain_command() {
+    let (_repo, state) = setup(Some("echo \"about $GIT_FULL_COMMIT_FILE\"; grep '^[-+]a'"));
+    let mut state = press(select_hunk(state), "J");
+    assert!(open_view!(state.modal, Modal::Explanation).running);
+    wait_for_explanation(&mut state);
+
+    assert_eq!(
+        open_view!(state.modal, Modal::Explanation).lines,
+        ["about a.txt", "-a", "+a2"]
+    );
+
+    state = press(state, "q");
+    assert!(!matches!(state.modal, Some(Modal::Explanation(_))));
+}
+
+#[test]
+fn test_failing_explain_command_shows_its_output() {
+    let (_repo, state) = setup(Some("echo nope >&2; exit 1"));
+    let mut state = press(select_hunk(state), "J");
+    wait_for_explanation(&mut state);
+
+    let lines = &open_view!(state.modal, Modal::Explanation).lines;
+    assert_eq!(lines[0], "echo nope >&2; exit 1 failed:");
+    assert_eq!(lines.last().unwrap(), "nope");
+}
+
+#[test]
+fn test_explain_needs_a_command_and_a_hunk() {
+    let (_repo, state) = setup(None);
+    let state = press(select_hunk(state), "J");
+    assert!(!matches!(state.modal, Some(Modal::Explanation(_))));
+    assert_eq!(
+        state.message_text(),
+        Some("Set explain_command to explain hunks")
+    );
+
+    let (_repo, state) = setup(Some("cat"));
+    let state = press(state, "J");
+    assert!(!matches!(state.modal, Some(Modal::Explanation(_))));
+    assert_eq!(
+        state.message_text(),
+        Some("Move to a hunk with j/k to explain it")
+    );
+}
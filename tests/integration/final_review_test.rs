@@ -0,0 +1,73 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::open_view;
+use git_full_commit::app_state::{AppState, Screen};
+use git_full_commit::git;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+fn review_lines(app_state: &AppState) -> Vec<String> {
+    open_view!(app_state.screen, Screen::FinalReview)
+        .files
+        .iter()
+        .flat_map(|f| f.lines.clone())
+        .collect()
+}
+
+#[test]
+fn test_final_review_shows_only_staged_changes() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "line1\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "line1\nstaged\n");
+    repo.add_all();
+    repo.create_file("a.txt", "line1\nstaged\nunstaged\n");
+
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+    app_state.main_screen.commit_message = "Add staged line".to_string();
+
+    app_state = update_state(app_state, Some(Input::Character('v')), 40, 80);
+
+    let lines = review_lines(&app_state);
+    assert!(lines.iter().any(|l| l == "    Add staged line"));
+    assert!(lines.iter().any(|l| l == "+staged"));
+    assert!(!lines.iter().any(|l| l.contains("unstaged")));
+    assert_eq!(
+        open_view!(app_state.screen, Screen::FinalReview).overlapping_files,
+        vec!["a.txt".to_string()]
+    );
+
+    // Keys are consumed by the review and do not reach the file list.
+    app_state = update_state(app_state, Some(Input::Character('u')), 40, 80);
+    assert_eq!(app_state.files.len(), 1);
+
+    app_state = update_state(app_state, Some(Input::Character('q')), 40, 80);
+    assert!(!matches!(app_state.screen, Some(Screen::FinalReview(_))));
+    assert!(app_state.running);
+}
+
+#[test]
+fn test_final_review_scrolling_is_clamped() {
+    let repo = TestRepo::new();
+    let content: String = (0..50).map(|i| format!("line{i}\n")).collect();
+    repo.create_file("a.txt", &content);
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    app_state = update_state(app_state, Some(Input::Character('v')), 20, 80);
+    for _ in 0..10 {
+        app_state = update_state(app_state, Some(Input::Character(' ')), 20, 80);
+    }
+
+    let review = open_view!(app_state.screen, Screen::FinalReview);
+    assert_eq!(review.scroll, review.total_lines() - 19);
+
+    app_state = update_state(app_state, Some(Input::Character('b')), 20, 80);
+    assert_eq!(
+        open_view!(app_state.screen, Screen::FinalReview).scroll,
+        review_lines(&app_state).len() - 19 - 19
+    );
+}
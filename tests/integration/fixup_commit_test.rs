@@ -31,7 +31,8 @@ fn test_fixup_commit_in_reorder_mode() {
         panic!("Expected a PreviousCommitInfo item");
     }
 
-    // Confirm the reorder
+    // Confirm the reorder and its plan
+    state = update_state(state, Some(Input::Character('\n')), 1024, 1024);
     state = update_state(state, Some(Input::Character('\n')), 1024, 1024);
 
     // After reordering, "commit 1" should be squashed into "commit 0".
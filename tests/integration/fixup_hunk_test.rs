@@ -0,0 +1,91 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{open_view, press, send};
+use git_full_commit::app_state::{AppState, Screen};
+use git_full_commit::git;
+use git_full_commit::ui::main_screen::UnstagedListItem;
+use pancurses::Input;
+
+/// Two local commits, and `a.txt` of the older one changed again.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "a\n");
+    repo.add_all();
+    repo.commit("Add a");
+    repo.create_file("b.txt", "b\n");
+    repo.add_all();
+    repo.commit("Add b");
+    repo.create_file("a.txt", "a2\n");
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    (repo, state)
+}
+
+fn select_file(mut state: AppState) -> AppState {
+    state = press(state, "\t");
+    while !matches!(
+        state
+            .unstaged_pane
+            .list_items
+            .get(state.unstaged_pane.cursor),
+        Some(UnstagedListItem::File(_))
+    ) {
+        state = send(state, Input::KeyDown);
+    }
+    state
+}
+
+/// Moves the diff cursor to the added line of the hunk.
+fn select_hunk(state: AppState) -> AppState {
+    let mut state = press(select_file(state), "j");
+    while state
+        .get_unstaged_file()
+        .and_then(|file| file.lines.get(state.main_screen.line_cursor))
+        .is_none_or(|line| line != "+a2")
+    {
+        state = press(state, "j");
+    }
+    state
+}
+
+#[test]
+fn test_hunk_is_fixed_up_into_the_picked_commit() {
+    let (repo, state) = setup();
+    let state = press(select_hunk(state), "A");
+    let picker = open_view!(state.screen, Screen::FixupPicker);
+    assert_eq!(picker.commits.len(), 2);
+    assert_eq!(picker.commits[0].message, "Add b");
+
+    let state = send(press(state, "j"), Input::Character('\n'));
+    assert!(!matches!(state.screen, Some(Screen::FixupPicker(_))));
+    let message = state.message_text().unwrap().to_string();
+    assert!(message.starts_with("Fixed up the hunk into "), "{message}");
+    assert!(message.ends_with(" Add a"), "{message}");
+    let content = git::run_git_command(&repo.path, &["show", "HEAD~1:a.txt"]).unwrap();
+    assert_eq!(content, "a2\n");
+    assert!(!state.main_screen.has_unstaged_changes);
+
+    // Undone in one step
+    let state = press(state, "<");
+    let content = git::run_git_command(&repo.path, &["show", "HEAD~1:a.txt"]).unwrap();
+    assert_eq!(content, "a\n");
+    assert!(state.main_screen.has_unstaged_changes);
+}
+
+#[test]
+fn test_fixup_needs_a_hunk_and_a_local_commit() {
+    let (repo, state) = setup();
+    let state = press(select_file(state), "A");
+    assert!(!matches!(state.screen, Some(Screen::FixupPicker(_))));
+    assert_eq!(
+        state.message_text(),
+        Some("Move to a hunk with j/k to fix it up into a commit")
+    );
+
+    repo.push();
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    let state = press(select_hunk(state), "A");
+    assert!(!matches!(state.screen, Some(Screen::FixupPicker(_))));
+    assert_eq!(
+        state.message_text(),
+        Some("No local commit to fix up the hunk into")
+    );
+}
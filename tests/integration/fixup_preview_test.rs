@@ -0,0 +1,120 @@
+use crate::integration::common::{TestRepo, get_log, select_commit_in_log};
+use git_full_commit::git;
+
+fn commit_file(repo: &TestRepo, name: &str, content: &str, message: &str) {
+    repo.create_file(name, content);
+    repo.add_all();
+    repo.commit(message);
+}
+
+fn preview_lines(repo: &TestRepo, index_from_oldest: usize) -> Vec<String> {
+    let hash = get_log(&repo.path)
+        .into_iter()
+        .rev()
+        .nth(index_from_oldest)
+        .unwrap()
+        .hash;
+    git::get_fixup_preview(&repo.path, &hash)
+        .unwrap()
+        .expect("a fixup preview")
+        .iter()
+        .flat_map(|f| f.lines.clone())
+        .collect()
+}
+
+#[test]
+fn test_preview_shows_target_with_fixup_applied() {
+    let repo = TestRepo::new();
+    commit_file(&repo, "a.txt", "one\n", "add a");
+    commit_file(&repo, "b.txt", "bee\n", "add b");
+    commit_file(&repo, "b.txt", "bee\nsee\n", "unrelated");
+    commit_file(&repo, "a.txt", "one\ntwo\n", "fixup! add b");
+
+    // The fixup targets "add b", not the newer commit touching b.txt.
+    let lines = preview_lines(&repo, 3);
+    let title = lines
+        .iter()
+        .find(|l| l.starts_with("Squashed into "))
+        .expect("a title line");
+    assert!(title.ends_with(" add b:"), "{title}");
+    assert!(lines.contains(&"+bee".to_string()));
+    assert!(lines.contains(&" one".to_string()));
+    assert!(lines.contains(&"+two".to_string()));
+    assert!(!lines.contains(&"+see".to_string()));
+}
+
+#[test]
+fn test_preview_is_shown_in_commit_log() {
+    let repo = TestRepo::new();
+    commit_file(&repo, "a.txt", "one\n", "add a");
+    commit_file(&repo, "a.txt", "one\ntwo\n", "fixup! add a");
+
+    let mut state = repo.create_initial_state();
+    select_commit_in_log(&mut state, 1);
+    state.update_selected_commit_diff();
+
+    let lines: Vec<&String> = state
+        .selected_commit_files
+        .iter()
+        .flat_map(|f| f.lines.iter())
+        .collect();
+    let title_index = lines
+        .iter()
+        .position(|l| l.starts_with("Squashed into "))
+        .expect("a title line");
+    // The fixup's own diff comes first, then the squashed result of the
+    // root commit, which adds the whole file.
+    assert!(lines[..title_index].iter().any(|l| *l == "+two"));
+    assert!(lines[title_index..].iter().any(|l| *l == "+one"));
+    assert!(lines[title_index..].iter().any(|l| *l == "+two"));
+}
+
+#[test]
+fn test_unmatched_and_conflicting_fixups_are_reported() {
+    let repo = TestRepo::new();
+    commit_file(&repo, "a.txt", "one\n", "add a");
+    commit_file(&repo, "a.txt", "uno\n", "translate a");
+    commit_file(&repo, "a.txt", "uno\ndos\n", "fixup! add a");
+    commit_file(&repo, "b.txt", "b\n", "fixup! nothing like this");
+
+    assert!(
+        preview_lines(&repo, 2)
+            .iter()
+            .any(|l| l.starts_with("The fixup does not apply cleanly to "))
+    );
+    assert!(
+        preview_lines(&repo, 3)
+            .iter()
+            .any(|l| l.starts_with("Fixup target not found"))
+    );
+}
+
+#[test]
+fn test_target_can_be_given_by_hash_or_subject_prefix() {
+    let repo = TestRepo::new();
+    commit_file(&repo, "a.txt", "one\n", "add a with a long subject");
+    let target = get_log(&repo.path)[0].hash.clone();
+    commit_file(&repo, "a.txt", "one\ntwo\n", "other");
+
+    assert_eq!(
+        git::find_fixup_target(&repo.path, "HEAD", &format!("fixup! {target}"))
+            .unwrap()
+            .map(|(_, subject)| subject),
+        Some("add a with a long subject".to_string())
+    );
+    assert_eq!(
+        git::find_fixup_target(&repo.path, "HEAD", "fixup! fixup! add a with")
+            .unwrap()
+            .map(|(hash, _)| hash),
+        Some(target)
+    );
+    assert_eq!(
+        git::find_fixup_target(&repo.path, "HEAD", "add a").unwrap(),
+        None
+    );
+    assert!(
+        git::get_fixup_preview(&repo.path, "HEAD")
+            .unwrap()
+            .is_none()
+    );
+}
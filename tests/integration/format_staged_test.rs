@@ -0,0 +1,112 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{open_view, send};
+use git_full_commit::app_state::{AppState, Modal};
+use git_full_commit::formatter::Formatter;
+use git_full_commit::git;
+use pancurses::Input;
+
+/// `a.txt` with trailing spaces staged, and more of them not staged, with
+/// a formatter stripping them.
+fn setup(command: &str) -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\ntwo\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "one  \ntwo\n");
+    repo.add_all();
+    repo.create_file("a.txt", "one  \ntwo\nwip  \n");
+    let mut state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    state.config.formatters = vec![Formatter {
+        pattern: "*.txt".to_string(),
+        command: command.to_string(),
+    }];
+    (repo, state)
+}
+
+fn staged(repo: &TestRepo) -> String {
+    git::run_git_command(&repo.path, &["show", ":a.txt"]).unwrap()
+}
+
+fn working_tree(repo: &TestRepo) -> String {
+    std::fs::read_to_string(repo.path.join("a.txt")).unwrap()
+}
+
+#[test]
+fn test_formatting_fixes_of_the_staged_content_are_staged() {
+    let (repo, state) = setup("sed 's/ *$//'");
+
+    let state = send(state, Input::Character('Z'));
+    let preview = open_view!(state.modal, Modal::FormatPreview);
+    assert_eq!(preview.fixes.len(), 1);
+    assert!(preview.fixes[0].patch.contains("-one  \n+one\n"));
+    assert_eq!(staged(&repo), "one  \ntwo\n");
+
+    let state = send(state, Input::Character('\n'));
+    assert!(!matches!(state.modal, Some(Modal::FormatPreview(_))));
+    assert_eq!(
+        state.message_text(),
+        Some("Staged the formatting fixes of 1 file")
+    );
+    assert_eq!(staged(&repo), "one\ntwo\n");
+    // The change that is not staged is not formatted.
+    assert_eq!(working_tree(&repo), "one\ntwo\nwip  \n");
+
+    let _state = send(state, Input::Character('<'));
+    assert_eq!(staged(&repo), "one  \ntwo\n");
+    assert_eq!(working_tree(&repo), "one  \ntwo\nwip  \n");
+}
+
+#[test]
+fn test_closing_the_preview_stages_nothing() {
+    let (repo, state) = setup("sed 's/ *$//'");
+
+    let state = send(state, Input::Character('Z'));
+    let state = send(state, Input::Character('q'));
+
+    assert!(!matches!(state.modal, Some(Modal::FormatPreview(_))));
+    assert_eq!(staged(&repo), "one  \ntwo\n");
+}
+
+#[test]
+fn test_formatter_failures_are_listed() {
+    let (repo, state) = setup("echo broken >&2; exit 1");
+
+    let state = send(state, Input::Character('Z'));
+    let preview = open_view!(state.modal, Modal::FormatPreview);
+    assert!(preview.fixes.is_empty());
+    assert_eq!(
+        preview.errors,
+        ["a.txt: echo broken >&2; exit 1 failed: broken"]
+    );
+
+    let state = send(state, Input::Character('\n'));
+    assert!(matches!(state.modal, Some(Modal::FormatPreview(_))));
+    assert_eq!(staged(&repo), "one  \ntwo\n");
+}
+
+#[test]
+fn test_formatted_content_and_missing_formatters_are_reported() {
+    let (_repo, state) = setup("cat");
+    let state = send(state, Input::Character('Z'));
+    assert!(!matches!(state.modal, Some(Modal::FormatPreview(_))));
+    assert_eq!(
+        state.message_text(),
+        Some("The staged changes are formatted already")
+    );
+
+    let (_repo, mut state) = setup("cat");
+    state.config.formatters[0].pattern = "*.rs".to_string();
+    let state = send(state, Input::Character('Z'));
+    assert_eq!(
+        state.message_text(),
+        Some("No formatter matches the staged files")
+    );
+
+    let (_repo, mut state) = setup("cat");
+    state.config.formatters.clear();
+    let state = send(state, Input::Character('Z'));
+    assert_eq!(
+        state.message_text(),
+        Some("Add formatters to the [formatters] section of the config")
+    );
+}
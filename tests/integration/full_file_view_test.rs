@@ -0,0 +1,78 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+/// `a.txt` with lines 1 to 20, of which 2 and 19 are changed, far enough
+/// apart to make two hunks. Nothing is staged.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    let lines = |changed: bool| -> String {
+        (1..=20)
+            .map(|n| match n {
+                2 | 19 if changed => format!("{n} changed\n"),
+                _ => format!("{n}\n"),
+            })
+            .collect()
+    };
+    repo.create_file("a.txt", &lines(false));
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", &lines(true));
+
+    let mut state = press(
+        AppState::new(repo.path.clone(), git::get_diff(repo.path.clone())),
+        "\t",
+    );
+    while state.get_unstaged_file().is_none() {
+        state = update_state(state, Some(Input::KeyDown), 40, 80);
+    }
+    (repo, state)
+}
+
+#[test]
+fn test_full_file_view_shows_every_line() {
+    let (_repo, state) = setup();
+    assert!(state.get_unstaged_file().unwrap().lines.len() < 20);
+
+    let state = press(state, "V");
+    let file = state.get_unstaged_file().unwrap();
+    let context: Vec<&str> = file
+        .lines
+        .iter()
+        .filter(|line| line.starts_with(' ') || line.starts_with('+') && !line.starts_with("+++"))
+        .map(String::as_str)
+        .collect();
+    let expected: Vec<String> = (1..=20)
+        .map(|n| match n {
+            2 | 19 => format!("+{n} changed"),
+            _ => format!(" {n}"),
+        })
+        .collect();
+    assert_eq!(context, expected);
+    assert_eq!(file.hunks.len(), 2);
+    // The view starts a few lines above the first hunk
+    assert_eq!(state.main_screen.line_cursor, file.hunks[0].start_line);
+
+    let state = press(state, "V");
+    assert!(state.get_unstaged_file().unwrap().lines.len() < 20);
+}
+
+#[test]
+fn test_staging_in_full_file_view_stages_one_hunk() {
+    let (repo, state) = setup();
+    let mut state = press(state, "V");
+    state.unstaged_pane.is_diff_cursor_active = true;
+    let last_hunk = state.get_unstaged_file().unwrap().hunks[1].start_line;
+    state.main_screen.line_cursor = last_hunk + 1;
+
+    let state = press(state, "u");
+
+    let staged = git::run_git_command(&repo.path, &["diff", "--cached"]).unwrap();
+    assert!(staged.contains("+19 changed"), "{staged}");
+    assert!(!staged.contains("+2 changed"), "{staged}");
+    // The staged diff is shown whole too
+    assert!(state.files[0].lines.iter().any(|line| line == " 10"));
+}
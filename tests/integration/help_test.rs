@@ -0,0 +1,57 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::{AppState, Screen};
+use git_full_commit::git;
+use git_full_commit::keymap::KeyMap;
+use git_full_commit::ui::help;
+
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "a\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "a2\n");
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    (repo, state)
+}
+
+#[test]
+fn test_help_lists_the_bound_keys() {
+    let lines = help::help_lines(&KeyMap::default());
+    assert_eq!(lines[0], "Staged and unstaged changes");
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.trim_start().starts_with("!")
+                && line.ends_with("Discard the file or hunk")),
+        "{lines:#?}"
+    );
+    assert!(lines.contains(&"Reordering commits".to_string()));
+
+    let keymap = KeyMap::new(&[("discard".to_string(), "d Ctrl-x".to_string())]);
+    let lines = help::help_lines(&keymap);
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.trim_start().starts_with("d, Ctrl-x ")),
+        "{lines:#?}"
+    );
+}
+
+#[test]
+fn test_help_opens_and_leads_to_the_tutorial() {
+    let (_repo, state) = setup();
+    let state = press(state, "?");
+    assert!(matches!(state.screen, Some(Screen::Help(_))));
+
+    // Keys scroll the list instead of acting on the files
+    let state = press(state, "jR");
+    assert!(state.files.is_empty());
+    let state = press(state, "q");
+    assert!(!matches!(state.screen, Some(Screen::Help(_))));
+    assert!(state.running);
+
+    let state = press(state, "?t");
+    assert!(!matches!(state.screen, Some(Screen::Help(_))));
+    assert!(state.tutorial.is_some());
+}
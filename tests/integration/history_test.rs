@@ -0,0 +1,104 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::open_view;
+use git_full_commit::app_state::{AppState, Screen};
+use git_full_commit::git;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+fn setup_pushed_history() -> TestRepo {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("first");
+    repo.create_file("a.txt", "one\ntwo\n");
+    repo.add_all();
+    repo.commit("second");
+    repo.push();
+    repo.create_file("a.txt", "one\ntwo\nthree\n");
+    repo.add_all();
+    repo.commit("third");
+    repo
+}
+
+fn diff_lines(app_state: &AppState) -> Vec<String> {
+    open_view!(app_state.screen, Screen::History)
+        .diff
+        .as_ref()
+        .expect("a commit diff should be open")
+        .iter()
+        .flat_map(|f| f.lines.clone())
+        .collect()
+}
+
+#[test]
+fn test_history_lists_commits_beyond_the_pushed_one() {
+    let repo = setup_pushed_history();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    app_state = update_state(app_state, Some(Input::Character('H')), 40, 80);
+
+    let messages: Vec<&str> = open_view!(app_state.screen, Screen::History)
+        .commits
+        .iter()
+        .map(|c| c.message.as_str())
+        .collect();
+    assert_eq!(messages, vec!["third", "second", "first"]);
+}
+
+#[test]
+fn test_enter_shows_diff_of_selected_commit() {
+    let repo = setup_pushed_history();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    app_state = update_state(app_state, Some(Input::Character('H')), 40, 80);
+    app_state = update_state(app_state, Some(Input::KeyDown), 40, 80);
+    app_state = update_state(app_state, Some(Input::Character('\n')), 40, 80);
+
+    let lines = diff_lines(&app_state);
+    assert!(lines.iter().any(|l| l == "+two"));
+    assert!(!lines.iter().any(|l| l == "+three"));
+
+    // Enter again goes back to the list, keeping the selection.
+    app_state = update_state(app_state, Some(Input::Character('\n')), 40, 80);
+    let history = open_view!(app_state.screen, Screen::History);
+    assert!(history.diff.is_none());
+    assert_eq!(history.selected_commit().unwrap().message, "second");
+}
+
+#[test]
+fn test_tab_returns_to_main_screen() {
+    let repo = setup_pushed_history();
+    repo.create_file("a.txt", "changed\n");
+    repo.add_all();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    app_state = update_state(app_state, Some(Input::Character('H')), 40, 80);
+    app_state = update_state(app_state, Some(Input::Character('\n')), 40, 80);
+    assert!(open_view!(app_state.screen, Screen::History).diff.is_some());
+
+    // Keys are consumed by the history and do not reach the file list.
+    app_state = update_state(app_state, Some(Input::Character('u')), 40, 80);
+    assert_eq!(app_state.files.len(), 1);
+
+    app_state = update_state(app_state, Some(Input::Character('\t')), 40, 80);
+    assert!(!matches!(app_state.screen, Some(Screen::History(_))));
+    assert!(app_state.running);
+}
+
+#[test]
+fn test_cursor_stays_within_commits() {
+    let repo = setup_pushed_history();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    app_state = update_state(app_state, Some(Input::Character('H')), 40, 80);
+    app_state = update_state(app_state, Some(Input::KeyUp), 40, 80);
+    assert_eq!(open_view!(app_state.screen, Screen::History).cursor, 0);
+    for _ in 0..5 {
+        app_state = update_state(app_state, Some(Input::KeyDown), 40, 80);
+    }
+    assert_eq!(open_view!(app_state.screen, Screen::History).cursor, 2);
+}
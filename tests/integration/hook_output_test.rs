@@ -0,0 +1,102 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{log_subjects, open_view, press, send};
+use git_full_commit::app_state::{AppState, Modal};
+use git_full_commit::git;
+use git_full_commit::ui::update::update_state_with_alt;
+use pancurses::Input;
+
+fn install_hook(repo: &TestRepo, name: &str, script: &str) {
+    use std::os::unix::fs::PermissionsExt;
+    let path = repo.path.join(".git/hooks").join(name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, format!("#!/bin/sh\n{script}\n")).unwrap();
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+/// A staged change with `Fix typo` typed as its message, and a
+/// `pre-commit` hook that refuses it, then committed with Alt+Enter.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "two\n");
+    repo.add_all();
+    install_hook(
+        &repo,
+        "pre-commit",
+        "echo 'lint: a.txt:1 trailing whitespace' >&2\nexit 1",
+    );
+
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    let state = press(send(state, Input::KeyDown), "Fix typo");
+    let state = update_state_with_alt(state, Some(Input::Character('\n')), 40, 80);
+    (repo, state)
+}
+
+#[test]
+fn test_failing_hook_output_is_shown() {
+    let (repo, state) = setup();
+    let output = open_view!(state.modal, Modal::HookOutput);
+    assert_eq!(output.failure.hooks, vec!["pre-commit".to_string()]);
+    assert!(
+        output
+            .failure
+            .output
+            .contains("lint: a.txt:1 trailing whitespace"),
+        "{}",
+        output.failure.output
+    );
+    assert_eq!(log_subjects(&repo), "initial\n");
+
+    // Aborting keeps the message to fix things and try again
+    let state = press(state, "q");
+    assert!(!matches!(state.modal, Some(Modal::HookOutput(_))));
+    assert_eq!(state.main_screen.commit_message, "Fix typo");
+    assert_eq!(
+        state.message_text(),
+        Some("Commit aborted, the message is kept")
+    );
+}
+
+#[test]
+fn test_retry_runs_the_hook_again() {
+    let (repo, state) = setup();
+    let state = press(state, "r");
+    assert!(matches!(state.modal, Some(Modal::HookOutput(_))));
+
+    install_hook(&repo, "pre-commit", "exit 0");
+    let state = press(state, "r");
+    assert!(!matches!(state.modal, Some(Modal::HookOutput(_))));
+    assert_eq!(log_subjects(&repo), "Fix typo\ninitial\n");
+}
+
+#[test]
+fn test_hooks_can_be_skipped() {
+    let (repo, state) = setup();
+    let state = press(state, "n");
+    assert!(!matches!(state.modal, Some(Modal::HookOutput(_))));
+    assert_eq!(log_subjects(&repo), "Fix typo\ninitial\n");
+}
+
+#[test]
+fn test_failing_commit_msg_hook_is_shown() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "two\n");
+    repo.add_all();
+    install_hook(
+        &repo,
+        "commit-msg",
+        "grep -q '^[A-Z]*-[0-9]' \"$1\" || { echo 'missing ticket id' >&2; exit 1; }",
+    );
+
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    let state = press(send(state, Input::KeyDown), "Fix typo");
+    let state = update_state_with_alt(state, Some(Input::Character('\n')), 40, 80);
+    let output = open_view!(state.modal, Modal::HookOutput);
+    assert_eq!(output.failure.hooks, vec!["commit-msg".to_string()]);
+    assert!(output.failure.output.contains("missing ticket id"));
+}
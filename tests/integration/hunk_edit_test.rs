@@ -0,0 +1,127 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::{AppState, FocusedPane};
+use git_full_commit::git;
+use std::fs;
+use std::process::Command;
+
+fn git_diff(repo: &TestRepo, cached: bool) -> String {
+    let mut command = Command::new("git");
+    command.arg("diff");
+    if cached {
+        command.arg("--cached");
+    }
+    let output = command.current_dir(&repo.path).output().unwrap();
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+/// Presses `E`, lets `edit` change the text in the editor, then returns to
+/// the app like the editor exited.
+fn edit_hunk(mut state: AppState, edit: impl FnOnce(&str) -> String) -> AppState {
+    state = press(state, "E");
+    let request = state.editor_request.take().expect("the editor opens");
+    let text = fs::read_to_string(&request.file_path).unwrap();
+    fs::write(&request.file_path, edit(&text)).unwrap();
+    state.finish_editor_request(request);
+    state
+}
+
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\ntwo\nthree\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "one\n2\nthree\nfour\n");
+
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new(repo.path.clone(), files);
+    state.focused_pane = FocusedPane::Unstaged;
+    state.unstaged_pane.cursor = 1;
+    (repo, state)
+}
+
+#[test]
+fn test_edited_hunk_is_staged() {
+    let (repo, state) = setup();
+
+    // Stage the new line only, keeping `two`
+    let state = edit_hunk(state, |text| {
+        text.replace("-two\n", " two\n").replace("+2\n", "")
+    });
+    assert_eq!(state.message_text(), None);
+    let staged = git_diff(&repo, true);
+    assert!(staged.contains("+four"), "{staged}");
+    assert!(!staged.contains("-two"), "{staged}");
+    assert!(git_diff(&repo, false).contains("+2"));
+    assert_eq!(
+        fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+        "one\n2\nthree\nfour\n"
+    );
+
+    // Undone like any other staging
+    press(state, "<");
+    assert_eq!(git_diff(&repo, true), "");
+}
+
+#[test]
+fn test_edited_staged_hunk_is_unstaged() {
+    let (repo, mut state) = setup();
+    repo.add_all();
+    state.refresh_diff(false);
+    state.focused_pane = FocusedPane::Main;
+    state.main_screen.file_cursor = 1;
+
+    // Unstage the new line only: the rules are flipped
+    let mut guide = String::new();
+    state = edit_hunk(state, |text| {
+        guide = text.to_string();
+        text.replace("-two\n", "").replace("+2\n", " 2\n")
+    });
+    assert!(guide.contains("# To remove '+' lines, make them ' ' lines (context).\n"));
+    assert_eq!(state.message_text(), None);
+    let staged = git_diff(&repo, true);
+    assert!(staged.contains("+2"), "{staged}");
+    assert!(!staged.contains("+four"), "{staged}");
+    assert!(git_diff(&repo, false).contains("+four"));
+}
+
+#[test]
+fn test_invalid_edits_change_nothing() {
+    let (repo, state) = setup();
+
+    let state = edit_hunk(state, |text| text.replace(" one\n", "one\n"));
+    assert_eq!(
+        state.message_text(),
+        Some("Edited hunk not applied: line 3 is not a diff line")
+    );
+    assert_eq!(git_diff(&repo, true), "");
+
+    // Context that isn't in the file
+    let state = edit_hunk(state, |text| text.replace(" one\n", " uno\n"));
+    assert_eq!(
+        state.message_text(),
+        Some("Edited hunk not applied: it does not apply to the index of a.txt")
+    );
+    assert_eq!(git_diff(&repo, true), "");
+
+    let mut state = edit_hunk(state, |text| {
+        text.lines()
+            .filter(|line| !line.starts_with('+') && !line.starts_with('-'))
+            .map(|line| format!("{line}\n"))
+            .collect()
+    });
+    assert_eq!(
+        state.message_text(),
+        Some("Edited hunk not applied: nothing is left to apply")
+    );
+
+    repo.create_file("new.txt", "new\n");
+    state.refresh_diff(false);
+    state.unstaged_pane.cursor = 3;
+    state = press(state, "E");
+    assert!(state.editor_request.is_none());
+    assert_eq!(
+        state.message_text(),
+        Some("Only hunks of modified files can be edited")
+    );
+}
@@ -0,0 +1,105 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::{AppState, FocusedPane};
+use git_full_commit::git;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+/// `a.txt` and `b.txt` with lines 1 to 20, of which 2 and 19 are changed,
+/// making two hunks each. Nothing is staged, the unstaged pane has the
+/// focus with `a.txt` selected.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    let lines = |changed: bool| -> String {
+        (1..=20)
+            .map(|n| match n {
+                2 | 19 if changed => format!("{n} changed\n"),
+                _ => format!("{n}\n"),
+            })
+            .collect()
+    };
+    for name in ["a.txt", "b.txt"] {
+        repo.create_file(name, &lines(false));
+    }
+    repo.add_all();
+    repo.commit("initial");
+    for name in ["a.txt", "b.txt"] {
+        repo.create_file(name, &lines(true));
+    }
+
+    let mut state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    state.focused_pane = FocusedPane::Unstaged;
+    while state.get_unstaged_file().is_none() {
+        state = update_state(state, Some(Input::KeyDown), 40, 80);
+    }
+    (repo, state)
+}
+
+fn selected(state: &AppState) -> String {
+    state.get_unstaged_file().unwrap().file_name.clone()
+}
+
+fn hunk_starts(state: &AppState) -> Vec<usize> {
+    let file = state.get_unstaged_file().unwrap();
+    file.hunks.iter().map(|hunk| hunk.start_line).collect()
+}
+
+#[test]
+fn test_jump_between_hunks() {
+    let (_repo, state) = setup();
+    let starts = hunk_starts(&state);
+    let state = press(state, "]");
+    assert!(state.unstaged_pane.is_diff_cursor_active);
+    assert_eq!(state.main_screen.line_cursor, starts[0]);
+    assert_eq!(state.message_text(), Some("Hunk 1 of 2"));
+
+    let state = press(state, "]");
+    assert_eq!(state.main_screen.line_cursor, starts[1]);
+    let state = press(state, "]");
+    assert_eq!(state.main_screen.line_cursor, starts[1]);
+    assert_eq!(
+        state.message_text(),
+        Some("No hunk below, } goes to the next file")
+    );
+
+    let state = press(state, "[");
+    assert_eq!(state.main_screen.line_cursor, starts[0]);
+    assert_eq!(state.message_text(), Some("Hunk 1 of 2"));
+}
+
+#[test]
+fn test_jump_between_files() {
+    let (_repo, state) = setup();
+    assert_eq!(selected(&state), "a.txt");
+    let state = press(state, "}");
+    assert_eq!(selected(&state), "b.txt");
+    assert_eq!(state.message_text(), Some("File 2 of 2: b.txt"));
+    // On the header of its first hunk
+    assert!(state.unstaged_pane.is_diff_cursor_active);
+    assert_eq!(state.main_screen.line_cursor, hunk_starts(&state)[0]);
+
+    let state = press(state, "}");
+    assert_eq!(state.message_text(), Some("No file below"));
+    let state = press(state, "{");
+    assert_eq!(selected(&state), "a.txt");
+}
+
+#[test]
+fn test_jump_between_the_files_of_a_commit() {
+    let (repo, state) = setup();
+    repo.add_all();
+    repo.commit("change both");
+    let mut state = state;
+    state.refresh_diff(true);
+    state.focused_pane = FocusedPane::Main;
+    // The latest commit, below the staged header and the message input
+    state.main_screen.file_cursor = 2;
+    state.update_selected_commit_diff();
+    let second_file = state.selected_commit_files[0].lines.len();
+
+    let state = press(state, "}");
+    assert_eq!(state.main_screen.line_cursor, second_file);
+    assert_eq!(state.message_text(), Some("File 2 of 2: b.txt"));
+    let state = press(state, "{");
+    assert_eq!(state.main_screen.line_cursor, 0);
+}
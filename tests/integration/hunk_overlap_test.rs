@@ -0,0 +1,47 @@
+use crate::git_test::common::{TestRepo, run_git};
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use git_full_commit::hunk_overlap::overlapping_hunks;
+
+/// `a.txt` with lines 1 to 20, lines 2 and 19 changed and staged.
+fn setup() -> TestRepo {
+    let repo = TestRepo::new();
+    let lines: String = (1..=20).map(|n| format!("{n}\n")).collect();
+    repo.create_file("a.txt", &lines);
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file(
+        "a.txt",
+        &lines
+            .replace("\n2\n", "\n2 staged\n")
+            .replace("19\n", "19 staged\n"),
+    );
+    run_git(&repo.path, &["add", "a.txt"]);
+    repo
+}
+
+fn overlapping_starts(repo: &TestRepo) -> Vec<usize> {
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    let unstaged = &state.unstaged_pane.unstaged_files[0];
+    overlapping_hunks(&state.files[0], unstaged)
+        .iter()
+        .map(|hunk| hunk.new_start)
+        .collect()
+}
+
+#[test]
+fn test_a_staged_hunk_edited_again_overlaps() {
+    let repo = setup();
+    let staged = std::fs::read_to_string(repo.path.join("a.txt")).unwrap();
+    repo.create_file("a.txt", &staged.replace("2 staged", "2 edited"));
+    // Only the hunk of line 2, starting at line 1 with its context
+    assert_eq!(overlapping_starts(&repo), [1]);
+}
+
+#[test]
+fn test_edits_elsewhere_do_not_overlap() {
+    let repo = setup();
+    let staged = std::fs::read_to_string(repo.path.join("a.txt")).unwrap();
+    repo.create_file("a.txt", &staged.replace("\n10\n", "\n10 edited\n"));
+    assert!(overlapping_starts(&repo).is_empty());
+}
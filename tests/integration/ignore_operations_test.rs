@@ -1,6 +1,7 @@
 use crate::git_test::common::TestRepo;
-use git_full_commit::app_state::{AppState, FocusedPane};
+use git_full_commit::app_state::{AppState, FocusedPane, Modal};
 use git_full_commit::git;
+use git_full_commit::ui::ignore_picker::{self, Choice};
 use git_full_commit::ui::update::update_state;
 use pancurses::Input;
 use std::fs;
@@ -19,6 +20,8 @@ fn test_ignore_staged_file() {
     app_state.main_screen.file_cursor = 1;
 
     app_state = update_state(app_state, Some(Input::Character('i')), 80, 80);
+    assert!(matches!(app_state.modal, Some(Modal::IgnorePicker(_))));
+    app_state = update_state(app_state, Some(Input::Character('\n')), 80, 80);
 
     let gitignore_content = fs::read_to_string(repo.path.join(".gitignore")).unwrap();
     assert!(gitignore_content.contains(file_to_ignore));
@@ -48,6 +51,8 @@ fn test_ignore_untracked_file() {
     app_state.unstaged_pane.cursor = 2;
 
     app_state = update_state(app_state, Some(Input::Character('i')), 80, 80);
+    assert!(matches!(app_state.modal, Some(Modal::IgnorePicker(_))));
+    app_state = update_state(app_state, Some(Input::Character('\n')), 80, 80);
 
     let gitignore_content = fs::read_to_string(repo.path.join(".gitignore")).unwrap();
     assert!(gitignore_content.contains(file_to_ignore));
@@ -58,3 +63,191 @@ fn test_ignore_untracked_file() {
 
     assert!(app_state.unstaged_pane.untracked_files.is_empty());
 }
+
+#[test]
+fn test_ignore_choices() {
+    let choice = |pattern: &str, description: &str| Choice {
+        pattern: pattern.to_string(),
+        description: description.to_string(),
+    };
+    assert_eq!(
+        ignore_picker::choices("build/out/app.log"),
+        vec![
+            choice("build/out/app.log", "this file only"),
+            choice("*.log", "every .log file"),
+            choice("build/out/", "everything in build/out"),
+        ]
+    );
+    assert_eq!(
+        ignore_picker::choices("Makefile"),
+        vec![choice("Makefile", "this file only")]
+    );
+}
+
+/// An untracked `logs/run.log` and `logs/old.log`, with the first one
+/// selected in the unstaged pane.
+fn select_untracked_log() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("README.md", "readme");
+    repo.add_all();
+    repo.commit("initial");
+    fs::create_dir_all(repo.path.join("logs")).unwrap();
+    repo.create_file("logs/old.log", "old");
+    repo.create_file("logs/run.log", "run");
+
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+    app_state = update_state(app_state, Some(Input::Character('\t')), 80, 80);
+    app_state.unstaged_pane.cursor = 2;
+    (repo, app_state)
+}
+
+#[test]
+fn test_ignore_by_extension() {
+    let (repo, mut app_state) = select_untracked_log();
+    app_state = update_state(app_state, Some(Input::Character('i')), 80, 80);
+    app_state = update_state(app_state, Some(Input::Character('2')), 80, 80);
+
+    assert!(!matches!(app_state.modal, Some(Modal::IgnorePicker(_))));
+    assert_eq!(
+        fs::read_to_string(repo.path.join(".gitignore")).unwrap(),
+        "*.log\n"
+    );
+    assert_eq!(app_state.message_text(), Some("Added *.log to .gitignore"));
+    assert!(app_state.unstaged_pane.untracked_files.is_empty());
+
+    // Undo takes the pattern out again, and the file it was written to
+    let app_state = update_state(app_state, Some(Input::Character('<')), 80, 80);
+    assert_eq!(app_state.unstaged_pane.untracked_files.len(), 2);
+    assert!(!repo.path.join(".gitignore").exists());
+    assert!(app_state.files.is_empty());
+}
+
+#[test]
+fn test_ignore_the_directory() {
+    let (repo, mut app_state) = select_untracked_log();
+    app_state = update_state(app_state, Some(Input::Character('i')), 80, 80);
+    app_state = update_state(app_state, Some(Input::Character('j')), 80, 80);
+    app_state = update_state(app_state, Some(Input::Character('j')), 80, 80);
+    app_state = update_state(app_state, Some(Input::Character('\n')), 80, 80);
+
+    assert_eq!(
+        fs::read_to_string(repo.path.join(".gitignore")).unwrap(),
+        "logs/\n"
+    );
+    assert!(app_state.unstaged_pane.untracked_files.is_empty());
+}
+
+#[test]
+fn test_ignore_can_be_cancelled() {
+    let (repo, mut app_state) = select_untracked_log();
+    app_state = update_state(app_state, Some(Input::Character('i')), 80, 80);
+    app_state = update_state(app_state, Some(Input::Character('\u{1b}')), 80, 80);
+
+    assert!(!matches!(app_state.modal, Some(Modal::IgnorePicker(_))));
+    assert!(!repo.path.join(".gitignore").exists());
+    assert_eq!(app_state.unstaged_pane.untracked_files.len(), 2);
+}
+
+#[test]
+fn test_ignore_in_the_local_exclude_file() {
+    let (repo, mut app_state) = select_untracked_log();
+    app_state = update_state(app_state, Some(Input::Character('i')), 80, 80);
+    app_state = update_state(app_state, Some(Input::Character('\t')), 80, 80);
+    app_state = update_state(app_state, Some(Input::Character('2')), 80, 80);
+
+    assert_eq!(
+        app_state.message_text(),
+        Some("Added *.log to .git/info/exclude")
+    );
+    let exclude = repo.path.join(".git/info/exclude");
+    assert!(fs::read_to_string(&exclude).unwrap().ends_with("\n*.log\n"));
+    assert!(!repo.path.join(".gitignore").exists());
+    assert!(app_state.unstaged_pane.untracked_files.is_empty());
+    // Nothing to commit
+    assert!(app_state.files.is_empty());
+
+    // Undo leaves the rest of the file, like git's own comments
+    let app_state = update_state(app_state, Some(Input::Character('<')), 80, 80);
+    let content = fs::read_to_string(&exclude).unwrap();
+    assert!(!content.contains("*.log"), "{content}");
+    assert!(content.starts_with('#'), "{content}");
+    assert_eq!(app_state.unstaged_pane.untracked_files.len(), 2);
+}
+
+#[test]
+fn test_ignore_in_the_global_excludes_file() {
+    let (repo, mut app_state) = select_untracked_log();
+    let global = repo.path.join("global-ignore");
+    git::run_git_command(
+        &repo.path,
+        &["config", "core.excludesFile", global.to_str().unwrap()],
+    )
+    .unwrap();
+    app_state = update_state(app_state, Some(Input::Character('i')), 80, 80);
+    app_state = update_state(app_state, Some(Input::Character('\t')), 80, 80);
+    app_state = update_state(app_state, Some(Input::Character('\t')), 80, 80);
+    app_state = update_state(app_state, Some(Input::Character('3')), 80, 80);
+
+    assert_eq!(
+        app_state.message_text(),
+        Some("Added logs/ to the global excludes file")
+    );
+    assert_eq!(fs::read_to_string(&global).unwrap(), "logs/\n");
+    assert!(!repo.path.join(".gitignore").exists());
+}
+
+#[test]
+fn test_undo_keeps_a_rule_that_was_already_there() {
+    let repo = TestRepo::new();
+    repo.create_file("app.log", "start");
+    repo.add_all();
+    repo.commit("initial");
+    let rules = "# logs\n*.log\n\n# editors\n*.swp\n";
+    repo.create_file(".gitignore", rules);
+    repo.add_all();
+    repo.commit("ignore logs");
+    repo.create_file("app.log", "start\nmore");
+
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+    app_state = update_state(app_state, Some(Input::Character('\t')), 80, 80);
+    app_state.unstaged_pane.cursor = 1;
+    app_state = update_state(app_state, Some(Input::Character('i')), 80, 80);
+    app_state = update_state(app_state, Some(Input::Character('2')), 80, 80);
+    assert_eq!(
+        fs::read_to_string(repo.path.join(".gitignore")).unwrap(),
+        format!("{rules}*.log\n")
+    );
+
+    let app_state = update_state(app_state, Some(Input::Character('<')), 80, 80);
+    assert_eq!(
+        fs::read_to_string(repo.path.join(".gitignore")).unwrap(),
+        rules
+    );
+    assert!(app_state.files.is_empty());
+    assert_eq!(app_state.unstaged_pane.unstaged_files.len(), 1);
+}
+
+#[test]
+fn test_undo_after_the_exclude_file_is_gone() {
+    let (repo, mut app_state) = select_untracked_log();
+    let global = repo.path.join("global-ignore");
+    git::run_git_command(
+        &repo.path,
+        &["config", "core.excludesFile", global.to_str().unwrap()],
+    )
+    .unwrap();
+    app_state = update_state(app_state, Some(Input::Character('i')), 80, 80);
+    app_state = update_state(app_state, Some(Input::Character('\t')), 80, 80);
+    app_state = update_state(app_state, Some(Input::Character('\t')), 80, 80);
+    app_state = update_state(app_state, Some(Input::Character('3')), 80, 80);
+    fs::remove_file(&global).unwrap();
+
+    let app_state = update_state(app_state, Some(Input::Character('<')), 80, 80);
+    let message = app_state.message_text().unwrap().to_string();
+    assert!(
+        message.starts_with("Failed to take logs/ out of the global excludes file: "),
+        "{message}"
+    );
+}
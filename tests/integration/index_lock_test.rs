@@ -0,0 +1,122 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{open_view, press};
+use git_full_commit::app_state::{AppState, Modal};
+use git_full_commit::command::StageFileCommand;
+use git_full_commit::git;
+use git_full_commit::ui::commit_view;
+use std::path::PathBuf;
+
+/// `a.txt` committed and changed again, with the index locked as if
+/// another git were at work.
+fn setup() -> (TestRepo, AppState, PathBuf) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "two\n");
+    let lock = repo.path.join(".git/index.lock");
+    std::fs::write(&lock, "").unwrap();
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    (repo, state, lock)
+}
+
+fn stage_a(state: &mut AppState) -> bool {
+    let command = Box::new(StageFileCommand::new(
+        state.repo_path.clone(),
+        "a.txt".to_string(),
+    ));
+    state.execute_and_guard(command)
+}
+
+#[test]
+fn test_the_action_waits_for_the_lock() {
+    let (repo, mut state, lock) = setup();
+    assert!(!stage_a(&mut state));
+    let wait = open_view!(state.modal, Modal::IndexLockWait);
+    assert!(wait.lines[0].starts_with("Another git process is using the index: "));
+    assert!(wait.lines[0].ends_with("index.lock exists."));
+    assert!(
+        wait.lines
+            .iter()
+            .any(|line| line.starts_with("It was made "))
+    );
+    assert_eq!(repo.get_status(), " M a.txt\n");
+
+    let mut state = press(state, "r");
+    assert_eq!(state.message_text(), Some("The index is still locked"));
+    assert!(matches!(state.modal, Some(Modal::IndexLockWait(_))));
+
+    std::fs::remove_file(&lock).unwrap();
+    assert!(state.poll_background());
+    assert!(!matches!(state.modal, Some(Modal::IndexLockWait(_))));
+    assert_eq!(repo.get_status(), "M  a.txt\n");
+    assert_eq!(
+        state.message_text(),
+        Some("The index is free again, the held action ran")
+    );
+}
+
+#[test]
+fn test_cancel_and_fresh_locks_are_not_removed() {
+    let (repo, mut state, lock) = setup();
+    stage_a(&mut state);
+    let state = press(state, "d");
+    // Just made, it may belong to a git that is starting
+    assert!(lock.exists());
+    assert!(!open_view!(state.modal, Modal::IndexLockWait).confirming_removal);
+
+    let mut state = press(state, "q");
+    assert!(!matches!(state.modal, Some(Modal::IndexLockWait(_))));
+    assert_eq!(
+        state.message_text(),
+        Some("Cancelled the action, the index is locked")
+    );
+    std::fs::remove_file(&lock).unwrap();
+    assert!(!state.poll_background());
+    assert_eq!(repo.get_status(), " M a.txt\n");
+}
+
+#[test]
+fn test_remove_a_stale_lock_after_confirming() {
+    let (repo, mut state, lock) = setup();
+    std::process::Command::new("touch")
+        .args(["-d", "1 hour ago"])
+        .arg(&lock)
+        .status()
+        .unwrap();
+    stage_a(&mut state);
+    assert_eq!(
+        open_view!(state.modal, Modal::IndexLockWait).lines[1],
+        "It was made 1 h ago."
+    );
+
+    let state = press(state, "d");
+    let message = state.message_text().unwrap().to_string();
+    assert!(message.starts_with("Remove "), "{message}");
+    assert!(message.ends_with("? y removes it, any other key keeps it"));
+    let state = press(state, "n");
+    assert_eq!(state.message_text(), Some("Kept the lock"));
+    assert!(lock.exists());
+
+    let state = press(state, "dy");
+    assert!(!lock.exists());
+    assert!(!matches!(state.modal, Some(Modal::IndexLockWait(_))));
+    assert!(state.message_text().unwrap().starts_with("Removed "));
+    assert_eq!(repo.get_status(), "M  a.txt\n");
+}
+
+#[test]
+fn test_a_commit_waits_for_the_lock() {
+    let (repo, mut state, lock) = setup();
+    std::fs::remove_file(&lock).unwrap();
+    stage_a(&mut state);
+    std::fs::write(&lock, "").unwrap();
+
+    commit_view::commit(&mut state, "second", false);
+    assert!(matches!(state.modal, Some(Modal::IndexLockWait(_))));
+    assert!(!repo.get_log(5).contains("second"));
+
+    std::fs::remove_file(&lock).unwrap();
+    state.poll_background();
+    assert!(repo.get_log(1).contains("second"));
+}
@@ -0,0 +1,92 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{open_view, press, staged_files};
+use git_full_commit::app_state::{AppState, FocusedPane, Screen};
+use git_full_commit::config::Config;
+use git_full_commit::git;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+/// `a.txt` staged and `b.txt` changed but not staged, with `content` as
+/// the config.
+fn setup(content: &str) -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.create_file("b.txt", "one\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "two\n");
+    repo.add_all();
+    repo.create_file("b.txt", "two\n");
+
+    let files = git::get_diff(repo.path.clone());
+    let state = AppState::new_with_config(repo.path.clone(), files, Config::parse(content));
+    (repo, state)
+}
+
+#[test]
+fn test_rebound_keys_act_in_both_panes() {
+    let (_repo, state) = setup("[keys]\nstage = \"S\"\nswitch_pane = \"W\"\nundo = \"Z\"\n");
+    assert_eq!(
+        state.config.key_bindings,
+        vec![
+            ("stage".to_string(), "S".to_string()),
+            ("switch_pane".to_string(), "W".to_string()),
+            ("undo".to_string(), "Z".to_string()),
+        ]
+    );
+
+    // Tab and u no longer do anything
+    let state = press(state, "\tu");
+    assert_eq!(state.focused_pane, FocusedPane::Main);
+    assert_eq!(staged_files(&state), vec!["a.txt"]);
+
+    let state = press(state, "W");
+    let state = update_state(state, Some(Input::KeyDown), 40, 80);
+    let state = press(state, "S");
+    assert_eq!(staged_files(&state), vec!["a.txt", "b.txt"]);
+
+    let state = press(state, "<");
+    assert_eq!(staged_files(&state), vec!["a.txt", "b.txt"]);
+    let state = press(state, "Z");
+    assert_eq!(staged_files(&state), vec!["a.txt"]);
+}
+
+#[test]
+fn test_rebound_keys_are_typed_as_is_in_the_message() {
+    let (_repo, state) = setup("[keys]\nstage = \"S\"\nquit = \"u\"\n");
+    let state = update_state(state, Some(Input::KeyDown), 40, 80);
+    let state = press(state, "uSq");
+    assert_eq!(state.main_screen.commit_message, "uSq");
+    assert!(state.running);
+}
+
+#[test]
+fn test_binding_problems_are_reported_at_startup_and_in_the_diagnostics() {
+    let (_repo, state) =
+        setup("[keys]\nstage = \"Space\"\nmove_down = \"Ctrl-v\"\nbogus = \"y\"\n");
+    assert_eq!(
+        state.message_text(),
+        Some("Key bindings: Unknown action bogus, and 1 more problem (see the diagnostics, D)")
+    );
+
+    let state = press(state, "D");
+    let lines = &open_view!(state.screen, Screen::Diagnostics).lines;
+    let keymap = lines
+        .iter()
+        .position(|line| line.starts_with("keymap:"))
+        .unwrap();
+    assert_eq!(
+        lines[keymap..keymap + 3],
+        [
+            "keymap:     2 actions rebound",
+            "            Unknown action bogus",
+            "            page_down has no key left, other actions are bound to its keys",
+        ]
+    );
+}
+
+#[test]
+fn test_valid_bindings_report_nothing() {
+    let (_repo, state) = setup("[keys]\nstage = \"@\"\n");
+    assert_eq!(state.message_text(), None);
+}
@@ -0,0 +1,108 @@
+use crate::git_test::common::TestRepo;
+use git_full_commit::app_state::{AppState, FocusedPane};
+use git_full_commit::config::Config;
+use git_full_commit::git;
+use git_full_commit::ui::main_screen::UnstagedListItem;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+use std::process::Command;
+
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("tracked.txt", "one\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("tracked.txt", "two\n");
+    repo.create_file("small.txt", "small\n");
+    repo.create_file("large.bin", &"x".repeat(2048));
+
+    let files = git::get_diff(repo.path.clone());
+    let config = Config::parse("max_file_size = \"1K\"\n");
+    let mut state = AppState::new_with_config(repo.path.clone(), files, config);
+    state.focused_pane = FocusedPane::Unstaged;
+    (repo, state)
+}
+
+fn status(repo: &TestRepo) -> String {
+    let output = Command::new("git")
+        .args(["status", "--porcelain"])
+        .current_dir(&repo.path)
+        .output()
+        .unwrap();
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn position(state: &AppState, is_item: impl Fn(&UnstagedListItem) -> bool) -> usize {
+    state
+        .unstaged_pane
+        .list_items
+        .iter()
+        .position(is_item)
+        .unwrap()
+}
+
+fn untracked_file(name: &str) -> impl Fn(&UnstagedListItem) -> bool + '_ {
+    move |item| matches!(item, UnstagedListItem::UntrackedFile(file) if file == name)
+}
+
+#[test]
+fn test_max_file_size_config() {
+    assert_eq!(
+        Config::parse("max_file_size = \"100M\"\n").max_file_size,
+        Some(100 * 1024 * 1024)
+    );
+    assert_eq!(
+        Config::parse("max_file_size = \"lots\"\n").max_file_size,
+        None
+    );
+}
+
+#[test]
+fn test_large_files_are_listed_in_their_own_section() {
+    let (_repo, state) = setup();
+    assert_eq!(
+        state.unstaged_pane.large_files,
+        vec![("large.bin".to_string(), 2048)]
+    );
+    let header = position(&state, |item| {
+        matches!(item, UnstagedListItem::LargeFilesHeader)
+    });
+    assert_eq!(position(&state, untracked_file("large.bin")), header + 1);
+    assert_eq!(state.unstaged_pane.list_items.len(), header + 2);
+    assert!(position(&state, untracked_file("small.txt")) < header);
+}
+
+#[test]
+fn test_stage_all_skips_large_files() {
+    let (repo, state) = setup();
+    let _ = update_state(state, Some(Input::Character('R')), 40, 80);
+    assert_eq!(
+        status(&repo),
+        "A  small.txt\nM  tracked.txt\n?? large.bin\n"
+    );
+}
+
+#[test]
+fn test_staging_the_untracked_header_skips_large_files() {
+    let (repo, mut state) = setup();
+    state.unstaged_pane.cursor = position(&state, |item| {
+        matches!(item, UnstagedListItem::UntrackedFilesHeader)
+    });
+    let _ = update_state(state, Some(Input::Character('u')), 40, 80);
+    assert_eq!(
+        status(&repo),
+        "A  small.txt\n M tracked.txt\n?? large.bin\n"
+    );
+}
+
+#[test]
+fn test_large_file_can_be_staged_anyway() {
+    let (repo, mut state) = setup();
+    state.unstaged_pane.cursor = position(&state, untracked_file("large.bin"));
+    let state = update_state(state, Some(Input::Character('u')), 40, 80);
+    assert_eq!(
+        status(&repo),
+        "A  large.bin\n M tracked.txt\n?? small.txt\n"
+    );
+    assert!(state.unstaged_pane.large_files.is_empty());
+}
@@ -1,7 +1,7 @@
 use crate::git_test::common::TestRepo;
-use git_full_commit::app_state::{AppState, FocusedPane};
+use git_full_commit::app_state::{AppState, FileListOrder, FocusedPane};
 use git_full_commit::git;
-use git_full_commit::ui::main_screen::UnstagedListItem;
+use git_full_commit::ui::main_screen::{ListItem, UnstagedListItem};
 use git_full_commit::ui::update::update_state;
 use pancurses::Input;
 
@@ -138,3 +138,60 @@ fn test_main_screen_no_untracked_files() {
             .any(|item| matches!(item, UnstagedListItem::UntrackedFilesHeader))
     );
 }
+
+fn staged_file_names(app_state: &AppState) -> Vec<String> {
+    app_state
+        .main_screen
+        .list_items
+        .iter()
+        .filter_map(|item| match item {
+            ListItem::File(f) => Some(f.file_name.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_toggle_staged_file_tree_order() {
+    let repo = TestRepo::new();
+    std::fs::create_dir_all(repo.path.join("a/c")).unwrap();
+    repo.create_file("z.txt", "z");
+    repo.create_file("b.txt", "b");
+    repo.create_file("a/b.txt", "ab");
+    repo.create_file("a/c/d.txt", "acd");
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+    assert_eq!(
+        staged_file_names(&app_state),
+        vec!["a/b.txt", "a/c/d.txt", "b.txt", "z.txt"]
+    );
+
+    // Select b.txt so we can check the cursor follows the file.
+    app_state.main_screen.file_cursor = 3;
+
+    app_state = update_state(app_state, Some(Input::Character('o')), 80, 80);
+    assert_eq!(app_state.main_screen.file_list_order, FileListOrder::Tree);
+    assert_eq!(
+        staged_file_names(&app_state),
+        vec!["a/c/d.txt", "a/b.txt", "b.txt", "z.txt"]
+    );
+    assert_eq!(app_state.current_main_file().unwrap().file_name, "b.txt");
+
+    // The order survives a refresh triggered by an unstage operation.
+    app_state.main_screen.file_cursor = 4;
+    app_state = update_state(app_state, Some(Input::Character('u')), 80, 80);
+    assert_eq!(
+        staged_file_names(&app_state),
+        vec!["a/c/d.txt", "a/b.txt", "b.txt"]
+    );
+
+    app_state.main_screen.file_cursor = 1;
+    app_state = update_state(app_state, Some(Input::Character('o')), 80, 80);
+    assert_eq!(app_state.main_screen.file_list_order, FileListOrder::Diff);
+    assert_eq!(
+        staged_file_names(&app_state),
+        vec!["a/b.txt", "a/c/d.txt", "b.txt"]
+    );
+}
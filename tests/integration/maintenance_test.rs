@@ -0,0 +1,177 @@
+use crate::git_test::common::{TestRepo, run_git};
+use crate::integration::common::{open_view, press};
+use git_full_commit::app_state::{AppState, Screen};
+use git_full_commit::git::{self, StatusCaches};
+use git_full_commit::maintenance::LARGE_INDEX_ENTRIES;
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn commands(state: &AppState) -> Vec<String> {
+    let panel = open_view!(state.screen, Screen::Maintenance);
+    panel
+        .suggestions
+        .iter()
+        .map(|suggestion| suggestion.command())
+        .collect()
+}
+
+fn wait_for_maintenance(state: &mut AppState) {
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while state.maintenance_running.is_some() {
+        assert!(Instant::now() < deadline, "the maintenance did not finish");
+        state.poll_background();
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// A few commits, their objects left loose, with `gc.auto` low enough
+/// for them to be too many.
+fn loose_objects_repo() -> TestRepo {
+    let repo = TestRepo::new();
+    for n in 0..3 {
+        repo.create_file("a.txt", &format!("{n}\n"));
+        repo.add_all();
+        repo.commit(&format!("commit {n}"));
+    }
+    run_git(&repo.path, &["config", "gc.auto", "3"]);
+    repo
+}
+
+#[test]
+fn test_loose_objects_are_told_about_at_startup() {
+    let repo = loose_objects_repo();
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    let message = state.message_text().unwrap().to_string();
+    assert!(message.starts_with("Too many loose objects: "), "{message}");
+    assert!(
+        message.ends_with(
+            ", gc.auto packs them past 3. git maintenance run fixes it (Ctrl-g shows the maintenance)"
+        ),
+        "{message}"
+    );
+}
+
+#[test]
+fn test_run_the_suggested_command() {
+    let repo = loose_objects_repo();
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    let mut state = press(state, "\u{7}");
+    assert_eq!(commands(&state), ["git maintenance run"]);
+
+    state = press(state, "\n");
+    assert_eq!(state.message_text(), Some("Running git maintenance run…"));
+    wait_for_maintenance(&mut state);
+    assert_eq!(state.message_text(), Some("Ran git maintenance run"));
+    assert_eq!(git::count_objects(&repo.path).unwrap().loose, 0);
+    assert!(commands(&state).is_empty());
+
+    let state = press(state, "q");
+    assert!(!matches!(state.screen, Some(Screen::Maintenance(_))));
+}
+
+/// A repository with as many files as make it large.
+fn large_repo() -> TestRepo {
+    let repo = TestRepo::new();
+    for n in 0..LARGE_INDEX_ENTRIES {
+        repo.create_file(&format!("f{n}"), "x\n");
+    }
+    repo.add_all();
+    repo.commit("initial");
+    repo
+}
+
+#[test]
+fn test_large_index_is_moved_to_version_4() {
+    let repo = large_repo();
+    run_git(&repo.path, &["update-index", "--index-version", "2"]);
+    assert_eq!(
+        git::read_index_header(&repo.path),
+        Some((2, LARGE_INDEX_ENTRIES))
+    );
+
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    let mut state = press(state, "\u{7}");
+    assert_eq!(
+        commands(&state).first().map(String::as_str),
+        Some("git update-index --index-version 4")
+    );
+    state = press(state, "\n");
+    wait_for_maintenance(&mut state);
+    assert_eq!(
+        git::read_index_header(&repo.path),
+        Some((4, LARGE_INDEX_ENTRIES))
+    );
+}
+
+#[test]
+fn test_nothing_is_suggested_for_a_small_repository() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "a\n");
+    repo.add_all();
+    repo.commit("initial");
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    assert_eq!(state.message_text(), None);
+    let state = press(state, "\u{7}\n");
+    assert!(commands(&state).is_empty());
+    assert!(state.maintenance_running.is_none());
+}
+
+#[test]
+fn test_enable_the_untracked_cache_and_time_it() {
+    let repo = large_repo();
+    run_git(&repo.path, &["update-index", "--index-version", "4"]);
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    assert!(!state.status_caches.any());
+    let mut state = press(state, "\u{7}");
+    let command = commands(&state)[0].clone();
+    // Linux has no fsmonitor daemon, other systems may
+    assert!(
+        command.ends_with("git config core.untrackedCache true"),
+        "{command}"
+    );
+
+    state = press(state, "\n");
+    wait_for_maintenance(&mut state);
+    let message = state.message_text().unwrap().to_string();
+    assert!(
+        message.starts_with(&format!("Ran {command}: reading the working tree took ")),
+        "{message}"
+    );
+    assert!(message.ends_with(" ms"), "{message}");
+    assert!(state.status_caches.untracked_cache);
+    assert!(commands(&state).is_empty());
+
+    // Untracked files are now read from git status
+    repo.create_file("new file.txt", "new\n");
+    let mut state = press(state, "q");
+    state.refresh_diff(false);
+    assert_eq!(state.unstaged_pane.untracked_files, ["new file.txt"]);
+}
+
+#[test]
+fn test_untracked_files_read_from_status_are_the_same() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "a\n");
+    repo.add_all();
+    repo.commit("initial");
+    std::fs::create_dir_all(repo.path.join("dir/sub")).unwrap();
+    repo.create_file("dir/sub/b c.txt", "b\n");
+    repo.create_file("d.txt", "d\n");
+    repo.create_file(".gitignore", "*.log\n");
+    repo.create_file("e.log", "e\n");
+    repo.create_file("a.txt", "changed\n");
+
+    let listed = git::get_untracked_files(&repo.path).unwrap();
+    assert_eq!(listed, [".gitignore", "d.txt", "dir/sub/b c.txt"]);
+    let caches = StatusCaches {
+        fsmonitor: false,
+        untracked_cache: true,
+    };
+    assert_eq!(
+        git::list_untracked_files(&repo.path, caches).unwrap(),
+        listed
+    );
+
+    run_git(&repo.path, &["config", "core.untrackedCache", "true"]);
+    assert_eq!(StatusCaches::read(&repo.path), caches);
+}
@@ -0,0 +1,69 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use std::fs;
+
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "hello");
+    repo.add_all();
+    git::run_git_command(&repo.path, &["config", "core.editor", "my-editor --wait"]).unwrap();
+
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new(repo.path.clone(), files);
+    // [Header, File, Input]
+    state.main_screen.file_cursor = 2;
+    (repo, state)
+}
+
+#[test]
+fn test_message_is_edited_in_the_git_editor() {
+    let (repo, state) = setup();
+    let mut state = press(state, "draft\u{18}");
+
+    let request = state.editor_request.take().expect("the editor opens");
+    let pending = request.edit_message.as_ref().unwrap();
+    // core.editor, unless GIT_EDITOR and the like override it.
+    let editor = git::run_git_command(&repo.path, &["var", "GIT_EDITOR"]).unwrap();
+    assert_eq!(pending.editor, editor.trim());
+    assert!(request.file_path.ends_with("COMMIT_EDITMSG"));
+    let text = fs::read_to_string(&request.file_path).unwrap();
+    assert!(text.starts_with("draft\n\n# Edit the commit message"));
+
+    fs::write(
+        &request.file_path,
+        "\nfeat: edited\n\nThe body.\n# a comment\n\n",
+    )
+    .unwrap();
+    state.finish_editor_request(request);
+
+    assert_eq!(
+        state.main_screen.commit_message,
+        "feat: edited\n\nThe body."
+    );
+    assert_eq!(state.main_screen.commit_cursor, 0);
+    // Still in the input, so typing goes on there.
+    let state = press(state, "x");
+    assert_eq!(
+        state.main_screen.commit_message,
+        "xfeat: edited\n\nThe body."
+    );
+}
+
+#[test]
+fn test_failed_editor_keeps_the_message() {
+    let (_repo, state) = setup();
+    let mut state = press(state, "draft\u{18}");
+
+    let mut request = state.editor_request.take().unwrap();
+    fs::write(&request.file_path, "half written").unwrap();
+    request.edit_message.as_mut().unwrap().error = Some("exit status: 1".to_string());
+    state.finish_editor_request(request);
+
+    assert_eq!(state.main_screen.commit_message, "draft");
+    assert_eq!(
+        state.message_text(),
+        Some("Editor failed, the message is kept: exit status: 1")
+    );
+}
@@ -0,0 +1,110 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{press, select_commit_input};
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use git_full_commit::ui::update::update_state_with_alt;
+use pancurses::Input;
+
+const CTRL_P: char = '\u{10}';
+const CTRL_N: char = '\u{e}';
+
+fn commit(state: AppState) -> AppState {
+    update_state_with_alt(state, Some(Input::Character('\n')), 40, 80)
+}
+
+/// Commits two messages through the tool, leaving a third change staged.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "a\n");
+    repo.create_file("b.txt", "b\n");
+    repo.create_file("c.txt", "c\n");
+    git::run_git_command(&repo.path, &["add", "a.txt"]).unwrap();
+
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new(repo.path.clone(), files);
+    select_commit_input(&mut state);
+    let mut state = commit(press(state, "Add a\n\nThe first file"));
+
+    // The rest is staged after a commit, so unstage c.txt again
+    git::run_git_command(&repo.path, &["reset", "-q", "--", "c.txt"]).unwrap();
+    state.refresh_diff(false);
+    select_commit_input(&mut state);
+    let mut state = commit(press(state, "Add b"));
+    select_commit_input(&mut state);
+    (repo, state)
+}
+
+#[test]
+fn test_ctrl_p_and_ctrl_n_go_through_earlier_messages() {
+    let (_repo, state) = setup();
+    let state = press(state, "wip");
+
+    let message = |state: &AppState| state.main_screen.commit_message.clone();
+    let state = press(state, &CTRL_P.to_string());
+    assert_eq!(message(&state), "Add b");
+    assert_eq!(
+        state.message_text(),
+        Some("Message 1/2 from history (Ctrl-P: older, Ctrl-N: newer)")
+    );
+    assert!(state.is_in_input_mode());
+
+    let state = press(state, &CTRL_P.to_string());
+    assert_eq!(message(&state), "Add a\n\nThe first file");
+    // The cursor is at the end of the subject
+    assert_eq!(state.main_screen.commit_cursor, 5);
+
+    // Nothing older
+    let state = press(state, &CTRL_P.to_string());
+    assert_eq!(message(&state), "Add a\n\nThe first file");
+
+    let state = press(state, &CTRL_N.to_string());
+    assert_eq!(message(&state), "Add b");
+    let state = press(state, &CTRL_N.to_string());
+    assert_eq!(message(&state), "wip");
+    assert!(state.main_screen.history_recall.is_none());
+}
+
+#[test]
+fn test_editing_a_recalled_message_starts_over() {
+    let (repo, state) = setup();
+    let state = press(state, &format!("{CTRL_P}{CTRL_P}"));
+    let state = press(state, " again");
+    assert_eq!(
+        state.main_screen.commit_message,
+        "Add a again\n\nThe first file"
+    );
+
+    // The edited message is the draft to come back to
+    let state = press(state, &CTRL_P.to_string());
+    assert_eq!(state.main_screen.commit_message, "Add b");
+    let state = press(state, &CTRL_N.to_string());
+    assert_eq!(
+        state.main_screen.commit_message,
+        "Add a again\n\nThe first file"
+    );
+
+    // Committing it puts it first
+    let mut state = commit(state);
+    let subject = git::run_git_command(&repo.path, &["log", "-1", "--format=%s"]).unwrap();
+    assert_eq!(subject, "Add a again\n");
+    select_commit_input(&mut state);
+    let state = press(state, &CTRL_P.to_string());
+    assert_eq!(
+        state.main_screen.commit_message,
+        "Add a again\n\nThe first file"
+    );
+}
+
+#[test]
+fn test_no_history_yet() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "a\n");
+    repo.add_all();
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new(repo.path.clone(), files);
+    select_commit_input(&mut state);
+
+    let state = press(state, &CTRL_P.to_string());
+    assert_eq!(state.main_screen.commit_message, "");
+    assert_eq!(state.message_text(), Some("No earlier commit messages"));
+}
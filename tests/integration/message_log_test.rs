@@ -0,0 +1,94 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press_remembering;
+use git_full_commit::app_state::{AppState, Screen};
+use git_full_commit::command::StageFileCommand;
+use git_full_commit::git;
+use git_full_commit::message_log::Severity;
+use std::fs;
+
+/// `a.txt` committed and changed again, with nothing staged.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "two\n");
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    (repo, state)
+}
+
+fn logged(state: &AppState) -> Vec<(Severity, &str)> {
+    state
+        .message_log
+        .entries()
+        .iter()
+        .map(|entry| (entry.severity, entry.text.as_str()))
+        .collect()
+}
+
+#[test]
+fn test_messages_are_logged_with_their_severity() {
+    let (_repo, state) = setup();
+    let state = press_remembering(state, "K");
+    assert_eq!(state.message_severity(), Some(Severity::Warning));
+    let state = press_remembering(state, "M");
+    assert_eq!(state.files.len(), 1);
+    let staged = state.message_text().unwrap().to_string();
+    assert_eq!(state.message_severity(), Some(Severity::Success));
+
+    assert_eq!(
+        logged(&state),
+        [
+            (Severity::Warning, "No deletions to stage"),
+            (Severity::Success, staged.as_str())
+        ]
+    );
+}
+
+#[test]
+fn test_open_and_close_the_message_log() {
+    let (_repo, state) = setup();
+    let state = press_remembering(state, "l");
+    assert!(matches!(state.screen, Some(Screen::MessageLog(_))));
+    let state = press_remembering(state, "kq");
+    assert!(!matches!(state.screen, Some(Screen::MessageLog(_))));
+}
+
+#[test]
+fn test_a_failing_command_tells_why_instead_of_crashing() {
+    let (repo, mut state) = setup();
+    let command = StageFileCommand::new(repo.path.clone(), "missing.txt".to_string());
+    assert!(!state.execute_and_refresh(Box::new(command)));
+    let message = state.message_text().unwrap().to_string();
+    assert!(
+        message.starts_with("Failed to stage missing.txt: fatal: "),
+        "{message}"
+    );
+    assert_eq!(state.message_severity(), Some(Severity::Error));
+    assert!(state.command_history.undo_stack.is_empty());
+
+    // Undoing while another git holds the index
+    let state = press_remembering(state, "M");
+    assert_eq!(state.files.len(), 1);
+    fs::write(repo.path.join(".git/index.lock"), "").unwrap();
+    let state = press_remembering(state, "<");
+    let message = state.message_text().unwrap().to_string();
+    assert!(message.starts_with("Failed to "), "{message}");
+    assert!(message.contains("index.lock"), "{message}");
+    assert_eq!(state.message_severity(), Some(Severity::Error));
+}
+
+#[test]
+fn test_severity_does_not_come_from_the_file_name() {
+    let repo = TestRepo::new();
+    repo.create_file("error.log", "one\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("error.log", "two\n");
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+
+    let state = press_remembering(state, ":add error.log\n");
+    let message = state.message_text().unwrap().to_string();
+    assert_eq!(message, "Staged 1 file matching error.log");
+    assert_eq!(state.message_severity(), Some(Severity::Success));
+}
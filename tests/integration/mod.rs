@@ -1,15 +1,100 @@
+pub mod alert_test;
+pub mod apply_patch_file_test;
+pub mod binary_diff_test;
+pub mod blame_summary_test;
+pub mod bookmarks_test;
+pub mod branch_status_test;
+pub mod change_author_test;
+pub mod command_prompt_test;
+pub mod commit_details_test;
+pub mod commit_folds_test;
+pub mod commit_hash_test;
 pub mod commit_input_view_test;
+pub mod commit_lint_test;
+pub mod commit_overlap_test;
+pub mod commit_scope_test;
+pub mod commit_template_test;
 pub mod common;
+pub mod cursor_anchor_test;
+pub mod deleted_file_test;
+pub mod diagnostics_view_test;
+pub mod diff_context_test;
+pub mod diff_filter_test;
 pub mod diff_jump_test;
+pub mod diff_search_test;
 pub mod diff_view_test;
+pub mod discard_bin_test;
 pub mod discard_operations_test;
+pub mod drop_commit_test;
 mod edit_commit_message_test;
+pub mod edit_stage_test;
+pub mod editor_mode_test;
+pub mod editor_return_test;
+pub mod empty_commit_test;
+pub mod empty_state_test;
+pub mod executable_bit_test;
+pub mod explain_test;
+pub mod final_review_test;
 pub mod fixup_commit_test;
+pub mod fixup_hunk_test;
+pub mod fixup_preview_test;
+pub mod format_staged_test;
+pub mod full_file_view_test;
+pub mod help_test;
+pub mod history_test;
+pub mod hook_output_test;
+pub mod hunk_edit_test;
+pub mod hunk_jump_test;
+pub mod hunk_overlap_test;
 pub mod ignore_operations_test;
+pub mod index_lock_test;
+pub mod keymap_test;
+pub mod large_files_test;
 pub mod main_screen_test;
+pub mod maintenance_test;
+pub mod message_editor_test;
+pub mod message_history_test;
+pub mod message_log_test;
+pub mod narrow_layout_test;
+pub mod never_commit_test;
+pub mod new_file_test;
+pub mod notification_test;
+pub mod other_side_test;
+pub mod palette_test;
 pub mod pane_switching_test;
+pub mod patch_export_test;
+pub mod patch_headers_test;
+pub mod patch_preview_test;
+pub mod path_abbreviation_test;
 pub mod performance_test;
+pub mod plan_preview_test;
+pub mod push_test;
+pub mod rebase_plan_test;
+pub mod rebase_recovery_test;
+pub mod recipes_test;
+pub mod remote_sync_test;
+pub mod rename_groups_test;
+pub mod rename_operations_test;
 pub mod reorder_commits_test;
+pub mod repo_init_test;
+pub mod restore_picker_test;
+pub mod reword_preview_test;
+pub mod screen_title_test;
+pub mod split_commit_test;
+pub mod stage_by_status_test;
 pub mod stage_operations_test;
+pub mod stage_pathspec_test;
+pub mod staged_check_test;
+pub mod staged_test_test;
+pub mod staged_tree_test;
+pub mod stashes_test;
+pub mod stat_panel_test;
+pub mod status_filter_test;
+pub mod textconv_test;
+pub mod tutorial_test;
 pub mod undo_redo_test;
+pub mod unified_list_test;
 pub mod unstage_operations_test;
+pub mod whitespace_test;
+pub mod word_diff_test;
+pub mod worktrees_test;
@@ -0,0 +1,149 @@
+use crate::git_test::common::TestRepo;
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use git_full_commit::ui::commit_view::{compute_scroll_for_prefix, layout_editor_line};
+use git_full_commit::ui::layout::{self, NARROW_WIDTH};
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+use unicode_width::UnicodeWidthStr;
+
+const PREFIX: &str = " ○ ";
+const WIDTHS: std::ops::RangeInclusive<i32> = 20..=60;
+
+#[test]
+fn test_file_rows_fit_at_every_width() {
+    let name = "src/very/deeply/nested/module/directory/structure/file_name.rs";
+    for max_x in WIDTHS {
+        let row = layout::file_row('M', name, None, max_x);
+        assert!(row.width() <= max_x as usize, "width {max_x}: {row:?}");
+        assert!(row.name.ends_with(".rs"), "width {max_x}: {row:?}");
+        assert!(row.name.starts_with("src/"), "width {max_x}: {row:?}");
+        assert_eq!(row.status.is_none(), (max_x as usize) < NARROW_WIDTH);
+    }
+}
+
+#[test]
+fn test_short_file_rows_are_unchanged() {
+    for max_x in WIDTHS {
+        let row = layout::file_row('A', "a.txt", None, max_x);
+        assert_eq!(row.name, "a.txt");
+    }
+}
+
+#[test]
+fn test_renamed_rows_show_only_new_name_when_narrow() {
+    for max_x in WIDTHS {
+        let row = layout::file_row('R', "new.txt", Some("old.txt"), max_x);
+        assert!(row.width() <= max_x as usize);
+        if (max_x as usize) < NARROW_WIDTH {
+            assert_eq!(row.name, "new.txt");
+        } else {
+            assert_eq!(row.name, "old.txt -> new.txt");
+        }
+    }
+}
+
+#[test]
+fn test_untracked_rows_keep_their_indent_when_wide() {
+    let row = layout::untracked_file_row("a.txt", 80);
+    assert_eq!(row.indent, "    ");
+    assert_eq!(row.status, Some('?'));
+    let row = layout::untracked_file_row("a.txt", NARROW_WIDTH as i32 - 1);
+    assert_eq!(row.indent, " ");
+    assert_eq!(row.status, None);
+}
+
+#[test]
+fn test_middle_ellipsis_handles_wide_characters() {
+    let name = "ドキュメント/とても長いディレクトリ名/ファイル.md";
+    for max_width in 1..=name.width() {
+        let shortened = layout::middle_ellipsis(name, max_width);
+        assert!(shortened.width() <= max_width, "{max_width}: {shortened}");
+    }
+    assert_eq!(layout::middle_ellipsis(name, name.width()), name);
+}
+
+#[test]
+fn test_editor_caret_stays_on_screen_at_every_width() {
+    let text = "Refactor the parser so that error messages point at the right token";
+    let char_count = text.chars().count();
+    for max_x in WIDTHS {
+        for cursor in 0..=char_count {
+            let (offset, extra) = compute_scroll_for_prefix(text, cursor, max_x, PREFIX);
+            let (prefix, shown, caret_x) =
+                layout_editor_line(text, cursor, max_x, PREFIX, offset, extra);
+            assert!(caret_x < max_x as usize, "width {max_x}, cursor {cursor}");
+            assert!(prefix.width() + shown.width() <= max_x as usize);
+            if cursor > 0 {
+                // The character before the caret is drawn right next to it.
+                let before: String = text.chars().nth(cursor - 1).into_iter().collect();
+                let caret_in_text = caret_x - prefix.width();
+                let visible_before = layout::truncate_to_width(&shown, caret_in_text);
+                assert!(
+                    visible_before.ends_with(&before),
+                    "width {max_x}, cursor {cursor}: {shown:?}"
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_editor_recovers_from_scroll_of_wider_terminal() {
+    let text = "a commit message that is long enough to scroll";
+    let cursor = text.chars().count();
+    let (offset, extra) = compute_scroll_for_prefix(text, cursor, 60, PREFIX);
+    assert_eq!(offset, 0);
+
+    for max_x in 20..60 {
+        let (prefix, shown, caret_x) =
+            layout_editor_line(text, cursor, max_x, PREFIX, offset, extra);
+        assert!(caret_x < max_x as usize, "width {max_x}");
+        assert!(
+            shown.ends_with("scroll"),
+            "width {max_x}: {prefix:?} {shown:?}"
+        );
+    }
+}
+
+#[test]
+fn test_editor_prefix_is_cut_on_tiny_terminals() {
+    let (prefix, _, caret_x) = layout_editor_line("abc", 3, 2, " Rename to: ", 0, false);
+    assert_eq!(prefix, " ");
+    assert_eq!(caret_x, 1);
+}
+
+#[test]
+fn test_typing_in_narrow_terminal_keeps_caret_visible() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "hello");
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+    app_state.main_screen.file_cursor = 2;
+    app_state = update_state(app_state, Some(Input::Character('\n')), 40, 20);
+
+    for ch in "fix: keep the caret visible in tiny terminals".chars() {
+        app_state = update_state(app_state, Some(Input::Character(ch)), 40, 20);
+        let (_, shown, caret_x) = layout_editor_line(
+            &app_state.main_screen.commit_message,
+            app_state.main_screen.commit_cursor,
+            20,
+            PREFIX,
+            app_state.main_screen.commit_scroll_offset,
+            app_state.main_screen.commit_scroll_extra_space,
+        );
+        assert!(caret_x < 20);
+        assert!(shown.ends_with(ch));
+    }
+}
+
+#[test]
+fn test_centered_x_is_never_negative() {
+    for max_x in WIDTHS {
+        let x = layout::centered_x(80, max_x);
+        assert_eq!(x, 0);
+    }
+    assert_eq!(layout::centered_x(10, 30), 10);
+}
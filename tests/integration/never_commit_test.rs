@@ -0,0 +1,165 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{press, send, staged_files};
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use git_full_commit::never_commit::{self, NeverCommit, Rule};
+use git_full_commit::ui::main_screen::UnstagedListItem;
+use pancurses::Input;
+use std::path::PathBuf;
+
+/// Removes the rules saved for a test repository, which live outside it.
+struct Rules(PathBuf);
+
+impl Rules {
+    fn of(repo: &TestRepo) -> Self {
+        Self(never_commit::rules_path(&repo.path).unwrap())
+    }
+}
+
+impl Drop for Rules {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+fn staged_lines(state: &AppState) -> Vec<&str> {
+    state
+        .files
+        .iter()
+        .flat_map(|file| &file.lines)
+        .map(String::as_str)
+        .filter(|line| line.starts_with('+') && !line.starts_with("+++"))
+        .collect()
+}
+
+/// Moves to the next row of the unstaged pane until `is_target` holds.
+fn select_unstaged(mut state: AppState, is_target: impl Fn(&UnstagedListItem) -> bool) -> AppState {
+    for _ in 0..10 {
+        let item = state
+            .unstaged_pane
+            .list_items
+            .get(state.unstaged_pane.cursor);
+        if item.is_some_and(&is_target) {
+            return state;
+        }
+        state = send(state, Input::KeyDown);
+    }
+    panic!("no such row in the unstaged pane");
+}
+
+/// A local `DEBUG=true` added at the top of `config.txt` and a real change
+/// at its bottom, far enough apart to make two hunks. Nothing is staged.
+fn setup_lines() -> (TestRepo, Rules, AppState) {
+    let repo = TestRepo::new();
+    let filler = "a\nb\nc\nd\ne\nf\ng\nh\n";
+    repo.create_file("config.txt", &format!("x = 1\n{filler}y = 1\n"));
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("config.txt", &format!("DEBUG=true\nx = 1\n{filler}y = 2\n"));
+    let rules = Rules::of(&repo);
+
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    (repo, rules, state)
+}
+
+#[test]
+fn test_marked_hunk_is_left_out_of_stage_all() {
+    let (repo, _rules, state) = setup_lines();
+    let state = select_unstaged(press(state, "\t"), |item| {
+        matches!(item, UnstagedListItem::File(_))
+    });
+    let mut state = press(state, "j");
+    while state
+        .get_unstaged_file()
+        .and_then(|file| file.lines.get(state.main_screen.line_cursor))
+        .is_none_or(|line| line != "+DEBUG=true")
+    {
+        state = press(state, "j");
+    }
+
+    let state = press(state, "X");
+    assert_eq!(
+        state.message_text(),
+        Some("Marked the added lines of this hunk never to commit")
+    );
+    assert_eq!(
+        NeverCommit::load(&repo.path).rules,
+        vec![Rule::Line("DEBUG=true".to_string())]
+    );
+
+    // The line itself cannot be staged
+    let state = press(state, "1");
+    assert_eq!(
+        state.message_text(),
+        Some("This line is marked never to commit, press X to unmark it")
+    );
+    assert!(state.files.is_empty());
+
+    let state = press(state, "R");
+    assert_eq!(staged_lines(&state), vec!["+y = 2"]);
+    assert_eq!(
+        state.message_text(),
+        Some("Left 1 never-commit change unstaged")
+    );
+
+    // Staging and leaving out are undone together
+    let state = press(state, "<");
+    assert!(state.files.is_empty());
+}
+
+#[test]
+fn test_marked_file_is_refused_and_left_out() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "a\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "a2\n");
+    repo.create_file("local.env", "TOKEN=secret\n");
+    let _rules = Rules::of(&repo);
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+
+    let state = select_unstaged(
+        press(state, "\t"),
+        |item| matches!(item, UnstagedListItem::UntrackedFile(name) if name == "local.env"),
+    );
+    let state = press(state, "X");
+    assert_eq!(
+        state.message_text(),
+        Some("Marked local.env never to commit")
+    );
+
+    let state = press(state, "u");
+    assert_eq!(
+        state.message_text(),
+        Some("local.env is marked never to commit, press X to unmark it")
+    );
+
+    let state = press(state, "R");
+    assert_eq!(staged_files(&state), vec!["a.txt"]);
+    assert_eq!(
+        state.unstaged_pane.untracked_files,
+        vec!["local.env".to_string()]
+    );
+
+    // Unmarking lets it be staged again
+    let state = select_unstaged(
+        state,
+        |item| matches!(item, UnstagedListItem::UntrackedFile(name) if name == "local.env"),
+    );
+    let state = press(state, "Xu");
+    assert_eq!(staged_files(&state), vec!["a.txt", "local.env"]);
+}
+
+#[test]
+fn test_guarded_changes_are_unstaged_on_startup() {
+    let (repo, _rules, _state) = setup_lines();
+    NeverCommit::parse("line DEBUG=true\n")
+        .save(&repo.path)
+        .unwrap();
+    repo.add_all();
+
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    assert_eq!(staged_lines(&state), vec!["+y = 2"]);
+    let unstaged = git::run_git_command(&repo.path, &["diff"]).unwrap();
+    assert!(unstaged.contains("+DEBUG=true"), "{unstaged}");
+}
@@ -0,0 +1,171 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::AppState;
+use git_full_commit::config::Config;
+use git_full_commit::git;
+use git_full_commit::ui::main_screen::ListItem;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+fn setup_repo() -> TestRepo {
+    let repo = TestRepo::new();
+    std::fs::create_dir_all(repo.path.join("docs")).unwrap();
+    repo.create_file("docs/readme.md", "readme\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("docs/readme.md", "readme\nmore\n");
+    repo.add_all();
+    repo
+}
+
+#[test]
+fn test_new_file_is_created_opened_and_staged_after_save() {
+    let repo = setup_repo();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    app_state = update_state(app_state, Some(Input::Character('a')), 80, 80);
+    // The prompt starts in the directory of the selected file.
+    assert_eq!(app_state.prompt.as_ref().unwrap().text, "docs/");
+
+    app_state = press(app_state, "CHANGELOG.md");
+    app_state = update_state(app_state, Some(Input::Character('\n')), 80, 80);
+
+    assert!(repo.path.join("docs/CHANGELOG.md").exists());
+    let request = app_state
+        .editor_request
+        .take()
+        .expect("editor should be requested");
+    assert!(request.file_path.ends_with("docs/CHANGELOG.md"));
+
+    // Simulate saving in the editor.
+    repo.create_file("docs/CHANGELOG.md", "- new entry\n");
+    app_state.finish_editor_request(request);
+
+    assert!(
+        app_state
+            .files
+            .iter()
+            .any(|f| f.file_name == "docs/CHANGELOG.md")
+    );
+    assert!(matches!(
+        &app_state.main_screen.list_items[app_state.main_screen.file_cursor],
+        ListItem::File(f) if f.file_name == "docs/CHANGELOG.md"
+    ));
+}
+
+#[test]
+fn test_new_file_is_not_staged_when_editor_exits_without_saving() {
+    let repo = setup_repo();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    app_state = update_state(app_state, Some(Input::Character('a')), 80, 80);
+    app_state = press(app_state, "empty.txt");
+    app_state = update_state(app_state, Some(Input::Character('\n')), 80, 80);
+
+    let request = app_state.editor_request.take().unwrap();
+    app_state.finish_editor_request(request);
+
+    assert!(
+        !app_state
+            .files
+            .iter()
+            .any(|f| f.file_name == "docs/empty.txt")
+    );
+    assert!(
+        app_state
+            .unstaged_pane
+            .untracked_files
+            .contains(&"docs/empty.txt".to_string())
+    );
+
+    // Undo removes the file again.
+    app_state = update_state(app_state, Some(Input::Character('<')), 80, 80);
+    assert!(!repo.path.join("docs/empty.txt").exists());
+    assert!(app_state.unstaged_pane.untracked_files.is_empty());
+}
+
+#[test]
+fn test_new_file_uses_template() {
+    let repo = setup_repo();
+    let template_dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(template_dir.path().join("default.sh"), "#!/bin/sh\n").unwrap();
+
+    let files = git::get_diff(repo.path.clone());
+    let config = Config {
+        template_dir: Some(template_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let mut app_state = AppState::new_with_config(repo.path.clone(), files, config);
+
+    app_state = update_state(app_state, Some(Input::Character('a')), 80, 80);
+    app_state = update_state(app_state, Some(Input::Character('\u{1}')), 80, 80);
+    app_state = update_state(app_state, Some(Input::Character('\u{b}')), 80, 80);
+    app_state = press(app_state, "run.sh");
+    app_state = update_state(app_state, Some(Input::Character('\n')), 80, 80);
+
+    assert!(app_state.editor_request.is_some());
+    assert_eq!(
+        std::fs::read_to_string(repo.path.join("run.sh")).unwrap(),
+        "#!/bin/sh\n"
+    );
+}
+
+#[test]
+fn test_new_directory() {
+    let repo = setup_repo();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    app_state = update_state(app_state, Some(Input::Character('a')), 80, 80);
+    app_state = press(app_state, "guides/");
+    app_state = update_state(app_state, Some(Input::Character('\n')), 80, 80);
+
+    assert!(repo.path.join("docs/guides").is_dir());
+    assert!(app_state.editor_request.is_none());
+}
+
+#[test]
+fn test_new_file_existing_path_shows_error() {
+    let repo = setup_repo();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    app_state = update_state(app_state, Some(Input::Character('a')), 80, 80);
+    app_state = press(app_state, "readme.md");
+    app_state = update_state(app_state, Some(Input::Character('\n')), 80, 80);
+
+    assert!(app_state.message.is_some());
+    assert!(app_state.editor_request.is_none());
+    assert_eq!(
+        std::fs::read_to_string(repo.path.join("docs/readme.md")).unwrap(),
+        "readme\nmore\n"
+    );
+}
+
+#[test]
+fn test_new_file_outside_the_repository_is_refused() {
+    let repo = setup_repo();
+    let outside = repo.path.parent().unwrap().join("outside.txt");
+    for path in [
+        "../outside.txt",
+        "docs/../../outside.txt",
+        outside.to_str().unwrap(),
+    ] {
+        let files = git::get_diff(repo.path.clone());
+        let mut app_state = AppState::new(repo.path.clone(), files);
+        app_state = update_state(app_state, Some(Input::Character('a')), 80, 80);
+        // Ctrl-A, Ctrl-K clear the prompt
+        app_state = press(app_state, "\u{1}\u{b}");
+        app_state = press(app_state, path);
+        app_state = update_state(app_state, Some(Input::Character('\n')), 80, 80);
+
+        assert_eq!(
+            app_state.message_text(),
+            Some(format!("Cannot create: {path} is outside the repository").as_str())
+        );
+        assert!(app_state.editor_request.is_none());
+        assert!(!outside.exists());
+    }
+}
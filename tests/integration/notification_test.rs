@@ -0,0 +1,106 @@
+use crate::integration::common::{TestRepo, select_commit_in_log};
+use git_full_commit::app_state::AppState;
+use git_full_commit::config::Config;
+use git_full_commit::git;
+use git_full_commit::ui::update::{update_state, update_state_with_alt};
+use pancurses::Input;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+/// Writes a notify command that records its arguments in `output`.
+fn recording_command(dir: &Path, output: &Path) -> String {
+    let script = dir.join("notify.sh");
+    std::fs::write(
+        &script,
+        format!("#!/bin/sh\nprintf '%s\\n' \"$@\" > {}\n", output.display()),
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    script.display().to_string()
+}
+
+fn wait_for(path: &Path) -> Option<String> {
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while Instant::now() < deadline {
+        if let Ok(content) = std::fs::read_to_string(path)
+            && !content.is_empty()
+        {
+            return Some(content);
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    None
+}
+
+fn reword_middle_commit(mut state: AppState) -> AppState {
+    select_commit_in_log(&mut state, 1);
+    state = update_state_with_alt(state, Some(Input::Character('\n')), 1024, 1024);
+    for ch in " (reworded)".chars() {
+        state = update_state(state, Some(Input::Character(ch)), 1024, 1024);
+    }
+    state = update_state(state, Some(Input::Character('\n')), 1024, 1024);
+    state = update_state(state, Some(Input::Character('\n')), 1024, 1024);
+    update_state(state, Some(Input::Character('\n')), 1024, 1024)
+}
+
+fn setup_repo() -> TestRepo {
+    let repo = TestRepo::new();
+    repo.commit("commit 0");
+    repo.commit("commit 1");
+    repo.commit("commit 2");
+    repo
+}
+
+#[test]
+fn test_notifies_when_reorder_finishes() {
+    let repo = setup_repo();
+    let tmp = TempDir::new().unwrap();
+    let output = tmp.path().join("args");
+    let config = Config {
+        notify_command: Some(recording_command(tmp.path(), &output)),
+        notify_after_seconds: Some(0),
+        ..Default::default()
+    };
+    let files = git::get_diff(repo.path.clone());
+    let state = AppState::new_with_config(repo.path.clone(), files, config);
+
+    let _state = reword_middle_commit(state);
+
+    assert_eq!(
+        wait_for(&output).as_deref(),
+        Some("git-full-commit\nReorder finished\n")
+    );
+}
+
+#[test]
+fn test_quick_operations_are_not_notified() {
+    let repo = setup_repo();
+    let tmp = TempDir::new().unwrap();
+    let output = tmp.path().join("args");
+    let config = Config {
+        notify_command: Some(recording_command(tmp.path(), &output)),
+        ..Default::default()
+    };
+    let files = git::get_diff(repo.path.clone());
+    let state = AppState::new_with_config(repo.path.clone(), files, config);
+
+    let state = reword_middle_commit(state);
+    assert!(state.previous_commits[1].message.ends_with("(reworded)"));
+
+    std::thread::sleep(Duration::from_millis(200));
+    assert!(!output.exists());
+}
+
+#[test]
+fn test_notify_config_keys() {
+    let config =
+        Config::parse("notify_command = \"notify-send -u low\"\nnotify_after_seconds = 3\n");
+    assert_eq!(config.notify_command.as_deref(), Some("notify-send -u low"));
+    assert_eq!(config.notify_after_seconds, Some(3));
+
+    let config = Config::parse("notify_command = \"\"\nnotify_after_seconds = soon\n");
+    assert_eq!(config.notify_command, None);
+    assert_eq!(config.notify_after_seconds, None);
+}
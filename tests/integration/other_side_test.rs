@@ -0,0 +1,92 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{press, send};
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use git_full_commit::ui::main_screen::{self, ListItem};
+use pancurses::Input;
+
+/// `a.txt` with a staged change on its first line and an unstaged one on
+/// its last, and `b.txt` with a staged change only.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\ntwo\n");
+    repo.create_file("b.txt", "b\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "ONE\ntwo\n");
+    repo.create_file("b.txt", "B\n");
+    repo.add_all();
+    repo.create_file("a.txt", "ONE\nTWO\n");
+
+    let mut state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    state.main_screen.file_cursor = state
+        .main_screen
+        .list_items
+        .iter()
+        .position(|item| matches!(item, ListItem::File(file) if file.file_name == "a.txt"))
+        .unwrap();
+    (repo, state)
+}
+
+fn shown_lines(state: &AppState) -> Vec<String> {
+    main_screen::other_side_file(state)
+        .unwrap()
+        .lines
+        .iter()
+        .filter(|line| line.starts_with('+') && !line.starts_with("+++"))
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn test_staged_file_shows_its_unstaged_changes() {
+    let (_repo, state) = setup();
+    assert!(main_screen::other_side_file(&state).is_none());
+
+    let state = press(state, "=");
+    assert_eq!(shown_lines(&state), vec!["+TWO"]);
+    assert_eq!(
+        state
+            .current_main_file()
+            .map(|file| file.file_name.as_str()),
+        Some("a.txt")
+    );
+
+    // Scrolling keeps it, `=` flips back
+    let state = press(state, "j");
+    assert!(main_screen::other_side_file(&state).is_some());
+    let state = press(state, "=");
+    assert!(main_screen::other_side_file(&state).is_none());
+    assert_eq!(state.main_screen.diff_scroll, 0);
+}
+
+#[test]
+fn test_unstaged_file_shows_its_staged_changes_until_another_key() {
+    let (_repo, state) = setup();
+    let state = press(state, "\t=");
+    assert_eq!(
+        state
+            .get_unstaged_file()
+            .map(|file| file.file_name.as_str()),
+        Some("a.txt")
+    );
+    assert_eq!(shown_lines(&state), vec!["+ONE"]);
+
+    let state = send(state, Input::KeyDown);
+    assert!(state.other_side.is_none());
+}
+
+#[test]
+fn test_file_without_the_other_side() {
+    let (_repo, mut state) = setup();
+    state.main_screen.file_cursor = state
+        .main_screen
+        .list_items
+        .iter()
+        .position(|item| matches!(item, ListItem::File(file) if file.file_name == "b.txt"))
+        .unwrap();
+
+    let state = press(state, "=");
+    assert!(state.other_side.is_none());
+    assert_eq!(state.message_text(), Some("b.txt has no unstaged changes"));
+}
@@ -0,0 +1,26 @@
+use git_full_commit::config::Config;
+use git_full_commit::ui::color::Palette;
+
+#[test]
+fn test_palette_is_read_from_config() {
+    assert_eq!(
+        Config::parse("palette = \"colorblind\"\n").palette,
+        Palette::Colorblind
+    );
+    assert_eq!(
+        Config::parse("palette = \"high-contrast\"\n").palette,
+        Palette::HighContrast
+    );
+    assert_eq!(Config::parse("").palette, Palette::Default);
+    assert_eq!(
+        Config::parse("palette = \"rainbow\"\n").palette,
+        Palette::Default
+    );
+}
+
+#[test]
+fn test_only_the_alternative_palettes_mark_the_gutter() {
+    assert!(!Palette::Default.marks_gutter());
+    assert!(Palette::HighContrast.marks_gutter());
+    assert!(Palette::Colorblind.marks_gutter());
+}
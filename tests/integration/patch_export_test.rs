@@ -0,0 +1,116 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use std::fs;
+use std::process::Command;
+
+fn git(repo: &TestRepo, args: &[&str]) -> (bool, String) {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(&repo.path)
+        .output()
+        .unwrap();
+    (
+        output.status.success(),
+        String::from_utf8_lossy(&output.stdout).to_string(),
+    )
+}
+
+/// `src/a.txt` staged with two hunks, far apart.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    fs::create_dir_all(repo.path.join("src")).unwrap();
+    let lines: Vec<String> = (1..=20).map(|n| format!("line {n}")).collect();
+    repo.create_file("src/a.txt", &(lines.join("\n") + "\n"));
+    repo.add_all();
+    repo.commit("initial");
+    let mut edited = lines.clone();
+    edited[0] = "first".to_string();
+    edited[19] = "last".to_string();
+    repo.create_file("src/a.txt", &(edited.join("\n") + "\n"));
+    repo.add_all();
+
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    assert_eq!(
+        state
+            .current_main_file()
+            .map(|file| file.file_name.as_str()),
+        Some("src/a.txt")
+    );
+    (repo, state)
+}
+
+#[test]
+fn test_save_the_file_as_a_patch() {
+    let (repo, state) = setup();
+    let state = press(state, "\u{17}");
+    let prompt = state.prompt.as_ref().expect("the save prompt opens");
+    assert_eq!(prompt.text, "a.txt.patch");
+
+    let state = press(state, "\n");
+    assert_eq!(state.message_text(), Some("Saved src/a.txt to a.txt.patch"));
+    let patch = fs::read_to_string(repo.path.join("a.txt.patch")).unwrap();
+    assert_eq!(patch, git(&repo, &["diff", "--cached"]).1);
+
+    // It takes the change back out of the index
+    assert!(git(&repo, &["apply", "--cached", "--reverse", "a.txt.patch"]).0);
+    assert_eq!(git(&repo, &["diff", "--cached"]).1, "");
+}
+
+#[test]
+fn test_save_refuses_an_existing_file_and_paths_outside() {
+    let (repo, state) = setup();
+    repo.create_file("a.txt.patch", "kept");
+    let state = press(state, "\u{17}\n");
+    assert_eq!(
+        state.message_text(),
+        Some("Cannot save the patch: a.txt.patch already exists")
+    );
+    assert_eq!(
+        fs::read_to_string(repo.path.join("a.txt.patch")).unwrap(),
+        "kept"
+    );
+
+    // Ctrl-A, Ctrl-K clear the prefilled path
+    let state = press(state, "\u{17}\u{1}\u{b}../a.patch\n");
+    assert_eq!(
+        state.message_text(),
+        Some("Cannot save the patch: ../a.patch is outside the repository")
+    );
+    assert!(!repo.path.join("../a.patch").exists());
+}
+
+#[test]
+fn test_save_the_hunk_under_the_cursor() {
+    let (repo, state) = setup();
+    let state = press(state, "jjjjjjjjjjjjjjj\u{17}");
+    let state = press(state, "\n");
+    assert_eq!(
+        state.message_text(),
+        Some("Saved the hunk of src/a.txt to a.txt.patch")
+    );
+    let patch = fs::read_to_string(repo.path.join("a.txt.patch")).unwrap();
+    assert!(patch.contains("+last\n"));
+    assert!(!patch.contains("+first\n"));
+    assert!(
+        git(
+            &repo,
+            &["apply", "--cached", "--reverse", "--check", "a.txt.patch"]
+        )
+        .0
+    );
+}
+
+#[test]
+fn test_copy_the_patch() {
+    let (_repo, state) = setup();
+    let state = press(state, "y");
+    let message = state.message_text().unwrap().to_string();
+    // Whether a clipboard command is installed depends on the machine
+    assert!(
+        message == "Copied src/a.txt to the clipboard as a patch"
+            || message.starts_with("Could not copy src/a.txt to the clipboard: "),
+        "{message}"
+    );
+}
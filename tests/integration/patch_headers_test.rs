@@ -0,0 +1,69 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::send;
+use git_full_commit::app_state::{AppState, FocusedPane};
+use git_full_commit::config::Config;
+use git_full_commit::git;
+use pancurses::Input;
+
+fn setup() -> TestRepo {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\ntwo\nthree\n");
+    repo.create_file("b.txt", "one\ntwo\nthree\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "one\n2\nthree\n");
+    repo.add_all();
+    repo.create_file("b.txt", "one\n2\nthree\n");
+    repo
+}
+
+fn line_under_cursor(lines: &[String], state: &AppState) -> String {
+    lines[state.main_screen.line_cursor].clone()
+}
+
+#[test]
+fn test_h_hides_headers_and_keeps_cursor_on_its_line() {
+    let repo = setup();
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new(repo.path.clone(), files);
+    let file = state.current_main_file().unwrap().clone();
+    assert!(file.lines[0].starts_with("diff --git "));
+    state.main_screen.line_cursor = file.lines.iter().position(|l| l == "+2").unwrap();
+
+    let state = send(state, Input::Character('h'));
+    assert!(state.hide_patch_headers);
+    let lines = state.current_main_file().unwrap().lines.clone();
+    assert!(lines[0].starts_with("@@ "), "{lines:?}");
+    assert_eq!(line_under_cursor(&lines, &state), "+2");
+
+    // The hunks moved with the lines, so the line is still unstaged right
+    let state = send(state, Input::Character('1'));
+    let staged = git::run_git_command(&repo.path, &["diff", "--cached"]).unwrap();
+    assert!(!staged.contains("+2"), "{staged}");
+    assert!(staged.contains("-two"), "{staged}");
+
+    let state = send(state, Input::Character('h'));
+    assert!(!state.hide_patch_headers);
+    assert!(state.current_main_file().unwrap().lines[0].starts_with("diff --git "));
+}
+
+#[test]
+fn test_unstaged_pane_stages_lines_with_headers_hidden() {
+    let repo = setup();
+    let files = git::get_diff(repo.path.clone());
+    let config = Config::parse("hide_patch_headers = true\n");
+    let state = AppState::new_with_config(repo.path.clone(), files, config);
+    let state = send(state, Input::Character('\t'));
+    assert_eq!(state.focused_pane, FocusedPane::Unstaged);
+    let mut state = send(state, Input::KeyDown);
+
+    let file = state.get_unstaged_file().unwrap().clone();
+    assert_eq!(file.file_name, "b.txt");
+    assert!(file.lines[0].starts_with("@@ "), "{:?}", file.lines);
+    state.main_screen.line_cursor = file.lines.iter().position(|l| l == "-two").unwrap();
+
+    let _state = send(state, Input::Character('1'));
+    let staged = git::run_git_command(&repo.path, &["diff", "--cached", "b.txt"]).unwrap();
+    assert!(staged.contains("-two"), "{staged}");
+    assert!(!staged.contains("+2"), "{staged}");
+}
@@ -0,0 +1,77 @@
+use crate::git_test::common::{TestRepo, run_git};
+use crate::integration::common::{open_view, press};
+use git_full_commit::app_state::{AppState, FocusedPane, Modal};
+use git_full_commit::git;
+use git_full_commit::message_log::Severity;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+/// `a.txt` committed and changed again, with the diff cursor on its hunk
+/// in the unstaged pane.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "two\n");
+    let mut state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    state.focused_pane = FocusedPane::Unstaged;
+    while state.get_unstaged_file().is_none() {
+        state = update_state(state, Some(Input::KeyDown), 40, 80);
+    }
+    state.unstaged_pane.is_diff_cursor_active = true;
+    state.main_screen.line_cursor = state.get_unstaged_file().unwrap().hunks[0].start_line + 1;
+    (repo, state)
+}
+
+#[test]
+fn test_preview_the_patch_under_the_cursor() {
+    let (_repo, state) = setup();
+    let state = press(state, "%");
+    let preview = open_view!(state.modal, Modal::PatchPreview);
+    assert_eq!(
+        preview.lines[0],
+        "Staging the hunk of a.txt: git apply --cached --unidiff-zero"
+    );
+    assert_eq!(preview.lines[1], "git apply --check: the patch applies");
+    assert!(preview.lines.iter().any(|line| line == "-one"));
+    assert!(preview.lines.iter().any(|line| line == "+two"));
+
+    let state = press(state, "q");
+    assert!(!matches!(state.modal, Some(Modal::PatchPreview(_))));
+}
+
+#[test]
+fn test_without_a_hunk_there_is_no_preview() {
+    let (_repo, mut state) = setup();
+    state.unstaged_pane.is_diff_cursor_active = false;
+    let state = press(state, "%");
+    assert!(!matches!(state.modal, Some(Modal::PatchPreview(_))));
+    assert_eq!(
+        state.message_text(),
+        Some("Move to a hunk with j/k to preview its patch")
+    );
+}
+
+#[test]
+fn test_a_patch_that_does_not_apply_is_shown_instead_of_applied() {
+    let (repo, state) = setup();
+    // Staged behind the app's back, so its hunk is stale
+    run_git(&repo.path, &["add", "a.txt"]);
+    let state = press(state, "u");
+
+    let message = state.message_text().unwrap().to_string();
+    assert!(
+        message.starts_with("Staging the hunk of a.txt failed, the patch does not apply: "),
+        "{message}"
+    );
+    assert_eq!(state.message_severity(), Some(Severity::Error));
+    assert!(state.command_history.undo_stack.is_empty());
+    let preview = open_view!(state.modal, Modal::PatchPreview);
+    assert_eq!(preview.lines[1], "git apply --check failed:");
+    assert!(
+        preview.lines[2].starts_with("error: "),
+        "{:?}",
+        preview.lines
+    );
+}
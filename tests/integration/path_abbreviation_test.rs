@@ -0,0 +1,94 @@
+use crate::git_test::common::TestRepo;
+use git_full_commit::app_state::{AppState, FocusedPane};
+use git_full_commit::config::Config;
+use git_full_commit::git;
+use git_full_commit::ui::layout::abbreviate_paths;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+#[test]
+fn test_directories_are_shortened_to_unique_prefixes() {
+    let names = abbreviate_paths([
+        "src/ui/main_screen.rs",
+        "src/util/path_order.rs",
+        "spec/main_screen.md",
+        "README.md",
+    ]);
+
+    assert_eq!(names["src/ui/main_screen.rs"], "sr/ui/main_screen.rs");
+    assert_eq!(names["src/util/path_order.rs"], "sr/ut/path_order.rs");
+    assert_eq!(names["spec/main_screen.md"], "sp/main_screen.md");
+    assert_eq!(names["README.md"], "README.md");
+}
+
+#[test]
+fn test_single_child_directories_use_one_character() {
+    let names = abbreviate_paths(["tests/integration/common.rs"]);
+    assert_eq!(names["tests/integration/common.rs"], "t/i/common.rs");
+}
+
+#[test]
+fn test_hidden_directories_keep_dot_and_one_character() {
+    let names = abbreviate_paths([".github/workflows/ci.yml", ".gitignore"]);
+    assert_eq!(names[".github/workflows/ci.yml"], ".g/w/ci.yml");
+    assert_eq!(names[".gitignore"], ".gitignore");
+}
+
+#[test]
+fn test_directory_that_prefixes_a_sibling_keeps_full_name() {
+    let names = abbreviate_paths(["ui/a.rs", "ui_old/b.rs"]);
+    assert_eq!(names["ui/a.rs"], "ui/a.rs");
+    assert_eq!(names["ui_old/b.rs"], "ui_/b.rs");
+}
+
+#[test]
+fn test_same_name_under_different_parents_is_abbreviated_independently() {
+    let names = abbreviate_paths(["app/src/lib.rs", "app/spec/lib.md", "web/src/index.ts"]);
+    assert_eq!(names["app/src/lib.rs"], "a/sr/lib.rs");
+    assert_eq!(names["app/spec/lib.md"], "a/sp/lib.md");
+    assert_eq!(names["web/src/index.ts"], "w/s/index.ts");
+}
+
+fn setup_repo() -> TestRepo {
+    let repo = TestRepo::new();
+    std::fs::create_dir_all(repo.path.join("src/deeply/nested")).unwrap();
+    repo.create_file("src/deeply/nested/staged.rs", "staged\n");
+    repo.add_all();
+    repo.create_file("src/deeply/nested/untracked.rs", "untracked\n");
+    repo
+}
+
+#[test]
+fn test_p_toggles_abbreviation_and_status_bar_shows_full_path() {
+    let repo = setup_repo();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+    assert!(!app_state.abbreviate_paths);
+
+    app_state = update_state(app_state, Some(Input::Character('p')), 80, 80);
+    assert!(app_state.abbreviate_paths);
+    assert_eq!(
+        app_state.selected_file_path().as_deref(),
+        Some("src/deeply/nested/staged.rs")
+    );
+
+    app_state = update_state(app_state, Some(Input::Character('\t')), 80, 80);
+    assert_eq!(app_state.focused_pane, FocusedPane::Unstaged);
+    app_state.unstaged_pane.cursor = 2;
+    assert_eq!(
+        app_state.selected_file_path().as_deref(),
+        Some("src/deeply/nested/untracked.rs")
+    );
+
+    app_state = update_state(app_state, Some(Input::Character('p')), 80, 80);
+    assert!(!app_state.abbreviate_paths);
+}
+
+#[test]
+fn test_abbreviation_can_be_enabled_in_config() {
+    let repo = setup_repo();
+    let files = git::get_diff(repo.path.clone());
+    let config = Config::parse("abbreviate_paths = true\n");
+    let app_state = AppState::new_with_config(repo.path.clone(), files, config);
+    assert!(app_state.abbreviate_paths);
+}
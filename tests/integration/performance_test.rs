@@ -1,6 +1,8 @@
 use crate::git_test::common::TestRepo;
 use git_full_commit::app_state::AppState;
+use git_full_commit::config::Config;
 use git_full_commit::git;
+use git_full_commit::message_log::Severity;
 use std::thread;
 use std::time::Duration;
 
@@ -58,3 +60,42 @@ fn test_debounce_diff_update() {
     }
     assert!(updated, "Background worker did not update state in time");
 }
+
+#[test]
+fn test_slow_refresh_shows_a_warning() {
+    let repo = TestRepo::new();
+    repo.create_file("test.txt", "content");
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let config = Config {
+        slow_refresh_ms: Some(0),
+        ..Default::default()
+    };
+    let mut state = AppState::new_with_config(repo.path.clone(), files, config);
+    let startup: Vec<&str> = state
+        .profile
+        .startup
+        .0
+        .iter()
+        .map(|(name, _)| *name)
+        .collect();
+    assert!(startup.contains(&"commit list"));
+
+    state.refresh_diff(false);
+    let warning = state.message_text().unwrap().to_string();
+    assert!(warning.starts_with("Slow refresh: "), "{warning}");
+    assert_eq!(state.profile.refresh_count, 1);
+
+    // Other messages are not replaced
+    state.set_message(Severity::Error, "Push failed");
+    state.refresh_diff(false);
+    assert_eq!(state.message_text(), Some("Push failed"));
+
+    // Fast refreshes stay quiet with the default threshold
+    state.config.slow_refresh_ms = None;
+    state.message = None;
+    state.refresh_diff(false);
+    assert_eq!(state.message_text(), None);
+    assert_eq!(state.profile.refresh_count, 3);
+}
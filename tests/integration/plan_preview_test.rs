@@ -0,0 +1,157 @@
+use crate::integration::common::{
+    TestRepo, assert_commit_list, get_log, open_view, press, select_commit_in_log,
+};
+use git_full_commit::app_state::{AppState, Modal, Screen};
+use git_full_commit::git;
+use git_full_commit::ui::plan_preview::{DEFAULT_SAVE_PATH, PlanSource};
+use git_full_commit::ui::update::update_state_with_alt;
+use pancurses::Input;
+
+fn setup_swapped() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.commit("commit 0");
+    repo.commit("commit 1");
+    repo.commit("commit 2");
+    let mut state = repo.create_initial_state();
+    select_commit_in_log(&mut state, 1);
+    state = update_state_with_alt(state, Some(Input::KeyUp), 40, 80);
+    (repo, state)
+}
+
+#[test]
+fn test_reorder_shows_plan_before_rewriting() {
+    let (repo, state) = setup_swapped();
+    let log = get_log(&repo.path);
+
+    let state = press(state, "\n");
+
+    let preview = open_view!(state.modal, Modal::PlanPreview);
+    assert_eq!(preview.source, PlanSource::Reorder);
+    assert_eq!(
+        preview.todo,
+        format!(
+            "pick {} commit 2\npick {} commit 1\n",
+            log[0].hash, log[1].hash
+        )
+    );
+    assert!(state.main_screen.is_reordering_commits);
+    assert_eq!(get_log(&repo.path), log);
+}
+
+#[test]
+fn test_clean_reorder_is_predicted() {
+    let (_repo, state) = setup_swapped();
+
+    let state = press(state, "\n");
+
+    let preview = open_view!(state.modal, Modal::PlanPreview);
+    assert_eq!(preview.prediction, "Trial run: applies cleanly");
+}
+
+#[test]
+fn test_conflicting_reorder_is_predicted_without_touching_the_branch() {
+    let repo = TestRepo::new();
+    for (content, message) in [("1\n", "base"), ("2\n", "two"), ("3\n", "three")] {
+        repo.create_file("a.txt", content);
+        repo.add_all();
+        repo.commit(message);
+    }
+    let log = get_log(&repo.path);
+    let mut state = repo.create_initial_state();
+    select_commit_in_log(&mut state, 1);
+    let state = update_state_with_alt(state, Some(Input::KeyUp), 40, 80);
+
+    let state = press(state, "\n");
+
+    let preview = open_view!(state.modal, Modal::PlanPreview);
+    assert_eq!(
+        preview.prediction,
+        format!("Trial run: {} three conflicts in a.txt", log[0].hash)
+    );
+    assert_eq!(get_log(&repo.path), log);
+    assert_eq!(repo.get_status(), "");
+    let worktrees = git::run_git_command(&repo.path, &["worktree", "list"]).unwrap();
+    assert_eq!(worktrees.lines().count(), 1, "{worktrees}");
+}
+
+#[test]
+fn test_escape_returns_to_reordering() {
+    let (repo, state) = setup_swapped();
+    let log = get_log(&repo.path);
+
+    let state = press(state, "\n\u{1b}");
+
+    assert!(!matches!(state.modal, Some(Modal::PlanPreview(_))));
+    assert!(state.main_screen.is_reordering_commits);
+    assert_commit_list(
+        &state.main_screen.list_items,
+        &["commit 1", "commit 2", "commit 0"],
+    );
+    assert_eq!(get_log(&repo.path), log);
+}
+
+#[test]
+fn test_plan_can_be_saved_to_a_file() {
+    let (repo, state) = setup_swapped();
+
+    let state = press(state, "\nw");
+    let prompt = state.prompt.as_ref().expect("save prompt should be open");
+    assert_eq!(prompt.text, DEFAULT_SAVE_PATH);
+
+    let state = press(state, "\n");
+    let saved = std::fs::read_to_string(repo.path.join(DEFAULT_SAVE_PATH)).unwrap();
+    assert_eq!(saved, open_view!(state.modal, Modal::PlanPreview).todo);
+    assert_eq!(
+        state.message_text(),
+        Some(format!("Saved the plan to {DEFAULT_SAVE_PATH}").as_str())
+    );
+
+    // The saved plan is not written over
+    std::fs::write(repo.path.join(DEFAULT_SAVE_PATH), "kept").unwrap();
+    let state = press(state, "w\n");
+    assert_eq!(
+        state.message_text(),
+        Some(format!("Cannot save the plan: {DEFAULT_SAVE_PATH} already exists").as_str())
+    );
+    assert_eq!(
+        std::fs::read_to_string(repo.path.join(DEFAULT_SAVE_PATH)).unwrap(),
+        "kept"
+    );
+    assert!(matches!(state.modal, Some(Modal::PlanPreview(_))));
+}
+
+#[test]
+fn test_plan_is_not_saved_outside_the_repository() {
+    let (repo, state) = setup_swapped();
+
+    // Ctrl-A, Ctrl-K clear the prefilled path
+    let state = press(state, "\nw\u{1}\u{b}../plan.txt\n");
+    assert_eq!(
+        state.message_text(),
+        Some("Cannot save the plan: ../plan.txt is outside the repository")
+    );
+    assert!(!repo.path.join("../plan.txt").exists());
+}
+
+#[test]
+fn test_rebase_plan_is_previewed() {
+    let repo = TestRepo::new();
+    repo.commit("commit 0");
+    repo.commit("commit 1");
+    let log = get_log(&repo.path);
+
+    let state = press(repo.create_initial_state(), "Id\n");
+    let preview = open_view!(state.modal, Modal::PlanPreview);
+    assert_eq!(preview.source, PlanSource::RebasePlan);
+    assert_eq!(
+        preview.todo,
+        format!(
+            "pick {} commit 0\ndrop {} commit 1\n",
+            log[1].hash, log[0].hash
+        )
+    );
+
+    let state = press(state, "\n");
+    assert!(!matches!(state.screen, Some(Screen::RebasePlan(_))));
+    assert_eq!(get_log(&repo.path).len(), 1);
+}
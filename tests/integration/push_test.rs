@@ -0,0 +1,120 @@
+use crate::integration::common::{TestRepo, get_log, select_commit_in_log};
+use git_full_commit::app_state::AppState;
+use git_full_commit::git::{self, PushStatus};
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn wait_for_push(app_state: &mut AppState) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while app_state.push_started.is_some() {
+        assert!(Instant::now() < deadline, "push did not finish");
+        app_state.poll_background();
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn test_push_sets_upstream_and_marks_commits_as_pushed() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("first");
+    let mut app_state = repo.create_initial_state();
+    select_commit_in_log(&mut app_state, 0);
+
+    app_state = update_state(app_state, Some(Input::Character('P')), 40, 80);
+    assert!(app_state.push_started.is_some());
+    wait_for_push(&mut app_state);
+
+    assert_eq!(app_state.message_text(), None);
+    assert!(app_state.previous_commits[0].is_on_remote);
+    assert!(get_log(&repo.path)[0].is_on_remote);
+}
+
+#[test]
+fn test_push_to_existing_upstream() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("first");
+    repo.push();
+    repo.create_file("a.txt", "one\ntwo\n");
+    repo.add_all();
+    repo.commit("second");
+    let mut app_state = repo.create_initial_state();
+    assert!(!app_state.previous_commits[0].is_on_remote);
+    select_commit_in_log(&mut app_state, 1);
+
+    app_state = update_state(app_state, Some(Input::Character('P')), 40, 80);
+    wait_for_push(&mut app_state);
+
+    assert!(app_state.previous_commits[0].is_on_remote);
+}
+
+#[test]
+fn test_push_failure_is_reported() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("first");
+    crate::git_test::common::run_git(&repo.path, &["remote", "remove", "origin"]);
+    let mut app_state = repo.create_initial_state();
+    select_commit_in_log(&mut app_state, 0);
+
+    app_state = update_state(app_state, Some(Input::Character('P')), 40, 80);
+    wait_for_push(&mut app_state);
+
+    let error = app_state.message_text().unwrap();
+    assert!(error.starts_with("Push failed: "), "{error}");
+}
+
+#[test]
+fn test_push_key_only_works_on_commits() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("first");
+    repo.create_file("a.txt", "one\ntwo\n");
+    repo.add_all();
+    let mut app_state = repo.create_initial_state();
+    assert_eq!(app_state.main_screen.file_cursor, 1);
+
+    app_state = update_state(app_state, Some(Input::Character('P')), 40, 80);
+
+    assert!(app_state.push_started.is_none());
+}
+
+#[test]
+fn test_commits_show_what_the_next_push_does() {
+    let repo = TestRepo::new();
+    repo.commit("first");
+    repo.commit("second");
+    assert_eq!(
+        push_statuses(&repo),
+        vec![PushStatus::WillPush, PushStatus::WillPush]
+    );
+
+    repo.push();
+    repo.commit("third");
+    assert_eq!(
+        push_statuses(&repo),
+        vec![PushStatus::WillPush, PushStatus::Pushed]
+    );
+
+    // Rewriting a pushed commit leaves the upstream with commits HEAD lacks
+    git::run_git_command(&repo.path, &["reset", "--hard", "HEAD~2"]).unwrap();
+    repo.commit("second again");
+    assert_eq!(
+        push_statuses(&repo),
+        vec![PushStatus::NeedsForce, PushStatus::Pushed]
+    );
+}
+
+fn push_statuses(repo: &TestRepo) -> Vec<PushStatus> {
+    get_log(&repo.path)
+        .iter()
+        .map(|commit| commit.push)
+        .collect()
+}
@@ -0,0 +1,125 @@
+use crate::integration::common::{TestRepo, get_log, open_view, press};
+use git_full_commit::app_state::Screen;
+use git_full_commit::git::{self, RebaseAction, RebaseStep};
+
+fn commit_file(repo: &TestRepo, name: &str, content: &str, message: &str) {
+    repo.create_file(name, content);
+    repo.add_all();
+    repo.commit(message);
+}
+
+fn setup_repo() -> TestRepo {
+    let repo = TestRepo::new();
+    commit_file(&repo, "base.txt", "base\n", "base");
+    commit_file(&repo, "a.txt", "a\n", "add a");
+    commit_file(&repo, "a.txt", "a fixed\n", "oops");
+    commit_file(&repo, "debug.txt", "debug\n", "debug output");
+    commit_file(&repo, "b.txt", "b\n", "add b");
+    repo
+}
+
+fn messages(repo: &TestRepo) -> Vec<String> {
+    get_log(&repo.path).into_iter().map(|c| c.message).collect()
+}
+
+#[test]
+fn test_plan_lists_local_commits_newest_first() {
+    let repo = setup_repo();
+    let state = press(repo.create_initial_state(), "I");
+
+    let plan = open_view!(state.screen, Screen::RebasePlan);
+    let listed: Vec<&str> = plan.steps.iter().map(|s| s.message.as_str()).collect();
+    assert_eq!(
+        listed,
+        vec!["add b", "debug output", "oops", "add a", "base"]
+    );
+    assert!(plan.steps.iter().all(|s| s.action == RebaseAction::Pick));
+}
+
+#[test]
+fn test_plan_runs_fixup_drop_and_reword() {
+    let repo = setup_repo();
+    let mut state = press(repo.create_initial_state(), "I");
+
+    // add b: reword, debug output: drop, oops: fixup into "add a".
+    state = press(state, "r");
+    assert!(state.prompt.is_some());
+    state = press(state, " file\n");
+    state = press(state, "jdjf");
+    let plan = open_view!(state.screen, Screen::RebasePlan);
+    let actions: Vec<RebaseAction> = plan.steps.iter().map(|s| s.action).collect();
+    assert_eq!(
+        actions,
+        vec![
+            RebaseAction::Reword,
+            RebaseAction::Drop,
+            RebaseAction::Fixup,
+            RebaseAction::Pick,
+            RebaseAction::Pick,
+        ]
+    );
+
+    // Enter shows the todo list; Enter again runs it.
+    state = press(state, "\n\n");
+    assert!(state.message.is_none(), "{:?}", state.message);
+    assert!(!matches!(state.screen, Some(Screen::RebasePlan(_))));
+
+    assert_eq!(messages(&repo), vec!["add b file", "add a", "base"]);
+    assert!(!repo.path.join("debug.txt").exists());
+    assert_eq!(
+        std::fs::read_to_string(repo.path.join("a.txt")).unwrap(),
+        "a fixed\n"
+    );
+    assert_eq!(
+        state
+            .previous_commits
+            .iter()
+            .map(|c| c.message.as_str())
+            .collect::<Vec<_>>(),
+        vec!["add b file", "add a", "base"]
+    );
+}
+
+#[test]
+fn test_squash_keeps_both_messages() {
+    let repo = setup_repo();
+    let state = press(repo.create_initial_state(), "Ijjs\n\n");
+    assert!(state.message.is_none(), "{:?}", state.message);
+
+    let log = get_log(&repo.path);
+    assert_eq!(log.len(), 4);
+    let body = repo.get_log(3);
+    assert!(body.contains("add a"));
+    assert!(body.contains("oops"));
+}
+
+#[test]
+fn test_cancel_leaves_history_unchanged() {
+    let repo = setup_repo();
+    let state = press(repo.create_initial_state(), "Iddq");
+    assert!(!matches!(state.screen, Some(Screen::RebasePlan(_))));
+    assert_eq!(messages(&repo).len(), 5);
+}
+
+#[test]
+fn test_oldest_commit_cannot_be_squashed() {
+    let repo = setup_repo();
+    let steps: Vec<RebaseStep> = get_log(&repo.path)
+        .into_iter()
+        .rev()
+        .enumerate()
+        .map(|(i, c)| RebaseStep {
+            hash: c.hash,
+            message: c.message,
+            action: if i == 0 {
+                RebaseAction::Fixup
+            } else {
+                RebaseAction::Pick
+            },
+            new_message: None,
+        })
+        .collect();
+
+    assert!(git::run_rebase_plan(&repo.path, &steps).is_err());
+    assert_eq!(messages(&repo).len(), 5);
+}
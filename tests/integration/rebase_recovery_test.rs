@@ -0,0 +1,99 @@
+use crate::integration::common::{
+    TestRepo, commit_subjects, open_view, press, select_commit_in_log,
+};
+use git_full_commit::app_state::{AppState, Modal};
+use git_full_commit::git;
+use git_full_commit::ui::rebase_recovery::{self, Retry, Snapshot};
+
+fn recovery_lines(state: &AppState) -> &[String] {
+    &open_view!(state.modal, Modal::RebaseRecovery).lines
+}
+
+/// `second` adds `a.txt` and `third` changes it, so `second` cannot be
+/// dropped. A change to `b.txt` is staged, and `second` is selected.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("b.txt", "b\n");
+    repo.add_all();
+    repo.commit("first");
+    repo.create_file("a.txt", "a\n");
+    repo.add_all();
+    repo.commit("second");
+    repo.create_file("a.txt", "a2\n");
+    repo.add_all();
+    repo.commit("third");
+    repo.create_file("b.txt", "staged\n");
+    repo.add_all();
+    let mut state = repo.create_initial_state();
+    select_commit_in_log(&mut state, 1);
+    (repo, state)
+}
+
+#[test]
+fn test_failed_drop_explains_and_restores() {
+    let (repo, state) = setup();
+    let state = press(state, "d\n");
+
+    let lines = recovery_lines(&state);
+    assert_eq!(lines[0], "Drop failed.");
+    assert!(lines.contains(&"The rebase was aborted, the branch is as it was before.".to_string()));
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.starts_with("Trial run: ") && line.ends_with("conflicts in a.txt")),
+        "{lines:?}"
+    );
+    assert!(lines.contains(&"git status:".to_string()));
+    assert!(lines.contains(&"  r  Restore, then retry the drop".to_string()));
+    assert_eq!(commit_subjects(&state), vec!["third", "second", "first"]);
+
+    // Retrying fails the same way and explains again
+    let state = press(state, "r");
+    assert_eq!(recovery_lines(&state)[0], "Drop failed.");
+
+    let state = press(state, "a");
+    assert!(!matches!(state.modal, Some(Modal::RebaseRecovery(_))));
+    assert_eq!(
+        state.message_text(),
+        Some("Restored the branch and the index from before the drop")
+    );
+    assert_eq!(repo.get_status(), "M  b.txt\n");
+}
+
+#[test]
+fn test_moved_branch_is_put_back() {
+    let (repo, state) = setup();
+    let before = Snapshot::take(&repo.path).unwrap();
+    // Like a `fixup!` commit left behind by a failed amend
+    repo.commit("fixup! second");
+    let mut state = state;
+    state.refresh_diff(false);
+    let retry = Retry::Amend {
+        hash: state.previous_commits[2].hash.clone(),
+        message: "second".to_string(),
+    };
+    rebase_recovery::open(
+        &mut state,
+        "Amend",
+        Some("could not apply".to_string()),
+        Some(before.clone()),
+        &[],
+        retry,
+    );
+
+    let lines = recovery_lines(&state);
+    assert_eq!(lines[0], "Amend failed: could not apply");
+    assert_eq!(
+        lines[2],
+        format!(
+            "The branch moved to {}, it was at {} before. a restores it and the index.",
+            &git::head_hash(&repo.path).unwrap()[..7],
+            &before.head[..7]
+        )
+    );
+
+    let state = press(state, "a");
+    assert_eq!(git::head_hash(&repo.path).unwrap(), before.head);
+    assert_eq!(commit_subjects(&state), vec!["third", "second", "first"]);
+    assert_eq!(repo.get_status(), "M  b.txt\n");
+}
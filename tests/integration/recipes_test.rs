@@ -0,0 +1,78 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{press, staged_files};
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use git_full_commit::recipe;
+
+/// A change to `a.txt` staged and a local tweak to `config.txt` left
+/// unstaged.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "a\n");
+    repo.create_file("config.txt", "debug = false\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "a2\n");
+    repo.create_file("config.txt", "debug = true\n");
+    git::run_git_command(&repo.path, &["add", "a.txt"]).unwrap();
+
+    let files = git::get_diff(repo.path.clone());
+    let state = AppState::new(repo.path.clone(), files);
+    (repo, state)
+}
+
+#[test]
+fn test_saved_recipe_restages_the_same_selection() {
+    let (repo, state) = setup();
+    let state = press(state, "cwork\n");
+    assert_eq!(state.message_text(), Some("Saved recipe work (1 files)"));
+    assert_eq!(recipe::names(&repo.path), vec!["work"]);
+
+    // Everything staged again, as on a fresh start
+    let state = press(state, "R");
+    assert_eq!(staged_files(&state), vec!["a.txt", "config.txt"]);
+
+    // The only recipe is filled in
+    let state = press(state, "C");
+    assert_eq!(state.prompt.as_ref().unwrap().text, "work");
+    let state = press(state, "\n");
+    assert_eq!(staged_files(&state), vec!["a.txt"]);
+    assert_eq!(state.message_text(), Some("Applied recipe work (1 files)"));
+
+    // One undo step brings back what was staged before
+    let state = press(state, "<");
+    assert_eq!(staged_files(&state), vec!["a.txt", "config.txt"]);
+}
+
+#[test]
+fn test_recipe_leaves_out_changes_not_in_the_working_tree() {
+    let (repo, state) = setup();
+    let state = press(state, "R");
+    let state = press(state, "cboth\n");
+
+    repo.create_file("a.txt", "a3\n");
+    let state = press(state, "C\n");
+    assert_eq!(staged_files(&state), vec!["config.txt"]);
+    assert_eq!(
+        state.message_text(),
+        Some("Applied recipe both (1 files). Not staged, as the working tree differs: a.txt")
+    );
+}
+
+#[test]
+fn test_recipe_errors() {
+    let (repo, state) = setup();
+    let state = press(state, "C");
+    assert!(state.prompt.is_none());
+    assert_eq!(
+        state.message_text(),
+        Some("No staging recipes yet, save one with c")
+    );
+
+    let state = press(state, "ca/b\n");
+    assert_eq!(
+        state.message_text(),
+        Some("Failed to save the recipe: invalid recipe name \"a/b\"")
+    );
+    assert!(recipe::names(&repo.path).is_empty());
+}
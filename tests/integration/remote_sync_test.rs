@@ -0,0 +1,138 @@
+use crate::git_test::common::{TestRepo, run_git};
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn wait_for_remote_sync(state: &mut AppState) {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while state.remote_sync.is_some() {
+        assert!(Instant::now() < deadline, "fetch or pull did not finish");
+        state.poll_background();
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+fn branch(repo: &TestRepo) -> String {
+    git::get_current_branch_name(&repo.path).unwrap()
+}
+
+/// Pushes a commit writing `content` to `name`, then takes it back out of the
+/// local branch, as if someone else had pushed it.
+fn push_commit_from_elsewhere(repo: &TestRepo, name: &str, content: &str) {
+    repo.create_file(name, content);
+    repo.add_all();
+    repo.commit("from elsewhere");
+    repo.push();
+    run_git(&repo.path, &["reset", "-q", "--hard", "HEAD~1"]);
+    let tracking = format!("refs/remotes/origin/{}", branch(repo));
+    run_git(&repo.path, &["update-ref", &tracking, "HEAD"]);
+}
+
+fn head(repo: &TestRepo) -> String {
+    git::head_hash(&repo.path).unwrap()
+}
+
+#[test]
+fn test_fetch_shows_the_new_upstream_commits() {
+    let repo = TestRepo::new();
+    repo.commit("first");
+    repo.push();
+    push_commit_from_elsewhere(&repo, "b.txt", "b\n");
+    let mut state = repo.create_initial_state();
+    assert_eq!(state.branch_status.behind, 0);
+
+    state = update_state(state, Some(Input::Character('G')), 40, 80);
+    assert!(state.remote_sync.is_some());
+    wait_for_remote_sync(&mut state);
+
+    let name = branch(&repo);
+    assert_eq!(
+        state.message_text(),
+        Some(format!("Fetched: {name} → origin/{name}  1 behind").as_str())
+    );
+    assert_eq!(state.branch_status.behind, 1);
+}
+
+#[test]
+fn test_pull_rebases_the_local_commits_and_keeps_what_is_staged() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("first");
+    repo.push();
+    push_commit_from_elsewhere(&repo, "b.txt", "b\n");
+    repo.create_file("c.txt", "c\n");
+    repo.add_all();
+    repo.commit("local");
+    repo.create_file("a.txt", "one\ntwo\n");
+    let mut state = repo.create_initial_state();
+    state = update_state(state, Some(Input::Character('M')), 40, 80);
+    assert_eq!(state.command_history.undo_stack.len(), 1);
+
+    state = update_state(state, Some(Input::Character('L')), 40, 80);
+    wait_for_remote_sync(&mut state);
+    // Undoing would work on the commits from before the rebase
+    assert!(state.command_history.undo_stack.is_empty());
+
+    let name = branch(&repo);
+    assert_eq!(
+        state.message_text(),
+        Some(format!("Pulled: {name} → origin/{name}  1 ahead").as_str())
+    );
+    let subjects: Vec<_> = state
+        .previous_commits
+        .iter()
+        .map(|commit| commit.message.as_str())
+        .collect();
+    assert_eq!(subjects[..2], ["local", "from elsewhere"]);
+    assert_eq!(
+        git::run_git_command(&repo.path, &["diff", "--cached", "--name-only"]).unwrap(),
+        "a.txt\n"
+    );
+}
+
+#[test]
+fn test_pull_leaves_the_branch_alone_on_conflicts() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("first");
+    repo.push();
+    push_commit_from_elsewhere(&repo, "a.txt", "remote\n");
+    repo.create_file("a.txt", "local\n");
+    repo.add_all();
+    repo.commit("local");
+    let before = head(&repo);
+    let mut state = repo.create_initial_state();
+
+    state = update_state(state, Some(Input::Character('L')), 40, 80);
+    wait_for_remote_sync(&mut state);
+
+    let message = state.message_text().unwrap().to_string();
+    assert!(
+        message.starts_with("Pull failed: the local commits conflict with origin/"),
+        "{message}"
+    );
+    assert_eq!(head(&repo), before);
+    assert!(!git::is_rebase_in_progress(&repo.path));
+    assert_eq!(state.branch_status.ahead, 1);
+    assert_eq!(state.branch_status.behind, 1);
+}
+
+#[test]
+fn test_pull_without_an_upstream_is_reported() {
+    let repo = TestRepo::new();
+    repo.commit("first");
+    let mut state = repo.create_initial_state();
+
+    state = update_state(state, Some(Input::Character('L')), 40, 80);
+    wait_for_remote_sync(&mut state);
+
+    assert_eq!(
+        state.message_text(),
+        Some("Pull failed: the branch has no upstream")
+    );
+}
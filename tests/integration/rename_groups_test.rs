@@ -0,0 +1,157 @@
+use crate::git_test::common::{TestRepo, run_git};
+use git_full_commit::app_state::{AppState, FocusedPane};
+use git_full_commit::git::{self, FileStatus};
+use git_full_commit::ui::main_screen::{ListItem, UnstagedListItem};
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+fn setup_moved_directory() -> TestRepo {
+    let repo = TestRepo::new();
+    std::fs::create_dir_all(repo.path.join("old")).unwrap();
+    repo.create_file("old/a.rs", "fn a() {}\n");
+    repo.create_file("old/b.rs", "fn b() {}\n");
+    repo.create_file("old/c.rs", "fn c() {}\n");
+    repo.create_file("other.txt", "other\n");
+    repo.add_all();
+    repo.commit("initial");
+    run_git(&repo.path, &["mv", "old", "new"]);
+    repo.create_file("other.txt", "changed\n");
+    repo.add_all();
+    repo
+}
+
+fn staged_rows(app_state: &AppState) -> Vec<String> {
+    app_state
+        .main_screen
+        .list_items
+        .iter()
+        .filter_map(|item| match item {
+            ListItem::File(file) => Some(file.file_name.clone()),
+            ListItem::RenameGroup { group, .. } => Some(group.label()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_moved_directory_is_collapsed_into_one_row() {
+    let repo = setup_moved_directory();
+    let files = git::get_diff(repo.path.clone());
+    let app_state = AppState::new(repo.path.clone(), files);
+
+    assert_eq!(
+        staged_rows(&app_state),
+        vec!["old/ → new/ (3 files)", "other.txt"]
+    );
+    let Some(ListItem::RenameGroup {
+        group,
+        files,
+        expanded,
+    }) = app_state.main_screen.list_items.get(1)
+    else {
+        panic!("expected a rename group");
+    };
+    assert!(!expanded);
+    assert_eq!(files.len(), 3);
+    assert_eq!(
+        group.renames[0],
+        ("old/a.rs".to_string(), "new/a.rs".to_string())
+    );
+}
+
+#[test]
+fn test_z_expands_and_collapses_group() {
+    let repo = setup_moved_directory();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+    app_state.main_screen.file_cursor = 1;
+
+    app_state = update_state(app_state, Some(Input::Character('z')), 80, 80);
+    assert_eq!(
+        staged_rows(&app_state),
+        vec![
+            "old/ → new/ (3 files)",
+            "new/a.rs",
+            "new/b.rs",
+            "new/c.rs",
+            "other.txt"
+        ]
+    );
+
+    // Collapsing from a file inside the group moves the cursor to its row.
+    app_state.main_screen.file_cursor = 3;
+    app_state = update_state(app_state, Some(Input::Character('z')), 80, 80);
+    assert_eq!(app_state.main_screen.file_cursor, 1);
+    assert_eq!(
+        staged_rows(&app_state),
+        vec!["old/ → new/ (3 files)", "other.txt"]
+    );
+}
+
+#[test]
+fn test_group_is_unstaged_and_staged_again_as_renames() {
+    let repo = setup_moved_directory();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+    app_state.main_screen.file_cursor = 1;
+
+    app_state = update_state(app_state, Some(Input::Character('\n')), 80, 80);
+    assert_eq!(staged_rows(&app_state), vec!["other.txt"]);
+
+    let group = app_state
+        .unstaged_pane
+        .list_items
+        .iter()
+        .find_map(|item| match item {
+            UnstagedListItem::RenameGroup(group) => Some(group.clone()),
+            _ => None,
+        })
+        .expect("a pending rename group");
+    assert_eq!(group.label(), "old/ → new/ (3 files)");
+    assert!(
+        !app_state
+            .unstaged_pane
+            .list_items
+            .iter()
+            .any(|item| matches!(item, UnstagedListItem::UntrackedFile(_)))
+    );
+
+    app_state.focused_pane = FocusedPane::Unstaged;
+    app_state.unstaged_pane.cursor = app_state
+        .unstaged_pane
+        .list_items
+        .iter()
+        .position(|item| matches!(item, UnstagedListItem::RenameGroup(_)))
+        .unwrap();
+    app_state = update_state(app_state, Some(Input::Character('\n')), 80, 80);
+
+    assert_eq!(
+        staged_rows(&app_state),
+        vec!["old/ → new/ (3 files)", "other.txt"]
+    );
+    assert!(
+        app_state
+            .files
+            .iter()
+            .filter(|f| f.file_name.starts_with("new/"))
+            .all(|f| f.status == FileStatus::Renamed)
+    );
+}
+
+#[test]
+fn test_undo_restores_staged_group() {
+    let repo = setup_moved_directory();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+    app_state.main_screen.file_cursor = 1;
+
+    app_state = update_state(app_state, Some(Input::Character('\n')), 80, 80);
+    assert_eq!(staged_rows(&app_state), vec!["other.txt"]);
+
+    app_state = update_state(app_state, Some(Input::Character('<')), 80, 80);
+    assert_eq!(
+        staged_rows(&app_state),
+        vec!["old/ → new/ (3 files)", "other.txt"]
+    );
+    assert!(!app_state.main_screen.has_unstaged_changes);
+}
@@ -0,0 +1,164 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::{AppState, FocusedPane};
+use git_full_commit::git::{self, FileStatus};
+use git_full_commit::ui::main_screen::{ListItem, UnstagedListItem};
+use git_full_commit::ui::update::update_state;
+use pancurses::Input;
+
+fn setup_committed_file() -> TestRepo {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "hello\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "hello\nworld\n");
+    repo.add_all();
+    repo
+}
+
+#[test]
+fn test_rename_staged_file_with_prompt() {
+    let repo = setup_committed_file();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    app_state = update_state(app_state, Some(Input::Character('r')), 80, 80);
+    let prompt = app_state.prompt.as_ref().expect("prompt should open");
+    assert_eq!(prompt.text, "a.txt");
+
+    // Ctrl-A, Ctrl-K clear the prompt first
+    app_state = press(app_state, "\u{1}\u{b}docs/b.txt");
+    app_state = update_state(app_state, Some(Input::Character('\n')), 80, 80);
+
+    assert!(app_state.prompt.is_none());
+    assert_eq!(app_state.files.len(), 1);
+    assert_eq!(app_state.files[0].file_name, "docs/b.txt");
+    assert_eq!(app_state.files[0].status, FileStatus::Renamed);
+    assert!(matches!(
+        &app_state.main_screen.list_items[app_state.main_screen.file_cursor],
+        ListItem::File(f) if f.file_name == "docs/b.txt"
+    ));
+
+    // Undo restores the original path
+    app_state = update_state(app_state, Some(Input::Character('<')), 80, 80);
+    assert_eq!(app_state.files.len(), 1);
+    assert_eq!(app_state.files[0].file_name, "a.txt");
+    assert!(!repo.path.join("docs").exists());
+
+    // Redo renames it again
+    app_state = update_state(app_state, Some(Input::Character('>')), 80, 80);
+    assert_eq!(app_state.files[0].file_name, "docs/b.txt");
+}
+
+#[test]
+fn test_rename_prompt_keys_do_not_trigger_commands() {
+    let repo = setup_committed_file();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    app_state = update_state(app_state, Some(Input::Character('r')), 80, 80);
+    app_state = press(app_state, "uq");
+
+    assert!(app_state.running);
+    assert_eq!(app_state.files.len(), 1);
+    assert_eq!(app_state.prompt.as_ref().unwrap().text, "a.txtuq");
+}
+
+#[test]
+fn test_rename_prompt_cancel() {
+    let repo = setup_committed_file();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    app_state = update_state(app_state, Some(Input::Character('r')), 80, 80);
+    app_state = press(app_state, ".bak");
+    app_state = update_state(app_state, Some(Input::Character('\u{1b}')), 80, 80);
+
+    assert!(app_state.prompt.is_none());
+    assert_eq!(app_state.files[0].file_name, "a.txt");
+    assert!(repo.path.join("a.txt").exists());
+}
+
+#[test]
+fn test_rename_to_existing_file_shows_error() {
+    let repo = setup_committed_file();
+    repo.create_file("b.txt", "other");
+    repo.add_all();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    app_state = update_state(app_state, Some(Input::Character('r')), 80, 80);
+    app_state = press(app_state, "\u{1}\u{b}b.txt");
+    app_state = update_state(app_state, Some(Input::Character('\n')), 80, 80);
+
+    assert!(app_state.prompt.is_none());
+    assert!(app_state.message.is_some());
+    assert!(repo.path.join("a.txt").exists());
+}
+
+#[test]
+fn test_rename_outside_the_repository_is_refused() {
+    let repo = setup_committed_file();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    app_state = update_state(app_state, Some(Input::Character('r')), 80, 80);
+    app_state = press(app_state, "\u{1}\u{b}../moved.txt");
+    app_state = update_state(app_state, Some(Input::Character('\n')), 80, 80);
+
+    assert_eq!(
+        app_state.message_text(),
+        Some("Cannot rename: ../moved.txt is outside the repository")
+    );
+    assert!(repo.path.join("a.txt").exists());
+    assert!(!repo.path.join("../moved.txt").exists());
+}
+
+#[test]
+fn test_rename_unstaged_file() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "hello\n");
+    repo.create_file("c.txt", "c\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "hello\nworld\n");
+    repo.create_file("c.txt", "c\nc\n");
+    repo.add_all();
+    repo.create_file("a.txt", "hello\nworld\nagain\n");
+
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+    app_state.focused_pane = FocusedPane::Unstaged;
+    app_state.unstaged_pane.cursor = 1;
+
+    app_state = update_state(app_state, Some(Input::Character('r')), 80, 80);
+    app_state = press(app_state, "\u{1}\u{b}b.txt");
+    app_state = update_state(app_state, Some(Input::Character('\n')), 80, 80);
+
+    assert!(app_state.files.iter().any(|f| f.file_name == "b.txt"));
+    assert!(matches!(
+        &app_state.unstaged_pane.list_items[app_state.unstaged_pane.cursor],
+        UnstagedListItem::File(f) if f.file_name == "b.txt"
+    ));
+    let content = std::fs::read_to_string(repo.path.join("b.txt")).unwrap();
+    assert_eq!(content, "hello\nworld\nagain\n");
+}
+
+#[test]
+fn test_failed_rename_tells_why() {
+    let repo = setup_committed_file();
+    repo.create_file("notes", "a file, not a directory\n");
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+
+    app_state = update_state(app_state, Some(Input::Character('r')), 80, 80);
+    app_state = press(app_state, "\u{1}\u{b}notes/a.txt");
+    app_state = update_state(app_state, Some(Input::Character('\n')), 80, 80);
+
+    let message = app_state.message_text().unwrap_or_default().to_string();
+    assert!(
+        message.starts_with("Failed to rename a.txt to notes/a.txt: "),
+        "{message}"
+    );
+    assert_eq!(app_state.files[0].file_name, "a.txt");
+}
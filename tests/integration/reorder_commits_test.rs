@@ -63,7 +63,8 @@ fn test_discard_commit_in_reorder_mode() {
     state = update_state(state, Some(Input::Character('!')), 80, 80);
     assert_commit_list(&state.main_screen.list_items, &["commit 2", "commit 0"]);
 
-    // Confirm reorder
+    // Confirm reorder and its plan
+    state = update_state(state, Some(Input::Character('\n')), 80, 80);
     state = update_state(state, Some(Input::Character('\n')), 80, 80);
 
     assert_eq!(
@@ -115,3 +116,122 @@ fn test_undo_discard_in_reorder_mode() {
         vec!["commit 2", "commit 1", "commit 0"]
     );
 }
+
+fn commit_messages(state: &git_full_commit::app_state::AppState) -> Vec<String> {
+    state
+        .previous_commits
+        .iter()
+        .map(|c| c.message.clone())
+        .collect()
+}
+
+#[test]
+fn test_grab_mode_moves_commit_with_arrows() {
+    let repo = TestRepo::new();
+    repo.commit("commit 0");
+    repo.commit("commit 1");
+    repo.commit("commit 2");
+
+    let mut state = repo.create_initial_state();
+
+    select_commit_in_log(&mut state, 2);
+    state = update_state(state, Some(Input::Character('g')), 80, 80);
+    assert!(state.main_screen.is_reordering_commits);
+    assert!(state.main_screen.is_grabbing_commit);
+
+    // The grabbed commit follows the arrows; nothing is rewritten yet.
+    state = update_state(state, Some(Input::KeyDown), 80, 80);
+    state = update_state(state, Some(Input::KeyDown), 80, 80);
+    assert_commit_list(
+        &state.main_screen.list_items,
+        &["commit 1", "commit 0", "commit 2"],
+    );
+    assert_eq!(
+        commit_messages(&state),
+        vec!["commit 2", "commit 1", "commit 0"]
+    );
+
+    state = update_state(state, Some(Input::KeyUp), 80, 80);
+    assert_commit_list(
+        &state.main_screen.list_items,
+        &["commit 1", "commit 2", "commit 0"],
+    );
+
+    state = update_state(state, Some(Input::Character('\n')), 80, 80);
+    state = update_state(state, Some(Input::Character('\n')), 80, 80);
+    assert!(!state.main_screen.is_reordering_commits);
+    assert!(!state.main_screen.is_grabbing_commit);
+    assert_eq!(
+        commit_messages(&state),
+        vec!["commit 1", "commit 2", "commit 0"]
+    );
+}
+
+#[test]
+fn test_grab_mode_escape_cancels() {
+    let repo = TestRepo::new();
+    repo.commit("commit 0");
+    repo.commit("commit 1");
+
+    let mut state = repo.create_initial_state();
+
+    select_commit_in_log(&mut state, 1);
+    state = update_state(state, Some(Input::Character('g')), 80, 80);
+    state = update_state(state, Some(Input::KeyDown), 80, 80);
+    assert_commit_list(&state.main_screen.list_items, &["commit 0", "commit 1"]);
+
+    state = update_state(state, Some(Input::Character('\u{1b}')), 80, 80);
+    assert!(!state.main_screen.is_reordering_commits);
+    assert!(!state.main_screen.is_grabbing_commit);
+    assert_commit_list(&state.main_screen.list_items, &["commit 1", "commit 0"]);
+    assert_eq!(commit_messages(&state), vec!["commit 1", "commit 0"]);
+}
+
+#[test]
+fn test_grab_mode_release_and_grab_another_commit() {
+    let repo = TestRepo::new();
+    repo.commit("commit 0");
+    repo.commit("commit 1");
+    repo.commit("commit 2");
+
+    let mut state = repo.create_initial_state();
+
+    select_commit_in_log(&mut state, 2);
+    state = update_state(state, Some(Input::Character('g')), 80, 80);
+    state = update_state(state, Some(Input::Character('g')), 80, 80);
+    assert!(!state.main_screen.is_grabbing_commit);
+
+    // Released: the arrows only move the selection.
+    state = update_state(state, Some(Input::KeyDown), 80, 80);
+    state = update_state(state, Some(Input::KeyDown), 80, 80);
+    assert_commit_list(
+        &state.main_screen.list_items,
+        &["commit 2", "commit 1", "commit 0"],
+    );
+
+    state = update_state(state, Some(Input::Character('g')), 80, 80);
+    state = update_state(state, Some(Input::KeyUp), 80, 80);
+    assert_commit_list(
+        &state.main_screen.list_items,
+        &["commit 2", "commit 0", "commit 1"],
+    );
+}
+
+#[test]
+fn test_grab_ignores_pushed_commits() {
+    let repo = TestRepo::new();
+    repo.commit("commit 0");
+    repo.push();
+    repo.commit("commit 1");
+
+    let mut state = repo.create_initial_state();
+
+    select_commit_in_log(&mut state, 0);
+    state = update_state(state, Some(Input::Character('g')), 80, 80);
+    assert!(!state.main_screen.is_reordering_commits);
+
+    select_commit_in_log(&mut state, 1);
+    state = update_state(state, Some(Input::Character('g')), 80, 80);
+    state = update_state(state, Some(Input::KeyDown), 80, 80);
+    assert_commit_list(&state.main_screen.list_items, &["commit 1", "commit 0"]);
+}
@@ -0,0 +1,46 @@
+use crate::git_test::common::run_git;
+use git_full_commit::git;
+use git_full_commit::repo_init;
+use std::fs;
+use tempfile::TempDir;
+
+#[test]
+fn test_initial_commit_of_a_generated_gitignore() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path();
+    fs::write(path.join("Cargo.toml"), "[package]\n").unwrap();
+
+    repo_init::init(path).unwrap();
+    run_git(path, &["config", "user.name", "Test"]);
+    run_git(path, &["config", "user.email", "test@example.com"]);
+    repo_init::initial_commit(path).unwrap();
+
+    let gitignore = fs::read_to_string(path.join(".gitignore")).unwrap();
+    assert!(gitignore.lines().any(|line| line == "/target"));
+    let commits = git::get_local_commits(path).unwrap();
+    assert_eq!(commits.len(), 1);
+    assert_eq!(commits[0].message, "Initial commit");
+    // The rest is left to pick on the screen
+    assert_eq!(
+        git::get_untracked_files(path).unwrap(),
+        vec!["Cargo.toml".to_string()]
+    );
+}
+
+#[test]
+fn test_initial_commit_keeps_an_existing_gitignore() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path();
+    fs::write(path.join(".gitignore"), "secret.txt\n").unwrap();
+
+    repo_init::init(path).unwrap();
+    run_git(path, &["config", "user.name", "Test"]);
+    run_git(path, &["config", "user.email", "test@example.com"]);
+    repo_init::initial_commit(path).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(path.join(".gitignore")).unwrap(),
+        "secret.txt\n"
+    );
+    assert_eq!(git::get_local_commits(path).unwrap().len(), 1);
+}
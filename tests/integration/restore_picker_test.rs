@@ -0,0 +1,89 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{open_view, press, send};
+use git_full_commit::app_state::{AppState, Screen};
+use git_full_commit::git;
+use pancurses::Input;
+
+fn content(repo: &TestRepo) -> String {
+    std::fs::read_to_string(repo.path.join("a.txt")).unwrap()
+}
+
+/// `a.txt` changed in three commits and edited in the working tree, with
+/// the unstaged pane on it.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    for version in ["one", "two", "three"] {
+        repo.create_file("a.txt", &format!("{version}\n"));
+        repo.create_file("other.txt", &format!("{version}\n"));
+        repo.add_all();
+        repo.commit(version);
+    }
+    repo.create_file("a.txt", "edited\n");
+
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    let state = send(press(state, "\t"), Input::KeyDown);
+    assert_eq!(
+        state
+            .get_unstaged_file()
+            .map(|file| file.file_name.as_str()),
+        Some("a.txt")
+    );
+    (repo, state)
+}
+
+#[test]
+fn test_restore_from_an_older_commit_and_undo() {
+    let (repo, state) = setup();
+    let state = press(state, "O");
+    let picker = open_view!(state.screen, Screen::RestorePicker);
+    let subjects: Vec<&str> = picker
+        .commits
+        .iter()
+        .map(|commit| commit.message.as_str())
+        .collect();
+    assert_eq!(subjects, vec!["three", "two", "one"]);
+
+    let hash = picker.commits[1].hash.clone();
+    let state = press(state, "j\n");
+    assert!(!matches!(state.screen, Some(Screen::RestorePicker(_))));
+    assert_eq!(content(&repo), "two\n");
+    assert_eq!(
+        state.message_text(),
+        Some(format!("Restored a.txt from {hash} to the working tree").as_str())
+    );
+    assert!(state.files.is_empty());
+
+    // The edit it overwrote comes back
+    let state = press(state, "<");
+    assert_eq!(content(&repo), "edited\n");
+    assert!(state.files.is_empty());
+}
+
+#[test]
+fn test_restore_into_the_index_too() {
+    let (repo, state) = setup();
+    let state = press(state, "Ojjs");
+    assert_eq!(content(&repo), "one\n");
+    assert_eq!(repo.get_status(), "M  a.txt\n");
+    assert_eq!(state.files.len(), 1);
+
+    let state = press(state, "<");
+    assert_eq!(content(&repo), "edited\n");
+    assert_eq!(repo.get_status(), " M a.txt\n");
+    assert!(state.files.is_empty());
+}
+
+#[test]
+fn test_file_without_history_has_nothing_to_restore_from() {
+    let (repo, _state) = setup();
+    repo.create_file("new.txt", "new\n");
+    git::run_git_command(&repo.path, &["add", "new.txt"]).unwrap();
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+
+    let state = press(state, "O");
+    assert!(!matches!(state.screen, Some(Screen::RestorePicker(_))));
+    assert_eq!(
+        state.message_text(),
+        Some("No commit has new.txt to restore it from")
+    );
+}
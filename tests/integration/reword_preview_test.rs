@@ -0,0 +1,110 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{open_view, press, send};
+use git_full_commit::app_state::{AppState, Modal};
+use git_full_commit::git;
+use git_full_commit::ui::main_screen::ListItem as MainScreenListItem;
+use git_full_commit::ui::update::update_state_with_alt;
+use pancurses::Input;
+
+fn confirm(state: AppState) -> AppState {
+    update_state_with_alt(state, Some(Input::Character('\n')), 40, 80)
+}
+
+/// Starts rewording a commit with a body, the only one in the log.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "hello");
+    repo.add_all();
+    git::run_git_command(
+        &repo.path,
+        &[
+            "commit",
+            "-q",
+            "-m",
+            "Add greeting",
+            "-m",
+            "Why it is needed",
+        ],
+    )
+    .unwrap();
+
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new(repo.path.clone(), files);
+    // [Staged header, Input, Commit]
+    state.main_screen.file_cursor = 2;
+    let state = send(state, Input::Character('\n'));
+    (repo, state)
+}
+
+fn log(repo: &TestRepo) -> String {
+    git::run_git_command(&repo.path, &["log", "--format=%B"]).unwrap()
+}
+
+#[test]
+fn test_preview_shows_both_messages() {
+    let (repo, state) = setup();
+    let state = press(state, "s");
+    let state = confirm(state);
+
+    let preview = open_view!(state.modal, Modal::RewordPreview);
+    assert_eq!(preview.old_message, "Add greeting\n\nWhy it is needed");
+    assert_eq!(preview.new_message, "Add greetings\n\nWhy it is needed");
+    assert_eq!(preview.lost_body_lines(), 0);
+    // Nothing was rewritten yet
+    assert_eq!(log(&repo), "Add greeting\n\nWhy it is needed\n\n");
+
+    let state = send(state, Input::Character('\n'));
+    assert!(!matches!(state.modal, Some(Modal::RewordPreview(_))));
+    assert_eq!(log(&repo), "Add greetings\n\nWhy it is needed\n\n");
+}
+
+#[test]
+fn test_removing_the_body_is_warned_about() {
+    let (repo, state) = setup();
+    // Join the blank line and the body to the subject, then cut them
+    let state = press(state, "\u{b}\u{b}\u{b}");
+    assert!(matches!(
+        &state.main_screen.list_items[2],
+        MainScreenListItem::AmendingCommitMessageInput { message, .. } if message == "Add greeting"
+    ));
+    let state = confirm(state);
+    let preview = open_view!(state.modal, Modal::RewordPreview);
+    assert_eq!(preview.lost_body_lines(), 1);
+
+    send(state, Input::Character('\n'));
+    assert_eq!(log(&repo), "Add greeting\n\n");
+}
+
+#[test]
+fn test_escape_goes_back_to_editing() {
+    let (repo, state) = setup();
+    let state = confirm(send(state, Input::Character('!')));
+    assert!(matches!(state.modal, Some(Modal::RewordPreview(_))));
+
+    let state = send(state, Input::Character('\u{1b}'));
+    assert!(!matches!(state.modal, Some(Modal::RewordPreview(_))));
+    assert!(matches!(
+        &state.main_screen.list_items[2],
+        MainScreenListItem::AmendingCommitMessageInput { message, .. } if message == "Add greeting!\n\nWhy it is needed"
+    ));
+    assert_eq!(log(&repo), "Add greeting\n\nWhy it is needed\n\n");
+}
+
+#[test]
+fn test_unchanged_message_is_not_previewed() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "hello");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("b.txt", "more");
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new(repo.path.clone(), files);
+    // [Staged header, b.txt, Input, Commit]
+    state.main_screen.file_cursor = 3;
+    let state = confirm(send(state, Input::Character('\n')));
+    assert!(!matches!(state.modal, Some(Modal::RewordPreview(_))));
+    let files = git::run_git_command(&repo.path, &["show", "--name-only", "--format="]).unwrap();
+    assert_eq!(files, "a.txt\nb.txt\n");
+}
@@ -0,0 +1,51 @@
+use crate::git_test::common::TestRepo;
+use git_full_commit::app_state::{AppState, FocusedPane};
+use git_full_commit::git;
+use git_full_commit::ui::chrome;
+use git_full_commit::ui::main_screen;
+
+#[test]
+fn test_breadcrumb_skips_empty_parts() {
+    assert_eq!(
+        chrome::breadcrumb("repo", &["History", "abc1234 fix"]),
+        "repo ▸ History ▸ abc1234 fix"
+    );
+    assert_eq!(
+        chrome::breadcrumb("repo", &["Commit message", ""]),
+        "repo ▸ Commit message"
+    );
+}
+
+#[test]
+fn test_main_screen_breadcrumb_follows_the_cursor() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "1\n");
+    repo.create_file("b.txt", "1\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "2\n");
+    repo.create_file("b.txt", "2\n");
+    repo.add_all();
+    repo.create_file("b.txt", "3\n");
+    let mut state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    let name = chrome::repo_name(&repo.path);
+
+    state.main_screen.file_cursor = 1;
+    assert_eq!(
+        main_screen::breadcrumb(&state),
+        format!("{name} ▸ Staged ▸ a.txt")
+    );
+
+    state.main_screen.file_cursor = 3;
+    assert_eq!(
+        main_screen::breadcrumb(&state),
+        format!("{name} ▸ Commit message")
+    );
+
+    state.focused_pane = FocusedPane::Unstaged;
+    state.unstaged_pane.cursor = 1;
+    assert_eq!(
+        main_screen::breadcrumb(&state),
+        format!("{name} ▸ Unstaged ▸ b.txt")
+    );
+}
@@ -0,0 +1,152 @@
+use crate::git_test::common::run_git;
+use crate::integration::common::{TestRepo, press, select_commit_in_log};
+use git_full_commit::app_state::{AppState, FocusedPane};
+use git_full_commit::split_commit;
+use git_full_commit::ui::commit_view;
+
+fn subjects(repo: &TestRepo) -> Vec<String> {
+    let output = std::process::Command::new("git")
+        .args(["log", "--format=%s"])
+        .current_dir(&repo.path)
+        .output()
+        .unwrap();
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+fn head(repo: &TestRepo) -> String {
+    git_full_commit::git::run_git_command(&repo.path, &["rev-parse", "HEAD"])
+        .unwrap()
+        .trim()
+        .to_string()
+}
+
+/// `both` changes `a.txt` and `b.txt`, and `later` after it changes
+/// `c.txt`.
+fn setup() -> TestRepo {
+    let repo = TestRepo::new();
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        repo.create_file(name, "one\n");
+    }
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "two\n");
+    repo.create_file("b.txt", "two\n");
+    repo.add_all();
+    repo.commit("both");
+    repo.create_file("c.txt", "two\n");
+    repo.add_all();
+    repo.commit("later");
+    repo
+}
+
+/// Splits `both`, the second commit of `setup`.
+fn start_split(repo: &TestRepo) -> AppState {
+    let mut state = repo.create_initial_state();
+    select_commit_in_log(&mut state, 1);
+    let hash = state.previous_commits[1].hash.clone();
+    let state = press(state, "S");
+    assert_eq!(
+        state.message_text(),
+        Some(
+            format!("Stage and commit the first part of {hash} both, S restores the original")
+                .as_str()
+        )
+    );
+    state
+}
+
+#[test]
+fn test_split_commit_in_two_and_replay_the_later_ones() {
+    let repo = setup();
+    let state = start_split(&repo);
+    assert!(state.split.is_some());
+    assert_eq!(state.focused_pane, FocusedPane::Unstaged);
+    assert_eq!(state.main_screen.commit_message, "both");
+    assert_eq!(repo.get_status(), " M a.txt\n M b.txt\n");
+    assert_eq!(subjects(&repo), vec!["initial"]);
+
+    let mut state = state;
+    run_git(&repo.path, &["add", "a.txt"]);
+    state.refresh_diff(true);
+    commit_view::commit(&mut state, "change a", false);
+    assert!(state.running);
+    // What is left is staged for the next part
+    assert_eq!(repo.get_status(), "M  b.txt\n");
+
+    commit_view::commit(&mut state, "change b", false);
+    assert!(state.split.is_none());
+    assert!(!state.running);
+    assert_eq!(
+        subjects(&repo),
+        vec!["later", "change b", "change a", "initial"]
+    );
+    assert_eq!(
+        std::fs::read_to_string(repo.path.join("c.txt")).unwrap(),
+        "two\n"
+    );
+    assert!(!split_commit::is_left_over(&repo.path));
+}
+
+#[test]
+fn test_split_can_be_restored() {
+    let repo = setup();
+    let original = head(&repo);
+    let mut state = start_split(&repo);
+    let hash = state.split.as_ref().unwrap().hash.clone();
+    run_git(&repo.path, &["add", "a.txt"]);
+    state.refresh_diff(true);
+    commit_view::commit(&mut state, "change a", false);
+
+    let state = press(state, "S");
+    assert_eq!(
+        state.message_text(),
+        Some(format!("Restored {hash} both from before the split").as_str())
+    );
+    assert!(state.split.is_none());
+    assert_eq!(head(&repo), original);
+    assert_eq!(repo.get_status(), "");
+    assert!(!split_commit::is_left_over(&repo.path));
+}
+
+#[test]
+fn test_unfinished_split_is_reported_on_startup() {
+    let repo = setup();
+    let _state = start_split(&repo);
+
+    let state = repo.create_initial_state();
+    assert_eq!(
+        state.message_text(),
+        Some(
+            "A split was not finished, git reset --hard refs/git-full-commit/split restores the branch from before it"
+        )
+    );
+}
+
+#[test]
+fn test_split_is_refused_with_changes_or_on_a_remote() {
+    let repo = setup();
+    repo.create_file("a.txt", "three\n");
+    let mut state = repo.create_initial_state();
+    select_commit_in_log(&mut state, 1);
+    let state = press(state, "S");
+    assert_eq!(
+        state.message_text(),
+        Some("Commit or stash the changes before splitting a commit")
+    );
+    assert!(state.split.is_none());
+
+    let repo = setup();
+    repo.push();
+    let mut state = repo.create_initial_state();
+    assert!(state.previous_commits[0].is_on_remote);
+    let newest = state.previous_commits.len() - 1;
+    select_commit_in_log(&mut state, newest);
+    let state = press(state, "S");
+    assert_eq!(
+        state.message_text(),
+        Some("Commits on a remote cannot be split")
+    );
+}
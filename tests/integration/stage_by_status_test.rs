@@ -0,0 +1,71 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+
+/// `a.txt` and `b.txt` modified, `c.txt` and `d.txt` deleted and `e.txt`
+/// untracked, with nothing staged.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    for name in ["a.txt", "b.txt", "c.txt", "d.txt"] {
+        repo.create_file(name, "one\n");
+    }
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "two\n");
+    repo.create_file("b.txt", "two\n");
+    std::fs::remove_file(repo.path.join("c.txt")).unwrap();
+    std::fs::remove_file(repo.path.join("d.txt")).unwrap();
+    repo.create_file("e.txt", "new\n");
+
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    (repo, state)
+}
+
+#[test]
+fn test_stage_modifications_leaves_deletions_unstaged() {
+    let (repo, state) = setup();
+
+    let state = press(state, "M");
+    assert_eq!(
+        state.message_text(),
+        Some("Staged 2 modified files, left 2 deletions unstaged")
+    );
+    assert_eq!(
+        repo.get_status(),
+        "M  a.txt\nM  b.txt\n D c.txt\n D d.txt\n?? e.txt\n"
+    );
+
+    // One undo step unstages all of them
+    let state = press(state, "<");
+    assert_eq!(
+        repo.get_status(),
+        " M a.txt\n M b.txt\n D c.txt\n D d.txt\n?? e.txt\n"
+    );
+
+    let state = press(state, "K");
+    assert_eq!(
+        state.message_text(),
+        Some("Staged 2 deletions, left 2 modified files unstaged")
+    );
+    assert_eq!(
+        repo.get_status(),
+        " M a.txt\n M b.txt\nD  c.txt\nD  d.txt\n?? e.txt\n"
+    );
+}
+
+#[test]
+fn test_nothing_left_to_stage_is_refused() {
+    let (repo, state) = setup();
+    let state = press(state, "M");
+
+    let state = press(state, "M");
+    assert_eq!(state.message_text(), Some("No modified files to stage"));
+
+    let state = press(state, "K");
+    assert_eq!(state.message_text(), Some("Staged 2 deletions"));
+    assert_eq!(
+        repo.get_status(),
+        "M  a.txt\nM  b.txt\nD  c.txt\nD  d.txt\n?? e.txt\n"
+    );
+}
@@ -0,0 +1,76 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+
+/// Two subprojects with a change each and a new file in `web`. Nothing is
+/// staged.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    for dir in ["api", "web"] {
+        std::fs::create_dir_all(repo.path.join(dir)).unwrap();
+        repo.create_file(&format!("{dir}/main.rs"), "fn main() {}\n");
+    }
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("api/main.rs", "fn main() { api() }\n");
+    repo.create_file("web/main.rs", "fn main() { web() }\n");
+    repo.create_file("web/page.html", "<p>\n");
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    (repo, state)
+}
+
+#[test]
+fn test_add_stages_one_subproject_as_one_undo_step() {
+    let (repo, state) = setup();
+    let state = press(state, ":add web/**\n");
+    assert_eq!(state.message_text(), Some("Staged 2 files matching web/**"));
+    assert_eq!(
+        repo.get_status(),
+        " M api/main.rs\nM  web/main.rs\nA  web/page.html\n"
+    );
+
+    let state = press(state, "<");
+    assert_eq!(
+        repo.get_status(),
+        " M api/main.rs\n M web/main.rs\n?? web/page.html\n"
+    );
+    assert!(state.files.is_empty());
+
+    let _state = press(state, ">");
+    assert_eq!(
+        repo.get_status(),
+        " M api/main.rs\nM  web/main.rs\nA  web/page.html\n"
+    );
+}
+
+#[test]
+fn test_add_takes_several_pathspecs() {
+    let (repo, state) = setup();
+    let state = press(state, ":add api/ *.html\n");
+    assert_eq!(
+        state.message_text(),
+        Some("Staged 2 files matching api/ *.html")
+    );
+    assert_eq!(
+        repo.get_status(),
+        "M  api/main.rs\n M web/main.rs\nA  web/page.html\n"
+    );
+}
+
+#[test]
+fn test_add_matching_nothing_is_refused() {
+    let (repo, state) = setup();
+    let state = press(state, ":add *.toml\n");
+    assert_eq!(state.message_text(), Some("No changes match *.toml"));
+    let state = press(state, ":add\n");
+    assert_eq!(
+        state.message_text(),
+        Some("Give the files, like *.rs or src/**")
+    );
+    assert!(state.files.is_empty());
+    assert_eq!(
+        repo.get_status(),
+        " M api/main.rs\n M web/main.rs\n?? web/page.html\n"
+    );
+}
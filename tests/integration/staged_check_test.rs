@@ -0,0 +1,71 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{log_subjects, open_view, press, send};
+use git_full_commit::app_state::{AppState, Modal};
+use git_full_commit::config::Config;
+use git_full_commit::git;
+use git_full_commit::ui::update::update_state_with_alt;
+use pancurses::Input;
+
+/// `lib.txt` renames a function and is staged, while the caller in
+/// `main.txt` is only fixed in the working tree. The check fails when
+/// `main.txt` calls a function `lib.txt` does not define.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("lib.txt", "old_name\n");
+    repo.create_file("main.txt", "old_name\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("lib.txt", "new_name\n");
+    repo.add_all();
+    repo.create_file("main.txt", "new_name\n");
+
+    let config = Config::parse(
+        "staged_check_command = \"grep -qxf main.txt lib.txt || { echo main.txt calls $(cat main.txt); exit 1; }\"\n",
+    );
+    let state =
+        AppState::new_with_config(repo.path.clone(), git::get_diff(repo.path.clone()), config);
+    let state = press(send(state, Input::KeyDown), "Rename");
+    (repo, state)
+}
+
+#[test]
+fn test_failing_check_of_the_staged_tree_stops_the_commit() {
+    let (repo, state) = setup();
+    let state = update_state_with_alt(state, Some(Input::Character('\n')), 40, 80);
+
+    let output = open_view!(state.modal, Modal::HookOutput);
+    assert_eq!(output.failure.hooks, vec!["staged tree check".to_string()]);
+    assert_eq!(output.failure.output, "main.txt calls old_name\n");
+    assert_eq!(log_subjects(&repo), "initial\n");
+    // The working tree and the index are left alone
+    assert_eq!(repo.get_status(), "M  lib.txt\n M main.txt\n");
+
+    // Skipping the hooks skips the check too
+    let state = press(state, "n");
+    assert!(!matches!(state.modal, Some(Modal::HookOutput(_))));
+    assert_eq!(log_subjects(&repo), "Rename\ninitial\n");
+}
+
+#[test]
+fn test_passing_check_commits() {
+    let (repo, state) = setup();
+    git::stage_file(&repo.path, "main.txt").unwrap();
+    let mut state = state;
+    state.refresh_diff(false);
+
+    let state = update_state_with_alt(state, Some(Input::Character('\n')), 40, 80);
+    assert!(!matches!(state.modal, Some(Modal::HookOutput(_))));
+    assert_eq!(log_subjects(&repo), "Rename\ninitial\n");
+}
+
+#[test]
+fn test_check_command_is_read_from_config() {
+    assert_eq!(
+        Config::parse("staged_check_command = \"cargo check\"\n").staged_check_command,
+        Some("cargo check".to_string())
+    );
+    assert_eq!(
+        Config::parse("staged_check_command = \" \"\n").staged_check_command,
+        None
+    );
+}
@@ -0,0 +1,120 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::AppState;
+use git_full_commit::config::Config;
+use git_full_commit::git;
+use git_full_commit::staged_test::Status;
+use std::time::{Duration, Instant};
+
+/// Polls the background worker until the staged test is done.
+fn wait_for_test(mut state: AppState) -> AppState {
+    let started = Instant::now();
+    while state
+        .staged_test
+        .as_ref()
+        .is_some_and(|test| test.status == Status::Running)
+    {
+        assert!(
+            started.elapsed() < Duration::from_secs(20),
+            "test timed out"
+        );
+        std::thread::sleep(Duration::from_millis(20));
+        state.poll_background();
+    }
+    state
+}
+
+/// `a.txt` is staged as `staged`, and changed on to `good` in the
+/// working tree. The test passes only if `a.txt` says `good`.
+fn setup(extra_config: &str) -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "initial\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "staged\n");
+    repo.add_all();
+    repo.create_file("a.txt", "good\n");
+
+    let config = Config::parse(&format!(
+        "staged_test_command = \"grep -q good a.txt || {{ echo a.txt is not good; exit 1; }}\"\n{extra_config}"
+    ));
+    let state =
+        AppState::new_with_config(repo.path.clone(), git::get_diff(repo.path.clone()), config);
+    (repo, state)
+}
+
+#[test]
+fn test_staged_tree_is_tested_on_request() {
+    let (repo, state) = setup("");
+    assert!(state.staged_test.is_none());
+
+    let state = press(state, "t");
+    assert_eq!(
+        state.message_text(),
+        Some(
+            "Testing the staged tree with grep -q good a.txt || { echo a.txt is not good; exit 1; }"
+        )
+    );
+    let state = wait_for_test(state);
+    let test = state.staged_test.as_ref().unwrap();
+    assert_eq!(
+        test.status,
+        Status::Failed("a.txt is not good\n".to_string())
+    );
+    assert_eq!(test.label(state.staged_tree.as_deref()), "tests failed");
+    assert_eq!(
+        state.message_text(),
+        Some("Staged tests failed: a.txt is not good")
+    );
+
+    // Staging more makes the result outdated until it is run again
+    repo.add_all();
+    let mut state = state;
+    state.refresh_diff(false);
+    let test = state.staged_test.as_ref().unwrap();
+    assert_eq!(test.label(state.staged_tree.as_deref()), "tests outdated");
+
+    let state = wait_for_test(press(state, "t"));
+    let test = state.staged_test.as_ref().unwrap();
+    assert_eq!(test.status, Status::Passed);
+    assert_eq!(test.label(state.staged_tree.as_deref()), "tests passed");
+}
+
+#[test]
+fn test_staged_tree_is_tested_whenever_it_changes_with_auto() {
+    let (repo, state) = setup("staged_test_auto = true\n");
+    let state = wait_for_test(state);
+    assert!(matches!(
+        state.staged_test.as_ref().unwrap().status,
+        Status::Failed(_)
+    ));
+
+    repo.add_all();
+    let mut state = state;
+    state.refresh_diff(false);
+    let state = wait_for_test(state);
+    let test = state.staged_test.as_ref().unwrap();
+    assert_eq!(test.status, Status::Passed);
+    assert_eq!(state.staged_tree.as_deref(), Some(test.tree.as_str()));
+}
+
+#[test]
+fn test_staged_test_needs_a_command_and_staged_changes() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "a\n");
+    repo.add_all();
+    repo.commit("initial");
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    let state = press(state, "t");
+    assert_eq!(
+        state.message_text(),
+        Some("Set staged_test_command to test the staged tree")
+    );
+
+    let (repo, state) = setup("");
+    git::run_git_command(&repo.path, &["reset", "-q"]).unwrap();
+    let mut state = state;
+    state.refresh_diff(false);
+    let state = press(state, "t");
+    assert_eq!(state.message_text(), Some("Nothing is staged to test"));
+}
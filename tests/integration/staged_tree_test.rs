@@ -0,0 +1,65 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::AppState;
+use git_full_commit::config::Config;
+use git_full_commit::git;
+use std::path::PathBuf;
+
+/// `a.txt` has a staged and a further unstaged change, and `new.txt` is
+/// untracked.
+fn setup(command: &str) -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    std::fs::create_dir(repo.path.join("dir")).unwrap();
+    repo.create_file("dir/b.txt", "b\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "two\n");
+    repo.add_all();
+    repo.create_file("a.txt", "three\n");
+    repo.create_file("new.txt", "new\n");
+
+    let config = Config::parse(&format!("staged_tree_command = \"{command}\"\n"));
+    let state =
+        AppState::new_with_config(repo.path.clone(), git::get_diff(repo.path.clone()), config);
+    (repo, state)
+}
+
+fn exported_dir(message: &str) -> PathBuf {
+    let rest = message
+        .strip_prefix("Exported the staged tree to ")
+        .unwrap_or_else(|| panic!("{message}"));
+    PathBuf::from(rest.split(", could not open it").next().unwrap())
+}
+
+#[test]
+fn test_staged_tree_is_exported_without_unstaged_changes() {
+    let (_repo, state) = setup("true");
+    let state = press(state, "m");
+
+    let message = state.message_text().unwrap().to_string();
+    let dir = exported_dir(&message);
+    assert_eq!(
+        message,
+        format!("Exported the staged tree to {}", dir.display())
+    );
+    assert_eq!(std::fs::read_to_string(dir.join("a.txt")).unwrap(), "two\n");
+    assert_eq!(
+        std::fs::read_to_string(dir.join("dir/b.txt")).unwrap(),
+        "b\n"
+    );
+    assert!(!dir.join("new.txt").exists());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_staged_tree_is_exported_even_if_it_cannot_be_opened() {
+    let (_repo, state) = setup("git-full-commit-no-such-command");
+    let state = press(state, "m");
+
+    let message = state.message_text().unwrap().to_string();
+    let dir = exported_dir(&message);
+    assert!(message.contains(", could not open it: "), "{message}");
+    assert_eq!(std::fs::read_to_string(dir.join("a.txt")).unwrap(), "two\n");
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,119 @@
+use crate::integration::common::{TestRepo, open_view, press};
+use git_full_commit::app_state::{AppState, Screen};
+use git_full_commit::git;
+
+fn setup_repo() -> TestRepo {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "base\n");
+    repo.create_file("b.txt", "base\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo
+}
+
+fn stash_messages(state: &AppState) -> Vec<String> {
+    open_view!(state.screen, Screen::Stashes)
+        .entries
+        .iter()
+        .map(|s| s.message.clone())
+        .collect()
+}
+
+fn read(repo: &TestRepo, name: &str) -> String {
+    std::fs::read_to_string(repo.path.join(name)).unwrap()
+}
+
+#[test]
+fn test_new_stash_takes_unstaged_changes_only() {
+    let repo = setup_repo();
+    repo.create_file("a.txt", "staged\n");
+    repo.add_all();
+    repo.create_file("b.txt", "unstaged\n");
+    repo.create_file("new.txt", "untracked\n");
+
+    let state = press(repo.create_initial_state(), "sn");
+    assert!(state.prompt.is_some());
+    let state = press(state, "work in progress\n");
+
+    let messages = stash_messages(&state);
+    assert_eq!(messages.len(), 1);
+    assert!(messages[0].ends_with(": work in progress"), "{messages:?}");
+    assert_eq!(read(&repo, "a.txt"), "staged\n");
+    assert_eq!(read(&repo, "b.txt"), "base\n");
+    assert!(!repo.path.join("new.txt").exists());
+    assert_eq!(state.files.len(), 1);
+    assert!(!state.main_screen.has_unstaged_changes);
+}
+
+#[test]
+fn test_enter_shows_stash_diff() {
+    let repo = setup_repo();
+    repo.create_file("b.txt", "unstaged\n");
+    git::stash_push(&repo.path, "wip", false).unwrap();
+
+    let state = press(repo.create_initial_state(), "s\n");
+    let lines: Vec<String> = open_view!(state.screen, Screen::Stashes)
+        .diff
+        .as_ref()
+        .expect("diff should be open")
+        .iter()
+        .flat_map(|f| f.lines.clone())
+        .collect();
+    assert!(lines.iter().any(|l| l == "+unstaged"));
+
+    let state = press(state, "\n");
+    assert!(open_view!(state.screen, Screen::Stashes).diff.is_none());
+}
+
+#[test]
+fn test_pop_restores_changes_and_can_be_undone() {
+    let repo = setup_repo();
+    repo.create_file("b.txt", "unstaged\n");
+    git::stash_push(&repo.path, "wip", false).unwrap();
+
+    let state = press(repo.create_initial_state(), "sp");
+    assert!(stash_messages(&state).is_empty());
+    assert_eq!(read(&repo, "b.txt"), "unstaged\n");
+    assert!(state.main_screen.has_unstaged_changes);
+
+    let state = press(state, "\t<");
+    assert!(!matches!(state.screen, Some(Screen::Stashes(_))));
+    assert_eq!(read(&repo, "b.txt"), "base\n");
+    assert_eq!(git::get_stashes(&repo.path).unwrap().len(), 1);
+}
+
+#[test]
+fn test_apply_keeps_stash_and_drop_removes_it() {
+    let repo = setup_repo();
+    repo.create_file("b.txt", "unstaged\n");
+    git::stash_push(&repo.path, "wip", false).unwrap();
+
+    let state = press(repo.create_initial_state(), "sa");
+    assert_eq!(stash_messages(&state).len(), 1);
+    assert_eq!(read(&repo, "b.txt"), "unstaged\n");
+
+    let state = press(state, "d");
+    assert!(stash_messages(&state).is_empty());
+
+    // Undoing the drop brings the stash back.
+    let state = press(state, "q<");
+    assert!(!matches!(state.screen, Some(Screen::Stashes(_))));
+    assert_eq!(git::get_stashes(&repo.path).unwrap().len(), 1);
+    assert_eq!(read(&repo, "b.txt"), "unstaged\n");
+}
+
+#[test]
+fn test_conflicting_apply_reports_error() {
+    let repo = setup_repo();
+    repo.create_file("b.txt", "stashed\n");
+    git::stash_push(&repo.path, "wip", false).unwrap();
+    repo.create_file("b.txt", "local\n");
+
+    let state = press(repo.create_initial_state(), "sp");
+    assert_eq!(
+        state.message_text(),
+        Some("Cannot apply stash@{0}: it conflicts with the working tree")
+    );
+    assert_eq!(stash_messages(&state).len(), 1);
+    assert_eq!(read(&repo, "b.txt"), "local\n");
+}
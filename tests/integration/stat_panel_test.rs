@@ -0,0 +1,47 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{open_view, press};
+use git_full_commit::app_state::{AppState, Modal};
+use git_full_commit::diff_stat::FileStat;
+use git_full_commit::git;
+
+#[test]
+fn test_stat_of_the_staged_changes() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "1\n2\n3\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "1\nchanged\n3\n");
+    repo.create_file("b.txt", "new\nfile\n");
+    repo.add_all();
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+
+    let state = press(state, ";");
+    let panel = open_view!(state.modal, Modal::StatPanel);
+    let stat = |name: &str, added, removed| FileStat {
+        name: name.to_string(),
+        added,
+        removed,
+        binary: false,
+    };
+    assert_eq!(panel.stats, vec![stat("a.txt", 1, 1), stat("b.txt", 2, 0)]);
+    assert_eq!(
+        panel.summary,
+        "2 files changed, 3 insertions(+), 1 deletion(-)"
+    );
+
+    let state = press(state, ";");
+    assert!(!matches!(state.modal, Some(Modal::StatPanel(_))));
+}
+
+#[test]
+fn test_nothing_staged() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "1\n");
+    repo.add_all();
+    repo.commit("initial");
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+
+    let state = press(state, ";");
+    assert!(!matches!(state.modal, Some(Modal::StatPanel(_))));
+    assert_eq!(state.message_text(), Some("Nothing is staged"));
+}
@@ -0,0 +1,102 @@
+use crate::git_test::common::{TestRepo, run_git};
+use crate::integration::common::press;
+use git_full_commit::app_state::AppState;
+use git_full_commit::git::{self, FileStatus};
+use git_full_commit::ui::main_screen::{ListItem, UnstagedListItem};
+
+/// Staged: `new.txt` added, `a.txt` modified, `gone.txt` deleted. Unstaged:
+/// `b.txt` modified and `untracked.txt`.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    for name in ["a.txt", "b.txt", "gone.txt"] {
+        repo.create_file(name, "one\n");
+    }
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "two\n");
+    repo.create_file("new.txt", "new\n");
+    run_git(&repo.path, &["add", "a.txt", "new.txt"]);
+    run_git(&repo.path, &["rm", "-q", "gone.txt"]);
+    repo.create_file("b.txt", "two\n");
+    repo.create_file("untracked.txt", "new\n");
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    (repo, state)
+}
+
+fn staged_files(state: &AppState) -> Vec<&str> {
+    state
+        .main_screen
+        .list_items
+        .iter()
+        .filter_map(|item| match item {
+            ListItem::File(file) => Some(file.file_name.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn unstaged_files(state: &AppState) -> Vec<&str> {
+    state
+        .unstaged_pane
+        .list_items
+        .iter()
+        .filter_map(|item| match item {
+            UnstagedListItem::File(file) => Some(file.file_name.as_str()),
+            UnstagedListItem::UntrackedFile(file_name) => Some(file_name.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn test_show_only_added_files() {
+    let (_repo, state) = setup();
+    let state = press(state, "|a");
+    assert_eq!(state.status_filter, Some(FileStatus::Added));
+    assert_eq!(state.message_text(), Some("Showing only added files"));
+    assert_eq!(staged_files(&state), ["new.txt"]);
+    // Untracked files are to be added
+    assert_eq!(unstaged_files(&state), ["untracked.txt"]);
+}
+
+#[test]
+fn test_the_filter_stays_until_esc() {
+    let (_repo, state) = setup();
+    let state = press(state, "|m");
+    assert_eq!(staged_files(&state), ["a.txt"]);
+    assert_eq!(unstaged_files(&state), ["b.txt"]);
+    assert!(
+        !state
+            .unstaged_pane
+            .list_items
+            .iter()
+            .any(|item| matches!(item, UnstagedListItem::UntrackedFilesHeader))
+    );
+
+    // Refreshing keeps the filter
+    let mut state = state;
+    state.refresh_diff(false);
+    assert_eq!(staged_files(&state), ["a.txt"]);
+
+    let state = press(state, "\u{1b}");
+    assert_eq!(state.status_filter, None);
+    assert_eq!(staged_files(&state).len(), 3);
+    assert_eq!(unstaged_files(&state).len(), 2);
+}
+
+#[test]
+fn test_a_key_without_a_status_filters_nothing() {
+    let (_repo, state) = setup();
+    let state = press(state, "|");
+    assert!(state.status_filter_menu);
+    let state = press(state, "x");
+    assert!(!state.status_filter_menu);
+    assert_eq!(state.status_filter, None);
+    assert_eq!(
+        state.message_text(),
+        Some("No status for x, nothing filtered")
+    );
+    let state = press(state, "|d");
+    assert_eq!(staged_files(&state), ["gone.txt"]);
+    assert!(unstaged_files(&state).is_empty());
+}
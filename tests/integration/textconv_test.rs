@@ -0,0 +1,72 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::press;
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use git_full_commit::ui::main_screen::focused_diff_lines;
+use std::process::Command;
+
+fn staged(repo: &TestRepo) -> String {
+    let output = Command::new("git")
+        .args(["diff", "--cached", "--no-textconv", "--name-status"])
+        .current_dir(&repo.path)
+        .output()
+        .unwrap();
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+/// A staged change to `notes.up`, shown upper-cased by its diff driver.
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file(".gitattributes", "*.up diff=upper\n");
+    Command::new("git")
+        .args(["config", "diff.upper.textconv", "tr a-z A-Z <"])
+        .current_dir(&repo.path)
+        .output()
+        .unwrap();
+    repo.create_file("notes.up", "one\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("notes.up", "two\n");
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new(repo.path.clone(), files);
+    state.main_screen.file_cursor = 1;
+    (repo, state)
+}
+
+#[test]
+fn test_textconv_diff_is_shown() {
+    let (_repo, state) = setup();
+    assert!(state.textconv_files.contains("notes.up"));
+    let lines = focused_diff_lines(&state);
+    assert!(lines.contains(&"-ONE".to_string()), "{lines:?}");
+    assert!(lines.contains(&"+TWO".to_string()), "{lines:?}");
+}
+
+#[test]
+fn test_textconv_hunks_are_not_unstaged() {
+    let (repo, state) = setup();
+    let state = press(state, "jjjjj");
+    assert!(state.main_screen.is_diff_cursor_active);
+
+    let state = press(state, "u");
+    assert_eq!(
+        state.message_text(),
+        Some("notes.up is shown through a textconv driver, only the whole file can be staged")
+    );
+    let state = press(state, "1");
+    assert!(state.message.is_some());
+    assert_eq!(staged(&repo), "M\tnotes.up\n");
+}
+
+#[test]
+fn test_textconv_file_is_unstaged_whole() {
+    let (repo, state) = setup();
+    let state = press(state, "u");
+    assert_eq!(state.message_text(), None);
+    assert_eq!(staged(&repo), "");
+    // Still marked in the unstaged pane
+    assert!(state.textconv_files.contains("notes.up"));
+    assert_eq!(state.unstaged_pane.unstaged_files[0].file_name, "notes.up");
+}
@@ -0,0 +1,75 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{press, send};
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use git_full_commit::ui::tutorial::Step;
+use git_full_commit::ui::update::update_state_with_alt;
+use pancurses::Input;
+
+fn step(state: &AppState) -> Option<Step> {
+    state.tutorial.as_ref().map(|tutorial| tutorial.step)
+}
+
+fn setup() -> (TestRepo, AppState) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "two\n");
+    repo.add_all();
+
+    let files = git::get_diff(repo.path.clone());
+    let state = AppState::new(repo.path.clone(), files);
+    (repo, state)
+}
+
+#[test]
+fn test_tutorial_walks_through_the_steps_in_a_sandbox() {
+    let (repo, state) = setup();
+    let state = press(state, "?t");
+    assert_eq!(step(&state), Some(Step::StageHunk));
+    let sandbox = state.tutorial.as_ref().unwrap().sandbox.repo_path.clone();
+    assert_ne!(sandbox, repo.path);
+
+    // Stage the hunk under the cursor in the unstaged pane
+    let state = send(press(state, "\t"), Input::KeyDown);
+    let state = press(state, "jjjjjjju");
+    assert_eq!(step(&state), Some(Step::UnstageLine));
+
+    // Unstage one of its lines
+    let state = press(state, "\tk1");
+    assert_eq!(step(&state), Some(Step::Undo));
+
+    let state = press(state, "<");
+    assert_eq!(step(&state), Some(Step::Commit));
+
+    // Type a message below the staged file and commit
+    let state = send(state, Input::KeyDown);
+    let state = press(state, "Buy more");
+    let state = update_state_with_alt(state, Some(Input::Character('\n')), 40, 80);
+    assert_eq!(step(&state), Some(Step::Done));
+    let log = git::run_git_command(&sandbox, &["log", "--format=%s"]).unwrap();
+    assert!(log.starts_with("Buy more\n"), "{log}");
+
+    // Any key closes it, removing the sandbox
+    let state = press(state, "j");
+    assert!(state.tutorial.is_none());
+    assert!(!sandbox.exists());
+
+    // The real repository was not touched
+    assert_eq!(state.files.len(), 1);
+    let log = git::run_git_command(&repo.path, &["log", "--format=%s"]).unwrap();
+    assert_eq!(log, "initial\n");
+}
+
+#[test]
+fn test_quitting_closes_the_tutorial_only() {
+    let (_repo, state) = setup();
+    let state = press(state, "?t");
+    let sandbox = state.tutorial.as_ref().unwrap().sandbox.repo_path.clone();
+
+    let state = press(state, "Q");
+    assert!(state.tutorial.is_none());
+    assert!(state.running);
+    assert!(!sandbox.exists());
+}
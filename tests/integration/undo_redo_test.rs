@@ -1,7 +1,7 @@
 use crate::git_test::common::TestRepo;
 use git_full_commit::app_state::AppState;
 use git_full_commit::git;
-use git_full_commit::ui::update::update_state;
+use git_full_commit::ui::update::{update_state, update_state_with_alt};
 use pancurses::Input;
 
 #[test]
@@ -53,7 +53,7 @@ fn test_undo_redo_history_clears_on_commit() {
     for ch in "commit".chars() {
         app_state = update_state(app_state, Some(Input::Character(ch)), 80, 80);
     }
-    app_state = update_state(app_state, Some(Input::Character('\n')), 80, 80); // Finalize
+    app_state = update_state_with_alt(app_state, Some(Input::Character('\n')), 80, 80); // Finalize
 
     // Check that redo stack is empty
     assert_eq!(app_state.command_history.redo_stack.len(), 0);
@@ -0,0 +1,106 @@
+use crate::git_test::common::TestRepo;
+use crate::integration::common::{open_view, send};
+use git_full_commit::app_state::{AppState, Screen};
+use git_full_commit::git;
+use git_full_commit::ui::unified_list;
+use pancurses::Input;
+
+fn lines(changed: &[usize]) -> String {
+    (1..=20)
+        .map(|i| {
+            if changed.contains(&i) {
+                format!("changed{i}\n")
+            } else {
+                format!("line{i}\n")
+            }
+        })
+        .collect()
+}
+
+fn staged_diff(repo: &TestRepo) -> String {
+    let output = std::process::Command::new("git")
+        .args(["diff", "--cached"])
+        .current_dir(&repo.path)
+        .output()
+        .unwrap();
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+fn counts(state: &AppState) -> Vec<(String, usize, usize)> {
+    unified_list::entries(state)
+        .iter()
+        .map(|e| {
+            (
+                e.file_name.to_string(),
+                e.staged_hunks(),
+                e.unstaged_hunks(),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn test_hunks_move_between_index_and_worktree() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", &lines(&[]));
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", &lines(&[1, 20]));
+
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new(repo.path.clone(), files);
+    state = send(state, Input::Character('U'));
+    assert!(matches!(state.screen, Some(Screen::UnifiedList(_))));
+    assert_eq!(counts(&state), vec![("a.txt".to_string(), 0, 2)]);
+
+    state = send(state, Input::KeyRight);
+    assert_eq!(counts(&state), vec![("a.txt".to_string(), 1, 1)]);
+    let staged = staged_diff(&repo);
+    assert!(staged.contains("+changed1"));
+    assert!(!staged.contains("+changed20"));
+
+    state = send(state, Input::KeyRight);
+    assert_eq!(counts(&state), vec![("a.txt".to_string(), 2, 0)]);
+
+    state = send(state, Input::KeyLeft);
+    assert_eq!(counts(&state), vec![("a.txt".to_string(), 1, 1)]);
+    let staged = staged_diff(&repo);
+    assert!(!staged.contains("+changed1"));
+    assert!(staged.contains("+changed20"));
+
+    // Moves are undone like any other staging once the list is closed
+    state = send(state, Input::Character('q'));
+    assert!(!matches!(state.screen, Some(Screen::UnifiedList(_))));
+    state = send(state, Input::Character('<'));
+    assert_eq!(counts(&state), vec![("a.txt".to_string(), 2, 0)]);
+}
+
+#[test]
+fn test_untracked_files_are_staged_whole() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "a\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("b.txt", "one\ntwo\n");
+    repo.create_file("a.txt", "changed\n");
+
+    let files = git::get_diff(repo.path.clone());
+    let mut state = AppState::new(repo.path.clone(), files);
+    state = send(state, Input::Character('U'));
+    assert_eq!(
+        counts(&state),
+        vec![("a.txt".to_string(), 0, 1), ("b.txt".to_string(), 0, 1)]
+    );
+
+    state = send(state, Input::Character('j'));
+    state = send(state, Input::Character('l'));
+    assert_eq!(repo.get_status(), " M a.txt\nA  b.txt\n");
+
+    state = send(state, Input::Character('h'));
+    assert_eq!(repo.get_status(), " M a.txt\n?? b.txt\n");
+    assert_eq!(open_view!(state.screen, Screen::UnifiedList).cursor, 1);
+
+    // Nothing to unstage
+    send(state, Input::Character('h'));
+    assert_eq!(repo.get_status(), " M a.txt\n?? b.txt\n");
+}
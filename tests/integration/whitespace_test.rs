@@ -0,0 +1,59 @@
+use crate::git_test::common::TestRepo;
+use git_full_commit::app_state::AppState;
+use git_full_commit::config::Config;
+use git_full_commit::git;
+use git_full_commit::ui::update::update_state;
+use git_full_commit::whitespace::{self, Mark};
+use pancurses::Input;
+
+#[test]
+fn test_underscore_toggles_the_whitespace_marks() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "one  \n");
+    repo.add_all();
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    assert!(!state.show_whitespace);
+
+    let state = update_state(state, Some(Input::Character('_')), 40, 80);
+    assert!(state.show_whitespace);
+    assert_eq!(
+        state.message_text(),
+        Some("Highlighting whitespace problems, _ hides them")
+    );
+    let state = update_state(state, Some(Input::Character('_')), 40, 80);
+    assert!(!state.show_whitespace);
+
+    let config = Config::parse("show_whitespace = true\n");
+    let state =
+        AppState::new_with_config(repo.path.clone(), git::get_diff(repo.path.clone()), config);
+    assert!(state.show_whitespace);
+}
+
+#[test]
+fn test_staged_files_adding_trailing_whitespace() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.create_file("b.txt", "one \n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("a.txt", "one\ttwo\t\n");
+    repo.create_file("b.txt", "one");
+    repo.add_all();
+    let files = git::get_diff(repo.path.clone());
+
+    let a = files.iter().find(|file| file.file_name == "a.txt").unwrap();
+    assert!(whitespace::adds_trailing_whitespace(a));
+    // b.txt drops its trailing space and its newline
+    let b = files.iter().find(|file| file.file_name == "b.txt").unwrap();
+    assert!(!whitespace::adds_trailing_whitespace(b));
+    let marks: Vec<Mark> = b
+        .lines
+        .iter()
+        .flat_map(|line| whitespace::marks(line))
+        .map(|(_, mark)| mark)
+        .collect();
+    assert_eq!(marks, vec![Mark::TrailingWhitespace, Mark::MissingNewline]);
+}
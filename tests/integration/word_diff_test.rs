@@ -0,0 +1,102 @@
+use crate::git_test::common::TestRepo;
+use git_full_commit::app_state::AppState;
+use git_full_commit::git;
+use git_full_commit::ui::update::update_state;
+use git_full_commit::util::word_diff::WordChange;
+use pancurses::Input;
+
+fn setup_repo() -> TestRepo {
+    let repo = TestRepo::new();
+    repo.create_file("doc.md", "The quick brown fox\njumps over the dog\n");
+    repo.add_all();
+    repo.commit("initial");
+    repo.create_file("doc.md", "The slow brown fox\njumps over the dog\n");
+    repo.add_all();
+    repo.commit("docs: slow fox");
+    repo
+}
+
+fn select_latest_commit(repo: &TestRepo) -> AppState {
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+    // [StagedHeader, CommitMessageInput, Commit2, Commit1]
+    app_state.main_screen.file_cursor = 2;
+    app_state.update_selected_commit_diff();
+    app_state
+}
+
+#[test]
+fn test_w_toggles_word_diff_for_selected_commit() {
+    let repo = setup_repo();
+    let mut app_state = select_latest_commit(&repo);
+    assert!(app_state.selected_commit_word_spans.is_empty());
+
+    app_state = update_state(app_state, Some(Input::Character('w')), 80, 80);
+    assert!(app_state.main_screen.commit_word_diff);
+
+    let line_count: usize = app_state
+        .selected_commit_files
+        .iter()
+        .map(|f| f.lines.len())
+        .sum();
+    assert_eq!(app_state.selected_commit_word_spans.len(), line_count);
+
+    let changed_line = app_state
+        .selected_commit_word_spans
+        .iter()
+        .flatten()
+        .find(|spans| spans.iter().any(|(_, c)| *c != WordChange::Unchanged))
+        .expect("a line with changed words");
+    assert!(changed_line.contains(&("quick".to_string(), WordChange::Removed)));
+    assert!(changed_line.contains(&("slow".to_string(), WordChange::Added)));
+
+    // The unchanged second line is a single line without changes.
+    assert!(
+        app_state
+            .selected_commit_files
+            .iter()
+            .flat_map(|f| f.lines.iter())
+            .any(|l| l == " jumps over the dog")
+    );
+
+    app_state = update_state(app_state, Some(Input::Character('w')), 80, 80);
+    assert!(!app_state.main_screen.commit_word_diff);
+    assert!(app_state.selected_commit_word_spans.is_empty());
+    assert!(
+        app_state
+            .selected_commit_files
+            .iter()
+            .flat_map(|f| f.lines.iter())
+            .any(|l| l == "-The quick brown fox")
+    );
+}
+
+#[test]
+fn test_word_diff_stays_on_when_selecting_another_commit() {
+    let repo = setup_repo();
+    let mut app_state = select_latest_commit(&repo);
+    app_state = update_state(app_state, Some(Input::Character('w')), 80, 80);
+
+    app_state.main_screen.file_cursor = 3;
+    app_state.update_selected_commit_diff();
+
+    assert!(app_state.main_screen.commit_word_diff);
+    assert!(
+        app_state
+            .selected_commit_files
+            .iter()
+            .flat_map(|f| f.lines.iter())
+            .any(|l| l == " {+The quick brown fox+}")
+    );
+}
+
+#[test]
+fn test_w_is_ignored_outside_commits() {
+    let repo = setup_repo();
+    let files = git::get_diff(repo.path.clone());
+    let mut app_state = AppState::new(repo.path.clone(), files);
+    app_state.main_screen.file_cursor = 0;
+
+    app_state = update_state(app_state, Some(Input::Character('w')), 80, 80);
+    assert!(!app_state.main_screen.commit_word_diff);
+}
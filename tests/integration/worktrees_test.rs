@@ -0,0 +1,125 @@
+use crate::git_test::common::{TestRepo, run_git};
+use crate::integration::common::{open_view, press};
+use git_full_commit::app_state::{AppState, Screen};
+use git_full_commit::git;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tempfile::TempDir;
+
+fn subjects(path: &Path) -> Vec<String> {
+    let output = Command::new("git")
+        .args(["log", "--format=%s"])
+        .current_dir(path)
+        .output()
+        .unwrap();
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+/// A repository with two commits, and a linked worktree on the `topic`
+/// branch.
+fn setup() -> (TestRepo, TempDir, PathBuf) {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("first");
+    repo.create_file("a.txt", "two\n");
+    repo.add_all();
+    repo.commit("second");
+
+    let dir = TempDir::new().unwrap();
+    let linked = dir.path().join("topic");
+    run_git(
+        &repo.path,
+        &["worktree", "add", "-b", "topic", linked.to_str().unwrap()],
+    );
+    (repo, dir, linked)
+}
+
+#[test]
+fn test_list_worktrees_marks_the_current_one() {
+    let (repo, _dir, linked) = setup();
+    let worktrees = git::list_worktrees(&linked).unwrap();
+    assert_eq!(worktrees.len(), 2);
+    assert_eq!(
+        worktrees[0].path.canonicalize().unwrap(),
+        repo.path.canonicalize().unwrap()
+    );
+    assert!(!worktrees[0].current);
+    assert_eq!(
+        worktrees[1].path.canonicalize().unwrap(),
+        linked.canonicalize().unwrap()
+    );
+    assert!(worktrees[1].current);
+    assert_eq!(worktrees[1].branch.as_deref(), Some("topic"));
+    assert_eq!(worktrees[0].head, worktrees[1].head);
+}
+
+#[test]
+fn test_switch_to_another_worktree() {
+    let (repo, _dir, linked) = setup();
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    let state = press(state, "W");
+    let picker = open_view!(state.screen, Screen::Worktrees);
+    assert_eq!(picker.cursor, 0);
+
+    // The current one is not switched to
+    let state = press(state, "\n");
+    assert!(state.running);
+    assert!(state.message_text().unwrap().starts_with("Already in "));
+
+    let state = press(state, "j\n");
+    assert!(!matches!(state.screen, Some(Screen::Worktrees(_))));
+    assert!(!state.running);
+    assert_eq!(
+        state.relaunch.unwrap().canonicalize().unwrap(),
+        linked.canonicalize().unwrap()
+    );
+}
+
+#[test]
+fn test_missing_worktree_is_not_switched_to() {
+    let (repo, _dir, linked) = setup();
+    std::fs::remove_dir_all(&linked).unwrap();
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    let state = press(state, "Wj\n");
+    assert!(state.running);
+    assert!(state.relaunch.is_none());
+    assert!(
+        state
+            .message_text()
+            .unwrap()
+            .ends_with("is missing, git worktree prune forgets it")
+    );
+
+    let state = press(state, "q");
+    assert!(!matches!(state.screen, Some(Screen::Worktrees(_))));
+    assert!(state.running);
+}
+
+#[test]
+fn test_no_picker_without_another_worktree() {
+    let repo = TestRepo::new();
+    repo.create_file("a.txt", "one\n");
+    repo.add_all();
+    repo.commit("first");
+    let state = AppState::new(repo.path.clone(), git::get_diff(repo.path.clone()));
+    let state = press(state, "W");
+    assert!(!matches!(state.screen, Some(Screen::Worktrees(_))));
+    assert_eq!(
+        state.message_text(),
+        Some("No other worktree, git worktree add creates one")
+    );
+}
+
+#[test]
+fn test_reword_in_a_linked_worktree() {
+    let (repo, _dir, linked) = setup();
+    let target = git::run_git_command(&linked, &["rev-parse", "HEAD~1"]).unwrap();
+    git::reword_commit(&linked, target.trim(), "reworded first").unwrap();
+    assert_eq!(subjects(&linked), vec!["second", "reworded first"]);
+    // The branch of the main worktree stays as it was
+    assert_eq!(subjects(&repo.path), vec!["second", "first"]);
+}